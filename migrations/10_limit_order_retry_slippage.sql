@@ -0,0 +1 @@
+ALTER TABLE limit_orders ADD COLUMN IF NOT EXISTS retry_slippage DOUBLE PRECISION;
@@ -0,0 +1,2 @@
+ALTER TABLE limit_orders ADD COLUMN IF NOT EXISTS trigger_metric TEXT NOT NULL DEFAULT 'PRICE';
+ALTER TABLE limit_orders ADD COLUMN IF NOT EXISTS trigger_value DOUBLE PRECISION;
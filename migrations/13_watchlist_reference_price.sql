@@ -0,0 +1,2 @@
+ALTER TABLE watchlist ADD COLUMN IF NOT EXISTS reference_price_in_sol DOUBLE PRECISION;
+ALTER TABLE watchlist ADD COLUMN IF NOT EXISTS reference_price_at TIMESTAMPTZ;
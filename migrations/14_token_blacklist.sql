@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS token_blacklist (
+    mint_address TEXT PRIMARY KEY,
+    reason TEXT,
+    added_by BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1 @@
+ALTER TABLE limit_orders ADD COLUMN IF NOT EXISTS price_currency TEXT NOT NULL DEFAULT 'SOL';
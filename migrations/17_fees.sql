@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS fees (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    trade_signature TEXT NOT NULL,
+    amount_sol DOUBLE PRECISION NOT NULL,
+    fee_bps INTEGER NOT NULL,
+    collection TEXT NOT NULL, -- "JUPITER" or "TRANSFER"
+    tx_signature TEXT,
+    status TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_fees_user_id ON fees(user_id);
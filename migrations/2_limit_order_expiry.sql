@@ -0,0 +1,2 @@
+ALTER TABLE limit_orders ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+CREATE INDEX IF NOT EXISTS idx_limit_orders_expires_at ON limit_orders(expires_at);
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS dca_orders (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_address TEXT NOT NULL,
+    token_symbol TEXT NOT NULL,
+    sol_per_interval DOUBLE PRECISION NOT NULL,
+    interval_seconds BIGINT NOT NULL,
+    next_run_at TIMESTAMPTZ NOT NULL,
+    remaining_runs INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_dca_orders_user_id ON dca_orders(user_id);
+CREATE INDEX IF NOT EXISTS idx_dca_orders_status_next_run ON dca_orders(status, next_run_at);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS swaps (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    from_token TEXT NOT NULL,
+    to_token TEXT NOT NULL,
+    amount_in DOUBLE PRECISION NOT NULL,
+    amount_out DOUBLE PRECISION NOT NULL,
+    tx_signature TEXT,
+    timestamp TIMESTAMPTZ NOT NULL,
+    status TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_swaps_user_id ON swaps(user_id);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS followed_wallets (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    wallet_address TEXT NOT NULL,
+    last_signature TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT followed_wallets_user_wallet_unique UNIQUE (user_id, wallet_address)
+);
+CREATE INDEX IF NOT EXISTS idx_followed_wallets_user_id ON followed_wallets(user_id);
@@ -0,0 +1 @@
+ALTER TABLE limit_orders ADD COLUMN IF NOT EXISTS filled_amount DOUBLE PRECISION NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE users ADD COLUMN IF NOT EXISTS referred_by BIGINT;
+CREATE INDEX IF NOT EXISTS idx_users_referred_by ON users(referred_by);
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::interactor::admin_interactor::AdminInteractorImpl;
+use crate::presenter::admin_presenter::{AdminPresenter, AdminPresenterImpl};
+use crate::view::admin_view::TelegramAdminView;
+
+pub struct AdminCommand;
+
+impl CommandHandler for AdminCommand {
+    fn command_name() -> &'static str {
+        "admin"
+    }
+
+    fn description() -> &'static str {
+        "operator commands"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        // Everything after "/admin", line breaks of the broadcast text are kept
+        let args = msg
+            .text()
+            .unwrap_or("")
+            .split_once(char::is_whitespace)
+            .map(|(_, args)| args)
+            .unwrap_or("");
+
+        let presenter = create_presenter(&bot, msg.chat.id, &services);
+        presenter.handle_command(telegram_id, args).await
+    }
+}
+
+fn create_presenter(
+    bot: &Bot,
+    chat_id: ChatId,
+    services: &Arc<ServiceContainer>,
+) -> AdminPresenterImpl<AdminInteractorImpl, TelegramAdminView> {
+    let interactor = Arc::new(AdminInteractorImpl::new(services.db_pool()));
+    let view = Arc::new(TelegramAdminView::new(bot.clone(), chat_id));
+
+    AdminPresenterImpl::new(interactor, view)
+}
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use log::info;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::entity::State;
+use crate::interactor::alert_interactor::AlertInteractorImpl;
+use crate::presenter::alert_presenter::{AlertPresenter, AlertPresenterImpl};
+use crate::view::alert_view::TelegramAlertView;
+
+pub struct AlertCommand;
+
+impl CommandHandler for AlertCommand {
+    fn command_name() -> &'static str {
+        "alerts"
+    }
+
+    fn description() -> &'static str {
+        "manage price alerts"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        info!("Alerts command initiated by user: {}", telegram_id);
+
+        let presenter = create_presenter(&bot, msg.chat.id, &services);
+
+        presenter.show_alerts(telegram_id).await?;
+
+        Ok(())
+    }
+}
+
+fn create_presenter(
+    bot: &Bot,
+    chat_id: ChatId,
+    services: &Arc<ServiceContainer>,
+) -> AlertPresenterImpl<AlertInteractorImpl, TelegramAlertView> {
+    let interactor = Arc::new(AlertInteractorImpl::new(
+        services.db_pool(),
+        services.price_service(),
+        services.token_repository(),
+    ));
+    let view = Arc::new(TelegramAlertView::new(bot.clone(), chat_id));
+
+    AlertPresenterImpl::new(interactor, view)
+}
+
+// Handler for the "Add Alert" button
+pub async fn start_alert_creation(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    dialogue.update(State::AwaitingAlertTokenAddress).await?;
+
+    let presenter = create_presenter(&bot, msg.chat.id, &services);
+    presenter.start_create_alert_flow().await?;
+
+    Ok(())
+}
+
+// Handler for the "Delete #id" buttons
+pub async fn delete_alert(
+    bot: Bot,
+    msg: Message,
+    telegram_id: i64,
+    alert_id: i32,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let presenter = create_presenter(&bot, msg.chat.id, &services);
+    presenter.delete_alert(telegram_id, alert_id).await?;
+
+    Ok(())
+}
+
+// Handler for the token address state
+pub async fn receive_token_address(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    if let Some(address_text) = msg.text() {
+        let address_text = address_text.trim();
+        let presenter = create_presenter(&bot, msg.chat.id, &services);
+
+        if let Some(token_symbol) = presenter.handle_token_address(address_text).await? {
+            dialogue
+                .update(State::AwaitingAlertCondition {
+                    token_address: address_text.to_string(),
+                    token_symbol,
+                })
+                .await?;
+        }
+    } else {
+        bot.send_message(
+            msg.chat.id,
+            "Please enter the token contract address as text:",
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Handler for the alert condition state
+pub async fn receive_condition(
+    bot: Bot,
+    msg: Message,
+    state: State,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    if let State::AwaitingAlertCondition {
+        token_address,
+        token_symbol,
+    } = state
+    {
+        if let Some(condition_text) = msg.text() {
+            let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+            let presenter = create_presenter(&bot, msg.chat.id, &services);
+
+            let completed = presenter
+                .handle_condition(telegram_id, &token_address, &token_symbol, condition_text)
+                .await?;
+
+            if completed {
+                dialogue.update(State::Start).await?;
+            }
+        } else {
+            bot.send_message(
+                msg.chat.id,
+                "Please enter the condition as text, e.g. above 0.5:",
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
@@ -9,7 +9,8 @@ use teloxide::{
 use crate::commands::{help, price, trade, ui, wallet, CommandHandler, MyDialogue};
 use crate::db;
 use crate::di::ServiceContainer;
-use crate::entity::State;
+use crate::entity::{BotError, Cluster, NotificationKind, State, WatchlistSort};
+use crate::i18n;
 use crate::interactor::balance_interactor::{BalanceInteractor, BalanceInteractorImpl};
 use crate::interactor::trade_interactor::{TradeInteractor, TradeInteractorImpl};
 use crate::interactor::wallet_interactor::WalletInteractorImpl;
@@ -19,7 +20,10 @@ use crate::presenter::limit_order_presenter::LimitOrderPresenter;
 use crate::presenter::settings_presenter::SettingsPresenter;
 use crate::presenter::watchlist_presenter::WatchlistPresenter;
 use crate::presenter::withdraw_presenter::WithdrawPresenter;
+use crate::solana::jupiter::SOL_MINT;
+use crate::utils::{format_price, format_usd_price};
 use crate::view::balance_view::TelegramBalanceView;
+use crate::view::message_utils::edit_or_ignore_unchanged;
 
 // Main callback handler function
 pub async fn handle_callback(
@@ -34,7 +38,20 @@ pub async fn handle_callback(
         None => return Ok(()),
     };
 
-    let message = q.regular_message().unwrap();
+    // Callbacks on deleted or inaccessible messages have no message to work with
+    let message = match q.regular_message() {
+        Some(message) => message,
+        None => {
+            if let Err(err) = bot
+                .answer_callback_query(q.id.clone())
+                .text("This message is no longer available. Use /menu to start again.")
+                .await
+            {
+                info!("Failed to answer callback query: {}", err);
+            }
+            return Ok(());
+        }
+    };
 
     // Get the chat ID
     let chat_id = match q.message {
@@ -55,10 +72,25 @@ pub async fn handle_callback(
         info!("Failed to answer callback query: {}", err);
     }
 
+    // Trading buttons can still be pressed on old messages when the bot runs off mainnet
+    if requires_jupiter(&callback_data)
+        && !ui::ensure_jupiter_available(&bot, chat_id, services.cluster()).await?
+    {
+        return Ok(());
+    }
+
     // Process the callback based on its type
     if callback_data == ("menu") || callback_data == "refresh" {
-        // Handle refresh action - update balance display
-        handle_refresh(&bot, Some(message.clone()), telegram_id, services).await?;
+        // Handle refresh action - update balance display, only "Refresh" bypasses the cache
+        let force_refresh = callback_data == "refresh";
+        handle_refresh(
+            &bot,
+            Some(message.clone()),
+            telegram_id,
+            force_refresh,
+            services,
+        )
+        .await?;
     } else if callback_data == "create_wallet" {
         // Handle create wallet action
         if let msg = message.clone() {
@@ -88,12 +120,54 @@ pub async fn handle_callback(
     } else if callback_data == "buy_manual_address" {
         // Handle manual address entry for buy
         handle_buy_manual_address(&bot, message.clone(), dialogue).await?;
+    } else if callback_data == "buy_search" {
+        // Handle token search by symbol or name for buy
+        handle_buy_search(&bot, message.clone(), dialogue).await?;
+    } else if callback_data.starts_with("buy_page_") {
+        // Show another page of the buy token selection
+        let page_str = callback_data.strip_prefix("buy_page_").unwrap_or("");
+        if let Ok(page) = page_str.parse::<usize>() {
+            handle_buy_page(&bot, message.clone(), telegram_id, page, services).await?;
+        }
+    } else if callback_data.starts_with("buy_quick_") {
+        // Buy for a preset SOL amount, format: "buy_quick_<sol>_<mint>"
+        if let Some((amount, token_address)) = trade::parse_quick_buy_callback(&callback_data) {
+            handle_quick_buy(
+                &bot,
+                amount,
+                token_address,
+                message.clone(),
+                telegram_id,
+                dialogue,
+                services,
+            )
+            .await?;
+        }
+    } else if callback_data.starts_with("buy_risk_ok_") {
+        // Buy a flagged token after the user saw the scam warning
+        let token_address = callback_data.strip_prefix("buy_risk_ok_").unwrap_or("");
+        handle_buy_token_selection(
+            &bot,
+            token_address,
+            false,
+            message.clone(),
+            telegram_id,
+            dialogue,
+            services,
+        )
+        .await?;
+    } else if callback_data.starts_with("token_info_") {
+        // Show the fundamentals of a token before buying it
+        let token_address = callback_data.strip_prefix("token_info_").unwrap_or("");
+        super::info::show_token_info(&bot, message.chat.id, telegram_id, token_address, &services)
+            .await?;
     } else if callback_data.starts_with("buy_token_") {
         // Handle token selection for buy
         let token_address = callback_data.strip_prefix("buy_token_").unwrap_or("");
         handle_buy_token_selection(
             &bot,
             token_address,
+            true,
             message.clone(),
             telegram_id,
             dialogue,
@@ -103,12 +177,35 @@ pub async fn handle_callback(
     } else if callback_data == "sell" {
         // Handle sell action - show token selection
         handle_sell_start(&bot, message.clone(), telegram_id, dialogue, services).await?;
+    } else if callback_data.starts_with("sell_page_") {
+        // Show another page of the sell token selection
+        let page_str = callback_data.strip_prefix("sell_page_").unwrap_or("");
+        if let Ok(page) = page_str.parse::<usize>() {
+            handle_sell_page(&bot, message.clone(), telegram_id, page, services).await?;
+        }
     } else if callback_data.starts_with("sell_token_") {
         // Handle token selection for sell
         let token_address = callback_data.strip_prefix("sell_token_").unwrap_or("");
         handle_sell_token_selection(
             &bot,
             token_address,
+            None,
+            message.clone(),
+            telegram_id,
+            dialogue,
+            services,
+        )
+        .await?;
+    } else if callback_data == "unwrap_sol" {
+        // Unwrap wrapped SOL instead of selling it
+        handle_unwrap_sol(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data.starts_with("sell_all_") {
+        // Sell the whole balance right after a trade
+        let token_address = callback_data.strip_prefix("sell_all_").unwrap_or("");
+        handle_sell_token_selection(
+            &bot,
+            token_address,
+            Some("All"),
             message.clone(),
             telegram_id,
             dialogue,
@@ -117,7 +214,7 @@ pub async fn handle_callback(
         .await?;
     } else if callback_data == "limit_orders" {
         // Display limit orders
-        handle_limit_orders(&bot, message.clone(), telegram_id, services).await?;
+        handle_limit_orders(&bot, message.clone(), telegram_id, false, services).await?;
     } else if callback_data == "create_limit_order" {
         // Start limit order creation flow
         handle_create_limit_order(&bot, message.clone(), dialogue, services).await?;
@@ -141,9 +238,60 @@ pub async fn handle_callback(
             services,
         )
         .await?;
+    } else if callback_data.starts_with("limit_risk_ok_") {
+        // Limit buy of a flagged token after the user saw the scam warning
+        let token_address = callback_data.strip_prefix("limit_risk_ok_").unwrap_or("");
+        crate::commands::limit_order::start_limit_order_for_token(
+            &bot,
+            message.chat.id,
+            crate::entity::OrderType::Buy,
+            token_address,
+            false,
+            dialogue,
+            services,
+        )
+        .await?;
+    } else if callback_data.starts_with("limit_sell_token_") {
+        // Limit sell for the token that was just traded
+        let token_address = callback_data
+            .strip_prefix("limit_sell_token_")
+            .unwrap_or("");
+        crate::commands::limit_order::start_limit_order_for_token(
+            &bot,
+            message.chat.id,
+            crate::entity::OrderType::Sell,
+            token_address,
+            false,
+            dialogue,
+            services,
+        )
+        .await?;
+    } else if callback_data == "stop_loss_order" {
+        // Handle stop-loss order type selection
+        crate::commands::limit_order::handle_order_type_selection(
+            bot,
+            message.clone(),
+            crate::entity::OrderType::StopLoss,
+            dialogue,
+            services,
+        )
+        .await?;
+    } else if callback_data == "take_profit_order" {
+        // Handle take-profit order type selection
+        crate::commands::limit_order::handle_order_type_selection(
+            bot,
+            message.clone(),
+            crate::entity::OrderType::TakeProfit,
+            dialogue,
+            services,
+        )
+        .await?;
     } else if callback_data == "refresh_limit_orders" {
-        // Refresh limit orders display
-        handle_limit_orders(&bot, message.clone(), telegram_id, services).await?;
+        // Refresh limit orders display with live prices
+        handle_limit_orders(&bot, message.clone(), telegram_id, true, services).await?;
+    } else if callback_data == "limit_order_book" {
+        // Show the orders grouped by token around the current price
+        handle_limit_order_book(&bot, message.clone(), telegram_id, services).await?;
     } else if callback_data == "cancel_limit_order" {
         // Show list of orders that can be cancelled
         handle_show_cancelable_orders(&bot, message.clone(), telegram_id, services).await?;
@@ -155,15 +303,218 @@ pub async fn handle_callback(
         } else {
             bot.send_message(chat_id, "Invalid order ID").await?;
         }
+    } else if callback_data == "edit_limit_order" {
+        // Show list of orders that can be edited
+        handle_show_editable_orders(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data.starts_with("edit_order_") {
+        // Ask for the new price and volume of the order
+        let order_id_str = callback_data.strip_prefix("edit_order_").unwrap_or("");
+        if let Ok(order_id) = order_id_str.parse::<i32>() {
+            crate::commands::limit_order::handle_edit_order(
+                bot,
+                chat_id,
+                telegram_id,
+                order_id,
+                dialogue,
+                services,
+            )
+            .await?;
+        } else {
+            bot.send_message(chat_id, "Invalid order ID").await?;
+        }
     } else if callback_data == "cancel_all_orders" {
         // Handle cancel all orders request
         handle_cancel_all_orders(&bot, message.clone(), telegram_id, services).await?;
     } else if callback_data == "confirm_cancel_all" {
         // Handle confirmation of cancelling all orders
         handle_confirm_cancel_all(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data == "portfolio" {
+        // Display portfolio with P&L
+        crate::commands::portfolio::PortfolioCommand::execute(
+            bot,
+            message.clone(),
+            telegram_id,
+            Some(dialogue),
+            services,
+        )
+        .await?;
+    } else if callback_data == "positions" {
+        // Display holdings together with their pending limit orders
+        crate::commands::positions::PositionsCommand::execute(
+            bot,
+            message.clone(),
+            telegram_id,
+            Some(dialogue),
+            services,
+        )
+        .await?;
+    } else if callback_data == "history" {
+        // Display transaction history
+        crate::commands::history::HistoryCommand::execute(
+            bot,
+            message.clone(),
+            telegram_id,
+            Some(dialogue),
+            services,
+        )
+        .await?;
+    } else if callback_data.starts_with("history_page_") {
+        // Show another page of the transaction history
+        let page_str = callback_data.strip_prefix("history_page_").unwrap_or("");
+        if let Ok(page) = page_str.parse::<usize>() {
+            crate::commands::history::show_history_page(
+                bot,
+                message.clone(),
+                telegram_id,
+                page,
+                services,
+            )
+            .await?;
+        }
+    } else if callback_data == "alerts" {
+        // Display price alerts
+        crate::commands::alert::AlertCommand::execute(
+            bot,
+            message.clone(),
+            telegram_id,
+            Some(dialogue),
+            services,
+        )
+        .await?;
+    } else if callback_data == "alert_add" {
+        // Start price alert creation flow
+        crate::commands::alert::start_alert_creation(bot, message.clone(), dialogue, services)
+            .await?;
+    } else if callback_data.starts_with("alert_delete_") {
+        // Delete a price alert
+        let alert_id_str = callback_data.strip_prefix("alert_delete_").unwrap_or("");
+        if let Ok(alert_id) = alert_id_str.parse::<i32>() {
+            crate::commands::alert::delete_alert(
+                bot,
+                message.clone(),
+                telegram_id,
+                alert_id,
+                services,
+            )
+            .await?;
+        }
+    } else if callback_data == "follow" {
+        // Show followed wallets
+        crate::commands::follow::FollowCommand::execute(
+            bot,
+            message.clone(),
+            telegram_id,
+            Some(dialogue),
+            services,
+        )
+        .await?;
+    } else if callback_data == "follow_add" {
+        // Start following a new wallet
+        crate::commands::follow::start_follow(bot, message.clone(), dialogue, services).await?;
+    } else if callback_data.starts_with("follow_delete_") {
+        // Stop following a wallet
+        let wallet_id_str = callback_data.strip_prefix("follow_delete_").unwrap_or("");
+        if let Ok(followed_wallet_id) = wallet_id_str.parse::<i32>() {
+            crate::commands::follow::unfollow(
+                bot,
+                message.clone(),
+                telegram_id,
+                followed_wallet_id,
+                services,
+            )
+            .await?;
+        }
+    } else if callback_data == "language" {
+        // Show language selection
+        crate::commands::language::LanguageCommand::execute(
+            bot,
+            message.clone(),
+            telegram_id,
+            Some(dialogue),
+            services,
+        )
+        .await?;
+    } else if callback_data.starts_with("language_") {
+        // Switch the bot language
+        let locale_code = callback_data.strip_prefix("language_").unwrap_or("");
+        crate::commands::language::set_language(
+            bot,
+            message.clone(),
+            telegram_id,
+            locale_code,
+            services,
+        )
+        .await?;
+    } else if callback_data == "confirm_swap" {
+        // Execute the pending swap
+        crate::commands::swap::confirm_swap(bot, message.clone(), telegram_id, dialogue, services)
+            .await?;
+    } else if callback_data == "cancel_swap" {
+        // Cancel the pending swap
+        crate::commands::swap::cancel_swap(bot, message.clone(), dialogue, services).await?;
+    } else if callback_data == "confirm_cleanup" {
+        // Close the empty token accounts
+        crate::commands::cleanup::confirm_cleanup(bot, message.clone(), telegram_id, services)
+            .await?;
+    } else if callback_data == "cancel_cleanup" {
+        // Cancel the token account cleanup
+        crate::commands::cleanup::cancel_cleanup(bot, message.clone(), services).await?;
+    } else if callback_data == "confirm_export" {
+        // Reveal the wallet secrets
+        crate::commands::export::confirm_export(bot, message.clone(), telegram_id, services)
+            .await?;
+    } else if callback_data == "cancel_export" {
+        // Cancel the wallet export
+        crate::commands::export::cancel_export(bot, message.clone(), services).await?;
+    } else if callback_data == "dialog_confirm" {
+        // Execute the pending buy, sell, withdraw or limit order
+        crate::commands::confirmation::confirm_pending(
+            bot,
+            message.clone(),
+            telegram_id,
+            dialogue,
+            services,
+        )
+        .await?;
+    } else if callback_data == "dialog_cancel" {
+        // Cancel the pending dialogue
+        crate::commands::confirmation::cancel_pending(bot, message.clone(), dialogue).await?;
+    } else if callback_data == "dca" {
+        // Display DCA orders
+        crate::commands::dca::DcaCommand::execute(
+            bot,
+            message.clone(),
+            telegram_id,
+            Some(dialogue),
+            services,
+        )
+        .await?;
+    } else if callback_data == "dca_create" {
+        // Start DCA order creation flow
+        crate::commands::dca::start_dca_creation(bot, message.clone(), dialogue, services).await?;
+    } else if callback_data.starts_with("dca_cancel_") {
+        // Cancel a DCA order
+        let order_id_str = callback_data.strip_prefix("dca_cancel_").unwrap_or("");
+        if let Ok(order_id) = order_id_str.parse::<i32>() {
+            crate::commands::dca::cancel_dca_order(
+                bot,
+                message.clone(),
+                telegram_id,
+                order_id,
+                services,
+            )
+            .await?;
+        }
     } else if callback_data == "settings" {
-        // Handle settings menu action
+        // Handle settings menu action, cancelling a settings prompt returns here
+        dialogue.update(State::Start).await?;
         handle_settings_menu(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data == "settings_export" {
+        // Send the preferences as JSON
+        handle_settings_export(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data == "settings_import" {
+        // Ask for the exported JSON
+        handle_settings_import(&bot, message.clone(), dialogue, services).await?;
     } else if callback_data == "set_slippage" {
         // Handle slippage setting action
         handle_set_slippage(&bot, message.clone(), dialogue, telegram_id, services).await?;
@@ -171,6 +522,98 @@ pub async fn handle_callback(
         // Handle preset slippage values
         handle_preset_slippage(&bot, &callback_data, message.clone(), telegram_id, services)
             .await?;
+    } else if callback_data == "set_priority_fee" {
+        // Handle priority fee setting action
+        handle_set_priority_fee(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data == "set_explorer" {
+        // Show the explorer choices
+        handle_set_explorer(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data.starts_with("explorer_") {
+        // Switch the explorer used for transaction links
+        handle_select_explorer(&bot, &callback_data, message.clone(), telegram_id, services)
+            .await?;
+    } else if callback_data == "set_auto_confirm" {
+        // Handle auto-confirm threshold setting
+        handle_set_auto_confirm(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data.starts_with("auto_confirm_") {
+        // Handle auto-confirm threshold preset selection
+        handle_preset_auto_confirm(&bot, &callback_data, message.clone(), telegram_id, services)
+            .await?;
+    } else if callback_data == "toggle_default_quote" {
+        // Switch the quote shown first in prices between SOL and USDC
+        handle_toggle_default_quote(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data == "toggle_trade_card" {
+        // Turn the image card sent with confirmed trades on or off
+        handle_toggle_trade_card(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data == "set_low_balance" {
+        // Show the low SOL balance warning thresholds
+        handle_set_low_balance(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data.starts_with("low_balance_") {
+        // Update the low SOL balance warning threshold
+        handle_preset_low_balance(&bot, &callback_data, message.clone(), telegram_id, services)
+            .await?;
+    } else if callback_data == "set_quick_buy" {
+        // Ask for the quick-buy amounts
+        handle_set_quick_buy(&bot, message.clone(), dialogue, telegram_id, services).await?;
+    } else if callback_data == "quick_buy_reset" {
+        // Restore the default quick-buy amounts
+        handle_reset_quick_buy(&bot, message.clone(), dialogue, telegram_id, services).await?;
+    } else if callback_data == "advanced_swap" {
+        // Show the route restriction settings
+        handle_advanced_swap_settings(&bot, message.clone(), dialogue, telegram_id, services)
+            .await?;
+    } else if callback_data == "notifications" {
+        // Show the notification switches
+        handle_notification_settings(&bot, message.clone(), telegram_id, None, services).await?;
+    } else if let Some(code) = callback_data.strip_prefix("toggle_notification_") {
+        // Switch a kind of notification on or off
+        match NotificationKind::from_code(code) {
+            Some(kind) => {
+                handle_notification_settings(
+                    &bot,
+                    message.clone(),
+                    telegram_id,
+                    Some(kind),
+                    services,
+                )
+                .await?;
+            }
+            None => {
+                bot.send_message(chat_id, "Unknown notification type")
+                    .await?;
+            }
+        }
+    } else if callback_data == "toggle_direct_routes" {
+        // Switch direct routes only on or off
+        handle_toggle_direct_routes(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data == "set_max_accounts" {
+        // Ask for the max accounts per route
+        handle_set_max_accounts(&bot, message.clone(), dialogue, telegram_id, services).await?;
+    } else if callback_data == "max_accounts_reset" {
+        // Leave max accounts to Jupiter
+        handle_reset_max_accounts(&bot, message.clone(), dialogue, telegram_id, services).await?;
+    } else if callback_data == "set_validity_slots" {
+        // Ask for the transaction validity in slots
+        handle_set_validity_slots(&bot, message.clone(), dialogue, telegram_id, services).await?;
+    } else if callback_data == "validity_slots_reset" {
+        // Keep swaps valid for the full blockhash lifetime
+        handle_reset_validity_slots(&bot, message.clone(), dialogue, telegram_id, services).await?;
+    } else if callback_data.starts_with("priority_fee_") {
+        // Handle priority fee preset selection
+        handle_preset_priority_fee(&bot, &callback_data, message.clone(), telegram_id, services)
+            .await?;
+    } else if callback_data == "set_max_trade" || callback_data == "set_daily_limit" {
+        // Show spending limit presets
+        let kind = if callback_data == "set_max_trade" {
+            crate::entity::TradeLimitKind::MaxTrade
+        } else {
+            crate::entity::TradeLimitKind::Daily
+        };
+        handle_set_trade_limit(&bot, message.clone(), telegram_id, kind, services).await?;
+    } else if callback_data.starts_with("max_trade_") || callback_data.starts_with("daily_limit_") {
+        // Handle spending limit preset selection
+        handle_preset_trade_limit(&bot, &callback_data, message.clone(), telegram_id, services)
+            .await?;
     } else if callback_data == "watchlist" {
         // Handle watchlist menu
         handle_watchlist_menu(&bot, message.clone(), telegram_id, services).await?;
@@ -180,6 +623,13 @@ pub async fn handle_callback(
     } else if callback_data == "watchlist_refresh" {
         // Handle watchlist refresh
         handle_watchlist_refresh(&bot, message.clone(), telegram_id, services).await?;
+    } else if callback_data.starts_with("watchlist_page_")
+        || callback_data.starts_with("watchlist_sort_")
+        || callback_data == "watchlist_filter_alerts"
+    {
+        // Page, sort or filter the watchlist in place
+        handle_watchlist_view_change(&bot, message.clone(), telegram_id, &callback_data, services)
+            .await?;
     } else if callback_data.starts_with("watchlist_view_") {
         // Handle view token details
         let token_address = callback_data.strip_prefix("watchlist_view_").unwrap_or("");
@@ -195,6 +645,26 @@ pub async fn handle_callback(
     } else if callback_data == "withdraw" {
         // Handle withdraw action - show token selection
         handle_withdraw_start(&bot, message.clone(), telegram_id, dialogue, services).await?;
+    } else if callback_data == "withdraw_batch" {
+        // Ask for the lines of a batch withdrawal
+        crate::commands::withdraw::start_batch_withdraw(&bot, message.chat.id, &dialogue).await?;
+    } else if callback_data == "batch_withdraw_stop" || callback_data == "batch_withdraw_continue" {
+        // Send the confirmed batch withdrawal
+        handle_batch_withdraw_confirm(
+            &bot,
+            message.clone(),
+            telegram_id,
+            dialogue,
+            callback_data == "batch_withdraw_continue",
+            services,
+        )
+        .await?;
+    } else if callback_data.starts_with("withdraw_page_") {
+        // Show another page of the withdraw token selection
+        let page_str = callback_data.strip_prefix("withdraw_page_").unwrap_or("");
+        if let Ok(page) = page_str.parse::<usize>() {
+            handle_withdraw_page(&bot, message.clone(), telegram_id, page, services).await?;
+        }
     } else if callback_data.starts_with("withdraw_token_") {
         // Handle token selection for withdraw
         let token_address = callback_data.strip_prefix("withdraw_token_").unwrap_or("");
@@ -221,16 +691,7 @@ pub async fn handle_callback(
 
 // Function to show token price selection
 async fn handle_check_price(bot: &Bot, chat_id: ChatId, dialogue: MyDialogue) -> Result<()> {
-    dialogue.update(State::AwaitingPriceTokenAddress).await?;
-
-    // Prompt user for token address
-    bot.send_message(
-        chat_id,
-        "Please enter the token contract address you want to check the price for:",
-    )
-    .await?;
-
-    Ok(())
+    price::ask_price_token(bot, chat_id, &dialogue).await
 }
 
 // Function to handle token price selection
@@ -254,8 +715,10 @@ async fn handle_price_selection(
         Ok(price_info) => {
             // Format price message
             let price_text = format!(
-                "Current price for {}:\n≈ {:.6} SOL\n≈ ${:.6}",
-                price_info.symbol, price_info.price_in_sol, price_info.price_in_usdc
+                "Current price for {}:\n≈ {} SOL\n≈ {}",
+                price_info.symbol,
+                format_price(price_info.price_in_sol),
+                format_usd_price(price_info.price_in_usdc)
             );
 
             // Add back button
@@ -265,17 +728,19 @@ async fn handle_price_selection(
             ]]);
 
             // Update message with price info
-            bot.edit_message_text(chat_id, message.id, price_text)
-                .reply_markup(keyboard)
-                .await?;
+            edit_or_ignore_unchanged(
+                bot.edit_message_text(chat_id, message.id, price_text)
+                    .reply_markup(keyboard),
+            )
+            .await?;
         }
         Err(e) => {
             // Show error message
-            bot.edit_message_text(
+            edit_or_ignore_unchanged(bot.edit_message_text(
                 chat_id,
                 message.id,
                 format!("Error getting price for {}: {}", token, e),
-            )
+            ))
             .await?;
         }
     }
@@ -283,11 +748,29 @@ async fn handle_price_selection(
     Ok(())
 }
 
+// Callbacks that start a buy, sell, swap, limit order or DCA order
+fn requires_jupiter(callback_data: &str) -> bool {
+    callback_data == "buy"
+        || callback_data.starts_with("buy_")
+        || callback_data == "sell"
+        || callback_data.starts_with("sell_")
+        || callback_data == "confirm_swap"
+        || callback_data == "create_limit_order"
+        || callback_data == "limit_buy_order"
+        || callback_data == "limit_sell_order"
+        || callback_data.starts_with("limit_sell_token_")
+        || callback_data.starts_with("limit_risk_ok_")
+        || callback_data == "stop_loss_order"
+        || callback_data == "take_profit_order"
+        || callback_data == "dca_create"
+}
+
 // Function to handle refresh action
 async fn handle_refresh(
     bot: &Bot,
     message: Option<Message>,
     telegram_id: i64,
+    force_refresh: bool,
     services: Arc<ServiceContainer>,
 ) -> Result<()> {
     if let Some(msg) = message {
@@ -301,73 +784,29 @@ async fn handle_refresh(
             solana_client,
             price_service,
         ));
-        let view = Arc::new(TelegramBalanceView::new(bot.clone(), chat_id));
+        let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+        let view = Arc::new(
+            TelegramBalanceView::new(bot.clone(), chat_id)
+                .with_cluster(services.cluster())
+                .with_locale(locale),
+        );
         let presenter = BalancePresenterImpl::new(interactor, view);
 
         // Call the refresh method that updates the existing message
-        presenter.refresh_balances(telegram_id, Some(msg)).await?;
-    }
-
-    Ok(())
-}
-
-// Function to handle swap with predefined amount
-async fn handle_swap_amount(
-    bot: &Bot,
-    callback_data: &str,
-    chat_id: ChatId,
-    telegram_id: i64,
-    services: Arc<ServiceContainer>,
-) -> Result<()> {
-    // Parse the callback data (format: swap_amount_AMOUNT_SOURCE_to_TARGET)
-    let parts: Vec<&str> = callback_data.split('_').collect();
-
-    if parts.len() >= 5 {
-        let amount_str = parts[2];
-        let source_token = parts[3];
-        let target_token = parts[4];
-
-        let amount = match f64::from_str(amount_str) {
-            Ok(val) => val,
-            Err(_) => {
-                bot.send_message(chat_id, "Invalid amount format. Please try again.")
-                    .await?;
-                return Ok(());
-            }
-        };
-
-        // Create confirmation keyboard
-        let confirm_keyboard = InlineKeyboardMarkup::new(vec![vec![
-            InlineKeyboardButton::callback(
-                "✅ Confirm Swap",
-                format!("confirm_swap_{}_{}_{}", amount, source_token, target_token),
-            ),
-            InlineKeyboardButton::callback("❌ Cancel", "swap"),
-        ]]);
-
-        // Show confirmation message
-        bot.send_message(
-            chat_id,
-            format!(
-                "You are about to swap {} {} to {}.\n\nDo you want to proceed?",
-                amount, source_token, target_token
-            ),
-        )
-        .reply_markup(confirm_keyboard)
-        .await?;
-    } else {
-        bot.send_message(chat_id, "Invalid swap parameters. Please try again.")
+        presenter
+            .refresh_balances(telegram_id, Some(msg), force_refresh)
             .await?;
     }
 
     Ok(())
 }
 
-// Function to display limit orders
+// Function to display limit orders, `live_prices` fetches current prices instead of the stored ones
 async fn handle_limit_orders(
     bot: &Bot,
     message: Message,
     telegram_id: i64,
+    live_prices: bool,
     services: Arc<ServiceContainer>,
 ) -> Result<()> {
     let chat_id = message.chat.id;
@@ -394,7 +833,40 @@ async fn handle_limit_orders(
         crate::presenter::limit_order_presenter::LimitOrderPresenterImpl::new(interactor, view);
 
     // Show limit orders
-    presenter.show_limit_orders(telegram_id).await?;
+    if live_prices {
+        presenter.refresh_limit_orders(telegram_id).await?;
+    } else {
+        presenter.show_limit_orders(telegram_id).await?;
+    }
+
+    Ok(())
+}
+
+// Function to show the order book of all active orders
+async fn handle_limit_order_book(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    let interactor = Arc::new(
+        crate::interactor::limit_order_interactor::LimitOrderInteractorImpl::new(
+            services.db_pool(),
+            services.solana_client(),
+            services.price_service(),
+            services.token_repository(),
+        ),
+    );
+    let view = Arc::new(crate::view::limit_order_view::TelegramLimitOrderView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::limit_order_presenter::LimitOrderPresenterImpl::new(interactor, view);
+
+    presenter.show_order_book(telegram_id).await?;
 
     Ok(())
 }
@@ -488,37 +960,83 @@ async fn handle_show_cancelable_orders(
     Ok(())
 }
 
-// Function to cancel a specific order
-async fn handle_cancel_order(
+// Function to show orders that can be edited, partially filled ones can't
+async fn handle_show_editable_orders(
     bot: &Bot,
     message: Message,
-    order_id: i32,
     telegram_id: i64,
     services: Arc<ServiceContainer>,
 ) -> Result<()> {
-    let db_pool = services.db_pool();
-
-    // Verify order exists and belongs to user
-    let user = crate::interactor::db::get_user_by_telegram_id(&db_pool, telegram_id).await?;
-    let order = crate::interactor::db::get_limit_order_by_id(&db_pool, order_id).await?;
+    let chat_id = message.chat.id;
 
-    match order {
-        Some(order) if order.user_id == user.id => {
-            // Cancel the order
-            crate::interactor::db::cancel_limit_order(&db_pool, order_id).await?;
+    let db_pool = services.db_pool();
+    let orders = crate::interactor::db::get_active_limit_orders(&db_pool, telegram_id).await?;
+    let orders: Vec<_> = orders
+        .into_iter()
+        .filter(|order| order.filled_amount <= 0.0)
+        .collect();
 
-            // Send confirmation
-            bot.send_message(
-                ChatId(telegram_id),
-                format!(
-                    "Order #{} ({} {} @ {} SOL) has been cancelled.",
-                    order_id, order.amount, order.token_symbol, order.price_in_sol
-                ),
-            )
+    if orders.is_empty() {
+        bot.send_message(chat_id, "You don't have any active orders to edit.")
             .await?;
-
+        return Ok(());
+    }
+
+    let mut keyboard_buttons = Vec::new();
+    for order in &orders {
+        let button_text = format!(
+            "#{}: {} {} @ {} SOL",
+            order.id, order.amount, order.token_symbol, order.price_in_sol
+        );
+        keyboard_buttons.push(vec![InlineKeyboardButton::callback(
+            button_text,
+            format!("edit_order_{}", order.id),
+        )]);
+    }
+
+    keyboard_buttons.push(vec![InlineKeyboardButton::callback(
+        "Back to Orders",
+        "limit_orders",
+    )]);
+
+    bot.send_message(chat_id, "Select an order to edit:")
+        .reply_markup(InlineKeyboardMarkup::new(keyboard_buttons))
+        .await?;
+
+    Ok(())
+}
+
+// Function to cancel a specific order
+async fn handle_cancel_order(
+    bot: &Bot,
+    message: Message,
+    order_id: i32,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let db_pool = services.db_pool();
+
+    // Verify order exists and belongs to user
+    let user = crate::interactor::db::get_user_by_telegram_id(&db_pool, telegram_id).await?;
+    let order = crate::interactor::db::get_limit_order_by_id(&db_pool, order_id).await?;
+
+    match order {
+        Some(order) if order.user_id == user.id => {
+            // Cancel the order
+            crate::interactor::db::cancel_limit_order(&db_pool, order_id).await?;
+
+            // Send confirmation
+            bot.send_message(
+                ChatId(telegram_id),
+                format!(
+                    "Order #{} ({} {} @ {} SOL) has been cancelled.",
+                    order_id, order.amount, order.token_symbol, order.price_in_sol
+                ),
+            )
+            .await?;
+
             // Refresh orders list
-            handle_limit_orders(bot, message, telegram_id, services).await?;
+            handle_limit_orders(bot, message, telegram_id, false, services).await?;
         }
         Some(_) => {
             // Order exists but doesn't belong to user
@@ -605,7 +1123,7 @@ async fn handle_confirm_cancel_all(
     .await?;
 
     // Refresh the orders list
-    handle_limit_orders(bot, message, telegram_id, services).await?;
+    handle_limit_orders(bot, message, telegram_id, false, services).await?;
 
     Ok(())
 }
@@ -695,8 +1213,8 @@ async fn handle_preset_slippage(
     Ok(())
 }
 
-// Function to show watchlist menu
-async fn handle_watchlist_menu(
+// Function to handle priority fee setting
+async fn handle_set_priority_fee(
     bot: &Bot,
     message: Message,
     telegram_id: i64,
@@ -704,65 +1222,711 @@ async fn handle_watchlist_menu(
 ) -> Result<()> {
     let chat_id = message.chat.id;
 
-    // Create presenter for watchlist
+    // Show priority fee presets
     let db_pool = services.db_pool();
-    let price_service = services.price_service();
-    let token_repository = services.token_repository();
-
-    let interactor = Arc::new(
-        crate::interactor::watchlist_interactor::WatchlistInteractorImpl::new(
-            db_pool,
-            price_service.clone(),
-            token_repository,
-        ),
-    );
-    let view = Arc::new(crate::view::watchlist_view::TelegramWatchlistView::new(
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
         bot.clone(),
         chat_id,
     ));
-    let presenter = crate::presenter::watchlist_presenter::WatchlistPresenterImpl::new(
-        interactor,
-        view,
-        price_service,
-    );
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
 
-    // Show watchlist
-    presenter.show_watchlist(telegram_id).await?;
+    presenter.show_priority_fee_prompt(telegram_id).await?;
 
     Ok(())
 }
 
-// Function to handle adding to watchlist
-async fn handle_watchlist_add(
+// Function to handle priority fee preset selections
+async fn handle_preset_priority_fee(
     bot: &Bot,
+    callback_data: &str,
     message: Message,
-    dialogue: MyDialogue,
     telegram_id: i64,
     services: Arc<ServiceContainer>,
 ) -> Result<()> {
     let chat_id = message.chat.id;
 
-    // Update dialogue state to expect token address
-    dialogue
-        .update(State::AwaitingWatchlistTokenAddress)
+    // Extract preset from callback data (format: "priority_fee_<preset>")
+    let preset_str = callback_data
+        .strip_prefix("priority_fee_")
+        .unwrap_or("none");
+    let priority_fee = crate::entity::PriorityFee::from_str(preset_str).unwrap_or_default();
+
+    // Update priority fee setting
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter
+        .set_priority_fee(telegram_id, priority_fee)
         .await?;
 
-    // Create presenter
+    Ok(())
+}
+
+// Function to show the explorer choices
+async fn handle_set_explorer(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
     let db_pool = services.db_pool();
-    let price_service = services.price_service();
-    let token_repository = services.token_repository();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
 
-    let interactor = Arc::new(
-        crate::interactor::watchlist_interactor::WatchlistInteractorImpl::new(
-            db_pool,
-            price_service.clone(),
-            token_repository,
-        ),
-    );
-    let view = Arc::new(crate::view::watchlist_view::TelegramWatchlistView::new(
+    presenter.show_explorer_prompt(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle explorer selections
+async fn handle_select_explorer(
+    bot: &Bot,
+    callback_data: &str,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // Extract explorer from callback data (format: "explorer_<code>")
+    let code = callback_data.strip_prefix("explorer_").unwrap_or("");
+    let explorer = crate::entity::Explorer::from_str(code).unwrap_or_default();
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.set_explorer(telegram_id, explorer).await?;
+
+    Ok(())
+}
+
+// Function to handle the default quote toggle
+async fn handle_toggle_default_quote(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.toggle_default_quote(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle the trade card toggle
+async fn handle_toggle_trade_card(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.toggle_trade_card(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to show the low SOL balance warning thresholds
+async fn handle_set_low_balance(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.show_low_balance_prompt(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle the settings export
+async fn handle_settings_export(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.export_settings(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle the settings import
+async fn handle_settings_import(
+    bot: &Bot,
+    message: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // Update dialogue state to expect the pasted settings
+    dialogue.update(State::AwaitingSettingsImport).await?;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
         bot.clone(),
         chat_id,
     ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.show_import_prompt().await?;
+
+    Ok(())
+}
+
+// Function to handle quick-buy amounts setting
+async fn handle_set_quick_buy(
+    bot: &Bot,
+    message: Message,
+    dialogue: MyDialogue,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // Update dialogue state to expect the amounts
+    dialogue.update(State::AwaitingQuickBuyAmountsInput).await?;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.show_quick_buy_prompt(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle the quick-buy amounts reset
+async fn handle_reset_quick_buy(
+    bot: &Bot,
+    message: Message,
+    dialogue: MyDialogue,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // The amounts prompt is answered with the button instead
+    dialogue.update(State::Start).await?;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.reset_quick_buy_amounts(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle the advanced swap settings menu
+async fn handle_advanced_swap_settings(
+    bot: &Bot,
+    message: Message,
+    dialogue: MyDialogue,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // Cancelling the max accounts prompt returns here
+    dialogue.update(State::Start).await?;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.show_advanced_swap_menu(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle the direct routes toggle
+async fn handle_toggle_direct_routes(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.toggle_direct_routes(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to show the notification settings, toggling `kind` first when given
+async fn handle_notification_settings(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    kind: Option<NotificationKind>,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    match kind {
+        Some(kind) => presenter.toggle_notification(telegram_id, kind).await?,
+        None => presenter.show_notifications_menu(telegram_id).await?,
+    }
+
+    Ok(())
+}
+
+// Function to handle max accounts setting
+async fn handle_set_max_accounts(
+    bot: &Bot,
+    message: Message,
+    dialogue: MyDialogue,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // Update dialogue state to expect the number
+    dialogue.update(State::AwaitingMaxAccountsInput).await?;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.show_max_accounts_prompt(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle the max accounts reset
+async fn handle_reset_max_accounts(
+    bot: &Bot,
+    message: Message,
+    dialogue: MyDialogue,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // The max accounts prompt is answered with the button instead
+    dialogue.update(State::Start).await?;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.reset_max_accounts(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle transaction validity setting
+async fn handle_set_validity_slots(
+    bot: &Bot,
+    message: Message,
+    dialogue: MyDialogue,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // Update dialogue state to expect the number of slots
+    dialogue.update(State::AwaitingValiditySlotsInput).await?;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.show_validity_slots_prompt(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle the transaction validity reset
+async fn handle_reset_validity_slots(
+    bot: &Bot,
+    message: Message,
+    dialogue: MyDialogue,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // The validity prompt is answered with the button instead
+    dialogue.update(State::Start).await?;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.reset_validity_slots(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle low SOL balance threshold selections
+async fn handle_preset_low_balance(
+    bot: &Bot,
+    callback_data: &str,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // Extract threshold from callback data (format: "low_balance_<value>" or "low_balance_off")
+    let value = callback_data.strip_prefix("low_balance_").unwrap_or("off");
+    let low_balance_sol = match value {
+        "off" => 0.0,
+        value => match value.parse::<f64>() {
+            Ok(value) => value,
+            Err(_) => return Ok(()),
+        },
+    };
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter
+        .set_low_balance_sol(telegram_id, low_balance_sol)
+        .await?;
+
+    Ok(())
+}
+
+// Function to show the auto-confirm thresholds
+async fn handle_set_auto_confirm(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.show_auto_confirm_prompt(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle auto-confirm threshold selections
+async fn handle_preset_auto_confirm(
+    bot: &Bot,
+    callback_data: &str,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // Extract threshold from callback data (format: "auto_confirm_<value>" or "auto_confirm_off")
+    let value = callback_data.strip_prefix("auto_confirm_").unwrap_or("off");
+    let auto_confirm_below_sol = match value {
+        "off" => 0.0,
+        value => match value.parse::<f64>() {
+            Ok(value) => value,
+            Err(_) => return Ok(()),
+        },
+    };
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter
+        .set_auto_confirm_below_sol(telegram_id, auto_confirm_below_sol)
+        .await?;
+
+    Ok(())
+}
+
+// Function to show the presets of a spending limit
+async fn handle_set_trade_limit(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    kind: crate::entity::TradeLimitKind,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.show_trade_limit_prompt(telegram_id, kind).await?;
+
+    Ok(())
+}
+
+// Function to handle spending limit preset selections
+async fn handle_preset_trade_limit(
+    bot: &Bot,
+    callback_data: &str,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    use crate::entity::TradeLimitKind;
+
+    let chat_id = message.chat.id;
+
+    // Extract the limit from callback data (format: "max_trade_<sol|off>" or "daily_limit_<sol|off>")
+    let (kind, value) = if let Some(value) = callback_data.strip_prefix("max_trade_") {
+        (TradeLimitKind::MaxTrade, value)
+    } else {
+        (
+            TradeLimitKind::Daily,
+            callback_data.strip_prefix("daily_limit_").unwrap_or("off"),
+        )
+    };
+    let limit = value.parse::<f64>().ok();
+
+    let db_pool = services.db_pool();
+    let interactor =
+        Arc::new(crate::interactor::settings_interactor::SettingsInteractorImpl::new(db_pool));
+    let view = Arc::new(crate::view::settings_view::TelegramSettingsView::new(
+        bot.clone(),
+        chat_id,
+    ));
+    let presenter =
+        crate::presenter::settings_presenter::SettingsPresenterImpl::new(interactor, view);
+
+    presenter.set_trade_limit(telegram_id, kind, limit).await?;
+
+    Ok(())
+}
+
+// Function to show watchlist menu
+async fn handle_watchlist_menu(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // Create presenter for watchlist
+    let db_pool = services.db_pool();
+    let price_service = services.price_service();
+    let token_repository = services.token_repository();
+
+    let interactor = Arc::new(
+        crate::interactor::watchlist_interactor::WatchlistInteractorImpl::new(
+            db_pool,
+            price_service.clone(),
+            token_repository,
+        ),
+    );
+    let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+    let view = Arc::new(
+        crate::view::watchlist_view::TelegramWatchlistView::new(bot.clone(), chat_id)
+            .with_locale(locale),
+    );
+    let presenter = crate::presenter::watchlist_presenter::WatchlistPresenterImpl::new(
+        interactor,
+        view,
+        price_service,
+    );
+
+    // Show watchlist
+    presenter.show_watchlist(telegram_id).await?;
+
+    Ok(())
+}
+
+// Function to handle adding to watchlist
+async fn handle_watchlist_add(
+    bot: &Bot,
+    message: Message,
+    dialogue: MyDialogue,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // Update dialogue state to expect token address
+    dialogue
+        .update(State::AwaitingWatchlistTokenAddress)
+        .await?;
+
+    // Create presenter
+    let db_pool = services.db_pool();
+    let price_service = services.price_service();
+    let token_repository = services.token_repository();
+
+    let interactor = Arc::new(
+        crate::interactor::watchlist_interactor::WatchlistInteractorImpl::new(
+            db_pool,
+            price_service.clone(),
+            token_repository,
+        ),
+    );
+    let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+    let view = Arc::new(
+        crate::view::watchlist_view::TelegramWatchlistView::new(bot.clone(), chat_id)
+            .with_locale(locale),
+    );
     let presenter = crate::presenter::watchlist_presenter::WatchlistPresenterImpl::new(
         interactor,
         view,
@@ -796,10 +1960,11 @@ async fn handle_watchlist_refresh(
             token_repository,
         ),
     );
-    let view = Arc::new(crate::view::watchlist_view::TelegramWatchlistView::new(
-        bot.clone(),
-        chat_id,
-    ));
+    let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+    let view = Arc::new(
+        crate::view::watchlist_view::TelegramWatchlistView::new(bot.clone(), chat_id)
+            .with_locale(locale),
+    );
     let presenter = crate::presenter::watchlist_presenter::WatchlistPresenterImpl::new(
         interactor,
         view,
@@ -812,6 +1977,55 @@ async fn handle_watchlist_refresh(
     Ok(())
 }
 
+// Function to page, sort or filter the watchlist message
+async fn handle_watchlist_view_change(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    callback_data: &str,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let price_service = services.price_service();
+
+    let interactor = Arc::new(
+        crate::interactor::watchlist_interactor::WatchlistInteractorImpl::new(
+            services.db_pool(),
+            price_service.clone(),
+            services.token_repository(),
+        ),
+    );
+    let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+    let view = Arc::new(
+        crate::view::watchlist_view::TelegramWatchlistView::new(bot.clone(), message.chat.id)
+            .with_locale(locale),
+    );
+    let presenter = crate::presenter::watchlist_presenter::WatchlistPresenterImpl::new(
+        interactor,
+        view,
+        price_service,
+    );
+
+    if let Some(page) = callback_data.strip_prefix("watchlist_page_") {
+        if let Ok(page) = page.parse::<usize>() {
+            presenter
+                .show_watchlist_page(telegram_id, message.id, page)
+                .await?;
+        }
+    } else if let Some(code) = callback_data.strip_prefix("watchlist_sort_") {
+        if let Some(sort) = WatchlistSort::from_code(code) {
+            presenter
+                .sort_watchlist(telegram_id, message.id, sort)
+                .await?;
+        }
+    } else {
+        presenter
+            .toggle_alerts_only(telegram_id, message.id)
+            .await?;
+    }
+
+    Ok(())
+}
+
 // Function to view token details
 async fn handle_watchlist_view_token(
     bot: &Bot,
@@ -834,10 +2048,11 @@ async fn handle_watchlist_view_token(
             token_repository,
         ),
     );
-    let view = Arc::new(crate::view::watchlist_view::TelegramWatchlistView::new(
-        bot.clone(),
-        chat_id,
-    ));
+    let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+    let view = Arc::new(
+        crate::view::watchlist_view::TelegramWatchlistView::new(bot.clone(), chat_id)
+            .with_locale(locale),
+    );
     let presenter = crate::presenter::watchlist_presenter::WatchlistPresenterImpl::new(
         interactor,
         view,
@@ -874,10 +2089,11 @@ async fn handle_watchlist_remove_token(
             token_repository,
         ),
     );
-    let view = Arc::new(crate::view::watchlist_view::TelegramWatchlistView::new(
-        bot.clone(),
-        chat_id,
-    ));
+    let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+    let view = Arc::new(
+        crate::view::watchlist_view::TelegramWatchlistView::new(bot.clone(), chat_id)
+            .with_locale(locale),
+    );
     let presenter = crate::presenter::watchlist_presenter::WatchlistPresenterImpl::new(
         interactor,
         view,
@@ -919,10 +2135,11 @@ async fn handle_withdraw_start(
             price_service,
         ),
     );
-    let view = Arc::new(crate::view::withdraw_view::TelegramWithdrawView::new(
-        bot.clone(),
-        chat_id,
-    ));
+    let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+    let view = Arc::new(
+        crate::view::withdraw_view::TelegramWithdrawView::new(bot.clone(), chat_id)
+            .with_locale(locale),
+    );
     let presenter =
         crate::presenter::withdraw_presenter::WithdrawPresenterImpl::new(interactor, view);
 
@@ -932,6 +2149,70 @@ async fn handle_withdraw_start(
     Ok(())
 }
 
+// Function to send a confirmed batch withdrawal
+async fn handle_batch_withdraw_confirm(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    dialogue: MyDialogue,
+    continue_on_error: bool,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    let withdrawals = match dialogue.get().await?.unwrap_or_default() {
+        State::AwaitingBatchWithdrawConfirmation { withdrawals } => withdrawals,
+        _ => {
+            bot.send_message(chat_id, "Nothing to confirm, this action has expired.")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // Reset the state first so a second tap doesn't send the batch again
+    dialogue.update(State::Start).await?;
+
+    crate::commands::withdraw::execute_batch_withdraw(
+        bot,
+        chat_id,
+        telegram_id,
+        withdrawals,
+        continue_on_error,
+        services,
+    )
+    .await
+}
+
+// Function to show another page of the withdraw token selection
+async fn handle_withdraw_page(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    page: usize,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let interactor = Arc::new(
+        crate::interactor::withdraw_interactor::WithdrawInteractorImpl::new(
+            services.db_pool(),
+            services.solana_client(),
+            services.price_service(),
+        ),
+    );
+    let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+    let view = Arc::new(
+        crate::view::withdraw_view::TelegramWithdrawView::new(bot.clone(), message.chat.id)
+            .with_locale(locale),
+    );
+    let presenter =
+        crate::presenter::withdraw_presenter::WithdrawPresenterImpl::new(interactor, view);
+
+    presenter
+        .show_token_page(telegram_id, message.id, page)
+        .await?;
+
+    Ok(())
+}
+
 // Function to handle token selection
 async fn handle_withdraw_token_selection(
     bot: &Bot,
@@ -998,16 +2279,16 @@ async fn handle_withdraw_token_selection(
                                 "<b>{} Token Details</b>\n\n\
                                 • Symbol: <b>{}</b>\n\
                                 • Address: <code>{}</code>\n\
-                                • Your Balance: <b>{:.6}</b>\n\
-                                • Price: <b>{:.6} SOL</b> (${:.2})\n\
+                                • Your Balance: <b>{}</b>\n\
+                                • Price: <b>{} SOL</b> ({})\n\
                                 • Total Value: <b>{:.6} SOL</b> (${:.2})\n\n\
-                                Enter the recipient's Solana address:",
+                                Enter the recipient's Solana address or Solana Pay link, or send a photo of its QR code:",
                                 token_balance.symbol,
                                 token_balance.symbol,
                                 short_address,
-                                token_balance.amount,
-                                price_in_sol,
-                                price_in_usdc,
+                                token_balance.display_amount(),
+                                format_price(price_in_sol),
+                                format_usd_price(price_in_usdc),
                                 total_sol_value,
                                 total_usdc_value
                             ),
@@ -1071,27 +2352,8 @@ async fn handle_sell_start(
                     "You don't have any tokens to sell. Please deposit some tokens to your wallet first."
                 ).await?;
             } else {
-                // Create keyboard buttons for each token
-                let mut keyboard_buttons = Vec::new();
-
-                for token in tokens {
-                    if token.symbol != "SOL" {
-                        // Exclude SOL from the sell options
-                        let token_text = format!("{}: {:.6}", token.symbol, token.amount);
-                        keyboard_buttons.push(vec![InlineKeyboardButton::callback(
-                            token_text,
-                            format!("sell_token_{}", token.mint_address),
-                        )]);
-                    }
-                }
-
-                // Add cancel button
-                keyboard_buttons.push(vec![InlineKeyboardButton::callback("← Cancel", "menu")]);
-
-                let keyboard = InlineKeyboardMarkup::new(keyboard_buttons);
-
                 bot.send_message(chat_id, "Select a token to sell:")
-                    .reply_markup(keyboard)
+                    .reply_markup(create_sell_token_keyboard(tokens, 0))
                     .await?;
             }
         }
@@ -1112,10 +2374,55 @@ async fn handle_sell_start(
     Ok(())
 }
 
+// Function to show another page of the sell token selection
+async fn handle_sell_page(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    page: usize,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    let db_pool = services.db_pool();
+    let solana_client = services.solana_client();
+
+    match crate::commands::trade::get_user_tokens(telegram_id, db_pool, solana_client).await {
+        Ok(tokens) => {
+            bot.edit_message_reply_markup(chat_id, message.id)
+                .reply_markup(create_sell_token_keyboard(tokens, page))
+                .await?;
+        }
+        Err(e) => {
+            bot.send_message(chat_id, format!("Error retrieving tokens: {}", e))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Keyboard with one button per token except native SOL, split into pages
+fn create_sell_token_keyboard(
+    tokens: Vec<crate::entity::TokenBalance>,
+    page: usize,
+) -> InlineKeyboardMarkup {
+    let token_buttons = ui::sell_token_buttons(tokens);
+
+    let mut keyboard_buttons = ui::paginate_buttons(token_buttons, page, "sell_page_");
+
+    // Add cancel button
+    keyboard_buttons.push(vec![InlineKeyboardButton::callback("← Cancel", "menu")]);
+
+    InlineKeyboardMarkup::new(keyboard_buttons)
+}
+
 // Function to handle token selection for sell
+// A given amount, e.g. "All", skips the amount prompt and goes to the confirmation
 async fn handle_sell_token_selection(
     bot: &Bot,
     token_address: &str,
+    amount_text: Option<&str>,
     message: Message,
     telegram_id: i64,
     dialogue: MyDialogue,
@@ -1123,6 +2430,12 @@ async fn handle_sell_token_selection(
 ) -> Result<()> {
     let chat_id = message.chat.id;
 
+    // Swapping wrapped SOL to SOL is a no-op Jupiter may reject, it is unwrapped instead
+    if token_address == SOL_MINT {
+        dialogue.update(State::Start).await?;
+        return handle_unwrap_sol_prompt(bot, chat_id, telegram_id, services).await;
+    }
+
     // Get token info and current price
     let db_pool = services.db_pool();
     let solana_client = services.solana_client();
@@ -1149,16 +2462,29 @@ async fn handle_sell_token_selection(
                         let total_value_sol = token.amount * price_in_sol;
                         let total_value_usdc = token.amount * price_in_usdc;
 
+                        let state = State::AwaitingSellAmount {
+                            token_address: token_address.to_string(),
+                            token_symbol: token.symbol.clone(),
+                            balance: token.amount,
+                            price_in_sol,
+                            price_in_usdc,
+                        };
+
+                        if let Some(amount_text) = amount_text {
+                            return trade::process_sell_amount(
+                                bot,
+                                chat_id,
+                                telegram_id,
+                                amount_text,
+                                state,
+                                &dialogue,
+                                &services,
+                            )
+                            .await;
+                        }
+
                         // Update dialogue state
-                        dialogue
-                            .update(State::AwaitingSellAmount {
-                                token_address: token_address.to_string(),
-                                token_symbol: token.symbol.clone(),
-                                balance: token.amount,
-                                price_in_sol,
-                                price_in_usdc,
-                            })
-                            .await?;
+                        dialogue.update(state).await?;
 
                         // Display token details and prompt for amount
                         bot.send_message(
@@ -1166,8 +2492,8 @@ async fn handle_sell_token_selection(
                             format!(
                                 "<b>{} Token Details</b>\n\n\
                                 • Symbol: <b>{}</b>\n\
-                                • Your Balance: <b>{:.6}</b>\n\
-                                • Current Price: <b>{:.6} SOL</b> (${:.2})\n\
+                                • Your Balance: <b>{}</b>\n\
+                                • Current Price: <b>{} SOL</b> ({})\n\
                                 • Total Value: <b>{:.6} SOL</b> (${:.2})\n\n\
                                 How many tokens do you want to sell?\n\
                                 • Enter a specific amount (e.g. <code>10.5</code>)\n\
@@ -1175,9 +2501,9 @@ async fn handle_sell_token_selection(
                                 • Or type <code>All</code> to sell your entire balance",
                                 token.symbol,
                                 token.symbol,
-                                token.amount,
-                                price_in_sol,
-                                price_in_usdc,
+                                token.display_amount(),
+                                format_price(price_in_sol),
+                                format_usd_price(price_in_usdc),
                                 total_value_sol,
                                 total_value_usdc
                             ),
@@ -1210,6 +2536,99 @@ async fn handle_sell_token_selection(
     Ok(())
 }
 
+// Function to offer unwrapping the wallet's wrapped SOL
+async fn handle_unwrap_sol_prompt(
+    bot: &Bot,
+    chat_id: ChatId,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let tokens =
+        trade::get_user_tokens(telegram_id, services.db_pool(), services.solana_client()).await?;
+    let wrapped_amount: f64 = tokens
+        .iter()
+        .filter(|token| token.mint_address == SOL_MINT)
+        .map(|token| token.amount)
+        .sum();
+
+    if wrapped_amount <= 0.0 {
+        bot.send_message(chat_id, "There is no wrapped SOL in your wallet.")
+            .await?;
+        return Ok(());
+    }
+
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback("🔄 Unwrap to SOL", "unwrap_sol"),
+        InlineKeyboardButton::callback("← Cancel", "menu"),
+    ]]);
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "<b>Wrapped SOL</b>\n\n\
+            You have <b>{:.6} wSOL</b>. Wrapped SOL is SOL held in a token account, \
+            it can't be sold for SOL.\n\n\
+            Unwrapping closes the account and returns the wSOL and the account rent \
+            to your wallet as SOL.",
+            wrapped_amount
+        ),
+    )
+    .parse_mode(ParseMode::Html)
+    .reply_markup(keyboard)
+    .await?;
+
+    Ok(())
+}
+
+// Function to unwrap the wallet's wrapped SOL into native SOL
+async fn handle_unwrap_sol(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    // Avoid a second unwrap from a double tap while the first is in flight
+    bot.edit_message_reply_markup(chat_id, message.id).await?;
+
+    let processing_msg = bot.send_message(chat_id, "Unwrapping SOL...").await?;
+
+    let interactor = TradeInteractorImpl::new(
+        services.db_pool(),
+        services.solana_client(),
+        services.price_service(),
+        services.token_repository(),
+        services.swap_service(),
+    );
+
+    let text = match interactor.unwrap_sol(telegram_id).await {
+        Ok((signature, sol_received)) => {
+            let explorer = db::get_user_by_telegram_id(&services.db_pool(), telegram_id)
+                .await
+                .map(|user| user.get_explorer())
+                .unwrap_or_default();
+
+            format!(
+                "✅ <b>SOL Unwrapped</b>\n\n\
+                • Received: <b>{:.6} SOL</b>\n\
+                • Transaction: <a href=\"{}\">View on {}</a>",
+                sol_received,
+                crate::utils::format_tx_url(&signature, explorer, Cluster::current()),
+                explorer
+            )
+        }
+        Err(e) => format!("❌ Failed to unwrap SOL: {}", e),
+    };
+
+    bot.edit_message_text(chat_id, processing_msg.id, text)
+        .parse_mode(ParseMode::Html)
+        .reply_markup(ui::create_wallet_menu_keyboard())
+        .await?;
+
+    Ok(())
+}
+
 // Function to start the buy flow with token selection
 async fn handle_buy_start(
     bot: &Bot,
@@ -1223,6 +2642,40 @@ async fn handle_buy_start(
     // Update dialogue state
     dialogue.update(State::AwaitingBuyTokenSelection).await?;
 
+    let token_buttons = get_buy_token_buttons(telegram_id, &services).await;
+
+    bot.send_message(
+        chat_id,
+        "Select a token to buy or enter a contract address manually:",
+    )
+    .reply_markup(create_buy_token_keyboard(token_buttons, 0))
+    .await?;
+
+    Ok(())
+}
+
+// Function to show another page of the buy token selection
+async fn handle_buy_page(
+    bot: &Bot,
+    message: Message,
+    telegram_id: i64,
+    page: usize,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let token_buttons = get_buy_token_buttons(telegram_id, &services).await;
+
+    bot.edit_message_reply_markup(message.chat.id, message.id)
+        .reply_markup(create_buy_token_keyboard(token_buttons, page))
+        .await?;
+
+    Ok(())
+}
+
+// Buttons for owned tokens, watchlist tokens and stablecoins
+async fn get_buy_token_buttons(
+    telegram_id: i64,
+    services: &Arc<ServiceContainer>,
+) -> Vec<Vec<InlineKeyboardButton>> {
     // Create set to track token addresses to avoid duplicates
     let mut token_addresses = std::collections::HashSet::new();
     let mut keyboard_buttons = Vec::new();
@@ -1277,7 +2730,21 @@ async fn handle_buy_start(
         )]);
     }
 
-    // Step 4: Add button for manual address entry
+    keyboard_buttons
+}
+
+// Keyboard with a page of token buttons followed by manual entry and cancel
+fn create_buy_token_keyboard(
+    token_buttons: Vec<Vec<InlineKeyboardButton>>,
+    page: usize,
+) -> InlineKeyboardMarkup {
+    let mut keyboard_buttons = ui::paginate_buttons(token_buttons, page, "buy_page_");
+
+    // Step 4: Add buttons for token search and manual address entry
+    keyboard_buttons.push(vec![InlineKeyboardButton::callback(
+        "🔍 Search Token",
+        "buy_search",
+    )]);
     keyboard_buttons.push(vec![InlineKeyboardButton::callback(
         "Enter Token Address Manually",
         "buy_manual_address",
@@ -1286,13 +2753,17 @@ async fn handle_buy_start(
     // Add cancel button
     keyboard_buttons.push(vec![InlineKeyboardButton::callback("← Cancel", "menu")]);
 
-    let keyboard = InlineKeyboardMarkup::new(keyboard_buttons);
+    InlineKeyboardMarkup::new(keyboard_buttons)
+}
+
+// Function to ask for a token symbol or name to search for
+async fn handle_buy_search(bot: &Bot, message: Message, dialogue: MyDialogue) -> Result<()> {
+    dialogue.update(State::AwaitingBuyTokenSearch).await?;
 
     bot.send_message(
-        chat_id,
-        "Select a token to buy or enter a contract address manually:",
+        message.chat.id,
+        "Enter the token symbol or name to search for, e.g. BONK or Jupiter:",
     )
-    .reply_markup(keyboard)
     .await?;
 
     Ok(())
@@ -1316,10 +2787,61 @@ async fn handle_buy_manual_address(
     Ok(())
 }
 
-// Function to handle token selection
+// Function to handle a quick-buy button, goes straight to the buy confirmation
+async fn handle_quick_buy(
+    bot: &Bot,
+    amount: f64,
+    token_address: &str,
+    message: Message,
+    telegram_id: i64,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = message.chat.id;
+
+    let interactor = TradeInteractorImpl::new(
+        services.db_pool(),
+        services.solana_client(),
+        services.price_service(),
+        services.token_repository(),
+        services.swap_service(),
+    );
+
+    // Refresh the price, the buttons may have been shown a while ago
+    match interactor.get_token_info(token_address).await {
+        Ok((token_symbol, price_in_sol, price_in_usdc)) => {
+            let state = State::AwaitingBuyAmount {
+                token_address: token_address.to_string(),
+                token_symbol,
+                price_in_sol,
+                price_in_usdc,
+            };
+
+            trade::process_buy_amount(
+                bot,
+                chat_id,
+                telegram_id,
+                &format!("{} SOL", amount),
+                state,
+                &dialogue,
+                &services,
+            )
+            .await?;
+        }
+        Err(e) => {
+            bot.send_message(chat_id, BotError::token_info_message(&e))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Function to handle token selection, `check_risk` warns about scam tokens first
 async fn handle_buy_token_selection(
     bot: &Bot,
     token_address: &str,
+    check_risk: bool,
     message: Message,
     telegram_id: i64,
     dialogue: MyDialogue,
@@ -1345,6 +2867,20 @@ async fn handle_buy_token_selection(
     // Get token information
     match interactor.get_token_info(token_address).await {
         Ok((token_symbol, price_in_sol, price_in_usdc)) => {
+            if check_risk {
+                let risk = services.token_risk().assess(token_address).await;
+                if risk.is_risky() {
+                    return ui::send_token_risk_warning(
+                        bot,
+                        chat_id,
+                        token_address,
+                        &risk,
+                        format!("buy_risk_ok_{}", token_address),
+                    )
+                    .await;
+                }
+            }
+
             // Update dialogue state
             dialogue
                 .update(State::AwaitingBuyAmount {
@@ -1355,26 +2891,88 @@ async fn handle_buy_token_selection(
                 })
                 .await?;
 
-            // Display token info with pricing
+            // Display token info with pricing and the quick-buy buttons
+            let quick_buy_amounts = trade::get_quick_buy_amounts(&services, telegram_id).await;
             bot.send_message(
                 chat_id,
                 format!(
                     "<b>{} Token Details</b>\n\n\
                     • Symbol: <b>{}</b>\n\
                     • Address: <code>{}</code>\n\
-                    • Current Price: <b>{:.6} SOL</b> (${:.2})\n\n\
-                    How many tokens do you want to buy?",
-                    token_symbol, token_symbol, token_address, price_in_sol, price_in_usdc
+                    • Current Price: <b>{} SOL</b> ({})\n\n\
+                    How many tokens do you want to buy?\n\
+                    You can also enter the SOL to spend, e.g. <code>0.5 SOL</code>, <code>25%</code> or <code>All</code>, \
+                    or tap a quick-buy amount.",
+                    token_symbol,
+                    token_symbol,
+                    token_address,
+                    format_price(price_in_sol),
+                    format_usd_price(price_in_usdc)
                 ),
             )
             .parse_mode(ParseMode::Html)
+            .reply_markup(trade::create_quick_buy_keyboard(&quick_buy_amounts, token_address))
             .await?;
         }
         Err(e) => {
-            bot.send_message(chat_id, format!("Error getting token info: {}", e))
+            bot.send_message(chat_id, BotError::token_info_message(&e))
                 .await?;
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{fake_telegram, run_with_large_stack, services};
+    use teloxide::dispatching::dialogue::{Dialogue, InMemStorage};
+
+    fn callback_query(message: Option<serde_json::Value>) -> CallbackQuery {
+        let mut query = serde_json::json!({
+            "id": "42",
+            "from": { "id": 1, "is_bot": false, "first_name": "Alice" },
+            "chat_instance": "1",
+            "data": "menu",
+        });
+        if let Some(message) = message {
+            query["message"] = message;
+        }
+        serde_json::from_value(query).unwrap()
+    }
+
+    fn assert_answered_without_message(q: CallbackQuery) {
+        run_with_large_stack(|| async move {
+            let (bot, mut calls) = fake_telegram().await;
+            let dialogue = Dialogue::new(InMemStorage::<State>::new(), ChatId(1));
+
+            handle_callback(bot, q, dialogue, services()).await.unwrap();
+
+            assert!(calls.recv().await.unwrap().is("answerCallbackQuery"));
+            assert!(calls.try_recv().is_err());
+        });
+    }
+
+    #[test]
+    fn callback_on_inaccessible_message_is_answered() {
+        // Telegram marks messages the bot can no longer access with a zero date
+        let q = callback_query(Some(serde_json::json!({
+            "chat": { "id": 1, "type": "private", "first_name": "Alice" },
+            "message_id": 7,
+            "date": 0,
+        })));
+        assert!(q.message.is_some());
+        assert!(q.regular_message().is_none());
+
+        assert_answered_without_message(q);
+    }
+
+    #[test]
+    fn callback_without_message_is_answered() {
+        let q = callback_query(None);
+        assert!(q.message.is_none());
+
+        assert_answered_without_message(q);
+    }
+}
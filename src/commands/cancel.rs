@@ -0,0 +1,112 @@
+use anyhow::Result;
+use log::info;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::entity::State;
+
+pub struct CancelCommand;
+
+impl CommandHandler for CancelCommand {
+    fn command_name() -> &'static str {
+        "cancel"
+    }
+
+    fn description() -> &'static str {
+        "cancel the current operation"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        dialogue: Option<MyDialogue>,
+        _services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        info!("Cancel command received from Telegram ID: {}", telegram_id);
+
+        let state = match &dialogue {
+            Some(dialogue) => dialogue.get().await?.unwrap_or_default(),
+            None => State::Start,
+        };
+
+        if matches!(state, State::Start) {
+            bot.send_message(msg.chat.id, "There is nothing to cancel.")
+                .await?;
+            return Ok(());
+        }
+
+        if let Some(dialogue) = dialogue {
+            dialogue.update(State::Start).await?;
+        }
+
+        bot.send_message(
+            msg.chat.id,
+            "Operation cancelled. Use /menu to start again.",
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{fake_telegram, run_with_large_stack, services, text_message};
+    use teloxide::dispatching::dialogue::{Dialogue, InMemStorage};
+
+    // Cancels from `state` and returns the dialogue state and the reply sent
+    fn cancel_from(state: State) -> (State, String) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        run_with_large_stack(move || async move {
+            let (bot, mut calls) = fake_telegram().await;
+            let dialogue = Dialogue::new(InMemStorage::<State>::new(), ChatId(1));
+            dialogue.update(state).await.unwrap();
+
+            CancelCommand::execute(
+                bot,
+                text_message(1, "/cancel"),
+                1,
+                Some(dialogue.clone()),
+                services(),
+            )
+            .await
+            .unwrap();
+
+            let call = calls.recv().await.unwrap();
+            assert!(call.is("sendMessage"));
+            let state = dialogue.get().await.unwrap().unwrap_or_default();
+            tx.send((state, call.body)).unwrap();
+        });
+        rx.recv().unwrap()
+    }
+
+    #[test]
+    fn cancel_mid_withdraw_clears_the_pending_amount() {
+        let (state, reply) = cancel_from(State::AwaitingAmount {
+            recipient: "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM".to_string(),
+        });
+
+        assert!(matches!(state, State::Start));
+        assert!(reply.contains("Operation cancelled"));
+    }
+
+    #[test]
+    fn cancel_mid_price_lookup_returns_to_start() {
+        let (state, reply) = cancel_from(State::AwaitingPriceTokenAddress);
+
+        assert!(matches!(state, State::Start));
+        assert!(reply.contains("Operation cancelled"));
+    }
+
+    #[test]
+    fn cancel_without_an_operation_says_so() {
+        let (state, reply) = cancel_from(State::Start);
+
+        assert!(matches!(state, State::Start));
+        assert!(reply.contains("nothing to cancel"));
+    }
+}
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use log::info;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::interactor::cleanup_interactor::CleanupInteractorImpl;
+use crate::presenter::cleanup_presenter::{CleanupPresenter, CleanupPresenterImpl};
+use crate::view::cleanup_view::TelegramCleanupView;
+
+pub struct CleanupCommand;
+
+impl CommandHandler for CleanupCommand {
+    fn command_name() -> &'static str {
+        "cleanup"
+    }
+
+    fn description() -> &'static str {
+        "close empty token accounts and reclaim their rent"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        info!("Cleanup command initiated by user: {}", telegram_id);
+
+        let presenter = create_presenter(&bot, msg.chat.id, &services);
+        presenter.show_cleanup_preview(telegram_id).await
+    }
+}
+
+fn create_presenter(
+    bot: &Bot,
+    chat_id: ChatId,
+    services: &Arc<ServiceContainer>,
+) -> CleanupPresenterImpl<CleanupInteractorImpl, TelegramCleanupView> {
+    let interactor = Arc::new(CleanupInteractorImpl::new(
+        services.db_pool(),
+        services.solana_client(),
+    ));
+    let view = Arc::new(TelegramCleanupView::new(bot.clone(), chat_id));
+
+    CleanupPresenterImpl::new(interactor, view)
+}
+
+// Handler for the "Close Accounts" button
+pub async fn confirm_cleanup(
+    bot: Bot,
+    msg: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    // Empty accounts are looked up again, so a stale button only closes what is still empty
+    let presenter = create_presenter(&bot, msg.chat.id, &services);
+    presenter.execute_cleanup(telegram_id).await
+}
+
+// Handler for the "Cancel" button
+pub async fn cancel_cleanup(bot: Bot, msg: Message, services: Arc<ServiceContainer>) -> Result<()> {
+    let presenter = create_presenter(&bot, msg.chat.id, &services);
+    presenter.cancel_cleanup().await
+}
@@ -0,0 +1,144 @@
+use anyhow::Result;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{limit_order, trade, withdraw, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::entity::{OrderType, State};
+
+// Handler for the inline "Confirm" button under buy, sell, withdraw and limit order prompts
+pub async fn confirm_pending(
+    bot: Bot,
+    msg: Message,
+    telegram_id: i64,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = msg.chat.id;
+    let state = dialogue.get().await?.unwrap_or_default();
+
+    if !is_confirmation_state(&state) {
+        bot.send_message(chat_id, "Nothing to confirm, this action has expired.")
+            .await?;
+        return Ok(());
+    }
+
+    // High price impact trades are confirmed twice
+    if trade::warn_high_price_impact(&bot, chat_id, &dialogue, &state).await? {
+        return Ok(());
+    }
+
+    // Reset the state first so a second tap doesn't execute the action again
+    dialogue.update(State::Start).await?;
+
+    match state {
+        State::AwaitingBuyConfirmation {
+            token_address,
+            token_symbol,
+            amount,
+            price_in_sol,
+            total_sol,
+            ..
+        } => {
+            trade::execute_confirmed_trade(
+                bot,
+                chat_id,
+                telegram_id,
+                trade::ConfirmedTrade {
+                    order_type: OrderType::Buy,
+                    token_address: &token_address,
+                    token_symbol: &token_symbol,
+                    amount,
+                    price_in_sol,
+                    total_sol,
+                },
+                services,
+            )
+            .await?;
+        }
+        State::AwaitingSellConfirmation {
+            token_address,
+            token_symbol,
+            amount,
+            price_in_sol,
+            total_sol,
+            ..
+        } => {
+            trade::execute_confirmed_trade(
+                bot,
+                chat_id,
+                telegram_id,
+                trade::ConfirmedTrade {
+                    order_type: OrderType::Sell,
+                    token_address: &token_address,
+                    token_symbol: &token_symbol,
+                    amount,
+                    price_in_sol,
+                    total_sol,
+                },
+                services,
+            )
+            .await?;
+        }
+        State::AwaitingWithdrawConfirmation {
+            token_address,
+            token_symbol,
+            recipient,
+            amount,
+            price_in_sol,
+            ..
+        } => {
+            withdraw::execute_confirmed_withdraw(
+                bot,
+                chat_id,
+                telegram_id,
+                withdraw::ConfirmedWithdraw {
+                    token_address: &token_address,
+                    token_symbol: &token_symbol,
+                    recipient: &recipient,
+                    amount,
+                    price_in_sol,
+                },
+                services,
+            )
+            .await?;
+        }
+        state @ State::AwaitingLimitOrderConfirmation { .. } => {
+            limit_order::execute_confirmation(bot, chat_id, telegram_id, state, true, services)
+                .await?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+// Handler for the inline "Cancel" button, drops whatever dialogue is in progress
+pub async fn cancel_pending(bot: Bot, msg: Message, dialogue: MyDialogue) -> Result<()> {
+    let state = dialogue.get().await?.unwrap_or_default();
+    dialogue.update(State::Start).await?;
+
+    let text = match state {
+        State::AwaitingBuyConfirmation { .. } | State::AwaitingSellConfirmation { .. } => {
+            "Trade cancelled."
+        }
+        State::AwaitingWithdrawConfirmation { .. } => "Withdrawal cancelled.",
+        State::AwaitingBatchWithdrawConfirmation { .. } => "Batch withdrawal cancelled.",
+        State::AwaitingLimitOrderConfirmation { .. } => "Limit order cancelled.",
+        _ => "Cancelled.",
+    };
+
+    bot.send_message(msg.chat.id, text).await?;
+
+    Ok(())
+}
+
+fn is_confirmation_state(state: &State) -> bool {
+    matches!(
+        state,
+        State::AwaitingBuyConfirmation { .. }
+            | State::AwaitingSellConfirmation { .. }
+            | State::AwaitingWithdrawConfirmation { .. }
+            | State::AwaitingLimitOrderConfirmation { .. }
+    )
+}
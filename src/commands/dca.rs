@@ -0,0 +1,229 @@
+use anyhow::Result;
+use log::info;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{ui, CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::entity::State;
+use crate::interactor::dca_interactor::DcaInteractorImpl;
+use crate::presenter::dca_presenter::{DcaPresenter, DcaPresenterImpl};
+use crate::view::dca_view::TelegramDcaView;
+
+pub struct DcaCommand;
+
+impl CommandHandler for DcaCommand {
+    fn command_name() -> &'static str {
+        "dca"
+    }
+
+    fn description() -> &'static str {
+        "manage recurring (DCA) buys"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        info!("DCA command initiated by user: {}", telegram_id);
+
+        if !ui::ensure_jupiter_available(&bot, msg.chat.id, services.cluster()).await? {
+            return Ok(());
+        }
+
+        let presenter = create_presenter(&bot, msg.chat.id, &services);
+
+        presenter.show_dca_orders(telegram_id).await?;
+
+        Ok(())
+    }
+}
+
+fn create_presenter(
+    bot: &Bot,
+    chat_id: ChatId,
+    services: &Arc<ServiceContainer>,
+) -> DcaPresenterImpl<DcaInteractorImpl, TelegramDcaView> {
+    let interactor = Arc::new(DcaInteractorImpl::new(
+        services.db_pool(),
+        services.price_service(),
+        services.token_repository(),
+    ));
+    let view = Arc::new(TelegramDcaView::new(bot.clone(), chat_id));
+
+    DcaPresenterImpl::new(interactor, view)
+}
+
+// Handler for the "Create DCA Order" button
+pub async fn start_dca_creation(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    dialogue.update(State::AwaitingDcaTokenAddress).await?;
+
+    let presenter = create_presenter(&bot, msg.chat.id, &services);
+    presenter.start_create_order_flow().await?;
+
+    Ok(())
+}
+
+// Handler for the "Cancel #id" buttons
+pub async fn cancel_dca_order(
+    bot: Bot,
+    msg: Message,
+    telegram_id: i64,
+    order_id: i32,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let presenter = create_presenter(&bot, msg.chat.id, &services);
+    presenter.cancel_order(telegram_id, order_id).await?;
+
+    Ok(())
+}
+
+// Handler for the token address state
+pub async fn receive_token_address(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    if let Some(address_text) = msg.text() {
+        let address_text = address_text.trim();
+        let presenter = create_presenter(&bot, msg.chat.id, &services);
+
+        if let Some(token_symbol) = presenter.handle_token_address(address_text).await? {
+            dialogue
+                .update(State::AwaitingDcaAmount {
+                    token_address: address_text.to_string(),
+                    token_symbol,
+                })
+                .await?;
+        }
+    } else {
+        bot.send_message(
+            msg.chat.id,
+            "Please enter the token contract address as text:",
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Handler for the SOL amount per buy state
+pub async fn receive_amount(
+    bot: Bot,
+    msg: Message,
+    state: State,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    if let State::AwaitingDcaAmount {
+        token_address,
+        token_symbol,
+    } = state
+    {
+        if let Some(amount_text) = msg.text() {
+            let presenter = create_presenter(&bot, msg.chat.id, &services);
+
+            if let Some(sol_per_interval) = presenter.handle_amount(amount_text).await? {
+                dialogue
+                    .update(State::AwaitingDcaInterval {
+                        token_address,
+                        token_symbol,
+                        sol_per_interval,
+                    })
+                    .await?;
+            }
+        } else {
+            bot.send_message(msg.chat.id, "Please enter the amount of SOL as text:")
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Handler for the interval state
+pub async fn receive_interval(
+    bot: Bot,
+    msg: Message,
+    state: State,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    if let State::AwaitingDcaInterval {
+        token_address,
+        token_symbol,
+        sol_per_interval,
+    } = state
+    {
+        if let Some(interval_text) = msg.text() {
+            let presenter = create_presenter(&bot, msg.chat.id, &services);
+
+            if let Some(interval_seconds) = presenter.handle_interval(interval_text).await? {
+                dialogue
+                    .update(State::AwaitingDcaRuns {
+                        token_address,
+                        token_symbol,
+                        sol_per_interval,
+                        interval_seconds,
+                    })
+                    .await?;
+            }
+        } else {
+            bot.send_message(msg.chat.id, "Please enter the interval as text, e.g. 1d:")
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Handler for the number of buys state
+pub async fn receive_runs(
+    bot: Bot,
+    msg: Message,
+    state: State,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    if let State::AwaitingDcaRuns {
+        token_address,
+        token_symbol,
+        sol_per_interval,
+        interval_seconds,
+    } = state
+    {
+        if let Some(runs_text) = msg.text() {
+            let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+            let presenter = create_presenter(&bot, msg.chat.id, &services);
+
+            let completed = presenter
+                .handle_runs(
+                    telegram_id,
+                    &token_address,
+                    &token_symbol,
+                    sol_per_interval,
+                    interval_seconds,
+                    runs_text,
+                )
+                .await?;
+
+            if completed {
+                dialogue.update(State::Start).await?;
+            }
+        } else {
+            bot.send_message(msg.chat.id, "Please enter the number of buys as text:")
+                .await?;
+        }
+    }
+
+    Ok(())
+}
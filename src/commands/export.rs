@@ -0,0 +1,124 @@
+use anyhow::Result;
+use log::info;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::interactor::export_interactor::ExportInteractorImpl;
+use crate::presenter::export_presenter::{ExportPresenter, ExportPresenterImpl};
+use crate::view::export_view::TelegramExportView;
+
+pub struct ExportCommand;
+
+impl CommandHandler for ExportCommand {
+    fn command_name() -> &'static str {
+        "export"
+    }
+
+    fn description() -> &'static str {
+        "back up your wallet's recovery phrase and private key"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        info!("Export command initiated by user: {}", telegram_id);
+
+        let presenter = create_presenter(&bot, msg.chat.id, &services);
+        presenter.show_export_warning(msg.chat.is_private()).await
+    }
+}
+
+fn create_presenter(
+    bot: &Bot,
+    chat_id: ChatId,
+    services: &Arc<ServiceContainer>,
+) -> ExportPresenterImpl<ExportInteractorImpl, TelegramExportView> {
+    let interactor = Arc::new(ExportInteractorImpl::new(services.db_pool()));
+    let view = Arc::new(TelegramExportView::new(bot.clone(), chat_id));
+
+    ExportPresenterImpl::new(interactor, view)
+}
+
+// Handler for the "Show Private Key" button
+pub async fn confirm_export(
+    bot: Bot,
+    msg: Message,
+    telegram_id: i64,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    // The private chat check is repeated, the button could have been forwarded
+    let presenter = create_presenter(&bot, msg.chat.id, &services);
+    presenter
+        .export_wallet(telegram_id, msg.chat.is_private())
+        .await
+}
+
+// Handler for the "Cancel" button
+pub async fn cancel_export(bot: Bot, msg: Message, services: Arc<ServiceContainer>) -> Result<()> {
+    let presenter = create_presenter(&bot, msg.chat.id, &services);
+    presenter.cancel_export().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{
+        fake_telegram, group_message, run_with_large_stack, services, text_message,
+    };
+
+    // Runs the handler and returns the only message it sent
+    fn reply_to<F, Fut>(handler: F) -> String
+    where
+        F: FnOnce(Bot) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<()>>,
+    {
+        let (tx, rx) = std::sync::mpsc::channel();
+        run_with_large_stack(move || async move {
+            let (bot, mut calls) = fake_telegram().await;
+
+            handler(bot).await.unwrap();
+
+            let call = calls.recv().await.unwrap();
+            assert!(call.is("sendMessage"));
+            assert!(calls.try_recv().is_err());
+            tx.send(call.body).unwrap();
+        });
+        rx.recv().unwrap()
+    }
+
+    #[test]
+    fn export_is_refused_in_a_group_chat() {
+        let reply = reply_to(|bot| {
+            ExportCommand::execute(bot, group_message(-100, 7, "/export"), 7, None, services())
+        });
+
+        assert!(reply.contains("only be exported in a private chat"));
+        assert!(!reply.contains("confirm_export"));
+    }
+
+    #[test]
+    fn forwarded_confirmation_is_refused_in_a_group_chat() {
+        // The database is never reached, the secrets are not loaded
+        let reply =
+            reply_to(|bot| confirm_export(bot, group_message(-100, 7, "/export"), 7, services()));
+
+        assert!(reply.contains("only be exported in a private chat"));
+    }
+
+    #[test]
+    fn export_in_a_private_chat_asks_for_confirmation() {
+        let reply = reply_to(|bot| {
+            ExportCommand::execute(bot, text_message(7, "/export"), 7, None, services())
+        });
+
+        assert!(reply.contains("Export Wallet"));
+        assert!(reply.contains("confirm_export"));
+        assert!(reply.contains("cancel_export"));
+    }
+}
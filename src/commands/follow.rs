@@ -0,0 +1,99 @@
+use anyhow::Result;
+use log::info;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::entity::State;
+use crate::interactor::follow_interactor::FollowInteractorImpl;
+use crate::presenter::follow_presenter::{FollowPresenter, FollowPresenterImpl};
+use crate::view::follow_view::TelegramFollowView;
+
+pub struct FollowCommand;
+
+impl CommandHandler for FollowCommand {
+    fn command_name() -> &'static str {
+        "follow"
+    }
+
+    fn description() -> &'static str {
+        "get notified when a wallet buys or sells tokens"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        info!("Follow command initiated by user: {}", telegram_id);
+
+        let presenter = create_presenter(&bot, msg.chat.id, &services);
+        presenter.show_followed_wallets(telegram_id).await
+    }
+}
+
+fn create_presenter(
+    bot: &Bot,
+    chat_id: ChatId,
+    services: &Arc<ServiceContainer>,
+) -> FollowPresenterImpl<FollowInteractorImpl, TelegramFollowView> {
+    let interactor = Arc::new(FollowInteractorImpl::new(services.db_pool()));
+    let view = Arc::new(TelegramFollowView::new(bot.clone(), chat_id));
+
+    FollowPresenterImpl::new(interactor, view)
+}
+
+// Handler for the "Follow Wallet" button
+pub async fn start_follow(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    dialogue.update(State::AwaitingFollowWalletAddress).await?;
+
+    let presenter = create_presenter(&bot, msg.chat.id, &services);
+    presenter.start_follow_flow().await
+}
+
+// Handler for the "Unfollow" buttons
+pub async fn unfollow(
+    bot: Bot,
+    msg: Message,
+    telegram_id: i64,
+    followed_wallet_id: i32,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let presenter = create_presenter(&bot, msg.chat.id, &services);
+    presenter
+        .unfollow_wallet(telegram_id, followed_wallet_id)
+        .await
+}
+
+// Handler for the wallet address state
+pub async fn receive_wallet_address(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    if let Some(address_text) = msg.text() {
+        let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+        let presenter = create_presenter(&bot, msg.chat.id, &services);
+
+        if presenter
+            .handle_wallet_address(telegram_id, address_text.trim())
+            .await?
+        {
+            dialogue.update(State::Start).await?;
+        }
+    } else {
+        bot.send_message(msg.chat.id, "Please enter the wallet address as text:")
+            .await?;
+    }
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use super::{CommandHandler, MyDialogue};
 use crate::di::ServiceContainer;
+use crate::i18n;
 use anyhow::Result;
 use std::sync::Arc;
 use teloxide::prelude::*;
@@ -20,16 +21,17 @@ impl CommandHandler for HelpCommand {
         msg: Message,
         telegram_id: i64,
         _dialogue: Option<MyDialogue>,
-        _services: Arc<ServiceContainer>,
+        services: Arc<ServiceContainer>,
     ) -> Result<()> {
-        bot.send_message(
-            msg.chat.id,
-            "Available commands:\n\
-            /start - Start working with the bot\n\
-            /menu - Main menu\n\
-            /help - Show this help",
-        )
-        .await?;
+        let language_code = msg
+            .from
+            .as_ref()
+            .and_then(|user| user.language_code.clone());
+        let locale =
+            i18n::user_locale(&services.db_pool(), telegram_id, language_code.as_deref()).await;
+
+        bot.send_message(msg.chat.id, i18n::t("help.text", locale))
+            .await?;
 
         Ok(())
     }
@@ -0,0 +1,59 @@
+use anyhow::Result;
+use log::info;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::interactor::history_interactor::HistoryInteractorImpl;
+use crate::presenter::history_presenter::{HistoryPresenter, HistoryPresenterImpl};
+use crate::view::history_view::TelegramHistoryView;
+
+pub struct HistoryCommand;
+
+impl CommandHandler for HistoryCommand {
+    fn command_name() -> &'static str {
+        "history"
+    }
+
+    fn description() -> &'static str {
+        "show your transaction history"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        info!("History command initiated by user: {}", telegram_id);
+
+        let interactor = Arc::new(HistoryInteractorImpl::new(services.db_pool()));
+        let view = Arc::new(TelegramHistoryView::new(bot, msg.chat.id));
+        let presenter = HistoryPresenterImpl::new(interactor, view);
+
+        presenter.show_history(telegram_id, 0, None).await?;
+
+        Ok(())
+    }
+}
+
+// Handler for the "Older"/"Newer" buttons, edits the history message in place
+pub async fn show_history_page(
+    bot: Bot,
+    msg: Message,
+    telegram_id: i64,
+    page: usize,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let interactor = Arc::new(HistoryInteractorImpl::new(services.db_pool()));
+    let view = Arc::new(TelegramHistoryView::new(bot, msg.chat.id));
+    let presenter = HistoryPresenterImpl::new(interactor, view);
+
+    presenter
+        .show_history(telegram_id, page, Some(msg.id))
+        .await?;
+
+    Ok(())
+}
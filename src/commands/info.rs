@@ -0,0 +1,67 @@
+use anyhow::Result;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::i18n;
+use crate::interactor::token_info_interactor::TokenInfoInteractorImpl;
+use crate::presenter::token_info_presenter::{TokenInfoPresenter, TokenInfoPresenterImpl};
+use crate::view::token_info_view::TelegramTokenInfoView;
+
+pub struct InfoCommand;
+
+impl CommandHandler for InfoCommand {
+    fn command_name() -> &'static str {
+        "info"
+    }
+
+    fn description() -> &'static str {
+        "show supply, holders and authorities of a token"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        let chat_id = msg.chat.id;
+
+        match msg.text().unwrap_or("").split_whitespace().nth(1) {
+            Some(mint_address) => {
+                show_token_info(&bot, chat_id, telegram_id, mint_address, &services).await
+            }
+            None => {
+                bot.send_message(
+                    chat_id,
+                    "Use the command in this format: /info <token_mint_address>",
+                )
+                .await?;
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Show the fundamentals of a token, used by /info and the "Token Info" button
+pub async fn show_token_info(
+    bot: &Bot,
+    chat_id: ChatId,
+    telegram_id: i64,
+    mint_address: &str,
+    services: &Arc<ServiceContainer>,
+) -> Result<()> {
+    let interactor = Arc::new(TokenInfoInteractorImpl::new(
+        services.solana_client(),
+        services.token_repository(),
+        services.price_service(),
+    ));
+    let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+    let view = Arc::new(TelegramTokenInfoView::new(bot.clone(), chat_id).with_locale(locale));
+    let presenter = TokenInfoPresenterImpl::new(interactor, view);
+
+    presenter.show_token_info(mint_address).await
+}
@@ -0,0 +1,88 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use std::sync::Arc;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+};
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::i18n::{self, Locale};
+use crate::interactor::db;
+
+pub struct LanguageCommand;
+
+impl CommandHandler for LanguageCommand {
+    fn command_name() -> &'static str {
+        "language"
+    }
+
+    fn description() -> &'static str {
+        "change the bot language"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        let language_code = msg
+            .from
+            .as_ref()
+            .and_then(|user| user.language_code.clone());
+        let locale =
+            i18n::user_locale(&services.db_pool(), telegram_id, language_code.as_deref()).await;
+
+        // One button per shipped locale, the current one is marked
+        let keyboard_buttons = Locale::ALL
+            .into_iter()
+            .map(|option| {
+                let text = if option == locale {
+                    format!("✓ {}", option.name())
+                } else {
+                    option.name().to_string()
+                };
+                vec![InlineKeyboardButton::callback(
+                    text,
+                    format!("language_{}", option.code()),
+                )]
+            })
+            .collect::<Vec<_>>();
+
+        bot.send_message(msg.chat.id, i18n::t("language.prompt", locale))
+            .reply_markup(InlineKeyboardMarkup::new(keyboard_buttons))
+            .await?;
+
+        Ok(())
+    }
+}
+
+// Handler for the language selection buttons
+pub async fn set_language(
+    bot: Bot,
+    msg: Message,
+    telegram_id: i64,
+    locale_code: &str,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let locale = Locale::from_code(locale_code)
+        .ok_or_else(|| anyhow!("Unsupported language: {}", locale_code))?;
+
+    db::update_user_locale(&services.db_pool(), telegram_id, &locale)
+        .await
+        .map_err(|e| anyhow!("Failed to update language: {}", e))?;
+
+    info!(
+        "User {} switched language to {}",
+        telegram_id,
+        locale.code()
+    );
+
+    bot.send_message(msg.chat.id, i18n::t("language.changed", locale))
+        .await?;
+
+    Ok(())
+}
@@ -1,13 +1,16 @@
 use anyhow::Result;
 use log::info;
+use std::str::FromStr;
 use std::sync::Arc;
 use teloxide::prelude::*;
 
-use super::{CommandHandler, MyDialogue};
+use super::{ui, CommandHandler, MyDialogue};
 use crate::di::ServiceContainer;
-use crate::entity::{OrderType, State};
+use crate::entity::{BotError, LimitOrder, NewLimitOrder, OrderType, PriceCurrency, State};
+use crate::i18n;
 use crate::interactor::limit_order_interactor::{LimitOrderInteractor, LimitOrderInteractorImpl};
 use crate::presenter::limit_order_presenter::{LimitOrderPresenter, LimitOrderPresenterImpl};
+use crate::utils::{format_price, format_usd, parse_order_expiry};
 use crate::view::limit_order_view::TelegramLimitOrderView;
 
 pub struct LimitOrdersCommand;
@@ -32,6 +35,10 @@ impl CommandHandler for LimitOrdersCommand {
 
         info!("Limit orders command initiated by user: {}", telegram_id);
 
+        if !ui::ensure_jupiter_available(&bot, msg.chat.id, services.cluster()).await? {
+            return Ok(());
+        }
+
         let db_pool = services.db_pool();
         let solana_client = services.solana_client();
         let price_service = services.price_service();
@@ -97,66 +104,106 @@ pub async fn receive_token_address(
 ) -> Result<()> {
     if let State::AwaitingLimitOrderTokenAddress { order_type } = state {
         if let Some(address_text) = msg.text() {
-            let chat_id = msg.chat.id;
+            start_limit_order_for_token(
+                &bot,
+                msg.chat.id,
+                order_type,
+                address_text,
+                true,
+                dialogue,
+                services,
+            )
+            .await?;
+        } else {
+            bot.send_message(
+                msg.chat.id,
+                "Please enter the token contract address as text:",
+            )
+            .await?;
+        }
+    }
 
-            let db_pool = services.db_pool();
-            let solana_client = services.solana_client();
-            let price_service = services.price_service();
-            let token_repository = services.token_repository();
+    Ok(())
+}
 
-            let interactor = Arc::new(LimitOrderInteractorImpl::new(
-                db_pool,
-                solana_client.clone(),
-                price_service.clone(),
-                token_repository.clone(),
-            ));
-            let view = Arc::new(TelegramLimitOrderView::new(bot.clone(), chat_id));
-            let presenter = LimitOrderPresenterImpl::new(interactor.clone(), view);
+// Validate the token and ask for the price and amount, also used to pre-fill the token
+// from the buttons under a finished trade. Limit buys of scam tokens are warned about first
+// when `check_risk` is set.
+pub async fn start_limit_order_for_token(
+    bot: &Bot,
+    chat_id: ChatId,
+    order_type: OrderType,
+    address_text: &str,
+    check_risk: bool,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let db_pool = services.db_pool();
+    let solana_client = services.solana_client();
+    let price_service = services.price_service();
+    let token_repository = services.token_repository();
 
-            // Validate token address
-            if let Ok(is_valid) = interactor.validate_token_address(address_text).await {
-                if is_valid {
-                    // Get token info to show to the user
-                    match interactor.get_token_info(address_text).await {
-                        Ok((token_symbol, price_in_sol, price_in_usdc)) => {
-                            // Update dialogue state
-                            dialogue
-                                .update(State::AwaitingLimitOrderPriceAndAmount {
-                                    order_type: order_type.clone(),
-                                    token_address: address_text.to_string(),
-                                    token_symbol: token_symbol.clone(),
-                                    current_price_in_sol: price_in_sol,
-                                    current_price_in_usdc: price_in_usdc,
-                                })
-                                .await?;
-
-                            presenter
-                                .handle_token_address(address_text, &order_type)
-                                .await?;
-                        }
-                        Err(e) => {
-                            bot.send_message(chat_id, format!("Error getting token info: {}", e))
-                                .await?;
+    let interactor = Arc::new(LimitOrderInteractorImpl::new(
+        db_pool,
+        solana_client.clone(),
+        price_service.clone(),
+        token_repository.clone(),
+    ));
+    let view = Arc::new(TelegramLimitOrderView::new(bot.clone(), chat_id));
+    let presenter = LimitOrderPresenterImpl::new(interactor.clone(), view);
+
+    // Validate token address
+    if let Ok(is_valid) = interactor.validate_token_address(address_text).await {
+        if is_valid {
+            // Get token info to show to the user
+            match interactor.get_token_info(address_text).await {
+                Ok((token_symbol, price_in_sol, price_in_usdc)) => {
+                    if check_risk && !order_type.is_sell() {
+                        let risk = services.token_risk().assess(address_text).await;
+                        if risk.is_risky() {
+                            // The button continues the order with this token
+                            dialogue.update(State::Start).await?;
+                            return ui::send_token_risk_warning(
+                                bot,
+                                chat_id,
+                                address_text,
+                                &risk,
+                                format!("limit_risk_ok_{}", address_text),
+                            )
+                            .await;
                         }
                     }
-                } else {
-                    bot.send_message(
-                        chat_id,
-                        "Invalid token address. Please enter a valid Solana token contract address:",
-                    )
+
+                    // Update dialogue state
+                    dialogue
+                        .update(State::AwaitingLimitOrderPriceAndAmount {
+                            order_type: order_type.clone(),
+                            token_address: address_text.to_string(),
+                            token_symbol: token_symbol.clone(),
+                            current_price_in_sol: price_in_sol,
+                            current_price_in_usdc: price_in_usdc,
+                        })
+                        .await?;
+
+                    presenter
+                        .handle_token_address(address_text, &order_type)
+                        .await?;
+                }
+                Err(e) => {
+                    bot.send_message(chat_id, BotError::token_info_message(&e))
                         .await?;
                 }
-            } else {
-                bot.send_message(chat_id, "Error validating token address. Please try again:")
-                    .await?;
             }
         } else {
             bot.send_message(
-                msg.chat.id,
-                "Please enter the token contract address as text:",
+                chat_id,
+                "Invalid token address. Please enter a valid Solana token contract address:",
             )
             .await?;
         }
+    } else {
+        bot.send_message(chat_id, "Error validating token address. Please try again:")
+            .await?;
     }
 
     Ok(())
@@ -180,26 +227,47 @@ pub async fn receive_price_and_amount(
     {
         if let Some(price_amount_text) = msg.text() {
             let chat_id = msg.chat.id;
-            let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+            let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
 
             let db_pool = services.db_pool();
             let solana_client = services.solana_client();
             let price_service = services.price_service();
             let token_repository = services.token_repository();
 
-            let interactor = Arc::new(LimitOrderInteractorImpl::new(
-                db_pool,
-                solana_client.clone(),
-                price_service.clone(),
-                token_repository.clone(),
-            ));
+            let interactor = Arc::new(
+                LimitOrderInteractorImpl::new(
+                    db_pool,
+                    solana_client.clone(),
+                    price_service.clone(),
+                    token_repository.clone(),
+                )
+                .with_token_supply(services.token_supply()),
+            );
             let view = Arc::new(TelegramLimitOrderView::new(bot.clone(), chat_id));
             let presenter = LimitOrderPresenterImpl::new(interactor.clone(), view);
 
+            // A market cap or FDV target is turned into the SOL price it corresponds to
+            let trigger = match interactor
+                .resolve_trigger(
+                    price_amount_text,
+                    &token_address,
+                    current_price_in_sol,
+                    current_price_in_usdc,
+                )
+                .await
+            {
+                Ok(trigger) => trigger,
+                Err(e) => {
+                    bot.send_message(chat_id, format!("Invalid input: {}", e))
+                        .await?;
+                    return Ok(());
+                }
+            };
+
             // Parse and validate price and amount
             match interactor
                 .validate_order_price_and_amount(
-                    price_amount_text,
+                    &trigger.price_amount_text,
                     &order_type,
                     &token_address,
                     &token_symbol,
@@ -210,24 +278,121 @@ pub async fn receive_price_and_amount(
                 Ok((price, amount, total_sol)) => {
                     // Update dialogue state
                     dialogue
-                        .update(State::AwaitingLimitOrderConfirmation {
+                        .update(State::AwaitingLimitOrderExpiry {
                             order_type: order_type.clone(),
                             token_address: token_address.clone(),
                             token_symbol: token_symbol.clone(),
                             price_in_sol: price,
                             amount,
                             total_sol,
+                            trigger_metric: trigger.trigger_metric,
+                            trigger_value: trigger.trigger_value,
+                            price_currency: trigger.price_currency,
                         })
                         .await?;
 
+                    // Ask how long the order should stay active
+                    bot.send_message(
+                        chat_id,
+                        "How long should the order stay active?\n\nEnter a duration such as 1h, 24h or 7d, or 'never' to keep it until filled or cancelled:",
+                    )
+                        .await?;
+                }
+                Err(e) => {
+                    bot.send_message(chat_id, format!("Invalid input: {}", e))
+                        .await?;
+                }
+            }
+        } else {
+            bot.send_message(
+                msg.chat.id,
+                "Please enter the price and amount in the format: <price> <amount>",
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Handler for expiry state
+pub async fn receive_expiry(
+    bot: Bot,
+    msg: Message,
+    state: State,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    if let State::AwaitingLimitOrderExpiry {
+        order_type,
+        token_address,
+        token_symbol,
+        price_in_sol,
+        amount,
+        total_sol,
+        trigger_metric,
+        trigger_value,
+        price_currency,
+    } = state
+    {
+        if let Some(expiry_text) = msg.text() {
+            let chat_id = msg.chat.id;
+
+            match parse_order_expiry(expiry_text) {
+                Ok(expiry) => {
+                    // Update dialogue state
+                    dialogue
+                        .update(State::AwaitingLimitOrderConfirmation {
+                            order_type: order_type.clone(),
+                            token_address,
+                            token_symbol: token_symbol.clone(),
+                            price_in_sol,
+                            amount,
+                            total_sol,
+                            trigger_metric: trigger_metric.clone(),
+                            trigger_value,
+                            price_currency: price_currency.clone(),
+                            expiry,
+                        })
+                        .await?;
+
+                    let expiry_info = match expiry {
+                        Some(_) => format!("in {}", expiry_text.trim().to_lowercase()),
+                        None => "never".to_string(),
+                    };
+
+                    let locale = i18n::user_locale(
+                        &services.db_pool(),
+                        msg.from.as_ref().map_or(0, |user| user.id.0 as i64),
+                        msg.from
+                            .as_ref()
+                            .and_then(|user| user.language_code.as_deref()),
+                    )
+                    .await;
+                    let trigger_info = match trigger_value {
+                        Some(target) if price_currency == PriceCurrency::Usdc => format!(
+                            "\nTrigger: USD price {} ${}",
+                            order_type.trigger_comparison(),
+                            target
+                        ),
+                        Some(target) => format!(
+                            "\nTrigger: {} {} {}",
+                            trigger_metric.display_name(),
+                            order_type.trigger_comparison(),
+                            format_usd(target, locale)
+                        ),
+                        None => "".to_string(),
+                    };
+
                     // Prompt for confirmation
                     bot.send_message(
                         chat_id,
                         format!(
-                            "Please confirm your limit order:\n\n{} {} {} @ {:.6} SOL each\nTotal: {:.6} SOL\n\nDo you want to proceed? (yes/no)",
-                            order_type, amount, token_symbol, price, total_sol
+                            "Please confirm your limit order:\n\n{} {} {} @ {} SOL each\nTotal: {:.6} SOL{}\nExpires: {}\n\nDo you want to proceed? (yes/no)",
+                            order_type, amount, token_symbol, format_price(price_in_sol), total_sol, trigger_info, expiry_info
                         ),
                     )
+                        .reply_markup(ui::create_confirmation_keyboard())
                         .await?;
                 }
                 Err(e) => {
@@ -238,7 +403,7 @@ pub async fn receive_price_and_amount(
         } else {
             bot.send_message(
                 msg.chat.id,
-                "Please enter the price and amount in the format: <price> <amount>",
+                "Please enter the expiry as text, e.g. 24h or never:",
             )
             .await?;
         }
@@ -254,6 +419,39 @@ pub async fn receive_confirmation(
     state: State,
     dialogue: MyDialogue,
     services: Arc<ServiceContainer>,
+) -> Result<()> {
+    if let Some(text) = msg.text() {
+        let confirmation = text.to_lowercase();
+        let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+
+        // Reset dialogue state
+        dialogue.update(State::Start).await?;
+
+        execute_confirmation(
+            bot,
+            msg.chat.id,
+            telegram_id,
+            state,
+            confirmation == "yes" || confirmation == "y",
+            services,
+        )
+        .await?;
+    } else {
+        bot.send_message(msg.chat.id, "Please confirm with 'yes' or 'no' as text:")
+            .await?;
+    }
+
+    Ok(())
+}
+
+// Create or cancel the pending limit order, used by both text and button confirmations
+pub async fn execute_confirmation(
+    bot: Bot,
+    chat_id: ChatId,
+    telegram_id: i64,
+    state: State,
+    confirmed: bool,
+    services: Arc<ServiceContainer>,
 ) -> Result<()> {
     if let State::AwaitingLimitOrderConfirmation {
         order_type,
@@ -262,47 +460,237 @@ pub async fn receive_confirmation(
         price_in_sol,
         amount,
         total_sol,
+        trigger_metric,
+        trigger_value,
+        price_currency,
+        expiry,
     } = state
     {
-        if let Some(text) = msg.text() {
-            let confirmation_text = text.to_lowercase();
-            let chat_id = msg.chat.id;
-            let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+        // A double tap on "Confirm" must not create the order twice
+        let _in_flight = if confirmed {
+            match services.idempotency_guard().begin(format!(
+                "{}:limit:{}:{}:{}:{}",
+                telegram_id, order_type, token_address, price_in_sol, amount
+            )) {
+                Some(in_flight) => Some(in_flight),
+                None => {
+                    bot.send_message(chat_id, "⏳ This limit order is already being created.")
+                        .await?;
+                    return Ok(());
+                }
+            }
+        } else {
+            None
+        };
 
-            // Reset dialogue state
-            dialogue.update(State::Start).await?;
+        let db_pool = services.db_pool();
+        let solana_client = services.solana_client();
+        let price_service = services.price_service();
+        let token_repository = services.token_repository();
 
-            let db_pool = services.db_pool();
-            let solana_client = services.solana_client();
-            let price_service = services.price_service();
-            let token_repository = services.token_repository();
+        let interactor = Arc::new(LimitOrderInteractorImpl::new(
+            db_pool,
+            solana_client,
+            price_service,
+            token_repository,
+        ));
+        let view = Arc::new(TelegramLimitOrderView::new(bot, chat_id));
+        let presenter = LimitOrderPresenterImpl::new(interactor, view);
 
-            let interactor = Arc::new(LimitOrderInteractorImpl::new(
-                db_pool,
-                solana_client,
-                price_service,
-                token_repository,
-            ));
-            let view = Arc::new(TelegramLimitOrderView::new(bot, chat_id));
-            let presenter = LimitOrderPresenterImpl::new(interactor, view);
-
-            presenter
-                .handle_confirmation(
-                    &confirmation_text,
-                    &order_type,
-                    &token_address,
-                    &token_symbol,
-                    price_in_sol,
-                    amount,
-                    total_sol,
-                    telegram_id,
-                )
-                .await?;
-        } else {
-            bot.send_message(msg.chat.id, "Please confirm with 'yes' or 'no' as text:")
-                .await?;
+        let order = NewLimitOrder {
+            order_type,
+            token_address,
+            token_symbol,
+            price_in_sol,
+            amount,
+            total_sol,
+            trigger_metric,
+            trigger_value,
+            price_currency,
+        };
+
+        presenter
+            .handle_confirmation(confirmed, &order, expiry, telegram_id)
+            .await?;
+    }
+
+    Ok(())
+}
+
+// Start editing the price and volume of an order (via callback)
+pub async fn handle_edit_order(
+    bot: Bot,
+    chat_id: ChatId,
+    telegram_id: i64,
+    order_id: i32,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let interactor = LimitOrderInteractorImpl::new(
+        services.db_pool(),
+        services.solana_client(),
+        services.price_service(),
+        services.token_repository(),
+    );
+
+    let order = match interactor.get_editable_order(telegram_id, order_id).await {
+        Ok(order) => order,
+        Err(e) => {
+            bot.send_message(chat_id, e.to_string()).await?;
+            return Ok(());
         }
+    };
+
+    if services
+        .idempotency_guard()
+        .begin(LimitOrder::execution_key(order_id))
+        .is_none()
+    {
+        bot.send_message(
+            chat_id,
+            format!(
+                "⏳ Order #{} is being executed right now and can't be edited.",
+                order_id
+            ),
+        )
+        .await?;
+        return Ok(());
     }
 
+    dialogue
+        .update(State::AwaitingLimitOrderEdit { order_id })
+        .await?;
+
+    let order_type = OrderType::from_str(&order.order_type)?;
+    let price_hint = if order.trigger_value.is_some() {
+        "The target of this order is set in USD, only the volume can be changed, e.g. '- 10'."
+    } else {
+        "Use '-' to keep a value, e.g. '- 10' changes only the volume."
+    };
+    let percentage_hint = if order_type.is_sell() {
+        " For sell orders the volume can also be a percentage of your balance, e.g. '0.5 50%'."
+    } else {
+        ""
+    };
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "✏️ Editing {} order #{}: {:.6} SOL ({:.6} {}) at {} SOL\n\n\
+             Enter the new price in SOL and volume in SOL, e.g. '0.5 10'. {}{}\n\n\
+             Use /cancel to keep the order as it is.",
+            order_type.display_name(),
+            order.id,
+            order.total_sol,
+            order.amount,
+            order.token_symbol,
+            format_price(order.price_in_sol),
+            price_hint,
+            percentage_hint
+        ),
+    )
+    .await?;
+
     Ok(())
 }
+
+// Handler for the new price and volume of an edited order
+pub async fn receive_order_edit(
+    bot: Bot,
+    msg: Message,
+    state: State,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let State::AwaitingLimitOrderEdit { order_id } = state else {
+        return Ok(());
+    };
+    let chat_id = msg.chat.id;
+
+    let Some(text) = msg.text() else {
+        bot.send_message(
+            chat_id,
+            "Please enter the new price and volume in the format: <price> <volume>",
+        )
+        .await?;
+        return Ok(());
+    };
+    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+
+    let interactor = LimitOrderInteractorImpl::new(
+        services.db_pool(),
+        services.solana_client(),
+        services.price_service(),
+        services.token_repository(),
+    );
+
+    // The order may have been filled or cancelled since the edit was started
+    let order = match interactor.get_editable_order(telegram_id, order_id).await {
+        Ok(order) => order,
+        Err(e) => {
+            dialogue.update(State::Start).await?;
+            bot.send_message(chat_id, e.to_string()).await?;
+            return Ok(());
+        }
+    };
+
+    let (price_in_sol, amount, total_sol) = match interactor
+        .validate_order_edit(&order, text, telegram_id)
+        .await
+    {
+        Ok(values) => values,
+        Err(e) => {
+            bot.send_message(chat_id, format!("Invalid input: {}", e))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // The limit order service holds the same key while it executes the order
+    let Some(_in_flight) = services
+        .idempotency_guard()
+        .begin(LimitOrder::execution_key(order_id))
+    else {
+        bot.send_message(
+            chat_id,
+            format!(
+                "⏳ Order #{} is being executed right now, please try again in a moment.",
+                order_id
+            ),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    dialogue.update(State::Start).await?;
+
+    if !interactor
+        .update_limit_order(order_id, price_in_sol, amount, total_sol)
+        .await?
+    {
+        bot.send_message(
+            chat_id,
+            format!(
+                "Order #{} was executed or cancelled in the meantime and wasn't changed.",
+                order_id
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "✅ Order #{} updated: {:.6} SOL ({:.6} {}) at {} SOL",
+            order_id,
+            total_sol,
+            amount,
+            order.token_symbol,
+            format_price(price_in_sol)
+        ),
+    )
+    .await?;
+
+    LimitOrdersCommand::execute(bot, msg, telegram_id, None, services).await
+}
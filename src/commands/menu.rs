@@ -5,6 +5,7 @@ use teloxide::{prelude::*, types::ParseMode};
 
 use super::{CommandHandler, MyDialogue};
 use crate::di::ServiceContainer;
+use crate::i18n;
 use crate::interactor::balance_interactor::BalanceInteractorImpl;
 use crate::interactor::db;
 use crate::presenter::balance_presenter::{BalancePresenter, BalancePresenterImpl};
@@ -29,7 +30,7 @@ impl CommandHandler for MenuCommand {
         services: Arc<ServiceContainer>,
     ) -> Result<()> {
         let db_pool = services.db_pool();
-        let username = msg.from().and_then(|user| user.username.clone());
+        let username = msg.from.as_ref().and_then(|user| user.username.clone());
         let chat_id = msg.chat.id;
 
         info!("Menu command received from Telegram ID: {}", telegram_id);
@@ -40,7 +41,7 @@ impl CommandHandler for MenuCommand {
 
         // Register user if they don't exist
         if !user_exists {
-            db::create_user(&db_pool, telegram_id, username)
+            db::create_user(&db_pool, telegram_id, username, None)
                 .await
                 .map_err(|e| anyhow!("Failed to create user: {}", e))?;
 
@@ -59,7 +60,16 @@ impl CommandHandler for MenuCommand {
             solana_client,
             price_service,
         ));
-        let view = Arc::new(TelegramBalanceView::new(bot, chat_id));
+        let language_code = msg
+            .from
+            .as_ref()
+            .and_then(|user| user.language_code.clone());
+        let locale = i18n::user_locale(&db_pool, telegram_id, language_code.as_deref()).await;
+        let view = Arc::new(
+            TelegramBalanceView::new(bot, chat_id)
+                .with_cluster(services.cluster())
+                .with_locale(locale),
+        );
         let presenter = BalancePresenterImpl::new(interactor, view);
 
         presenter.show_balances(telegram_id).await?;
@@ -6,17 +6,36 @@ use crate::di::ServiceContainer;
 use crate::entity::State;
 use teloxide::dispatching::dialogue::Dialogue;
 
+pub mod admin;
+pub mod alert;
 pub mod callback;
+pub mod cancel;
+pub mod cleanup;
+pub mod confirmation;
+pub mod dca;
+pub mod export;
+pub mod follow;
 pub mod help;
+pub mod history;
+pub mod info;
+pub mod language;
 pub mod limit_order;
 pub mod menu;
+pub mod portfolio;
+pub mod positions;
 pub mod price;
+pub mod referral;
 pub mod settings;
 pub mod start;
+pub mod status;
+pub mod swap;
+#[cfg(test)]
+pub(crate) mod testing;
 pub mod trade;
 pub mod ui;
 pub mod wallet;
 pub mod watchlist;
+pub mod whoami;
 pub mod withdraw;
 
 type MyDialogue = Dialogue<State, InMemStorage<State>>;
@@ -54,6 +73,82 @@ pub fn register_commands() -> Vec<(&'static str, &'static str)> {
             menu::MenuCommand::command_name(),
             menu::MenuCommand::description(),
         ),
+        (
+            wallet::BalanceCommand::command_name(),
+            wallet::BalanceCommand::description(),
+        ),
+        (
+            wallet::AddressCommand::command_name(),
+            wallet::AddressCommand::description(),
+        ),
+        (
+            wallet::DepositCommand::command_name(),
+            wallet::DepositCommand::description(),
+        ),
+        (
+            dca::DcaCommand::command_name(),
+            dca::DcaCommand::description(),
+        ),
+        (
+            price::PriceCommand::command_name(),
+            price::PriceCommand::description(),
+        ),
+        (
+            info::InfoCommand::command_name(),
+            info::InfoCommand::description(),
+        ),
+        (
+            portfolio::PortfolioCommand::command_name(),
+            portfolio::PortfolioCommand::description(),
+        ),
+        (
+            positions::PositionsCommand::command_name(),
+            positions::PositionsCommand::description(),
+        ),
+        (
+            history::HistoryCommand::command_name(),
+            history::HistoryCommand::description(),
+        ),
+        (
+            alert::AlertCommand::command_name(),
+            alert::AlertCommand::description(),
+        ),
+        (
+            follow::FollowCommand::command_name(),
+            follow::FollowCommand::description(),
+        ),
+        (
+            withdraw::WithdrawCommand::command_name(),
+            withdraw::WithdrawCommand::description(),
+        ),
+        (
+            swap::SwapCommand::command_name(),
+            swap::SwapCommand::description(),
+        ),
+        (
+            cleanup::CleanupCommand::command_name(),
+            cleanup::CleanupCommand::description(),
+        ),
+        (
+            export::ExportCommand::command_name(),
+            export::ExportCommand::description(),
+        ),
+        (
+            referral::ReferralCommand::command_name(),
+            referral::ReferralCommand::description(),
+        ),
+        (
+            whoami::WhoAmICommand::command_name(),
+            whoami::WhoAmICommand::description(),
+        ),
+        (
+            language::LanguageCommand::command_name(),
+            language::LanguageCommand::description(),
+        ),
+        (
+            cancel::CancelCommand::command_name(),
+            cancel::CancelCommand::description(),
+        ),
         (
             help::HelpCommand::command_name(),
             help::HelpCommand::description(),
@@ -65,12 +160,115 @@ pub fn register_commands() -> Vec<(&'static str, &'static str)> {
 #[derive(teloxide::utils::command::BotCommands, Clone, Debug)]
 #[command(rename_rule = "lowercase", description = "Available commands:")]
 pub enum BotCommands {
+    // Deep links pass a payload, e.g. "/start ref_12345"
     #[command(description = "start the bot and show the main menu")]
-    Start,
+    Start(String),
     #[command(rename = "create_wallet", description = "create a new Solana wallet")]
     CreateWallet,
     #[command(description = "show the main menu")]
     Menu,
+    #[command(description = "show your SOL and token balances")]
+    Balance,
+    #[command(description = "show your wallet address and QR code")]
+    Address,
+    // An optional amount goes into the Solana Pay QR code, e.g. "/deposit 1.5"
+    #[command(description = "show your deposit address and QR code, e.g. /deposit 1.5")]
+    Deposit(String),
+    #[command(description = "manage recurring (DCA) buys")]
+    Dca,
+    // Without a token the bot asks for one, like the Price button
+    #[command(description = "show the price of a token, e.g. /price SOL")]
+    Price(String),
+    #[command(description = "show supply, holders and authorities of a token, e.g. /info <mint>")]
+    Info(String),
+    #[command(description = "show profit and loss of your positions")]
+    Portfolio,
+    #[command(description = "show your token holdings together with their pending limit orders")]
+    Positions,
+    #[command(description = "manage price alerts")]
+    Alerts,
+    #[command(description = "get notified when a wallet buys or sells tokens")]
+    Follow,
+    #[command(description = "show your transaction history")]
+    History,
+    #[command(description = "send SOL or tokens to another address")]
+    Send,
+    #[command(description = "swap tokens, e.g. /swap 0.5 SOL USDC")]
+    Swap(String),
+    #[command(description = "close empty token accounts and reclaim their rent")]
+    Cleanup,
+    #[command(description = "back up your wallet's recovery phrase and private key")]
+    Export,
+    #[command(description = "invite friends with your referral link")]
+    Referral,
+    #[command(description = "show your account details to share with support")]
+    WhoAmI,
+    #[command(description = "change the bot language")]
+    Language,
+    // "/settings export" or "/settings import" copy preferences between accounts
+    #[command(description = "configure trading settings, export or import them")]
+    Settings(String),
+    #[command(description = "cancel the current operation")]
+    Cancel,
     #[command(description = "display this help message")]
     Help,
+    // Not listed anywhere, non-admins get the unknown command reply
+    #[command(hide)]
+    Admin(String),
+    // Admin only as well
+    #[command(hide)]
+    Status,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teloxide::utils::command::BotCommands as _;
+
+    const BOT_NAME: &str = "trade_bot";
+
+    fn parse(text: &str) -> Option<BotCommands> {
+        BotCommands::parse(text, BOT_NAME).ok()
+    }
+
+    #[test]
+    fn wallet_commands_are_recognized() {
+        assert!(matches!(parse("/balance"), Some(BotCommands::Balance)));
+        assert!(matches!(parse("/address"), Some(BotCommands::Address)));
+        assert!(matches!(parse("/send"), Some(BotCommands::Send)));
+    }
+
+    #[test]
+    fn command_addressed_to_the_bot_is_recognized() {
+        assert!(matches!(
+            parse("/balance@trade_bot"),
+            Some(BotCommands::Balance)
+        ));
+        // In a group the command can be meant for another bot
+        assert!(parse("/balance@other_bot").is_none());
+    }
+
+    #[test]
+    fn price_takes_an_optional_token() {
+        assert!(matches!(parse("/price BONK"), Some(BotCommands::Price(args)) if args == "BONK"));
+        assert!(matches!(parse("/price"), Some(BotCommands::Price(args)) if args.is_empty()));
+    }
+
+    #[test]
+    fn unknown_command_is_not_recognized() {
+        assert!(parse("/balances").is_none());
+        assert!(parse("balance").is_none());
+    }
+
+    #[test]
+    fn wallet_commands_are_listed_in_the_menu() {
+        let names: Vec<&str> = register_commands()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+
+        for command in ["balance", "address", "price", "send"] {
+            assert!(names.contains(&command), "{} is not listed", command);
+        }
+    }
 }
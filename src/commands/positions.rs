@@ -0,0 +1,46 @@
+use anyhow::Result;
+use log::info;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::interactor::positions_interactor::PositionsInteractorImpl;
+use crate::presenter::positions_presenter::{PositionsPresenter, PositionsPresenterImpl};
+use crate::view::positions_view::TelegramPositionsView;
+
+pub struct PositionsCommand;
+
+impl CommandHandler for PositionsCommand {
+    fn command_name() -> &'static str {
+        "positions"
+    }
+
+    fn description() -> &'static str {
+        "show your token holdings together with their pending limit orders"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        let chat_id = msg.chat.id;
+
+        info!("Positions command initiated by user: {}", telegram_id);
+
+        let interactor = Arc::new(PositionsInteractorImpl::new(
+            services.db_pool(),
+            services.solana_client(),
+            services.price_service(),
+        ));
+        let view = Arc::new(TelegramPositionsView::new(bot, chat_id));
+        let presenter = PositionsPresenterImpl::new(interactor, view);
+
+        presenter.show_positions(telegram_id).await?;
+
+        Ok(())
+    }
+}
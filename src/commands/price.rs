@@ -1,11 +1,17 @@
 use super::{CommandHandler, MyDialogue};
 use crate::di::ServiceContainer;
+use crate::entity::PriceCurrency;
+use crate::i18n;
+use crate::interactor::db;
 use crate::interactor::price_interactor::PriceInteractorImpl;
 use crate::presenter::price_presenter::{PricePresenter, PricePresenterImpl};
+use crate::utils::{format_price, format_usd_price};
 use crate::view::price_view::TelegramPriceView;
 use crate::State;
 use anyhow::Result;
 use log::info;
+use sqlx::PgPool;
+use std::str::FromStr;
 use std::sync::Arc;
 use teloxide::prelude::*;
 use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
@@ -18,42 +24,126 @@ impl CommandHandler for PriceCommand {
     }
 
     fn description() -> &'static str {
-        "get price for a token"
+        "show the price of a token, e.g. /price SOL or /price BONK usdc"
     }
 
     async fn execute(
         bot: Bot,
         msg: Message,
         telegram_id: i64,
-        _dialogue: Option<MyDialogue>,
+        dialogue: Option<MyDialogue>,
         services: Arc<ServiceContainer>,
     ) -> Result<()> {
-        let command_parts: Vec<&str> = msg.text().unwrap_or("").split_whitespace().collect();
         let chat_id = msg.chat.id;
 
-        if command_parts.len() >= 2 {
-            let token = command_parts[1];
+        let (token, quote) = match parse_price_args(msg.text().unwrap_or("")) {
+            PriceArgs::Token { token, quote } => (token, quote),
+            PriceArgs::InvalidQuote { token, quote } => {
+                bot.send_message(
+                    chat_id,
+                    format!(
+                        "Unknown quote \"{}\". Use SOL or USDC, e.g. /price {} USDC",
+                        quote, token
+                    ),
+                )
+                .await?;
+                return Ok(());
+            }
+            // Plain "/price" asks for the token like the Price button
+            PriceArgs::Missing => {
+                if let Some(dialogue) = dialogue {
+                    return ask_price_token(&bot, chat_id, &dialogue).await;
+                }
 
-            info!("Price command received for token: {}", token);
+                bot.send_message(
+                    chat_id,
+                    "Use the command in this format: /price <token_symbol> [SOL|USDC]\n\nExample: /price SOL",
+                )
+                .await?;
+                return Ok(());
+            }
+        };
 
-            let price_service = services.price_service();
-            let interactor = Arc::new(PriceInteractorImpl::new(price_service));
-            let view = Arc::new(TelegramPriceView::new(bot, chat_id));
-            let presenter = PricePresenterImpl::new(interactor, view);
+        info!("Price command received for token: {}", token);
 
-            presenter.show_token_price(token).await
-        } else {
-            bot.send_message(
-                chat_id,
-                "Use the command in this format: /price <token_symbol>\n\nExample: /price SOL",
-            )
-            .await?;
+        // "/price <token> <quote>" overrides the quote from the settings
+        let quote = match quote {
+            Some(quote) => quote,
+            None => default_quote(&services.db_pool(), telegram_id).await,
+        };
 
-            Ok(())
-        }
+        let price_service = services.price_service();
+        let interactor = Arc::new(PriceInteractorImpl::new(price_service));
+        let language_code = msg
+            .from
+            .as_ref()
+            .and_then(|user| user.language_code.clone());
+        let locale =
+            i18n::user_locale(&services.db_pool(), telegram_id, language_code.as_deref()).await;
+        let view = Arc::new(TelegramPriceView::new(bot, chat_id).with_locale(locale));
+        let presenter = PricePresenterImpl::new(interactor, view);
+
+        presenter.show_token_price(token, quote).await
+    }
+}
+
+// Arguments of "/price <token> [SOL|USDC]"
+#[derive(Debug, PartialEq)]
+enum PriceArgs<'a> {
+    // No token, the bot asks for one
+    Missing,
+    // `quote` is None when the user's default quote applies
+    Token {
+        token: &'a str,
+        quote: Option<PriceCurrency>,
+    },
+    // The quote is neither SOL nor USDC
+    InvalidQuote {
+        token: &'a str,
+        quote: &'a str,
+    },
+}
+
+fn parse_price_args(text: &str) -> PriceArgs<'_> {
+    let mut parts = text.split_whitespace().skip(1);
+
+    let Some(token) = parts.next() else {
+        return PriceArgs::Missing;
+    };
+
+    match parts.next() {
+        None => PriceArgs::Token { token, quote: None },
+        Some(quote) => match PriceCurrency::from_str(quote) {
+            Ok(currency) => PriceArgs::Token {
+                token,
+                quote: Some(currency),
+            },
+            Err(_) => PriceArgs::InvalidQuote { token, quote },
+        },
     }
 }
 
+/// Quote the user shows prices in first, SOL when the user is unknown
+async fn default_quote(db_pool: &PgPool, telegram_id: i64) -> PriceCurrency {
+    db::get_user_by_telegram_id(db_pool, telegram_id)
+        .await
+        .map(|user| user.get_default_quote())
+        .unwrap_or_default()
+}
+
+/// Ask for the token whose price to show, the reply is handled by `receive_price_token_address`
+pub async fn ask_price_token(bot: &Bot, chat_id: ChatId, dialogue: &MyDialogue) -> Result<()> {
+    dialogue.update(State::AwaitingPriceTokenAddress).await?;
+
+    bot.send_message(
+        chat_id,
+        "Please enter the token contract address you want to check the price for:",
+    )
+    .await?;
+
+    Ok(())
+}
+
 pub async fn receive_price_token_address(
     bot: Bot,
     msg: Message,
@@ -80,10 +170,22 @@ pub async fn receive_price_token_address(
 
                 match price_service.get_token_price(address_text).await {
                     Ok(price_info) => {
-                        // Format price message
+                        // Format price message, the user's quote first
+                        let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                        let (primary, secondary) =
+                            match default_quote(&services.db_pool(), telegram_id).await {
+                                PriceCurrency::Sol => (
+                                    format!("{} SOL", format_price(price_info.price_in_sol)),
+                                    format!("{} USDC", format_usd_price(price_info.price_in_usdc)),
+                                ),
+                                PriceCurrency::Usdc => (
+                                    format!("{} USDC", format_usd_price(price_info.price_in_usdc)),
+                                    format!("{} SOL", format_price(price_info.price_in_sol)),
+                                ),
+                            };
                         let price_text = format!(
-                            "Current price for {}:\n• {:.6} SOL\n• ${:.6} USDC",
-                            token.symbol, price_info.price_in_sol, price_info.price_in_usdc
+                            "Current price for {}:\n• {}\n• {}",
+                            token.symbol, primary, secondary
                         );
 
                         // Create a button to return to main menu
@@ -123,3 +225,54 @@ pub async fn receive_price_token_address(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_without_a_quote_uses_the_default() {
+        assert_eq!(
+            parse_price_args("/price BONK"),
+            PriceArgs::Token {
+                token: "BONK",
+                quote: None
+            }
+        );
+    }
+
+    #[test]
+    fn explicit_quote_is_used_in_any_case() {
+        assert_eq!(
+            parse_price_args("/price BONK usdc"),
+            PriceArgs::Token {
+                token: "BONK",
+                quote: Some(PriceCurrency::Usdc)
+            }
+        );
+        assert_eq!(
+            parse_price_args("/price  BONK  SOL"),
+            PriceArgs::Token {
+                token: "BONK",
+                quote: Some(PriceCurrency::Sol)
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_quote_is_reported() {
+        assert_eq!(
+            parse_price_args("/price BONK EUR"),
+            PriceArgs::InvalidQuote {
+                token: "BONK",
+                quote: "EUR"
+            }
+        );
+    }
+
+    #[test]
+    fn price_without_a_token_asks_for_one() {
+        assert_eq!(parse_price_args("/price"), PriceArgs::Missing);
+        assert_eq!(parse_price_args("/price   "), PriceArgs::Missing);
+    }
+}
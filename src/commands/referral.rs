@@ -0,0 +1,41 @@
+use anyhow::Result;
+use log::info;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::interactor::referral_interactor::ReferralInteractorImpl;
+use crate::presenter::referral_presenter::{ReferralPresenter, ReferralPresenterImpl};
+use crate::view::referral_view::TelegramReferralView;
+
+pub struct ReferralCommand;
+
+impl CommandHandler for ReferralCommand {
+    fn command_name() -> &'static str {
+        "referral"
+    }
+
+    fn description() -> &'static str {
+        "invite friends with your referral link"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        info!("Referral command initiated by user: {}", telegram_id);
+
+        // The link points to this bot, whatever name it is deployed under
+        let me = bot.get_me().await?;
+
+        let interactor = Arc::new(ReferralInteractorImpl::new(services.db_pool()));
+        let view = Arc::new(TelegramReferralView::new(bot, msg.chat.id));
+        let presenter = ReferralPresenterImpl::new(interactor, view);
+
+        presenter.show_referrals(telegram_id, me.username()).await
+    }
+}
@@ -40,7 +40,23 @@ impl CommandHandler for SettingsCommand {
         let view = Arc::new(TelegramSettingsView::new(bot, chat_id));
         let presenter = SettingsPresenterImpl::new(interactor, view);
 
-        presenter.show_settings_menu(telegram_id).await?;
+        // "/settings export" and "/settings import" skip the menu
+        let action = msg
+            .text()
+            .unwrap_or("")
+            .split_whitespace()
+            .nth(1)
+            .map(|action| action.to_lowercase());
+
+        match action.as_deref() {
+            Some("export") => presenter.export_settings(telegram_id).await?,
+            Some("import") => {
+                let dialogue = dialogue.ok_or_else(|| anyhow!("Dialogue context not provided"))?;
+                dialogue.update(State::AwaitingSettingsImport).await?;
+                presenter.show_import_prompt().await?;
+            }
+            _ => presenter.show_settings_menu(telegram_id).await?,
+        }
 
         Ok(())
     }
@@ -54,7 +70,7 @@ pub async fn handle_slippage_input(
     services: Arc<ServiceContainer>,
 ) -> Result<()> {
     let chat_id = msg.chat.id;
-    let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
 
     // Reset dialogue state
     dialogue.update(State::Start).await?;
@@ -76,3 +92,132 @@ pub async fn handle_slippage_input(
 
     Ok(())
 }
+
+// State for max accounts setting
+pub async fn handle_max_accounts_input(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = msg.chat.id;
+    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+
+    // Reset dialogue state
+    dialogue.update(State::Start).await?;
+
+    if let Some(max_accounts_text) = msg.text() {
+        let db_pool = services.db_pool();
+        let interactor = Arc::new(SettingsInteractorImpl::new(db_pool));
+        let view = Arc::new(TelegramSettingsView::new(bot.clone(), chat_id));
+        let presenter = SettingsPresenterImpl::new(interactor, view);
+
+        presenter
+            .update_max_accounts(telegram_id, max_accounts_text)
+            .await?;
+    } else {
+        bot.send_message(
+            chat_id,
+            "Please enter the max accounts as a number, e.g. 32",
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+// State for transaction validity setting
+pub async fn handle_validity_slots_input(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = msg.chat.id;
+    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+
+    // Reset dialogue state
+    dialogue.update(State::Start).await?;
+
+    if let Some(validity_slots_text) = msg.text() {
+        let db_pool = services.db_pool();
+        let interactor = Arc::new(SettingsInteractorImpl::new(db_pool));
+        let view = Arc::new(TelegramSettingsView::new(bot.clone(), chat_id));
+        let presenter = SettingsPresenterImpl::new(interactor, view);
+
+        presenter
+            .update_validity_slots(telegram_id, validity_slots_text)
+            .await?;
+    } else {
+        bot.send_message(
+            chat_id,
+            "Please enter the transaction validity in slots, e.g. 60",
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+// State for quick-buy amounts setting
+pub async fn handle_quick_buy_amounts_input(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = msg.chat.id;
+    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+
+    // Reset dialogue state
+    dialogue.update(State::Start).await?;
+
+    if let Some(amounts_text) = msg.text() {
+        let db_pool = services.db_pool();
+        let interactor = Arc::new(SettingsInteractorImpl::new(db_pool));
+        let view = Arc::new(TelegramSettingsView::new(bot.clone(), chat_id));
+        let presenter = SettingsPresenterImpl::new(interactor, view);
+
+        presenter
+            .update_quick_buy_amounts(telegram_id, amounts_text)
+            .await?;
+    } else {
+        bot.send_message(
+            chat_id,
+            "Please enter the amounts as text, e.g. 0.1 0.5 1 5",
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+// State for pasted settings to import
+pub async fn handle_settings_import_input(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = msg.chat.id;
+    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+
+    // Reset dialogue state
+    dialogue.update(State::Start).await?;
+
+    if let Some(settings_json) = msg.text() {
+        let db_pool = services.db_pool();
+        let interactor = Arc::new(SettingsInteractorImpl::new(db_pool));
+        let view = Arc::new(TelegramSettingsView::new(bot.clone(), chat_id));
+        let presenter = SettingsPresenterImpl::new(interactor, view);
+
+        presenter
+            .import_settings(telegram_id, settings_json)
+            .await?;
+    } else {
+        bot.send_message(chat_id, "Please paste the exported settings as text.")
+            .await?;
+    }
+
+    Ok(())
+}
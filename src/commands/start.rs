@@ -5,8 +5,12 @@ use teloxide::{prelude::*, types::ParseMode};
 
 use super::{CommandHandler, MyDialogue};
 use crate::di::ServiceContainer;
+use crate::i18n::{self, Locale};
 use crate::interactor::balance_interactor::BalanceInteractorImpl;
 use crate::interactor::db;
+use crate::interactor::referral_interactor::{
+    parse_referral_payload, ReferralInteractor, ReferralInteractorImpl,
+};
 use crate::interactor::wallet_interactor::WalletInteractorImpl;
 use crate::presenter::balance_presenter::{BalancePresenter, BalancePresenterImpl};
 use crate::presenter::wallet_presenter::{WalletPresenter, WalletPresenterImpl};
@@ -32,7 +36,11 @@ impl CommandHandler for StartCommand {
         services: Arc<ServiceContainer>,
     ) -> Result<()> {
         let db_pool = services.db_pool();
-        let username = msg.from().and_then(|user| user.username.clone());
+        let username = msg.from.as_ref().and_then(|user| user.username.clone());
+        let language_code = msg
+            .from
+            .as_ref()
+            .and_then(|user| user.language_code.clone());
         let chat_id = msg.chat.id;
 
         info!("Start command received from Telegram ID: {}", telegram_id);
@@ -41,28 +49,41 @@ impl CommandHandler for StartCommand {
             .await
             .map_err(|e| anyhow!("Database error: {}", e))?;
 
-        // Register user if they don't exist
+        // Register user if they don't exist, a referral link only counts for new users
         if !user_exists {
-            db::create_user(&db_pool, telegram_id, username)
+            let referred_by = match msg.text().and_then(parse_referral_payload) {
+                Some(referrer) => {
+                    ReferralInteractorImpl::new(db_pool.clone())
+                        .validate_referrer(telegram_id, referrer)
+                        .await?
+                }
+                None => None,
+            };
+
+            db::create_user(&db_pool, telegram_id, username, referred_by)
                 .await
                 .map_err(|e| anyhow!("Failed to create user: {}", e))?;
 
-            bot.send_message(
-                chat_id,
-                "<b>Hello!</b> I'm a Solana trading bot. You have been successfully registered.",
-            )
-            .parse_mode(ParseMode::Html)
-            .await?;
+            if let Some(referrer) = referred_by {
+                info!("User {} was referred by {}", telegram_id, referrer);
+            }
+        }
+
+        // Check if user has a wallet and create one if not
+        let user = db::get_user_by_telegram_id(&db_pool, telegram_id).await?;
+        let locale = Locale::resolve(user.get_locale(), language_code.as_deref());
+
+        if !user_exists {
+            bot.send_message(chat_id, i18n::t("start.registered", locale))
+                .parse_mode(ParseMode::Html)
+                .await?;
         } else {
             // Welcome returning user
-            bot.send_message(chat_id, "<b>Welcome back to Solana Trading Bot!</b>")
+            bot.send_message(chat_id, i18n::t("start.welcome_back", locale))
                 .parse_mode(ParseMode::Html)
                 .await?;
         }
 
-        // Check if user has a wallet and create one if not
-        let user = db::get_user_by_telegram_id(&db_pool, telegram_id).await?;
-
         if user.solana_address.is_none() {
             info!(
                 "User {} does not have a wallet. Creating one automatically.",
@@ -71,16 +92,15 @@ impl CommandHandler for StartCommand {
 
             // Create wallet interactor and presenter
             let wallet_interactor = Arc::new(WalletInteractorImpl::new(db_pool.clone()));
-            let wallet_view = Arc::new(TelegramWalletView::new(bot.clone(), chat_id));
+            let wallet_view =
+                Arc::new(TelegramWalletView::new(bot.clone(), chat_id).with_locale(locale));
             let wallet_presenter = WalletPresenterImpl::new(wallet_interactor, wallet_view);
 
             // Create wallet
             match wallet_presenter.create_wallet(telegram_id).await {
                 Ok(()) => {
-                    bot.send_message(
-                        chat_id,
-                        "I've automatically created a Solana wallet for you! ✅\nYou can now send and receive tokens.",
-                    ).await?;
+                    bot.send_message(chat_id, i18n::t("start.wallet_auto_created", locale))
+                        .await?;
                 }
                 Err(e) => {
                     info!("Failed to auto-create wallet: {}", e);
@@ -97,7 +117,11 @@ impl CommandHandler for StartCommand {
             solana_client,
             price_service,
         ));
-        let view = Arc::new(TelegramBalanceView::new(bot, chat_id));
+        let view = Arc::new(
+            TelegramBalanceView::new(bot, chat_id)
+                .with_cluster(services.cluster())
+                .with_locale(locale),
+        );
         let presenter = BalancePresenterImpl::new(interactor, view);
 
         presenter.show_balances(telegram_id).await?;
@@ -0,0 +1,46 @@
+use anyhow::Result;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::interactor::status_interactor::StatusInteractorImpl;
+use crate::presenter::status_presenter::{StatusPresenter, StatusPresenterImpl};
+use crate::view::status_view::TelegramStatusView;
+
+pub struct StatusCommand;
+
+impl CommandHandler for StatusCommand {
+    fn command_name() -> &'static str {
+        "status"
+    }
+
+    fn description() -> &'static str {
+        "RPC and database health"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        let presenter = create_presenter(&bot, msg.chat.id, &services);
+        presenter.show_status(telegram_id).await
+    }
+}
+
+fn create_presenter(
+    bot: &Bot,
+    chat_id: ChatId,
+    services: &Arc<ServiceContainer>,
+) -> StatusPresenterImpl<StatusInteractorImpl, TelegramStatusView> {
+    let interactor = Arc::new(StatusInteractorImpl::new(
+        services.db_pool(),
+        services.solana_client(),
+    ));
+    let view = Arc::new(TelegramStatusView::new(bot.clone(), chat_id));
+
+    StatusPresenterImpl::new(interactor, view)
+}
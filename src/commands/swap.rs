@@ -0,0 +1,135 @@
+use anyhow::Result;
+use log::info;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{ui, CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::entity::State;
+use crate::interactor::swap_interactor::SwapInteractorImpl;
+use crate::presenter::swap_presenter::{SwapPresenter, SwapPresenterImpl};
+use crate::solana::jupiter::quote_service::JupiterQuoteService;
+use crate::solana::jupiter::token_repository::JupiterTokenRepository;
+use crate::view::swap_view::TelegramSwapView;
+
+pub struct SwapCommand;
+
+impl CommandHandler for SwapCommand {
+    fn command_name() -> &'static str {
+        "swap"
+    }
+
+    fn description() -> &'static str {
+        "swap tokens, e.g. /swap 0.5 SOL USDC"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        if !ui::ensure_jupiter_available(&bot, msg.chat.id, services.cluster()).await? {
+            return Ok(());
+        }
+
+        let command_parts: Vec<&str> = msg.text().unwrap_or("").split_whitespace().collect();
+        let presenter = create_presenter(&bot, msg.chat.id, &services);
+
+        if command_parts.len() != 4 {
+            return presenter.show_usage().await;
+        }
+
+        info!("Swap command initiated by user: {}", telegram_id);
+
+        let quote = presenter
+            .show_swap_quote(
+                telegram_id,
+                command_parts[1],
+                command_parts[2],
+                command_parts[3],
+            )
+            .await?;
+
+        if let (Some(quote), Some(dialogue)) = (quote, dialogue) {
+            dialogue
+                .update(State::AwaitingSwapConfirmation {
+                    amount: quote.amount_in,
+                    source_token: quote.source.mint_address,
+                    target_token: quote.target.mint_address,
+                })
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn create_presenter(
+    bot: &Bot,
+    chat_id: ChatId,
+    services: &Arc<ServiceContainer>,
+) -> SwapPresenterImpl<
+    SwapInteractorImpl<JupiterTokenRepository, JupiterQuoteService<JupiterTokenRepository>>,
+    TelegramSwapView,
+> {
+    let interactor = Arc::new(SwapInteractorImpl::new(
+        services.db_pool(),
+        services.solana_client(),
+        services.token_repository(),
+        services.quote_service(),
+        services.swap_service(),
+    ));
+    let view = Arc::new(TelegramSwapView::new(bot.clone(), chat_id));
+
+    SwapPresenterImpl::new(interactor, view)
+}
+
+// Handler for the "Confirm Swap" button
+pub async fn confirm_swap(
+    bot: Bot,
+    msg: Message,
+    telegram_id: i64,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let presenter = create_presenter(&bot, msg.chat.id, &services);
+
+    if let Some(State::AwaitingSwapConfirmation {
+        amount,
+        source_token,
+        target_token,
+    }) = dialogue.get().await?
+    {
+        // Reset the state first so a second tap doesn't submit the swap again
+        dialogue.update(State::Start).await?;
+
+        presenter
+            .execute_swap(telegram_id, amount, &source_token, &target_token)
+            .await?;
+    } else {
+        bot.send_message(
+            msg.chat.id,
+            "This swap is no longer pending. Use /swap to start a new one.",
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Handler for the "Cancel" button
+pub async fn cancel_swap(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    dialogue.update(State::Start).await?;
+
+    let presenter = create_presenter(&bot, msg.chat.id, &services);
+    presenter.cancel_swap().await?;
+
+    Ok(())
+}
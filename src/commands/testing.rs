@@ -0,0 +1,129 @@
+//! Helpers for command handler tests: a local Telegram API and offline services
+
+use std::future::Future;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use tokio::sync::mpsc;
+
+use crate::di::ServiceContainer;
+use crate::solana::client::ResilientRpcClient;
+use crate::testing::{fake_http, HttpResponse};
+use sqlx::postgres::PgPoolOptions;
+
+/// Bot API method called by a handler and its JSON body
+#[derive(Debug)]
+pub struct TelegramCall {
+    pub method: String,
+    pub body: String,
+}
+
+impl TelegramCall {
+    pub fn is(&self, method: &str) -> bool {
+        self.method.eq_ignore_ascii_case(method)
+    }
+}
+
+/// Successful answer to `method`: sent and edited messages are answered with a message, every
+/// other method with `true`
+pub fn response_body(method: &str) -> String {
+    let method = method.to_lowercase();
+    if method.starts_with("send") || method.starts_with("edit") {
+        serde_json::json!({
+            "ok": true,
+            "result": {
+                "message_id": 1,
+                "date": 1,
+                "chat": { "id": 1, "type": "private", "first_name": "Alice" },
+                "text": "ok",
+            },
+        })
+        .to_string()
+    } else {
+        r#"{"ok":true,"result":true}"#.to_string()
+    }
+}
+
+/// Bot talking to a local Telegram API, every call is sent to the returned receiver
+pub async fn fake_telegram() -> (Bot, mpsc::UnboundedReceiver<TelegramCall>) {
+    fake_telegram_with(response_body).await
+}
+
+/// Like [`fake_telegram`], with `respond` giving the JSON answer to each method
+pub async fn fake_telegram_with(
+    respond: fn(&str) -> String,
+) -> (Bot, mpsc::UnboundedReceiver<TelegramCall>) {
+    let (calls_tx, calls_rx) = mpsc::unbounded_channel();
+
+    let url = fake_http(move |request| {
+        let method = request
+            .path
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let body = respond(&method);
+        let _ = calls_tx.send(TelegramCall {
+            method,
+            body: request.body,
+        });
+        async move { HttpResponse::ok(body) }
+    })
+    .await;
+
+    let bot = Bot::new("123:abc").set_api_url(url.parse().unwrap());
+    (bot, calls_rx)
+}
+
+/// Services that never connect, for handlers that don't reach the database or the network
+pub fn services() -> Arc<ServiceContainer> {
+    let pool = PgPoolOptions::new()
+        .connect_lazy("postgres://localhost/unused")
+        .unwrap();
+    let rpc = ResilientRpcClient::new(&["http://127.0.0.1:8899".to_string()]).unwrap();
+    Arc::new(ServiceContainer::new(Arc::new(pool), Arc::new(rpc)))
+}
+
+/// Text message from the user in their private chat
+pub fn text_message(chat_id: i64, text: &str) -> Message {
+    serde_json::from_value(serde_json::json!({
+        "message_id": 1,
+        "date": 1,
+        "chat": { "id": chat_id, "type": "private", "first_name": "Alice" },
+        "from": { "id": chat_id, "is_bot": false, "first_name": "Alice" },
+        "text": text,
+    }))
+    .unwrap()
+}
+
+/// Text message from the user in a group chat the bot was added to
+pub fn group_message(chat_id: i64, user_id: i64, text: &str) -> Message {
+    serde_json::from_value(serde_json::json!({
+        "message_id": 1,
+        "date": 1,
+        "chat": { "id": chat_id, "type": "group", "title": "Traders" },
+        "from": { "id": user_id, "is_bot": false, "first_name": "Alice" },
+        "text": text,
+    }))
+    .unwrap()
+}
+
+/// Run a test on a thread with a larger stack, handler futures outgrow the default one in
+/// debug builds
+pub fn run_with_large_stack<F, Fut>(test: F)
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = ()>,
+{
+    std::thread::Builder::new()
+        .stack_size(32 * 1024 * 1024)
+        .spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(test())
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
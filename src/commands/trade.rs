@@ -1,13 +1,21 @@
-use super::{CommandHandler, MyDialogue};
+use super::{ui, CommandHandler, MyDialogue};
 use crate::di::ServiceContainer;
-use crate::entity::{BotError, OrderType, State};
-use crate::interactor::trade_interactor::{TradeInteractor, TradeInteractorImpl};
+use crate::entity::{BotError, Cluster, OrderType, State, DEFAULT_QUICK_BUY_AMOUNTS};
+use crate::i18n::{self, Locale};
+use crate::interactor::trade_interactor::{
+    ImpactPreview, TradeInteractor, TradeInteractorImpl, TradeQuote, TradeRequest,
+    IMPACT_PREVIEW_FRACTIONS, IMPACT_PREVIEW_MIN_PCT,
+};
 use crate::presenter::trade_presenter::{TradePresenter, TradePresenterImpl};
+use crate::solana::client::ResilientRpcClient;
+use crate::utils::{
+    format_amount, format_locale_price, format_price, format_tx_url, format_usd, parse_decimal,
+};
+use crate::view::trade_card::{send_trade_card, TradeCard};
 use crate::view::trade_view::TelegramTradeView;
 use crate::{db, solana, TokenBalance};
 use anyhow::Result;
 use log::info;
-use solana_client::nonblocking::rpc_client::RpcClient;
 use sqlx::PgPool;
 use std::sync::Arc;
 use teloxide::prelude::*;
@@ -36,6 +44,10 @@ impl CommandHandler for BuyCommand {
 
         info!("Buy command initiated by user: {}", telegram_id);
 
+        if !ui::ensure_jupiter_available(&bot, msg.chat.id, services.cluster()).await? {
+            return Ok(());
+        }
+
         // Update dialogue state to token selection rather than directly asking for address
         dialogue.update(State::AwaitingBuyTokenSelection).await?;
 
@@ -92,7 +104,11 @@ impl CommandHandler for BuyCommand {
             )]);
         }
 
-        // Step 4: Add button for manual address entry
+        // Step 4: Add buttons for token search and manual address entry
+        keyboard_buttons.push(vec![InlineKeyboardButton::callback(
+            "🔍 Search Token",
+            "buy_search",
+        )]);
         keyboard_buttons.push(vec![InlineKeyboardButton::callback(
             "Enter Token Address Manually",
             "buy_manual_address",
@@ -137,6 +153,10 @@ impl CommandHandler for SellCommand {
 
         info!("Sell command initiated by user: {}", telegram_id);
 
+        if !ui::ensure_jupiter_available(&bot, msg.chat.id, services.cluster()).await? {
+            return Ok(());
+        }
+
         // Update dialogue state to token selection rather than directly asking for address
         dialogue.update(State::AwaitingSellTokenSelection).await?;
 
@@ -154,19 +174,8 @@ impl CommandHandler for SellCommand {
                         "You don't have any tokens to sell. Please deposit some tokens to your wallet first."
                     ).await?;
                 } else {
-                    // Create keyboard buttons for each token
-                    let mut keyboard_buttons = Vec::new();
-
-                    for token in tokens {
-                        if token.symbol != "SOL" {
-                            // Exclude SOL from the sell options
-                            let token_text = format!("{}: {:.6}", token.symbol, token.amount);
-                            keyboard_buttons.push(vec![InlineKeyboardButton::callback(
-                                token_text,
-                                format!("sell_token_{}", token.mint_address),
-                            )]);
-                        }
-                    }
+                    // Create keyboard buttons for each token, wrapped SOL is offered for unwrapping
+                    let mut keyboard_buttons = ui::sell_token_buttons(tokens);
 
                     // Add cancel button
                     keyboard_buttons.push(vec![InlineKeyboardButton::callback("← Cancel", "menu")]);
@@ -200,7 +209,7 @@ impl CommandHandler for SellCommand {
 pub(crate) async fn get_user_tokens(
     telegram_id: i64,
     db_pool: Arc<PgPool>,
-    solana_client: Arc<RpcClient>,
+    solana_client: Arc<ResilientRpcClient>,
 ) -> Result<Vec<TokenBalance>> {
     // Get user's wallet address
     let user = db::get_user_by_telegram_id(&db_pool, telegram_id).await?;
@@ -221,6 +230,211 @@ pub(crate) async fn get_user_tokens(
     Ok(non_zero_balances)
 }
 
+// Helper function to get the SOL balance of the user's wallet
+async fn get_user_sol_balance(
+    telegram_id: i64,
+    db_pool: Arc<PgPool>,
+    solana_client: Arc<ResilientRpcClient>,
+) -> Result<f64> {
+    let user = db::get_user_by_telegram_id(&db_pool, telegram_id).await?;
+
+    let address = user
+        .solana_address
+        .ok_or_else(|| BotError::WalletNotFound)?;
+
+    solana::get_sol_balance(&solana_client, &address).await
+}
+
+// Jupiter quote for the trade, None if it couldn't be fetched
+async fn fetch_trade_quote(
+    services: &Arc<ServiceContainer>,
+    telegram_id: i64,
+    trade_type: &OrderType,
+    token_address: &str,
+    amount: f64,
+    price_in_sol: f64,
+) -> Option<TradeQuote> {
+    let interactor = TradeInteractorImpl::new(
+        services.db_pool(),
+        services.solana_client(),
+        services.price_service(),
+        services.token_repository(),
+        services.swap_service(),
+    );
+
+    match interactor
+        .get_trade_quote(telegram_id, trade_type, token_address, amount, price_in_sol)
+        .await
+    {
+        Ok(quote) => Some(quote),
+        Err(e) => {
+            info!("Failed to get trade quote for {}: {}", token_address, e);
+            None
+        }
+    }
+}
+
+// Expected output, minimum, route and price impact lines for the confirmation prompt
+fn format_trade_quote(quote: Option<&TradeQuote>, locale: Locale) -> String {
+    let quote = match quote {
+        Some(quote) => quote,
+        None => return String::new(),
+    };
+
+    let route = if quote.route.is_empty() {
+        String::new()
+    } else {
+        format!("• Route: {}\n", quote.route.join(" → "))
+    };
+
+    let platform_fee = match quote.platform_fee {
+        Some(fee) => format!("• Platform fee: {}%\n", fee),
+        None => String::new(),
+    };
+
+    let impact_warning = if quote.is_high_price_impact() {
+        " ⚠️"
+    } else {
+        ""
+    };
+
+    format!(
+        "• Expected: <b>{} {}</b>\n\
+        • Minimum: <b>{} {}</b> (after {}% slippage)\n\
+        {}\
+        {}\
+        • Price impact: <b>{:.2}%</b>{}\n",
+        format_amount(quote.expected_out, locale),
+        quote.output_symbol,
+        format_amount(quote.minimum_out, locale),
+        quote.output_symbol,
+        quote.slippage,
+        route,
+        platform_fee,
+        quote.price_impact,
+        impact_warning
+    )
+}
+
+// Price impact of smaller parts of the order, only quoted when the full order moves the price
+async fn fetch_impact_preview(
+    services: &Arc<ServiceContainer>,
+    telegram_id: i64,
+    trade_type: &OrderType,
+    token_address: &str,
+    amount: f64,
+    price_in_sol: f64,
+    quote: Option<&TradeQuote>,
+) -> Vec<ImpactPreview> {
+    if !quote.is_some_and(|quote| quote.price_impact >= IMPACT_PREVIEW_MIN_PCT) {
+        return Vec::new();
+    }
+
+    let interactor = TradeInteractorImpl::new(
+        services.db_pool(),
+        services.solana_client(),
+        services.price_service(),
+        services.token_repository(),
+        services.swap_service(),
+    );
+
+    let sizes: Vec<f64> = IMPACT_PREVIEW_FRACTIONS
+        .iter()
+        .map(|fraction| amount * fraction)
+        .collect();
+
+    match interactor
+        .preview_impact(telegram_id, trade_type, token_address, &sizes, price_in_sol)
+        .await
+    {
+        Ok(previews) => previews,
+        Err(e) => {
+            info!(
+                "Failed to preview price impact for {}: {}",
+                token_address, e
+            );
+            Vec::new()
+        }
+    }
+}
+
+// Table of the price impact per order size, so the user can downsize a large trade
+fn format_impact_preview(
+    previews: &[ImpactPreview],
+    amount: f64,
+    price_in_sol: f64,
+    locale: Locale,
+) -> String {
+    if previews.is_empty() {
+        return String::new();
+    }
+
+    let rows: String = previews
+        .iter()
+        .map(|preview| {
+            let impact = match preview.price_impact {
+                Some(impact) => format!("{:.2}%", impact),
+                None => "no route".to_string(),
+            };
+            format!(
+                "{:>4.0}%  {:>12}  {:>8}\n",
+                preview.amount / amount * 100.0,
+                format!(
+                    "{} SOL",
+                    format_amount(preview.amount * price_in_sol, locale)
+                ),
+                impact
+            )
+        })
+        .collect();
+
+    format!("\n<b>Price impact by size</b>\n<pre>{}</pre>", rows)
+}
+
+// Asks once more before executing a trade with a high price impact
+//
+// Returns true when the warning was shown, the dialogue then waits for the second confirmation.
+pub async fn warn_high_price_impact(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: &MyDialogue,
+    state: &State,
+) -> Result<bool> {
+    let mut acknowledged = state.clone();
+    let price_impact = match &mut acknowledged {
+        State::AwaitingBuyConfirmation {
+            high_price_impact, ..
+        }
+        | State::AwaitingSellConfirmation {
+            high_price_impact, ..
+        } => high_price_impact.take(),
+        _ => None,
+    };
+
+    let price_impact = match price_impact {
+        Some(price_impact) => price_impact,
+        None => return Ok(false),
+    };
+
+    dialogue.update(acknowledged).await?;
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "⚠️ <b>High price impact: {:.2}%</b>\n\n\
+            This trade moves the market price noticeably, you may receive much less \
+            than the token price suggests.\n\n\
+            Confirm again to proceed anyway. (yes/no)",
+            price_impact
+        ),
+    )
+    .parse_mode(ParseMode::Html)
+    .reply_markup(ui::create_confirmation_keyboard())
+    .await?;
+
+    Ok(true)
+}
+
 // New handler for sell amount input after token selection
 pub async fn receive_sell_amount(
     bot: Bot,
@@ -228,6 +442,36 @@ pub async fn receive_sell_amount(
     state: State,
     dialogue: MyDialogue,
     services: Arc<ServiceContainer>,
+) -> Result<()> {
+    if let Some(amount_text) = msg.text() {
+        let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+        process_sell_amount(
+            &bot,
+            msg.chat.id,
+            telegram_id,
+            amount_text,
+            state,
+            &dialogue,
+            &services,
+        )
+        .await?;
+    } else {
+        bot.send_message(msg.chat.id, "Please enter the amount as text:")
+            .await?;
+    }
+
+    Ok(())
+}
+
+// Turn a typed amount, percentage or "All" into a sell confirmation prompt
+pub async fn process_sell_amount(
+    bot: &Bot,
+    chat_id: ChatId,
+    telegram_id: i64,
+    amount_text: &str,
+    state: State,
+    dialogue: &MyDialogue,
+    services: &Arc<ServiceContainer>,
 ) -> Result<()> {
     if let State::AwaitingSellAmount {
         token_address,
@@ -237,104 +481,156 @@ pub async fn receive_sell_amount(
         price_in_usdc,
     } = state
     {
-        if let Some(amount_text) = msg.text() {
-            let chat_id = msg.chat.id;
-            let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
-
-            // Create interactor for token operations
-            let db_pool = services.db_pool();
-            let solana_client = services.solana_client();
-            let price_service = services.price_service();
-
-            // Validate amount
-            let amount = if amount_text.to_lowercase() == "all" {
-                // User wants to sell all tokens
-                balance
-            } else if amount_text.ends_with('%') {
-                // User specified a percentage
-                let percentage_str = amount_text.trim_end_matches('%');
-                match percentage_str.parse::<f64>() {
-                    Ok(percentage) if percentage > 0.0 && percentage <= 100.0 => {
-                        balance * (percentage / 100.0)
-                    }
-                    Ok(_) => {
-                        bot.send_message(chat_id, "Percentage must be between 0 and 100%")
-                            .await?;
-                        return Ok(());
-                    }
-                    Err(_) => {
-                        bot.send_message(
-                            chat_id,
-                            "Invalid percentage format. Please enter a number followed by %",
-                        )
+        // Create interactor for token operations
+        let db_pool = services.db_pool();
+        let solana_client = services.solana_client();
+        let price_service = services.price_service();
+
+        // Validate amount
+        let amount = if amount_text.to_lowercase() == "all" {
+            // User wants to sell all tokens
+            balance
+        } else if amount_text.ends_with('%') {
+            // User specified a percentage
+            let percentage_str = amount_text.trim_end_matches('%');
+            match parse_decimal(percentage_str) {
+                Ok(percentage) if percentage > 0.0 && percentage <= 100.0 => {
+                    balance * (percentage / 100.0)
+                }
+                Ok(_) => {
+                    bot.send_message(chat_id, "Percentage must be between 0 and 100%")
                         .await?;
-                        return Ok(());
-                    }
+                    return Ok(());
                 }
-            } else {
-                // User specified a direct amount
-                match amount_text.parse::<f64>() {
-                    Ok(amount) if amount > 0.0 => {
-                        if amount > balance {
-                            bot.send_message(
-                                chat_id,
-                                format!("Insufficient balance. You only have {} tokens", balance),
-                            )
-                            .await?;
-                            return Ok(());
-                        }
-                        amount
-                    }
-                    Ok(_) => {
-                        bot.send_message(chat_id, "Amount must be greater than zero")
-                            .await?;
-                        return Ok(());
-                    }
-                    Err(_) => {
+                Err(_) => {
+                    bot.send_message(
+                        chat_id,
+                        "Invalid percentage format. Please enter a number followed by %",
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            }
+        } else {
+            // User specified a direct amount
+            match parse_decimal(amount_text) {
+                Ok(amount) if amount > 0.0 => {
+                    if amount > balance {
                         bot.send_message(
                             chat_id,
-                            "Invalid amount format. Please enter a number, percentage, or 'All'",
+                            format!("Insufficient balance. You only have {} tokens", balance),
                         )
                         .await?;
                         return Ok(());
                     }
+                    amount
                 }
-            };
-
-            // Calculate total values
-            let total_sol = amount * price_in_sol;
-            let total_usdc = amount * price_in_usdc;
+                Ok(_) => {
+                    bot.send_message(chat_id, "Amount must be greater than zero")
+                        .await?;
+                    return Ok(());
+                }
+                Err(_) => {
+                    bot.send_message(
+                        chat_id,
+                        "Invalid amount format. Please enter a number, percentage, or 'All'",
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            }
+        };
+
+        // Calculate total values
+        let total_sol = amount * price_in_sol;
+        let total_usdc = amount * price_in_usdc;
+
+        // Show the expected and minimum output before asking for confirmation
+        let quote = fetch_trade_quote(
+            services,
+            telegram_id,
+            &OrderType::Sell,
+            &token_address,
+            amount,
+            price_in_sol,
+        )
+        .await;
+        let impact_preview = fetch_impact_preview(
+            services,
+            telegram_id,
+            &OrderType::Sell,
+            &token_address,
+            amount,
+            price_in_sol,
+            quote.as_ref(),
+        )
+        .await;
+        let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+        let quote_info = format_trade_quote(quote.as_ref(), locale)
+            + &format_impact_preview(&impact_preview, amount, price_in_sol, locale);
+        let high_price_impact = quote
+            .filter(|quote| quote.is_high_price_impact())
+            .map(|quote| quote.price_impact);
+
+        // Small trades the user chose to auto-confirm skip the prompt, risky ones never do
+        if high_price_impact.is_none() && auto_confirms(services, telegram_id, total_sol).await {
+            info!(
+                "Auto-confirming sell of {} {} ({} SOL) for user {}",
+                amount, token_symbol, total_sol, telegram_id
+            );
+            dialogue.update(State::Start).await?;
 
-            // Update dialogue state
-            dialogue
-                .update(State::AwaitingSellConfirmation {
-                    token_address: token_address.clone(),
-                    token_symbol: token_symbol.clone(),
+            return execute_confirmed_trade(
+                bot.clone(),
+                chat_id,
+                telegram_id,
+                ConfirmedTrade {
+                    order_type: OrderType::Sell,
+                    token_address: &token_address,
+                    token_symbol: &token_symbol,
                     amount,
                     price_in_sol,
                     total_sol,
-                    total_usdc,
-                })
-                .await?;
-
-            // Prompt for confirmation
-            bot.send_message(
-                chat_id,
-                format!(
-                    "<b>Confirm Sell Order</b>\n\n\
-                    • Sell: <b>{:.6} {}</b>\n\
-                    • Price: <b>{:.6} SOL</b> per token\n\
-                    • Total: <b>{:.6} SOL</b> (${:.2})\n\n\
-                    Do you want to proceed? (yes/no)",
-                    amount, token_symbol, price_in_sol, total_sol, total_usdc
-                ),
+                },
+                services.clone(),
             )
-            .parse_mode(ParseMode::Html)
-            .await?;
-        } else {
-            bot.send_message(msg.chat.id, "Please enter the amount as text:")
-                .await?;
+            .await;
         }
+
+        // Update dialogue state
+        dialogue
+            .update(State::AwaitingSellConfirmation {
+                token_address: token_address.clone(),
+                token_symbol: token_symbol.clone(),
+                amount,
+                price_in_sol,
+                total_sol,
+                total_usdc,
+                high_price_impact,
+            })
+            .await?;
+
+        // Prompt for confirmation
+        bot.send_message(
+            chat_id,
+            format!(
+                "<b>Confirm Sell Order</b>\n\n\
+                • Sell: <b>{} {}</b>\n\
+                • Price: <b>{} SOL</b> per token\n\
+                • Total: <b>{} SOL</b> ({})\n\
+                {}\n\
+                Do you want to proceed? (yes/no)",
+                format_amount(amount, locale),
+                token_symbol,
+                format_locale_price(price_in_sol, locale),
+                format_amount(total_sol, locale),
+                format_usd(total_usdc, locale),
+                quote_info
+            ),
+        )
+        .parse_mode(ParseMode::Html)
+        .reply_markup(ui::create_confirmation_keyboard())
+        .await?;
     }
 
     Ok(())
@@ -348,91 +644,46 @@ pub async fn receive_sell_confirmation(
     dialogue: MyDialogue,
     services: Arc<ServiceContainer>,
 ) -> Result<()> {
+    let pending = state.clone();
+
     if let State::AwaitingSellConfirmation {
         token_address,
         token_symbol,
         amount,
         price_in_sol,
         total_sol,
-        total_usdc,
+        ..
     } = state
     {
         if let Some(text) = msg.text() {
             let confirmation = text.to_lowercase();
+            let confirmed = confirmation == "yes" || confirmation == "y";
             let chat_id = msg.chat.id;
-            let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+            let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+
+            if confirmed && warn_high_price_impact(&bot, chat_id, &dialogue, &pending).await? {
+                return Ok(());
+            }
 
             // Reset dialogue state
             dialogue.update(State::Start).await?;
 
-            if confirmation == "yes" || confirmation == "y" {
-                // Show processing message
-                let processing_msg = bot
-                    .send_message(
-                        chat_id,
-                        format!("Processing your SELL order... Please wait."),
-                    )
-                    .await?;
-
-                // Execute the trade
-                let db_pool = services.db_pool();
-                let solana_client = services.solana_client();
-                let price_service = services.price_service();
-                let token_repository = services.token_repository();
-                let swap_service = services.swap_service();
-
-                let interactor = Arc::new(TradeInteractorImpl::new(
-                    db_pool.clone(),
-                    solana_client,
-                    price_service,
-                    token_repository,
-                    swap_service,
-                ));
-
-                let result = interactor
-                    .execute_trade(
-                        telegram_id,
-                        &OrderType::Sell,
-                        &token_address,
-                        &token_symbol,
-                        amount,
-                        price_in_sol,
-                    )
-                    .await?;
-
-                if result.success {
-                    // Trade was successful
-                    let success_text = format!(
-                        "✅ SELL order completed successfully.\n\
-                        Amount: {} {}\n\
-                        Price: {:.6} SOL per token\n\
-                        Total: {:.6} SOL\n\
-                        Tx Signature: {}\n\
-                        Check transaction: https://explorer.solana.com/tx/{}",
+            if confirmed {
+                execute_confirmed_trade(
+                    bot,
+                    chat_id,
+                    telegram_id,
+                    ConfirmedTrade {
+                        order_type: OrderType::Sell,
+                        token_address: &token_address,
+                        token_symbol: &token_symbol,
                         amount,
-                        token_symbol,
                         price_in_sol,
                         total_sol,
-                        result.signature.as_deref().unwrap_or("unknown"),
-                        result.signature.as_deref().unwrap_or("unknown")
-                    );
-
-                    bot.edit_message_text(chat_id, processing_msg.id, success_text)
-                        .await?;
-                } else {
-                    // Trade failed
-                    let error_text = format!(
-                        "❌ Error executing SELL order for {} {}:\n{}",
-                        amount,
-                        token_symbol,
-                        result
-                            .error_message
-                            .unwrap_or_else(|| "Unknown error".to_string())
-                    );
-
-                    bot.edit_message_text(chat_id, processing_msg.id, error_text)
-                        .await?;
-                }
+                    },
+                    services,
+                )
+                .await?;
             } else {
                 // User cancelled the trade
                 bot.send_message(chat_id, "Trade cancelled.").await?;
@@ -455,7 +706,7 @@ pub async fn receive_buy_manual_address(
 ) -> Result<()> {
     if let Some(address_text) = msg.text() {
         let chat_id = msg.chat.id;
-        let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+        let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
 
         // Validate the token address
         let db_pool = services.db_pool();
@@ -477,6 +728,20 @@ pub async fn receive_buy_manual_address(
                 // Get token info to display to the user
                 match interactor.get_token_info(address_text).await {
                     Ok((token_symbol, price_in_sol, price_in_usdc)) => {
+                        let risk = services.token_risk().assess(address_text).await;
+                        if risk.is_risky() {
+                            // The button continues the buy with this token
+                            dialogue.update(State::Start).await?;
+                            return ui::send_token_risk_warning(
+                                &bot,
+                                chat_id,
+                                address_text,
+                                &risk,
+                                format!("buy_risk_ok_{}", address_text),
+                            )
+                            .await;
+                        }
+
                         // Update dialogue state
                         dialogue
                             .update(State::AwaitingBuyAmount {
@@ -487,18 +752,33 @@ pub async fn receive_buy_manual_address(
                             })
                             .await?;
 
-                        // Display token info
+                        // Display token info with the quick-buy buttons
+                        let quick_buy_amounts = get_quick_buy_amounts(&services, telegram_id).await;
+                        let language_code = msg
+                            .from
+                            .as_ref()
+                            .and_then(|user| user.language_code.clone());
+                        let locale = i18n::user_locale(
+                            &services.db_pool(),
+                            telegram_id,
+                            language_code.as_deref(),
+                        )
+                        .await;
                         bot.send_message(
                             chat_id,
                             format!(
-                                "Token: {} ({})\nCurrent price: {:.6} SOL (${:.2})\n\nHow many tokens do you want to buy?",
-                                token_symbol, address_text, price_in_sol, price_in_usdc
+                                "Token: {} ({})\nCurrent price: {} SOL ({})\n\nHow many tokens do you want to buy? You can also enter the SOL to spend, e.g. 0.5 SOL, 25% or All.",
+                                token_symbol,
+                                address_text,
+                                format_locale_price(price_in_sol, locale),
+                                format_usd(price_in_usdc, locale)
                             ),
                         )
+                            .reply_markup(create_quick_buy_keyboard(&quick_buy_amounts, address_text))
                             .await?;
                     }
                     Err(e) => {
-                        bot.send_message(chat_id, format!("Error getting token info: {}", e))
+                        bot.send_message(chat_id, BotError::token_info_message(&e))
                             .await?;
                     }
                 }
@@ -524,6 +804,105 @@ pub async fn receive_buy_manual_address(
     Ok(())
 }
 
+// Handler for the token search query in the buy flow
+pub async fn receive_buy_token_search(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = msg.chat.id;
+
+    let query = match msg.text() {
+        Some(text) if !text.trim().is_empty() => text.trim().to_string(),
+        _ => {
+            bot.send_message(chat_id, "Please enter the token symbol or name as text:")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // A pasted contract address skips the search
+    if crate::validate_solana_address(&query) {
+        return receive_buy_manual_address(bot, msg, dialogue, services).await;
+    }
+
+    let interactor = TradeInteractorImpl::new(
+        services.db_pool(),
+        services.solana_client(),
+        services.price_service(),
+        services.token_repository(),
+        services.swap_service(),
+    );
+
+    let tokens = match interactor.search_tokens(&query).await {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            bot.send_message(chat_id, format!("Error searching tokens: {}", e))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    if tokens.is_empty() {
+        bot.send_message(
+            chat_id,
+            "No tokens found. Try another symbol or name, or paste the contract address:",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // Wait for a button press, the buttons carry the mint address
+    dialogue.update(State::AwaitingBuyTokenSelection).await?;
+
+    let mut keyboard_buttons: Vec<Vec<InlineKeyboardButton>> = tokens
+        .iter()
+        .map(|token| {
+            vec![InlineKeyboardButton::callback(
+                format_search_result(token),
+                format!("buy_token_{}", token.id),
+            )]
+        })
+        .collect();
+
+    keyboard_buttons.push(vec![InlineKeyboardButton::callback(
+        "🔍 Search Again",
+        "buy_search",
+    )]);
+    keyboard_buttons.push(vec![InlineKeyboardButton::callback("← Cancel", "menu")]);
+
+    let has_unverified = tokens.iter().any(|token| !token.verified);
+    let text = if has_unverified {
+        "Select the token to buy. Check the contract address, many tokens share the same symbol.\n\
+        ⚠️ marks tokens that are not verified by Jupiter."
+    } else {
+        "Select the token to buy. Check the contract address, many tokens share the same symbol."
+    };
+
+    bot.send_message(chat_id, text)
+        .reply_markup(InlineKeyboardMarkup::new(keyboard_buttons))
+        .await?;
+
+    Ok(())
+}
+
+// Button text for a search result, the mint prefix tells apart tokens with the same symbol
+fn format_search_result(token: &crate::entity::Token) -> String {
+    let mint_prefix: String = token.id.chars().take(4).collect();
+    let mint_suffix: String = token
+        .id
+        .chars()
+        .skip(token.id.chars().count().saturating_sub(4))
+        .collect();
+    let badge = if token.verified { "✅" } else { "⚠️" };
+
+    format!(
+        "{} {} · {} ({}…{})",
+        badge, token.symbol, token.name, mint_prefix, mint_suffix
+    )
+}
+
 // Handler for buy amount
 pub async fn receive_buy_amount(
     bot: Bot,
@@ -531,6 +910,36 @@ pub async fn receive_buy_amount(
     state: State,
     dialogue: MyDialogue,
     services: Arc<ServiceContainer>,
+) -> Result<()> {
+    if let Some(amount_text) = msg.text() {
+        let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+        process_buy_amount(
+            &bot,
+            msg.chat.id,
+            telegram_id,
+            amount_text,
+            state,
+            &dialogue,
+            &services,
+        )
+        .await?;
+    } else {
+        bot.send_message(msg.chat.id, "Please enter the amount as text:")
+            .await?;
+    }
+
+    Ok(())
+}
+
+// Turn a typed or quick-buy amount into a buy confirmation prompt
+pub async fn process_buy_amount(
+    bot: &Bot,
+    chat_id: ChatId,
+    telegram_id: i64,
+    amount_text: &str,
+    state: State,
+    dialogue: &MyDialogue,
+    services: &Arc<ServiceContainer>,
 ) -> Result<()> {
     if let State::AwaitingBuyAmount {
         token_address,
@@ -539,61 +948,190 @@ pub async fn receive_buy_amount(
         price_in_usdc,
     } = state
     {
-        if let Some(amount_text) = msg.text() {
-            let chat_id = msg.chat.id;
-
-            // Validate amount
-            match amount_text.parse::<f64>() {
-                Ok(amount) if amount > 0.0 => {
-                    // Calculate total
-                    let total_sol = amount * price_in_sol;
-                    let total_usdc = amount * price_in_usdc;
-
-                    // Update dialogue state
-                    dialogue
-                        .update(State::AwaitingBuyConfirmation {
-                            token_address: token_address.clone(),
-                            token_symbol: token_symbol.clone(),
-                            amount,
-                            price_in_sol,
-                            total_sol,
-                            total_usdc,
-                        })
-                        .await?;
-
-                    // Prompt for confirmation
-                    bot.send_message(
-                        chat_id,
-                        format!(
-                            "<b>Confirm Buy Order</b>\n\n\
-                            • Buy: <b>{:.6} {}</b>\n\
-                            • Price: <b>{:.6} SOL</b> per token\n\
-                            • Total: <b>{:.6} SOL</b> (${:.2})\n\n\
-                            Do you want to proceed? (yes/no)",
-                            amount, token_symbol, price_in_sol, total_sol, total_usdc
-                        ),
-                    )
-                    .parse_mode(ParseMode::Html)
+        // A bare number is a token amount, "0.5 SOL", "25%" or "All" is the SOL to spend
+        let amount = match parse_decimal(amount_text) {
+            Ok(amount) if amount > 0.0 => amount,
+            Ok(_) => {
+                bot.send_message(chat_id, "Amount must be greater than zero")
                     .await?;
-                }
-                Ok(_) => {
-                    bot.send_message(chat_id, "Amount must be greater than zero")
+                return Ok(());
+            }
+            Err(_) => {
+                if price_in_sol <= 0.0 {
+                    bot.send_message(chat_id, "No price available for this token")
                         .await?;
+                    return Ok(());
                 }
-                Err(_) => {
-                    bot.send_message(chat_id, "Invalid amount format. Please enter a number.")
+
+                let interactor = TradeInteractorImpl::new(
+                    services.db_pool(),
+                    services.solana_client(),
+                    services.price_service(),
+                    services.token_repository(),
+                    services.swap_service(),
+                );
+
+                let sol_balance =
+                    get_user_sol_balance(telegram_id, services.db_pool(), services.solana_client())
                         .await?;
+
+                match interactor
+                    .validate_buy_spend(amount_text, sol_balance)
+                    .await
+                {
+                    Ok(sol_to_spend) => sol_to_spend / price_in_sol,
+                    Err(e) => {
+                        bot.send_message(chat_id, e.to_string()).await?;
+                        return Ok(());
+                    }
                 }
             }
-        } else {
-            bot.send_message(msg.chat.id, "Please enter the amount as text:")
-                .await?;
+        };
+
+        // Calculate total
+        let total_sol = amount * price_in_sol;
+        let total_usdc = amount * price_in_usdc;
+
+        // Show the expected and minimum output before asking for confirmation
+        let quote = fetch_trade_quote(
+            services,
+            telegram_id,
+            &OrderType::Buy,
+            &token_address,
+            amount,
+            price_in_sol,
+        )
+        .await;
+        let impact_preview = fetch_impact_preview(
+            services,
+            telegram_id,
+            &OrderType::Buy,
+            &token_address,
+            amount,
+            price_in_sol,
+            quote.as_ref(),
+        )
+        .await;
+        let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+        let quote_info = format_trade_quote(quote.as_ref(), locale)
+            + &format_impact_preview(&impact_preview, amount, price_in_sol, locale);
+        let high_price_impact = quote
+            .filter(|quote| quote.is_high_price_impact())
+            .map(|quote| quote.price_impact);
+
+        // Small trades the user chose to auto-confirm skip the prompt, risky ones never do
+        if high_price_impact.is_none() && auto_confirms(services, telegram_id, total_sol).await {
+            info!(
+                "Auto-confirming buy of {} {} ({} SOL) for user {}",
+                amount, token_symbol, total_sol, telegram_id
+            );
+            dialogue.update(State::Start).await?;
+
+            return execute_confirmed_trade(
+                bot.clone(),
+                chat_id,
+                telegram_id,
+                ConfirmedTrade {
+                    order_type: OrderType::Buy,
+                    token_address: &token_address,
+                    token_symbol: &token_symbol,
+                    amount,
+                    price_in_sol,
+                    total_sol,
+                },
+                services.clone(),
+            )
+            .await;
         }
+
+        // Update dialogue state
+        dialogue
+            .update(State::AwaitingBuyConfirmation {
+                token_address: token_address.clone(),
+                token_symbol: token_symbol.clone(),
+                amount,
+                price_in_sol,
+                total_sol,
+                total_usdc,
+                high_price_impact,
+            })
+            .await?;
+
+        // Prompt for confirmation
+        bot.send_message(
+            chat_id,
+            format!(
+                "<b>Confirm Buy Order</b>\n\n\
+                • Buy: <b>{} {}</b>\n\
+                • Price: <b>{} SOL</b> per token\n\
+                • Total: <b>{} SOL</b> ({})\n\
+                {}\n\
+                Do you want to proceed? (yes/no)",
+                format_amount(amount, locale),
+                token_symbol,
+                format_locale_price(price_in_sol, locale),
+                format_amount(total_sol, locale),
+                format_usd(total_usdc, locale),
+                quote_info
+            ),
+        )
+        .parse_mode(ParseMode::Html)
+        .reply_markup(ui::create_confirmation_keyboard())
+        .await?;
     }
 
     Ok(())
 }
 
+// Quick-buy buttons for the user's preset SOL amounts, shown under the buy amount prompt
+pub fn create_quick_buy_keyboard(amounts: &[f64], token_address: &str) -> InlineKeyboardMarkup {
+    let buttons: Vec<InlineKeyboardButton> = amounts
+        .iter()
+        .map(|amount| {
+            InlineKeyboardButton::callback(
+                format!("{} SOL", amount),
+                format!("buy_quick_{}_{}", amount, token_address),
+            )
+        })
+        .collect();
+
+    let mut keyboard_buttons: Vec<Vec<InlineKeyboardButton>> =
+        buttons.chunks(3).map(|row| row.to_vec()).collect();
+    keyboard_buttons.push(vec![
+        InlineKeyboardButton::callback("ℹ️ Token Info", format!("token_info_{}", token_address)),
+        InlineKeyboardButton::callback("← Cancel", "menu"),
+    ]);
+
+    InlineKeyboardMarkup::new(keyboard_buttons)
+}
+
+/// SOL amount and mint of a quick-buy button, the reverse of `create_quick_buy_keyboard`
+pub fn parse_quick_buy_callback(callback_data: &str) -> Option<(f64, &str)> {
+    let (amount, token_address) = callback_data.strip_prefix("buy_quick_")?.split_once('_')?;
+    let amount = amount
+        .parse::<f64>()
+        .ok()
+        .filter(|amount| amount.is_finite() && *amount > 0.0)?;
+
+    Some((amount, token_address))
+}
+
+// Quick-buy amounts of the user, the defaults when the user can't be loaded
+pub async fn get_quick_buy_amounts(services: &ServiceContainer, telegram_id: i64) -> Vec<f64> {
+    db::get_user_by_telegram_id(&services.db_pool(), telegram_id)
+        .await
+        .map(|user| user.get_quick_buy_amounts())
+        .unwrap_or_else(|_| DEFAULT_QUICK_BUY_AMOUNTS.to_vec())
+}
+
+// Whether a trade of `total_sol` is below the user's auto-confirm threshold
+async fn auto_confirms(services: &ServiceContainer, telegram_id: i64, total_sol: f64) -> bool {
+    db::get_user_by_telegram_id(&services.db_pool(), telegram_id)
+        .await
+        .map(|user| total_sol < user.get_auto_confirm_below_sol())
+        .unwrap_or(false)
+}
+
 // Handler for buy confirmation
 pub async fn receive_buy_confirmation(
     bot: Bot,
@@ -602,91 +1140,46 @@ pub async fn receive_buy_confirmation(
     dialogue: MyDialogue,
     services: Arc<ServiceContainer>,
 ) -> Result<()> {
+    let pending = state.clone();
+
     if let State::AwaitingBuyConfirmation {
         token_address,
         token_symbol,
         amount,
         price_in_sol,
         total_sol,
-        total_usdc,
+        ..
     } = state
     {
         if let Some(text) = msg.text() {
             let confirmation = text.to_lowercase();
+            let confirmed = confirmation == "yes" || confirmation == "y";
             let chat_id = msg.chat.id;
-            let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+            let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+
+            if confirmed && warn_high_price_impact(&bot, chat_id, &dialogue, &pending).await? {
+                return Ok(());
+            }
 
             // Reset dialogue state
             dialogue.update(State::Start).await?;
 
-            if confirmation == "yes" || confirmation == "y" {
-                // Show processing message
-                let processing_msg = bot
-                    .send_message(
-                        chat_id,
-                        format!("Processing your BUY order... Please wait."),
-                    )
-                    .await?;
-
-                // Execute the trade
-                let db_pool = services.db_pool();
-                let solana_client = services.solana_client();
-                let price_service = services.price_service();
-                let token_repository = services.token_repository();
-                let swap_service = services.swap_service();
-
-                let interactor = Arc::new(TradeInteractorImpl::new(
-                    db_pool.clone(),
-                    solana_client,
-                    price_service,
-                    token_repository,
-                    swap_service,
-                ));
-
-                let result = interactor
-                    .execute_trade(
-                        telegram_id,
-                        &OrderType::Buy,
-                        &token_address,
-                        &token_symbol,
-                        amount,
-                        price_in_sol,
-                    )
-                    .await?;
-
-                if result.success {
-                    // Trade was successful
-                    let success_text = format!(
-                        "✅ BUY order completed successfully.\n\
-                        Amount: {} {}\n\
-                        Price: {:.6} SOL per token\n\
-                        Total: {:.6} SOL\n\
-                        Tx Signature: {}\n\
-                        Check transaction: https://explorer.solana.com/tx/{}",
+            if confirmed {
+                execute_confirmed_trade(
+                    bot,
+                    chat_id,
+                    telegram_id,
+                    ConfirmedTrade {
+                        order_type: OrderType::Buy,
+                        token_address: &token_address,
+                        token_symbol: &token_symbol,
                         amount,
-                        token_symbol,
                         price_in_sol,
                         total_sol,
-                        result.signature.as_deref().unwrap_or("unknown"),
-                        result.signature.as_deref().unwrap_or("unknown")
-                    );
-
-                    bot.edit_message_text(chat_id, processing_msg.id, success_text)
-                        .await?;
-                } else {
-                    // Trade failed
-                    let error_text = format!(
-                        "❌ Error executing BUY order for {} {}:\n{}",
-                        amount,
-                        token_symbol,
-                        result
-                            .error_message
-                            .unwrap_or_else(|| "Unknown error".to_string())
-                    );
-
-                    bot.edit_message_text(chat_id, processing_msg.id, error_text)
-                        .await?;
-                }
+                    },
+                    services,
+                )
+                .await?;
             } else {
                 // User cancelled the trade
                 bot.send_message(chat_id, "Trade cancelled.").await?;
@@ -699,3 +1192,365 @@ pub async fn receive_buy_confirmation(
 
     Ok(())
 }
+
+/// A buy or sell the user confirmed, at the price and total shown in the prompt
+pub struct ConfirmedTrade<'a> {
+    pub order_type: OrderType,
+    pub token_address: &'a str,
+    pub token_symbol: &'a str,
+    pub amount: f64,
+    pub price_in_sol: f64,
+    pub total_sol: f64,
+}
+
+// Execute a confirmed buy or sell order, used by both text and button confirmations
+pub async fn execute_confirmed_trade(
+    bot: Bot,
+    chat_id: ChatId,
+    telegram_id: i64,
+    trade: ConfirmedTrade<'_>,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let ConfirmedTrade {
+        order_type,
+        token_address,
+        token_symbol,
+        amount,
+        price_in_sol,
+        total_sol,
+    } = trade;
+
+    // A double tap on "Confirm" must not submit the same swap twice
+    let _in_flight = match services.idempotency_guard().begin(format!(
+        "{}:{}:{}:{}",
+        telegram_id, order_type, token_address, amount
+    )) {
+        Some(in_flight) => in_flight,
+        None => {
+            bot.send_message(chat_id, "⏳ This trade is already processing.")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // Show processing message
+    let processing_msg = bot
+        .send_message(
+            chat_id,
+            format!("Processing your {} order... Please wait.", order_type),
+        )
+        .await?;
+
+    // Execute the trade
+    let db_pool = services.db_pool();
+    let solana_client = services.solana_client();
+    let price_service = services.price_service();
+    let token_repository = services.token_repository();
+    let swap_service = services.swap_service();
+
+    // The processing message switches to "Submitted" once the swap is sent
+    let (submitted_tx, submitted_progress) =
+        ui::show_submitted_progress(&bot, chat_id, processing_msg.id);
+
+    let interactor = TradeInteractorImpl::new(
+        db_pool.clone(),
+        solana_client,
+        price_service,
+        token_repository,
+        swap_service,
+    )
+    .with_submitted_sender(submitted_tx)
+    .with_cooldown(services.trade_cooldown());
+
+    let result = interactor
+        .execute_trade(
+            telegram_id,
+            TradeRequest {
+                trade_type: &order_type,
+                token_address,
+                token_symbol,
+                amount,
+                price_in_sol,
+                slippage: None,
+            },
+        )
+        .await;
+
+    // Close the progress channel and let the last edit finish before the result is shown
+    drop(interactor);
+    let _ = submitted_progress.await;
+    let result = result?;
+
+    if result.success {
+        // Trade was successful
+        let signature = result.signature.as_deref().unwrap_or("unknown");
+        let user = db::get_user_by_telegram_id(&db_pool, telegram_id)
+            .await
+            .ok();
+        let explorer = user
+            .as_ref()
+            .map(|user| user.get_explorer())
+            .unwrap_or_default();
+        let locale = i18n::user_locale(&db_pool, telegram_id, None).await;
+        // A sell is capped to the on-chain balance when the wallet held less than expected
+        let (amount, total_sol, reduced_note) = match result.requested_amount {
+            Some(requested) => (
+                result.amount,
+                result.total_sol,
+                format!(
+                    " (reduced from {}, the wallet held less)",
+                    format_amount(requested, locale)
+                ),
+            ),
+            None => (amount, total_sol, String::new()),
+        };
+        let success_text = format!(
+            "✅ {} order confirmed.\n\
+            Amount: {} {}{}\n\
+            Price: {} SOL per token\n\
+            Total: {} SOL\n\
+            Tx Signature: {}\n\
+            Check transaction: {}",
+            order_type,
+            format_amount(amount, locale),
+            token_symbol,
+            reduced_note,
+            format_locale_price(price_in_sol, locale),
+            format_amount(total_sol, locale),
+            signature,
+            format_tx_url(signature, explorer, Cluster::current())
+        );
+
+        bot.edit_message_text(chat_id, processing_msg.id, success_text)
+            .reply_markup(ui::trade_followup_keyboard(token_address))
+            .await?;
+
+        if user.is_some_and(|user| user.get_trade_card()) {
+            let card = TradeCard {
+                order_type,
+                token_symbol: token_symbol.to_string(),
+                amount: format_amount(amount, locale),
+                price: format_price(price_in_sol),
+                total: format_amount(total_sol, locale),
+            };
+            send_trade_card(&bot, chat_id, card).await;
+        }
+    } else {
+        // Trade failed
+        let error_text = format!(
+            "❌ Error executing {} order for {} {}:\n{}",
+            order_type,
+            amount,
+            token_symbol,
+            result
+                .error_message
+                .unwrap_or_else(|| "Unknown error".to_string())
+        );
+
+        let mut request = bot.edit_message_text(chat_id, processing_msg.id, error_text);
+        if let Some(keyboard) = result.error.as_ref().and_then(ui::error_keyboard) {
+            request = request.reply_markup(keyboard);
+        }
+        request.await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{fake_telegram, run_with_large_stack};
+    use crate::entity::normalize_quick_buy_amounts;
+    use teloxide::dispatching::dialogue::{Dialogue, InMemStorage};
+
+    fn quote(price_impact: f64) -> TradeQuote {
+        TradeQuote {
+            output_symbol: "BONK".to_string(),
+            expected_out: 1000.0,
+            minimum_out: 995.0,
+            slippage: 0.5,
+            price_impact,
+            route: vec!["Orca (40%)".to_string(), "Raydium (60%)".to_string()],
+            platform_fee: None,
+        }
+    }
+
+    fn buy_confirmation(high_price_impact: Option<f64>) -> State {
+        State::AwaitingBuyConfirmation {
+            token_address: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+            token_symbol: "BONK".to_string(),
+            amount: 1000.0,
+            price_in_sol: 0.001,
+            total_sol: 1.0,
+            total_usdc: 150.0,
+            high_price_impact,
+        }
+    }
+
+    // Confirms `state` once and returns whether the warning was shown, the state left
+    // behind and the Telegram methods called
+    fn confirm_once(state: State) -> (bool, State, Vec<String>) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        run_with_large_stack(move || async move {
+            let (bot, mut calls) = fake_telegram().await;
+            let dialogue = Dialogue::new(InMemStorage::<State>::new(), ChatId(1));
+            dialogue.update(state.clone()).await.unwrap();
+
+            let warned = warn_high_price_impact(&bot, ChatId(1), &dialogue, &state)
+                .await
+                .unwrap();
+
+            drop(bot);
+            let mut methods = Vec::new();
+            while let Ok(call) = calls.try_recv() {
+                if call.is("sendMessage") {
+                    assert!(call.body.contains("High price impact: 6.12%"));
+                }
+                methods.push(call.method);
+            }
+            let state = dialogue.get().await.unwrap().unwrap_or_default();
+            tx.send((warned, state, methods)).unwrap();
+        });
+        rx.recv().unwrap()
+    }
+
+    #[test]
+    fn quote_lines_show_the_route_and_impact() {
+        let lines = format_trade_quote(Some(&quote(0.12)), Locale::En);
+
+        assert!(lines.contains("• Route: Orca (40%) → Raydium (60%)"));
+        assert!(lines.contains("• Price impact: <b>0.12%</b>\n"));
+    }
+
+    #[test]
+    fn high_impact_quote_is_marked() {
+        let lines = format_trade_quote(Some(&quote(6.12)), Locale::En);
+
+        assert!(lines.contains("• Price impact: <b>6.12%</b> ⚠️"));
+    }
+
+    #[test]
+    fn missing_quote_adds_no_lines() {
+        assert!(format_trade_quote(None, Locale::En).is_empty());
+    }
+
+    #[test]
+    fn high_impact_trade_asks_for_a_second_confirmation() {
+        let (warned, state, methods) = confirm_once(buy_confirmation(Some(6.12)));
+
+        assert!(warned);
+        assert_eq!(methods, vec!["SendMessage"]);
+        // The warning is acknowledged, the next confirmation executes the trade
+        assert!(matches!(
+            state,
+            State::AwaitingBuyConfirmation {
+                high_price_impact: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn normal_trade_is_not_warned() {
+        let (warned, state, methods) = confirm_once(buy_confirmation(None));
+
+        assert!(!warned);
+        assert!(methods.is_empty());
+        assert!(matches!(state, State::AwaitingBuyConfirmation { .. }));
+    }
+
+    #[test]
+    fn search_result_shows_the_mint_ends_and_verification() {
+        let token = crate::entity::Token {
+            id: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            symbol: "USDC".to_string(),
+            name: "USD Coin".to_string(),
+            decimals: 6,
+            logo_uri: String::new(),
+            verified: true,
+        };
+
+        assert_eq!(
+            format_search_result(&token),
+            "✅ USDC · USD Coin (EPjF…Dt1v)"
+        );
+
+        let copycat = crate::entity::Token {
+            id: "Fake1111111111111111111111111111111111abcd".to_string(),
+            verified: false,
+            ..token
+        };
+        assert_eq!(
+            format_search_result(&copycat),
+            "⚠️ USDC · USD Coin (Fake…abcd)"
+        );
+    }
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    fn callback(button: &InlineKeyboardButton) -> &str {
+        match &button.kind {
+            teloxide::types::InlineKeyboardButtonKind::CallbackData(data) => data,
+            _ => panic!("callback button expected"),
+        }
+    }
+
+    #[test]
+    fn quick_buy_buttons_carry_the_amount_and_mint() {
+        let keyboard = create_quick_buy_keyboard(&[0.1, 0.5, 1.0, 5.0], BONK);
+
+        let rows = &keyboard.inline_keyboard;
+        assert_eq!(rows[0].len(), 3);
+        assert_eq!(rows[0][1].text, "0.5 SOL");
+        assert_eq!(callback(&rows[0][1]), format!("buy_quick_0.5_{}", BONK));
+        assert_eq!(callback(&rows[1][0]), format!("buy_quick_5_{}", BONK));
+        assert_eq!(callback(rows.last().unwrap().last().unwrap()), "menu");
+    }
+
+    #[test]
+    fn quick_buy_callback_is_read_back_from_the_button() {
+        let keyboard = create_quick_buy_keyboard(&DEFAULT_QUICK_BUY_AMOUNTS, BONK);
+
+        let parsed: Vec<(f64, &str)> = keyboard.inline_keyboard[..2]
+            .iter()
+            .flatten()
+            .filter_map(|button| parse_quick_buy_callback(callback(button)))
+            .collect();
+
+        assert_eq!(
+            parsed,
+            vec![(0.1, BONK), (0.5, BONK), (1.0, BONK), (5.0, BONK)]
+        );
+    }
+
+    #[test]
+    fn longest_quick_buy_callback_fits_in_telegram_limit() {
+        // A 44 character mint with the longest amount the settings accept
+        let mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        let amounts = normalize_quick_buy_amounts(&[999.9999]).unwrap();
+
+        let keyboard = create_quick_buy_keyboard(&amounts, mint);
+
+        let data = callback(&keyboard.inline_keyboard[0][0]);
+        assert_eq!(data, format!("buy_quick_999.9999_{}", mint));
+        assert!(data.len() <= 64, "{} bytes", data.len());
+    }
+
+    #[test]
+    fn malformed_quick_buy_callbacks_are_ignored() {
+        assert_eq!(
+            parse_quick_buy_callback(&format!("buy_quick_abc_{}", BONK)),
+            None
+        );
+        assert_eq!(
+            parse_quick_buy_callback(&format!("buy_quick_-1_{}", BONK)),
+            None
+        );
+        assert_eq!(parse_quick_buy_callback("buy_quick_0.5"), None);
+        assert_eq!(
+            parse_quick_buy_callback(&format!("buy_token_{}", BONK)),
+            None
+        );
+    }
+}
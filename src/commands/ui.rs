@@ -1,4 +1,14 @@
-use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+use crate::entity::{BotError, Cluster, TokenBalance, TokenRisk};
+use crate::logging;
+use crate::solana::jupiter::SOL_MINT;
+use crate::solana::tokens::transaction::SubmittedSender;
+use anyhow::Result;
+use log::error;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageId, ParseMode};
+use teloxide::utils::html;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
 pub fn create_wallet_menu_keyboard() -> InlineKeyboardMarkup {
     InlineKeyboardMarkup::new(vec![
@@ -7,13 +17,20 @@ pub fn create_wallet_menu_keyboard() -> InlineKeyboardMarkup {
             InlineKeyboardButton::callback("Sell", "sell"),
             InlineKeyboardButton::callback("Watchlist", "watchlist"),
         ],
+        vec![
+            InlineKeyboardButton::callback("Portfolio", "portfolio"),
+            InlineKeyboardButton::callback("Price Alerts", "alerts"),
+            InlineKeyboardButton::callback("History", "history"),
+        ],
         vec![
             InlineKeyboardButton::callback("Check Price", "price"),
             InlineKeyboardButton::callback("Limit Orders", "limit_orders"),
+            InlineKeyboardButton::callback("DCA", "dca"),
         ],
         vec![
             InlineKeyboardButton::callback("Withdraw", "withdraw"),
             InlineKeyboardButton::callback("View Address", "address"),
+            InlineKeyboardButton::callback("Follow Wallets", "follow"),
         ],
         vec![
             InlineKeyboardButton::callback("Help", "help"),
@@ -22,3 +39,398 @@ pub fn create_wallet_menu_keyboard() -> InlineKeyboardMarkup {
         ],
     ])
 }
+
+/// Inline "Confirm" / "Cancel" buttons shown under dialogue confirmation prompts
+pub fn create_confirmation_keyboard() -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback("✅ Confirm", "dialog_confirm"),
+        InlineKeyboardButton::callback("❌ Cancel", "dialog_cancel"),
+    ]])
+}
+
+/// Next step offered under a failed trade or withdrawal, `None` when there's nothing to suggest
+pub fn error_keyboard(error: &BotError) -> Option<InlineKeyboardMarkup> {
+    let button = match error {
+        BotError::InsufficientSol { .. } | BotError::InsufficientToken { .. } => {
+            InlineKeyboardButton::callback("💰 Deposit", "address")
+        }
+        BotError::SlippageExceeded { .. } => {
+            InlineKeyboardButton::callback("⚙️ Adjust Slippage", "set_slippage")
+        }
+        _ => return None,
+    };
+
+    Some(InlineKeyboardMarkup::new(vec![
+        vec![button],
+        vec![InlineKeyboardButton::callback("← Back to Menu", "menu")],
+    ]))
+}
+
+/// Warn about a risky token before it is bought, `continue_callback` proceeds anyway
+pub async fn send_token_risk_warning(
+    bot: &Bot,
+    chat_id: ChatId,
+    token_address: &str,
+    risk: &TokenRisk,
+    continue_callback: String,
+) -> Result<()> {
+    let flags: Vec<String> = risk
+        .flags
+        .iter()
+        .map(|flag| format!("• {}", html::escape(&flag.to_string())))
+        .collect();
+
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![InlineKeyboardButton::callback(
+            "⚠️ Continue Anyway",
+            continue_callback,
+        )],
+        vec![InlineKeyboardButton::callback("← Cancel", "menu")],
+    ]);
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "⚠️ <b>This token may be a scam</b>\n\n\
+            <code>{}</code>\n\n\
+            {}\n\n\
+            You could lose everything you put in. Only continue if you trust this token.",
+            token_address,
+            flags.join("\n")
+        ),
+    )
+    .parse_mode(ParseMode::Html)
+    .reply_markup(keyboard)
+    .await?;
+
+    Ok(())
+}
+
+/// Follow-up actions shown under a successful buy or sell of `token_address`
+pub fn trade_followup_keyboard(token_address: &str) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback("🟢 Buy More", format!("buy_token_{}", token_address)),
+            InlineKeyboardButton::callback("🔴 Sell All", format!("sell_all_{}", token_address)),
+        ],
+        vec![InlineKeyboardButton::callback(
+            "🎯 Set Limit Sell",
+            format!("limit_sell_token_{}", token_address),
+        )],
+        vec![InlineKeyboardButton::callback("← Back to Menu", "menu")],
+    ])
+}
+
+/// One button per token of the sell list
+///
+/// Native SOL can't be sold for SOL and is left out. Wrapped SOL is listed as wSOL and
+/// its button offers unwrapping instead of a swap.
+pub fn sell_token_buttons(tokens: Vec<TokenBalance>) -> Vec<Vec<InlineKeyboardButton>> {
+    tokens
+        .into_iter()
+        .filter_map(|token| {
+            let label = if token.mint_address == SOL_MINT {
+                format!("wSOL: {} (unwrap)", token.display_amount())
+            } else if token.symbol == "SOL" {
+                return None;
+            } else {
+                format!("{}: {}", token.symbol, token.display_amount())
+            };
+
+            Some(vec![InlineKeyboardButton::callback(
+                label,
+                format!("sell_token_{}", token.mint_address),
+            )])
+        })
+        .collect()
+}
+
+/// Swaps are routed through Jupiter, which only runs on mainnet. Tells the user and returns
+/// false when trading features can't be used on the active cluster
+pub async fn ensure_jupiter_available(
+    bot: &Bot,
+    chat_id: ChatId,
+    cluster: Cluster,
+) -> Result<bool> {
+    if cluster.supports_jupiter() {
+        return Ok(true);
+    }
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "⚠️ Trading is unavailable on {}.\n\n\
+            Swaps, limit orders and DCA buys go through Jupiter, which only runs on mainnet.",
+            cluster
+        ),
+    )
+    .await?;
+
+    Ok(false)
+}
+
+/// Maximum number of token buttons shown on one page of a token selection keyboard
+pub const TOKENS_PER_PAGE: usize = 8;
+
+/// Number of pages needed to show `total` items, an empty list still has one page
+pub fn page_count(total: usize, per_page: usize) -> usize {
+    total.div_ceil(per_page).max(1)
+}
+
+/// Range of item indices shown on `page`, pages past the end are clamped to the last one
+pub fn page_bounds(total: usize, page: usize, per_page: usize) -> std::ops::Range<usize> {
+    let page = page.min(page_count(total, per_page) - 1);
+    let start = page * per_page;
+    let end = (start + per_page).min(total);
+
+    start..end
+}
+
+/// Keeps the button rows of the requested page and appends a "◀ Prev"/"Next ▶" row
+/// whose callbacks are `page_callback_prefix` followed by the page index, e.g. `sell_page_2`
+pub fn paginate_buttons(
+    buttons: Vec<Vec<InlineKeyboardButton>>,
+    page: usize,
+    page_callback_prefix: &str,
+) -> Vec<Vec<InlineKeyboardButton>> {
+    let total = buttons.len();
+    let pages = page_count(total, TOKENS_PER_PAGE);
+    let page = page.min(pages - 1);
+    let bounds = page_bounds(total, page, TOKENS_PER_PAGE);
+
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = buttons
+        .into_iter()
+        .skip(bounds.start)
+        .take(bounds.len())
+        .collect();
+
+    if let Some(navigation) = page_navigation(page, pages, page_callback_prefix) {
+        rows.push(navigation);
+    }
+
+    rows
+}
+
+/// "◀ Prev"/"Next ▶" row for lists that are paged before building the keyboard,
+/// `None` when there is only one page
+pub fn page_navigation(
+    page: usize,
+    pages: usize,
+    page_callback_prefix: &str,
+) -> Option<Vec<InlineKeyboardButton>> {
+    let mut navigation = Vec::new();
+    if page > 0 {
+        navigation.push(InlineKeyboardButton::callback(
+            "◀ Prev",
+            format!("{}{}", page_callback_prefix, page - 1),
+        ));
+    }
+    if page + 1 < pages {
+        navigation.push(InlineKeyboardButton::callback(
+            "Next ▶",
+            format!("{}{}", page_callback_prefix, page + 1),
+        ));
+    }
+
+    (!navigation.is_empty()).then_some(navigation)
+}
+
+/// Edit the processing message to "Submitted" once the transaction signature is known
+///
+/// Pass the sender to the interactor, then drop the interactor and await the handle
+/// before showing the final result so the "Submitted" edit can't overwrite it.
+pub fn show_submitted_progress(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+) -> (SubmittedSender, JoinHandle<()>) {
+    let (submitted_tx, mut submitted_rx) = mpsc::unbounded_channel::<String>();
+    let bot = bot.clone();
+
+    let handle = logging::spawn(async move {
+        while let Some(signature) = submitted_rx.recv().await {
+            let text = format!(
+                "⏳ Submitted, waiting for confirmation...\nTx Signature: {}",
+                signature
+            );
+
+            if let Err(e) = bot.edit_message_text(chat_id, message_id, text).await {
+                error!("Failed to show submitted transaction: {}", e);
+            }
+        }
+    });
+
+    (submitted_tx, handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teloxide::types::InlineKeyboardButtonKind;
+
+    fn token_buttons(count: usize) -> Vec<Vec<InlineKeyboardButton>> {
+        (0..count)
+            .map(|i| {
+                vec![InlineKeyboardButton::callback(
+                    format!("T{}", i),
+                    format!("t_{}", i),
+                )]
+            })
+            .collect()
+    }
+
+    fn callback_data(button: &InlineKeyboardButton) -> &str {
+        match &button.kind {
+            InlineKeyboardButtonKind::CallbackData(data) => data,
+            _ => panic!("callback button expected"),
+        }
+    }
+
+    #[test]
+    fn pages_hold_up_to_the_page_size() {
+        assert_eq!(page_count(0, 8), 1);
+        assert_eq!(page_count(8, 8), 1);
+        assert_eq!(page_count(9, 8), 2);
+        assert_eq!(page_count(20, 8), 3);
+    }
+
+    #[test]
+    fn last_page_is_partial() {
+        assert_eq!(page_bounds(20, 0, 8), 0..8);
+        assert_eq!(page_bounds(20, 1, 8), 8..16);
+        assert_eq!(page_bounds(20, 2, 8), 16..20);
+    }
+
+    #[test]
+    fn pages_past_the_end_show_the_last_page() {
+        assert_eq!(page_bounds(20, 7, 8), 16..20);
+        assert_eq!(page_bounds(0, 3, 8), 0..0);
+    }
+
+    #[test]
+    fn middle_page_links_both_ways() {
+        let rows = paginate_buttons(token_buttons(20), 1, "sell_page_");
+
+        assert_eq!(rows.len(), TOKENS_PER_PAGE + 1);
+        assert_eq!(rows[0][0].text, "T8");
+        let navigation = rows.last().unwrap();
+        assert_eq!(callback_data(&navigation[0]), "sell_page_0");
+        assert_eq!(callback_data(&navigation[1]), "sell_page_2");
+    }
+
+    #[test]
+    fn last_page_only_links_back() {
+        let rows = paginate_buttons(token_buttons(20), 2, "sell_page_");
+
+        assert_eq!(rows.len(), 4 + 1);
+        let navigation = rows.last().unwrap();
+        assert_eq!(navigation.len(), 1);
+        assert_eq!(navigation[0].text, "◀ Prev");
+        assert_eq!(callback_data(&navigation[0]), "sell_page_1");
+    }
+
+    #[test]
+    fn single_page_has_no_navigation() {
+        let rows = paginate_buttons(token_buttons(5), 0, "buy_page_");
+
+        assert_eq!(rows.len(), 5);
+        assert!(page_navigation(0, 1, "buy_page_").is_none());
+    }
+
+    // Runs the check against a local Telegram API, returns the result and the message sent
+    fn check_jupiter(cluster: Cluster) -> (bool, Option<String>) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        crate::commands::testing::run_with_large_stack(move || async move {
+            let (bot, mut calls) = crate::commands::testing::fake_telegram().await;
+
+            let available = ensure_jupiter_available(&bot, ChatId(1), cluster)
+                .await
+                .unwrap();
+
+            let sent = calls.try_recv().ok().map(|call| call.body);
+            tx.send((available, sent)).unwrap();
+        });
+        rx.recv().unwrap()
+    }
+
+    #[test]
+    fn trading_is_available_on_mainnet_without_a_message() {
+        assert_eq!(check_jupiter(Cluster::Mainnet), (true, None));
+    }
+
+    #[test]
+    fn trading_off_mainnet_explains_why_it_is_unavailable() {
+        let (available, sent) = check_jupiter(Cluster::Devnet);
+
+        assert!(!available);
+        assert!(sent.unwrap().contains("Trading is unavailable on Devnet"));
+    }
+
+    fn first_callback(keyboard: &InlineKeyboardMarkup) -> &str {
+        callback_data(&keyboard.inline_keyboard[0][0])
+    }
+
+    #[test]
+    fn missing_funds_offer_a_deposit() {
+        let sol = error_keyboard(&BotError::InsufficientSol {
+            required: 1.0,
+            available: 0.2,
+        })
+        .unwrap();
+        let token = error_keyboard(&BotError::InsufficientToken {
+            symbol: "BONK".to_string(),
+            required: 1000.0,
+            available: 10.0,
+        })
+        .unwrap();
+
+        assert_eq!(first_callback(&sol), "address");
+        assert_eq!(first_callback(&token), "address");
+        assert_eq!(callback_data(&sol.inline_keyboard[1][0]), "menu");
+    }
+
+    #[test]
+    fn slippage_failure_offers_the_slippage_setting() {
+        let keyboard = error_keyboard(&BotError::SlippageExceeded { slippage: 0.5 }).unwrap();
+
+        assert_eq!(first_callback(&keyboard), "set_slippage");
+    }
+
+    #[test]
+    fn other_failures_have_no_next_step() {
+        assert!(error_keyboard(&BotError::RpcUnavailable("timeout".to_string())).is_none());
+        assert!(error_keyboard(&BotError::InvalidAddress("abc".to_string())).is_none());
+    }
+
+    #[test]
+    fn trade_followup_offers_the_next_trades_of_the_token() {
+        let mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        let keyboard = trade_followup_keyboard(mint);
+        let buy = format!("buy_token_{}", mint);
+        let sell_all = format!("sell_all_{}", mint);
+        let limit_sell = format!("limit_sell_token_{}", mint);
+
+        let buttons: Vec<Vec<(&str, &str)>> = keyboard
+            .inline_keyboard
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|button| (button.text.as_str(), callback_data(button)))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            buttons,
+            vec![
+                vec![
+                    ("🟢 Buy More", buy.as_str()),
+                    ("🔴 Sell All", sell_all.as_str())
+                ],
+                vec![("🎯 Set Limit Sell", limit_sell.as_str())],
+                vec![("← Back to Menu", "menu")],
+            ]
+        );
+        // Telegram rejects callback data over 64 bytes
+        assert!(buttons.iter().flatten().all(|(_, data)| data.len() <= 64));
+    }
+}
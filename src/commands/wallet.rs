@@ -8,8 +8,13 @@ use teloxide::{
 
 use super::{ui, CommandHandler, MyDialogue};
 use crate::di::ServiceContainer;
+use crate::i18n;
+use crate::interactor::balance_interactor::BalanceInteractorImpl;
 use crate::interactor::wallet_interactor::WalletInteractorImpl;
+use crate::presenter::balance_presenter::{BalancePresenter, BalancePresenterImpl};
 use crate::presenter::wallet_presenter::{WalletPresenter, WalletPresenterImpl};
+use crate::utils::parse_decimal;
+use crate::view::balance_view::TelegramBalanceView;
 use crate::view::wallet_view::TelegramWalletView;
 
 pub struct CreateWalletCommand;
@@ -38,8 +43,14 @@ impl CommandHandler for CreateWalletCommand {
         );
 
         let db_pool = services.db_pool();
+        let language_code = msg
+            .from
+            .as_ref()
+            .and_then(|user| user.language_code.clone());
+        let locale = i18n::user_locale(&db_pool, telegram_id, language_code.as_deref()).await;
+
         let interactor = Arc::new(WalletInteractorImpl::new(db_pool));
-        let view = Arc::new(TelegramWalletView::new(bot.clone(), chat_id));
+        let view = Arc::new(TelegramWalletView::new(bot.clone(), chat_id).with_locale(locale));
         let presenter = WalletPresenterImpl::new(interactor, view);
 
         let result = presenter.create_wallet(telegram_id).await;
@@ -48,18 +59,62 @@ impl CommandHandler for CreateWalletCommand {
         if result.is_ok() {
             // Show user the main menu
             let keyboard = ui::create_wallet_menu_keyboard();
-            bot.send_message(
-                chat_id,
-                "Your wallet has been created successfully. What would you like to do next?",
-            )
-            .reply_markup(keyboard)
-            .await?;
+            bot.send_message(chat_id, i18n::t("wallet.created_next_step", locale))
+                .reply_markup(keyboard)
+                .await?;
         }
 
         Ok(())
     }
 }
 
+pub struct BalanceCommand;
+
+impl CommandHandler for BalanceCommand {
+    fn command_name() -> &'static str {
+        "balance"
+    }
+
+    fn description() -> &'static str {
+        "show your SOL and token balances"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        let db_pool = services.db_pool();
+        let chat_id = msg.chat.id;
+
+        info!("Balance command received from Telegram ID: {}", telegram_id);
+
+        let interactor = Arc::new(BalanceInteractorImpl::new(
+            db_pool.clone(),
+            services.solana_client(),
+            services.price_service(),
+        ));
+        let language_code = msg
+            .from
+            .as_ref()
+            .and_then(|user| user.language_code.clone());
+        let locale = i18n::user_locale(&db_pool, telegram_id, language_code.as_deref()).await;
+        let view = Arc::new(
+            TelegramBalanceView::new(bot, chat_id)
+                .with_cluster(services.cluster())
+                .with_locale(locale),
+        );
+        let presenter = BalancePresenterImpl::new(interactor, view);
+
+        // Asked for explicitly, so skip the balance cache like the Refresh button
+        presenter.refresh_balances(telegram_id, None, true).await?;
+
+        Ok(())
+    }
+}
+
 pub struct AddressCommand;
 
 impl CommandHandler for AddressCommand {
@@ -93,3 +148,52 @@ impl CommandHandler for AddressCommand {
         Ok(())
     }
 }
+
+pub struct DepositCommand;
+
+impl CommandHandler for DepositCommand {
+    fn command_name() -> &'static str {
+        "deposit"
+    }
+
+    fn description() -> &'static str {
+        "show your deposit address and QR code, e.g. /deposit 1.5"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        let chat_id = msg.chat.id;
+
+        info!("Deposit command received from Telegram ID: {}", telegram_id);
+
+        // "/deposit 1.5" requests an amount, plain "/deposit" just shows the address
+        let amount = match msg.text().unwrap_or("").split_whitespace().nth(1) {
+            Some(amount) => match parse_decimal(amount) {
+                Ok(amount) if amount.is_finite() && amount > 0.0 => Some(amount),
+                _ => {
+                    bot.send_message(
+                        chat_id,
+                        "Invalid amount. Use /deposit or /deposit <amount>, e.g. /deposit 1.5",
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            },
+            None => None,
+        };
+
+        let db_pool = services.db_pool();
+        let interactor = Arc::new(WalletInteractorImpl::new(db_pool));
+        let view = Arc::new(TelegramWalletView::new(bot.clone(), chat_id));
+        let presenter = WalletPresenterImpl::new(interactor, view);
+
+        presenter.show_deposit(telegram_id, amount).await?;
+
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ use teloxide::prelude::*;
 use super::{CommandHandler, MyDialogue};
 use crate::di::ServiceContainer;
 use crate::entity::State;
+use crate::i18n;
 use crate::interactor::watchlist_interactor::WatchlistInteractorImpl;
 use crate::presenter::watchlist_presenter::{WatchlistPresenter, WatchlistPresenterImpl};
 use crate::view::watchlist_view::TelegramWatchlistView;
@@ -44,7 +45,13 @@ impl CommandHandler for WatchlistCommand {
             price_service.clone(),
             token_repository,
         ));
-        let view = Arc::new(TelegramWatchlistView::new(bot, chat_id));
+        let language_code = msg
+            .from
+            .as_ref()
+            .and_then(|user| user.language_code.clone());
+        let locale =
+            i18n::user_locale(&services.db_pool(), telegram_id, language_code.as_deref()).await;
+        let view = Arc::new(TelegramWatchlistView::new(bot, chat_id).with_locale(locale));
         let presenter = WatchlistPresenterImpl::new(interactor, view, price_service);
 
         presenter.show_watchlist(telegram_id).await?;
@@ -61,7 +68,7 @@ pub async fn handle_watchlist_token_address(
     services: Arc<ServiceContainer>,
 ) -> Result<()> {
     let chat_id = msg.chat.id;
-    let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
 
     // Reset dialogue state
     dialogue.update(State::Start).await?;
@@ -0,0 +1,38 @@
+use anyhow::Result;
+use log::info;
+use std::sync::Arc;
+use teloxide::prelude::*;
+
+use super::{CommandHandler, MyDialogue};
+use crate::di::ServiceContainer;
+use crate::interactor::account_interactor::AccountInteractorImpl;
+use crate::presenter::account_presenter::{AccountPresenter, AccountPresenterImpl};
+use crate::view::account_view::TelegramAccountView;
+
+pub struct WhoAmICommand;
+
+impl CommandHandler for WhoAmICommand {
+    fn command_name() -> &'static str {
+        "whoami"
+    }
+
+    fn description() -> &'static str {
+        "show your account details to share with support"
+    }
+
+    async fn execute(
+        bot: Bot,
+        msg: Message,
+        telegram_id: i64,
+        _dialogue: Option<MyDialogue>,
+        services: Arc<ServiceContainer>,
+    ) -> Result<()> {
+        info!("Whoami command initiated by user: {}", telegram_id);
+
+        let interactor = Arc::new(AccountInteractorImpl::new(services.db_pool()));
+        let view = Arc::new(TelegramAccountView::new(bot, msg.chat.id));
+        let presenter = AccountPresenterImpl::new(interactor, view);
+
+        presenter.show_account_info(telegram_id).await
+    }
+}
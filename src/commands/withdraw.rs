@@ -1,24 +1,39 @@
 use anyhow::Result;
-use log::info;
+use log::{error, info};
 use std::sync::Arc;
+use teloxide::net::Download;
 use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, PhotoSize};
+use teloxide::utils::html;
 
-use super::{CommandHandler, MyDialogue};
+use super::{ui, CommandHandler, MyDialogue};
 use crate::di::ServiceContainer;
-use crate::entity::State;
-use crate::interactor::withdraw_interactor::{WithdrawInteractor, WithdrawInteractorImpl};
+use crate::entity::{
+    parse_batch_withdrawal, BatchLineError, BatchWithdrawal, Cluster, State, MAX_BATCH_WITHDRAWALS,
+};
+use crate::i18n::{self, Locale};
+use crate::interactor::db;
+use crate::interactor::withdraw_interactor::{
+    RecipientCheck, WithdrawInteractor, WithdrawInteractorImpl,
+};
 use crate::presenter::withdraw_presenter::{WithdrawPresenter, WithdrawPresenterImpl};
-use crate::view::withdraw_view::TelegramWithdrawView;
+use crate::qrcodeutils;
+use crate::solana::jupiter::SOL_MINT;
+use crate::utils::{
+    format_amount, format_token_amount, format_tx_url, format_usd, is_solana_pay_uri,
+    parse_solana_pay, shorten_address,
+};
+use crate::view::withdraw_view::{recipient_warning, token_account_notice, TelegramWithdrawView};
 
 pub struct WithdrawCommand;
 
 impl CommandHandler for WithdrawCommand {
     fn command_name() -> &'static str {
-        "withdraw"
+        "send"
     }
 
     fn description() -> &'static str {
-        "withdraw tokens to another address"
+        "send SOL or tokens to another address"
     }
 
     async fn execute(
@@ -48,7 +63,13 @@ impl CommandHandler for WithdrawCommand {
             solana_client,
             price_service,
         ));
-        let view = Arc::new(TelegramWithdrawView::new(bot, chat_id));
+        let language_code = msg
+            .from
+            .as_ref()
+            .and_then(|user| user.language_code.clone());
+        let locale =
+            i18n::user_locale(&services.db_pool(), telegram_id, language_code.as_deref()).await;
+        let view = Arc::new(TelegramWithdrawView::new(bot, chat_id).with_locale(locale));
         let presenter = WithdrawPresenterImpl::new(interactor, view);
 
         // Start the withdraw flow
@@ -74,78 +95,227 @@ pub async fn receive_recipient_address(
         price_in_usdc,
     } = state
     {
-        if let Some(address_text) = msg.text() {
-            let chat_id = msg.chat.id;
-            let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
-
-            // Create presenter
-            let db_pool = services.db_pool();
-            let solana_client = services.solana_client();
-            let price_service = services.price_service();
-
-            let interactor = Arc::new(WithdrawInteractorImpl::new(
-                db_pool,
-                solana_client,
-                price_service,
-            ));
-            let view = Arc::new(TelegramWithdrawView::new(bot.clone(), chat_id));
-            let presenter = WithdrawPresenterImpl::new(interactor.clone(), view);
-
-            // Check if address is valid
-            if let Ok(is_valid) = interactor.validate_recipient_address(address_text).await {
-                if is_valid {
-                    // Update dialogue state
-                    dialogue
-                        .update(State::AwaitingWithdrawAmount {
-                            token_address: token_address.clone(),
-                            token_symbol: token_symbol.clone(),
-                            recipient: address_text.to_string(),
-                            balance: amount,
-                            price_in_sol,
-                            price_in_usdc,
-                        })
-                        .await?;
+        let chat_id = msg.chat.id;
 
-                    // Prompt for amount
+        // Accept the address typed as text or scanned from a QR code photo
+        let address_text = if let Some(text) = msg.text() {
+            text.to_string()
+        } else if let Some(photos) = msg.photo() {
+            match scan_qr_from_photo(&bot, photos).await {
+                Ok(Some(content)) => {
                     bot.send_message(
                         chat_id,
-                        format!(
-                            "You have <b>{:.6} {}</b> (worth {:.6} SOL / ${:.2}).\n\n\
-                            Enter the amount to withdraw:\n\
-                            • Enter a specific amount (e.g. <code>0.5</code>)\n\
-                            • Enter a percentage (e.g. <code>50%</code>)\n\
-                            • Or type <code>All</code> to withdraw your entire balance",
-                            amount,
-                            token_symbol,
-                            amount * price_in_sol,
-                            amount * price_in_usdc
-                        ),
+                        format!("Scanned QR code: <code>{}</code>", html::escape(&content)),
                     )
                     .parse_mode(teloxide::types::ParseMode::Html)
                     .await?;
-                } else {
+                    content
+                }
+                Ok(None) => {
                     bot.send_message(
                         chat_id,
-                        "Invalid Solana address. Please check the address and try again:",
+                        "No QR code found in this photo. Please send a clearer photo or enter the address as text:",
                     )
                     .await?;
+                    return Ok(());
                 }
-            } else {
-                bot.send_message(chat_id, "Error validating address. Please try again:")
+                Err(e) => {
+                    error!("Failed to download QR code photo: {}", e);
+                    bot.send_message(
+                        chat_id,
+                        "Couldn't read this photo. Please try again or enter the address as text:",
+                    )
                     .await?;
+                    return Ok(());
+                }
             }
         } else {
             bot.send_message(
-                msg.chat.id,
-                "Please enter the recipient's Solana address as text:",
+                chat_id,
+                "Please enter the recipient's Solana address as text or send a photo of its QR code:",
             )
             .await?;
+            return Ok(());
+        };
+
+        // Solana Pay links carry the recipient and may pre-fill the amount and token
+        let (address_text, requested_amount) = if is_solana_pay_uri(&address_text) {
+            match parse_solana_pay(&address_text) {
+                Ok(request) => {
+                    let requested_token = request.spl_token.as_deref().unwrap_or(SOL_MINT);
+                    if requested_token != token_address {
+                        bot.send_message(
+                            chat_id,
+                            format!(
+                                "This payment link asks for a different token (<code>{}</code>), \
+                                not {}.\n\nUse /withdraw to pick that token, or enter a plain address:",
+                                requested_token, token_symbol
+                            ),
+                        )
+                        .parse_mode(teloxide::types::ParseMode::Html)
+                        .await?;
+                        return Ok(());
+                    }
+                    (request.recipient, request.amount)
+                }
+                Err(e) => {
+                    bot.send_message(
+                        chat_id,
+                        format!(
+                            "Invalid Solana Pay link: {}\n\nPlease enter a wallet address or a valid solana: link:",
+                            e
+                        ),
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            }
+        } else {
+            (address_text, None)
+        };
+
+        let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+
+        // Create presenter
+        let db_pool = services.db_pool();
+        let solana_client = services.solana_client();
+        let price_service = services.price_service();
+
+        let interactor = Arc::new(WithdrawInteractorImpl::new(
+            db_pool,
+            solana_client,
+            price_service,
+        ));
+        let language_code = msg
+            .from
+            .as_ref()
+            .and_then(|user| user.language_code.clone());
+        let locale =
+            i18n::user_locale(&services.db_pool(), telegram_id, language_code.as_deref()).await;
+        let view = Arc::new(TelegramWithdrawView::new(bot.clone(), chat_id).with_locale(locale));
+        let presenter = WithdrawPresenterImpl::new(interactor.clone(), view);
+
+        // Check the address is a wallet that can receive the token
+        match interactor
+            .validate_recipient_address(&address_text, &token_address)
+            .await
+        {
+            Ok(RecipientCheck::Valid {
+                creates_token_account,
+                warning,
+            }) => {
+                if let Some(warning) = warning {
+                    bot.send_message(chat_id, recipient_warning(&warning))
+                        .await?;
+                }
+                if creates_token_account {
+                    bot.send_message(chat_id, token_account_notice(&token_symbol))
+                        .await?;
+                }
+
+                // An amount from the payment link skips straight to confirmation
+                if let Some(requested_amount) = requested_amount {
+                    match interactor
+                        .validate_withdraw_amount(&requested_amount.to_string(), amount)
+                        .await
+                    {
+                        Ok(withdraw_amount) => {
+                            return prompt_withdraw_confirmation(
+                                &bot,
+                                chat_id,
+                                telegram_id,
+                                language_code.as_deref(),
+                                &dialogue,
+                                &services,
+                                WithdrawDetails {
+                                    token_address,
+                                    token_symbol,
+                                    recipient: address_text,
+                                    amount: withdraw_amount,
+                                    fee_deducted: None,
+                                    price_in_sol,
+                                    price_in_usdc,
+                                },
+                            )
+                            .await;
+                        }
+                        Err(e) => {
+                            bot.send_message(
+                                chat_id,
+                                format!("The requested amount can't be withdrawn: {}", e),
+                            )
+                            .await?;
+                        }
+                    }
+                }
+
+                // Update dialogue state
+                dialogue
+                    .update(State::AwaitingWithdrawAmount {
+                        token_address: token_address.clone(),
+                        token_symbol: token_symbol.clone(),
+                        recipient: address_text,
+                        balance: amount,
+                        price_in_sol,
+                        price_in_usdc,
+                    })
+                    .await?;
+
+                // Prompt for amount
+                bot.send_message(
+                    chat_id,
+                    format!(
+                        "You have <b>{} {}</b> (worth {} SOL / {}).\n\n\
+                        Enter the amount to withdraw:\n\
+                        • Enter a specific amount (e.g. <code>0.5</code>)\n\
+                        • Enter a percentage (e.g. <code>50%</code>)\n\
+                        • Or type <code>All</code> to withdraw your entire balance",
+                        format_amount(amount, locale),
+                        token_symbol,
+                        format_amount(amount * price_in_sol, locale),
+                        format_usd(amount * price_in_usdc, locale)
+                    ),
+                )
+                .parse_mode(teloxide::types::ParseMode::Html)
+                .await?;
+            }
+            Ok(RecipientCheck::InvalidAddress) => {
+                bot.send_message(
+                    chat_id,
+                    "Invalid Solana address. Please check the address and try again:",
+                )
+                .await?;
+            }
+            Ok(RecipientCheck::Rejected(reason)) => {
+                bot.send_message(
+                    chat_id,
+                    format!("⚠️ {}\n\nPlease enter a wallet address:", reason),
+                )
+                .await?;
+            }
+            Err(_) => {
+                bot.send_message(chat_id, "Error validating address. Please try again:")
+                    .await?;
+            }
         }
     }
 
     Ok(())
 }
 
+// Download the largest version of a photo and decode the QR code in it
+async fn scan_qr_from_photo(bot: &Bot, photos: &[PhotoSize]) -> Result<Option<String>> {
+    let Some(photo) = photos.last() else {
+        return Ok(None);
+    };
+
+    let file = bot.get_file(photo.file.id.clone()).await?;
+    let mut image_data = Vec::new();
+    bot.download_file(&file.path, &mut image_data).await?;
+
+    Ok(qrcodeutils::decode_qr(&image_data))
+}
+
 // Handler for amount state
 pub async fn receive_withdraw_amount(
     bot: Bot,
@@ -165,7 +335,7 @@ pub async fn receive_withdraw_amount(
     {
         if let Some(amount_text) = msg.text() {
             let chat_id = msg.chat.id;
-            let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+            let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
 
             // Create interactor
             let db_pool = services.db_pool();
@@ -178,53 +348,46 @@ pub async fn receive_withdraw_amount(
                 price_service,
             ));
 
+            // "All" SOL has to leave the network fee in the wallet
+            let sends_all_sol =
+                token_address == SOL_MINT && amount_text.trim().eq_ignore_ascii_case("all");
+
             // Validate amount
-            match interactor
-                .validate_withdraw_amount(amount_text, balance)
-                .await
-            {
-                Ok(amount) => {
-                    // Calculate total values
-                    let total_sol = amount * price_in_sol;
-                    let total_usdc = amount * price_in_usdc;
-
-                    // Update dialogue state
-                    dialogue
-                        .update(State::AwaitingWithdrawConfirmation {
-                            token_address: token_address.clone(),
-                            token_symbol: token_symbol.clone(),
-                            recipient: recipient.clone(),
+            let validated = if sends_all_sol {
+                interactor
+                    .max_sol_withdrawal(telegram_id)
+                    .await
+                    .map(|(amount, fee)| (amount, Some(fee)))
+            } else {
+                interactor
+                    .validate_withdraw_amount(amount_text, balance)
+                    .await
+                    .map(|amount| (amount, None))
+            };
+
+            match validated {
+                Ok((amount, fee_deducted)) => {
+                    let language_code = msg
+                        .from
+                        .as_ref()
+                        .and_then(|user| user.language_code.clone());
+                    prompt_withdraw_confirmation(
+                        &bot,
+                        chat_id,
+                        telegram_id,
+                        language_code.as_deref(),
+                        &dialogue,
+                        &services,
+                        WithdrawDetails {
+                            token_address,
+                            token_symbol,
+                            recipient,
                             amount,
+                            fee_deducted,
                             price_in_sol,
-                            total_sol,
-                            total_usdc,
-                        })
-                        .await?;
-
-                    // Format address for display (shortened)
-                    let short_address = if recipient.len() > 12 {
-                        format!(
-                            "{}...{}",
-                            &recipient[..6],
-                            &recipient[recipient.len() - 6..]
-                        )
-                    } else {
-                        recipient.clone()
-                    };
-
-                    // Prompt for confirmation
-                    bot.send_message(
-                        chat_id,
-                        format!(
-                            "<b>Confirm Withdrawal</b>\n\n\
-                            • Amount: <b>{:.6} {}</b>\n\
-                            • Value: <b>{:.6} SOL</b> (${:.2})\n\
-                            • To: <code>{}</code>\n\n\
-                            Proceed with this withdrawal? (yes/no)",
-                            amount, token_symbol, total_sol, total_usdc, short_address
-                        ),
+                            price_in_usdc,
+                        },
                     )
-                    .parse_mode(teloxide::types::ParseMode::Html)
                     .await?;
                 }
                 Err(e) => {
@@ -241,6 +404,96 @@ pub async fn receive_withdraw_amount(
     Ok(())
 }
 
+// Withdrawal with every detail known, ready to be confirmed
+struct WithdrawDetails {
+    token_address: String,
+    token_symbol: String,
+    recipient: String,
+    amount: f64,
+    /// Network fee kept back from an "All" SOL withdrawal
+    fee_deducted: Option<f64>,
+    price_in_sol: f64,
+    price_in_usdc: f64,
+}
+
+// Move the dialogue to confirmation and show the withdrawal summary
+async fn prompt_withdraw_confirmation(
+    bot: &Bot,
+    chat_id: ChatId,
+    telegram_id: i64,
+    language_code: Option<&str>,
+    dialogue: &MyDialogue,
+    services: &Arc<ServiceContainer>,
+    details: WithdrawDetails,
+) -> Result<()> {
+    let WithdrawDetails {
+        token_address,
+        token_symbol,
+        recipient,
+        amount,
+        fee_deducted,
+        price_in_sol,
+        price_in_usdc,
+    } = details;
+
+    // Calculate total values
+    let total_sol = amount * price_in_sol;
+    let total_usdc = amount * price_in_usdc;
+
+    // Update dialogue state
+    dialogue
+        .update(State::AwaitingWithdrawConfirmation {
+            token_address,
+            token_symbol: token_symbol.clone(),
+            recipient: recipient.clone(),
+            amount,
+            price_in_sol,
+            total_sol,
+            total_usdc,
+        })
+        .await?;
+
+    // Format address for display (shortened)
+    let short_address = shorten_address(&recipient);
+
+    // Prompt for confirmation
+    let locale = i18n::user_locale(&services.db_pool(), telegram_id, language_code).await;
+
+    // The exact lamport amount is shown when the whole SOL balance is sent
+    let amount_text = match fee_deducted {
+        Some(_) => format_token_amount(amount, 9),
+        None => format_amount(amount, locale),
+    };
+
+    let mut text = i18n::t_with(
+        "withdraw.confirm",
+        locale,
+        &[
+            ("amount", &amount_text),
+            ("symbol", &token_symbol),
+            ("total_sol", &format_amount(total_sol, locale)),
+            ("total_usdc", &format_usd(total_usdc, locale)),
+            ("recipient", &short_address),
+        ],
+    );
+
+    if let Some(fee) = fee_deducted {
+        text.push_str("\n\n");
+        text.push_str(&i18n::t_with(
+            "withdraw.fee_deducted",
+            locale,
+            &[("fee", &format_token_amount(fee, 9))],
+        ));
+    }
+
+    bot.send_message(chat_id, text)
+        .parse_mode(teloxide::types::ParseMode::Html)
+        .reply_markup(ui::create_confirmation_keyboard())
+        .await?;
+
+    Ok(())
+}
+
 // Handler for confirmation state
 pub async fn receive_withdraw_confirmation(
     bot: Bot,
@@ -255,84 +508,42 @@ pub async fn receive_withdraw_confirmation(
         recipient,
         amount,
         price_in_sol,
-        total_sol,
-        total_usdc,
+        ..
     } = state
     {
         if let Some(text) = msg.text() {
             let confirmation = text.to_lowercase();
             let chat_id = msg.chat.id;
-            let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+            let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
 
             // Reset dialogue state
             dialogue.update(State::Start).await?;
 
-            if confirmation == "yes" || confirmation == "y" {
-                // Show processing message
-                let processing_msg = bot
-                    .send_message(chat_id, "Processing withdrawal... Please wait.")
-                    .await?;
+            let language_code = msg
+                .from
+                .as_ref()
+                .and_then(|user| user.language_code.clone());
+            let locale =
+                i18n::user_locale(&services.db_pool(), telegram_id, language_code.as_deref()).await;
 
-                // Create interactor
-                let db_pool = services.db_pool();
-                let solana_client = services.solana_client();
-                let price_service = services.price_service();
-
-                let interactor = Arc::new(WithdrawInteractorImpl::new(
-                    db_pool,
-                    solana_client,
-                    price_service,
-                ));
-
-                // Execute withdrawal
-                let result = interactor
-                    .execute_withdraw(
-                        telegram_id,
-                        &token_address,
-                        &token_symbol,
-                        &recipient,
+            if confirmation == "yes"
+                || confirmation == "y"
+                || confirmation == i18n::t("withdraw.confirm_yes", locale)
+            {
+                execute_confirmed_withdraw(
+                    bot,
+                    chat_id,
+                    telegram_id,
+                    ConfirmedWithdraw {
+                        token_address: &token_address,
+                        token_symbol: &token_symbol,
+                        recipient: &recipient,
                         amount,
                         price_in_sol,
-                    )
-                    .await?;
-
-                if result.success {
-                    // Success message
-                    let text = format!(
-                        "✅ <b>Withdrawal Successful</b>\n\n\
-                        • Amount: <b>{:.6} {}</b>\n\
-                        • Recipient: <code>{}</code>\n\
-                        • Tx Signature: <code>{}</code>\n\n\
-                        <a href=\"https://explorer.solana.com/tx/{}\">View on Explorer</a>",
-                        amount,
-                        token_symbol,
-                        recipient,
-                        result.signature.as_deref().unwrap_or("unknown"),
-                        result.signature.as_deref().unwrap_or("unknown")
-                    );
-
-                    bot.edit_message_text(chat_id, processing_msg.id, text)
-                        .parse_mode(teloxide::types::ParseMode::Html)
-                        .await?;
-                } else {
-                    // Error message
-                    let text = format!(
-                        "❌ <b>Withdrawal Failed</b>\n\n\
-                        • Amount: <b>{:.6} {}</b>\n\
-                        • Recipient: <code>{}</code>\n\
-                        • Error: <code>{}</code>",
-                        amount,
-                        token_symbol,
-                        recipient,
-                        result
-                            .error_message
-                            .unwrap_or_else(|| "Unknown error".to_string())
-                    );
-
-                    bot.edit_message_text(chat_id, processing_msg.id, text)
-                        .parse_mode(teloxide::types::ParseMode::Html)
-                        .await?;
-                }
+                    },
+                    services,
+                )
+                .await?;
             } else {
                 // Cancelled
                 bot.send_message(chat_id, "Withdrawal cancelled.").await?;
@@ -345,3 +556,398 @@ pub async fn receive_withdraw_confirmation(
 
     Ok(())
 }
+
+/// A withdrawal the user confirmed, `amount` in tokens valued at `price_in_sol`
+pub struct ConfirmedWithdraw<'a> {
+    pub token_address: &'a str,
+    pub token_symbol: &'a str,
+    pub recipient: &'a str,
+    pub amount: f64,
+    pub price_in_sol: f64,
+}
+
+// Execute a confirmed withdrawal, used by both text and button confirmations
+pub async fn execute_confirmed_withdraw(
+    bot: Bot,
+    chat_id: ChatId,
+    telegram_id: i64,
+    withdraw: ConfirmedWithdraw<'_>,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let ConfirmedWithdraw {
+        token_address,
+        token_symbol,
+        recipient,
+        amount,
+        price_in_sol,
+    } = withdraw;
+
+    // A double tap on "Confirm" must not send the same transfer twice
+    let _in_flight = match services.idempotency_guard().begin(format!(
+        "{}:withdraw:{}:{}:{}",
+        telegram_id, token_address, recipient, amount
+    )) {
+        Some(in_flight) => in_flight,
+        None => {
+            bot.send_message(chat_id, "⏳ This withdrawal is already processing.")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // Show processing message
+    let processing_msg = bot
+        .send_message(chat_id, "Processing withdrawal... Please wait.")
+        .await?;
+
+    // Create interactor
+    let db_pool = services.db_pool();
+    let solana_client = services.solana_client();
+    let price_service = services.price_service();
+
+    // The processing message switches to "Submitted" once the transfer is sent
+    let (submitted_tx, submitted_progress) =
+        ui::show_submitted_progress(&bot, chat_id, processing_msg.id);
+
+    let interactor = WithdrawInteractorImpl::new(db_pool, solana_client, price_service)
+        .with_submitted_sender(submitted_tx);
+
+    // Execute withdrawal
+    let result = interactor
+        .execute_withdraw(
+            telegram_id,
+            token_address,
+            token_symbol,
+            recipient,
+            amount,
+            price_in_sol,
+        )
+        .await;
+
+    // Close the progress channel and let the last edit finish before the result is shown
+    drop(interactor);
+    let _ = submitted_progress.await;
+    let result = result?;
+    let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+
+    if result.success {
+        // Success message
+        let signature = result.signature.as_deref().unwrap_or("unknown");
+        let explorer = db::get_user_by_telegram_id(&services.db_pool(), telegram_id)
+            .await
+            .map(|user| user.get_explorer())
+            .unwrap_or_default();
+        let text = format!(
+            "✅ <b>Withdrawal Confirmed</b>\n\n\
+            • Amount: <b>{} {}</b>\n\
+            • Recipient: <code>{}</code>\n\
+            • Tx Signature: <code>{}</code>\n\n\
+            <a href=\"{}\">View on {}</a>",
+            format_amount(amount, locale),
+            token_symbol,
+            recipient,
+            signature,
+            format_tx_url(signature, explorer, Cluster::current()),
+            explorer
+        );
+
+        bot.edit_message_text(chat_id, processing_msg.id, text)
+            .parse_mode(teloxide::types::ParseMode::Html)
+            .await?;
+    } else {
+        // Error message
+        let text = format!(
+            "❌ <b>Withdrawal Failed</b>\n\n\
+            • Amount: <b>{} {}</b>\n\
+            • Recipient: <code>{}</code>\n\
+            • Error: <code>{}</code>",
+            format_amount(amount, locale),
+            token_symbol,
+            recipient,
+            result
+                .error_message
+                .unwrap_or_else(|| "Unknown error".to_string())
+        );
+
+        let mut request = bot
+            .edit_message_text(chat_id, processing_msg.id, text)
+            .parse_mode(teloxide::types::ParseMode::Html);
+        if let Some(keyboard) = result.error.as_ref().and_then(ui::error_keyboard) {
+            request = request.reply_markup(keyboard);
+        }
+        request.await?;
+    }
+
+    Ok(())
+}
+
+// Ask for the batch withdrawal lines
+pub async fn start_batch_withdraw(bot: &Bot, chat_id: ChatId, dialogue: &MyDialogue) -> Result<()> {
+    dialogue.update(State::AwaitingBatchWithdrawInput).await?;
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "<b>Batch Withdrawal</b>\n\n\
+            Send one transfer per line as <code>address amount</code>, SOL is sent by default. \
+            Add the token symbol to send another token:\n\n\
+            <code>7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU 0.5\n\
+            9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM 25 USDC</code>\n\n\
+            Up to {} recipients, use /cancel to stop.",
+            MAX_BATCH_WITHDRAWALS
+        ),
+    )
+    .parse_mode(teloxide::types::ParseMode::Html)
+    .await?;
+
+    Ok(())
+}
+
+// Handler for the pasted batch withdrawal lines
+pub async fn receive_batch_withdraw_input(
+    bot: Bot,
+    msg: Message,
+    dialogue: MyDialogue,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    let chat_id = msg.chat.id;
+    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+
+    let Some(text) = msg.text() else {
+        bot.send_message(chat_id, "Please send the transfers as text, one per line:")
+            .await?;
+        return Ok(());
+    };
+
+    let (lines, errors) = parse_batch_withdrawal(text);
+    if !errors.is_empty() {
+        return display_batch_errors(&bot, chat_id, &errors).await;
+    }
+    if lines.is_empty() {
+        bot.send_message(chat_id, "No transfers found, please send one per line:")
+            .await?;
+        return Ok(());
+    }
+
+    let interactor = WithdrawInteractorImpl::new(
+        services.db_pool(),
+        services.solana_client(),
+        services.price_service(),
+    );
+
+    let (withdrawals, errors) = match interactor.validate_batch(telegram_id, &lines).await {
+        Ok(checked) => checked,
+        Err(e) => {
+            dialogue.update(State::Start).await?;
+            bot.send_message(chat_id, format!("Error: {}", e)).await?;
+            return Ok(());
+        }
+    };
+
+    // The dialogue keeps waiting for a corrected list
+    if !errors.is_empty() {
+        return display_batch_errors(&bot, chat_id, &errors).await;
+    }
+
+    let language_code = msg
+        .from
+        .as_ref()
+        .and_then(|user| user.language_code.clone());
+    let locale =
+        i18n::user_locale(&services.db_pool(), telegram_id, language_code.as_deref()).await;
+    let text = format_batch_summary(&withdrawals, locale);
+
+    dialogue
+        .update(State::AwaitingBatchWithdrawConfirmation { withdrawals })
+        .await?;
+
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![InlineKeyboardButton::callback(
+            "✅ Send, stop at first failure",
+            "batch_withdraw_stop",
+        )],
+        vec![InlineKeyboardButton::callback(
+            "✅ Send, skip failed transfers",
+            "batch_withdraw_continue",
+        )],
+        vec![InlineKeyboardButton::callback("❌ Cancel", "dialog_cancel")],
+    ]);
+
+    bot.send_message(chat_id, text)
+        .parse_mode(teloxide::types::ParseMode::Html)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+async fn display_batch_errors(bot: &Bot, chat_id: ChatId, errors: &[BatchLineError]) -> Result<()> {
+    let lines: Vec<String> = errors
+        .iter()
+        .map(|error| format!("• Line {}: {}", error.line, html::escape(&error.message)))
+        .collect();
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "❌ <b>Nothing has been sent.</b> Please fix these lines and send the whole list again:\n\n{}",
+            lines.join("\n")
+        ),
+    )
+    .parse_mode(teloxide::types::ParseMode::Html)
+    .await?;
+
+    Ok(())
+}
+
+// Every transfer and the totals per token, in the order the tokens first appear
+fn format_batch_summary(withdrawals: &[BatchWithdrawal], locale: Locale) -> String {
+    let transfers: Vec<String> = withdrawals
+        .iter()
+        .enumerate()
+        .map(|(i, withdrawal)| {
+            format!(
+                "{}. <b>{} {}</b> → <code>{}</code>",
+                i + 1,
+                format_amount(withdrawal.amount, locale),
+                withdrawal.token_symbol,
+                shorten_address(&withdrawal.recipient)
+            )
+        })
+        .collect();
+
+    let mut totals: Vec<(&str, f64)> = Vec::new();
+    for withdrawal in withdrawals {
+        match totals
+            .iter_mut()
+            .find(|(symbol, _)| *symbol == withdrawal.token_symbol)
+        {
+            Some((_, total)) => *total += withdrawal.amount,
+            None => totals.push((&withdrawal.token_symbol, withdrawal.amount)),
+        }
+    }
+    let totals: Vec<String> = totals
+        .iter()
+        .map(|(symbol, total)| format!("{} {}", format_amount(*total, locale), symbol))
+        .collect();
+    let total_sol: f64 = withdrawals
+        .iter()
+        .map(|withdrawal| withdrawal.amount * withdrawal.price_in_sol)
+        .sum();
+
+    format!(
+        "<b>Confirm Batch Withdrawal</b>\n\n\
+        {}\n\n\
+        • Total: <b>{}</b> (≈ {} SOL)\n\
+        • Transfers: <b>{}</b>, each pays its own network fee\n\n\
+        Transfers are sent one after another. Choose what happens if one of them fails:",
+        transfers.join("\n"),
+        totals.join(" + "),
+        format_amount(total_sol, locale),
+        withdrawals.len()
+    )
+}
+
+// Execute a confirmed batch withdrawal and report every transfer
+pub async fn execute_batch_withdraw(
+    bot: &Bot,
+    chat_id: ChatId,
+    telegram_id: i64,
+    withdrawals: Vec<BatchWithdrawal>,
+    continue_on_error: bool,
+    services: Arc<ServiceContainer>,
+) -> Result<()> {
+    // A double tap must not send the batch twice
+    let batch_key: Vec<String> = withdrawals
+        .iter()
+        .map(|w| format!("{}:{}:{}", w.token_address, w.recipient, w.amount))
+        .collect();
+    let _in_flight = match services.idempotency_guard().begin(format!(
+        "{}:withdraw_batch:{}",
+        telegram_id,
+        batch_key.join(",")
+    )) {
+        Some(in_flight) => in_flight,
+        None => {
+            bot.send_message(chat_id, "⏳ This batch withdrawal is already processing.")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let processing_msg = bot
+        .send_message(
+            chat_id,
+            format!("Sending {} transfers... Please wait.", withdrawals.len()),
+        )
+        .await?;
+
+    let interactor = WithdrawInteractorImpl::new(
+        services.db_pool(),
+        services.solana_client(),
+        services.price_service(),
+    );
+    let results = interactor
+        .execute_batch(telegram_id, &withdrawals, continue_on_error)
+        .await?;
+
+    let explorer = db::get_user_by_telegram_id(&services.db_pool(), telegram_id)
+        .await
+        .map(|user| user.get_explorer())
+        .unwrap_or_default();
+    let locale = i18n::user_locale(&services.db_pool(), telegram_id, None).await;
+
+    let mut lines = Vec::new();
+    for (i, withdrawal) in withdrawals.iter().enumerate() {
+        let transfer = format!(
+            "{}. {} {} → <code>{}</code>",
+            i + 1,
+            format_amount(withdrawal.amount, locale),
+            withdrawal.token_symbol,
+            shorten_address(&withdrawal.recipient)
+        );
+
+        let line = match results.get(i) {
+            Some(result) if result.success => {
+                let signature = result.signature.as_deref().unwrap_or("unknown");
+                format!(
+                    "✅ {} (<a href=\"{}\">tx</a>)",
+                    transfer,
+                    format_tx_url(signature, explorer, Cluster::current())
+                )
+            }
+            Some(result) => format!(
+                "❌ {}\n    {}",
+                transfer,
+                html::escape(result.error_message.as_deref().unwrap_or("Unknown error"))
+            ),
+            None => format!("⏭ {} (not sent)", transfer),
+        };
+        lines.push(line);
+    }
+
+    let sent = results.iter().filter(|result| result.success).count();
+    let title = if sent == withdrawals.len() {
+        "✅ <b>Batch Withdrawal Completed</b>"
+    } else if sent == 0 {
+        "❌ <b>Batch Withdrawal Failed</b>"
+    } else {
+        "⚠️ <b>Batch Withdrawal Partially Completed</b>"
+    };
+
+    bot.edit_message_text(
+        chat_id,
+        processing_msg.id,
+        format!(
+            "{}\n\nSent {} of {} transfers.\n\n{}",
+            title,
+            sent,
+            withdrawals.len(),
+            lines.join("\n")
+        ),
+    )
+    .parse_mode(teloxide::types::ParseMode::Html)
+    .await?;
+
+    Ok(())
+}
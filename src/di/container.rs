@@ -1,9 +1,16 @@
 use std::sync::Arc;
 
-use solana_client::nonblocking::rpc_client::RpcClient;
 use sqlx::PgPool;
 
+use crate::entity::Cluster;
+use crate::services::{
+    DialogueActivity, IdempotencyGuard, RateLimiter, TokenRiskChecker, TokenSupplyCache,
+    TradeCooldown,
+};
+use crate::solana::client::ResilientRpcClient;
+use crate::solana::jupiter::cached_price_service::CachedPriceService;
 use crate::solana::jupiter::config::Config as JupiterConfig;
+use crate::solana::jupiter::platform_fee::PlatformFee;
 use crate::solana::jupiter::price_service::JupiterPriceService;
 use crate::solana::jupiter::price_service::PriceService;
 use crate::solana::jupiter::quote_service::JupiterQuoteService;
@@ -18,7 +25,7 @@ use crate::solana::jupiter::token_repository::TokenRepository;
 pub struct ServiceContainer {
     // Core services
     db_pool: Arc<PgPool>,
-    solana_client: Arc<RpcClient>,
+    solana_client: Arc<ResilientRpcClient>,
 
     // Jupiter services
     token_repository: Arc<dyn TokenRepository + Send + Sync>,
@@ -32,11 +39,28 @@ pub struct ServiceContainer {
 
     // Configuration
     jupiter_config: JupiterConfig,
+    cluster: Cluster,
+
+    // Last activity per chat, used to expire abandoned dialogues
+    dialogue_activity: Arc<DialogueActivity>,
+
+    // Request buckets per Telegram user
+    rate_limiter: Arc<RateLimiter>,
+
+    // Trades and withdrawals that are currently being executed
+    idempotency_guard: Arc<IdempotencyGuard>,
+
+    // Last manual trade per user and token
+    trade_cooldown: Arc<TradeCooldown>,
+    // Token supplies for market cap and FDV limit orders
+    token_supply: Arc<TokenSupplyCache>,
+    // Scam warnings shown before buying a token
+    token_risk: Arc<TokenRiskChecker>,
 }
 
 impl ServiceContainer {
     /// Create a new service container with essential dependencies
-    pub fn new(db_pool: Arc<PgPool>, solana_client: Arc<RpcClient>) -> Self {
+    pub fn new(db_pool: Arc<PgPool>, solana_client: Arc<ResilientRpcClient>) -> Self {
         let db_pool = db_pool;
         let solana_client = solana_client;
 
@@ -45,27 +69,44 @@ impl ServiceContainer {
 
         // Initialize repositories
         let token_repository =
-            Arc::new(JupiterTokenRepository::new()) as Arc<dyn TokenRepository + Send + Sync>;
-
-        // Initialize services
-        let quote_service = Arc::new(JupiterQuoteService::new(JupiterTokenRepository::new()))
-            as Arc<dyn QuoteService + Send + Sync>;
-
-        // Create a price service
-        let price_service = Arc::new(JupiterPriceService::new(
+            Arc::new(JupiterTokenRepository::new().with_solana_client(solana_client.clone()))
+                as Arc<dyn TokenRepository + Send + Sync>;
+
+        // Initialize services, Jupiter endpoints come from the config
+        let new_quote_service = || {
+            JupiterQuoteService::new(JupiterTokenRepository::new())
+                .with_api_url(&jupiter_config.quote_api_url)
+        };
+        let quote_service = Arc::new(new_quote_service()) as Arc<dyn QuoteService + Send + Sync>;
+
+        // Create a price service, prices are cached briefly to avoid Jupiter rate limits
+        let price_service = Arc::new(CachedPriceService::new(JupiterPriceService::new(
             JupiterTokenRepository::new(),
-            JupiterQuoteService::new(JupiterTokenRepository::new()),
+            new_quote_service(),
             jupiter_config.clone(),
-        )) as Arc<dyn PriceService + Send + Sync>;
+        ))) as Arc<dyn PriceService + Send + Sync>;
 
         // Create a route service
         let route_service = Arc::new(JupiterRouteService::new(jupiter_config.clone()))
             as Arc<dyn RouteService + Send + Sync>;
 
-        // Create swap service with concrete types
-        let swap_service = Arc::new(SwapService::new(
-            JupiterTokenRepository::new(),
-            JupiterQuoteService::new(JupiterTokenRepository::new()),
+        // Create swap service with concrete types, versioned transactions are used
+        // unless JUPITER_LEGACY_TRANSACTIONS is set, buys pay the platform fee if one is set
+        let as_legacy_transaction = std::env::var("JUPITER_LEGACY_TRANSACTIONS")
+            .map(|value| value.trim().eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        let swap_service = Arc::new(
+            SwapService::new(JupiterTokenRepository::new(), new_quote_service())
+                .with_api_url(&jupiter_config.swap_api_url)
+                .with_legacy_transactions(as_legacy_transaction)
+                .with_platform_fee(PlatformFee::from_env()),
+        );
+
+        let token_supply = Arc::new(TokenSupplyCache::new(solana_client.clone()));
+        let token_risk = Arc::new(TokenRiskChecker::new(
+            db_pool.clone(),
+            token_repository.clone(),
+            quote_service.clone(),
         ));
 
         Self {
@@ -77,6 +118,13 @@ impl ServiceContainer {
             route_service,
             swap_service,
             jupiter_config,
+            cluster: Cluster::current(),
+            dialogue_activity: Arc::new(DialogueActivity::new()),
+            rate_limiter: Arc::new(RateLimiter::from_env()),
+            idempotency_guard: Arc::new(IdempotencyGuard::new(IdempotencyGuard::DEFAULT_WINDOW)),
+            trade_cooldown: Arc::new(TradeCooldown::from_env()),
+            token_supply,
+            token_risk,
         }
     }
 
@@ -86,7 +134,8 @@ impl ServiceContainer {
         self.db_pool.clone()
     }
 
-    pub fn solana_client(&self) -> Arc<RpcClient> {
+    /// Client with retries and failover across all configured RPC endpoints
+    pub fn solana_client(&self) -> Arc<ResilientRpcClient> {
         self.solana_client.clone()
     }
 
@@ -115,4 +164,32 @@ impl ServiceContainer {
     pub fn jupiter_config(&self) -> JupiterConfig {
         self.jupiter_config.clone()
     }
+
+    /// Cluster the bot is connected to, from SOLANA_CLUSTER or the RPC URL
+    pub fn cluster(&self) -> Cluster {
+        self.cluster
+    }
+
+    pub fn dialogue_activity(&self) -> Arc<DialogueActivity> {
+        self.dialogue_activity.clone()
+    }
+
+    pub fn rate_limiter(&self) -> Arc<RateLimiter> {
+        self.rate_limiter.clone()
+    }
+    pub fn idempotency_guard(&self) -> Arc<IdempotencyGuard> {
+        self.idempotency_guard.clone()
+    }
+
+    pub fn trade_cooldown(&self) -> Arc<TradeCooldown> {
+        self.trade_cooldown.clone()
+    }
+
+    pub fn token_supply(&self) -> Arc<TokenSupplyCache> {
+        self.token_supply.clone()
+    }
+
+    pub fn token_risk(&self) -> Arc<TokenRiskChecker> {
+        self.token_risk.clone()
+    }
 }
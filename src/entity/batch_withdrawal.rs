@@ -0,0 +1,183 @@
+use crate::utils::{parse_decimal, validate_solana_address};
+
+/// Most recipients accepted in one batch withdrawal
+pub const MAX_BATCH_WITHDRAWALS: usize = 20;
+
+/// One pasted `address amount [token]` line, the token defaults to SOL
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchLine {
+    // 1-based line number in the pasted text, used in error messages
+    pub line: usize,
+    pub recipient: String,
+    pub amount: f64,
+    pub token: Option<String>,
+}
+
+/// A line that can't be withdrawn and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchLineError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl BatchLineError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+/// A validated transfer of the batch, ready to be sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchWithdrawal {
+    pub recipient: String,
+    pub amount: f64,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub price_in_sol: f64,
+}
+
+/// Parse one transfer per line, separated by spaces or commas
+///
+/// Empty lines are skipped. Every other line is either parsed or reported with its
+/// line number, so the user can fix all mistakes at once.
+pub fn parse_batch_withdrawal(text: &str) -> (Vec<BatchLine>, Vec<BatchLineError>) {
+    let mut lines = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let parts: Vec<&str> = raw
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|part| !part.is_empty())
+            .collect();
+
+        match parts.as_slice() {
+            [] => continue,
+            [recipient, amount] | [recipient, amount, _] => {
+                if !validate_solana_address(recipient) {
+                    errors.push(BatchLineError::new(line, "invalid Solana address"));
+                    continue;
+                }
+
+                let amount = match parse_decimal(amount) {
+                    Ok(amount) if amount.is_finite() && amount > 0.0 => amount,
+                    _ => {
+                        errors.push(BatchLineError::new(
+                            line,
+                            format!("invalid amount \"{}\"", amount),
+                        ));
+                        continue;
+                    }
+                };
+
+                lines.push(BatchLine {
+                    line,
+                    recipient: recipient.to_string(),
+                    amount,
+                    token: parts.get(2).map(|token| token.to_string()),
+                });
+            }
+            _ => errors.push(BatchLineError::new(
+                line,
+                "expected \"address amount\" or \"address amount token\"",
+            )),
+        }
+    }
+
+    if lines.len() > MAX_BATCH_WITHDRAWALS {
+        errors.push(BatchLineError::new(
+            lines[MAX_BATCH_WITHDRAWALS].line,
+            format!(
+                "a batch can have at most {} recipients",
+                MAX_BATCH_WITHDRAWALS
+            ),
+        ));
+    }
+
+    (lines, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const BOB: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    #[test]
+    fn parses_one_transfer_per_line() {
+        let text = format!("{} 0.5\n\n{}, 12.5 USDC\n", ALICE, BOB);
+
+        let (lines, errors) = parse_batch_withdrawal(&text);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            lines,
+            vec![
+                BatchLine {
+                    line: 1,
+                    recipient: ALICE.to_string(),
+                    amount: 0.5,
+                    token: None,
+                },
+                BatchLine {
+                    line: 3,
+                    recipient: BOB.to_string(),
+                    amount: 12.5,
+                    token: Some("USDC".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn mixed_batch_reports_every_invalid_line() {
+        let text = format!(
+            "{} 1\nnot-an-address 1\n{} -2\n{}\n{} 3 SOL\n{} 1 SOL extra",
+            ALICE, BOB, ALICE, BOB, ALICE
+        );
+
+        let (lines, errors) = parse_batch_withdrawal(&text);
+
+        assert_eq!(
+            lines.iter().map(|line| line.line).collect::<Vec<_>>(),
+            vec![1, 5]
+        );
+        assert_eq!(
+            errors,
+            vec![
+                BatchLineError::new(2, "invalid Solana address"),
+                BatchLineError::new(3, "invalid amount \"-2\""),
+                BatchLineError::new(4, "expected \"address amount\" or \"address amount token\""),
+                BatchLineError::new(6, "expected \"address amount\" or \"address amount token\""),
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_amounts_are_rejected() {
+        let (lines, errors) = parse_batch_withdrawal(&format!("{} 0", ALICE));
+
+        assert!(lines.is_empty());
+        assert_eq!(errors, vec![BatchLineError::new(1, "invalid amount \"0\"")]);
+    }
+
+    #[test]
+    fn batches_above_the_recipient_limit_are_rejected() {
+        let text = vec![format!("{} 0.1", ALICE); MAX_BATCH_WITHDRAWALS + 1].join("\n");
+
+        let (lines, errors) = parse_batch_withdrawal(&text);
+
+        assert_eq!(lines.len(), MAX_BATCH_WITHDRAWALS + 1);
+        assert_eq!(
+            errors,
+            vec![BatchLineError::new(
+                MAX_BATCH_WITHDRAWALS + 1,
+                "a batch can have at most 20 recipients"
+            )]
+        );
+    }
+}
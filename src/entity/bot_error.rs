@@ -1,3 +1,5 @@
+use crate::entity::TransactionStatus;
+
 #[derive(Debug, thiserror::Error)]
 pub enum BotError {
     #[error("Database error: {0}")]
@@ -15,15 +17,154 @@ pub enum BotError {
     #[error("Wallet not found")]
     WalletNotFound,
 
-    #[error("Insufficient funds")]
-    InsufficientFunds,
+    #[error("Insufficient funds: {required:.6} SOL needed including fees, wallet has {available:.6} SOL")]
+    InsufficientSol { required: f64, available: f64 },
+
+    #[error("Insufficient funds: {required} {symbol} needed, wallet has {available} {symbol}")]
+    InsufficientToken {
+        symbol: String,
+        required: f64,
+        available: f64,
+    },
+
+    #[error("Price moved beyond your {slippage:.1}% slippage tolerance")]
+    SlippageExceeded { slippage: f64 },
+
+    #[error("Solana network is unavailable right now, please try again later ({0})")]
+    RpcUnavailable(String),
 
-    #[error("Invalid address")]
-    InvalidAddress,
+    #[error("Jupiter is unavailable right now, please try again later ({0})")]
+    JupiterUnavailable(String),
+
+    #[error("This token isn't tradable via Jupiter yet, there is no swap route for it")]
+    TokenNotTradable,
+
+    #[error("Invalid Solana address: {0}")]
+    InvalidAddress(String),
 
     #[error("Invalid amount")]
     InvalidAmount,
 
     #[error("Failed to create wallet: {0}")]
     WalletCreationError(String),
+
+    #[error("Transaction expired, not submitted at a stale price")]
+    TransactionExpired,
+
+    #[error("Transaction {signature} {status}")]
+    TransactionNotConfirmed {
+        signature: String,
+        status: TransactionStatus,
+    },
+
+    #[error("Limit order #{order_id} was filled by {signature} but the fill couldn't be recorded")]
+    FillNotRecorded { order_id: i32, signature: String },
+}
+
+impl BotError {
+    /// Message for a failed token lookup, unroutable tokens aren't reported as an error
+    pub fn token_info_message(error: &anyhow::Error) -> String {
+        match error.downcast_ref::<BotError>() {
+            Some(BotError::TokenNotTradable) => BotError::TokenNotTradable.to_string(),
+            _ => format!("Error getting token info: {}", error),
+        }
+    }
+
+    /// Signature and database status to record for a failed send
+    ///
+    /// Transactions that reached the network keep their signature so the user can look
+    /// them up, errors before submitting are recorded without one.
+    pub fn failed_transaction_record(error: &anyhow::Error) -> (Option<String>, &'static str) {
+        match error.downcast_ref::<BotError>() {
+            Some(BotError::TransactionNotConfirmed { signature, status }) => {
+                (Some(signature.clone()), status.db_status())
+            }
+            _ => (None, "FAILED"),
+        }
+    }
+
+    /// Signature of a transaction that was dropped before landing
+    ///
+    /// Failed transactions executed on chain aren't included, resubmitting them would
+    /// only fail again.
+    pub fn dropped_signature(error: &anyhow::Error) -> Option<&str> {
+        match error.downcast_ref::<BotError>() {
+            Some(BotError::TransactionNotConfirmed {
+                signature,
+                status: TransactionStatus::Dropped,
+            }) => Some(signature),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    fn not_confirmed(status: TransactionStatus) -> anyhow::Error {
+        BotError::TransactionNotConfirmed {
+            signature: "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW".to_string(),
+            status,
+        }
+        .into()
+    }
+
+    #[test]
+    fn transactions_that_reached_the_network_keep_their_signature() {
+        let (signature, status) =
+            BotError::failed_transaction_record(&not_confirmed(TransactionStatus::Dropped));
+        assert!(signature.is_some());
+        assert_eq!(status, "DROPPED");
+
+        let (signature, status) = BotError::failed_transaction_record(&not_confirmed(
+            TransactionStatus::Failed("insufficient funds".to_string()),
+        ));
+        assert!(signature.is_some());
+        assert_eq!(status, "FAILED");
+    }
+
+    #[test]
+    fn errors_before_submitting_are_recorded_without_a_signature() {
+        let (signature, status) =
+            BotError::failed_transaction_record(&anyhow!("Failed to get recent blockhash"));
+
+        assert_eq!(signature, None);
+        assert_eq!(status, "FAILED");
+    }
+
+    #[test]
+    fn unconfirmed_error_message_shows_the_outcome() {
+        let message = not_confirmed(TransactionStatus::Dropped).to_string();
+
+        assert!(message.ends_with("was not confirmed in time and was dropped"));
+    }
+
+    #[test]
+    fn typed_errors_read_as_friendly_messages() {
+        let sol = BotError::InsufficientSol {
+            required: 1.00303928,
+            available: 0.5,
+        };
+        assert_eq!(
+            sol.to_string(),
+            "Insufficient funds: 1.003039 SOL needed including fees, wallet has 0.500000 SOL"
+        );
+
+        let token = BotError::InsufficientToken {
+            symbol: "BONK".to_string(),
+            required: 1000.0,
+            available: 10.5,
+        };
+        assert_eq!(
+            token.to_string(),
+            "Insufficient funds: 1000 BONK needed, wallet has 10.5 BONK"
+        );
+
+        assert_eq!(
+            BotError::SlippageExceeded { slippage: 0.5 }.to_string(),
+            "Price moved beyond your 0.5% slippage tolerance"
+        );
+    }
 }
@@ -0,0 +1,151 @@
+use anyhow::anyhow;
+use lazy_static::lazy_static;
+use std::env;
+use std::str::FromStr;
+
+/// Solana cluster the bot is connected to
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Cluster {
+    #[default]
+    Mainnet,
+    Devnet,
+    Testnet,
+}
+
+impl Cluster {
+    /// Guess the cluster from an RPC endpoint, anything unknown is treated as mainnet
+    pub fn from_rpc_url(url: &str) -> Self {
+        let url = url.to_lowercase();
+
+        if url.contains("devnet") {
+            Cluster::Devnet
+        } else if url.contains("testnet") {
+            Cluster::Testnet
+        } else {
+            Cluster::Mainnet
+        }
+    }
+
+    /// SOLANA_CLUSTER if it is set, otherwise inferred from the first endpoint in SOLANA_RPC_URL
+    pub fn from_env() -> Self {
+        if let Ok(value) = env::var("SOLANA_CLUSTER") {
+            if !value.trim().is_empty() {
+                return Cluster::from_str(value.trim()).unwrap_or_else(|e| {
+                    log::warn!("{}, falling back to the RPC URL", e);
+                    Cluster::from_rpc_url(&first_rpc_url())
+                });
+            }
+        }
+
+        Cluster::from_rpc_url(&first_rpc_url())
+    }
+
+    /// Cluster from the environment, resolved once
+    pub fn current() -> Self {
+        lazy_static! {
+            static ref CLUSTER: Cluster = Cluster::from_env();
+        }
+
+        *CLUSTER
+    }
+
+    pub fn is_mainnet(&self) -> bool {
+        *self == Cluster::Mainnet
+    }
+
+    /// Jupiter only routes swaps on mainnet, so swaps, trades, limit and DCA orders
+    /// are unavailable elsewhere
+    pub fn supports_jupiter(&self) -> bool {
+        self.is_mainnet()
+    }
+}
+
+fn first_rpc_url() -> String {
+    env::var("SOLANA_RPC_URL")
+        .unwrap_or_default()
+        .split(',')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_string()
+}
+
+impl std::fmt::Display for Cluster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cluster::Mainnet => write!(f, "Mainnet"),
+            Cluster::Devnet => write!(f, "Devnet"),
+            Cluster::Testnet => write!(f, "Testnet"),
+        }
+    }
+}
+
+impl FromStr for Cluster {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mainnet" | "mainnet-beta" => Ok(Cluster::Mainnet),
+            "devnet" => Ok(Cluster::Devnet),
+            "testnet" => Ok(Cluster::Testnet),
+            _ => Err(anyhow!("Invalid cluster: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cluster_is_inferred_from_common_rpc_urls() {
+        assert_eq!(
+            Cluster::from_rpc_url("https://api.mainnet-beta.solana.com"),
+            Cluster::Mainnet
+        );
+        assert_eq!(
+            Cluster::from_rpc_url("https://api.devnet.solana.com"),
+            Cluster::Devnet
+        );
+        assert_eq!(
+            Cluster::from_rpc_url("https://api.testnet.solana.com"),
+            Cluster::Testnet
+        );
+        assert_eq!(
+            Cluster::from_rpc_url("https://devnet.helius-rpc.com/?api-key=abc"),
+            Cluster::Devnet
+        );
+        assert_eq!(
+            Cluster::from_rpc_url("https://mainnet.helius-rpc.com/?api-key=abc"),
+            Cluster::Mainnet
+        );
+        assert_eq!(
+            Cluster::from_rpc_url("https://API.DEVNET.SOLANA.COM"),
+            Cluster::Devnet
+        );
+    }
+
+    #[test]
+    fn unknown_rpc_urls_are_treated_as_mainnet() {
+        assert_eq!(
+            Cluster::from_rpc_url("http://127.0.0.1:8899"),
+            Cluster::Mainnet
+        );
+        assert_eq!(Cluster::from_rpc_url(""), Cluster::Mainnet);
+    }
+
+    #[test]
+    fn cluster_names_parse() {
+        assert_eq!("mainnet-beta".parse::<Cluster>().unwrap(), Cluster::Mainnet);
+        assert_eq!("Devnet".parse::<Cluster>().unwrap(), Cluster::Devnet);
+        assert_eq!("testnet".parse::<Cluster>().unwrap(), Cluster::Testnet);
+        assert!("localnet".parse::<Cluster>().is_err());
+    }
+
+    #[test]
+    fn jupiter_is_only_available_on_mainnet() {
+        assert!(Cluster::Mainnet.supports_jupiter());
+        assert!(!Cluster::Devnet.supports_jupiter());
+        assert!(!Cluster::Testnet.supports_jupiter());
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Status of the DCA order
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum DcaOrderStatus {
+    Active,
+    Completed,
+    Cancelled,
+}
+
+impl std::fmt::Display for DcaOrderStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DcaOrderStatus::Active => write!(f, "ACTIVE"),
+            DcaOrderStatus::Completed => write!(f, "COMPLETED"),
+            DcaOrderStatus::Cancelled => write!(f, "CANCELLED"),
+        }
+    }
+}
+
+/// Recurring buy of a token for a fixed amount of SOL
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DcaOrder {
+    pub id: i32,
+    pub user_id: i32,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub sol_per_interval: f64,
+    pub interval_seconds: i64,
+    pub next_run_at: DateTime<Utc>,
+    pub remaining_runs: i32,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Schedule of a DCA order after a run
+#[derive(Debug, Clone, PartialEq)]
+pub struct DcaSchedule {
+    pub next_run_at: DateTime<Utc>,
+    pub remaining_runs: i32,
+    pub status: DcaOrderStatus,
+}
+
+impl DcaOrder {
+    pub fn interval(&self) -> Duration {
+        Duration::seconds(self.interval_seconds)
+    }
+
+    /// Whether the next buy is due
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.next_run_at <= now
+    }
+
+    /// Schedule after a run at `now`. Runs missed while the bot was offline are
+    /// not caught up, the next run is always at least one interval away.
+    pub fn advance(&self, now: DateTime<Utc>) -> DcaSchedule {
+        let remaining_runs = (self.remaining_runs - 1).max(0);
+
+        let mut next_run_at = self.next_run_at + self.interval();
+        if next_run_at <= now {
+            next_run_at = now + self.interval();
+        }
+
+        let status = if remaining_runs == 0 {
+            DcaOrderStatus::Completed
+        } else {
+            DcaOrderStatus::Active
+        };
+
+        DcaSchedule {
+            next_run_at,
+            remaining_runs,
+            status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(next_run_at: DateTime<Utc>, remaining_runs: i32) -> DcaOrder {
+        DcaOrder {
+            id: 1,
+            user_id: 1,
+            token_address: String::new(),
+            token_symbol: "TEST".to_string(),
+            sol_per_interval: 0.1,
+            interval_seconds: 3600,
+            next_run_at,
+            remaining_runs,
+            status: DcaOrderStatus::Active.to_string(),
+            created_at: next_run_at,
+            updated_at: next_run_at,
+        }
+    }
+
+    #[test]
+    fn next_run_is_one_interval_after_the_scheduled_one() {
+        let scheduled = Utc::now();
+        let order = order(scheduled, 3);
+
+        // Running a bit late keeps the schedule
+        let schedule = order.advance(scheduled + Duration::minutes(5));
+
+        assert_eq!(schedule.next_run_at, scheduled + Duration::hours(1));
+        assert_eq!(schedule.remaining_runs, 2);
+        assert_eq!(schedule.status, DcaOrderStatus::Active);
+    }
+
+    #[test]
+    fn missed_runs_are_not_caught_up() {
+        let scheduled = Utc::now();
+        let order = order(scheduled, 3);
+        let now = scheduled + Duration::hours(5);
+
+        let schedule = order.advance(now);
+
+        assert_eq!(schedule.next_run_at, now + Duration::hours(1));
+        assert_eq!(schedule.remaining_runs, 2);
+    }
+
+    #[test]
+    fn last_run_completes_the_order() {
+        let scheduled = Utc::now();
+
+        let schedule = order(scheduled, 1).advance(scheduled);
+
+        assert_eq!(schedule.remaining_runs, 0);
+        assert_eq!(schedule.status, DcaOrderStatus::Completed);
+    }
+
+    #[test]
+    fn remaining_runs_never_go_negative() {
+        let scheduled = Utc::now();
+
+        let schedule = order(scheduled, 0).advance(scheduled);
+
+        assert_eq!(schedule.remaining_runs, 0);
+        assert_eq!(schedule.status, DcaOrderStatus::Completed);
+    }
+}
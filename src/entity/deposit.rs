@@ -0,0 +1,114 @@
+use crate::solana::activity::{TokenChange, WalletActivity};
+use std::collections::HashSet;
+
+/// SOL changes below this are dust or rounding, not deposits
+const MIN_SOL_DEPOSIT: f64 = 0.000001;
+
+/// SOL and tokens that arrived in the user's wallet in one transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deposit {
+    pub signature: String,
+    pub sol_amount: f64,
+    pub tokens: Vec<TokenChange>,
+}
+
+impl Deposit {
+    /// Deposit made by the transaction, None for the user's own transactions
+    ///
+    /// Transactions recorded by the bot (withdrawals, trades, swaps) are skipped by signature.
+    /// Anything that took SOL or tokens out of the wallet was signed by the user as well,
+    /// so it is skipped even when the bot didn't record it, e.g. a trade made in another wallet app.
+    pub fn from_activity(
+        activity: &WalletActivity,
+        own_signatures: &HashSet<String>,
+    ) -> Option<Self> {
+        if own_signatures.contains(&activity.signature) {
+            return None;
+        }
+
+        if activity.sol_change < 0.0 || activity.token_changes.iter().any(|c| c.amount < 0.0) {
+            return None;
+        }
+
+        let sol_amount = if activity.sol_change >= MIN_SOL_DEPOSIT {
+            activity.sol_change
+        } else {
+            0.0
+        };
+
+        if sol_amount == 0.0 && activity.token_changes.is_empty() {
+            return None;
+        }
+
+        Some(Deposit {
+            signature: activity.signature.clone(),
+            sol_amount,
+            tokens: activity.token_changes.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    fn activity(sol_change: f64, token_changes: &[(&str, f64)]) -> WalletActivity {
+        WalletActivity {
+            signature: "sig".to_string(),
+            token_changes: token_changes
+                .iter()
+                .map(|(mint, amount)| TokenChange {
+                    mint: mint.to_string(),
+                    amount: *amount,
+                })
+                .collect(),
+            sol_change,
+        }
+    }
+
+    #[test]
+    fn incoming_sol_is_a_deposit() {
+        let deposit = Deposit::from_activity(&activity(1.5, &[]), &HashSet::new()).unwrap();
+
+        assert_eq!(deposit.signature, "sig");
+        assert_eq!(deposit.sol_amount, 1.5);
+        assert!(deposit.tokens.is_empty());
+    }
+
+    #[test]
+    fn incoming_tokens_are_a_deposit_without_sol_dust() {
+        let deposit =
+            Deposit::from_activity(&activity(0.0000001, &[(BONK, 1000.0)]), &HashSet::new())
+                .unwrap();
+
+        assert_eq!(deposit.sol_amount, 0.0);
+        assert_eq!(deposit.tokens.len(), 1);
+        assert_eq!(deposit.tokens[0].amount, 1000.0);
+    }
+
+    #[test]
+    fn outgoing_transactions_are_not_deposits() {
+        let own = HashSet::new();
+
+        // Withdrawal, the wallet paid SOL
+        assert!(Deposit::from_activity(&activity(-0.5, &[]), &own).is_none());
+        // Sale of tokens for SOL
+        assert!(Deposit::from_activity(&activity(0.2, &[(BONK, -1000.0)]), &own).is_none());
+        // Purchase of tokens with SOL
+        assert!(Deposit::from_activity(&activity(-0.2, &[(BONK, 1000.0)]), &own).is_none());
+    }
+
+    #[test]
+    fn transactions_recorded_by_the_bot_are_not_deposits() {
+        let own = HashSet::from(["sig".to_string()]);
+
+        assert!(Deposit::from_activity(&activity(1.5, &[]), &own).is_none());
+    }
+
+    #[test]
+    fn dust_alone_is_not_a_deposit() {
+        assert!(Deposit::from_activity(&activity(0.0000001, &[]), &HashSet::new()).is_none());
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Block explorer used for transaction links
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum Explorer {
+    #[default]
+    SolanaExplorer,
+    Solscan,
+    SolanaFm,
+}
+
+impl Explorer {
+    pub const ALL: [Explorer; 3] = [
+        Explorer::SolanaExplorer,
+        Explorer::Solscan,
+        Explorer::SolanaFm,
+    ];
+
+    /// Value stored in the user settings and used in callback data
+    pub fn code(&self) -> &'static str {
+        match self {
+            Explorer::SolanaExplorer => "explorer",
+            Explorer::Solscan => "solscan",
+            Explorer::SolanaFm => "solanafm",
+        }
+    }
+}
+
+impl std::fmt::Display for Explorer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Explorer::SolanaExplorer => write!(f, "Solana Explorer"),
+            Explorer::Solscan => write!(f, "Solscan"),
+            Explorer::SolanaFm => write!(f, "SolanaFM"),
+        }
+    }
+}
+
+impl FromStr for Explorer {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "explorer" => Ok(Explorer::SolanaExplorer),
+            "solscan" => Ok(Explorer::Solscan),
+            "solanafm" => Ok(Explorer::SolanaFm),
+            _ => Err(anyhow!("Invalid explorer: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_code_parses_back_to_the_explorer() {
+        for explorer in Explorer::ALL {
+            assert_eq!(explorer.code().parse::<Explorer>().unwrap(), explorer);
+        }
+    }
+
+    #[test]
+    fn unknown_explorer_is_rejected() {
+        assert!("etherscan".parse::<Explorer>().is_err());
+        assert_eq!("Solscan".parse::<Explorer>().unwrap(), Explorer::Solscan);
+    }
+}
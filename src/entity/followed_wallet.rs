@@ -0,0 +1,116 @@
+use crate::utils::shorten_address;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Wallet whose trades are forwarded to the follower
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FollowedWallet {
+    pub id: i32,
+    pub user_id: i32,
+    pub wallet_address: String,
+    pub last_signature: Option<String>, // Newest transaction already seen, None until the first check
+    pub created_at: DateTime<Utc>,
+}
+
+/// Transactions that haven't been reported yet and the signature to remember next
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureDiff {
+    // Oldest first, so notifications arrive in the order the trades happened
+    pub new_signatures: Vec<String>,
+    pub last_signature: Option<String>,
+}
+
+impl FollowedWallet {
+    /// Shortened address for messages, e.g. "7xKXt...bgAsU"
+    pub fn short_address(&self) -> String {
+        shorten_address(&self.wallet_address)
+    }
+
+    /// Compare the wallet's recent signatures (newest first) with the last one seen
+    pub fn diff_signatures(&self, recent_signatures: &[String]) -> SignatureDiff {
+        SignatureDiff::between(self.last_signature.as_ref(), recent_signatures)
+    }
+}
+
+impl SignatureDiff {
+    /// Signatures newer than `last_signature` in a window of recent signatures (newest first)
+    ///
+    /// On the first check only the newest signature is recorded, so watching a wallet
+    /// doesn't replay its history. If the last seen signature is no longer in the window,
+    /// every signature in the window is new.
+    pub fn between(last_signature: Option<&String>, recent_signatures: &[String]) -> Self {
+        let newest = recent_signatures.first().cloned();
+
+        let Some(last_signature) = last_signature else {
+            return SignatureDiff {
+                new_signatures: Vec::new(),
+                last_signature: newest,
+            };
+        };
+
+        let mut new_signatures: Vec<String> = recent_signatures
+            .iter()
+            .take_while(|signature| *signature != last_signature)
+            .cloned()
+            .collect();
+        new_signatures.reverse();
+
+        SignatureDiff {
+            new_signatures,
+            last_signature: newest.or_else(|| Some(last_signature.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signatures(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn first_check_records_the_newest_without_reporting_history() {
+        let diff = SignatureDiff::between(None, &signatures(&["c", "b", "a"]));
+
+        assert!(diff.new_signatures.is_empty());
+        assert_eq!(diff.last_signature.as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn signatures_newer_than_the_last_seen_are_reported_oldest_first() {
+        let last = "a".to_string();
+        let diff = SignatureDiff::between(Some(&last), &signatures(&["c", "b", "a"]));
+
+        assert_eq!(diff.new_signatures, signatures(&["b", "c"]));
+        assert_eq!(diff.last_signature.as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn nothing_new_keeps_the_last_seen_signature() {
+        let last = "c".to_string();
+        let diff = SignatureDiff::between(Some(&last), &signatures(&["c", "b", "a"]));
+
+        assert!(diff.new_signatures.is_empty());
+        assert_eq!(diff.last_signature.as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn last_seen_signature_outside_the_window_reports_the_whole_window() {
+        let last = "old".to_string();
+        let diff = SignatureDiff::between(Some(&last), &signatures(&["c", "b", "a"]));
+
+        assert_eq!(diff.new_signatures, signatures(&["a", "b", "c"]));
+        assert_eq!(diff.last_signature.as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn empty_window_keeps_the_last_seen_signature() {
+        let last = "c".to_string();
+        let diff = SignatureDiff::between(Some(&last), &[]);
+
+        assert!(diff.new_signatures.is_empty());
+        assert_eq!(diff.last_signature.as_deref(), Some("c"));
+    }
+}
@@ -0,0 +1,233 @@
+use chrono::{DateTime, Utc};
+
+use super::{Swap, Trade, Transaction};
+
+/// Kind of operation shown in the transaction history
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistoryKind {
+    Withdraw,
+    Swap,
+    Buy,
+    Sell,
+}
+
+impl HistoryKind {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            HistoryKind::Withdraw => "📤",
+            HistoryKind::Swap => "🔄",
+            HistoryKind::Buy => "🟢",
+            HistoryKind::Sell => "🔴",
+        }
+    }
+}
+
+impl std::fmt::Display for HistoryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryKind::Withdraw => write!(f, "Withdraw"),
+            HistoryKind::Swap => write!(f, "Swap"),
+            HistoryKind::Buy => write!(f, "Buy"),
+            HistoryKind::Sell => write!(f, "Sell"),
+        }
+    }
+}
+
+/// Single row of the unified history feed built from transfers, swaps and trades
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub kind: HistoryKind,
+    pub timestamp: DateTime<Utc>,
+    /// Human readable amount with token, e.g. "1.5 SOL" or "1 SOL → 150 USDC"
+    pub amount: String,
+    pub status: String,
+    pub tx_signature: Option<String>,
+}
+
+impl HistoryEntry {
+    /// Rows without a signature never reached the chain and are shown as failed
+    pub fn is_failed(&self) -> bool {
+        self.tx_signature.is_none() || self.status != "SUCCESS"
+    }
+}
+
+impl From<Transaction> for HistoryEntry {
+    fn from(transaction: Transaction) -> Self {
+        Self {
+            kind: HistoryKind::Withdraw,
+            timestamp: transaction.timestamp,
+            amount: format!("{:.6} {}", transaction.amount, transaction.token_symbol),
+            status: transaction.status,
+            tx_signature: transaction.tx_signature,
+        }
+    }
+}
+
+impl From<Swap> for HistoryEntry {
+    fn from(swap: Swap) -> Self {
+        Self {
+            kind: HistoryKind::Swap,
+            timestamp: swap.timestamp,
+            amount: format!(
+                "{:.6} {} → {:.6} {}",
+                swap.amount_in, swap.from_token, swap.amount_out, swap.to_token
+            ),
+            status: swap.status,
+            tx_signature: swap.tx_signature,
+        }
+    }
+}
+
+impl From<Trade> for HistoryEntry {
+    fn from(trade: Trade) -> Self {
+        let kind = if trade.trade_type == "SELL" {
+            HistoryKind::Sell
+        } else {
+            HistoryKind::Buy
+        };
+
+        Self {
+            kind,
+            timestamp: trade.timestamp,
+            amount: format!(
+                "{:.6} {} for {:.6} SOL",
+                trade.amount, trade.token_symbol, trade.total_paid
+            ),
+            status: trade.status,
+            tx_signature: trade.tx_signature,
+        }
+    }
+}
+
+/// Merges the three history sources into one feed, newest first
+pub fn merge_history(
+    transactions: Vec<Transaction>,
+    swaps: Vec<Swap>,
+    trades: Vec<Trade>,
+) -> Vec<HistoryEntry> {
+    let mut entries: Vec<HistoryEntry> = transactions
+        .into_iter()
+        .map(HistoryEntry::from)
+        .chain(swaps.into_iter().map(HistoryEntry::from))
+        .chain(trades.into_iter().map(HistoryEntry::from))
+        .collect();
+
+    // Stable sort keeps the source order for rows with the same timestamp
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.timestamp));
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 5, 1, 12, minute, 0).unwrap()
+    }
+
+    fn transaction(minute: u32, tx_signature: Option<&str>) -> Transaction {
+        Transaction {
+            id: 1,
+            user_id: 1,
+            recipient_address: "recipient".to_string(),
+            amount: 1.5,
+            token_symbol: "SOL".to_string(),
+            tx_signature: tx_signature.map(str::to_string),
+            timestamp: at(minute),
+            status: "SUCCESS".to_string(),
+        }
+    }
+
+    fn swap(minute: u32) -> Swap {
+        Swap {
+            id: 1,
+            user_id: 1,
+            from_token: "SOL".to_string(),
+            to_token: "USDC".to_string(),
+            amount_in: 1.0,
+            amount_out: 150.0,
+            tx_signature: Some("swap".to_string()),
+            timestamp: at(minute),
+            status: "SUCCESS".to_string(),
+        }
+    }
+
+    fn trade(minute: u32, trade_type: &str) -> Trade {
+        Trade {
+            id: 1,
+            user_id: 1,
+            token_address: "mint".to_string(),
+            token_symbol: "BONK".to_string(),
+            amount: 1000.0,
+            price_in_sol: 0.001,
+            price_in_usdc: 0.15,
+            total_paid: 1.0,
+            trade_type: trade_type.to_string(),
+            tx_signature: Some("trade".to_string()),
+            timestamp: at(minute),
+            status: "SUCCESS".to_string(),
+        }
+    }
+
+    #[test]
+    fn sources_are_merged_newest_first() {
+        let entries = merge_history(
+            vec![transaction(10, Some("tx")), transaction(40, Some("tx"))],
+            vec![swap(30), swap(5)],
+            vec![trade(20, "BUY"), trade(50, "SELL")],
+        );
+
+        let kinds: Vec<HistoryKind> = entries.iter().map(|entry| entry.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                HistoryKind::Sell,
+                HistoryKind::Withdraw,
+                HistoryKind::Swap,
+                HistoryKind::Buy,
+                HistoryKind::Withdraw,
+                HistoryKind::Swap,
+            ]
+        );
+        assert!(entries
+            .windows(2)
+            .all(|pair| pair[0].timestamp >= pair[1].timestamp));
+    }
+
+    #[test]
+    fn same_timestamp_keeps_the_source_order() {
+        let entries = merge_history(
+            vec![transaction(10, Some("tx"))],
+            vec![swap(10)],
+            vec![trade(10, "BUY")],
+        );
+
+        let kinds: Vec<HistoryKind> = entries.iter().map(|entry| entry.kind).collect();
+        assert_eq!(
+            kinds,
+            [HistoryKind::Withdraw, HistoryKind::Swap, HistoryKind::Buy]
+        );
+    }
+
+    #[test]
+    fn rows_without_a_signature_are_failed() {
+        let entries = merge_history(vec![transaction(10, None)], Vec::new(), Vec::new());
+
+        assert!(entries[0].is_failed());
+        assert!(!HistoryEntry::from(transaction(10, Some("tx"))).is_failed());
+    }
+
+    #[test]
+    fn entries_describe_their_amounts() {
+        assert_eq!(
+            HistoryEntry::from(swap(0)).amount,
+            "1.000000 SOL → 150.000000 USDC"
+        );
+        assert_eq!(
+            HistoryEntry::from(trade(0, "BUY")).amount,
+            "1000.000000 BONK for 1.000000 SOL"
+        );
+    }
+}
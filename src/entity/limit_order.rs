@@ -8,6 +8,43 @@ use std::str::FromStr;
 pub enum OrderType {
     Buy,
     Sell,
+    /// Sell that triggers when the price drops to or below the stop price
+    StopLoss,
+    /// Sell that triggers when the price rises to or above the target price
+    TakeProfit,
+}
+
+impl OrderType {
+    /// Whether the order sells tokens for SOL when executed
+    pub fn is_sell(&self) -> bool {
+        !matches!(self, OrderType::Buy)
+    }
+
+    /// Whether the order should be executed at the current market price
+    pub fn should_trigger(&self, current_price: f64, trigger_price: f64) -> bool {
+        match self {
+            OrderType::Buy | OrderType::StopLoss => current_price <= trigger_price,
+            OrderType::Sell | OrderType::TakeProfit => current_price >= trigger_price,
+        }
+    }
+
+    /// Comparison used by `should_trigger`, for displaying the trigger
+    pub fn trigger_comparison(&self) -> &'static str {
+        match self {
+            OrderType::Buy | OrderType::StopLoss => "≤",
+            OrderType::Sell | OrderType::TakeProfit => "≥",
+        }
+    }
+
+    /// Human readable name, e.g. "Stop-Loss"
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            OrderType::Buy => "Buy",
+            OrderType::Sell => "Sell",
+            OrderType::StopLoss => "Stop-Loss",
+            OrderType::TakeProfit => "Take-Profit",
+        }
+    }
 }
 
 impl std::fmt::Display for OrderType {
@@ -15,6 +52,8 @@ impl std::fmt::Display for OrderType {
         match self {
             OrderType::Buy => write!(f, "BUY"),
             OrderType::Sell => write!(f, "SELL"),
+            OrderType::StopLoss => write!(f, "STOP_LOSS"),
+            OrderType::TakeProfit => write!(f, "TAKE_PROFIT"),
         }
     }
 }
@@ -26,11 +65,143 @@ impl FromStr for OrderType {
         match s.to_uppercase().as_str() {
             "BUY" => Ok(OrderType::Buy),
             "SELL" => Ok(OrderType::Sell),
+            "STOP_LOSS" => Ok(OrderType::StopLoss),
+            "TAKE_PROFIT" => Ok(OrderType::TakeProfit),
             _ => Err(anyhow!("Invalid order type: {}", s)),
         }
     }
 }
 
+/// What the order's trigger is compared with
+///
+/// Market cap and FDV triggers are in USD and are computed from the token's mint supply.
+/// Jupiter's token metadata has no circulating supply, so both use the on-chain supply.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TriggerMetric {
+    Price,
+    MarketCap,
+    Fdv,
+}
+
+impl TriggerMetric {
+    /// Split a "mc" or "fdv" prefix off the order input, input without one is a price trigger
+    pub fn split_prefix(input: &str) -> (Self, &str) {
+        let input = input.trim();
+        let (first, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+
+        match first.to_lowercase().as_str() {
+            "mc" | "mcap" | "marketcap" => (TriggerMetric::MarketCap, rest.trim()),
+            "fdv" => (TriggerMetric::Fdv, rest.trim()),
+            _ => (TriggerMetric::Price, input),
+        }
+    }
+
+    /// Whether the metric needs the token supply
+    pub fn needs_supply(&self) -> bool {
+        !matches!(self, TriggerMetric::Price)
+    }
+
+    /// Human readable name, e.g. "Market Cap"
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TriggerMetric::Price => "Price",
+            TriggerMetric::MarketCap => "Market Cap",
+            TriggerMetric::Fdv => "FDV",
+        }
+    }
+}
+
+impl std::fmt::Display for TriggerMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TriggerMetric::Price => write!(f, "PRICE"),
+            TriggerMetric::MarketCap => write!(f, "MARKET_CAP"),
+            TriggerMetric::Fdv => write!(f, "FDV"),
+        }
+    }
+}
+
+impl FromStr for TriggerMetric {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "PRICE" => Ok(TriggerMetric::Price),
+            "MARKET_CAP" => Ok(TriggerMetric::MarketCap),
+            "FDV" => Ok(TriggerMetric::Fdv),
+            _ => Err(anyhow!("Invalid trigger metric: {}", s)),
+        }
+    }
+}
+
+/// Currency a price-triggered order's target is set in
+///
+/// USDC orders keep their target in `trigger_value` and are compared with the token's USD
+/// price, so the SOL/USD rate moving doesn't move the trigger.
+///
+/// Also the quote users pick for price displays, see `User::get_default_quote`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub enum PriceCurrency {
+    #[default]
+    Sol,
+    Usdc,
+}
+
+impl PriceCurrency {
+    /// Split a "$" off the price of the order input, e.g. "$0.0015 10" is a USDC price
+    pub fn split_prefix(input: &str) -> (Self, &str) {
+        let input = input.trim();
+
+        match input.strip_prefix('$') {
+            Some(rest) => (PriceCurrency::Usdc, rest.trim_start()),
+            None => (PriceCurrency::Sol, input),
+        }
+    }
+}
+
+impl std::fmt::Display for PriceCurrency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PriceCurrency::Sol => write!(f, "SOL"),
+            PriceCurrency::Usdc => write!(f, "USDC"),
+        }
+    }
+}
+
+impl FromStr for PriceCurrency {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "SOL" => Ok(PriceCurrency::Sol),
+            "USDC" => Ok(PriceCurrency::Usdc),
+            _ => Err(anyhow!("Invalid price currency: {}", s)),
+        }
+    }
+}
+
+/// Market cap (or FDV) in USD of a token at the given price
+pub fn market_cap(price_in_usdc: f64, supply: f64) -> f64 {
+    price_in_usdc * supply
+}
+
+/// Parse a USD valuation such as "2500000", "$2.5M" or "800k"
+pub fn parse_usd_value(input: &str) -> Option<f64> {
+    let input = input.trim().trim_start_matches('$').replace(',', "");
+    let (number, multiplier) = match input.chars().last()?.to_ascii_lowercase() {
+        'k' => (&input[..input.len() - 1], 1e3),
+        'm' => (&input[..input.len() - 1], 1e6),
+        'b' => (&input[..input.len() - 1], 1e9),
+        _ => (input.as_str(), 1.0),
+    };
+
+    number
+        .parse::<f64>()
+        .ok()
+        .map(|value| value * multiplier)
+        .filter(|value| value.is_finite() && *value > 0.0)
+}
+
 /// Status of the limit order
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum LimitOrderStatus {
@@ -38,6 +209,7 @@ pub enum LimitOrderStatus {
     Filled,
     Cancelled,
     Failed,
+    Expired,
 }
 
 impl std::fmt::Display for LimitOrderStatus {
@@ -47,18 +219,47 @@ impl std::fmt::Display for LimitOrderStatus {
             LimitOrderStatus::Filled => write!(f, "FILLED"),
             LimitOrderStatus::Cancelled => write!(f, "CANCELLED"),
             LimitOrderStatus::Failed => write!(f, "FAILED"),
+            LimitOrderStatus::Expired => write!(f, "EXPIRED"),
         }
     }
 }
 
+/// A confirmed limit order as entered by the user, before it is stored
+#[derive(Debug, Clone)]
+pub struct NewLimitOrder {
+    pub order_type: OrderType,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub price_in_sol: f64,
+    pub amount: f64,
+    pub total_sol: f64,
+    pub trigger_metric: TriggerMetric,
+    pub trigger_value: Option<f64>,
+    pub price_currency: PriceCurrency,
+}
+
+/// Progress of a limit order after one of its slices executed
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitOrderFill {
+    pub order_id: i32,
+    /// Token amount filled including the slice
+    pub filled_amount: f64,
+    /// `Filled` once nothing is left, `Active` otherwise
+    pub status: LimitOrderStatus,
+}
+
 /// Limit order entity
+///
+/// A buy order spends its SOL budget: `amount` is `total_sol` converted at the limit price
+/// and fills are tracked in those units, so a buy that triggers below its limit spends
+/// `total_sol` and receives more tokens. Sell orders sell exactly `amount` tokens.
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct LimitOrder {
     pub id: i32,
     pub user_id: i32,
     pub token_address: String,
     pub token_symbol: String,
-    pub order_type: String, // "BUY" or "SELL"
+    pub order_type: String, // "BUY", "SELL", "STOP_LOSS" or "TAKE_PROFIT"
     pub price_in_sol: f64,
     pub amount: f64,    // Token amount
     pub total_sol: f64, // Total SOL volume
@@ -67,7 +268,254 @@ pub struct LimitOrder {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub status: String,
-    pub retry_count: i32, // Track retry attempts
+    pub retry_count: i32,                  // Track retry attempts
+    pub expires_at: Option<DateTime<Utc>>, // None for good-till-cancelled orders
+    // Why the order failed without being retried
+    pub failure_reason: Option<String>,
+    pub filled_amount: f64, // Token amount executed so far
+    // Escalated slippage (percent) for the next attempt after a slippage failure
+    pub retry_slippage: Option<f64>,
+    pub trigger_metric: String,     // "PRICE", "MARKET_CAP" or "FDV"
+    pub trigger_value: Option<f64>, // Target market cap, FDV or USDC price in USD
+    pub price_currency: String,     // "SOL" or "USDC", existing orders are in SOL
+    // A failed order isn't attempted again before this time
+    pub next_retry_at: Option<DateTime<Utc>>,
+}
+
+/// Remaining amounts below this are treated as dust and complete the order
+const FILL_EPSILON: f64 = 1e-9;
+
+impl LimitOrder {
+    /// Idempotency key held while the order is executed or edited, so both can't overlap
+    pub fn execution_key(order_id: i32) -> String {
+        format!("limit_order:{}", order_id)
+    }
+
+    /// Whether the order has passed its expiry time
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Whether a failed attempt is still backing off, the order is skipped until then
+    pub fn is_waiting_for_retry(&self, now: DateTime<Utc>) -> bool {
+        self.next_retry_at
+            .is_some_and(|next_retry_at| next_retry_at > now)
+    }
+
+    /// Whether the order may execute when triggered, expired and backing off orders may not
+    pub fn is_executable(&self, now: DateTime<Utc>) -> bool {
+        !self.is_expired(now) && !self.is_waiting_for_retry(now)
+    }
+
+    /// What the trigger is compared with, unknown values fall back to the price
+    pub fn trigger_metric(&self) -> TriggerMetric {
+        TriggerMetric::from_str(&self.trigger_metric).unwrap_or(TriggerMetric::Price)
+    }
+
+    /// Currency of the price target, unknown values fall back to SOL
+    pub fn price_currency(&self) -> PriceCurrency {
+        PriceCurrency::from_str(&self.price_currency).unwrap_or(PriceCurrency::Sol)
+    }
+
+    /// Whether a price trigger is met, USDC orders follow the token's USD price
+    pub fn is_price_triggered(
+        &self,
+        order_type: &OrderType,
+        price_in_sol: f64,
+        price_in_usdc: f64,
+    ) -> bool {
+        match self.trigger_value {
+            Some(target) if self.price_currency() == PriceCurrency::Usdc => {
+                order_type.should_trigger(price_in_usdc, target)
+            }
+            _ => order_type.should_trigger(price_in_sol, self.price_in_sol),
+        }
+    }
+
+    /// Whether a market cap or FDV trigger is met at the token's USD price and supply
+    pub fn is_valuation_triggered(
+        &self,
+        order_type: &OrderType,
+        price_in_usdc: f64,
+        supply: f64,
+    ) -> bool {
+        self.trigger_value.is_some_and(|target| {
+            order_type.should_trigger(market_cap(price_in_usdc, supply), target)
+        })
+    }
+
+    /// Token amount that still has to be executed
+    pub fn remaining_amount(&self) -> f64 {
+        (self.amount - self.filled_amount).max(0.0)
+    }
+
+    /// Whether some, but not all, of the order has been executed
+    pub fn is_partially_filled(&self) -> bool {
+        self.filled_amount > FILL_EPSILON && !self.is_fully_filled(self.filled_amount)
+    }
+
+    /// Whether the given filled amount consumes the whole order
+    pub fn is_fully_filled(&self, filled_amount: f64) -> bool {
+        self.amount - filled_amount <= self.amount.abs() * FILL_EPSILON + FILL_EPSILON
+    }
+
+    /// The fill to record once `slice_amount` more of the order executed
+    pub fn fill_after(&self, slice_amount: f64) -> LimitOrderFill {
+        let filled_amount = self.filled_amount + slice_amount;
+
+        if self.is_fully_filled(filled_amount) {
+            LimitOrderFill {
+                order_id: self.id,
+                filled_amount: self.amount,
+                status: LimitOrderStatus::Filled,
+            }
+        } else {
+            LimitOrderFill {
+                order_id: self.id,
+                filled_amount,
+                status: LimitOrderStatus::Active,
+            }
+        }
+    }
+
+    /// Token amount to execute in one run, at most `max_slice_sol` worth at the given price
+    ///
+    /// A `max_slice_sol` of zero or less disables splitting, the whole remainder is executed.
+    /// A remainder that would be left below 1% of the order is folded into the slice so the
+    /// order doesn't end with a dust fill.
+    pub fn next_slice(&self, max_slice_sol: f64, price_in_sol: f64) -> f64 {
+        let remaining = self.remaining_amount();
+
+        if max_slice_sol <= 0.0 || price_in_sol <= 0.0 {
+            return remaining;
+        }
+
+        let slice = max_slice_sol / price_in_sol;
+        if remaining - slice <= self.amount * 0.01 {
+            remaining
+        } else {
+            slice
+        }
+    }
+
+    /// SOL volume of a token amount at the order's limit price
+    pub fn slice_total_sol(&self, amount: f64) -> f64 {
+        if self.amount > 0.0 {
+            self.total_sol * amount / self.amount
+        } else {
+            0.0
+        }
+    }
+
+    /// Price to size the next slice with, buys are sliced by their SOL budget so a slice
+    /// never spends more than the slice limit, sells by the tokens' market value
+    pub fn slice_price(&self, market_price: f64) -> f64 {
+        if self.is_buy() {
+            self.slice_total_sol(1.0)
+        } else {
+            market_price
+        }
+    }
+
+    /// Tokens to trade for a slice at the market price
+    ///
+    /// A buy slice spends its share of `total_sol`, so it acquires more tokens below the
+    /// limit price. A sell slice sells the slice amount as is.
+    pub fn tokens_to_trade(&self, slice_amount: f64, market_price: f64) -> f64 {
+        if self.is_buy() && market_price > 0.0 {
+            self.slice_total_sol(slice_amount) / market_price
+        } else {
+            slice_amount
+        }
+    }
+
+    /// Whether the order buys tokens, stop-loss and take-profit orders sell
+    pub fn is_buy(&self) -> bool {
+        OrderType::from_str(&self.order_type).is_ok_and(|order_type| !order_type.is_sell())
+    }
+
+    /// Fill progress for display, e.g. "3.2/10 filled"
+    pub fn fill_progress(&self) -> String {
+        format!(
+            "{}/{} filled",
+            format_amount(self.filled_amount),
+            format_amount(self.amount)
+        )
+    }
+}
+
+// Up to 6 decimals without trailing zeros
+fn format_amount(amount: f64) -> String {
+    let formatted = format!("{:.6}", amount);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// Active orders of one token as a ladder around its current price
+#[derive(Debug, Clone)]
+pub struct OrderBook {
+    pub token_address: String,
+    pub token_symbol: String,
+    // Price the orders were last checked against, None before the first check
+    pub current_price_in_sol: Option<f64>,
+    // Buy orders, highest price first
+    pub buys: Vec<LimitOrder>,
+    // Sell, stop-loss and take-profit orders, lowest price first
+    pub sells: Vec<LimitOrder>,
+}
+
+impl OrderBook {
+    /// Group orders by token, each side sorted from the current price outwards
+    ///
+    /// Books are sorted by token symbol. A token can have orders on one side only.
+    pub fn group(orders: Vec<LimitOrder>) -> Vec<OrderBook> {
+        let mut books: Vec<OrderBook> = Vec::new();
+
+        for order in orders {
+            let index = match books
+                .iter()
+                .position(|book| book.token_address == order.token_address)
+            {
+                Some(index) => index,
+                None => {
+                    books.push(OrderBook {
+                        token_address: order.token_address.clone(),
+                        token_symbol: order.token_symbol.clone(),
+                        current_price_in_sol: None,
+                        buys: Vec::new(),
+                        sells: Vec::new(),
+                    });
+                    books.len() - 1
+                }
+            };
+            let book = &mut books[index];
+
+            if book.current_price_in_sol.is_none() {
+                book.current_price_in_sol = order.current_price_in_sol;
+            }
+
+            let is_sell = OrderType::from_str(&order.order_type)
+                .map(|order_type| order_type.is_sell())
+                .unwrap_or(false);
+            if is_sell {
+                book.sells.push(order);
+            } else {
+                book.buys.push(order);
+            }
+        }
+
+        for book in books.iter_mut() {
+            book.buys
+                .sort_by(|a, b| b.price_in_sol.total_cmp(&a.price_in_sol));
+            book.sells
+                .sort_by(|a, b| a.price_in_sol.total_cmp(&b.price_in_sol));
+        }
+        books.sort_by_key(|book| book.token_symbol.to_lowercase());
+
+        books
+    }
 }
 
 /// State for the limit order dialogue
@@ -93,3 +541,282 @@ pub enum LimitOrderState {
         total_sol: f64,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buy_and_stop_loss_trigger_at_or_below_the_price() {
+        for order_type in [OrderType::Buy, OrderType::StopLoss] {
+            assert!(order_type.should_trigger(0.9, 1.0));
+            assert!(order_type.should_trigger(1.0, 1.0));
+            assert!(!order_type.should_trigger(1.1, 1.0));
+        }
+    }
+
+    #[test]
+    fn sell_and_take_profit_trigger_at_or_above_the_price() {
+        for order_type in [OrderType::Sell, OrderType::TakeProfit] {
+            assert!(order_type.should_trigger(1.1, 1.0));
+            assert!(order_type.should_trigger(1.0, 1.0));
+            assert!(!order_type.should_trigger(0.9, 1.0));
+        }
+    }
+
+    #[test]
+    fn only_buys_are_not_sells() {
+        assert!(!OrderType::Buy.is_sell());
+        assert!(OrderType::Sell.is_sell());
+        assert!(OrderType::StopLoss.is_sell());
+        assert!(OrderType::TakeProfit.is_sell());
+    }
+
+    // 10 tokens for 5 SOL, a limit price of 0.5 SOL
+    fn order(order_type: OrderType) -> LimitOrder {
+        let now = Utc::now();
+        LimitOrder {
+            id: 1,
+            user_id: 1,
+            token_address: "mint".to_string(),
+            token_symbol: "TKN".to_string(),
+            order_type: order_type.to_string(),
+            price_in_sol: 0.5,
+            amount: 10.0,
+            total_sol: 5.0,
+            current_price_in_sol: None,
+            tx_signature: None,
+            created_at: now,
+            updated_at: now,
+            status: "ACTIVE".to_string(),
+            retry_count: 0,
+            expires_at: None,
+            failure_reason: None,
+            filled_amount: 0.0,
+            retry_slippage: None,
+            trigger_metric: "PRICE".to_string(),
+            trigger_value: None,
+            price_currency: "SOL".to_string(),
+            next_retry_at: None,
+        }
+    }
+
+    #[test]
+    fn buy_slices_are_sized_by_the_limit_price() {
+        let order = order(OrderType::Buy);
+
+        assert_eq!(order.slice_price(0.4), 0.5);
+        assert_eq!(order.slice_price(0.6), 0.5);
+        // 1 SOL slices at the limit price are 2 tokens, whatever the market price
+        assert_eq!(order.next_slice(1.0, order.slice_price(0.4)), 2.0);
+    }
+
+    #[test]
+    fn buy_slice_spends_its_sol_share_at_the_market_price() {
+        let order = order(OrderType::Buy);
+
+        // 2 tokens are 1 SOL of the budget, which buys more tokens below the limit price
+        assert!((order.tokens_to_trade(2.0, 0.4) - 2.5).abs() < 1e-9);
+        assert!((order.tokens_to_trade(2.0, 0.5) - 2.0).abs() < 1e-9);
+        assert!((order.tokens_to_trade(2.0, 0.625) - 1.6).abs() < 1e-9);
+        // Without a market price the slice amount is kept
+        assert_eq!(order.tokens_to_trade(2.0, 0.0), 2.0);
+    }
+
+    #[test]
+    fn sell_slices_use_the_market_price_and_sell_the_slice_amount() {
+        for order_type in [OrderType::Sell, OrderType::StopLoss, OrderType::TakeProfit] {
+            let order = order(order_type);
+
+            for market_price in [0.4, 0.6] {
+                assert_eq!(order.slice_price(market_price), market_price);
+                assert_eq!(order.tokens_to_trade(2.0, market_price), 2.0);
+            }
+        }
+    }
+
+    #[test]
+    fn order_past_its_expiry_is_not_executed() {
+        let now = Utc::now();
+        let mut order = order(OrderType::Buy);
+        assert!(order.is_executable(now));
+
+        // Triggered by the price, but expired a second ago
+        order.expires_at = Some(now - chrono::Duration::seconds(1));
+        assert!(OrderType::Buy.should_trigger(0.4, order.price_in_sol));
+        assert!(order.is_expired(now));
+        assert!(!order.is_executable(now));
+
+        // Expiring later still executes
+        order.expires_at = Some(now + chrono::Duration::hours(1));
+        assert!(order.is_executable(now));
+    }
+
+    fn usdc_order(order_type: OrderType, target_usd: f64) -> LimitOrder {
+        LimitOrder {
+            trigger_value: Some(target_usd),
+            price_currency: "USDC".to_string(),
+            ..order(order_type)
+        }
+    }
+
+    #[test]
+    fn usdc_order_triggers_on_the_usd_price() {
+        let buy = usdc_order(OrderType::Buy, 100.0);
+        let sell = usdc_order(OrderType::Sell, 100.0);
+
+        // SOL rallied: the SOL price fell below the 0.5 SOL limit, the USD price didn't move
+        assert!(!buy.is_price_triggered(&OrderType::Buy, 0.4, 120.0));
+        assert!(buy.is_price_triggered(&OrderType::Buy, 0.6, 95.0));
+
+        // SOL crashed: the SOL price rose above the limit, the USD price is still short of it
+        assert!(!sell.is_price_triggered(&OrderType::Sell, 0.6, 90.0));
+        assert!(sell.is_price_triggered(&OrderType::Sell, 0.4, 100.0));
+    }
+
+    #[test]
+    fn sol_order_ignores_the_usd_price() {
+        let buy = order(OrderType::Buy);
+
+        assert!(buy.is_price_triggered(&OrderType::Buy, 0.4, 1_000.0));
+        assert!(!buy.is_price_triggered(&OrderType::Buy, 0.6, 0.01));
+    }
+
+    #[test]
+    fn mixed_currency_orders_are_each_evaluated_in_their_currency() {
+        let orders = [
+            order(OrderType::Buy),                   // 0.5 SOL
+            usdc_order(OrderType::Buy, 80.0),        // $80
+            order(OrderType::TakeProfit),            // 0.5 SOL
+            usdc_order(OrderType::TakeProfit, 85.0), // $85
+        ];
+
+        // 0.45 SOL at $200 per SOL is $90
+        let triggered: Vec<bool> = orders
+            .iter()
+            .map(|order| {
+                let order_type = OrderType::from_str(&order.order_type).unwrap();
+                order.is_price_triggered(&order_type, 0.45, 90.0)
+            })
+            .collect();
+
+        assert_eq!(triggered, [true, false, false, true]);
+    }
+
+    fn market_cap_order(order_type: OrderType, target_usd: f64) -> LimitOrder {
+        LimitOrder {
+            trigger_metric: "MARKET_CAP".to_string(),
+            trigger_value: Some(target_usd),
+            ..order(order_type)
+        }
+    }
+
+    #[test]
+    fn market_cap_is_the_usd_price_times_the_supply() {
+        assert_eq!(market_cap(0.002, 1_000_000_000.0), 2_000_000.0);
+        assert_eq!(market_cap(0.0, 1_000_000_000.0), 0.0);
+    }
+
+    #[test]
+    fn take_profit_triggers_once_the_market_cap_crosses_the_target() {
+        let order = market_cap_order(OrderType::TakeProfit, 2_000_000.0);
+
+        // $1.9M, $2M and $2.1M on a supply of a billion
+        assert!(!order.is_valuation_triggered(&OrderType::TakeProfit, 0.0019, 1e9));
+        assert!(order.is_valuation_triggered(&OrderType::TakeProfit, 0.002, 1e9));
+        assert!(order.is_valuation_triggered(&OrderType::TakeProfit, 0.0021, 1e9));
+    }
+
+    #[test]
+    fn buy_triggers_once_the_market_cap_drops_to_the_target() {
+        let order = market_cap_order(OrderType::Buy, 500_000.0);
+
+        assert!(!order.is_valuation_triggered(&OrderType::Buy, 0.0006, 1e9));
+        assert!(order.is_valuation_triggered(&OrderType::Buy, 0.0005, 1e9));
+    }
+
+    #[test]
+    fn valuation_trigger_needs_a_target() {
+        let order = order(OrderType::TakeProfit);
+
+        assert!(!order.is_valuation_triggered(&OrderType::TakeProfit, 1.0, 1e9));
+    }
+
+    #[test]
+    fn trigger_metric_prefix_is_split_off_the_input() {
+        assert_eq!(
+            TriggerMetric::split_prefix("mc 2.5M 10"),
+            (TriggerMetric::MarketCap, "2.5M 10")
+        );
+        assert_eq!(
+            TriggerMetric::split_prefix("FDV 800k 1"),
+            (TriggerMetric::Fdv, "800k 1")
+        );
+        assert_eq!(
+            TriggerMetric::split_prefix("0.001 10"),
+            (TriggerMetric::Price, "0.001 10")
+        );
+        assert_eq!(order(OrderType::Buy).trigger_metric(), TriggerMetric::Price);
+        assert_eq!(
+            market_cap_order(OrderType::Buy, 1.0).trigger_metric(),
+            TriggerMetric::MarketCap
+        );
+    }
+
+    #[test]
+    fn parses_usd_valuations() {
+        assert_eq!(parse_usd_value("2500000"), Some(2_500_000.0));
+        assert_eq!(parse_usd_value("$2.5M"), Some(2_500_000.0));
+        assert_eq!(parse_usd_value("800k"), Some(800_000.0));
+        assert_eq!(parse_usd_value("1,000,000"), Some(1_000_000.0));
+        assert_eq!(parse_usd_value("1b"), Some(1e9));
+        assert_eq!(parse_usd_value("0"), None);
+        assert_eq!(parse_usd_value("lots"), None);
+        assert_eq!(parse_usd_value(""), None);
+    }
+
+    #[test]
+    fn order_book_groups_orders_by_token_sorted_by_price() {
+        let token =
+            |order: LimitOrder, id: i32, address: &str, symbol: &str, price: f64| LimitOrder {
+                id,
+                token_address: address.to_string(),
+                token_symbol: symbol.to_string(),
+                price_in_sol: price,
+                ..order
+            };
+        let orders = vec![
+            token(order(OrderType::Buy), 1, "wif", "WIF", 0.2),
+            token(order(OrderType::Sell), 2, "bonk", "bonk", 0.9),
+            token(order(OrderType::Buy), 3, "wif", "WIF", 0.4),
+            LimitOrder {
+                current_price_in_sol: Some(0.5),
+                ..token(order(OrderType::TakeProfit), 4, "wif", "WIF", 0.8)
+            },
+            token(order(OrderType::StopLoss), 5, "wif", "WIF", 0.3),
+            LimitOrder {
+                current_price_in_sol: Some(0.6),
+                ..token(order(OrderType::Sell), 6, "wif", "WIF", 0.6)
+            },
+            token(order(OrderType::Buy), 7, "wif", "WIF", 0.3),
+            token(order(OrderType::TakeProfit), 8, "bonk", "bonk", 0.7),
+        ];
+
+        let books = OrderBook::group(orders);
+        let ids = |orders: &[LimitOrder]| orders.iter().map(|order| order.id).collect::<Vec<_>>();
+
+        assert_eq!(books.len(), 2);
+        // Sorted by symbol regardless of case
+        assert_eq!(books[0].token_address, "bonk");
+        assert_eq!(books[1].token_address, "wif");
+
+        assert!(books[0].buys.is_empty());
+        assert_eq!(ids(&books[0].sells), vec![8, 2]);
+        assert_eq!(books[0].current_price_in_sol, None);
+
+        assert_eq!(ids(&books[1].buys), vec![3, 7, 1]);
+        assert_eq!(ids(&books[1].sells), vec![5, 6, 4]);
+        // The first order with a price sets the book's price
+        assert_eq!(books[1].current_price_in_sol, Some(0.5));
+    }
+}
@@ -1,26 +1,72 @@
+mod batch_withdrawal;
 mod bot_error;
+mod cluster;
+mod dca_order;
+mod deposit;
+mod explorer;
+mod followed_wallet;
+mod history;
 mod limit_order;
+mod notification;
+mod portfolio;
+mod position;
+mod price_alert;
+mod priority_fee;
 mod state;
 mod swap;
 mod swap_result;
 mod token;
 mod token_balance;
 mod token_price;
+mod token_risk;
 mod trade;
+mod trade_failure;
+mod trade_limits;
 mod transaction;
 mod user;
+mod user_settings;
 mod watchlist;
 
 // Re-export models from jupiter that should be considered entities
+pub use batch_withdrawal::{
+    parse_batch_withdrawal, BatchLine, BatchLineError, BatchWithdrawal, MAX_BATCH_WITHDRAWALS,
+};
 pub use bot_error::BotError;
-pub use limit_order::{LimitOrder, LimitOrderState, LimitOrderStatus, OrderType};
+pub use cluster::Cluster;
+pub use dca_order::{DcaOrder, DcaOrderStatus, DcaSchedule};
+pub use deposit::Deposit;
+pub use explorer::Explorer;
+pub use followed_wallet::{FollowedWallet, SignatureDiff};
+pub use history::{merge_history, HistoryEntry, HistoryKind};
+pub use limit_order::{
+    market_cap, parse_usd_value, LimitOrder, LimitOrderFill, LimitOrderState, LimitOrderStatus,
+    NewLimitOrder, OrderBook, OrderType, PriceCurrency, TriggerMetric,
+};
+pub use notification::{should_notify, NotificationKind};
+pub use portfolio::{CostBasis, PortfolioPosition, PortfolioSummary};
+pub use position::{PositionsSummary, TokenPosition};
+pub use price_alert::{AlertDirection, PriceAlert};
+pub use priority_fee::PriorityFee;
 pub use state::State;
 pub use swap::Swap;
 pub use swap_result::SwapResult;
 pub use token::Token;
 pub use token_balance::TokenBalance;
 pub use token_price::TokenPrice;
+pub use token_risk::{
+    BlacklistedToken, TokenRisk, TokenRiskFlag, LIQUIDITY_PROBE_SOL, LOW_LIQUIDITY_PRICE_IMPACT_PCT,
+};
 pub use trade::Trade;
-pub use transaction::Transaction;
-pub use user::User;
-pub use watchlist::WatchlistItem;
+pub use trade_failure::TradeFailure;
+pub use trade_limits::{TradeLimitKind, TradeLimits};
+pub use transaction::{Transaction, TransactionStatus};
+pub use user::{
+    User, AUTO_CONFIRM_PRESETS, DEFAULT_LOW_BALANCE_SOL, DEFAULT_QUICK_BUY_AMOUNTS,
+    LOW_BALANCE_PRESETS, MAX_ACCOUNTS_RANGE, MAX_QUICK_BUY_AMOUNTS, MAX_QUICK_BUY_SOL,
+    VALIDITY_SLOTS_RANGE,
+};
+pub use user_settings::{
+    exportable_settings, normalize_quick_buy_amounts, parse_slippage_bps, validate_user_settings,
+    DEFAULT_SLIPPAGE_BPS, SETTINGS_KEYS, SLIPPAGE_BPS_RANGE, SLIPPAGE_RANGE,
+};
+pub use watchlist::{WatchlistItem, WatchlistPage, WatchlistSort};
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use super::User;
+
+/// Background notification a user can turn off in the settings
+///
+/// Failed and expired orders are always reported, the user has to act on them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// Limit orders filled completely or partially
+    OrderFills,
+    /// Failed limit order executions that are retried automatically
+    OrderRetries,
+    /// Triggered price alerts
+    PriceAlerts,
+    /// SOL and tokens arriving in the wallet
+    Deposits,
+    /// The new balance after limit orders were filled
+    BalanceUpdates,
+    /// Buys made by DCA orders
+    DcaBuys,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 6] = [
+        NotificationKind::OrderFills,
+        NotificationKind::OrderRetries,
+        NotificationKind::PriceAlerts,
+        NotificationKind::Deposits,
+        NotificationKind::BalanceUpdates,
+        NotificationKind::DcaBuys,
+    ];
+
+    /// Key in the `notifications` settings object, also used in callbacks
+    pub fn code(&self) -> &'static str {
+        match self {
+            NotificationKind::OrderFills => "order_fills",
+            NotificationKind::OrderRetries => "order_retries",
+            NotificationKind::PriceAlerts => "price_alerts",
+            NotificationKind::Deposits => "deposits",
+            NotificationKind::BalanceUpdates => "balance_updates",
+            NotificationKind::DcaBuys => "dca_buys",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.code() == code)
+    }
+}
+
+impl fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotificationKind::OrderFills => write!(f, "Order Fills"),
+            NotificationKind::OrderRetries => write!(f, "Order Retries"),
+            NotificationKind::PriceAlerts => write!(f, "Price Alerts"),
+            NotificationKind::Deposits => write!(f, "Deposits"),
+            NotificationKind::BalanceUpdates => write!(f, "Balance Updates"),
+            NotificationKind::DcaBuys => write!(f, "DCA Buys"),
+        }
+    }
+}
+
+/// Whether the user wants this kind of notification, every kind is on until turned off
+pub fn should_notify(user: &User, kind: NotificationKind) -> bool {
+    user.settings
+        .as_ref()
+        .and_then(|settings| settings.get("notifications"))
+        .and_then(|notifications| notifications.get(kind.code()))
+        .and_then(|enabled| enabled.as_bool())
+        .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn user_with_settings(settings: serde_json::Value) -> User {
+        User {
+            id: 1,
+            telegram_id: 1001,
+            username: None,
+            solana_address: None,
+            encrypted_private_key: None,
+            mnemonic: None,
+            created_at: Utc::now(),
+            settings: Some(settings),
+            referred_by: None,
+            last_seen_signature: None,
+        }
+    }
+
+    #[test]
+    fn every_kind_is_on_by_default() {
+        let user = user_with_settings(serde_json::json!({}));
+
+        assert!(NotificationKind::ALL
+            .into_iter()
+            .all(|kind| should_notify(&user, kind)));
+    }
+
+    #[test]
+    fn disabled_dca_buys_suppress_only_dca_notifications() {
+        let user = user_with_settings(serde_json::json!({
+            "notifications": { "dca_buys": false }
+        }));
+
+        assert!(!should_notify(&user, NotificationKind::DcaBuys));
+        assert!(should_notify(&user, NotificationKind::OrderFills));
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        for kind in NotificationKind::ALL {
+            assert_eq!(NotificationKind::from_code(kind.code()), Some(kind));
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use super::Trade;
+
+// Amounts below this are treated as dust left over from rounding
+const DUST_AMOUNT: f64 = 1e-9;
+
+/// Average cost basis of a token position built from the trade history
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CostBasis {
+    /// Tokens currently held according to the trade history
+    pub amount: f64,
+    /// SOL paid for the tokens currently held
+    pub total_cost: f64,
+    /// SOL gained or lost on sold tokens
+    pub realized_pnl: f64,
+}
+
+impl CostBasis {
+    /// Builds the cost basis from trades in chronological order. Each sell reduces the
+    /// cost at the average price paid so far.
+    pub fn from_trades<'a>(trades: impl IntoIterator<Item = &'a Trade>) -> Self {
+        let mut basis = CostBasis::default();
+
+        for trade in trades {
+            match trade.trade_type.as_str() {
+                "BUY" => basis.add_buy(trade.amount, trade.total_paid),
+                "SELL" => basis.add_sell(trade.amount, trade.total_paid),
+                _ => {}
+            }
+        }
+
+        basis
+    }
+
+    pub fn add_buy(&mut self, amount: f64, total_paid: f64) {
+        self.amount += amount;
+        self.total_cost += total_paid;
+    }
+
+    pub fn add_sell(&mut self, amount: f64, proceeds: f64) {
+        // Tokens that were not bought through the bot have no known cost, only the share
+        // of the proceeds from tokens that were counts towards the P&L
+        let sold = amount.min(self.amount);
+        if sold <= 0.0 {
+            return;
+        }
+        let cost_of_sold = self.average_cost() * sold;
+        let proceeds_of_sold = proceeds * sold / amount;
+
+        self.realized_pnl += proceeds_of_sold - cost_of_sold;
+        self.total_cost -= cost_of_sold;
+        self.amount -= sold;
+
+        if self.amount < DUST_AMOUNT {
+            self.amount = 0.0;
+            self.total_cost = 0.0;
+        }
+    }
+
+    /// Average price paid per token in SOL
+    pub fn average_cost(&self) -> f64 {
+        if self.amount > 0.0 {
+            self.total_cost / self.amount
+        } else {
+            0.0
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.amount > 0.0
+    }
+}
+
+/// Open position with its current market value
+#[derive(Debug, Clone)]
+pub struct PortfolioPosition {
+    pub token_address: String,
+    pub token_symbol: String,
+    pub cost_basis: CostBasis,
+    pub current_price_in_sol: Option<f64>,
+}
+
+impl PortfolioPosition {
+    pub fn current_value(&self) -> Option<f64> {
+        self.current_price_in_sol
+            .map(|price| price * self.cost_basis.amount)
+    }
+
+    pub fn unrealized_pnl(&self) -> Option<f64> {
+        self.current_value()
+            .map(|value| value - self.cost_basis.total_cost)
+    }
+
+    pub fn unrealized_pnl_percent(&self) -> Option<f64> {
+        match self.unrealized_pnl() {
+            Some(pnl) if self.cost_basis.total_cost > 0.0 => {
+                Some(pnl / self.cost_basis.total_cost * 100.0)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Portfolio totals across all traded tokens
+#[derive(Debug, Clone, Default)]
+pub struct PortfolioSummary {
+    pub positions: Vec<PortfolioPosition>,
+    /// Realized P&L of all tokens, including fully closed positions
+    pub realized_pnl: f64,
+}
+
+impl PortfolioSummary {
+    pub fn total_cost(&self) -> f64 {
+        self.positions
+            .iter()
+            .map(|position| position.cost_basis.total_cost)
+            .sum()
+    }
+
+    /// Value of positions with a known price
+    pub fn total_value(&self) -> f64 {
+        self.positions
+            .iter()
+            .filter_map(|position| position.current_value())
+            .sum()
+    }
+
+    pub fn unrealized_pnl(&self) -> f64 {
+        self.positions
+            .iter()
+            .filter_map(|position| position.unrealized_pnl())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn averages_buys_at_different_prices() {
+        let mut basis = CostBasis::default();
+        basis.add_buy(100.0, 1.0);
+        basis.add_buy(100.0, 3.0);
+
+        assert_close(basis.amount, 200.0);
+        assert_close(basis.average_cost(), 0.02);
+    }
+
+    #[test]
+    fn partial_sell_realizes_pnl_at_the_average_cost() {
+        let mut basis = CostBasis::default();
+        basis.add_buy(100.0, 1.0);
+        basis.add_buy(100.0, 3.0);
+
+        basis.add_sell(50.0, 1.5);
+
+        assert_close(basis.amount, 150.0);
+        assert_close(basis.total_cost, 3.0);
+        assert_close(basis.realized_pnl, 0.5);
+        assert_close(basis.average_cost(), 0.02);
+        assert!(basis.is_open());
+    }
+
+    #[test]
+    fn selling_everything_closes_the_position() {
+        let mut basis = CostBasis::default();
+        basis.add_buy(100.0, 2.0);
+
+        basis.add_sell(40.0, 1.0);
+        basis.add_sell(60.0, 0.9);
+
+        assert_close(basis.amount, 0.0);
+        assert_close(basis.total_cost, 0.0);
+        assert_close(basis.realized_pnl, -0.1);
+        assert!(!basis.is_open());
+    }
+
+    #[test]
+    fn proceeds_of_tokens_bought_elsewhere_are_not_realized() {
+        let mut basis = CostBasis::default();
+        basis.add_buy(100.0, 1.0);
+
+        // Half of the sold tokens came from outside the bot
+        basis.add_sell(200.0, 4.0);
+
+        assert_close(basis.amount, 0.0);
+        assert_close(basis.realized_pnl, 1.0);
+
+        // Nothing is held anymore, a further sell has no cost basis
+        basis.add_sell(10.0, 1.0);
+        assert_close(basis.realized_pnl, 1.0);
+    }
+
+    #[test]
+    fn builds_from_trades_in_order() {
+        let trade = |trade_type: &str, amount: f64, total_paid: f64| Trade {
+            id: 0,
+            user_id: 0,
+            token_address: String::new(),
+            token_symbol: "TEST".to_string(),
+            amount,
+            price_in_sol: total_paid / amount,
+            price_in_usdc: 0.0,
+            total_paid,
+            trade_type: trade_type.to_string(),
+            tx_signature: None,
+            timestamp: chrono::Utc::now(),
+            status: "SUCCESS".to_string(),
+        };
+        let trades = [
+            trade("BUY", 100.0, 1.0),
+            trade("SELL", 50.0, 1.0),
+            trade("BUY", 50.0, 2.0),
+        ];
+
+        let basis = CostBasis::from_trades(&trades);
+
+        assert_close(basis.amount, 100.0);
+        assert_close(basis.total_cost, 2.5);
+        assert_close(basis.realized_pnl, 0.5);
+    }
+}
@@ -0,0 +1,194 @@
+use super::{LimitOrder, OrderType, TokenBalance};
+use std::str::FromStr;
+
+/// A token the user holds, has active limit orders for, or both
+#[derive(Debug, Clone)]
+pub struct TokenPosition {
+    pub token_address: String,
+    pub token_symbol: String,
+    // None for tokens with pending buys only
+    pub balance: Option<TokenBalance>,
+    // Current price, None until fetched or when the price API doesn't know the token
+    pub price_in_sol: Option<f64>,
+    pub price_in_usdc: Option<f64>,
+    // Active orders of the token, buys first, each side by price
+    pub orders: Vec<LimitOrder>,
+}
+
+impl TokenPosition {
+    /// Join the wallet's token balances with the active limit orders by mint
+    ///
+    /// Held tokens keep the order of `balances`, tokens with orders but no balance (pending
+    /// buys) follow sorted by symbol. Prices are left for the caller to fill in.
+    pub fn join(balances: &[TokenBalance], orders: Vec<LimitOrder>) -> Vec<TokenPosition> {
+        let mut positions: Vec<TokenPosition> = balances
+            .iter()
+            .map(|balance| TokenPosition {
+                token_address: balance.mint_address.clone(),
+                token_symbol: balance.symbol.clone(),
+                balance: Some(balance.clone()),
+                price_in_sol: None,
+                price_in_usdc: None,
+                orders: Vec::new(),
+            })
+            .collect();
+        let held = positions.len();
+
+        for order in orders {
+            match positions
+                .iter_mut()
+                .find(|position| position.token_address == order.token_address)
+            {
+                Some(position) => position.orders.push(order),
+                None => positions.push(TokenPosition {
+                    token_address: order.token_address.clone(),
+                    token_symbol: order.token_symbol.clone(),
+                    balance: None,
+                    price_in_sol: None,
+                    price_in_usdc: None,
+                    orders: vec![order],
+                }),
+            }
+        }
+
+        positions[held..].sort_by_key(|position| position.token_symbol.to_lowercase());
+        for position in positions.iter_mut() {
+            position.orders.sort_by(|a, b| {
+                is_sell(a)
+                    .cmp(&is_sell(b))
+                    .then(a.price_in_sol.total_cmp(&b.price_in_sol))
+            });
+        }
+
+        positions
+    }
+
+    /// Tokens held in the wallet, zero for pending buys only
+    pub fn held_amount(&self) -> f64 {
+        self.balance.as_ref().map_or(0.0, |balance| balance.amount)
+    }
+
+    /// Value of the held tokens in SOL, None without a price
+    pub fn value_in_sol(&self) -> Option<f64> {
+        self.price_in_sol.map(|price| price * self.held_amount())
+    }
+
+    /// Value of the held tokens in USD, None without a price
+    pub fn value_in_usdc(&self) -> Option<f64> {
+        self.price_in_usdc.map(|price| price * self.held_amount())
+    }
+}
+
+fn is_sell(order: &LimitOrder) -> bool {
+    OrderType::from_str(&order.order_type)
+        .map(|order_type| order_type.is_sell())
+        .unwrap_or(false)
+}
+
+/// Holdings and pending orders shown by /positions
+#[derive(Debug, Clone, Default)]
+pub struct PositionsSummary {
+    pub sol_balance: f64,
+    pub positions: Vec<TokenPosition>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn balance(mint: &str, symbol: &str, amount: f64) -> TokenBalance {
+        TokenBalance {
+            symbol: symbol.to_string(),
+            amount,
+            mint_address: mint.to_string(),
+            decimals: 6,
+        }
+    }
+
+    fn order(id: i32, mint: &str, symbol: &str, order_type: OrderType, price: f64) -> LimitOrder {
+        let now = Utc::now();
+        LimitOrder {
+            id,
+            user_id: 1,
+            token_address: mint.to_string(),
+            token_symbol: symbol.to_string(),
+            order_type: order_type.to_string(),
+            price_in_sol: price,
+            amount: 10.0,
+            total_sol: price * 10.0,
+            current_price_in_sol: None,
+            tx_signature: None,
+            created_at: now,
+            updated_at: now,
+            status: "ACTIVE".to_string(),
+            retry_count: 0,
+            expires_at: None,
+            failure_reason: None,
+            filled_amount: 0.0,
+            retry_slippage: None,
+            trigger_metric: "PRICE".to_string(),
+            trigger_value: None,
+            price_currency: "SOL".to_string(),
+            next_retry_at: None,
+        }
+    }
+
+    fn ids(position: &TokenPosition) -> Vec<i32> {
+        position.orders.iter().map(|order| order.id).collect()
+    }
+
+    #[test]
+    fn holding_without_orders_is_a_position() {
+        let positions = TokenPosition::join(&[balance("bonk", "BONK", 1000.0)], Vec::new());
+
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].token_address, "bonk");
+        assert_eq!(positions[0].held_amount(), 1000.0);
+        assert!(positions[0].orders.is_empty());
+    }
+
+    #[test]
+    fn orders_without_a_holding_are_pending_buys_sorted_by_symbol() {
+        let positions = TokenPosition::join(
+            &[],
+            vec![
+                order(1, "wif", "WIF", OrderType::Buy, 0.2),
+                order(2, "bonk", "bonk", OrderType::Buy, 0.1),
+            ],
+        );
+
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].token_address, "bonk");
+        assert_eq!(positions[1].token_address, "wif");
+        assert!(positions[0].balance.is_none());
+        assert_eq!(positions[0].held_amount(), 0.0);
+        assert_eq!(ids(&positions[0]), vec![2]);
+    }
+
+    #[test]
+    fn orders_join_the_holding_of_their_token() {
+        let positions = TokenPosition::join(
+            &[balance("wif", "WIF", 50.0), balance("bonk", "BONK", 1000.0)],
+            vec![
+                order(1, "wif", "WIF", OrderType::TakeProfit, 0.9),
+                order(2, "jup", "JUP", OrderType::Buy, 0.3),
+                order(3, "wif", "WIF", OrderType::Buy, 0.4),
+                order(4, "wif", "WIF", OrderType::StopLoss, 0.2),
+                order(5, "wif", "WIF", OrderType::Buy, 0.3),
+            ],
+        );
+
+        let addresses: Vec<&str> = positions
+            .iter()
+            .map(|position| position.token_address.as_str())
+            .collect();
+        // Holdings keep the balance order, pending buys follow
+        assert_eq!(addresses, vec!["wif", "bonk", "jup"]);
+        assert_eq!(positions[0].held_amount(), 50.0);
+        // Buys first, each side by price
+        assert_eq!(ids(&positions[0]), vec![5, 3, 4, 1]);
+        assert!(positions[1].orders.is_empty());
+        assert!(positions[2].balance.is_none());
+    }
+}
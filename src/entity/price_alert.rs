@@ -0,0 +1,147 @@
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Side of the target price the alert waits for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum AlertDirection {
+    Above,
+    Below,
+}
+
+impl AlertDirection {
+    /// Whether the price is on the alert side of the target
+    pub fn is_reached(&self, price: f64, target_price: f64) -> bool {
+        match self {
+            AlertDirection::Above => price >= target_price,
+            AlertDirection::Below => price <= target_price,
+        }
+    }
+}
+
+impl std::fmt::Display for AlertDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertDirection::Above => write!(f, "ABOVE"),
+            AlertDirection::Below => write!(f, "BELOW"),
+        }
+    }
+}
+
+impl FromStr for AlertDirection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "ABOVE" => Ok(AlertDirection::Above),
+            "BELOW" => Ok(AlertDirection::Below),
+            _ => Err(anyhow!("Invalid alert direction: {}", s)),
+        }
+    }
+}
+
+/// Price alert entity
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PriceAlert {
+    pub id: i32,
+    pub user_id: i32,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub direction: String, // "ABOVE" or "BELOW"
+    pub target_price_in_sol: f64,
+    pub one_shot: bool,
+    pub is_active: bool,
+    pub last_price_in_sol: Option<f64>, // Price seen on the previous check
+    pub triggered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PriceAlert {
+    pub fn direction(&self) -> Option<AlertDirection> {
+        AlertDirection::from_str(&self.direction).ok()
+    }
+
+    /// Whether the price crossed the target since the previous check. The alert only
+    /// fires on the crossing itself, not while the price stays past the target.
+    pub fn is_crossed(&self, current_price: f64) -> bool {
+        let Some(direction) = self.direction() else {
+            return false;
+        };
+
+        let was_reached = self
+            .last_price_in_sol
+            .is_some_and(|last_price| direction.is_reached(last_price, self.target_price_in_sol));
+
+        !was_reached && direction.is_reached(current_price, self.target_price_in_sol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(direction: AlertDirection, last_price_in_sol: Option<f64>) -> PriceAlert {
+        PriceAlert {
+            id: 1,
+            user_id: 1,
+            token_address: "mint".to_string(),
+            token_symbol: "TKN".to_string(),
+            direction: direction.to_string(),
+            target_price_in_sol: 1.0,
+            one_shot: false,
+            is_active: true,
+            last_price_in_sol,
+            triggered_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn fires_when_the_price_crosses_above_the_target() {
+        let alert = alert(AlertDirection::Above, Some(0.9));
+
+        assert!(alert.is_crossed(1.0));
+        assert!(alert.is_crossed(1.2));
+        assert!(!alert.is_crossed(0.95));
+    }
+
+    #[test]
+    fn fires_when_the_price_crosses_below_the_target() {
+        let alert = alert(AlertDirection::Below, Some(1.1));
+
+        assert!(alert.is_crossed(1.0));
+        assert!(alert.is_crossed(0.8));
+        assert!(!alert.is_crossed(1.05));
+    }
+
+    #[test]
+    fn does_not_fire_again_while_the_price_stays_past_the_target() {
+        let alert = alert(AlertDirection::Above, Some(1.1));
+
+        assert!(!alert.is_crossed(1.2));
+        assert!(!alert.is_crossed(1.0));
+    }
+
+    #[test]
+    fn fires_again_after_the_price_went_back() {
+        // The previous check saw the price back under the target
+        let alert = alert(AlertDirection::Above, Some(0.99));
+
+        assert!(alert.is_crossed(1.01));
+    }
+
+    #[test]
+    fn first_check_fires_when_the_target_is_already_reached() {
+        assert!(alert(AlertDirection::Above, None).is_crossed(1.5));
+        assert!(!alert(AlertDirection::Above, None).is_crossed(0.5));
+    }
+
+    #[test]
+    fn unknown_direction_never_fires() {
+        let mut alert = alert(AlertDirection::Above, None);
+        alert.direction = "SIDEWAYS".to_string();
+
+        assert!(!alert.is_crossed(100.0));
+    }
+}
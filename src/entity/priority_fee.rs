@@ -0,0 +1,146 @@
+use crate::solana::jupiter::PrioritizationFeeLamports;
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Priority fee preset applied to swap transactions
+///
+/// Lamport values for the fixed presets:
+/// * `None`   - no priority fee, Jupiter defaults are used
+/// * `Low`    - 10,000 lamports (0.00001 SOL)
+/// * `Medium` - 100,000 lamports (0.0001 SOL)
+/// * `High`   - 1,000,000 lamports (0.001 SOL)
+/// * `Auto`   - fee recommended by Jupiter based on current network congestion
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum PriorityFee {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Auto,
+}
+
+impl PriorityFee {
+    pub const ALL: [PriorityFee; 5] = [
+        PriorityFee::None,
+        PriorityFee::Low,
+        PriorityFee::Medium,
+        PriorityFee::High,
+        PriorityFee::Auto,
+    ];
+
+    /// Fixed fee in lamports, if the preset has one
+    pub fn lamports(&self) -> Option<u64> {
+        match self {
+            PriorityFee::Low => Some(10_000),
+            PriorityFee::Medium => Some(100_000),
+            PriorityFee::High => Some(1_000_000),
+            PriorityFee::None | PriorityFee::Auto => None,
+        }
+    }
+
+    /// Value passed to the swap request, `None` leaves the Jupiter default
+    pub fn to_prioritization_fee(&self) -> Option<PrioritizationFeeLamports> {
+        match self {
+            PriorityFee::None => None,
+            PriorityFee::Auto => Some(PrioritizationFeeLamports::Auto),
+            _ => self
+                .lamports()
+                .map(|lamports| PrioritizationFeeLamports::Exact { lamports }),
+        }
+    }
+
+    /// Human readable description for the settings menu
+    pub fn description(&self) -> String {
+        match self.lamports() {
+            Some(lamports) => format!("{} ({} SOL)", self, lamports as f64 / 1_000_000_000.0),
+            None => self.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for PriorityFee {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PriorityFee::None => write!(f, "None"),
+            PriorityFee::Low => write!(f, "Low"),
+            PriorityFee::Medium => write!(f, "Medium"),
+            PriorityFee::High => write!(f, "High"),
+            PriorityFee::Auto => write!(f, "Auto"),
+        }
+    }
+}
+
+impl FromStr for PriorityFee {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(PriorityFee::None),
+            "low" => Ok(PriorityFee::Low),
+            "medium" => Ok(PriorityFee::Medium),
+            "high" => Ok(PriorityFee::High),
+            "auto" => Ok(PriorityFee::Auto),
+            _ => Err(anyhow!("Invalid priority fee: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jupiter_swap_api_client::transaction_config::TransactionConfig;
+
+    // `prioritizationFeeLamports` of the swap request Jupiter receives, the config is
+    // flattened into it
+    fn request_field(preset: PriorityFee) -> serde_json::Value {
+        let config = TransactionConfig {
+            prioritization_fee_lamports: preset.to_prioritization_fee().map(Into::into),
+            ..TransactionConfig::default()
+        };
+
+        serde_json::to_value(config).unwrap()["prioritizationFeeLamports"].clone()
+    }
+
+    #[test]
+    fn fixed_presets_serialize_their_lamports_into_the_swap_request() {
+        assert_eq!(request_field(PriorityFee::Low), serde_json::json!(10_000));
+        assert_eq!(
+            request_field(PriorityFee::Medium),
+            serde_json::json!(100_000)
+        );
+        assert_eq!(
+            request_field(PriorityFee::High),
+            serde_json::json!(1_000_000)
+        );
+    }
+
+    #[test]
+    fn auto_asks_jupiter_for_the_fee() {
+        assert_eq!(request_field(PriorityFee::Auto), serde_json::json!("auto"));
+    }
+
+    #[test]
+    fn none_leaves_the_jupiter_default() {
+        assert!(PriorityFee::None.to_prioritization_fee().is_none());
+        assert!(request_field(PriorityFee::None).is_null());
+    }
+
+    #[test]
+    fn fixed_presets_grow_tenfold() {
+        assert_eq!(PriorityFee::Low.lamports(), Some(10_000));
+        assert_eq!(PriorityFee::Medium.lamports(), Some(100_000));
+        assert_eq!(PriorityFee::High.lamports(), Some(1_000_000));
+        assert_eq!(PriorityFee::High.description(), "High (0.001 SOL)");
+    }
+
+    #[test]
+    fn presets_round_trip_through_their_names() {
+        for preset in PriorityFee::ALL {
+            assert_eq!(PriorityFee::from_str(&preset.to_string()).unwrap(), preset);
+        }
+        assert_eq!(PriorityFee::from_str("HIGH").unwrap(), PriorityFee::High);
+        assert!(PriorityFee::from_str("turbo").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::entity::OrderType;
+use crate::entity::{BatchWithdrawal, OrderType, PriceCurrency, TriggerMetric};
 
 #[derive(Clone, Default, Debug)]
 pub enum State {
@@ -25,6 +25,17 @@ pub enum State {
         current_price_in_sol: f64,
         current_price_in_usdc: f64,
     },
+    AwaitingLimitOrderExpiry {
+        order_type: OrderType,
+        token_address: String,
+        token_symbol: String,
+        price_in_sol: f64,
+        amount: f64,
+        total_sol: f64,
+        trigger_metric: TriggerMetric,
+        trigger_value: Option<f64>,
+        price_currency: PriceCurrency,
+    },
     AwaitingLimitOrderConfirmation {
         order_type: OrderType,
         token_address: String,
@@ -32,8 +43,45 @@ pub enum State {
         price_in_sol: f64,
         amount: f64,
         total_sol: f64,
+        trigger_metric: TriggerMetric,
+        trigger_value: Option<f64>,
+        price_currency: PriceCurrency,
+        expiry: Option<chrono::Duration>,
+    },
+    AwaitingLimitOrderEdit {
+        order_id: i32,
+    },
+    AwaitingDcaTokenAddress,
+    AwaitingDcaAmount {
+        token_address: String,
+        token_symbol: String,
+    },
+    AwaitingDcaInterval {
+        token_address: String,
+        token_symbol: String,
+        sol_per_interval: f64,
+    },
+    AwaitingDcaRuns {
+        token_address: String,
+        token_symbol: String,
+        sol_per_interval: f64,
+        interval_seconds: i64,
+    },
+    AwaitingAlertTokenAddress,
+    AwaitingAlertCondition {
+        token_address: String,
+        token_symbol: String,
+    },
+    AwaitingSwapConfirmation {
+        amount: f64,
+        source_token: String,
+        target_token: String,
     },
     AwaitingSlippageInput,
+    AwaitingQuickBuyAmountsInput,
+    AwaitingMaxAccountsInput,
+    AwaitingValiditySlotsInput,
+    AwaitingSettingsImport,
     AwaitingWatchlistTokenAddress,
     AwaitingWithdrawTokenSelection,
     AwaitingWithdrawRecipientAddress {
@@ -60,6 +108,10 @@ pub enum State {
         total_sol: f64,
         total_usdc: f64,
     },
+    AwaitingBatchWithdrawInput,
+    AwaitingBatchWithdrawConfirmation {
+        withdrawals: Vec<BatchWithdrawal>,
+    },
     AwaitingSellTokenSelection,
     AwaitingSellAmount {
         token_address: String,
@@ -75,9 +127,13 @@ pub enum State {
         price_in_sol: f64,
         total_sol: f64,
         total_usdc: f64,
+        // Price impact in percent while the high impact warning hasn't been confirmed yet
+        high_price_impact: Option<f64>,
     },
     AwaitingBuyTokenSelection,
     AwaitingBuyManualAddress,
+    AwaitingBuyTokenSearch,
+    AwaitingFollowWalletAddress,
     AwaitingBuyAmount {
         token_address: String,
         token_symbol: String,
@@ -91,5 +147,7 @@ pub enum State {
         price_in_sol: f64,
         total_sol: f64,
         total_usdc: f64,
+        // Price impact in percent while the high impact warning hasn't been confirmed yet
+        high_price_impact: Option<f64>,
     },
 }
@@ -7,4 +7,6 @@ pub struct Token {
     pub name: String,     // Full token name
     pub decimals: u8,     // Number of decimal places
     pub logo_uri: String, // Token logo URI (optional)
+    #[serde(default)]
+    pub verified: bool, // Verified in the Jupiter token list
 }
@@ -1,8 +1,38 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::format_token_amount;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenBalance {
     pub symbol: String,
     pub amount: f64,
     pub mint_address: String,
+    pub decimals: u8,
+}
+
+impl TokenBalance {
+    /// Balance formatted with the token's own decimals
+    pub fn display_amount(&self) -> String {
+        format_token_amount(self.amount, self.decimals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance(amount: f64, decimals: u8) -> TokenBalance {
+        TokenBalance {
+            symbol: "TKN".to_string(),
+            amount,
+            mint_address: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+            decimals,
+        }
+    }
+
+    #[test]
+    fn display_amount_keeps_every_decimal_of_the_token() {
+        assert_eq!(balance(0.000000001, 9).display_amount(), "0.000000001");
+        assert_eq!(balance(1500.25, 5).display_amount(), "1500.25");
+    }
 }
@@ -7,4 +7,7 @@ pub struct TokenPrice {
     pub price_in_sol: f64,  // Price in SOL
     pub price_in_usdc: f64, // Price in USDC
     pub timestamp: u64,     // Timestamp of price retrieval
+    // Change of the USD price over the last 24h in percent, None when Jupiter doesn't know it
+    #[serde(default)]
+    pub price_change_24h: Option<f64>,
 }
@@ -0,0 +1,144 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::account::Account;
+use std::fmt;
+
+use crate::solana::tokens::MintInfo;
+
+/// Price impact in percent of the liquidity probe above which a token counts as illiquid
+pub const LOW_LIQUIDITY_PRICE_IMPACT_PCT: f64 = 10.0;
+
+/// SOL amount quoted to probe the liquidity of a token
+pub const LIQUIDITY_PROBE_SOL: f64 = 1.0;
+
+/// Token an admin marked as a scam, buying it needs an extra confirmation
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BlacklistedToken {
+    pub mint_address: String,
+    pub reason: Option<String>,
+    pub added_by: i64, // Telegram ID of the admin
+    pub created_at: DateTime<Utc>,
+}
+
+/// Something that makes a token likely to be a rug pull or honeypot
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenRiskFlag {
+    /// On the admins' blacklist, with the reason they gave
+    Blacklisted(Option<String>),
+    /// The creator can still mint new tokens and dilute holders
+    MintAuthority,
+    /// The creator can freeze token accounts, so bought tokens may never be sellable
+    FreezeAuthority,
+    /// Buying `LIQUIDITY_PROBE_SOL` moves the price by `price_impact` percent
+    LowLiquidity { price_impact: f64 },
+}
+
+impl fmt::Display for TokenRiskFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenRiskFlag::Blacklisted(Some(reason)) => {
+                write!(f, "Blacklisted as a scam token: {}", reason)
+            }
+            TokenRiskFlag::Blacklisted(None) => write!(f, "Blacklisted as a scam token"),
+            TokenRiskFlag::MintAuthority => {
+                write!(f, "Mint authority is active, more tokens can be minted")
+            }
+            TokenRiskFlag::FreezeAuthority => {
+                write!(f, "Freeze authority is set, your tokens can be frozen")
+            }
+            TokenRiskFlag::LowLiquidity { price_impact } => write!(
+                f,
+                "Very low liquidity, buying {} SOL moves the price by {:.1}%",
+                LIQUIDITY_PROBE_SOL, price_impact
+            ),
+        }
+    }
+}
+
+/// Risk flags of a token, empty when nothing suspicious was found
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenRisk {
+    pub flags: Vec<TokenRiskFlag>,
+}
+
+impl TokenRisk {
+    /// Flags from the authorities of a mint account of the SPL token or Token-2022 program
+    ///
+    /// Token-2022 mints start with the same layout, extensions after it are ignored.
+    pub fn from_mint_account(account: &Account) -> Result<Self> {
+        Ok(Self::from_mint_info(&MintInfo::from_account(account)?))
+    }
+
+    /// Flags from the authorities of an already parsed mint
+    pub fn from_mint_info(mint: &MintInfo) -> Self {
+        let mut flags = Vec::new();
+        if mint.is_inflatable() {
+            flags.push(TokenRiskFlag::MintAuthority);
+        }
+        if mint.is_freezable() {
+            flags.push(TokenRiskFlag::FreezeAuthority);
+        }
+
+        Self { flags }
+    }
+
+    pub fn is_risky(&self) -> bool {
+        !self.flags.is_empty()
+    }
+
+    pub fn add(&mut self, flag: TokenRiskFlag) {
+        self.flags.push(flag);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUTHORITY: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    fn risk(mint_authority: bool, freeze_authority: bool) -> TokenRisk {
+        let authority = |set: bool| set.then(|| AUTHORITY.to_string());
+
+        TokenRisk::from_mint_info(&MintInfo {
+            decimals: 6,
+            supply: 1_000_000,
+            mint_authority: authority(mint_authority),
+            freeze_authority: authority(freeze_authority),
+            is_token_2022: false,
+        })
+    }
+
+    #[test]
+    fn mint_without_authorities_is_not_risky() {
+        let risk = risk(false, false);
+
+        assert!(risk.flags.is_empty());
+        assert!(!risk.is_risky());
+    }
+
+    #[test]
+    fn mint_authority_is_flagged() {
+        assert_eq!(risk(true, false).flags, vec![TokenRiskFlag::MintAuthority]);
+    }
+
+    #[test]
+    fn freeze_authority_is_flagged() {
+        assert_eq!(
+            risk(false, true).flags,
+            vec![TokenRiskFlag::FreezeAuthority]
+        );
+    }
+
+    #[test]
+    fn both_authorities_are_flagged() {
+        let risk = risk(true, true);
+
+        assert_eq!(
+            risk.flags,
+            vec![TokenRiskFlag::MintAuthority, TokenRiskFlag::FreezeAuthority]
+        );
+        assert!(risk.is_risky());
+    }
+}
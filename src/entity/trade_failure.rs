@@ -0,0 +1,104 @@
+/// Why a trade failed, decides whether and how a limit order is retried
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TradeFailure {
+    /// The price moved past the slippage tolerance, worth retrying with a higher one
+    Slippage,
+    /// The transaction expired before it landed, worth retrying as is
+    BlockhashExpired,
+    /// Not enough SOL or tokens, retrying won't help
+    InsufficientFunds,
+    /// Anything else, retried as is
+    Other,
+}
+
+// Error fragments from Jupiter, the RPC node and the token program, matched lowercase
+const INSUFFICIENT_FUNDS_MARKERS: [&str; 3] = [
+    "insufficient funds",
+    "insufficient lamports",
+    "insufficient balance",
+];
+// 0x1771 is Jupiter's SlippageToleranceExceeded program error
+const SLIPPAGE_MARKERS: [&str; 3] = ["slippage", "0x1771", "price moved"];
+const BLOCKHASH_MARKERS: [&str; 3] = [
+    "blockhash not found",
+    "block height exceeded",
+    "transaction expired",
+];
+
+impl TradeFailure {
+    /// Classify the error message of a failed trade
+    pub fn classify(error_message: &str) -> Self {
+        let message = error_message.to_lowercase();
+        let matches = |markers: &[&str]| markers.iter().any(|marker| message.contains(marker));
+
+        if matches(&INSUFFICIENT_FUNDS_MARKERS) {
+            TradeFailure::InsufficientFunds
+        } else if matches(&SLIPPAGE_MARKERS) {
+            TradeFailure::Slippage
+        } else if matches(&BLOCKHASH_MARKERS) {
+            TradeFailure::BlockhashExpired
+        } else {
+            TradeFailure::Other
+        }
+    }
+
+    pub fn is_retriable(&self) -> bool {
+        *self != TradeFailure::InsufficientFunds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slippage_errors_are_retried_with_more_slippage() {
+        for message in [
+            "Swap failed: Slippage tolerance exceeded",
+            "Transaction simulation failed: custom program error: 0x1771",
+            "Price moved beyond the quote",
+        ] {
+            let failure = TradeFailure::classify(message);
+            assert_eq!(failure, TradeFailure::Slippage, "{}", message);
+            assert!(failure.is_retriable());
+        }
+    }
+
+    #[test]
+    fn expired_blockhash_is_retried() {
+        for message in [
+            "RPC error: Blockhash not found",
+            "Transaction expired: block height exceeded",
+        ] {
+            let failure = TradeFailure::classify(message);
+            assert_eq!(failure, TradeFailure::BlockhashExpired, "{}", message);
+            assert!(failure.is_retriable());
+        }
+    }
+
+    #[test]
+    fn insufficient_funds_is_not_retried() {
+        for message in [
+            "Insufficient funds for fee",
+            "Transfer: insufficient lamports 1000, need 5000",
+            "Error processing Instruction 2: insufficient balance",
+        ] {
+            let failure = TradeFailure::classify(message);
+            assert_eq!(failure, TradeFailure::InsufficientFunds, "{}", message);
+            assert!(!failure.is_retriable());
+        }
+    }
+
+    #[test]
+    fn insufficient_funds_wins_over_slippage() {
+        let failure = TradeFailure::classify("Insufficient funds, slippage check skipped");
+        assert_eq!(failure, TradeFailure::InsufficientFunds);
+    }
+
+    #[test]
+    fn unknown_errors_are_retried_as_is() {
+        let failure = TradeFailure::classify("connection reset by peer");
+        assert_eq!(failure, TradeFailure::Other);
+        assert!(failure.is_retriable());
+    }
+}
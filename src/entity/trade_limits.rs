@@ -0,0 +1,180 @@
+use super::Trade;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// Spending limits for buys that protect against fat-finger trades
+///
+/// Both limits are in SOL, `None` means the limit is off.
+/// The daily limit is a rolling 24 hour window over successful buys.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub struct TradeLimits {
+    pub max_trade_sol: Option<f64>,
+    pub daily_limit_sol: Option<f64>,
+}
+
+/// One of the two spending limits, used by the settings menu
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TradeLimitKind {
+    MaxTrade,
+    Daily,
+}
+
+impl TradeLimitKind {
+    /// Values offered in the settings menu, in SOL
+    pub fn presets(&self) -> [f64; 4] {
+        match self {
+            TradeLimitKind::MaxTrade => [0.5, 1.0, 5.0, 10.0],
+            TradeLimitKind::Daily => [1.0, 5.0, 10.0, 50.0],
+        }
+    }
+
+    /// Prefix of the preset callbacks, followed by the value or "off"
+    pub fn callback_prefix(&self) -> &'static str {
+        match self {
+            TradeLimitKind::MaxTrade => "max_trade_",
+            TradeLimitKind::Daily => "daily_limit_",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            TradeLimitKind::MaxTrade => "Max per Trade",
+            TradeLimitKind::Daily => "Daily Limit",
+        }
+    }
+}
+
+impl TradeLimits {
+    pub fn get(&self, kind: TradeLimitKind) -> Option<f64> {
+        match kind {
+            TradeLimitKind::MaxTrade => self.max_trade_sol,
+            TradeLimitKind::Daily => self.daily_limit_sol,
+        }
+    }
+
+    pub fn with(mut self, kind: TradeLimitKind, limit: Option<f64>) -> Self {
+        match kind {
+            TradeLimitKind::MaxTrade => self.max_trade_sol = limit,
+            TradeLimitKind::Daily => self.daily_limit_sol = limit,
+        }
+        self
+    }
+
+    /// Check a buy of `total_sol` against the limits, `spent_sol` is what was bought in the last 24h
+    pub fn check(&self, total_sol: f64, spent_sol: f64) -> Result<()> {
+        if let Some(max_trade_sol) = self.max_trade_sol {
+            if total_sol > max_trade_sol {
+                return Err(anyhow!(
+                    "This trade spends {:.4} SOL, above your limit of {} SOL per trade. \
+                    You can change the limit in Settings.",
+                    total_sol,
+                    max_trade_sol
+                ));
+            }
+        }
+
+        if let Some(daily_limit_sol) = self.daily_limit_sol {
+            if spent_sol + total_sol > daily_limit_sol {
+                return Err(anyhow!(
+                    "This trade would bring your spending in the last 24 hours to {:.4} SOL, \
+                    above your daily limit of {} SOL ({:.4} SOL left). \
+                    You can change the limit in Settings.",
+                    spent_sol + total_sol,
+                    daily_limit_sol,
+                    (daily_limit_sol - spent_sol).max(0.0)
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// SOL spent on successful buys among `trades`, what counts against the daily limit
+    pub fn spent_sol(trades: &[Trade]) -> f64 {
+        trades
+            .iter()
+            .filter(|trade| trade.trade_type == "BUY" && trade.status == "SUCCESS")
+            .map(|trade| trade.total_paid)
+            .sum()
+    }
+
+    /// Human readable description of a single limit
+    pub fn describe(limit: Option<f64>) -> String {
+        match limit {
+            Some(limit) => format!("{} SOL", limit),
+            None => "Off".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn trade(trade_type: &str, status: &str, total_paid: f64) -> Trade {
+        Trade {
+            id: 1,
+            user_id: 1,
+            token_address: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+            token_symbol: "BONK".to_string(),
+            amount: 1000.0,
+            price_in_sol: total_paid / 1000.0,
+            price_in_usdc: 0.0,
+            total_paid,
+            trade_type: trade_type.to_string(),
+            tx_signature: None,
+            timestamp: Utc::now(),
+            status: status.to_string(),
+        }
+    }
+
+    #[test]
+    fn trade_above_the_per_trade_cap_is_rejected() {
+        let limits = TradeLimits::default().with(TradeLimitKind::MaxTrade, Some(1.0));
+
+        assert!(limits.check(1.0, 0.0).is_ok());
+        let err = limits.check(1.5, 0.0).unwrap_err().to_string();
+        assert!(err.contains("spends 1.5000 SOL, above your limit of 1 SOL per trade"));
+    }
+
+    #[test]
+    fn trade_pushing_the_rolling_sum_over_the_daily_limit_is_rejected() {
+        let limits = TradeLimits::default().with(TradeLimitKind::Daily, Some(5.0));
+
+        assert!(limits.check(1.0, 4.0).is_ok());
+        let err = limits.check(1.5, 4.0).unwrap_err().to_string();
+        assert!(err.contains("to 5.5000 SOL, above your daily limit of 5 SOL (1.0000 SOL left)"));
+    }
+
+    #[test]
+    fn overspent_daily_limit_has_nothing_left() {
+        let limits = TradeLimits::default().with(TradeLimitKind::Daily, Some(5.0));
+
+        let err = limits.check(0.1, 6.0).unwrap_err().to_string();
+        assert!(err.contains("(0.0000 SOL left)"));
+    }
+
+    #[test]
+    fn limits_that_are_off_allow_any_trade() {
+        assert!(TradeLimits::default().check(1_000.0, 1_000.0).is_ok());
+    }
+
+    #[test]
+    fn only_successful_buys_count_against_the_daily_limit() {
+        let trades = vec![
+            trade("BUY", "SUCCESS", 1.5),
+            trade("BUY", "SUCCESS", 0.25),
+            trade("BUY", "FAILED", 3.0),
+            trade("SELL", "SUCCESS", 2.0),
+        ];
+
+        assert_eq!(TradeLimits::spent_sol(&trades), 1.75);
+    }
+
+    #[test]
+    fn limit_is_described_in_sol_or_off() {
+        assert_eq!(TradeLimits::describe(Some(0.5)), "0.5 SOL");
+        assert_eq!(TradeLimits::describe(None), "Off");
+    }
+}
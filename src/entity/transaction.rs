@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Transaction {
@@ -12,3 +13,34 @@ pub struct Transaction {
     pub timestamp: DateTime<Utc>,
     pub status: String,
 }
+
+/// Final state of a transaction sent to the network
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionStatus {
+    Confirmed,
+    // Executed on chain with an error, the fee is still charged
+    Failed(String),
+    // Not confirmed before the timeout, most likely dropped with an expired blockhash
+    Dropped,
+}
+
+impl TransactionStatus {
+    /// Value stored in the status column of trades and transactions
+    pub fn db_status(&self) -> &'static str {
+        match self {
+            TransactionStatus::Confirmed => "SUCCESS",
+            TransactionStatus::Failed(_) => "FAILED",
+            TransactionStatus::Dropped => "DROPPED",
+        }
+    }
+}
+
+impl fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionStatus::Confirmed => write!(f, "confirmed"),
+            TransactionStatus::Failed(error) => write!(f, "failed: {}", error),
+            TransactionStatus::Dropped => write!(f, "was not confirmed in time and was dropped"),
+        }
+    }
+}
@@ -1,6 +1,13 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::str::FromStr;
+
+use super::{
+    Explorer, PriceCurrency, PriorityFee, TradeLimits, WatchlistSort, DEFAULT_SLIPPAGE_BPS,
+};
+use crate::i18n::Locale;
+use crate::solana::jupiter::RouteOptions;
 
 // User model matching the database schema
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -14,28 +21,224 @@ pub struct User {
     pub created_at: DateTime<Utc>,
     #[serde(default)]
     pub settings: Option<JsonValue>,
+    // Telegram ID of the user whose referral link was used to register
+    #[serde(default)]
+    pub referred_by: Option<i64>,
+    // Newest wallet transaction checked for deposits, None until the first check
+    #[serde(default)]
+    pub last_seen_signature: Option<String>,
 }
 
+/// SOL balance below which the menu warns that fees may not be covered
+pub const DEFAULT_LOW_BALANCE_SOL: f64 = 0.01;
+
+/// Thresholds offered in the settings, zero turns the warning off
+pub const LOW_BALANCE_PRESETS: [f64; 4] = [0.005, 0.01, 0.05, 0.1];
+
+/// Auto-confirm thresholds offered in the settings, zero always asks for confirmation
+pub const AUTO_CONFIRM_PRESETS: [f64; 4] = [0.01, 0.05, 0.1, 0.5];
+
+/// SOL amounts on the quick-buy buttons shown after picking a token to buy
+pub const DEFAULT_QUICK_BUY_AMOUNTS: [f64; 4] = [0.1, 0.5, 1.0, 5.0];
+
+/// Most quick-buy buttons a user can configure
+pub const MAX_QUICK_BUY_AMOUNTS: usize = 6;
+
+/// Largest quick-buy amount in SOL, keeps the button's callback data within Telegram's 64 bytes
+pub const MAX_QUICK_BUY_SOL: f64 = 1_000.0;
+
+/// Range accepted for the max accounts route limit, Jupiter routes use up to 64 accounts
+pub const MAX_ACCOUNTS_RANGE: std::ops::RangeInclusive<u64> = 16..=64;
+
+/// Range accepted for the swap transaction validity in slots, a blockhash expires after 150
+pub const VALIDITY_SLOTS_RANGE: std::ops::RangeInclusive<u64> = 10..=150;
+
 // Default user settings
 pub fn default_user_settings() -> JsonValue {
     serde_json::json!({
-        "slippage": 0.5
+        "slippage_bps": DEFAULT_SLIPPAGE_BPS
     })
 }
 
 // Helper methods for User
 impl User {
-    // Get slippage value from settings (with default fallback)
+    // Get slippage in percent (with default fallback)
     pub fn get_slippage(&self) -> f64 {
-        match &self.settings {
-            Some(settings) => settings
-                .get("slippage")
+        self.get_slippage_bps() as f64 / 100.0
+    }
+
+    /// Slippage of a swap as the fraction Jupiter expects (0.005 for 0.5%), a percent
+    /// `slippage_override` replaces the stored slippage for a single trade
+    pub fn swap_slippage(&self, slippage_override: Option<f64>) -> f64 {
+        slippage_override.unwrap_or_else(|| self.get_slippage()) / 100.0
+    }
+
+    // Get slippage in basis points, older settings keep a percent value under "slippage"
+    pub fn get_slippage_bps(&self) -> u64 {
+        self.settings
+            .as_ref()
+            .and_then(|settings| {
+                settings
+                    .get("slippage_bps")
+                    .and_then(|v| v.as_u64())
+                    .or_else(|| {
+                        settings
+                            .get("slippage")
+                            .and_then(|v| v.as_f64())
+                            .map(|slippage| (slippage * 100.0).round() as u64)
+                    })
+            })
+            .unwrap_or(DEFAULT_SLIPPAGE_BPS)
+    }
+
+    // Get priority fee preset from settings (defaults to no priority fee)
+    pub fn get_priority_fee(&self) -> PriorityFee {
+        self.settings
+            .as_ref()
+            .and_then(|settings| settings.get("priority_fee"))
+            .and_then(|v| v.as_str())
+            .and_then(|v| PriorityFee::from_str(v).ok())
+            .unwrap_or_default()
+    }
+
+    // Get the Jupiter route restrictions from the advanced swap settings (Jupiter defaults when unset)
+    pub fn get_route_options(&self) -> RouteOptions {
+        let settings = self.settings.as_ref();
+
+        RouteOptions {
+            only_direct_routes: settings
+                .and_then(|settings| settings.get("only_direct_routes"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            max_accounts: settings
+                .and_then(|settings| settings.get("max_accounts"))
+                .and_then(|v| v.as_u64())
+                .filter(|max_accounts| MAX_ACCOUNTS_RANGE.contains(max_accounts)),
+            validity_slots: settings
+                .and_then(|settings| settings.get("validity_slots"))
+                .and_then(|v| v.as_u64())
+                .filter(|validity_slots| VALIDITY_SLOTS_RANGE.contains(validity_slots)),
+            platform_fee_bps: None,
+        }
+    }
+
+    // Get the block explorer used for transaction links
+    pub fn get_explorer(&self) -> Explorer {
+        self.settings
+            .as_ref()
+            .and_then(|settings| settings.get("explorer"))
+            .and_then(|v| v.as_str())
+            .and_then(|v| Explorer::from_str(v).ok())
+            .unwrap_or_default()
+    }
+
+    // Get the quote shown first in price displays
+    pub fn get_default_quote(&self) -> PriceCurrency {
+        self.settings
+            .as_ref()
+            .and_then(|settings| settings.get("default_quote"))
+            .and_then(|v| v.as_str())
+            .and_then(|v| PriceCurrency::from_str(v).ok())
+            .unwrap_or_default()
+    }
+
+    // Whether a confirmed trade also gets an image card, off by default
+    pub fn get_trade_card(&self) -> bool {
+        self.settings
+            .as_ref()
+            .and_then(|settings| settings.get("trade_card"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    // Get the low SOL balance warning threshold, 0 means the warning is off
+    pub fn get_low_balance_sol(&self) -> f64 {
+        self.settings
+            .as_ref()
+            .and_then(|settings| settings.get("low_balance_sol"))
+            .and_then(|v| v.as_f64())
+            .filter(|v| v.is_finite() && *v >= 0.0)
+            .unwrap_or(DEFAULT_LOW_BALANCE_SOL)
+    }
+
+    // Get the SOL total below which trades skip confirmation, 0 means always confirm
+    pub fn get_auto_confirm_below_sol(&self) -> f64 {
+        self.settings
+            .as_ref()
+            .and_then(|settings| settings.get("auto_confirm_below_sol"))
+            .and_then(|v| v.as_f64())
+            .filter(|v| v.is_finite() && *v >= 0.0)
+            .unwrap_or(0.0)
+    }
+
+    // Get the quick-buy amounts in SOL (defaults when unset or invalid)
+    pub fn get_quick_buy_amounts(&self) -> Vec<f64> {
+        let amounts: Vec<f64> = self
+            .settings
+            .as_ref()
+            .and_then(|settings| settings.get("quick_buy_amounts"))
+            .and_then(|v| v.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_f64())
+                    .filter(|v| v.is_finite() && *v > 0.0)
+                    .take(MAX_QUICK_BUY_AMOUNTS)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if amounts.is_empty() {
+            DEFAULT_QUICK_BUY_AMOUNTS.to_vec()
+        } else {
+            amounts
+        }
+    }
+
+    // Get the per-trade and daily spending limits (both off by default)
+    pub fn get_trade_limits(&self) -> TradeLimits {
+        let limit = |key: &str| {
+            self.settings
+                .as_ref()
+                .and_then(|settings| settings.get(key))
                 .and_then(|v| v.as_f64())
-                .unwrap_or(0.5),
-            None => 0.5,
+                .filter(|limit| *limit > 0.0)
+        };
+
+        TradeLimits {
+            max_trade_sol: limit("max_trade_sol"),
+            daily_limit_sol: limit("daily_limit_sol"),
         }
     }
 
+    // Get the language chosen by the user, if any
+    pub fn get_locale(&self) -> Option<Locale> {
+        self.settings
+            .as_ref()
+            .and_then(|settings| settings.get("locale"))
+            .and_then(|v| v.as_str())
+            .and_then(Locale::from_code)
+    }
+
+    // Get the watchlist order, kept across refreshes
+    pub fn get_watchlist_sort(&self) -> WatchlistSort {
+        self.settings
+            .as_ref()
+            .and_then(|settings| settings.get("watchlist_sort"))
+            .and_then(|v| v.as_str())
+            .and_then(WatchlistSort::from_code)
+            .unwrap_or_default()
+    }
+
+    // Whether the watchlist shows only tokens with active price alerts
+    pub fn get_watchlist_alerts_only(&self) -> bool {
+        self.settings
+            .as_ref()
+            .and_then(|settings| settings.get("watchlist_alerts_only"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
     // Update slippage value in settings
     pub fn with_slippage(mut self, slippage: f64) -> Self {
         // Limit slippage to reasonable range (0.1% to 5%)
@@ -58,3 +261,135 @@ impl User {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_with_settings(settings: JsonValue) -> User {
+        User {
+            id: 1,
+            telegram_id: 1001,
+            username: None,
+            solana_address: None,
+            encrypted_private_key: None,
+            mnemonic: None,
+            created_at: Utc::now(),
+            settings: Some(settings),
+            referred_by: None,
+            last_seen_signature: None,
+        }
+    }
+
+    #[test]
+    fn configured_slippage_is_passed_to_the_swap_as_a_fraction() {
+        let user = user_with_settings(serde_json::json!({ "slippage_bps": 200 }));
+
+        assert_eq!(user.swap_slippage(None), 0.02);
+    }
+
+    #[test]
+    fn legacy_percent_slippage_is_still_read() {
+        let user = user_with_settings(serde_json::json!({ "slippage": 2.0 }));
+
+        assert_eq!(user.get_slippage_bps(), 200);
+        assert_eq!(user.swap_slippage(None), 0.02);
+    }
+
+    #[test]
+    fn unset_slippage_defaults_to_half_a_percent() {
+        let user = user_with_settings(serde_json::json!({}));
+
+        assert_eq!(user.swap_slippage(None), 0.005);
+    }
+
+    #[test]
+    fn slippage_override_replaces_the_stored_one() {
+        let user = user_with_settings(serde_json::json!({ "slippage_bps": 200 }));
+
+        assert_eq!(user.swap_slippage(Some(3.5)), 0.035);
+    }
+
+    #[test]
+    fn low_balance_warning_defaults_to_a_hundredth_of_a_sol() {
+        let user = user_with_settings(serde_json::json!({}));
+
+        assert_eq!(user.get_low_balance_sol(), DEFAULT_LOW_BALANCE_SOL);
+        assert_eq!(DEFAULT_LOW_BALANCE_SOL, 0.01);
+    }
+
+    #[test]
+    fn configured_low_balance_threshold_is_used() {
+        let user = user_with_settings(serde_json::json!({ "low_balance_sol": 0.05 }));
+        assert_eq!(user.get_low_balance_sol(), 0.05);
+
+        // 0 turns the warning off
+        let user = user_with_settings(serde_json::json!({ "low_balance_sol": 0.0 }));
+        assert_eq!(user.get_low_balance_sol(), 0.0);
+    }
+
+    #[test]
+    fn invalid_low_balance_threshold_falls_back_to_the_default() {
+        let user = user_with_settings(serde_json::json!({ "low_balance_sol": -1.0 }));
+        assert_eq!(user.get_low_balance_sol(), DEFAULT_LOW_BALANCE_SOL);
+
+        let user = user_with_settings(serde_json::json!({ "low_balance_sol": "lots" }));
+        assert_eq!(user.get_low_balance_sol(), DEFAULT_LOW_BALANCE_SOL);
+    }
+
+    #[test]
+    fn route_options_default_to_jupiter_defaults() {
+        let route = user_with_settings(serde_json::json!({})).get_route_options();
+
+        assert!(!route.only_direct_routes);
+        assert_eq!(route.max_accounts, None);
+    }
+
+    #[test]
+    fn configured_route_options_are_read() {
+        let user = user_with_settings(serde_json::json!({
+            "only_direct_routes": true,
+            "max_accounts": 40
+        }));
+        let route = user.get_route_options();
+
+        assert!(route.only_direct_routes);
+        assert_eq!(route.max_accounts, Some(40));
+    }
+
+    #[test]
+    fn out_of_range_max_accounts_is_ignored() {
+        let user = user_with_settings(serde_json::json!({ "max_accounts": 1000 }));
+
+        assert_eq!(user.get_route_options().max_accounts, None);
+    }
+
+    #[test]
+    fn quick_buy_amounts_default_when_unset() {
+        let user = user_with_settings(serde_json::json!({}));
+
+        assert_eq!(user.get_quick_buy_amounts(), vec![0.1, 0.5, 1.0, 5.0]);
+    }
+
+    #[test]
+    fn configured_quick_buy_amounts_skip_invalid_values_and_are_capped() {
+        let user = user_with_settings(serde_json::json!({
+            "quick_buy_amounts": [0.25, "one", -1, 0, 2, 3, 4, 5, 6, 7]
+        }));
+
+        assert_eq!(
+            user.get_quick_buy_amounts(),
+            vec![0.25, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+    }
+
+    #[test]
+    fn quick_buy_amounts_without_a_valid_value_fall_back_to_the_defaults() {
+        let user = user_with_settings(serde_json::json!({ "quick_buy_amounts": [0, "x"] }));
+
+        assert_eq!(
+            user.get_quick_buy_amounts(),
+            DEFAULT_QUICK_BUY_AMOUNTS.to_vec()
+        );
+    }
+}
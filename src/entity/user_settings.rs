@@ -0,0 +1,377 @@
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value as JsonValue};
+use std::str::FromStr;
+
+use super::{
+    Explorer, NotificationKind, PriceCurrency, PriorityFee, MAX_ACCOUNTS_RANGE,
+    MAX_QUICK_BUY_AMOUNTS, MAX_QUICK_BUY_SOL, VALIDITY_SLOTS_RANGE,
+};
+use crate::i18n::Locale;
+use crate::utils::parse_decimal;
+
+/// Slippage accepted by the settings, in percent
+pub const SLIPPAGE_RANGE: std::ops::RangeInclusive<f64> = 0.1..=5.0;
+
+/// Slippage accepted by the settings, in basis points (1 bps = 0.01%)
+pub const SLIPPAGE_BPS_RANGE: std::ops::RangeInclusive<u64> = 10..=500;
+
+/// Slippage of users who never changed it, 0.5%
+pub const DEFAULT_SLIPPAGE_BPS: u64 = 50;
+
+/// Parse slippage typed by a user into basis points
+///
+/// Accepts basis points ("50bps", "50 bps") or a percentage ("0.5%", "0.5"). Values outside
+/// `SLIPPAGE_BPS_RANGE` are rejected instead of clamped, so the stored value is what was typed.
+pub fn parse_slippage_bps(input: &str) -> Result<u64> {
+    let input = input.trim().to_lowercase();
+    let malformed = || {
+        anyhow!(
+            "\"{}\" is not a slippage, enter a percentage like 0.5% or basis points like 50bps",
+            input
+        )
+    };
+
+    let bps = match input.strip_suffix("bps") {
+        Some(bps) => {
+            let bps = parse_decimal(bps).map_err(|_| malformed())?;
+            if bps.fract() != 0.0 {
+                return Err(anyhow!("Basis points must be a whole number, e.g. 50bps"));
+            }
+            bps
+        }
+        None => {
+            let percent = parse_decimal(input.trim_end_matches('%')).map_err(|_| malformed())?;
+            (percent * 100.0).round()
+        }
+    };
+
+    if bps < *SLIPPAGE_BPS_RANGE.start() as f64 || bps > *SLIPPAGE_BPS_RANGE.end() as f64 {
+        return Err(anyhow!(
+            "Slippage must be between {}% ({} bps) and {}% ({} bps)",
+            *SLIPPAGE_BPS_RANGE.start() as f64 / 100.0,
+            SLIPPAGE_BPS_RANGE.start(),
+            *SLIPPAGE_BPS_RANGE.end() as f64 / 100.0,
+            SLIPPAGE_BPS_RANGE.end()
+        ));
+    }
+
+    Ok(bps as u64)
+}
+
+/// Keys of `users.settings` that can be exported and imported
+pub const SETTINGS_KEYS: [&str; 16] = [
+    "slippage",
+    "slippage_bps",
+    "priority_fee",
+    "explorer",
+    "default_quote",
+    "trade_card",
+    "low_balance_sol",
+    "auto_confirm_below_sol",
+    "quick_buy_amounts",
+    "max_trade_sol",
+    "daily_limit_sol",
+    "only_direct_routes",
+    "max_accounts",
+    "validity_slots",
+    "notifications",
+    "locale",
+];
+
+/// Check pasted settings before they replace the user's settings
+///
+/// Every key must be one the bot knows and every value must be in the range the settings menu
+/// accepts, so an import can't store something the menu couldn't. Returns the settings object,
+/// with a legacy percent "slippage" converted to "slippage_bps".
+pub fn validate_user_settings(settings: &JsonValue) -> Result<Map<String, JsonValue>> {
+    let settings = settings
+        .as_object()
+        .ok_or_else(|| anyhow!("Settings must be a JSON object"))?;
+
+    // With both keys it's unclear which slippage is meant
+    if settings.contains_key("slippage") && settings.contains_key("slippage_bps") {
+        return Err(anyhow!(
+            "Settings contain both \"slippage\" and \"slippage_bps\", keep only one"
+        ));
+    }
+
+    for (key, value) in settings {
+        validate_setting(key, value)?;
+    }
+
+    let mut settings = settings.clone();
+    if let Some(amounts) = settings.get("quick_buy_amounts") {
+        let amounts = quick_buy_amounts_from_json(amounts)?;
+        settings.insert("quick_buy_amounts".to_string(), JsonValue::from(amounts));
+    }
+    if let Some(slippage) = settings.remove("slippage").and_then(|value| value.as_f64()) {
+        settings.insert(
+            "slippage_bps".to_string(),
+            JsonValue::from((slippage * 100.0).round() as u64),
+        );
+    }
+
+    Ok(settings)
+}
+
+/// Check quick-buy amounts and round them to 4 decimals, sorted and without duplicates
+///
+/// Rounding and the `MAX_QUICK_BUY_SOL` cap keep the amounts short enough for button
+/// callback data.
+pub fn normalize_quick_buy_amounts(amounts: &[f64]) -> Result<Vec<f64>> {
+    if amounts.is_empty() || amounts.len() > MAX_QUICK_BUY_AMOUNTS {
+        return Err(anyhow!(
+            "Enter between 1 and {} amounts",
+            MAX_QUICK_BUY_AMOUNTS
+        ));
+    }
+
+    if let Some(amount) = amounts.iter().find(|a| !a.is_finite() || **a <= 0.0) {
+        return Err(anyhow!("Invalid amount: {}", amount));
+    }
+
+    if let Some(amount) = amounts.iter().find(|a| **a > MAX_QUICK_BUY_SOL) {
+        return Err(anyhow!(
+            "Amount {} is too large, quick-buy amounts go up to {} SOL",
+            amount,
+            MAX_QUICK_BUY_SOL
+        ));
+    }
+
+    let mut amounts: Vec<f64> = amounts
+        .iter()
+        .map(|amount| (amount * 10_000.0).round() / 10_000.0)
+        .filter(|amount| *amount > 0.0)
+        .collect();
+    amounts.sort_by(|a, b| a.total_cmp(b));
+    amounts.dedup();
+
+    if amounts.is_empty() {
+        return Err(anyhow!("Amounts must be at least 0.0001 SOL"));
+    }
+
+    Ok(amounts)
+}
+
+// Imported quick-buy amounts, normalized like the ones entered in the settings menu
+fn quick_buy_amounts_from_json(value: &JsonValue) -> Result<Vec<f64>> {
+    let invalid = || anyhow!("Invalid value for \"quick_buy_amounts\": {}", value);
+    let amounts = value
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|amount| amount.as_f64().ok_or_else(invalid))
+        .collect::<Result<Vec<f64>>>()?;
+
+    normalize_quick_buy_amounts(&amounts)
+}
+
+fn validate_setting(key: &str, value: &JsonValue) -> Result<()> {
+    let invalid = || anyhow!("Invalid value for \"{}\": {}", key, value);
+
+    match key {
+        "slippage" => {
+            let slippage = value.as_f64().ok_or_else(invalid)?;
+            if !SLIPPAGE_RANGE.contains(&slippage) {
+                return Err(anyhow!(
+                    "Slippage must be between {}% and {}%",
+                    SLIPPAGE_RANGE.start(),
+                    SLIPPAGE_RANGE.end()
+                ));
+            }
+        }
+        "slippage_bps" => {
+            let bps = value.as_u64().ok_or_else(invalid)?;
+            if !SLIPPAGE_BPS_RANGE.contains(&bps) {
+                return Err(anyhow!(
+                    "Slippage must be between {} and {} bps",
+                    SLIPPAGE_BPS_RANGE.start(),
+                    SLIPPAGE_BPS_RANGE.end()
+                ));
+            }
+        }
+        "priority_fee" => {
+            let fee = value.as_str().ok_or_else(invalid)?;
+            PriorityFee::from_str(fee)?;
+        }
+        "explorer" => {
+            let explorer = value.as_str().ok_or_else(invalid)?;
+            Explorer::from_str(explorer)?;
+        }
+        "default_quote" => {
+            let quote = value.as_str().ok_or_else(invalid)?;
+            PriceCurrency::from_str(quote)?;
+        }
+        "low_balance_sol" | "auto_confirm_below_sol" => {
+            let threshold = value.as_f64().ok_or_else(invalid)?;
+            if !threshold.is_finite() || threshold < 0.0 {
+                return Err(invalid());
+            }
+        }
+        "quick_buy_amounts" => {
+            quick_buy_amounts_from_json(value)?;
+        }
+        // null turns the limit off
+        "max_trade_sol" | "daily_limit_sol" => {
+            if !value.is_null() && !value.as_f64().is_some_and(|limit| limit > 0.0) {
+                return Err(invalid());
+            }
+        }
+        "only_direct_routes" | "trade_card" => {
+            value.as_bool().ok_or_else(invalid)?;
+        }
+        // null leaves Jupiter's default
+        "max_accounts" => {
+            if !value.is_null() {
+                let max_accounts = value.as_u64().ok_or_else(invalid)?;
+                if !MAX_ACCOUNTS_RANGE.contains(&max_accounts) {
+                    return Err(anyhow!(
+                        "Max accounts must be between {} and {}",
+                        MAX_ACCOUNTS_RANGE.start(),
+                        MAX_ACCOUNTS_RANGE.end()
+                    ));
+                }
+            }
+        }
+        // null keeps the full blockhash lifetime
+        "validity_slots" => {
+            if !value.is_null() {
+                let validity_slots = value.as_u64().ok_or_else(invalid)?;
+                if !VALIDITY_SLOTS_RANGE.contains(&validity_slots) {
+                    return Err(anyhow!(
+                        "Transaction validity must be between {} and {} slots",
+                        VALIDITY_SLOTS_RANGE.start(),
+                        VALIDITY_SLOTS_RANGE.end()
+                    ));
+                }
+            }
+        }
+        // Only the known kinds can be switched, missing ones stay on
+        "notifications" => {
+            let notifications = value.as_object().ok_or_else(invalid)?;
+            for (kind, enabled) in notifications {
+                if NotificationKind::from_code(kind).is_none() || !enabled.is_boolean() {
+                    return Err(invalid());
+                }
+            }
+        }
+        "locale" => {
+            let code = value.as_str().ok_or_else(invalid)?;
+            Locale::from_code(code).ok_or_else(invalid)?;
+        }
+        _ => return Err(anyhow!("Unknown setting \"{}\"", key)),
+    }
+
+    Ok(())
+}
+
+/// The user's settings as they are exported, only the keys an import accepts
+pub fn exportable_settings(settings: Option<&JsonValue>) -> Map<String, JsonValue> {
+    settings
+        .and_then(|settings| settings.as_object())
+        .map(|settings| {
+            settings
+                .iter()
+                .filter(|(key, _)| SETTINGS_KEYS.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_basis_points() {
+        assert_eq!(parse_slippage_bps("50bps").unwrap(), 50);
+        assert_eq!(parse_slippage_bps(" 75 BPS ").unwrap(), 75);
+        assert!(parse_slippage_bps("50.5bps").is_err());
+    }
+
+    #[test]
+    fn parses_percentages() {
+        assert_eq!(parse_slippage_bps("0.5%").unwrap(), 50);
+        assert_eq!(parse_slippage_bps("0.5").unwrap(), 50);
+        assert_eq!(parse_slippage_bps("0,57%").unwrap(), 57);
+        assert_eq!(parse_slippage_bps("5%").unwrap(), 500);
+    }
+
+    #[test]
+    fn rejects_slippage_out_of_bounds() {
+        assert!(parse_slippage_bps("10%").is_err());
+        assert!(parse_slippage_bps("0.05%").is_err());
+        assert!(parse_slippage_bps("501bps").is_err());
+        assert!(parse_slippage_bps("9bps").is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_slippage() {
+        assert!(parse_slippage_bps("").is_err());
+        assert!(parse_slippage_bps("abc").is_err());
+        assert!(parse_slippage_bps("bps").is_err());
+        assert!(parse_slippage_bps("0.5.1%").is_err());
+    }
+
+    #[test]
+    fn imported_percent_slippage_is_stored_in_basis_points() {
+        for (percent, bps) in [(0.12, 12), (0.29, 29), (0.35, 35), (0.57, 57), (0.99, 99)] {
+            let settings = validate_user_settings(&json!({ "slippage": percent })).unwrap();
+
+            assert_eq!(settings.get("slippage_bps"), Some(&json!(bps)));
+            assert!(!settings.contains_key("slippage"));
+        }
+    }
+
+    #[test]
+    fn import_with_both_slippage_keys_is_rejected() {
+        let settings = json!({ "slippage": 1.0, "slippage_bps": 50 });
+
+        assert!(validate_user_settings(&settings).is_err());
+    }
+
+    #[test]
+    fn imported_quick_buy_amounts_are_rounded_like_the_menu() {
+        let settings = json!({ "quick_buy_amounts": [0.123456789, 1.0] });
+
+        let settings = validate_user_settings(&settings).unwrap();
+
+        assert_eq!(
+            settings.get("quick_buy_amounts"),
+            Some(&json!([0.1235, 1.0]))
+        );
+        assert_eq!(
+            normalize_quick_buy_amounts(&[0.123456789, 1.0]).unwrap(),
+            vec![0.1235, 1.0]
+        );
+    }
+
+    #[test]
+    fn imported_quick_buy_amounts_below_the_precision_are_rejected() {
+        let settings = json!({ "quick_buy_amounts": [0.00001] });
+
+        assert!(validate_user_settings(&settings).is_err());
+        assert!(validate_user_settings(&json!({ "quick_buy_amounts": ["1"] })).is_err());
+    }
+
+    #[test]
+    fn imported_quick_buy_amounts_above_the_cap_are_rejected() {
+        let settings = json!({ "quick_buy_amounts": [0.5, MAX_QUICK_BUY_SOL + 1.0] });
+
+        let err = validate_user_settings(&settings).unwrap_err();
+
+        assert!(err.to_string().contains("up to 1000 SOL"), "{}", err);
+    }
+
+    #[test]
+    fn quick_buy_amounts_above_the_cap_are_rejected() {
+        assert_eq!(
+            normalize_quick_buy_amounts(&[MAX_QUICK_BUY_SOL]).unwrap(),
+            vec![MAX_QUICK_BUY_SOL]
+        );
+        assert!(normalize_quick_buy_amounts(&[0.5, 1_000.0001]).is_err());
+        assert!(normalize_quick_buy_amounts(&[1e300]).is_err());
+    }
+}
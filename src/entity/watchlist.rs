@@ -1,6 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::PriceCurrency;
+use crate::utils::format_price;
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct WatchlistItem {
     pub id: i32,
@@ -10,11 +13,86 @@ pub struct WatchlistItem {
     pub last_price_in_sol: f64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    // Price at the start of the current 24h window, None until the first price update
+    pub reference_price_in_sol: Option<f64>,
+    pub reference_price_at: Option<DateTime<Utc>>,
 }
 
 impl WatchlistItem {
     // Format price for display
     pub fn format_price(&self) -> String {
-        format!("{:.6} SOL", self.last_price_in_sol)
+        format!("{} SOL", format_price(self.last_price_in_sol))
+    }
+
+    /// Price change in percent over the current 24h window, None without price data
+    pub fn change_24h(&self) -> Option<f64> {
+        self.reference_price_in_sol
+            .filter(|reference| *reference > 0.0 && self.last_price_in_sol > 0.0)
+            .map(|reference| (self.last_price_in_sol - reference) / reference * 100.0)
+    }
+}
+
+/// Order of the watchlist, kept in the user's settings
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum WatchlistSort {
+    #[default]
+    Alphabetical,
+    Change24h,
+}
+
+impl WatchlistSort {
+    /// Code stored in settings and used in callbacks, e.g. `watchlist_sort_change`
+    pub fn code(&self) -> &'static str {
+        match self {
+            WatchlistSort::Alphabetical => "alpha",
+            WatchlistSort::Change24h => "change",
+        }
     }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "alpha" => Some(WatchlistSort::Alphabetical),
+            "change" => Some(WatchlistSort::Change24h),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            WatchlistSort::Alphabetical => "A-Z",
+            WatchlistSort::Change24h => "24h Change",
+        }
+    }
+
+    /// ORDER BY clause for the watchlist query
+    ///
+    /// Biggest gainers come first. Tokens without price data, where
+    /// `WatchlistItem::change_24h` is None, have a NULL change and are listed last.
+    /// Ties are broken by symbol so pages stay stable between refreshes.
+    pub fn order_by(&self) -> &'static str {
+        match self {
+            WatchlistSort::Alphabetical => "token_symbol ASC, id ASC",
+            WatchlistSort::Change24h => {
+                "CASE WHEN last_price_in_sol > 0 AND reference_price_in_sol > 0
+                     THEN (last_price_in_sol - reference_price_in_sol) / reference_price_in_sol
+                     ELSE NULL
+                 END DESC NULLS LAST,
+                 token_symbol ASC, id ASC"
+            }
+        }
+    }
+}
+
+/// One page of the user's watchlist with the sort and filter it was built with
+#[derive(Debug, Clone)]
+pub struct WatchlistPage {
+    pub items: Vec<WatchlistItem>,
+    pub page: usize,
+    pub pages: usize,
+    pub sort: WatchlistSort,
+    // Only tokens with an active price alert
+    pub alerts_only: bool,
+    // Quote the prices are shown in, USDC prices need the SOL price
+    pub quote: PriceCurrency,
+    pub sol_price_in_usdc: Option<f64>,
 }
@@ -0,0 +1,55 @@
+// English messages, the fallback for keys missing in other locales
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    (
+        "start.registered",
+        "<b>Hello!</b> I'm a Solana trading bot. You have been successfully registered.",
+    ),
+    (
+        "start.welcome_back",
+        "<b>Welcome back to Solana Trading Bot!</b>",
+    ),
+    (
+        "start.wallet_auto_created",
+        "I've automatically created a Solana wallet for you! ✅\nYou can now send and receive tokens.",
+    ),
+    (
+        "help.text",
+        "Available commands:\n\
+        /start - Start working with the bot\n\
+        /menu - Main menu\n\
+        /language - Change the bot language\n\
+        /cancel - Cancel the current operation\n\
+        /help - Show this help",
+    ),
+    (
+        "wallet.created",
+        "Your Solana wallet has been successfully created!\n\n\
+        Public address: `{address}`\n\n\
+        Mnemonic phrase: `{mnemonic}`\n\n\
+        <b>Important:</b> Save your mnemonic phrase - it's needed to recover access!",
+    ),
+    (
+        "wallet.created_next_step",
+        "Your wallet has been created successfully. What would you like to do next?",
+    ),
+    (
+        "withdraw.confirm",
+        "<b>Confirm Withdrawal</b>\n\n\
+        • Amount: <b>{amount} {symbol}</b>\n\
+        • Value: <b>{total_sol} SOL</b> ({total_usdc})\n\
+        • To: <code>{recipient}</code>\n\n\
+        Proceed with this withdrawal? (yes/no)",
+    ),
+    ("withdraw.confirm_yes", "yes"),
+    (
+        "withdraw.fee_deducted",
+        "Network fee of <b>{fee} SOL</b> is deducted so your whole balance can be sent.",
+    ),
+    ("number.decimal_separator", "."),
+    ("number.group_separator", ","),
+    ("number.million", "M"),
+    ("number.billion", "B"),
+    ("number.trillion", "T"),
+    ("language.prompt", "Choose the bot language:"),
+    ("language.changed", "Language set to English."),
+];
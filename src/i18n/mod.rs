@@ -0,0 +1,132 @@
+mod en;
+mod ru;
+
+use crate::interactor::db;
+use sqlx::PgPool;
+
+/// Language used for bot messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Ru,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Ru];
+
+    /// Two-letter language code, as stored in the user settings
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ru => "ru",
+        }
+    }
+
+    /// Language name in the language itself, used on the selection buttons
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::Ru => "Русский",
+        }
+    }
+
+    /// Parses a language code such as "ru" or a Telegram IETF tag such as "ru-RU"
+    pub fn from_code(code: &str) -> Option<Self> {
+        let language = code.split(['-', '_']).next().unwrap_or("").to_lowercase();
+
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.code() == language)
+    }
+
+    /// Picks the locale stored in the user settings, then the Telegram client language,
+    /// and falls back to English when neither is known
+    pub fn resolve(user_locale: Option<Locale>, language_code: Option<&str>) -> Self {
+        user_locale
+            .or_else(|| language_code.and_then(Locale::from_code))
+            .unwrap_or_default()
+    }
+
+    fn table(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::En => en::MESSAGES,
+            Locale::Ru => ru::MESSAGES,
+        }
+    }
+}
+
+/// Returns the message for `key` in `locale`, falling back to English and then to the key
+pub fn t(key: &'static str, locale: Locale) -> &'static str {
+    lookup(key, locale)
+        .or_else(|| lookup(key, Locale::En))
+        .unwrap_or(key)
+}
+
+/// Same as [`t`] with `{name}` placeholders replaced by the given values
+pub fn t_with(key: &'static str, locale: Locale, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(t(key, locale).to_string(), |message, (name, value)| {
+            message.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+/// Locale for a Telegram user, see [`Locale::resolve`]
+pub async fn user_locale(pool: &PgPool, telegram_id: i64, language_code: Option<&str>) -> Locale {
+    let stored_locale = db::get_user_by_telegram_id(pool, telegram_id)
+        .await
+        .ok()
+        .and_then(|user| user.get_locale());
+
+    Locale::resolve(stored_locale, language_code)
+}
+
+fn lookup(key: &str, locale: Locale) -> Option<&'static str> {
+    locale
+        .table()
+        .iter()
+        .find(|(message_key, _)| *message_key == key)
+        .map(|(_, message)| *message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn every_key_exists_in_every_locale() {
+        let keys = |locale: Locale| -> HashSet<&str> {
+            locale.table().iter().map(|(key, _)| *key).collect()
+        };
+        let english = keys(Locale::En);
+
+        for locale in Locale::ALL {
+            let translated = keys(locale);
+
+            let missing: Vec<_> = english.difference(&translated).collect();
+            assert!(missing.is_empty(), "{:?} is missing {:?}", locale, missing);
+
+            let unknown: Vec<_> = translated.difference(&english).collect();
+            assert!(unknown.is_empty(), "{:?} has unknown {:?}", locale, unknown);
+        }
+    }
+
+    #[test]
+    fn keys_are_not_duplicated() {
+        for locale in Locale::ALL {
+            let mut seen = HashSet::new();
+            for (key, _) in locale.table() {
+                assert!(seen.insert(key), "{:?} defines {} twice", locale, key);
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_client_language_falls_back_to_english() {
+        assert_eq!(Locale::resolve(None, Some("xx")), Locale::En);
+        assert_eq!(Locale::resolve(None, None), Locale::En);
+        assert_eq!(Locale::resolve(None, Some("ru")), Locale::Ru);
+        assert_eq!(Locale::resolve(Some(Locale::En), Some("ru")), Locale::En);
+    }
+}
@@ -0,0 +1,55 @@
+// Russian messages
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    (
+        "start.registered",
+        "<b>Привет!</b> Я бот для торговли на Solana. Вы успешно зарегистрированы.",
+    ),
+    (
+        "start.welcome_back",
+        "<b>С возвращением в Solana Trading Bot!</b>",
+    ),
+    (
+        "start.wallet_auto_created",
+        "Я автоматически создал для вас кошелек Solana! ✅\nТеперь вы можете отправлять и получать токены.",
+    ),
+    (
+        "help.text",
+        "Доступные команды:\n\
+        /start - Начать работу с ботом\n\
+        /menu - Главное меню\n\
+        /language - Сменить язык бота\n\
+        /cancel - Отменить текущую операцию\n\
+        /help - Показать эту справку",
+    ),
+    (
+        "wallet.created",
+        "Ваш кошелек Solana успешно создан!\n\n\
+        Публичный адрес: `{address}`\n\n\
+        Мнемоническая фраза: `{mnemonic}`\n\n\
+        <b>Важно:</b> Сохраните мнемоническую фразу - она нужна для восстановления доступа!",
+    ),
+    (
+        "wallet.created_next_step",
+        "Ваш кошелек успешно создан. Что вы хотите сделать дальше?",
+    ),
+    (
+        "withdraw.confirm",
+        "<b>Подтвердите вывод</b>\n\n\
+        • Сумма: <b>{amount} {symbol}</b>\n\
+        • Стоимость: <b>{total_sol} SOL</b> ({total_usdc})\n\
+        • Получатель: <code>{recipient}</code>\n\n\
+        Выполнить вывод? (да/нет)",
+    ),
+    ("withdraw.confirm_yes", "да"),
+    (
+        "withdraw.fee_deducted",
+        "Комиссия сети <b>{fee} SOL</b> вычтена, чтобы отправить весь баланс.",
+    ),
+    ("number.decimal_separator", ","),
+    ("number.group_separator", "\u{a0}"),
+    ("number.million", "\u{a0}млн"),
+    ("number.billion", "\u{a0}млрд"),
+    ("number.trillion", "\u{a0}трлн"),
+    ("language.prompt", "Выберите язык бота:"),
+    ("language.changed", "Язык изменен на русский."),
+];
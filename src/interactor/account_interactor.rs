@@ -0,0 +1,66 @@
+use crate::entity::User;
+use crate::interactor::db;
+use crate::VERSION;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// What a user can share with support, no keys or recovery phrases
+pub struct AccountInfo {
+    pub telegram_id: i64,
+    pub username: Option<String>,
+    pub registered_at: DateTime<Utc>,
+    pub wallet_address: Option<String>,
+    pub active_limit_orders: usize,
+    pub active_dca_orders: usize,
+    pub version: &'static str,
+}
+
+impl AccountInfo {
+    /// Shareable details of `user`, the wallet's keys are left behind
+    pub fn from_user(user: User, active_limit_orders: usize, active_dca_orders: usize) -> Self {
+        Self {
+            telegram_id: user.telegram_id,
+            username: user.username,
+            registered_at: user.created_at,
+            wallet_address: user.solana_address,
+            active_limit_orders,
+            active_dca_orders,
+            version: VERSION,
+        }
+    }
+}
+
+#[async_trait]
+pub trait AccountInteractor: Send + Sync {
+    async fn get_account_info(&self, telegram_id: i64) -> Result<AccountInfo>;
+}
+
+pub struct AccountInteractorImpl {
+    db_pool: Arc<PgPool>,
+}
+
+impl AccountInteractorImpl {
+    pub fn new(db_pool: Arc<PgPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl AccountInteractor for AccountInteractorImpl {
+    async fn get_account_info(&self, telegram_id: i64) -> Result<AccountInfo> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+        let (limit_orders, dca_orders) = tokio::join!(
+            db::get_active_limit_orders(&self.db_pool, telegram_id),
+            db::get_active_dca_orders(&self.db_pool, telegram_id),
+        );
+
+        Ok(AccountInfo::from_user(
+            user,
+            limit_orders?.len(),
+            dca_orders?.len(),
+        ))
+    }
+}
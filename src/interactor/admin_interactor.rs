@@ -0,0 +1,138 @@
+use crate::entity::{BlacklistedToken, User};
+use crate::interactor::db;
+use crate::utils::validate_solana_address;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::env;
+use std::sync::Arc;
+
+/// Number of most recent users shown by `/admin users`
+pub const RECENT_USERS_LIMIT: i64 = 20;
+
+lazy_static! {
+    static ref ADMIN_TELEGRAM_IDS: HashSet<i64> =
+        parse_admin_ids(&env::var("ADMIN_TELEGRAM_IDS").unwrap_or_default());
+}
+
+/// Parse a comma-separated list of Telegram IDs, invalid entries are skipped
+pub fn parse_admin_ids(value: &str) -> HashSet<i64> {
+    value
+        .split(',')
+        .filter_map(|id| id.trim().parse::<i64>().ok())
+        .collect()
+}
+
+/// Telegram IDs listed in ADMIN_TELEGRAM_IDS
+pub fn configured_admin_ids() -> HashSet<i64> {
+    ADMIN_TELEGRAM_IDS.clone()
+}
+
+/// Registered users for the admin overview
+pub struct UserOverview {
+    pub total_users: i64,
+    pub recent_users: Vec<User>,
+}
+
+#[async_trait]
+pub trait AdminInteractor: Send + Sync {
+    fn is_admin(&self, telegram_id: i64) -> bool;
+    async fn get_user_overview(&self) -> Result<UserOverview>;
+    async fn get_broadcast_recipients(&self) -> Result<Vec<i64>>;
+    async fn get_blacklisted_tokens(&self) -> Result<Vec<BlacklistedToken>>;
+    async fn blacklist_token(
+        &self,
+        telegram_id: i64,
+        mint_address: &str,
+        reason: Option<&str>,
+    ) -> Result<()>;
+    // Returns false when the token wasn't blacklisted
+    async fn unblacklist_token(&self, mint_address: &str) -> Result<bool>;
+}
+
+pub struct AdminInteractorImpl {
+    db_pool: Arc<PgPool>,
+    admin_ids: HashSet<i64>,
+}
+
+impl AdminInteractorImpl {
+    /// Admins are read from the ADMIN_TELEGRAM_IDS environment variable
+    pub fn new(db_pool: Arc<PgPool>) -> Self {
+        Self::with_admin_ids(db_pool, configured_admin_ids())
+    }
+
+    pub fn with_admin_ids(db_pool: Arc<PgPool>, admin_ids: HashSet<i64>) -> Self {
+        Self { db_pool, admin_ids }
+    }
+}
+
+#[async_trait]
+impl AdminInteractor for AdminInteractorImpl {
+    fn is_admin(&self, telegram_id: i64) -> bool {
+        self.admin_ids.contains(&telegram_id)
+    }
+
+    async fn get_user_overview(&self) -> Result<UserOverview> {
+        let total_users = db::count_users(&self.db_pool).await?;
+        let recent_users = db::get_recent_users(&self.db_pool, RECENT_USERS_LIMIT).await?;
+
+        Ok(UserOverview {
+            total_users,
+            recent_users,
+        })
+    }
+
+    async fn get_broadcast_recipients(&self) -> Result<Vec<i64>> {
+        Ok(db::get_all_user_telegram_ids(&self.db_pool).await?)
+    }
+
+    async fn get_blacklisted_tokens(&self) -> Result<Vec<BlacklistedToken>> {
+        Ok(db::get_blacklisted_tokens(&self.db_pool).await?)
+    }
+
+    async fn blacklist_token(
+        &self,
+        telegram_id: i64,
+        mint_address: &str,
+        reason: Option<&str>,
+    ) -> Result<()> {
+        if !validate_solana_address(mint_address) {
+            return Err(anyhow!("Invalid token address: {}", mint_address));
+        }
+
+        db::add_blacklisted_token(&self.db_pool, mint_address, reason, telegram_id).await?;
+        Ok(())
+    }
+
+    async fn unblacklist_token(&self, mint_address: &str) -> Result<bool> {
+        Ok(db::remove_blacklisted_token(&self.db_pool, mint_address).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+
+    #[test]
+    fn admin_ids_skip_blank_and_invalid_entries() {
+        let ids = parse_admin_ids(" 123456789, ,abc,-1001234,123456789 ");
+
+        assert_eq!(ids, HashSet::from([123456789, -1001234]));
+        assert!(parse_admin_ids("").is_empty());
+    }
+
+    #[tokio::test]
+    async fn only_listed_ids_are_admins() {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        let interactor =
+            AdminInteractorImpl::with_admin_ids(Arc::new(pool), parse_admin_ids("123456789"));
+
+        assert!(interactor.is_admin(123456789));
+        assert!(!interactor.is_admin(987654321));
+    }
+}
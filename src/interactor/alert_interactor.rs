@@ -0,0 +1,147 @@
+use crate::entity::{AlertDirection, PriceAlert};
+use crate::interactor::db;
+use crate::solana::jupiter::price_service::PriceService;
+use crate::solana::jupiter::token_repository::TokenRepository;
+use crate::utils::parse_decimal;
+use crate::validate_solana_address;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait AlertInteractor: Send + Sync {
+    async fn validate_token_address(&self, token_address: &str) -> Result<bool>;
+    async fn get_token_info(&self, token_address: &str) -> Result<(String, f64, f64)>;
+    /// Parses "above 0.5", "below 0.5" or "above 0.5 repeat"
+    fn parse_alert_condition(&self, condition_text: &str) -> Result<(AlertDirection, f64, bool)>;
+    async fn create_alert(
+        &self,
+        telegram_id: i64,
+        token_address: &str,
+        token_symbol: &str,
+        direction: AlertDirection,
+        target_price_in_sol: f64,
+        one_shot: bool,
+    ) -> Result<i32>;
+    async fn get_alerts(&self, telegram_id: i64) -> Result<Vec<PriceAlert>>;
+    async fn delete_alert(&self, telegram_id: i64, alert_id: i32) -> Result<bool>;
+}
+
+pub struct AlertInteractorImpl {
+    db_pool: Arc<PgPool>,
+    price_service: Arc<dyn PriceService + Send + Sync>,
+    token_repository: Arc<dyn TokenRepository + Send + Sync>,
+}
+
+impl AlertInteractorImpl {
+    pub fn new(
+        db_pool: Arc<PgPool>,
+        price_service: Arc<dyn PriceService + Send + Sync>,
+        token_repository: Arc<dyn TokenRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            db_pool,
+            price_service,
+            token_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl AlertInteractor for AlertInteractorImpl {
+    async fn validate_token_address(&self, token_address: &str) -> Result<bool> {
+        // First check if it's a valid Solana address
+        if !validate_solana_address(token_address) {
+            return Ok(false);
+        }
+
+        // Then check if it's actually a token mint address by trying to get its info
+        match self.token_repository.get_token_by_id(token_address).await {
+            Ok(_) => Ok(true),
+            Err(_) => Ok(false),
+        }
+    }
+
+    async fn get_token_info(&self, token_address: &str) -> Result<(String, f64, f64)> {
+        let token = self.token_repository.get_token_by_id(token_address).await?;
+        let price_info = self.price_service.get_token_price(token_address).await?;
+
+        Ok((
+            token.symbol,
+            price_info.price_in_sol,
+            price_info.price_in_usdc,
+        ))
+    }
+
+    fn parse_alert_condition(&self, condition_text: &str) -> Result<(AlertDirection, f64, bool)> {
+        let parts: Vec<&str> = condition_text.split_whitespace().collect();
+
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(anyhow!(
+                "Invalid format. Please enter e.g. 'above 0.5' or 'below 0.5 repeat'"
+            ));
+        }
+
+        let direction = AlertDirection::from_str(parts[0])
+            .map_err(|_| anyhow!("Direction must be 'above' or 'below'"))?;
+
+        let target_price = match parse_decimal(parts[1]) {
+            Ok(price) if price > 0.0 => price,
+            Ok(_) => return Err(anyhow!("Price must be greater than zero")),
+            Err(_) => return Err(anyhow!("Invalid price format. Please enter a number.")),
+        };
+
+        let one_shot = match parts.get(2) {
+            None => true,
+            Some(flag) if flag.eq_ignore_ascii_case("repeat") => false,
+            Some(_) => return Err(anyhow!("Add 'repeat' to keep the alert after it fires")),
+        };
+
+        Ok((direction, target_price, one_shot))
+    }
+
+    async fn create_alert(
+        &self,
+        telegram_id: i64,
+        token_address: &str,
+        token_symbol: &str,
+        direction: AlertDirection,
+        target_price_in_sol: f64,
+        one_shot: bool,
+    ) -> Result<i32> {
+        // Remember the current price so the alert only fires on a crossing
+        let current_price = self
+            .price_service
+            .get_token_price(token_address)
+            .await
+            .ok()
+            .map(|price_info| price_info.price_in_sol);
+
+        let alert = db::PriceAlertRecord {
+            telegram_id,
+            token_address,
+            token_symbol,
+            direction: &direction,
+            target_price_in_sol,
+            one_shot,
+        };
+
+        db::create_price_alert(&self.db_pool, &alert, current_price)
+            .await
+            .map_err(|e| anyhow!("Failed to create price alert: {}", e))
+    }
+
+    async fn get_alerts(&self, telegram_id: i64) -> Result<Vec<PriceAlert>> {
+        db::get_user_price_alerts(&self.db_pool, telegram_id)
+            .await
+            .map_err(|e| anyhow!("Error fetching price alerts: {}", e))
+    }
+
+    async fn delete_alert(&self, telegram_id: i64, alert_id: i32) -> Result<bool> {
+        db::delete_price_alert(&self.db_pool, telegram_id, alert_id)
+            .await
+            .map_err(|e| anyhow!("Failed to delete price alert: {}", e))
+    }
+}
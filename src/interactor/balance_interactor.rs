@@ -1,32 +1,103 @@
 use crate::entity::{BotError, TokenBalance};
 use crate::interactor::db;
 use crate::solana;
+use crate::solana::client::ResilientRpcClient;
 use crate::solana::jupiter::PriceService;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use log::info;
-use solana_client::nonblocking::rpc_client::RpcClient;
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use log::{debug, info};
 use sqlx::PgPool;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// How long wallet balances are reused before they are fetched again
+pub const BALANCE_CACHE_TTL: Duration = Duration::from_secs(15);
+
+/// Balances of a wallet with their USD values
+#[derive(Debug, Clone)]
+pub struct WalletBalances {
+    pub address: String,
+    pub sol_balance: f64,
+    pub token_balances: Vec<TokenBalance>,
+    // USD value per symbol, None when the price couldn't be fetched
+    pub usd_values: Vec<(String, Option<f64>)>,
+    // When the balances were fetched from the RPC node
+    pub updated_at: DateTime<Utc>,
+    // User's low SOL balance warning threshold, 0 when the warning is off
+    pub low_balance_sol: f64,
+}
+
+impl WalletBalances {
+    /// The threshold when the SOL balance is below it and the warning is on
+    pub fn low_balance_warning(&self) -> Option<f64> {
+        (self.low_balance_sol > 0.0 && self.sol_balance < self.low_balance_sol)
+            .then_some(self.low_balance_sol)
+    }
+
+    /// USD value of the SOL and tokens whose price is known
+    pub fn total_usd(&self) -> f64 {
+        self.usd_values.iter().filter_map(|(_, value)| *value).sum()
+    }
+}
+
+type BalanceCache = RwLock<HashMap<String, (WalletBalances, Instant)>>;
+
+lazy_static! {
+    // Keyed by wallet address and shared by all interactor instances,
+    // handlers create a new interactor per request
+    static ref SHARED_BALANCE_CACHE: BalanceCache = RwLock::new(HashMap::new());
+}
+
+// A forced refresh (the Refresh button) never reuses the cache
+fn lookup_balances(
+    cache: &BalanceCache,
+    address: &str,
+    force_refresh: bool,
+    now: Instant,
+) -> Option<WalletBalances> {
+    if force_refresh {
+        return None;
+    }
+
+    let cache = cache.read().unwrap();
+    cache
+        .get(address)
+        .filter(|(_, fetched_at)| now.duration_since(*fetched_at) < BALANCE_CACHE_TTL)
+        .map(|(balances, _)| balances.clone())
+}
+
+fn store_balances(cache: &BalanceCache, balances: &WalletBalances, now: Instant) {
+    let mut cache = cache.write().unwrap();
+
+    // Drop expired entries so the map doesn't keep every wallet that was ever shown
+    cache.retain(|_, (_, fetched_at)| now.duration_since(*fetched_at) < BALANCE_CACHE_TTL);
+
+    cache.insert(balances.address.clone(), (balances.clone(), now));
+}
 
 #[async_trait]
 pub trait BalanceInteractor: Send + Sync {
+    /// Balances of the user's wallet, reused for `BALANCE_CACHE_TTL` unless `force_refresh` is set
     async fn get_wallet_balances(
         &self,
         telegram_id: i64,
-    ) -> Result<(String, f64, Vec<TokenBalance>, Vec<(String, f64)>)>;
+        force_refresh: bool,
+    ) -> Result<WalletBalances>;
 }
 
 pub struct BalanceInteractorImpl {
     db_pool: Arc<PgPool>,
-    solana_client: Arc<RpcClient>,
+    solana_client: Arc<ResilientRpcClient>,
     price_service: Arc<dyn PriceService + Send + Sync>,
 }
 
 impl BalanceInteractorImpl {
     pub fn new(
         db_pool: Arc<PgPool>,
-        solana_client: Arc<RpcClient>,
+        solana_client: Arc<ResilientRpcClient>,
         price_service: Arc<dyn PriceService + Send + Sync>,
     ) -> Self {
         Self {
@@ -35,6 +106,71 @@ impl BalanceInteractorImpl {
             price_service,
         }
     }
+
+    // Cached balances of the wallet if they are still within the TTL
+    fn cached_balances(address: &str, force_refresh: bool) -> Option<WalletBalances> {
+        lookup_balances(
+            &SHARED_BALANCE_CACHE,
+            address,
+            force_refresh,
+            Instant::now(),
+        )
+    }
+
+    fn cache_balances(balances: &WalletBalances) {
+        store_balances(&SHARED_BALANCE_CACHE, balances, Instant::now());
+    }
+
+    // USD value of the SOL and each held token, a token whose price lookup fails is kept
+    // with None so the rest of the balances are still shown
+    async fn fetch_usd_values(
+        &self,
+        sol_balance: f64,
+        token_balances: &[TokenBalance],
+    ) -> Vec<(String, Option<f64>)> {
+        // Initialize vector for USD values
+        let mut usd_values = Vec::new();
+
+        // Get SOL price first for reference
+        let sol_usd = match self.price_service.get_sol_price().await {
+            Ok(price) => Some(sol_balance * price),
+            Err(e) => {
+                info!("Error fetching SOL price: {}", e);
+                None
+            }
+        };
+        usd_values.push((String::from("SOL"), sol_usd));
+
+        // Get prices for other tokens if there are any
+        if !token_balances.is_empty() {
+            for token in token_balances {
+                if token.amount > 0.0 {
+                    match self
+                        .price_service
+                        .get_token_price(&token.mint_address)
+                        .await
+                    {
+                        Ok(price_info) => {
+                            let usd_value = token.amount * price_info.price_in_usdc;
+                            usd_values.push((token.symbol.clone(), Some(usd_value)));
+                        }
+                        Err(e) => {
+                            // Shown as "price n/a", the rest of the balances are still rendered
+                            info!("Error fetching price for {}: {}", token.symbol, e);
+                            usd_values.push((token.symbol.clone(), None));
+                        }
+                    }
+                }
+            }
+        }
+
+        usd_values
+    }
+
+    /// Forget the cached balances of a wallet, e.g. after a trade changed them
+    pub fn invalidate(address: &str) {
+        SHARED_BALANCE_CACHE.write().unwrap().remove(address);
+    }
 }
 
 #[async_trait]
@@ -42,14 +178,26 @@ impl BalanceInteractor for BalanceInteractorImpl {
     async fn get_wallet_balances(
         &self,
         telegram_id: i64,
-    ) -> Result<(String, f64, Vec<TokenBalance>, Vec<(String, f64)>)> {
+        force_refresh: bool,
+    ) -> Result<WalletBalances> {
         // Get user's wallet address
         let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
 
+        // Read from the settings on every call, the threshold may have changed since caching
+        let low_balance_sol = user.get_low_balance_sol();
+
         let address = user
             .solana_address
             .ok_or_else(|| BotError::WalletNotFound)?;
 
+        if let Some(balances) = Self::cached_balances(&address, force_refresh) {
+            debug!("Balance cache hit: {}", address);
+            return Ok(WalletBalances {
+                low_balance_sol,
+                ..balances
+            });
+        }
+
         // Get SOL balance
         let sol_balance = solana::get_sol_balance(&self.solana_client, &address).await?;
 
@@ -61,44 +209,218 @@ impl BalanceInteractor for BalanceInteractorImpl {
             }
         };
 
-        // Initialize vector for USD values
-        let mut usd_values = Vec::new();
+        let usd_values = self.fetch_usd_values(sol_balance, &token_balances).await;
 
-        // Get SOL price first for reference
-        let sol_price = match self.price_service.get_sol_price().await {
-            Ok(price) => price,
-            Err(e) => {
-                info!("Error fetching SOL price: {}. Using fallback.", e);
-                0.0 // Fallback to zero if price service fails
-            }
+        let balances = WalletBalances {
+            address,
+            sol_balance,
+            token_balances,
+            usd_values,
+            updated_at: Utc::now(),
+            low_balance_sol,
         };
+        Self::cache_balances(&balances);
 
-        // Calculate SOL USD value
-        let sol_usd = sol_balance * sol_price;
-        usd_values.push((String::from("SOL"), sol_usd));
+        Ok(balances)
+    }
+}
 
-        // Get prices for other tokens if there are any
-        if !token_balances.is_empty() {
-            for token in &token_balances {
-                if token.amount > 0.0 {
-                    match self
-                        .price_service
-                        .get_token_price(&token.mint_address)
-                        .await
-                    {
-                        Ok(price_info) => {
-                            let usd_value = token.amount * price_info.price_in_usdc;
-                            usd_values.push((token.symbol.clone(), usd_value));
-                        }
-                        Err(e) => {
-                            info!("Error fetching price for {}: {}", token.symbol, e);
-                            usd_values.push((token.symbol.clone(), 0.0)); // Default to 0 if error
-                        }
-                    }
-                }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::TokenPrice;
+    use crate::solana::jupiter::price_service::PriceService;
+    use sqlx::postgres::PgPoolOptions;
+
+    fn balances(address: &str, sol_balance: f64) -> WalletBalances {
+        WalletBalances {
+            address: address.to_string(),
+            sol_balance,
+            token_balances: Vec::new(),
+            usd_values: vec![("SOL".to_string(), Some(sol_balance * 150.0))],
+            updated_at: Utc::now(),
+            low_balance_sol: 0.0,
+        }
+    }
+
+    fn empty_cache() -> BalanceCache {
+        RwLock::new(HashMap::new())
+    }
+
+    #[test]
+    fn balances_are_reused_within_the_ttl() {
+        let cache = empty_cache();
+        let fetched_at = Instant::now();
+        store_balances(&cache, &balances("wallet-a", 1.5), fetched_at);
+
+        let cached = lookup_balances(
+            &cache,
+            "wallet-a",
+            false,
+            fetched_at + Duration::from_secs(14),
+        );
+
+        assert_eq!(cached.map(|b| b.sol_balance), Some(1.5));
+    }
+
+    #[test]
+    fn balances_expire_after_the_ttl() {
+        let cache = empty_cache();
+        let fetched_at = Instant::now();
+        store_balances(&cache, &balances("wallet-a", 1.5), fetched_at);
+
+        let cached = lookup_balances(&cache, "wallet-a", false, fetched_at + BALANCE_CACHE_TTL);
+
+        assert!(cached.is_none());
+    }
+
+    #[test]
+    fn forced_refresh_bypasses_fresh_balances() {
+        let cache = empty_cache();
+        let fetched_at = Instant::now();
+        store_balances(&cache, &balances("wallet-a", 1.5), fetched_at);
+
+        assert!(lookup_balances(&cache, "wallet-a", true, fetched_at).is_none());
+        // The bypass doesn't evict them for the next menu navigation
+        assert!(lookup_balances(&cache, "wallet-a", false, fetched_at).is_some());
+    }
+
+    #[test]
+    fn balances_are_cached_per_wallet_address() {
+        let cache = empty_cache();
+        let fetched_at = Instant::now();
+        store_balances(&cache, &balances("wallet-a", 1.5), fetched_at);
+        store_balances(&cache, &balances("wallet-b", 0.2), fetched_at);
+
+        let a = lookup_balances(&cache, "wallet-a", false, fetched_at);
+        let b = lookup_balances(&cache, "wallet-b", false, fetched_at);
+
+        assert_eq!(a.map(|b| b.sol_balance), Some(1.5));
+        assert_eq!(b.map(|b| b.sol_balance), Some(0.2));
+        assert!(lookup_balances(&cache, "wallet-c", false, fetched_at).is_none());
+    }
+
+    #[test]
+    fn storing_drops_expired_wallets() {
+        let cache = empty_cache();
+        let fetched_at = Instant::now();
+        store_balances(&cache, &balances("wallet-a", 1.5), fetched_at);
+
+        store_balances(
+            &cache,
+            &balances("wallet-b", 0.2),
+            fetched_at + BALANCE_CACHE_TTL,
+        );
+
+        let cache = cache.read().unwrap();
+        assert!(!cache.contains_key("wallet-a"));
+        assert!(cache.contains_key("wallet-b"));
+    }
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+    const WIF: &str = "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm";
+
+    // SOL at $150, BONK at $0.00002 and no price for anything else
+    struct Prices;
+
+    #[async_trait]
+    impl PriceService for Prices {
+        async fn get_sol_price(&self) -> Result<f64> {
+            Ok(150.0)
+        }
+
+        async fn get_token_price(&self, token_id: &str) -> Result<TokenPrice> {
+            if token_id != BONK {
+                return Err(anyhow!("No price for {}", token_id));
             }
+            Ok(TokenPrice {
+                token_id: token_id.to_string(),
+                symbol: "BONK".to_string(),
+                price_in_sol: 0.0000001,
+                price_in_usdc: 0.00002,
+                timestamp: 0,
+                price_change_24h: None,
+            })
+        }
+
+        async fn get_prices(&self, _vs_token: Option<&str>) -> Result<HashMap<String, f64>> {
+            Ok(HashMap::new())
+        }
+    }
+
+    fn interactor() -> BalanceInteractorImpl {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        let rpc = ResilientRpcClient::new(&["http://127.0.0.1:8899".to_string()]).unwrap();
+        BalanceInteractorImpl::new(Arc::new(pool), Arc::new(rpc), Arc::new(Prices))
+    }
+
+    fn token(symbol: &str, mint: &str, amount: f64) -> TokenBalance {
+        TokenBalance {
+            symbol: symbol.to_string(),
+            amount,
+            mint_address: mint.to_string(),
+            decimals: 6,
         }
+    }
+
+    #[tokio::test]
+    async fn failed_token_price_keeps_the_other_values() {
+        let tokens = vec![token("BONK", BONK, 1_000_000.0), token("WIF", WIF, 10.0)];
+
+        let usd_values = interactor().fetch_usd_values(2.0, &tokens).await;
+
+        assert_eq!(
+            usd_values,
+            vec![
+                ("SOL".to_string(), Some(300.0)),
+                ("BONK".to_string(), Some(20.0)),
+                ("WIF".to_string(), None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn empty_token_accounts_are_not_priced() {
+        let tokens = vec![token("BONK", BONK, 0.0)];
+
+        let usd_values = interactor().fetch_usd_values(1.0, &tokens).await;
+
+        assert_eq!(usd_values, vec![("SOL".to_string(), Some(150.0))]);
+    }
+
+    #[test]
+    fn total_skips_tokens_without_a_price() {
+        let balances = WalletBalances {
+            usd_values: vec![
+                ("SOL".to_string(), Some(300.0)),
+                ("BONK".to_string(), Some(20.0)),
+                ("WIF".to_string(), None),
+            ],
+            ..balances("wallet-a", 2.0)
+        };
+
+        assert_eq!(balances.total_usd(), 320.0);
+    }
+
+    #[test]
+    fn low_balance_warning_shows_below_the_threshold_only() {
+        let low = WalletBalances {
+            low_balance_sol: 0.01,
+            ..balances("wallet-a", 0.005)
+        };
+        let enough = WalletBalances {
+            low_balance_sol: 0.01,
+            ..balances("wallet-a", 0.01)
+        };
+        let off = WalletBalances {
+            low_balance_sol: 0.0,
+            ..balances("wallet-a", 0.0)
+        };
 
-        Ok((address, sol_balance, token_balances, usd_values))
+        assert_eq!(low.low_balance_warning(), Some(0.01));
+        assert_eq!(enough.low_balance_warning(), None);
+        assert_eq!(off.low_balance_warning(), None);
     }
 }
@@ -0,0 +1,70 @@
+use crate::interactor::db;
+use crate::solana;
+use crate::solana::client::ResilientRpcClient;
+use crate::solana::tokens::spl::{
+    close_empty_token_accounts, find_empty_token_accounts, EmptyTokenAccount, TokenAccountCleanup,
+};
+use crate::solana::wallet::parse_pubkey;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Empty token accounts of a wallet and the rent that closing them returns
+#[derive(Debug, Clone)]
+pub struct CleanupPreview {
+    pub accounts: Vec<EmptyTokenAccount>,
+    pub reclaimable_lamports: u64,
+}
+
+#[async_trait]
+pub trait CleanupInteractor: Send + Sync {
+    async fn preview_cleanup(&self, telegram_id: i64) -> Result<CleanupPreview>;
+    async fn execute_cleanup(&self, telegram_id: i64) -> Result<TokenAccountCleanup>;
+}
+
+pub struct CleanupInteractorImpl {
+    db_pool: Arc<PgPool>,
+    solana_client: Arc<ResilientRpcClient>,
+}
+
+impl CleanupInteractorImpl {
+    pub fn new(db_pool: Arc<PgPool>, solana_client: Arc<ResilientRpcClient>) -> Self {
+        Self {
+            db_pool,
+            solana_client,
+        }
+    }
+}
+
+#[async_trait]
+impl CleanupInteractor for CleanupInteractorImpl {
+    async fn preview_cleanup(&self, telegram_id: i64) -> Result<CleanupPreview> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+
+        let address = user.solana_address.ok_or_else(|| {
+            anyhow!("Wallet not found. Use /create_wallet to create a new wallet.")
+        })?;
+        let owner = parse_pubkey(&address)?;
+
+        let accounts = find_empty_token_accounts(&self.solana_client, &owner).await?;
+        let reclaimable_lamports = accounts.iter().map(|account| account.lamports).sum();
+
+        Ok(CleanupPreview {
+            accounts,
+            reclaimable_lamports,
+        })
+    }
+
+    async fn execute_cleanup(&self, telegram_id: i64) -> Result<TokenAccountCleanup> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+
+        let keypair_base58 = user.encrypted_private_key.ok_or_else(|| {
+            anyhow!("Wallet not found. Use /create_wallet to create a new wallet.")
+        })?;
+        let keypair = solana::keypair_from_base58(&keypair_base58)
+            .map_err(|e| anyhow!("Error with private key: {}", e))?;
+
+        close_empty_token_accounts(&self.solana_client, &keypair).await
+    }
+}
@@ -1,33 +1,82 @@
 use crate::entity::{
-    LimitOrder, LimitOrderStatus, OrderType, Swap, Trade, Transaction, User, WatchlistItem,
+    AlertDirection, BlacklistedToken, DcaOrder, DcaOrderStatus, DcaSchedule, Explorer,
+    FollowedWallet, LimitOrder, LimitOrderFill, LimitOrderStatus, NewLimitOrder, NotificationKind,
+    PriceAlert, PriceCurrency, PriorityFee, Swap, Trade, TradeLimits, Transaction, User,
+    WatchlistItem, WatchlistSort,
 };
-use chrono::Utc;
-use log::info;
-use sqlx::{postgres::PgQueryResult, Error as SqlxError, PgPool, Row};
+use crate::i18n::Locale;
+use crate::solana::jupiter::{FeeCollection, RouteOptions};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use sqlx::{postgres::PgQueryResult, Error as SqlxError, PgExecutor, PgPool, Row};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Whether the error means the connection was lost rather than the query being wrong
+///
+/// Covers dropped sockets, pool timeouts and the errors Postgres sends while shutting down
+/// or starting up.
+pub fn is_connection_error(e: &SqlxError) -> bool {
+    match e {
+        SqlxError::Io(_) | SqlxError::Tls(_) | SqlxError::Protocol(_) | SqlxError::PoolTimedOut => {
+            true
+        }
+        // admin_shutdown, crash_shutdown, cannot_connect_now
+        SqlxError::Database(e) => {
+            matches!(e.code().as_deref(), Some("57P01" | "57P02" | "57P03"))
+        }
+        _ => false,
+    }
+}
+
+// Run a query once more when its connection was lost, e.g. after a Postgres restart.
+// Only for reads, a write may have been applied before the connection dropped.
+async fn retry_on_connection_error<T, F, Fut>(operation: &str, query: F) -> Result<T, SqlxError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, SqlxError>>,
+{
+    match query().await {
+        Err(e) if is_connection_error(&e) => {
+            warn!(
+                "Database connection error in {}, retrying once: {}",
+                operation, e
+            );
+            query().await
+        }
+        result => result,
+    }
+}
 
 // Check if user exists in database
 pub async fn check_user_exists(pool: &PgPool, telegram_id: i64) -> Result<bool, SqlxError> {
-    let row = sqlx::query("SELECT COUNT(*) as count FROM users WHERE telegram_id = $1")
-        .bind(telegram_id)
-        .fetch_one(pool)
-        .await?;
+    let row = retry_on_connection_error("check_user_exists", || {
+        sqlx::query("SELECT COUNT(*) as count FROM users WHERE telegram_id = $1")
+            .bind(telegram_id)
+            .fetch_one(pool)
+    })
+    .await?;
 
     let count: i64 = row.try_get("count")?;
     Ok(count > 0)
 }
 
-// Create new user in database
+// Create new user in database, `referred_by` is the Telegram ID of the referrer
 pub async fn create_user(
     pool: &PgPool,
     telegram_id: i64,
     username: Option<String>,
+    referred_by: Option<i64>,
 ) -> Result<i32, SqlxError> {
     let row = sqlx::query(
-        "INSERT INTO users (telegram_id, username, created_at) VALUES ($1, $2, $3) RETURNING id",
+        "INSERT INTO users (telegram_id, username, created_at, referred_by)
+         VALUES ($1, $2, $3, $4) RETURNING id",
     )
     .bind(telegram_id)
     .bind(username)
     .bind(Utc::now())
+    .bind(referred_by)
     .fetch_one(pool)
     .await?;
 
@@ -39,10 +88,12 @@ pub async fn create_user(
 
 // Get user by telegram_id
 pub async fn get_user_by_telegram_id(pool: &PgPool, telegram_id: i64) -> Result<User, SqlxError> {
-    let row = sqlx::query("SELECT * FROM users WHERE telegram_id = $1")
-        .bind(telegram_id)
-        .fetch_one(pool)
-        .await?;
+    let row = retry_on_connection_error("get_user_by_telegram_id", || {
+        sqlx::query("SELECT * FROM users WHERE telegram_id = $1")
+            .bind(telegram_id)
+            .fetch_one(pool)
+    })
+    .await?;
 
     let user = User {
         id: row.try_get("id")?,
@@ -53,6 +104,8 @@ pub async fn get_user_by_telegram_id(pool: &PgPool, telegram_id: i64) -> Result<
         mnemonic: row.try_get("mnemonic")?,
         settings: row.try_get("settings")?,
         created_at: row.try_get("created_at")?,
+        referred_by: row.try_get("referred_by")?,
+        last_seen_signature: row.try_get("last_seen_signature")?,
     };
 
     Ok(user)
@@ -144,6 +197,52 @@ pub async fn record_swap(
     Ok(id)
 }
 
+/// A platform fee taken from the trade with signature `trade_signature`
+#[derive(Debug)]
+pub struct FeeRecord<'a> {
+    pub telegram_id: i64,
+    pub trade_signature: &'a str,
+    pub amount_sol: f64,
+    pub fee_bps: u8,
+    pub collection: &'a str,
+}
+
+/// Record the platform fee of a buy, `tx_signature` is the transaction that paid it
+pub async fn record_fee(
+    pool: &PgPool,
+    fee: &FeeRecord<'_>,
+    tx_signature: &Option<String>,
+    status: &str,
+) -> Result<i32, SqlxError> {
+    let user = get_user_by_telegram_id(pool, fee.telegram_id).await?;
+
+    insert_fee(pool, user.id, fee, tx_signature.as_deref(), status).await
+}
+
+async fn insert_fee<'e>(
+    executor: impl PgExecutor<'e>,
+    user_id: i32,
+    fee: &FeeRecord<'_>,
+    tx_signature: Option<&str>,
+    status: &str,
+) -> Result<i32, SqlxError> {
+    let row = sqlx::query("INSERT INTO fees (user_id, trade_signature, amount_sol, fee_bps, collection, tx_signature, status) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id")
+        .bind(user_id)
+        .bind(fee.trade_signature)
+        .bind(fee.amount_sol)
+        .bind(fee.fee_bps as i32)
+        .bind(fee.collection)
+        .bind(tx_signature)
+        .bind(status)
+        .fetch_one(executor)
+        .await?;
+
+    let id: i32 = row.try_get("id")?;
+    info!("Recorded platform fee with ID: {}", id);
+
+    Ok(id)
+}
+
 // Get user transaction history
 pub async fn get_user_transactions(
     pool: &PgPool,
@@ -175,6 +274,30 @@ pub async fn get_user_transactions(
     Ok(transactions)
 }
 
+// Get a page of user transaction history, newest first
+pub async fn get_user_transactions_paginated(
+    pool: &PgPool,
+    telegram_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Transaction>, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    // amount is NUMERIC in the schema, cast it so it decodes into f64
+    let transactions = sqlx::query_as::<_, Transaction>(
+        "SELECT id, user_id, recipient_address, amount::DOUBLE PRECISION AS amount, token_symbol, tx_signature, timestamp, status
+         FROM transactions WHERE user_id = $1 ORDER BY timestamp DESC LIMIT $2 OFFSET $3",
+    )
+    .bind(user.id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(transactions)
+}
+
 // Get user swap history
 pub async fn get_user_swaps(pool: &PgPool, telegram_id: i64) -> Result<Vec<Swap>, SqlxError> {
     // Get user ID from telegram_id
@@ -204,22 +327,129 @@ pub async fn get_user_swaps(pool: &PgPool, telegram_id: i64) -> Result<Vec<Swap>
     Ok(swaps)
 }
 
+// Get a page of user swap history, newest first
+pub async fn get_user_swaps_paginated(
+    pool: &PgPool,
+    telegram_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Swap>, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let swaps = sqlx::query_as::<_, Swap>(
+        "SELECT * FROM swaps WHERE user_id = $1 ORDER BY timestamp DESC LIMIT $2 OFFSET $3",
+    )
+    .bind(user.id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(swaps)
+}
+
 // Record a trade operation in the database
 pub async fn record_trade(
     pool: &PgPool,
-    telegram_id: i64,
-    token_address: &str,
-    token_symbol: &str,
-    amount: f64,
-    price_in_sol: f64,
-    total_paid: f64,
-    trade_type: &str,
+    trade: &TradeRecord<'_>,
     tx_signature: &Option<String>,
     status: &str,
 ) -> Result<i32, SqlxError> {
     // Get user ID from telegram_id
-    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+    let user = get_user_by_telegram_id(pool, trade.telegram_id).await?;
+
+    insert_trade(pool, user.id, trade, tx_signature.as_deref(), status).await
+}
+
+/// Bookkeeping settled by a successful trade, written in the same transaction as the trade
+#[derive(Debug, Default)]
+pub struct TradeSettlement<'a> {
+    /// Limit order the trade executed a slice of
+    pub limit_order_fill: Option<&'a LimitOrderFill>,
+    /// Platform fee Jupiter took from the swap, in SOL, and its basis points
+    pub platform_fee: Option<(f64, u8)>,
+}
+
+/// A trade to record, with its amounts in tokens and SOL
+#[derive(Debug)]
+pub struct TradeRecord<'a> {
+    pub telegram_id: i64,
+    pub token_address: &'a str,
+    pub token_symbol: &'a str,
+    pub amount: f64,
+    pub price_in_sol: f64,
+    pub total_paid: f64,
+    pub trade_type: &'a str,
+}
+
+/// Record a successful trade together with what it settles, all or nothing
+///
+/// A crash between separate writes would leave a filled limit order active, so it would be
+/// executed again, or a fee without the trade it was taken from.
+pub async fn record_settled_trade(
+    pool: &PgPool,
+    trade: &TradeRecord<'_>,
+    tx_signature: &str,
+    settlement: &TradeSettlement<'_>,
+) -> Result<i32, SqlxError> {
+    let user = get_user_by_telegram_id(pool, trade.telegram_id).await?;
+
+    // Dropping the transaction on an error rolls back every write
+    let mut tx = pool.begin().await?;
+
+    let id = insert_trade(&mut *tx, user.id, trade, Some(tx_signature), "SUCCESS").await?;
+
+    if let Some(fill) = settlement.limit_order_fill {
+        update_limit_order_fill(
+            &mut *tx,
+            fill.order_id,
+            fill.filled_amount,
+            &fill.status,
+            Some(tx_signature),
+        )
+        .await?;
+    }
+
+    if let Some((amount_sol, fee_bps)) = settlement.platform_fee {
+        let fee = FeeRecord {
+            telegram_id: trade.telegram_id,
+            trade_signature: tx_signature,
+            amount_sol,
+            fee_bps,
+            collection: &FeeCollection::Jupiter.to_string(),
+        };
+        insert_fee(&mut *tx, user.id, &fee, Some(tx_signature), "SUCCESS").await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Record a limit order fill on its own, for a trade that couldn't be recorded with it
+pub async fn record_limit_order_fill(
+    pool: &PgPool,
+    fill: &LimitOrderFill,
+    tx_signature: &str,
+) -> Result<PgQueryResult, SqlxError> {
+    update_limit_order_fill(
+        pool,
+        fill.order_id,
+        fill.filled_amount,
+        &fill.status,
+        Some(tx_signature),
+    )
+    .await
+}
 
+async fn insert_trade<'e>(
+    executor: impl PgExecutor<'e>,
+    user_id: i32,
+    trade: &TradeRecord<'_>,
+    tx_signature: Option<&str>,
+    status: &str,
+) -> Result<i32, SqlxError> {
     let price_in_usdc = 0.0; // In a real implementation, get the actual USDC price
 
     let row = sqlx::query(
@@ -227,18 +457,18 @@ pub async fn record_trade(
          VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
          RETURNING id",
     )
-        .bind(user.id)
-        .bind(token_address)
-        .bind(token_symbol)
-        .bind(amount)
-        .bind(price_in_sol)
+        .bind(user_id)
+        .bind(trade.token_address)
+        .bind(trade.token_symbol)
+        .bind(trade.amount)
+        .bind(trade.price_in_sol)
         .bind(price_in_usdc)
-        .bind(total_paid)
-        .bind(trade_type)
-        .bind(tx_signature.as_deref())
+        .bind(trade.total_paid)
+        .bind(trade.trade_type)
+        .bind(tx_signature)
         .bind(Utc::now())
         .bind(status)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
     let id: i32 = row.try_get("id")?;
@@ -279,26 +509,93 @@ pub async fn get_user_trades(pool: &PgPool, telegram_id: i64) -> Result<Vec<Trad
     Ok(trades)
 }
 
+// Get a page of user trade history, newest first
+pub async fn get_user_trades_paginated(
+    pool: &PgPool,
+    telegram_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Trade>, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let trades = sqlx::query_as::<_, Trade>(
+        "SELECT * FROM trades WHERE user_id = $1 ORDER BY timestamp DESC LIMIT $2 OFFSET $3",
+    )
+    .bind(user.id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(trades)
+}
+
+// Get user trades made at or after `since`, newest first
+pub async fn get_trades_since(
+    pool: &PgPool,
+    telegram_id: i64,
+    since: DateTime<Utc>,
+) -> Result<Vec<Trade>, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let trades = sqlx::query_as::<_, Trade>(
+        "SELECT * FROM trades WHERE user_id = $1 AND timestamp >= $2 ORDER BY timestamp DESC",
+    )
+    .bind(user.id)
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(trades)
+}
+
+// Get user's successful trades grouped by token address, oldest first
+pub async fn get_trades_by_token(
+    pool: &PgPool,
+    telegram_id: i64,
+) -> Result<HashMap<String, Vec<Trade>>, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let rows = sqlx::query_as::<_, Trade>(
+        "SELECT * FROM trades
+         WHERE user_id = $1 AND status = 'SUCCESS'
+         ORDER BY timestamp ASC",
+    )
+    .bind(user.id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut trades_by_token: HashMap<String, Vec<Trade>> = HashMap::new();
+    for trade in rows {
+        trades_by_token
+            .entry(trade.token_address.clone())
+            .or_default()
+            .push(trade);
+    }
+
+    Ok(trades_by_token)
+}
+
 pub async fn create_limit_order(
     pool: &PgPool,
     telegram_id: i64,
-    token_address: &str,
-    token_symbol: &str,
-    order_type: &OrderType,
-    price_in_sol: f64,
-    total_sol: f64,
+    order: &NewLimitOrder,
     current_price_in_sol: Option<f64>,
+    expires_at: Option<DateTime<Utc>>,
 ) -> Result<i32, SqlxError> {
     // Get user ID from telegram_id
     let user = get_user_by_telegram_id(pool, telegram_id).await?;
 
-    let order_type_str = order_type.to_string();
+    let order_type_str = order.order_type.to_string();
     let status = LimitOrderStatus::Active.to_string();
     let now = Utc::now();
 
     // Calculate token amount based on total_sol and price_in_sol
-    let amount = if price_in_sol > 0.0 {
-        total_sol / price_in_sol
+    let amount = if order.price_in_sol > 0.0 {
+        order.total_sol / order.price_in_sol
     } else {
         0.0
     };
@@ -307,23 +604,28 @@ pub async fn create_limit_order(
         "INSERT INTO limit_orders (
             user_id, token_address, token_symbol, order_type,
             price_in_sol, amount, total_sol, current_price_in_sol,
-            created_at, updated_at, status, retry_count
+            created_at, updated_at, status, retry_count, expires_at,
+            trigger_metric, trigger_value, price_currency
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
         RETURNING id",
     )
     .bind(user.id)
-    .bind(token_address)
-    .bind(token_symbol)
+    .bind(&order.token_address)
+    .bind(&order.token_symbol)
     .bind(order_type_str)
-    .bind(price_in_sol)
+    .bind(order.price_in_sol)
     .bind(amount)
-    .bind(total_sol)
+    .bind(order.total_sol)
     .bind(current_price_in_sol)
     .bind(now)
     .bind(now)
     .bind(status)
     .bind(0) // Initial retry_count = 0
+    .bind(expires_at)
+    .bind(order.trigger_metric.to_string())
+    .bind(order.trigger_value)
+    .bind(order.price_currency.to_string())
     .fetch_one(pool)
     .await?;
 
@@ -343,6 +645,7 @@ pub async fn get_active_limit_orders(
     let rows = sqlx::query_as::<_, LimitOrder>(
         "SELECT * FROM limit_orders
          WHERE user_id = $1 AND status = $2
+           AND (expires_at IS NULL OR expires_at > NOW())
          ORDER BY created_at DESC",
     )
     .bind(user.id)
@@ -428,6 +731,36 @@ pub async fn update_limit_order_status(
     Ok(result)
 }
 
+// Record an executed slice of a limit order, the order stays active until it is filled
+async fn update_limit_order_fill<'e>(
+    executor: impl PgExecutor<'e>,
+    order_id: i32,
+    filled_amount: f64,
+    status: &LimitOrderStatus,
+    tx_signature: Option<&str>,
+) -> Result<PgQueryResult, SqlxError> {
+    // A successful slice resets the retry counter, it only counts consecutive failures
+    let result = sqlx::query(
+        "UPDATE limit_orders
+         SET filled_amount = $1, status = $2, tx_signature = COALESCE($3, tx_signature),
+             retry_count = 0, retry_slippage = NULL, next_retry_at = NULL, updated_at = $4
+         WHERE id = $5",
+    )
+    .bind(filled_amount)
+    .bind(status.to_string())
+    .bind(tx_signature)
+    .bind(Utc::now())
+    .bind(order_id)
+    .execute(executor)
+    .await?;
+
+    info!(
+        "Recorded limit order fill: id={}, filled_amount={}, status={}",
+        order_id, filled_amount, status
+    );
+    Ok(result)
+}
+
 /// Update current price for a limit order
 pub async fn update_limit_order_current_price(
     pool: &PgPool,
@@ -454,6 +787,28 @@ pub async fn update_limit_order_current_price(
     Ok(result)
 }
 
+/// Mark a limit order as failed without retrying, the reason is shown to the user
+pub async fn fail_limit_order(
+    pool: &PgPool,
+    order_id: i32,
+    reason: &str,
+) -> Result<PgQueryResult, SqlxError> {
+    let result = sqlx::query(
+        "UPDATE limit_orders
+         SET status = $1, failure_reason = $2, updated_at = $3
+         WHERE id = $4",
+    )
+    .bind(LimitOrderStatus::Failed.to_string())
+    .bind(reason)
+    .bind(Utc::now())
+    .bind(order_id)
+    .execute(pool)
+    .await?;
+
+    info!("Limit order {} failed: {}", order_id, reason);
+    Ok(result)
+}
+
 /// Get a specific limit order by ID
 pub async fn get_limit_order_by_id(
     pool: &PgPool,
@@ -467,6 +822,41 @@ pub async fn get_limit_order_by_id(
     Ok(order)
 }
 
+/// Change the price and volume of an active limit order, `false` when it is no longer
+/// active or has been partially filled meanwhile
+pub async fn update_limit_order_params(
+    pool: &PgPool,
+    order_id: i32,
+    price_in_sol: f64,
+    amount: f64,
+    total_sol: f64,
+) -> Result<bool, SqlxError> {
+    // Failed attempts were at the old price, the edited order starts without a backoff
+    let result = sqlx::query(
+        "UPDATE limit_orders
+         SET price_in_sol = $1, amount = $2, total_sol = $3, retry_count = 0,
+             retry_slippage = NULL, next_retry_at = NULL, updated_at = $4
+         WHERE id = $5 AND status = $6 AND filled_amount = 0",
+    )
+    .bind(price_in_sol)
+    .bind(amount)
+    .bind(total_sol)
+    .bind(Utc::now())
+    .bind(order_id)
+    .bind(LimitOrderStatus::Active.to_string())
+    .execute(pool)
+    .await?;
+
+    let updated = result.rows_affected() == 1;
+    if updated {
+        info!(
+            "Updated limit order #{}: price={} SOL, amount={}, total={} SOL",
+            order_id, price_in_sol, amount, total_sol
+        );
+    }
+    Ok(updated)
+}
+
 /// Cancel a limit order
 pub async fn cancel_limit_order(pool: &PgPool, order_id: i32) -> Result<PgQueryResult, SqlxError> {
     update_limit_order_status(pool, order_id, &LimitOrderStatus::Cancelled, None).await
@@ -498,20 +888,25 @@ pub async fn cancel_all_limit_orders(pool: &PgPool, telegram_id: i64) -> Result<
     Ok(count)
 }
 
-/// Update retry count for a limit order
+/// Update retry count for a limit order, the next attempt uses `retry_slippage` and
+/// isn't made before `next_retry_at`
 pub async fn update_limit_order_retry_count(
     pool: &PgPool,
     order_id: i32,
     retry_count: i32,
+    retry_slippage: Option<f64>,
+    next_retry_at: DateTime<Utc>,
 ) -> Result<PgQueryResult, SqlxError> {
     let now = Utc::now();
 
     let result = sqlx::query(
         "UPDATE limit_orders
-         SET retry_count = $1, updated_at = $2
-         WHERE id = $3",
+         SET retry_count = $1, retry_slippage = $2, next_retry_at = $3, updated_at = $4
+         WHERE id = $5",
     )
     .bind(retry_count)
+    .bind(retry_slippage)
+    .bind(next_retry_at)
     .bind(now)
     .bind(order_id)
     .execute(pool)
@@ -526,15 +921,39 @@ pub async fn update_limit_order_retry_count(
 
 /// Get all active limit orders across all users
 pub async fn get_all_active_limit_orders(pool: &PgPool) -> Result<Vec<LimitOrder>, SqlxError> {
+    let rows = retry_on_connection_error("get_all_active_limit_orders", || {
+        sqlx::query_as::<_, LimitOrder>(
+            "SELECT * FROM limit_orders
+             WHERE status = $1
+               AND (expires_at IS NULL OR expires_at > NOW())
+             ORDER BY created_at ASC",
+        )
+        .bind(LimitOrderStatus::Active.to_string())
+        .fetch_all(pool)
+    })
+    .await?;
+
+    Ok(rows)
+}
+
+/// Mark active limit orders past their expiry as expired and return them
+pub async fn expire_limit_orders(pool: &PgPool) -> Result<Vec<LimitOrder>, SqlxError> {
     let rows = sqlx::query_as::<_, LimitOrder>(
-        "SELECT * FROM limit_orders
-         WHERE status = $1
-         ORDER BY created_at ASC",
+        "UPDATE limit_orders
+         SET status = $1, updated_at = $2
+         WHERE status = $3 AND expires_at IS NOT NULL AND expires_at <= $2
+         RETURNING *",
     )
+    .bind(LimitOrderStatus::Expired.to_string())
+    .bind(Utc::now())
     .bind(LimitOrderStatus::Active.to_string())
     .fetch_all(pool)
     .await?;
 
+    if !rows.is_empty() {
+        info!("Expired {} limit orders", rows.len());
+    }
+
     Ok(rows)
 }
 
@@ -548,215 +967,1408 @@ pub async fn get_user_by_id(pool: &PgPool, user_id: i32) -> Result<User, SqlxErr
     Ok(row)
 }
 
-// Update user settings
-pub async fn update_user_settings(
+/// Telegram IDs of all registered users, oldest users first
+pub async fn get_all_user_telegram_ids(pool: &PgPool) -> Result<Vec<i64>, SqlxError> {
+    let ids = sqlx::query_scalar::<_, i64>("SELECT telegram_id FROM users ORDER BY id ASC")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(ids)
+}
+
+/// Users that have a wallet, oldest users first
+pub async fn get_users_with_wallets(pool: &PgPool) -> Result<Vec<User>, SqlxError> {
+    let users = sqlx::query_as::<_, User>(
+        "SELECT * FROM users WHERE solana_address IS NOT NULL ORDER BY id ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(users)
+}
+
+/// Remember the newest wallet transaction checked for deposits
+pub async fn update_user_last_seen_signature(
     pool: &PgPool,
-    telegram_id: i64,
-    settings: &serde_json::Value,
+    user_id: i32,
+    last_seen_signature: &str,
 ) -> Result<PgQueryResult, SqlxError> {
-    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
-        .bind(settings)
-        .bind(telegram_id)
+    let result = sqlx::query("UPDATE users SET last_seen_signature = $1 WHERE id = $2")
+        .bind(last_seen_signature)
+        .bind(user_id)
         .execute(pool)
         .await?;
 
-    info!(
-        "Updated settings for user with Telegram ID: {}",
-        telegram_id
-    );
-
     Ok(result)
 }
 
-// Update user slippage setting
-pub async fn update_user_slippage(
+/// Which of the signatures belong to withdrawals, trades or swaps the user made through the bot
+pub async fn get_user_recorded_signatures(
     pool: &PgPool,
-    telegram_id: i64,
-    slippage: f64,
-) -> Result<PgQueryResult, SqlxError> {
-    // Get current user settings
-    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+    user_id: i32,
+    signatures: &[String],
+) -> Result<Vec<String>, SqlxError> {
+    let rows = sqlx::query_scalar::<_, String>(
+        "SELECT tx_signature FROM transactions WHERE user_id = $1 AND tx_signature = ANY($2)
+         UNION
+         SELECT tx_signature FROM trades WHERE user_id = $1 AND tx_signature = ANY($2)
+         UNION
+         SELECT tx_signature FROM swaps WHERE user_id = $1 AND tx_signature = ANY($2)",
+    )
+    .bind(user_id)
+    .bind(signatures)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Number of users who registered through the referral link of the given user
+pub async fn count_referrals(pool: &PgPool, telegram_id: i64) -> Result<i64, SqlxError> {
+    let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users WHERE referred_by = $1")
+        .bind(telegram_id)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(count)
+}
+
+/// Number of registered users
+pub async fn count_users(pool: &PgPool) -> Result<i64, SqlxError> {
+    let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
+        .fetch_one(pool)
+        .await?;
+
+    Ok(count)
+}
+
+/// Round trip time of a trivial query, used by the status command
+pub async fn check_connection(pool: &PgPool) -> Result<Duration, SqlxError> {
+    let started = Instant::now();
+    sqlx::query("SELECT 1").execute(pool).await?;
+
+    Ok(started.elapsed())
+}
+
+/// Number of active, unexpired limit orders across all users
+pub async fn count_active_limit_orders(pool: &PgPool) -> Result<i64, SqlxError> {
+    let count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM limit_orders
+         WHERE status = $1
+           AND (expires_at IS NULL OR expires_at > NOW())",
+    )
+    .bind(LimitOrderStatus::Active.to_string())
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Most recently registered users, newest first
+pub async fn get_recent_users(pool: &PgPool, limit: i64) -> Result<Vec<User>, SqlxError> {
+    let users = sqlx::query_as::<_, User>("SELECT * FROM users ORDER BY created_at DESC LIMIT $1")
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(users)
+}
+
+// Update user settings
+pub async fn update_user_settings(
+    pool: &PgPool,
+    telegram_id: i64,
+    settings: &serde_json::Value,
+) -> Result<PgQueryResult, SqlxError> {
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Updated settings for user with Telegram ID: {}",
+        telegram_id
+    );
+
+    Ok(result)
+}
+
+// Update user slippage setting
+pub async fn update_user_slippage(
+    pool: &PgPool,
+    telegram_id: i64,
+    slippage_bps: u64,
+) -> Result<PgQueryResult, SqlxError> {
+    // Get current user settings
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    // Create updated settings
+    let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
+
+    // Slippage is stored in basis points, the old percent value is dropped
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert("slippage_bps".to_string(), serde_json::json!(slippage_bps));
+        obj.remove("slippage");
+    }
+
+    // Save to database
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Updated slippage setting to {} bps for user with Telegram ID: {}",
+        slippage_bps, telegram_id
+    );
+
+    Ok(result)
+}
+
+// Update user priority fee setting
+pub async fn update_user_priority_fee(
+    pool: &PgPool,
+    telegram_id: i64,
+    priority_fee: &PriorityFee,
+) -> Result<PgQueryResult, SqlxError> {
+    // Get current user settings
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    // Create updated settings
+    let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
+
+    // Update the priority fee value
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert(
+            "priority_fee".to_string(),
+            serde_json::json!(priority_fee.to_string().to_lowercase()),
+        );
+    }
+
+    // Save to database
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Updated priority fee setting to {} for user with Telegram ID: {}",
+        priority_fee, telegram_id
+    );
+
+    Ok(result)
+}
+
+// Update the block explorer used for the user's transaction links
+pub async fn update_user_explorer(
+    pool: &PgPool,
+    telegram_id: i64,
+    explorer: &Explorer,
+) -> Result<PgQueryResult, SqlxError> {
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+    let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert("explorer".to_string(), serde_json::json!(explorer.code()));
+    }
+
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Updated explorer setting to {} for user with Telegram ID: {}",
+        explorer, telegram_id
+    );
+
+    Ok(result)
+}
+
+// Update the quote shown first in the user's price displays
+pub async fn update_user_default_quote(
+    pool: &PgPool,
+    telegram_id: i64,
+    quote: &PriceCurrency,
+) -> Result<PgQueryResult, SqlxError> {
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+    let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert(
+            "default_quote".to_string(),
+            serde_json::json!(quote.to_string()),
+        );
+    }
+
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Updated default quote to {} for user with Telegram ID: {}",
+        quote, telegram_id
+    );
+
+    Ok(result)
+}
+
+// Turn the image card sent with confirmed trades on or off
+pub async fn update_user_trade_card(
+    pool: &PgPool,
+    telegram_id: i64,
+    enabled: bool,
+) -> Result<PgQueryResult, SqlxError> {
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+    let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert("trade_card".to_string(), serde_json::json!(enabled));
+    }
+
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Updated trade card to {} for user with Telegram ID: {}",
+        enabled, telegram_id
+    );
+
+    Ok(result)
+}
+
+// Update the low SOL balance warning threshold in settings, 0 turns the warning off
+pub async fn update_user_low_balance_sol(
+    pool: &PgPool,
+    telegram_id: i64,
+    low_balance_sol: f64,
+) -> Result<PgQueryResult, SqlxError> {
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+    let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert(
+            "low_balance_sol".to_string(),
+            serde_json::json!(low_balance_sol),
+        );
+    }
+
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Updated low balance warning to {} SOL for user with Telegram ID: {}",
+        low_balance_sol, telegram_id
+    );
+
+    Ok(result)
+}
+
+// Update the SOL total below which trades skip confirmation, 0 always confirms
+pub async fn update_user_auto_confirm_below_sol(
+    pool: &PgPool,
+    telegram_id: i64,
+    auto_confirm_below_sol: f64,
+) -> Result<PgQueryResult, SqlxError> {
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+    let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert(
+            "auto_confirm_below_sol".to_string(),
+            serde_json::json!(auto_confirm_below_sol),
+        );
+    }
+
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Updated auto-confirm threshold to {} SOL for user with Telegram ID: {}",
+        auto_confirm_below_sol, telegram_id
+    );
+
+    Ok(result)
+}
+
+// Update the SOL amounts on the quick-buy buttons in settings
+pub async fn update_user_quick_buy_amounts(
+    pool: &PgPool,
+    telegram_id: i64,
+    amounts: &[f64],
+) -> Result<PgQueryResult, SqlxError> {
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+    let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert("quick_buy_amounts".to_string(), serde_json::json!(amounts));
+    }
+
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Updated quick-buy amounts to {:?} SOL for user with Telegram ID: {}",
+        amounts, telegram_id
+    );
+
+    Ok(result)
+}
+
+// Update user spending limits in settings, `None` turns a limit off
+pub async fn update_user_trade_limits(
+    pool: &PgPool,
+    telegram_id: i64,
+    limits: &TradeLimits,
+) -> Result<PgQueryResult, SqlxError> {
+    // Get current user settings
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
 
     // Create updated settings
     let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
 
-    // Limit slippage to reasonable range (0.1% to 5%)
-    let slippage = slippage.max(0.1).min(5.0);
+    // Update the limit values, null means no limit
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert(
+            "max_trade_sol".to_string(),
+            serde_json::json!(limits.max_trade_sol),
+        );
+        obj.insert(
+            "daily_limit_sol".to_string(),
+            serde_json::json!(limits.daily_limit_sol),
+        );
+    }
+
+    // Save to database
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Updated trade limits to {:?} for user with Telegram ID: {}",
+        limits, telegram_id
+    );
+
+    Ok(result)
+}
+
+// Update the advanced swap settings, `None` values leave Jupiter's defaults
+pub async fn update_user_route_options(
+    pool: &PgPool,
+    telegram_id: i64,
+    route: &RouteOptions,
+) -> Result<PgQueryResult, SqlxError> {
+    // Get current user settings
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    // Create updated settings
+    let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert(
+            "only_direct_routes".to_string(),
+            serde_json::json!(route.only_direct_routes),
+        );
+        obj.insert(
+            "max_accounts".to_string(),
+            serde_json::json!(route.max_accounts),
+        );
+        obj.insert(
+            "validity_slots".to_string(),
+            serde_json::json!(route.validity_slots),
+        );
+    }
+
+    // Save to database
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Updated route options to {:?} for user with Telegram ID: {}",
+        route, telegram_id
+    );
+
+    Ok(result)
+}
+
+// Turn a kind of notification on or off in settings
+pub async fn update_user_notification(
+    pool: &PgPool,
+    telegram_id: i64,
+    kind: NotificationKind,
+    enabled: bool,
+) -> Result<PgQueryResult, SqlxError> {
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+    let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(obj) = settings.as_object_mut() {
+        let notifications = obj
+            .entry("notifications")
+            .or_insert_with(|| serde_json::json!({}));
+
+        // Replace a malformed value instead of failing
+        if !notifications.is_object() {
+            *notifications = serde_json::json!({});
+        }
+        if let Some(notifications) = notifications.as_object_mut() {
+            notifications.insert(kind.code().to_string(), serde_json::json!(enabled));
+        }
+    }
+
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Turned {} notifications {} for user with Telegram ID: {}",
+        kind.code(),
+        if enabled { "on" } else { "off" },
+        telegram_id
+    );
+
+    Ok(result)
+}
+
+// Update user language in settings
+pub async fn update_user_locale(
+    pool: &PgPool,
+    telegram_id: i64,
+    locale: &Locale,
+) -> Result<PgQueryResult, SqlxError> {
+    // Get current user settings
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    // Create updated settings
+    let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
+
+    // Update the locale value
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert("locale".to_string(), serde_json::json!(locale.code()));
+    }
+
+    // Save to database
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Updated language to {} for user with Telegram ID: {}",
+        locale.code(),
+        telegram_id
+    );
+
+    Ok(result)
+}
+
+// Get user's watchlist items
+pub async fn get_user_watchlist(
+    pool: &PgPool,
+    telegram_id: i64,
+) -> Result<Vec<WatchlistItem>, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let items = sqlx::query_as::<_, WatchlistItem>(
+        "SELECT * FROM watchlist WHERE user_id = $1 ORDER BY token_symbol ASC",
+    )
+    .bind(user.id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(items)
+}
+
+// Watchlist items matching the alerts filter, `w` is the watchlist table
+const WATCHLIST_FILTER: &str = "w.user_id = $1
+     AND ($2 = FALSE OR EXISTS (
+         SELECT 1 FROM price_alerts a
+         WHERE a.user_id = w.user_id AND a.token_address = w.token_address AND a.is_active
+     ))";
+
+// Count the user's watchlist items matching the alerts filter
+pub async fn count_user_watchlist(
+    pool: &PgPool,
+    telegram_id: i64,
+    alerts_only: bool,
+) -> Result<i64, SqlxError> {
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let count = sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT COUNT(*) FROM watchlist w WHERE {}",
+        WATCHLIST_FILTER
+    ))
+    .bind(user.id)
+    .bind(alerts_only)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// One page of the user's watchlist in the given order
+pub async fn get_user_watchlist_page(
+    pool: &PgPool,
+    telegram_id: i64,
+    sort: WatchlistSort,
+    alerts_only: bool,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<WatchlistItem>, SqlxError> {
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let items = sqlx::query_as::<_, WatchlistItem>(&format!(
+        "SELECT w.* FROM watchlist w WHERE {} ORDER BY {} LIMIT $3 OFFSET $4",
+        WATCHLIST_FILTER,
+        sort.order_by()
+    ))
+    .bind(user.id)
+    .bind(alerts_only)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(items)
+}
+
+// Save the watchlist sort and alerts filter in settings
+pub async fn update_user_watchlist_view(
+    pool: &PgPool,
+    telegram_id: i64,
+    sort: WatchlistSort,
+    alerts_only: bool,
+) -> Result<PgQueryResult, SqlxError> {
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+    let mut settings = user.settings.unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert("watchlist_sort".to_string(), serde_json::json!(sort.code()));
+        obj.insert(
+            "watchlist_alerts_only".to_string(),
+            serde_json::json!(alerts_only),
+        );
+    }
+
+    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
+        .bind(settings)
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result)
+}
+
+// Add token to watchlist
+pub async fn add_to_watchlist(
+    pool: &PgPool,
+    telegram_id: i64,
+    token_address: &str,
+    token_symbol: &str,
+    price_in_sol: f64,
+) -> Result<i32, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let now = Utc::now();
+
+    // Try to insert, if token already exists update it
+    let row = sqlx::query(
+        "INSERT INTO watchlist
+         (user_id, token_address, token_symbol, last_price_in_sol, created_at, updated_at)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         ON CONFLICT (user_id, token_address)
+         DO UPDATE SET
+            token_symbol = EXCLUDED.token_symbol,
+            last_price_in_sol = EXCLUDED.last_price_in_sol,
+            updated_at = EXCLUDED.updated_at
+         RETURNING id",
+    )
+    .bind(user.id)
+    .bind(token_address)
+    .bind(token_symbol)
+    .bind(price_in_sol)
+    .bind(now)
+    .bind(now)
+    .fetch_one(pool)
+    .await?;
+
+    let id: i32 = row.try_get("id")?;
+
+    info!(
+        "Added/Updated token {} to watchlist for user ID: {}",
+        token_symbol, user.id
+    );
+
+    Ok(id)
+}
+
+// Remove token from watchlist
+pub async fn remove_from_watchlist(
+    pool: &PgPool,
+    telegram_id: i64,
+    token_address: &str,
+) -> Result<bool, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let result = sqlx::query("DELETE FROM watchlist WHERE user_id = $1 AND token_address = $2")
+        .bind(user.id)
+        .bind(token_address)
+        .execute(pool)
+        .await?;
+
+    let removed = result.rows_affected() > 0;
+
+    if removed {
+        info!(
+            "Removed token {} from watchlist for user ID: {}",
+            token_address, user.id
+        );
+    }
+
+    Ok(removed)
+}
+
+// Check if token is in watchlist
+pub async fn is_in_watchlist(
+    pool: &PgPool,
+    telegram_id: i64,
+    token_address: &str,
+) -> Result<bool, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let row = sqlx::query(
+        "SELECT COUNT(*) as count FROM watchlist WHERE user_id = $1 AND token_address = $2",
+    )
+    .bind(user.id)
+    .bind(token_address)
+    .fetch_one(pool)
+    .await?;
+
+    let count: i64 = row.try_get("count")?;
+
+    Ok(count > 0)
+}
+
+// Update token price in watchlist
+pub async fn update_watchlist_price(
+    pool: &PgPool,
+    telegram_id: i64,
+    token_address: &str,
+    price_in_sol: f64,
+) -> Result<PgQueryResult, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let now = Utc::now();
+
+    // The reference price for the 24h change is reset once its window has passed
+    let result = sqlx::query(
+        "UPDATE watchlist
+         SET last_price_in_sol = $1, updated_at = $2,
+             reference_price_in_sol = CASE
+                 WHEN reference_price_at IS NULL OR reference_price_at <= $2 - INTERVAL '24 hours'
+                 THEN $1 ELSE reference_price_in_sol END,
+             reference_price_at = CASE
+                 WHEN reference_price_at IS NULL OR reference_price_at <= $2 - INTERVAL '24 hours'
+                 THEN $2 ELSE reference_price_at END
+         WHERE user_id = $3 AND token_address = $4",
+    )
+    .bind(price_in_sol)
+    .bind(now)
+    .bind(user.id)
+    .bind(token_address)
+    .execute(pool)
+    .await?;
+
+    Ok(result)
+}
+
+// Get specific watchlist item
+pub async fn get_watchlist_item(
+    pool: &PgPool,
+    telegram_id: i64,
+    token_address: &str,
+) -> Result<Option<WatchlistItem>, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let item = sqlx::query_as::<_, WatchlistItem>(
+        "SELECT * FROM watchlist WHERE user_id = $1 AND token_address = $2",
+    )
+    .bind(user.id)
+    .bind(token_address)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(item)
+}
+
+// Create a new DCA order
+pub async fn create_dca_order(
+    pool: &PgPool,
+    telegram_id: i64,
+    token_address: &str,
+    token_symbol: &str,
+    sol_per_interval: f64,
+    interval_seconds: i64,
+    total_runs: i32,
+) -> Result<i32, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let now = Utc::now();
+
+    // The first buy happens on the next service tick
+    let row = sqlx::query(
+        "INSERT INTO dca_orders (
+            user_id, token_address, token_symbol, sol_per_interval, interval_seconds,
+            next_run_at, remaining_runs, status, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING id",
+    )
+    .bind(user.id)
+    .bind(token_address)
+    .bind(token_symbol)
+    .bind(sol_per_interval)
+    .bind(interval_seconds)
+    .bind(now)
+    .bind(total_runs)
+    .bind(DcaOrderStatus::Active.to_string())
+    .bind(now)
+    .bind(now)
+    .fetch_one(pool)
+    .await?;
+
+    let id: i32 = row.try_get("id")?;
+    info!("Created new DCA order with ID: {}", id);
+
+    Ok(id)
+}
+
+// Get user's active DCA orders
+pub async fn get_active_dca_orders(
+    pool: &PgPool,
+    telegram_id: i64,
+) -> Result<Vec<DcaOrder>, SqlxError> {
+    // Get user ID from telegram_id
+    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+
+    let rows = sqlx::query_as::<_, DcaOrder>(
+        "SELECT * FROM dca_orders
+         WHERE user_id = $1 AND status = $2
+         ORDER BY created_at DESC",
+    )
+    .bind(user.id)
+    .bind(DcaOrderStatus::Active.to_string())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+// Get active DCA orders across all users whose next run is due
+pub async fn get_due_dca_orders(pool: &PgPool) -> Result<Vec<DcaOrder>, SqlxError> {
+    let rows = sqlx::query_as::<_, DcaOrder>(
+        "SELECT * FROM dca_orders
+         WHERE status = $1 AND next_run_at <= $2
+         ORDER BY next_run_at ASC",
+    )
+    .bind(DcaOrderStatus::Active.to_string())
+    .bind(Utc::now())
+    .fetch_all(pool)
+    .await?;
 
-    // Update the slippage value
-    if let Some(obj) = settings.as_object_mut() {
-        obj.insert("slippage".to_string(), serde_json::json!(slippage));
-    }
+    Ok(rows)
+}
 
-    // Save to database
-    let result = sqlx::query("UPDATE users SET settings = $1 WHERE telegram_id = $2")
-        .bind(settings)
-        .bind(telegram_id)
-        .execute(pool)
+// Get DCA order by ID
+pub async fn get_dca_order_by_id(pool: &PgPool, order_id: i32) -> Result<DcaOrder, SqlxError> {
+    let order = sqlx::query_as::<_, DcaOrder>("SELECT * FROM dca_orders WHERE id = $1")
+        .bind(order_id)
+        .fetch_one(pool)
         .await?;
 
+    Ok(order)
+}
+
+// Save the schedule of a DCA order after a run
+pub async fn update_dca_order_schedule(
+    pool: &PgPool,
+    order_id: i32,
+    schedule: &DcaSchedule,
+) -> Result<PgQueryResult, SqlxError> {
+    let result = sqlx::query(
+        "UPDATE dca_orders
+         SET next_run_at = $1, remaining_runs = $2, status = $3, updated_at = $4
+         WHERE id = $5",
+    )
+    .bind(schedule.next_run_at)
+    .bind(schedule.remaining_runs)
+    .bind(schedule.status.to_string())
+    .bind(Utc::now())
+    .bind(order_id)
+    .execute(pool)
+    .await?;
+
     info!(
-        "Updated slippage setting to {}% for user with Telegram ID: {}",
-        slippage, telegram_id
+        "Updated DCA order #{}: {} runs left, status {}",
+        order_id, schedule.remaining_runs, schedule.status
     );
 
     Ok(result)
 }
 
-// Get user's watchlist items
-pub async fn get_user_watchlist(
+// Cancel a DCA order
+pub async fn cancel_dca_order(pool: &PgPool, order_id: i32) -> Result<PgQueryResult, SqlxError> {
+    let result = sqlx::query(
+        "UPDATE dca_orders
+         SET status = $1, updated_at = $2
+         WHERE id = $3 AND status = $4",
+    )
+    .bind(DcaOrderStatus::Cancelled.to_string())
+    .bind(Utc::now())
+    .bind(order_id)
+    .bind(DcaOrderStatus::Active.to_string())
+    .execute(pool)
+    .await?;
+
+    info!("Cancelled DCA order #{}", order_id);
+
+    Ok(result)
+}
+
+/// A price alert to create, `one_shot` alerts are deactivated after they fire
+#[derive(Debug)]
+pub struct PriceAlertRecord<'a> {
+    pub telegram_id: i64,
+    pub token_address: &'a str,
+    pub token_symbol: &'a str,
+    pub direction: &'a AlertDirection,
+    pub target_price_in_sol: f64,
+    pub one_shot: bool,
+}
+
+// Create a new price alert
+pub async fn create_price_alert(
     pool: &PgPool,
-    telegram_id: i64,
-) -> Result<Vec<WatchlistItem>, SqlxError> {
+    alert: &PriceAlertRecord<'_>,
+    current_price_in_sol: Option<f64>,
+) -> Result<i32, SqlxError> {
     // Get user ID from telegram_id
-    let user = get_user_by_telegram_id(pool, telegram_id).await?;
+    let user = get_user_by_telegram_id(pool, alert.telegram_id).await?;
 
-    let items = sqlx::query_as::<_, WatchlistItem>(
-        "SELECT * FROM watchlist WHERE user_id = $1 ORDER BY token_symbol ASC",
+    let row = sqlx::query(
+        "INSERT INTO price_alerts (
+            user_id, token_address, token_symbol, direction, target_price_in_sol,
+            one_shot, is_active, last_price_in_sol, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, TRUE, $7, $8)
+        RETURNING id",
     )
     .bind(user.id)
-    .fetch_all(pool)
+    .bind(alert.token_address)
+    .bind(alert.token_symbol)
+    .bind(alert.direction.to_string())
+    .bind(alert.target_price_in_sol)
+    .bind(alert.one_shot)
+    .bind(current_price_in_sol)
+    .bind(Utc::now())
+    .fetch_one(pool)
     .await?;
 
-    Ok(items)
+    let id: i32 = row.try_get("id")?;
+    info!("Created new price alert with ID: {}", id);
+
+    Ok(id)
 }
 
-// Add token to watchlist
-pub async fn add_to_watchlist(
+// Get user's active price alerts
+pub async fn get_user_price_alerts(
     pool: &PgPool,
     telegram_id: i64,
-    token_address: &str,
-    token_symbol: &str,
-    price_in_sol: f64,
-) -> Result<i32, SqlxError> {
+) -> Result<Vec<PriceAlert>, SqlxError> {
     // Get user ID from telegram_id
     let user = get_user_by_telegram_id(pool, telegram_id).await?;
 
-    let now = Utc::now();
-
-    // Try to insert, if token already exists update it
-    let row = sqlx::query(
-        "INSERT INTO watchlist
-         (user_id, token_address, token_symbol, last_price_in_sol, created_at, updated_at)
-         VALUES ($1, $2, $3, $4, $5, $6)
-         ON CONFLICT (user_id, token_address)
-         DO UPDATE SET
-            token_symbol = EXCLUDED.token_symbol,
-            last_price_in_sol = EXCLUDED.last_price_in_sol,
-            updated_at = EXCLUDED.updated_at
-         RETURNING id",
+    let rows = sqlx::query_as::<_, PriceAlert>(
+        "SELECT * FROM price_alerts
+         WHERE user_id = $1 AND is_active = TRUE
+         ORDER BY created_at DESC",
     )
     .bind(user.id)
-    .bind(token_address)
-    .bind(token_symbol)
-    .bind(price_in_sol)
-    .bind(now)
-    .bind(now)
-    .fetch_one(pool)
+    .fetch_all(pool)
     .await?;
 
-    let id: i32 = row.try_get("id")?;
+    Ok(rows)
+}
 
-    info!(
-        "Added/Updated token {} to watchlist for user ID: {}",
-        token_symbol, user.id
-    );
+// Get active price alerts across all users
+pub async fn get_all_active_price_alerts(pool: &PgPool) -> Result<Vec<PriceAlert>, SqlxError> {
+    let rows = retry_on_connection_error("get_all_active_price_alerts", || {
+        sqlx::query_as::<_, PriceAlert>(
+            "SELECT * FROM price_alerts
+             WHERE is_active = TRUE
+             ORDER BY created_at ASC",
+        )
+        .fetch_all(pool)
+    })
+    .await?;
 
-    Ok(id)
+    Ok(rows)
 }
 
-// Remove token from watchlist
-pub async fn remove_from_watchlist(
+// Remember the last checked price of an alert, marking it triggered if needed
+pub async fn update_price_alert_check(
+    pool: &PgPool,
+    alert_id: i32,
+    price_in_sol: f64,
+    triggered: bool,
+    is_active: bool,
+) -> Result<PgQueryResult, SqlxError> {
+    let result = sqlx::query(
+        "UPDATE price_alerts
+         SET last_price_in_sol = $1,
+             triggered_at = CASE WHEN $2 THEN $3 ELSE triggered_at END,
+             is_active = $4
+         WHERE id = $5",
+    )
+    .bind(price_in_sol)
+    .bind(triggered)
+    .bind(Utc::now())
+    .bind(is_active)
+    .bind(alert_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result)
+}
+
+// Delete a user's price alert
+pub async fn delete_price_alert(
     pool: &PgPool,
     telegram_id: i64,
-    token_address: &str,
+    alert_id: i32,
 ) -> Result<bool, SqlxError> {
     // Get user ID from telegram_id
     let user = get_user_by_telegram_id(pool, telegram_id).await?;
 
-    let result = sqlx::query("DELETE FROM watchlist WHERE user_id = $1 AND token_address = $2")
+    let result = sqlx::query("DELETE FROM price_alerts WHERE id = $1 AND user_id = $2")
+        .bind(alert_id)
         .bind(user.id)
-        .bind(token_address)
         .execute(pool)
         .await?;
 
-    let removed = result.rows_affected() > 0;
-
-    if removed {
-        info!(
-            "Removed token {} from watchlist for user ID: {}",
-            token_address, user.id
-        );
-    }
+    info!("Deleted price alert #{} for user ID: {}", alert_id, user.id);
 
-    Ok(removed)
+    Ok(result.rows_affected() > 0)
 }
 
-// Check if token is in watchlist
-pub async fn is_in_watchlist(
+// Start following a wallet, returns None when the user already follows it
+pub async fn create_followed_wallet(
     pool: &PgPool,
     telegram_id: i64,
-    token_address: &str,
-) -> Result<bool, SqlxError> {
+    wallet_address: &str,
+) -> Result<Option<i32>, SqlxError> {
     // Get user ID from telegram_id
     let user = get_user_by_telegram_id(pool, telegram_id).await?;
 
     let row = sqlx::query(
-        "SELECT COUNT(*) as count FROM watchlist WHERE user_id = $1 AND token_address = $2",
+        "INSERT INTO followed_wallets (user_id, wallet_address, created_at)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (user_id, wallet_address) DO NOTHING
+         RETURNING id",
     )
     .bind(user.id)
-    .bind(token_address)
-    .fetch_one(pool)
+    .bind(wallet_address)
+    .bind(Utc::now())
+    .fetch_optional(pool)
     .await?;
 
-    let count: i64 = row.try_get("count")?;
-
-    Ok(count > 0)
+    match row {
+        Some(row) => {
+            let id: i32 = row.try_get("id")?;
+            info!("User ID {} now follows wallet {}", user.id, wallet_address);
+            Ok(Some(id))
+        }
+        None => Ok(None),
+    }
 }
 
-// Update token price in watchlist
-pub async fn update_watchlist_price(
+// Get the wallets a user follows
+pub async fn get_user_followed_wallets(
     pool: &PgPool,
     telegram_id: i64,
-    token_address: &str,
-    price_in_sol: f64,
-) -> Result<PgQueryResult, SqlxError> {
+) -> Result<Vec<FollowedWallet>, SqlxError> {
     // Get user ID from telegram_id
     let user = get_user_by_telegram_id(pool, telegram_id).await?;
 
-    let now = Utc::now();
-
-    let result = sqlx::query(
-        "UPDATE watchlist
-         SET last_price_in_sol = $1, updated_at = $2
-         WHERE user_id = $3 AND token_address = $4",
+    let rows = sqlx::query_as::<_, FollowedWallet>(
+        "SELECT * FROM followed_wallets
+         WHERE user_id = $1
+         ORDER BY created_at ASC",
     )
-    .bind(price_in_sol)
-    .bind(now)
     .bind(user.id)
-    .bind(token_address)
-    .execute(pool)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+// Get followed wallets across all users
+pub async fn get_all_followed_wallets(pool: &PgPool) -> Result<Vec<FollowedWallet>, SqlxError> {
+    let rows = sqlx::query_as::<_, FollowedWallet>(
+        "SELECT * FROM followed_wallets
+         ORDER BY wallet_address ASC, id ASC",
+    )
+    .fetch_all(pool)
     .await?;
 
+    Ok(rows)
+}
+
+// Remember the newest transaction seen for a followed wallet
+pub async fn update_followed_wallet_signature(
+    pool: &PgPool,
+    followed_wallet_id: i32,
+    last_signature: &str,
+) -> Result<PgQueryResult, SqlxError> {
+    let result = sqlx::query("UPDATE followed_wallets SET last_signature = $1 WHERE id = $2")
+        .bind(last_signature)
+        .bind(followed_wallet_id)
+        .execute(pool)
+        .await?;
+
     Ok(result)
 }
 
-// Get specific watchlist item
-pub async fn get_watchlist_item(
+// Stop following a wallet
+pub async fn delete_followed_wallet(
     pool: &PgPool,
     telegram_id: i64,
-    token_address: &str,
-) -> Result<Option<WatchlistItem>, SqlxError> {
+    followed_wallet_id: i32,
+) -> Result<bool, SqlxError> {
     // Get user ID from telegram_id
     let user = get_user_by_telegram_id(pool, telegram_id).await?;
 
-    let item = sqlx::query_as::<_, WatchlistItem>(
-        "SELECT * FROM watchlist WHERE user_id = $1 AND token_address = $2",
+    let result = sqlx::query("DELETE FROM followed_wallets WHERE id = $1 AND user_id = $2")
+        .bind(followed_wallet_id)
+        .bind(user.id)
+        .execute(pool)
+        .await?;
+
+    info!(
+        "Deleted followed wallet #{} for user ID: {}",
+        followed_wallet_id, user.id
+    );
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Add a token to the scam blacklist, an existing entry gets the new reason
+pub async fn add_blacklisted_token(
+    pool: &PgPool,
+    mint_address: &str,
+    reason: Option<&str>,
+    added_by: i64,
+) -> Result<PgQueryResult, SqlxError> {
+    let result = sqlx::query(
+        "INSERT INTO token_blacklist (mint_address, reason, added_by, created_at)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (mint_address) DO UPDATE SET reason = $2, added_by = $3",
     )
-    .bind(user.id)
-    .bind(token_address)
+    .bind(mint_address)
+    .bind(reason)
+    .bind(added_by)
+    .bind(Utc::now())
+    .execute(pool)
+    .await?;
+
+    info!("Token {} blacklisted by {}", mint_address, added_by);
+
+    Ok(result)
+}
+
+// Remove a token from the scam blacklist
+pub async fn remove_blacklisted_token(
+    pool: &PgPool,
+    mint_address: &str,
+) -> Result<bool, SqlxError> {
+    let result = sqlx::query("DELETE FROM token_blacklist WHERE mint_address = $1")
+        .bind(mint_address)
+        .execute(pool)
+        .await?;
+
+    info!("Token {} removed from the blacklist", mint_address);
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Get the blacklist entry of a token, if any
+pub async fn get_blacklisted_token(
+    pool: &PgPool,
+    mint_address: &str,
+) -> Result<Option<BlacklistedToken>, SqlxError> {
+    let row = sqlx::query_as::<_, BlacklistedToken>(
+        "SELECT * FROM token_blacklist WHERE mint_address = $1",
+    )
+    .bind(mint_address)
     .fetch_optional(pool)
     .await?;
 
-    Ok(item)
+    Ok(row)
+}
+
+// Get all blacklisted tokens, newest first
+pub async fn get_blacklisted_tokens(pool: &PgPool) -> Result<Vec<BlacklistedToken>, SqlxError> {
+    let rows = sqlx::query_as::<_, BlacklistedToken>(
+        "SELECT * FROM token_blacklist ORDER BY created_at DESC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{OrderType, TriggerMetric};
+
+    const TELEGRAM_ID: i64 = 1001;
+    const MINT: &str = "So11111111111111111111111111111111111111112";
+
+    async fn create_buy_order(pool: &PgPool) -> i32 {
+        create_user(pool, TELEGRAM_ID, None, None).await.unwrap();
+        let order = NewLimitOrder {
+            order_type: OrderType::Buy,
+            token_address: MINT.to_string(),
+            token_symbol: "TEST".to_string(),
+            price_in_sol: 0.5,
+            amount: 4.0,
+            total_sol: 2.0,
+            trigger_metric: TriggerMetric::Price,
+            trigger_value: None,
+            price_currency: PriceCurrency::Sol,
+        };
+        create_limit_order(pool, TELEGRAM_ID, &order, Some(0.5), None)
+            .await
+            .unwrap()
+    }
+
+    fn buy(amount: f64) -> TradeRecord<'static> {
+        TradeRecord {
+            telegram_id: TELEGRAM_ID,
+            token_address: MINT,
+            token_symbol: "TEST",
+            amount,
+            price_in_sol: 0.5,
+            total_paid: amount * 0.5,
+            trade_type: "BUY",
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs DATABASE_URL"]
+    async fn settled_trade_records_trade_fill_and_fee(pool: PgPool) {
+        let order_id = create_buy_order(&pool).await;
+        let fill = LimitOrderFill {
+            order_id,
+            filled_amount: 4.0,
+            status: LimitOrderStatus::Filled,
+        };
+        let settlement = TradeSettlement {
+            limit_order_fill: Some(&fill),
+            platform_fee: Some((0.01, 50)),
+        };
+
+        record_settled_trade(&pool, &buy(4.0), "sig1", &settlement)
+            .await
+            .unwrap();
+
+        let order = get_limit_order_by_id(&pool, order_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(order.filled_amount, 4.0);
+        assert_eq!(order.status, LimitOrderStatus::Filled.to_string());
+        assert_eq!(get_user_trades(&pool, TELEGRAM_ID).await.unwrap().len(), 1);
+        let fees: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM fees")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(fees, 1);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs DATABASE_URL"]
+    async fn settled_trade_rolls_back_when_a_write_fails(pool: PgPool) {
+        let order_id = create_buy_order(&pool).await;
+        let fill = LimitOrderFill {
+            order_id,
+            filled_amount: 2.0,
+            status: LimitOrderStatus::Active,
+        };
+        let settlement = TradeSettlement {
+            limit_order_fill: Some(&fill),
+            platform_fee: Some((0.01, 50)),
+        };
+
+        // The fee is the last write, the trade and the fill before it are rolled back
+        sqlx::query("ALTER TABLE fees RENAME TO fees_unavailable")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(record_settled_trade(&pool, &buy(2.0), "sig1", &settlement)
+            .await
+            .is_err());
+
+        let order = get_limit_order_by_id(&pool, order_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(order.filled_amount, 0.0);
+        assert_eq!(order.status, LimitOrderStatus::Active.to_string());
+        assert_eq!(order.tx_signature, None);
+        assert!(get_user_trades(&pool, TELEGRAM_ID)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // The fill alone can still be recorded, so the order won't execute the slice again
+        record_limit_order_fill(&pool, &fill, "sig1").await.unwrap();
+
+        let order = get_limit_order_by_id(&pool, order_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(order.filled_amount, 2.0);
+        assert_eq!(order.tx_signature.as_deref(), Some("sig1"));
+    }
+
+    // Adds a watchlist token whose price moved from `reference` to `price`
+    async fn watch(pool: &PgPool, symbol: &str, reference: Option<f64>, price: f64) {
+        add_to_watchlist(pool, TELEGRAM_ID, symbol, symbol, price)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE watchlist SET reference_price_in_sol = $1 WHERE token_address = $2")
+            .bind(reference)
+            .bind(symbol)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs DATABASE_URL"]
+    async fn watchlist_by_change_lists_tokens_without_a_price_last(pool: PgPool) {
+        create_user(&pool, TELEGRAM_ID, None, None).await.unwrap();
+        watch(&pool, "AAA", None, 1.0).await;
+        watch(&pool, "BBB", Some(1.0), 0.9).await;
+        watch(&pool, "CCC", Some(1.0), 1.5).await;
+        watch(&pool, "DDD", Some(1.0), 0.0).await;
+        watch(&pool, "EEE", Some(2.0), 2.0).await;
+
+        let items =
+            get_user_watchlist_page(&pool, TELEGRAM_ID, WatchlistSort::Change24h, false, 10, 0)
+                .await
+                .unwrap();
+
+        let symbols: Vec<&str> = items
+            .iter()
+            .map(|item| item.token_symbol.as_str())
+            .collect();
+        // Gainers first, then tokens without price data by symbol
+        assert_eq!(symbols, vec!["CCC", "EEE", "BBB", "AAA", "DDD"]);
+        assert_eq!(items[3].change_24h(), None);
+        assert_eq!(items[4].change_24h(), None);
+    }
+
+    // Runs `retry_on_connection_error` over queries failing with `errors` in turn, then
+    // succeeding, and returns the result with the number of attempts
+    async fn run_with_errors(errors: &[fn() -> SqlxError]) -> (Result<i32, SqlxError>, usize) {
+        let attempts = std::sync::atomic::AtomicUsize::new(0);
+
+        let result = retry_on_connection_error("test", || {
+            let attempt = attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let error = errors.get(attempt).map(|error| error());
+            async move {
+                match error {
+                    Some(error) => Err(error),
+                    None => Ok(7),
+                }
+            }
+        })
+        .await;
+
+        (result, attempts.into_inner())
+    }
+
+    fn connection_reset() -> SqlxError {
+        SqlxError::Io(std::io::Error::from(std::io::ErrorKind::ConnectionReset))
+    }
+
+    #[test]
+    fn lost_connections_are_connection_errors() {
+        assert!(is_connection_error(&connection_reset()));
+        assert!(is_connection_error(&SqlxError::PoolTimedOut));
+        assert!(!is_connection_error(&SqlxError::RowNotFound));
+        assert!(!is_connection_error(&SqlxError::ColumnNotFound(
+            "id".to_string()
+        )));
+    }
+
+    #[tokio::test]
+    async fn connection_error_is_retried_once() {
+        let (result, attempts) = run_with_errors(&[connection_reset]).await;
+
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn connection_error_on_the_retry_is_returned() {
+        let (result, attempts) = run_with_errors(&[connection_reset, connection_reset]).await;
+
+        assert!(matches!(result, Err(SqlxError::Io(_))));
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_retried() {
+        let (result, attempts) = run_with_errors(&[|| SqlxError::RowNotFound]).await;
+
+        assert!(matches!(result, Err(SqlxError::RowNotFound)));
+        assert_eq!(attempts, 1);
+    }
 }
@@ -0,0 +1,146 @@
+use crate::entity::DcaOrder;
+use crate::interactor::db;
+use crate::solana::jupiter::price_service::PriceService;
+use crate::solana::jupiter::token_repository::TokenRepository;
+use crate::utils::parse_decimal;
+use crate::validate_solana_address;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Upper bound for the number of buys in a single DCA order
+pub const MAX_DCA_RUNS: i32 = 1000;
+
+#[async_trait]
+pub trait DcaInteractor: Send + Sync {
+    async fn validate_token_address(&self, token_address: &str) -> Result<bool>;
+    async fn get_token_info(&self, token_address: &str) -> Result<(String, f64, f64)>;
+    fn validate_sol_amount(&self, amount_text: &str) -> Result<f64>;
+    fn validate_runs(&self, runs_text: &str) -> Result<i32>;
+    async fn create_dca_order(
+        &self,
+        telegram_id: i64,
+        token_address: &str,
+        token_symbol: &str,
+        sol_per_interval: f64,
+        interval_seconds: i64,
+        total_runs: i32,
+    ) -> Result<i32>;
+    async fn get_active_dca_orders(&self, telegram_id: i64) -> Result<Vec<DcaOrder>>;
+    async fn cancel_dca_order(&self, telegram_id: i64, order_id: i32) -> Result<()>;
+}
+
+pub struct DcaInteractorImpl {
+    db_pool: Arc<PgPool>,
+    price_service: Arc<dyn PriceService + Send + Sync>,
+    token_repository: Arc<dyn TokenRepository + Send + Sync>,
+}
+
+impl DcaInteractorImpl {
+    pub fn new(
+        db_pool: Arc<PgPool>,
+        price_service: Arc<dyn PriceService + Send + Sync>,
+        token_repository: Arc<dyn TokenRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            db_pool,
+            price_service,
+            token_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl DcaInteractor for DcaInteractorImpl {
+    async fn validate_token_address(&self, token_address: &str) -> Result<bool> {
+        // First check if it's a valid Solana address
+        if !validate_solana_address(token_address) {
+            return Ok(false);
+        }
+
+        // Then check if it's actually a token mint address by trying to get its info
+        match self.token_repository.get_token_by_id(token_address).await {
+            Ok(_) => Ok(true),
+            Err(_) => Ok(false),
+        }
+    }
+
+    async fn get_token_info(&self, token_address: &str) -> Result<(String, f64, f64)> {
+        let token = self.token_repository.get_token_by_id(token_address).await?;
+        let price_info = self.price_service.get_token_price(token_address).await?;
+
+        Ok((
+            token.symbol,
+            price_info.price_in_sol,
+            price_info.price_in_usdc,
+        ))
+    }
+
+    fn validate_sol_amount(&self, amount_text: &str) -> Result<f64> {
+        match parse_decimal(amount_text) {
+            Ok(amount) if amount > 0.0 => Ok(amount),
+            Ok(_) => Err(anyhow!("Amount must be greater than zero")),
+            Err(_) => Err(anyhow!("Invalid amount format. Please enter a number.")),
+        }
+    }
+
+    fn validate_runs(&self, runs_text: &str) -> Result<i32> {
+        match runs_text.trim().parse::<i32>() {
+            Ok(runs) if runs > 0 && runs <= MAX_DCA_RUNS => Ok(runs),
+            Ok(_) => Err(anyhow!(
+                "Number of buys must be between 1 and {}",
+                MAX_DCA_RUNS
+            )),
+            Err(_) => Err(anyhow!(
+                "Invalid number format. Please enter a whole number."
+            )),
+        }
+    }
+
+    async fn create_dca_order(
+        &self,
+        telegram_id: i64,
+        token_address: &str,
+        token_symbol: &str,
+        sol_per_interval: f64,
+        interval_seconds: i64,
+        total_runs: i32,
+    ) -> Result<i32> {
+        db::create_dca_order(
+            &self.db_pool,
+            telegram_id,
+            token_address,
+            token_symbol,
+            sol_per_interval,
+            interval_seconds,
+            total_runs,
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to create DCA order: {}", e))
+    }
+
+    async fn get_active_dca_orders(&self, telegram_id: i64) -> Result<Vec<DcaOrder>> {
+        db::get_active_dca_orders(&self.db_pool, telegram_id)
+            .await
+            .map_err(|e| anyhow!("Error fetching DCA orders: {}", e))
+    }
+
+    async fn cancel_dca_order(&self, telegram_id: i64, order_id: i32) -> Result<()> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+        let order = db::get_dca_order_by_id(&self.db_pool, order_id)
+            .await
+            .map_err(|_| anyhow!("DCA order #{} not found", order_id))?;
+
+        // Users can only cancel their own orders
+        if order.user_id != user.id {
+            return Err(anyhow!("DCA order #{} not found", order_id));
+        }
+
+        db::cancel_dca_order(&self.db_pool, order_id)
+            .await
+            .map_err(|e| anyhow!("Failed to cancel DCA order: {}", e))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use crate::entity::BotError;
+use crate::interactor::db;
+use crate::solana;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::info;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Wallet secrets for a backup, never log or store this
+pub struct WalletExport {
+    pub address: String,
+    pub mnemonic: Option<String>,
+    // Base58 encoded 64 byte keypair, the format Phantom and Solflare import
+    pub private_key: String,
+}
+
+#[async_trait]
+pub trait ExportInteractor: Send + Sync {
+    async fn export_wallet(&self, telegram_id: i64) -> Result<WalletExport>;
+}
+
+pub struct ExportInteractorImpl {
+    db_pool: Arc<PgPool>,
+}
+
+impl ExportInteractorImpl {
+    pub fn new(db_pool: Arc<PgPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl ExportInteractor for ExportInteractorImpl {
+    async fn export_wallet(&self, telegram_id: i64) -> Result<WalletExport> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+
+        let (address, private_key) = match (user.solana_address, user.encrypted_private_key) {
+            (Some(address), Some(private_key)) => (address, private_key),
+            _ => return Err(BotError::WalletNotFound.into()),
+        };
+
+        // Make sure the stored key is usable before handing it out
+        solana::keypair_from_base58(&private_key)
+            .map_err(|e| anyhow!("Stored private key is invalid: {}", e))?;
+
+        // Audit log, the secrets themselves are never written
+        info!(
+            "Wallet {} exported by Telegram ID: {}",
+            address, telegram_id
+        );
+
+        Ok(WalletExport {
+            address,
+            mnemonic: user.mnemonic,
+            private_key,
+        })
+    }
+}
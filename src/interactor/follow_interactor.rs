@@ -0,0 +1,62 @@
+use crate::entity::FollowedWallet;
+use crate::interactor::db;
+use crate::validate_solana_address;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Maximum number of wallets one user can follow
+pub const MAX_FOLLOWED_WALLETS: usize = 10;
+
+#[async_trait]
+pub trait FollowInteractor: Send + Sync {
+    async fn get_followed_wallets(&self, telegram_id: i64) -> Result<Vec<FollowedWallet>>;
+    /// Follows the wallet, returns the id of the new entry
+    async fn follow_wallet(&self, telegram_id: i64, wallet_address: &str) -> Result<i32>;
+    async fn unfollow_wallet(&self, telegram_id: i64, followed_wallet_id: i32) -> Result<bool>;
+}
+
+pub struct FollowInteractorImpl {
+    db_pool: Arc<PgPool>,
+}
+
+impl FollowInteractorImpl {
+    pub fn new(db_pool: Arc<PgPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl FollowInteractor for FollowInteractorImpl {
+    async fn get_followed_wallets(&self, telegram_id: i64) -> Result<Vec<FollowedWallet>> {
+        Ok(db::get_user_followed_wallets(&self.db_pool, telegram_id).await?)
+    }
+
+    async fn follow_wallet(&self, telegram_id: i64, wallet_address: &str) -> Result<i32> {
+        if !validate_solana_address(wallet_address) {
+            return Err(anyhow!("Invalid Solana address"));
+        }
+
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+        if user.solana_address.as_deref() == Some(wallet_address) {
+            return Err(anyhow!("You can't follow your own wallet"));
+        }
+
+        let followed = db::get_user_followed_wallets(&self.db_pool, telegram_id).await?;
+        if followed.len() >= MAX_FOLLOWED_WALLETS {
+            return Err(anyhow!(
+                "You can follow at most {} wallets, unfollow one first",
+                MAX_FOLLOWED_WALLETS
+            ));
+        }
+
+        db::create_followed_wallet(&self.db_pool, telegram_id, wallet_address)
+            .await?
+            .ok_or_else(|| anyhow!("You already follow this wallet"))
+    }
+
+    async fn unfollow_wallet(&self, telegram_id: i64, followed_wallet_id: i32) -> Result<bool> {
+        Ok(db::delete_followed_wallet(&self.db_pool, telegram_id, followed_wallet_id).await?)
+    }
+}
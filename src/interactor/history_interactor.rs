@@ -0,0 +1,73 @@
+use crate::entity::{merge_history, HistoryEntry};
+use crate::interactor::db;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Number of history entries shown per page
+pub const HISTORY_PAGE_SIZE: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    pub page: usize,
+    pub has_older: bool,
+}
+
+impl HistoryPage {
+    pub fn has_newer(&self) -> bool {
+        self.page > 0
+    }
+}
+
+#[async_trait]
+pub trait HistoryInteractor: Send + Sync {
+    async fn get_history_page(&self, telegram_id: i64, page: usize) -> Result<HistoryPage>;
+}
+
+pub struct HistoryInteractorImpl {
+    db_pool: Arc<PgPool>,
+}
+
+impl HistoryInteractorImpl {
+    pub fn new(db_pool: Arc<PgPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl HistoryInteractor for HistoryInteractorImpl {
+    async fn get_history_page(&self, telegram_id: i64, page: usize) -> Result<HistoryPage> {
+        // Any of the three sources can fill the requested page on its own, so each one is read
+        // from the start up to the end of the page plus one row to know if older entries exist
+        let skip = page * HISTORY_PAGE_SIZE;
+        let limit = (skip + HISTORY_PAGE_SIZE + 1) as i64;
+
+        let transactions =
+            db::get_user_transactions_paginated(&self.db_pool, telegram_id, limit, 0)
+                .await
+                .map_err(|e| anyhow!("Failed to get transfers: {}", e))?;
+        let swaps = db::get_user_swaps_paginated(&self.db_pool, telegram_id, limit, 0)
+            .await
+            .map_err(|e| anyhow!("Failed to get swaps: {}", e))?;
+        let trades = db::get_user_trades_paginated(&self.db_pool, telegram_id, limit, 0)
+            .await
+            .map_err(|e| anyhow!("Failed to get trades: {}", e))?;
+
+        let merged = merge_history(transactions, swaps, trades);
+        let has_older = merged.len() > skip + HISTORY_PAGE_SIZE;
+
+        let entries = merged
+            .into_iter()
+            .skip(skip)
+            .take(HISTORY_PAGE_SIZE)
+            .collect();
+
+        Ok(HistoryPage {
+            entries,
+            page,
+            has_older,
+        })
+    }
+}
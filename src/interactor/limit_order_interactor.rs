@@ -1,12 +1,21 @@
-use crate::entity::{LimitOrder, OrderType};
+use crate::entity::{
+    parse_usd_value, LimitOrder, LimitOrderStatus, NewLimitOrder, OrderType, PriceCurrency,
+    TriggerMetric,
+};
 use crate::interactor::db;
+use crate::services::TokenSupplyCache;
+use crate::solana::client::ResilientRpcClient;
 use crate::solana::jupiter::price_service::PriceService;
 use crate::solana::jupiter::token_repository::TokenRepository;
+use crate::utils::parse_decimal;
 use crate::validate_solana_address;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use solana_client::nonblocking::rpc_client::RpcClient;
+use chrono::{DateTime, Utc};
+use log::warn;
 use sqlx::PgPool;
+use std::collections::HashSet;
+use std::str::FromStr;
 use std::sync::Arc;
 
 pub struct LimitOrderResult {
@@ -21,6 +30,17 @@ pub struct LimitOrderResult {
     pub error_message: Option<String>,
 }
 
+/// The order input with a market cap, FDV or USDC price target turned into a SOL price
+#[derive(Debug)]
+pub struct ResolvedTrigger {
+    pub trigger_metric: TriggerMetric,
+    // Target market cap, FDV or price in USD, None for SOL price triggers
+    pub trigger_value: Option<f64>,
+    pub price_currency: PriceCurrency,
+    // "<price> <volume>" for validate_order_price_and_amount
+    pub price_amount_text: String,
+}
+
 #[async_trait]
 pub trait LimitOrderInteractor: Send + Sync {
     async fn validate_token_address(&self, token_address: &str) -> Result<bool>;
@@ -34,6 +54,14 @@ pub trait LimitOrderInteractor: Send + Sync {
         telegram_id: i64,
     ) -> Result<Option<f64>>;
 
+    async fn resolve_trigger(
+        &self,
+        input: &str,
+        token_address: &str,
+        current_price_in_sol: f64,
+        current_price_in_usdc: f64,
+    ) -> Result<ResolvedTrigger>;
+
     async fn validate_order_price_and_amount(
         &self,
         price_amount_text: &str,
@@ -46,30 +74,51 @@ pub trait LimitOrderInteractor: Send + Sync {
     async fn create_limit_order(
         &self,
         telegram_id: i64,
-        order_type: &OrderType,
-        token_address: &str,
-        token_symbol: &str,
-        price_in_sol: f64,
-        amount: f64,
-        total_sol: f64,
+        order: &NewLimitOrder,
+        expires_at: Option<DateTime<Utc>>,
     ) -> Result<LimitOrderResult>;
 
     async fn get_active_limit_orders(&self, telegram_id: i64) -> Result<Vec<LimitOrder>>;
 
+    /// Replace the stored current prices of the orders with live ones and save them
+    async fn refresh_order_prices(&self, orders: &mut [LimitOrder]);
+
     async fn cancel_limit_order(&self, order_id: i32) -> Result<bool>;
+
+    /// The user's active order, as long as nothing of it has been executed yet
+    async fn get_editable_order(&self, telegram_id: i64, order_id: i32) -> Result<LimitOrder>;
+
+    /// New price, token amount and volume of an edited order, validated like a new order
+    ///
+    /// The input is "<price> <volume_in_sol>", a "-" keeps the current value.
+    async fn validate_order_edit(
+        &self,
+        order: &LimitOrder,
+        input: &str,
+        telegram_id: i64,
+    ) -> Result<(f64, f64, f64)>;
+
+    async fn update_limit_order(
+        &self,
+        order_id: i32,
+        price_in_sol: f64,
+        amount: f64,
+        total_sol: f64,
+    ) -> Result<bool>;
 }
 
 pub struct LimitOrderInteractorImpl {
     db_pool: Arc<PgPool>,
-    solana_client: Arc<RpcClient>,
+    solana_client: Arc<ResilientRpcClient>,
     price_service: Arc<dyn PriceService + Send + Sync>,
     token_repository: Arc<dyn TokenRepository + Send + Sync>,
+    token_supply: Option<Arc<TokenSupplyCache>>,
 }
 
 impl LimitOrderInteractorImpl {
     pub fn new(
         db_pool: Arc<PgPool>,
-        solana_client: Arc<RpcClient>,
+        solana_client: Arc<ResilientRpcClient>,
         price_service: Arc<dyn PriceService + Send + Sync>,
         token_repository: Arc<dyn TokenRepository + Send + Sync>,
     ) -> Self {
@@ -78,12 +127,69 @@ impl LimitOrderInteractorImpl {
             solana_client,
             price_service,
             token_repository,
+            token_supply: None,
         }
     }
 
+    /// Allow market cap and FDV triggers, without a supply source orders trigger on price only
+    pub fn with_token_supply(mut self, token_supply: Arc<TokenSupplyCache>) -> Self {
+        self.token_supply = Some(token_supply);
+        self
+    }
+
     async fn is_percentage_format(&self, input: &str) -> bool {
         input.trim().ends_with('%')
     }
+
+    // Price trigger in SOL, or in USDC when the price starts with "$", e.g. "$0.0015 10"
+    fn resolve_price_trigger(
+        input: &str,
+        current_price_in_sol: f64,
+        current_price_in_usdc: f64,
+    ) -> Result<ResolvedTrigger> {
+        let (price_currency, rest) = PriceCurrency::split_prefix(input);
+
+        if price_currency == PriceCurrency::Sol {
+            return Ok(ResolvedTrigger {
+                trigger_metric: TriggerMetric::Price,
+                trigger_value: None,
+                price_currency,
+                price_amount_text: input.to_string(),
+            });
+        }
+
+        let parts: Vec<&str> = rest.split_whitespace().collect();
+        if parts.len() != 2 {
+            return Err(anyhow!(
+                "Invalid format. Please enter the price in USD and volume in SOL (e.g. '$0.0015 10')"
+            ));
+        }
+
+        let target_price_in_usdc = match parse_decimal(parts[0]) {
+            Ok(price) if price.is_finite() && price > 0.0 => price,
+            _ => {
+                return Err(anyhow!(
+                    "Invalid price \"{}\". Please enter a USD price such as $0.0015",
+                    parts[0]
+                ))
+            }
+        };
+
+        if current_price_in_sol <= 0.0 || current_price_in_usdc <= 0.0 {
+            return Err(anyhow!("The current token price is not available"));
+        }
+
+        // The SOL price is only used for the volume, the trigger compares USD prices
+        let target_price_in_sol =
+            target_price_in_usdc * current_price_in_sol / current_price_in_usdc;
+
+        Ok(ResolvedTrigger {
+            trigger_metric: TriggerMetric::Price,
+            trigger_value: Some(target_price_in_usdc),
+            price_currency,
+            price_amount_text: format!("{} {}", target_price_in_sol, parts[1]),
+        })
+    }
 }
 
 #[async_trait]
@@ -105,7 +211,8 @@ impl LimitOrderInteractor for LimitOrderInteractorImpl {
         // Get token information
         let token = self.token_repository.get_token_by_id(token_address).await?;
 
-        // Get token price info
+        // Tokens the price API doesn't know are quoted through Jupiter, the ones without
+        // a swap route fail here with BotError::TokenNotTradable before the user picks an amount
         let price_info = self.price_service.get_token_price(token_address).await?;
 
         Ok((
@@ -115,6 +222,67 @@ impl LimitOrderInteractor for LimitOrderInteractorImpl {
         ))
     }
 
+    async fn resolve_trigger(
+        &self,
+        input: &str,
+        token_address: &str,
+        current_price_in_sol: f64,
+        current_price_in_usdc: f64,
+    ) -> Result<ResolvedTrigger> {
+        // Expected format: "mc target_usd volume_in_sol" - e.g. "mc 2.5M 10"
+        let (trigger_metric, rest) = TriggerMetric::split_prefix(input);
+
+        if !trigger_metric.needs_supply() {
+            return Self::resolve_price_trigger(input, current_price_in_sol, current_price_in_usdc);
+        }
+
+        let parts: Vec<&str> = rest.split_whitespace().collect();
+        if parts.len() != 2 {
+            return Err(anyhow!(
+                "Invalid format. Please enter the target {} in USD and volume in SOL (e.g. 'mc 2.5M 10')",
+                trigger_metric.display_name()
+            ));
+        }
+
+        let target = parse_usd_value(parts[0]).ok_or_else(|| {
+            anyhow!(
+                "Invalid {} \"{}\". Please enter a USD value such as 2500000 or 2.5M",
+                trigger_metric.display_name(),
+                parts[0]
+            )
+        })?;
+
+        // Without a supply the target can't be turned into a price, the user enters one instead
+        let supply = match &self.token_supply {
+            Some(token_supply) => token_supply.supply(token_address).await.ok(),
+            None => None,
+        };
+        let supply = supply.ok_or_else(|| {
+            warn!("Supply of {} is unknown, {} trigger refused", token_address, trigger_metric);
+            anyhow!(
+                "⚠️ The supply of this token couldn't be determined, so a {} trigger isn't possible. \
+                 Please enter a price trigger instead: <price> <volume_in_sol>",
+                trigger_metric.display_name()
+            )
+        })?;
+
+        if current_price_in_sol <= 0.0 || current_price_in_usdc <= 0.0 {
+            return Err(anyhow!("The current token price is not available"));
+        }
+
+        // The token price at the target valuation, in SOL at the current SOL/USD rate
+        let target_price_in_usdc = target / supply;
+        let target_price_in_sol =
+            target_price_in_usdc * current_price_in_sol / current_price_in_usdc;
+
+        Ok(ResolvedTrigger {
+            trigger_metric,
+            trigger_value: Some(target),
+            price_currency: PriceCurrency::Sol,
+            price_amount_text: format!("{} {}", target_price_in_sol, parts[1]),
+        })
+    }
+
     async fn validate_order_price_and_amount(
         &self,
         price_amount_text: &str,
@@ -132,20 +300,20 @@ impl LimitOrderInteractor for LimitOrderInteractorImpl {
         }
 
         // Parse price
-        let price = match parts[0].parse::<f64>() {
+        let price = match parse_decimal(parts[0]) {
             Ok(p) if p > 0.0 => p,
             Ok(_) => return Err(anyhow!("Price must be greater than zero")),
             Err(_) => return Err(anyhow!("Invalid price format. Please enter a number.")),
         };
 
         // Check if user wants to use percentage for sell orders
-        let is_percentage = *order_type == OrderType::Sell && parts[1].ends_with('%');
+        let is_percentage = order_type.is_sell() && parts[1].ends_with('%');
 
         let (amount, total_sol) = if is_percentage {
             // This is a percentage-based sell order
             // First, get the percentage value
             let percentage_str = parts[1].trim_end_matches('%');
-            let percentage = match percentage_str.parse::<f64>() {
+            let percentage = match parse_decimal(percentage_str) {
                 Ok(p) if p > 0.0 && p <= 100.0 => p / 100.0, // Convert to decimal
                 Ok(p) if p > 100.0 => return Err(anyhow!("Percentage cannot exceed 100%")),
                 Ok(_) => return Err(anyhow!("Percentage must be greater than zero")),
@@ -191,7 +359,7 @@ impl LimitOrderInteractor for LimitOrderInteractorImpl {
         } else {
             // Regular volume-based order
             // Parse volume in SOL
-            let total_sol = match parts[1].parse::<f64>() {
+            let total_sol = match parse_decimal(parts[1]) {
                 Ok(v) if v > 0.0 => v,
                 Ok(_) => return Err(anyhow!("Volume must be greater than zero")),
                 Err(_) => {
@@ -208,7 +376,7 @@ impl LimitOrderInteractor for LimitOrderInteractorImpl {
         };
 
         // For sell orders, verify user has enough tokens
-        if *order_type == OrderType::Sell {
+        if order_type.is_sell() {
             // Get user wallet
             let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
 
@@ -284,53 +452,42 @@ impl LimitOrderInteractor for LimitOrderInteractorImpl {
     async fn create_limit_order(
         &self,
         telegram_id: i64,
-        order_type: &OrderType,
-        token_address: &str,
-        token_symbol: &str,
-        price_in_sol: f64,
-        amount: f64,
-        total_sol: f64,
+        order: &NewLimitOrder,
+        expires_at: Option<DateTime<Utc>>,
     ) -> Result<LimitOrderResult> {
         // Get current price for comparison
-        let price_info = self.price_service.get_token_price(token_address).await?;
+        let price_info = self
+            .price_service
+            .get_token_price(&order.token_address)
+            .await?;
         let current_price = price_info.price_in_sol;
 
         // Create the order
-        match db::create_limit_order(
+        let created = db::create_limit_order(
             &self.db_pool,
             telegram_id,
-            token_address,
-            token_symbol,
-            order_type,
-            price_in_sol,
-            total_sol,
+            order,
             Some(current_price),
+            expires_at,
         )
-        .await
-        {
-            Ok(order_id) => Ok(LimitOrderResult {
-                token_address: token_address.to_string(),
-                token_symbol: token_symbol.to_string(),
-                order_type: order_type.clone(),
-                price_in_sol,
-                amount,
-                total_sol,
-                order_id: Some(order_id),
-                success: true,
-                error_message: None,
-            }),
-            Err(e) => Ok(LimitOrderResult {
-                token_address: token_address.to_string(),
-                token_symbol: token_symbol.to_string(),
-                order_type: order_type.clone(),
-                price_in_sol,
-                amount,
-                total_sol,
-                order_id: None,
-                success: false,
-                error_message: Some(format!("Failed to create limit order: {}", e)),
-            }),
-        }
+        .await;
+
+        let (order_id, error_message) = match created {
+            Ok(order_id) => (Some(order_id), None),
+            Err(e) => (None, Some(format!("Failed to create limit order: {}", e))),
+        };
+
+        Ok(LimitOrderResult {
+            token_address: order.token_address.clone(),
+            token_symbol: order.token_symbol.clone(),
+            order_type: order.order_type.clone(),
+            price_in_sol: order.price_in_sol,
+            amount: order.amount,
+            total_sol: order.total_sol,
+            order_id,
+            success: error_message.is_none(),
+            error_message,
+        })
     }
     async fn get_active_limit_orders(&self, telegram_id: i64) -> Result<Vec<LimitOrder>> {
         db::get_active_limit_orders(&self.db_pool, telegram_id)
@@ -338,10 +495,279 @@ impl LimitOrderInteractor for LimitOrderInteractorImpl {
             .map_err(|e| anyhow!("Error fetching limit orders: {}", e))
     }
 
+    async fn refresh_order_prices(&self, orders: &mut [LimitOrder]) {
+        // One batched price request for all tokens, however many orders they have
+        let token_addresses: HashSet<&str> = orders
+            .iter()
+            .map(|order| order.token_address.as_str())
+            .collect();
+        let token_addresses: Vec<&str> = token_addresses.into_iter().collect();
+
+        // Live prices, a cached price may be no newer than the stored one
+        let prices = match self
+            .price_service
+            .get_token_prices_fresh(&token_addresses)
+            .await
+        {
+            Ok(prices) => prices,
+            Err(e) => {
+                warn!("Failed to refresh limit order prices: {}", e);
+                return;
+            }
+        };
+
+        // Orders of a token whose price can't be fetched keep the stored price
+        for order in orders.iter_mut() {
+            let Some(price) = prices.get(&order.token_address) else {
+                continue;
+            };
+
+            if let Err(e) =
+                db::update_limit_order_current_price(&self.db_pool, order.id, price.price_in_sol)
+                    .await
+            {
+                warn!("Failed to save price of limit order #{}: {}", order.id, e);
+            }
+            order.current_price_in_sol = Some(price.price_in_sol);
+        }
+    }
+
     async fn cancel_limit_order(&self, order_id: i32) -> Result<bool> {
         match db::cancel_limit_order(&self.db_pool, order_id).await {
             Ok(_) => Ok(true),
             Err(e) => Err(anyhow!("Failed to cancel limit order: {}", e)),
         }
     }
+
+    async fn get_editable_order(&self, telegram_id: i64, order_id: i32) -> Result<LimitOrder> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+        let order = db::get_limit_order_by_id(&self.db_pool, order_id)
+            .await?
+            .filter(|order| order.user_id == user.id)
+            .ok_or_else(|| anyhow!("Order #{} not found.", order_id))?;
+
+        if order.status != LimitOrderStatus::Active.to_string() {
+            return Err(anyhow!(
+                "Order #{} is no longer active and can't be edited.",
+                order_id
+            ));
+        }
+        // The filled part was traded at the old price, changing it would mix two orders
+        if order.filled_amount > 0.0 {
+            return Err(anyhow!(
+                "Order #{} is partially filled ({}), cancel it and create a new one instead.",
+                order_id,
+                order.fill_progress()
+            ));
+        }
+
+        Ok(order)
+    }
+
+    async fn validate_order_edit(
+        &self,
+        order: &LimitOrder,
+        input: &str,
+        telegram_id: i64,
+    ) -> Result<(f64, f64, f64)> {
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        if parts.len() != 2 {
+            return Err(anyhow!(
+                "Invalid format. Please enter the new price and volume in SOL separated by space (e.g. '0.5 10'), use '-' to keep a value"
+            ));
+        }
+
+        let order_type = OrderType::from_str(&order.order_type)?;
+
+        // USD targets are turned into a SOL price when the order is created, a new SOL
+        // price would no longer match the target
+        let price = match parts[0] {
+            "-" => order.price_in_sol.to_string(),
+            _ if order.trigger_value.is_some() => {
+                return Err(anyhow!(
+                    "The target of this order is set in USD and can't be changed, only the volume (e.g. '- 10')"
+                ))
+            }
+            price => price.to_string(),
+        };
+        let volume = match parts[1] {
+            "-" => order.total_sol.to_string(),
+            volume => volume.to_string(),
+        };
+
+        self.validate_order_price_and_amount(
+            &format!("{} {}", price, volume),
+            &order_type,
+            &order.token_address,
+            &order.token_symbol,
+            telegram_id,
+        )
+        .await
+    }
+
+    async fn update_limit_order(
+        &self,
+        order_id: i32,
+        price_in_sol: f64,
+        amount: f64,
+        total_sol: f64,
+    ) -> Result<bool> {
+        db::update_limit_order_params(&self.db_pool, order_id, price_in_sol, amount, total_sol)
+            .await
+            .map_err(|e| anyhow!("Failed to update limit order: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::TokenPrice;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    const TOKEN: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn interactor() -> LimitOrderInteractorImpl {
+        let services = crate::commands::testing::services();
+
+        LimitOrderInteractorImpl::new(
+            services.db_pool(),
+            services.solana_client(),
+            services.price_service(),
+            services.token_repository(),
+        )
+    }
+
+    #[tokio::test]
+    async fn price_input_is_a_price_trigger() {
+        let resolved = interactor()
+            .resolve_trigger("0.001 10", TOKEN, 0.001, 0.2)
+            .await
+            .unwrap();
+
+        assert_eq!(resolved.trigger_metric, TriggerMetric::Price);
+        assert_eq!(resolved.trigger_value, None);
+        assert_eq!(resolved.price_amount_text, "0.001 10");
+    }
+
+    #[tokio::test]
+    async fn market_cap_trigger_without_a_supply_falls_back_to_a_price() {
+        let error = interactor()
+            .resolve_trigger("mc 2.5M 10", TOKEN, 0.001, 0.2)
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("couldn't be determined"));
+        assert!(error.to_string().contains("price trigger"));
+    }
+
+    #[tokio::test]
+    async fn market_cap_trigger_with_an_unreachable_supply_falls_back_to_a_price() {
+        let rpc = ResilientRpcClient::new(&["http://127.0.0.1:9".to_string()])
+            .unwrap()
+            .with_retry_policy(0, Duration::ZERO);
+        let interactor =
+            interactor().with_token_supply(Arc::new(TokenSupplyCache::new(Arc::new(rpc))));
+
+        let error = interactor
+            .resolve_trigger("fdv 800k 1", TOKEN, 0.001, 0.2)
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("FDV trigger isn't possible"));
+    }
+
+    #[tokio::test]
+    async fn malformed_market_cap_input_is_rejected() {
+        let interactor = interactor();
+
+        let error = interactor
+            .resolve_trigger("mc 2.5M", TOKEN, 0.001, 0.2)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("e.g. 'mc 2.5M 10'"));
+
+        let error = interactor
+            .resolve_trigger("mc lots 10", TOKEN, 0.001, 0.2)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("Invalid Market Cap \"lots\""));
+    }
+
+    // Prices of 0.4 SOL from the cache, while the market is at 0.5 SOL
+    struct StaleCache;
+
+    fn price(token_id: &str, price_in_sol: f64) -> TokenPrice {
+        TokenPrice {
+            token_id: token_id.to_string(),
+            symbol: "TKN".to_string(),
+            price_in_sol,
+            price_in_usdc: price_in_sol * 150.0,
+            timestamp: 0,
+            price_change_24h: None,
+        }
+    }
+
+    #[async_trait]
+    impl PriceService for StaleCache {
+        async fn get_sol_price(&self) -> Result<f64> {
+            Ok(150.0)
+        }
+
+        async fn get_token_price(&self, token_id: &str) -> Result<TokenPrice> {
+            Ok(price(token_id, 0.4))
+        }
+
+        async fn get_token_prices_fresh(
+            &self,
+            token_ids: &[&str],
+        ) -> Result<HashMap<String, TokenPrice>> {
+            Ok(token_ids
+                .iter()
+                .map(|token_id| (token_id.to_string(), price(token_id, 0.5)))
+                .collect())
+        }
+
+        async fn get_prices(&self, _vs_token: Option<&str>) -> Result<HashMap<String, f64>> {
+            Ok(HashMap::new())
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs DATABASE_URL"]
+    async fn refresh_stores_live_prices_instead_of_cached_ones(pool: PgPool) {
+        let services = crate::commands::testing::services();
+        let pool = Arc::new(pool);
+        let interactor = LimitOrderInteractorImpl::new(
+            pool.clone(),
+            services.solana_client(),
+            Arc::new(StaleCache),
+            services.token_repository(),
+        );
+        db::create_user(&pool, 1001, None, None).await.unwrap();
+        let order = NewLimitOrder {
+            order_type: OrderType::Buy,
+            token_address: TOKEN.to_string(),
+            token_symbol: "USDC".to_string(),
+            price_in_sol: 0.3,
+            amount: 10.0,
+            total_sol: 3.0,
+            trigger_metric: TriggerMetric::Price,
+            trigger_value: None,
+            price_currency: PriceCurrency::Sol,
+        };
+        let order_id = db::create_limit_order(&pool, 1001, &order, Some(0.4), None)
+            .await
+            .unwrap();
+
+        let mut orders = interactor.get_active_limit_orders(1001).await.unwrap();
+        interactor.refresh_order_prices(&mut orders).await;
+
+        assert_eq!(orders[0].current_price_in_sol, Some(0.5));
+        let stored = db::get_limit_order_by_id(&pool, order_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.current_price_in_sol, Some(0.5));
+    }
 }
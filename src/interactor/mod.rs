@@ -1,11 +1,25 @@
 use async_trait::async_trait;
 
+pub mod account_interactor;
+pub mod admin_interactor;
+pub mod alert_interactor;
 pub mod balance_interactor;
+pub mod cleanup_interactor;
 pub mod db;
+pub mod dca_interactor;
+pub mod export_interactor;
+pub mod follow_interactor;
+pub mod history_interactor;
 pub mod limit_order_interactor;
+pub mod portfolio_interactor;
+pub mod positions_interactor;
 pub mod price_interactor;
+pub mod referral_interactor;
 pub mod send_interactor;
 pub mod settings_interactor;
+pub mod status_interactor;
+pub mod swap_interactor;
+pub mod token_info_interactor;
 pub mod trade_interactor;
 pub mod wallet_interactor;
 pub(crate) mod watchlist_interactor;
@@ -0,0 +1,79 @@
+use crate::entity::{CostBasis, PortfolioPosition, PortfolioSummary};
+use crate::interactor::db;
+use crate::solana::jupiter::price_service::PriceService;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::warn;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait PortfolioInteractor: Send + Sync {
+    async fn get_portfolio(&self, telegram_id: i64) -> Result<PortfolioSummary>;
+}
+
+pub struct PortfolioInteractorImpl {
+    db_pool: Arc<PgPool>,
+    price_service: Arc<dyn PriceService + Send + Sync>,
+}
+
+impl PortfolioInteractorImpl {
+    pub fn new(db_pool: Arc<PgPool>, price_service: Arc<dyn PriceService + Send + Sync>) -> Self {
+        Self {
+            db_pool,
+            price_service,
+        }
+    }
+}
+
+#[async_trait]
+impl PortfolioInteractor for PortfolioInteractorImpl {
+    async fn get_portfolio(&self, telegram_id: i64) -> Result<PortfolioSummary> {
+        let trades_by_token = db::get_trades_by_token(&self.db_pool, telegram_id)
+            .await
+            .map_err(|e| anyhow!("Failed to get trade history: {}", e))?;
+
+        let mut summary = PortfolioSummary::default();
+
+        for (token_address, trades) in trades_by_token {
+            let cost_basis = CostBasis::from_trades(&trades);
+            summary.realized_pnl += cost_basis.realized_pnl;
+
+            if !cost_basis.is_open() {
+                continue;
+            }
+
+            // A missing price shouldn't hide the rest of the portfolio
+            let current_price_in_sol =
+                match self.price_service.get_token_price(&token_address).await {
+                    Ok(price_info) => Some(price_info.price_in_sol),
+                    Err(e) => {
+                        warn!("Failed to get price for {}: {}", token_address, e);
+                        None
+                    }
+                };
+
+            let token_symbol = trades
+                .last()
+                .map(|trade| trade.token_symbol.clone())
+                .unwrap_or_default();
+
+            summary.positions.push(PortfolioPosition {
+                token_address,
+                token_symbol,
+                cost_basis,
+                current_price_in_sol,
+            });
+        }
+
+        // Largest positions first
+        summary.positions.sort_by(|a, b| {
+            b.cost_basis
+                .total_cost
+                .partial_cmp(&a.cost_basis.total_cost)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        Ok(summary)
+    }
+}
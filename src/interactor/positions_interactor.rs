@@ -0,0 +1,89 @@
+use crate::entity::{PositionsSummary, TokenPosition};
+use crate::interactor::balance_interactor::{BalanceInteractor, BalanceInteractorImpl};
+use crate::interactor::db;
+use crate::solana::client::ResilientRpcClient;
+use crate::solana::jupiter::price_service::PriceService;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::warn;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait PositionsInteractor: Send + Sync {
+    async fn get_positions(&self, telegram_id: i64) -> Result<PositionsSummary>;
+}
+
+pub struct PositionsInteractorImpl {
+    db_pool: Arc<PgPool>,
+    solana_client: Arc<ResilientRpcClient>,
+    price_service: Arc<dyn PriceService + Send + Sync>,
+}
+
+impl PositionsInteractorImpl {
+    pub fn new(
+        db_pool: Arc<PgPool>,
+        solana_client: Arc<ResilientRpcClient>,
+        price_service: Arc<dyn PriceService + Send + Sync>,
+    ) -> Self {
+        Self {
+            db_pool,
+            solana_client,
+            price_service,
+        }
+    }
+}
+
+#[async_trait]
+impl PositionsInteractor for PositionsInteractorImpl {
+    async fn get_positions(&self, telegram_id: i64) -> Result<PositionsSummary> {
+        let balances = BalanceInteractorImpl::new(
+            self.db_pool.clone(),
+            self.solana_client.clone(),
+            self.price_service.clone(),
+        )
+        .get_wallet_balances(telegram_id, false)
+        .await?;
+
+        let orders = db::get_active_limit_orders(&self.db_pool, telegram_id)
+            .await
+            .map_err(|e| anyhow!("Error fetching limit orders: {}", e))?;
+
+        let mut positions = TokenPosition::join(&balances.token_balances, orders);
+
+        // One batched price request, a missing price only hides that token's value
+        let token_addresses: Vec<&str> = positions
+            .iter()
+            .map(|position| position.token_address.as_str())
+            .collect();
+        let prices = match self.price_service.get_token_prices(&token_addresses).await {
+            Ok(prices) => prices,
+            Err(e) => {
+                warn!("Failed to get prices for positions: {}", e);
+                HashMap::new()
+            }
+        };
+
+        for position in positions.iter_mut() {
+            if let Some(price) = prices.get(&position.token_address) {
+                position.price_in_sol = Some(price.price_in_sol);
+                position.price_in_usdc = Some(price.price_in_usdc);
+            }
+        }
+
+        // Largest holdings first, pending buys without a balance stay at the end
+        positions.sort_by(|a, b| {
+            b.balance.is_some().cmp(&a.balance.is_some()).then(
+                b.value_in_sol()
+                    .unwrap_or(0.0)
+                    .total_cmp(&a.value_in_sol().unwrap_or(0.0)),
+            )
+        });
+
+        Ok(PositionsSummary {
+            sol_balance: balances.sol_balance,
+            positions,
+        })
+    }
+}
@@ -7,6 +7,7 @@ use std::sync::Arc;
 #[async_trait]
 pub trait PriceInteractor: Send + Sync {
     async fn get_token_price(&self, token_id: &str) -> Result<TokenPrice>;
+    async fn get_price_history(&self, token_id: &str) -> Vec<f64>;
 }
 
 pub struct PriceInteractorImpl {
@@ -24,4 +25,8 @@ impl PriceInteractor for PriceInteractorImpl {
     async fn get_token_price(&self, token_id: &str) -> Result<TokenPrice> {
         self.price_service.get_token_price(token_id).await
     }
+
+    async fn get_price_history(&self, token_id: &str) -> Vec<f64> {
+        self.price_service.get_price_history(token_id).await
+    }
 }
@@ -0,0 +1,126 @@
+use crate::interactor::db;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Prefix of the `/start` payload in referral links, e.g. `t.me/bot?start=ref_12345`
+pub const REFERRAL_PAYLOAD_PREFIX: &str = "ref_";
+
+/// Referrer Telegram ID from a `/start ref_<id>` message, if there is one
+pub fn parse_referral_payload(text: &str) -> Option<i64> {
+    let mut parts = text.split_whitespace();
+
+    // "/start" or "/start@bot_name"
+    let command = parts.next()?;
+    if command.split('@').next() != Some("/start") {
+        return None;
+    }
+
+    parts
+        .next()?
+        .strip_prefix(REFERRAL_PAYLOAD_PREFIX)?
+        .parse::<i64>()
+        .ok()
+        .filter(|id| *id > 0)
+}
+
+/// Deep link that opens the bot with the user's referral payload
+pub fn referral_link(bot_username: &str, telegram_id: i64) -> String {
+    format!(
+        "https://t.me/{}?start={}{}",
+        bot_username, REFERRAL_PAYLOAD_PREFIX, telegram_id
+    )
+}
+
+#[async_trait]
+pub trait ReferralInteractor: Send + Sync {
+    /// Referrer to store for a new user, self-referrals and unknown referrers are dropped
+    async fn validate_referrer(&self, telegram_id: i64, referrer: i64) -> Result<Option<i64>>;
+    async fn count_referrals(&self, telegram_id: i64) -> Result<i64>;
+}
+
+pub struct ReferralInteractorImpl {
+    db_pool: Arc<PgPool>,
+}
+
+impl ReferralInteractorImpl {
+    pub fn new(db_pool: Arc<PgPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl ReferralInteractor for ReferralInteractorImpl {
+    async fn validate_referrer(&self, telegram_id: i64, referrer: i64) -> Result<Option<i64>> {
+        if referrer == telegram_id {
+            info!("Ignoring self-referral of Telegram ID: {}", telegram_id);
+            return Ok(None);
+        }
+
+        if !db::check_user_exists(&self.db_pool, referrer).await? {
+            info!(
+                "Ignoring referral of {} by unknown user {}",
+                telegram_id, referrer
+            );
+            return Ok(None);
+        }
+
+        Ok(Some(referrer))
+    }
+
+    async fn count_referrals(&self, telegram_id: i64) -> Result<i64> {
+        Ok(db::count_referrals(&self.db_pool, telegram_id).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+
+    #[test]
+    fn referrer_is_read_from_the_start_payload() {
+        assert_eq!(parse_referral_payload("/start ref_12345"), Some(12345));
+        assert_eq!(
+            parse_referral_payload("/start@trade_bot ref_12345"),
+            Some(12345)
+        );
+    }
+
+    #[test]
+    fn start_without_a_referral_payload_has_no_referrer() {
+        assert_eq!(parse_referral_payload("/start"), None);
+        assert_eq!(parse_referral_payload("/start promo_2024"), None);
+        assert_eq!(parse_referral_payload("/start ref_"), None);
+        assert_eq!(parse_referral_payload("/start ref_abc"), None);
+        assert_eq!(parse_referral_payload("/start ref_-5"), None);
+        assert_eq!(parse_referral_payload("/help ref_12345"), None);
+    }
+
+    #[test]
+    fn referral_link_round_trips_through_the_payload() {
+        let link = referral_link("trade_bot", 12345);
+
+        assert_eq!(link, "https://t.me/trade_bot?start=ref_12345");
+        let payload = link.split_once("?start=").unwrap().1;
+        assert_eq!(
+            parse_referral_payload(&format!("/start {}", payload)),
+            Some(12345)
+        );
+    }
+
+    #[tokio::test]
+    async fn self_referral_is_rejected() {
+        // The lookup of the referrer is skipped, the database is never reached
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        let interactor = ReferralInteractorImpl::new(Arc::new(pool));
+
+        let referrer = interactor.validate_referrer(12345, 12345).await.unwrap();
+
+        assert_eq!(referrer, None);
+    }
+}
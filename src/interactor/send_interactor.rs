@@ -1,9 +1,13 @@
+use crate::entity::BotError;
 use crate::interactor::db;
 use crate::solana;
+use crate::solana::client::ResilientRpcClient;
+use crate::solana::jupiter::token_repository::TokenRepository;
+use crate::solana::utils::{address_kind, get_mint_from_symbol};
+use crate::solana::wallet::parse_pubkey;
 use crate::utils;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use solana_client::nonblocking::rpc_client::RpcClient;
 use sqlx::PgPool;
 use std::sync::Arc;
 
@@ -19,6 +23,8 @@ pub struct TransactionResult {
 #[async_trait]
 pub trait SendInteractor: Send + Sync {
     async fn validate_address(&self, address: &str) -> Result<bool>;
+    // Warning for off-curve addresses and known programs, sending there is still allowed
+    async fn recipient_warning(&self, address: &str) -> Result<Option<String>>;
     async fn parse_amount_and_token(&self, amount_text: &str) -> Result<(f64, String)>;
     async fn send_transaction(
         &self,
@@ -31,14 +37,48 @@ pub trait SendInteractor: Send + Sync {
 
 pub struct SendInteractorImpl {
     db_pool: Arc<PgPool>,
-    solana_client: Arc<RpcClient>,
+    solana_client: Arc<ResilientRpcClient>,
+    token_repository: Arc<dyn TokenRepository + Send + Sync>,
 }
 
 impl SendInteractorImpl {
-    pub fn new(db_pool: Arc<PgPool>, solana_client: Arc<RpcClient>) -> Self {
+    pub fn new(
+        db_pool: Arc<PgPool>,
+        solana_client: Arc<ResilientRpcClient>,
+        token_repository: Arc<dyn TokenRepository + Send + Sync>,
+    ) -> Self {
         Self {
             db_pool,
             solana_client,
+            token_repository,
+        }
+    }
+
+    /// Mint address and symbol of a token given by symbol or mint address
+    async fn resolve_token(&self, token: &str) -> Result<(String, String)> {
+        if utils::validate_solana_address(token) {
+            let symbol = match self.token_repository.get_token_by_id(token).await {
+                Ok(info) => info.symbol,
+                Err(_) => token.to_string(),
+            };
+            return Ok((token.to_string(), symbol));
+        }
+
+        if let Some(mint) = get_mint_from_symbol(token) {
+            return Ok((mint, token.to_uppercase()));
+        }
+
+        // Only an exact symbol match, a send must not guess the token
+        let matches = self.token_repository.search_tokens(token).await?;
+        match matches
+            .into_iter()
+            .find(|info| info.symbol.eq_ignore_ascii_case(token))
+        {
+            Some(info) => Ok((info.id, info.symbol)),
+            None => Err(anyhow!(
+                "Unknown token: {}. Use the token's mint address instead",
+                token
+            )),
         }
     }
 }
@@ -49,6 +89,12 @@ impl SendInteractor for SendInteractorImpl {
         Ok(utils::validate_solana_address(address))
     }
 
+    async fn recipient_warning(&self, address: &str) -> Result<Option<String>> {
+        let recipient = parse_pubkey(address)?;
+
+        Ok(address_kind(&recipient).warning())
+    }
+
     async fn parse_amount_and_token(&self, amount_text: &str) -> Result<(f64, String)> {
         match utils::parse_amount_and_token(amount_text) {
             Some((amount, token)) => Ok((amount, token.to_string())),
@@ -85,12 +131,25 @@ impl SendInteractor for SendInteractorImpl {
                     }
                 };
 
-                // Send transaction
+                // Send transaction, the token balance is checked before the transfer
                 let result = if token.to_uppercase() == "SOL" {
-                    solana::send_sol(&self.solana_client, &keypair, recipient, amount).await
+                    solana::send_sol(&self.solana_client, &keypair, recipient, amount, None).await
                 } else {
-                    solana::send_spl_token(&self.solana_client, &keypair, recipient, token, amount)
-                        .await
+                    match self.resolve_token(token).await {
+                        Ok((mint, symbol)) => {
+                            solana::send_spl_token(
+                                &self.solana_client,
+                                &keypair,
+                                recipient,
+                                &mint,
+                                &symbol,
+                                amount,
+                                None,
+                            )
+                            .await
+                        }
+                        Err(e) => Err(e),
+                    }
                 };
 
                 match result {
@@ -118,14 +177,15 @@ impl SendInteractor for SendInteractorImpl {
                     }
                     Err(e) => {
                         // Record failed transaction
+                        let (signature, status) = BotError::failed_transaction_record(&e);
                         let _ = db::record_transaction(
                             &self.db_pool,
                             telegram_id,
                             recipient,
                             amount,
                             token,
-                            &None::<String>,
-                            "FAILED",
+                            &signature,
+                            status,
                         )
                         .await;
 
@@ -153,3 +213,104 @@ impl SendInteractor for SendInteractorImpl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{Token, TokenRisk};
+    use sqlx::postgres::PgPoolOptions;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    // Knows BONK, and a look-alike whose symbol only starts the same
+    struct TestTokens;
+
+    fn token(id: &str, symbol: &str) -> Token {
+        Token {
+            id: id.to_string(),
+            symbol: symbol.to_string(),
+            name: symbol.to_string(),
+            decimals: 5,
+            logo_uri: String::new(),
+            verified: true,
+        }
+    }
+
+    #[async_trait]
+    impl TokenRepository for TestTokens {
+        async fn get_token_by_id(&self, token_id: &str) -> Result<Token> {
+            match token_id {
+                BONK => Ok(token(BONK, "Bonk")),
+                _ => Err(anyhow!("Unknown token {}", token_id)),
+            }
+        }
+
+        async fn search_tokens(&self, query: &str) -> Result<Vec<Token>> {
+            let tokens = vec![
+                token("Bonky111111111111111111111111111111111111111", "BONKY"),
+                token(BONK, "Bonk"),
+            ];
+            Ok(tokens
+                .into_iter()
+                .filter(|token| {
+                    token
+                        .symbol
+                        .to_lowercase()
+                        .starts_with(&query.to_lowercase())
+                })
+                .collect())
+        }
+
+        async fn get_token_risk(&self, _mint: &str) -> Result<TokenRisk> {
+            Err(anyhow!("not used"))
+        }
+    }
+
+    fn interactor() -> SendInteractorImpl {
+        // Never connected, resolving a token doesn't touch the database or the network
+        let db_pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        let solana_client =
+            ResilientRpcClient::new(&["http://127.0.0.1:8899".to_string()]).unwrap();
+
+        SendInteractorImpl::new(
+            Arc::new(db_pool),
+            Arc::new(solana_client),
+            Arc::new(TestTokens),
+        )
+    }
+
+    #[tokio::test]
+    async fn usdc_resolves_to_its_mint() {
+        let (mint, symbol) = interactor().resolve_token("usdc").await.unwrap();
+
+        assert_eq!(mint, "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+        assert_eq!(symbol, "USDC");
+    }
+
+    #[tokio::test]
+    async fn symbol_resolves_to_the_exact_match_only() {
+        let (mint, symbol) = interactor().resolve_token("BONK").await.unwrap();
+
+        assert_eq!(mint, BONK);
+        assert_eq!(symbol, "Bonk");
+    }
+
+    #[tokio::test]
+    async fn mint_address_is_sent_as_is() {
+        let (mint, symbol) = interactor().resolve_token(BONK).await.unwrap();
+
+        assert_eq!((mint.as_str(), symbol.as_str()), (BONK, "Bonk"));
+    }
+
+    #[tokio::test]
+    async fn unknown_symbol_is_an_error() {
+        let err = interactor().resolve_token("NOPE").await.unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Unknown token: NOPE. Use the token's mint address instead"
+        );
+    }
+}
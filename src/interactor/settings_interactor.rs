@@ -3,13 +3,74 @@ use async_trait::async_trait;
 use sqlx::PgPool;
 use std::sync::Arc;
 
-use crate::entity::User;
+use crate::entity::{
+    exportable_settings, normalize_quick_buy_amounts, should_notify, validate_user_settings,
+    Explorer, NotificationKind, PriceCurrency, PriorityFee, TradeLimitKind, User,
+    MAX_ACCOUNTS_RANGE, SETTINGS_KEYS, SLIPPAGE_BPS_RANGE, VALIDITY_SLOTS_RANGE,
+};
 use crate::interactor::db;
+use crate::solana::jupiter::RouteOptions;
 
 #[async_trait]
 pub trait SettingsInteractor: Send + Sync {
     async fn get_user_settings(&self, telegram_id: i64) -> Result<User>;
-    async fn update_slippage(&self, telegram_id: i64, slippage: f64) -> Result<f64>;
+    // Basis points, values outside `SLIPPAGE_BPS_RANGE` are rejected
+    async fn update_slippage(&self, telegram_id: i64, slippage_bps: u64) -> Result<u64>;
+    async fn update_priority_fee(
+        &self,
+        telegram_id: i64,
+        priority_fee: PriorityFee,
+    ) -> Result<PriorityFee>;
+    async fn update_explorer(&self, telegram_id: i64, explorer: Explorer) -> Result<Explorer>;
+    // Switches the quote shown first in price displays between SOL and USDC
+    async fn toggle_default_quote(&self, telegram_id: i64) -> Result<PriceCurrency>;
+    // Turns the image card sent with confirmed trades on or off
+    async fn toggle_trade_card(&self, telegram_id: i64) -> Result<bool>;
+    // 0 turns the low SOL balance warning off
+    async fn update_low_balance_sol(&self, telegram_id: i64, low_balance_sol: f64) -> Result<f64>;
+    async fn update_auto_confirm_below_sol(
+        &self,
+        telegram_id: i64,
+        auto_confirm_below_sol: f64,
+    ) -> Result<f64>;
+    // Amounts are in SOL, stored sorted and rounded to 4 decimals
+    async fn update_quick_buy_amounts(
+        &self,
+        telegram_id: i64,
+        amounts: Vec<f64>,
+    ) -> Result<Vec<f64>>;
+    async fn toggle_direct_routes(&self, telegram_id: i64) -> Result<RouteOptions>;
+    // `None` leaves Jupiter's default
+    async fn update_max_accounts(
+        &self,
+        telegram_id: i64,
+        max_accounts: Option<u64>,
+    ) -> Result<RouteOptions>;
+    // `None` keeps the full blockhash lifetime
+    async fn update_validity_slots(
+        &self,
+        telegram_id: i64,
+        validity_slots: Option<u64>,
+    ) -> Result<RouteOptions>;
+    // `None` turns the limit off
+    async fn update_trade_limit(
+        &self,
+        telegram_id: i64,
+        kind: TradeLimitKind,
+        limit: Option<f64>,
+    ) -> Result<Option<f64>>;
+    // Every notification kind and whether it is on
+    async fn get_notifications(&self, telegram_id: i64) -> Result<Vec<(NotificationKind, bool)>>;
+    // Switches the kind on or off, returns all kinds like `get_notifications`
+    async fn toggle_notification(
+        &self,
+        telegram_id: i64,
+        kind: NotificationKind,
+    ) -> Result<Vec<(NotificationKind, bool)>>;
+    // Preferences as pretty printed JSON, to be imported on another account
+    async fn export_settings(&self, telegram_id: i64) -> Result<String>;
+    // Replaces the preferences with the pasted JSON once it passes validation
+    async fn import_settings(&self, telegram_id: i64, settings_json: &str) -> Result<()>;
 }
 
 pub struct SettingsInteractorImpl {
@@ -30,14 +91,284 @@ impl SettingsInteractor for SettingsInteractorImpl {
             .map_err(|e| anyhow!("Failed to get user settings: {}", e))
     }
 
-    async fn update_slippage(&self, telegram_id: i64, slippage: f64) -> Result<f64> {
-        // Limit slippage to reasonable range (0.1% to 5%)
-        let slippage = slippage.max(0.1).min(5.0);
+    async fn update_slippage(&self, telegram_id: i64, slippage_bps: u64) -> Result<u64> {
+        if !SLIPPAGE_BPS_RANGE.contains(&slippage_bps) {
+            return Err(anyhow!(
+                "Slippage must be between {} and {} bps",
+                SLIPPAGE_BPS_RANGE.start(),
+                SLIPPAGE_BPS_RANGE.end()
+            ));
+        }
 
-        db::update_user_slippage(&self.db_pool, telegram_id, slippage)
+        db::update_user_slippage(&self.db_pool, telegram_id, slippage_bps)
             .await
             .map_err(|e| anyhow!("Failed to update slippage setting: {}", e))?;
 
-        Ok(slippage)
+        Ok(slippage_bps)
+    }
+
+    async fn update_priority_fee(
+        &self,
+        telegram_id: i64,
+        priority_fee: PriorityFee,
+    ) -> Result<PriorityFee> {
+        db::update_user_priority_fee(&self.db_pool, telegram_id, &priority_fee)
+            .await
+            .map_err(|e| anyhow!("Failed to update priority fee setting: {}", e))?;
+
+        Ok(priority_fee)
+    }
+
+    async fn update_explorer(&self, telegram_id: i64, explorer: Explorer) -> Result<Explorer> {
+        db::update_user_explorer(&self.db_pool, telegram_id, &explorer)
+            .await
+            .map_err(|e| anyhow!("Failed to update explorer setting: {}", e))?;
+
+        Ok(explorer)
+    }
+
+    async fn toggle_default_quote(&self, telegram_id: i64) -> Result<PriceCurrency> {
+        let user = self.get_user_settings(telegram_id).await?;
+
+        let quote = match user.get_default_quote() {
+            PriceCurrency::Sol => PriceCurrency::Usdc,
+            PriceCurrency::Usdc => PriceCurrency::Sol,
+        };
+
+        db::update_user_default_quote(&self.db_pool, telegram_id, &quote)
+            .await
+            .map_err(|e| anyhow!("Failed to update default quote: {}", e))?;
+
+        Ok(quote)
+    }
+
+    async fn toggle_trade_card(&self, telegram_id: i64) -> Result<bool> {
+        let user = self.get_user_settings(telegram_id).await?;
+        let enabled = !user.get_trade_card();
+
+        db::update_user_trade_card(&self.db_pool, telegram_id, enabled)
+            .await
+            .map_err(|e| anyhow!("Failed to update trade card setting: {}", e))?;
+
+        Ok(enabled)
+    }
+
+    async fn update_low_balance_sol(&self, telegram_id: i64, low_balance_sol: f64) -> Result<f64> {
+        if !low_balance_sol.is_finite() || low_balance_sol < 0.0 {
+            return Err(anyhow!("Invalid threshold: {}", low_balance_sol));
+        }
+
+        db::update_user_low_balance_sol(&self.db_pool, telegram_id, low_balance_sol)
+            .await
+            .map_err(|e| anyhow!("Failed to update low balance warning: {}", e))?;
+
+        Ok(low_balance_sol)
+    }
+
+    async fn update_auto_confirm_below_sol(
+        &self,
+        telegram_id: i64,
+        auto_confirm_below_sol: f64,
+    ) -> Result<f64> {
+        if !auto_confirm_below_sol.is_finite() || auto_confirm_below_sol < 0.0 {
+            return Err(anyhow!("Invalid threshold: {}", auto_confirm_below_sol));
+        }
+
+        db::update_user_auto_confirm_below_sol(&self.db_pool, telegram_id, auto_confirm_below_sol)
+            .await
+            .map_err(|e| anyhow!("Failed to update auto-confirm threshold: {}", e))?;
+
+        Ok(auto_confirm_below_sol)
+    }
+
+    async fn update_quick_buy_amounts(
+        &self,
+        telegram_id: i64,
+        amounts: Vec<f64>,
+    ) -> Result<Vec<f64>> {
+        let amounts = normalize_quick_buy_amounts(&amounts)?;
+
+        db::update_user_quick_buy_amounts(&self.db_pool, telegram_id, &amounts)
+            .await
+            .map_err(|e| anyhow!("Failed to update quick-buy amounts: {}", e))?;
+
+        Ok(amounts)
+    }
+
+    async fn toggle_direct_routes(&self, telegram_id: i64) -> Result<RouteOptions> {
+        let user = self.get_user_settings(telegram_id).await?;
+
+        let mut route = user.get_route_options();
+        route.only_direct_routes = !route.only_direct_routes;
+
+        db::update_user_route_options(&self.db_pool, telegram_id, &route)
+            .await
+            .map_err(|e| anyhow!("Failed to update route setting: {}", e))?;
+
+        Ok(route)
+    }
+
+    async fn update_max_accounts(
+        &self,
+        telegram_id: i64,
+        max_accounts: Option<u64>,
+    ) -> Result<RouteOptions> {
+        if let Some(max_accounts) = max_accounts {
+            if !MAX_ACCOUNTS_RANGE.contains(&max_accounts) {
+                return Err(anyhow!(
+                    "Max accounts must be between {} and {}",
+                    MAX_ACCOUNTS_RANGE.start(),
+                    MAX_ACCOUNTS_RANGE.end()
+                ));
+            }
+        }
+
+        let user = self.get_user_settings(telegram_id).await?;
+
+        let mut route = user.get_route_options();
+        route.max_accounts = max_accounts;
+
+        db::update_user_route_options(&self.db_pool, telegram_id, &route)
+            .await
+            .map_err(|e| anyhow!("Failed to update route setting: {}", e))?;
+
+        Ok(route)
+    }
+
+    async fn update_validity_slots(
+        &self,
+        telegram_id: i64,
+        validity_slots: Option<u64>,
+    ) -> Result<RouteOptions> {
+        if let Some(validity_slots) = validity_slots {
+            if !VALIDITY_SLOTS_RANGE.contains(&validity_slots) {
+                return Err(anyhow!(
+                    "Transaction validity must be between {} and {} slots",
+                    VALIDITY_SLOTS_RANGE.start(),
+                    VALIDITY_SLOTS_RANGE.end()
+                ));
+            }
+        }
+
+        let user = self.get_user_settings(telegram_id).await?;
+
+        let mut route = user.get_route_options();
+        route.validity_slots = validity_slots;
+
+        db::update_user_route_options(&self.db_pool, telegram_id, &route)
+            .await
+            .map_err(|e| anyhow!("Failed to update transaction validity: {}", e))?;
+
+        Ok(route)
+    }
+
+    async fn update_trade_limit(
+        &self,
+        telegram_id: i64,
+        kind: TradeLimitKind,
+        limit: Option<f64>,
+    ) -> Result<Option<f64>> {
+        let user = self.get_user_settings(telegram_id).await?;
+
+        // Zero or negative values mean no limit
+        let limit = limit.filter(|limit| *limit > 0.0);
+        let limits = user.get_trade_limits().with(kind, limit);
+
+        db::update_user_trade_limits(&self.db_pool, telegram_id, &limits)
+            .await
+            .map_err(|e| anyhow!("Failed to update trade limits: {}", e))?;
+
+        Ok(limit)
+    }
+
+    async fn get_notifications(&self, telegram_id: i64) -> Result<Vec<(NotificationKind, bool)>> {
+        let user = self.get_user_settings(telegram_id).await?;
+
+        Ok(NotificationKind::ALL
+            .into_iter()
+            .map(|kind| (kind, should_notify(&user, kind)))
+            .collect())
+    }
+
+    async fn toggle_notification(
+        &self,
+        telegram_id: i64,
+        kind: NotificationKind,
+    ) -> Result<Vec<(NotificationKind, bool)>> {
+        let user = self.get_user_settings(telegram_id).await?;
+
+        db::update_user_notification(
+            &self.db_pool,
+            telegram_id,
+            kind,
+            !should_notify(&user, kind),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to update notification setting: {}", e))?;
+
+        self.get_notifications(telegram_id).await
+    }
+
+    async fn export_settings(&self, telegram_id: i64) -> Result<String> {
+        let user = self.get_user_settings(telegram_id).await?;
+        let settings = exportable_settings(user.settings.as_ref());
+
+        serde_json::to_string_pretty(&settings)
+            .map_err(|e| anyhow!("Failed to export settings: {}", e))
+    }
+
+    async fn import_settings(&self, telegram_id: i64, settings_json: &str) -> Result<()> {
+        let imported: serde_json::Value = serde_json::from_str(settings_json.trim())
+            .map_err(|e| anyhow!("Settings are not valid JSON: {}", e))?;
+        let imported = validate_user_settings(&imported)?;
+
+        let user = self.get_user_settings(telegram_id).await?;
+
+        // Preferences missing from the import go back to their defaults,
+        // anything else kept in the settings is left as is
+        let mut settings = user
+            .settings
+            .as_ref()
+            .and_then(|settings| settings.as_object())
+            .cloned()
+            .unwrap_or_default();
+        settings.retain(|key, _| !SETTINGS_KEYS.contains(&key.as_str()));
+        settings.extend(imported);
+
+        db::update_user_settings(
+            &self.db_pool,
+            telegram_id,
+            &serde_json::Value::Object(settings),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to import settings: {}", e))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+
+    fn interactor() -> SettingsInteractorImpl {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+
+        SettingsInteractorImpl::new(Arc::new(pool))
+    }
+
+    #[tokio::test]
+    async fn out_of_range_max_accounts_is_rejected_before_saving() {
+        for max_accounts in [0, 15, 65, 1000] {
+            let error = interactor()
+                .update_max_accounts(1001, Some(max_accounts))
+                .await
+                .unwrap_err();
+
+            assert_eq!(error.to_string(), "Max accounts must be between 16 and 64");
+        }
     }
 }
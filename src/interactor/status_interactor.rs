@@ -0,0 +1,84 @@
+use crate::interactor::admin_interactor::configured_admin_ids;
+use crate::interactor::db;
+use crate::solana::client::ResilientRpcClient;
+use crate::VERSION;
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Result of the RPC health check
+pub struct RpcHealth {
+    pub slot: u64,
+    pub latency: Duration,
+}
+
+/// Connections of the database pool
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: usize,
+    pub max: u32,
+}
+
+/// Health of the bot's dependencies, every check fails on its own with an error message
+pub struct StatusReport {
+    pub version: &'static str,
+    pub rpc: Result<RpcHealth, String>,
+    pub database: Result<Duration, String>,
+    pub pool: PoolStats,
+    pub active_limit_orders: Result<i64, String>,
+}
+
+#[async_trait]
+pub trait StatusInteractor: Send + Sync {
+    fn is_admin(&self, telegram_id: i64) -> bool;
+    async fn get_status(&self) -> StatusReport;
+}
+
+pub struct StatusInteractorImpl {
+    db_pool: Arc<PgPool>,
+    solana_client: Arc<ResilientRpcClient>,
+    admin_ids: HashSet<i64>,
+}
+
+impl StatusInteractorImpl {
+    /// Admins are read from the ADMIN_TELEGRAM_IDS environment variable
+    pub fn new(db_pool: Arc<PgPool>, solana_client: Arc<ResilientRpcClient>) -> Self {
+        Self {
+            db_pool,
+            solana_client,
+            admin_ids: configured_admin_ids(),
+        }
+    }
+}
+
+#[async_trait]
+impl StatusInteractor for StatusInteractorImpl {
+    fn is_admin(&self, telegram_id: i64) -> bool {
+        self.admin_ids.contains(&telegram_id)
+    }
+
+    async fn get_status(&self) -> StatusReport {
+        // Run the checks side by side so a hanging dependency doesn't delay the others
+        let (rpc, database, active_limit_orders) = tokio::join!(
+            self.solana_client.check_health(),
+            db::check_connection(&self.db_pool),
+            db::count_active_limit_orders(&self.db_pool),
+        );
+
+        StatusReport {
+            version: VERSION,
+            rpc: rpc
+                .map(|(slot, latency)| RpcHealth { slot, latency })
+                .map_err(|e| e.to_string()),
+            database: database.map_err(|e| e.to_string()),
+            pool: PoolStats {
+                size: self.db_pool.size(),
+                idle: self.db_pool.num_idle(),
+                max: self.db_pool.options().get_max_connections(),
+            },
+            active_limit_orders: active_limit_orders.map_err(|e| e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,444 @@
+use crate::entity::BotError;
+use crate::interactor::balance_interactor::BalanceInteractorImpl;
+use crate::interactor::db;
+use crate::solana::client::ResilientRpcClient;
+use crate::solana::jupiter::models::SOL_MINT;
+use crate::solana::jupiter::quote_service::QuoteService;
+use crate::solana::jupiter::swap_service::{SwapParams, SwapService};
+use crate::solana::jupiter::token_repository::TokenRepository;
+use crate::solana::tokens::from_base_units;
+use crate::utils::parse_decimal;
+use crate::{solana, validate_solana_address};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Token taking part in a swap
+#[derive(Debug, Clone)]
+pub struct SwapToken {
+    pub mint_address: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct SwapQuote {
+    pub source: SwapToken,
+    pub target: SwapToken,
+    pub amount_in: f64,
+    pub amount_out: f64,
+    pub slippage: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SwapResult {
+    pub source_symbol: String,
+    pub target_symbol: String,
+    pub amount_in: f64,
+    pub amount_out: f64,
+    pub signature: Option<String>,
+    pub success: bool,
+    pub error_message: Option<String>,
+}
+
+#[async_trait]
+pub trait SwapInteractor: Send + Sync {
+    /// Resolves a token symbol (SOL, USDC, ...) or a mint address
+    async fn resolve_token(&self, token: &str) -> Result<SwapToken>;
+    async fn validate_amount(&self, amount_text: &str) -> Result<f64>;
+    /// Quotes the swap using the user's configured slippage
+    async fn get_quote(
+        &self,
+        telegram_id: i64,
+        amount: f64,
+        source: SwapToken,
+        target: SwapToken,
+    ) -> Result<SwapQuote>;
+    async fn execute_swap(
+        &self,
+        telegram_id: i64,
+        amount: f64,
+        source_token: &str,
+        target_token: &str,
+    ) -> Result<SwapResult>;
+}
+
+pub struct SwapInteractorImpl<T, Q>
+where
+    T: TokenRepository,
+    Q: QuoteService,
+{
+    db_pool: Arc<PgPool>,
+    solana_client: Arc<ResilientRpcClient>,
+    token_repository: Arc<dyn TokenRepository + Send + Sync>,
+    quote_service: Arc<dyn QuoteService + Send + Sync>,
+    swap_service: Arc<SwapService<T, Q>>,
+}
+
+impl<T, Q> SwapInteractorImpl<T, Q>
+where
+    T: TokenRepository + 'static,
+    Q: QuoteService + 'static,
+{
+    pub fn new(
+        db_pool: Arc<PgPool>,
+        solana_client: Arc<ResilientRpcClient>,
+        token_repository: Arc<dyn TokenRepository + Send + Sync>,
+        quote_service: Arc<dyn QuoteService + Send + Sync>,
+        swap_service: Arc<SwapService<T, Q>>,
+    ) -> Self {
+        Self {
+            db_pool,
+            solana_client,
+            token_repository,
+            quote_service,
+            swap_service,
+        }
+    }
+}
+
+#[async_trait]
+impl<T, Q> SwapInteractor for SwapInteractorImpl<T, Q>
+where
+    T: TokenRepository + Send + Sync + 'static,
+    Q: QuoteService + Send + Sync + 'static,
+{
+    async fn resolve_token(&self, token: &str) -> Result<SwapToken> {
+        // Native SOL is swapped through its wrapped mint
+        let mint_address = if token.eq_ignore_ascii_case("SOL") {
+            SOL_MINT.to_string()
+        } else if let Some(mint) = solana::get_mint_from_symbol(token) {
+            mint
+        } else if validate_solana_address(token) {
+            token.to_string()
+        } else {
+            return Err(anyhow!("Unknown token: {}", token));
+        };
+
+        let token_info = self
+            .token_repository
+            .get_token_by_id(&mint_address)
+            .await
+            .map_err(|_| anyhow!("Token {} is not tradable", token))?;
+
+        Ok(SwapToken {
+            mint_address,
+            symbol: token_info.symbol,
+            decimals: token_info.decimals,
+        })
+    }
+
+    async fn validate_amount(&self, amount_text: &str) -> Result<f64> {
+        match parse_decimal(amount_text) {
+            Ok(amount) if amount > 0.0 => Ok(amount),
+            Ok(_) => Err(anyhow!("Amount must be greater than zero")),
+            Err(_) => Err(anyhow!("Invalid amount format. Please enter a number.")),
+        }
+    }
+
+    async fn get_quote(
+        &self,
+        telegram_id: i64,
+        amount: f64,
+        source: SwapToken,
+        target: SwapToken,
+    ) -> Result<SwapQuote> {
+        if source.mint_address == target.mint_address {
+            return Err(anyhow!("Source and target tokens must be different"));
+        }
+
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+
+        let slippage = user.swap_slippage(None);
+        let route = user.get_route_options();
+
+        let quote = self
+            .quote_service
+            .get_swap_quote_with_route(
+                amount,
+                &source.mint_address,
+                &target.mint_address,
+                slippage,
+                route,
+            )
+            .await?;
+
+        let amount_out = from_base_units(quote.out_amount, target.decimals);
+
+        Ok(SwapQuote {
+            source,
+            target,
+            amount_in: amount,
+            amount_out,
+            slippage: user.get_slippage(),
+        })
+    }
+
+    async fn execute_swap(
+        &self,
+        telegram_id: i64,
+        amount: f64,
+        source_token: &str,
+        target_token: &str,
+    ) -> Result<SwapResult> {
+        let source = self.resolve_token(source_token).await?;
+        let target = self.resolve_token(target_token).await?;
+
+        let mut result = SwapResult {
+            source_symbol: source.symbol.clone(),
+            target_symbol: target.symbol.clone(),
+            amount_in: amount,
+            amount_out: 0.0,
+            signature: None,
+            success: false,
+            error_message: None,
+        };
+
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+        let slippage = user.swap_slippage(None);
+        let route = user.get_route_options();
+        let priority_fee = user.get_priority_fee().to_prioritization_fee();
+
+        let (user_address, keypair) = match (user.solana_address, user.encrypted_private_key) {
+            (Some(user_address), Some(keypair_base58)) => {
+                match solana::keypair_from_base58(&keypair_base58) {
+                    Ok(keypair) => (user_address, keypair),
+                    Err(e) => {
+                        result.error_message = Some(format!("Error with private key: {}", e));
+                        return Ok(result);
+                    }
+                }
+            }
+            _ => {
+                result.error_message = Some(
+                    "Wallet not found. Use /create_wallet to create a new wallet.".to_string(),
+                );
+                return Ok(result);
+            }
+        };
+
+        // Quote the expected output for the swap record
+        let quote = match self
+            .quote_service
+            .get_swap_quote_with_route(
+                amount,
+                &source.mint_address,
+                &target.mint_address,
+                slippage,
+                route,
+            )
+            .await
+        {
+            Ok(quote) => quote,
+            Err(e) => {
+                result.error_message = Some(format!("Failed to get quote: {}", e));
+                return Ok(result);
+            }
+        };
+        result.amount_out = from_base_units(quote.out_amount, target.decimals);
+
+        let swap = SwapParams {
+            amount,
+            source_token: &source.mint_address,
+            target_token: &target.mint_address,
+            slippage,
+            route,
+            // Only buys pay the platform fee
+            platform_fee: None,
+        };
+
+        let swap_response = match self
+            .swap_service
+            .prepare_swap(&swap, priority_fee.clone(), &user_address)
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                result.error_message = Some(format!("Failed to prepare swap: {}", e));
+                return Ok(result);
+            }
+        };
+
+        let status = match self
+            .swap_service
+            .execute_swap_with_retries(
+                &self.solana_client,
+                &keypair,
+                swap_response,
+                None,
+                |attempt| {
+                    self.swap_service.prepare_swap(
+                        &swap,
+                        priority_fee.as_ref().map(|fee| fee.bumped(attempt)),
+                        &user_address,
+                    )
+                },
+            )
+            .await
+        {
+            Ok(signature) => {
+                result.signature = Some(signature);
+                result.success = true;
+                "SUCCESS"
+            }
+            Err(e) => {
+                let (signature, status) = BotError::failed_transaction_record(&e);
+                result.signature = signature;
+                result.error_message = Some(format!("Failed to execute swap: {}", e));
+                status
+            }
+        };
+
+        // The wallet's balances have changed, don't show the cached ones
+        BalanceInteractorImpl::invalidate(&user_address);
+
+        // Record the swap in the database
+        let _ = db::record_swap(
+            &self.db_pool,
+            telegram_id,
+            &source.symbol,
+            &target.symbol,
+            result.amount_in,
+            result.amount_out,
+            &result.signature,
+            status,
+        )
+        .await;
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{Token, TokenRisk};
+    use crate::solana::jupiter::models::RouteOptions;
+    use crate::solana::USDC_MINT;
+    use jupiter_swap_api_client::quote::QuoteResponse;
+    use sqlx::postgres::PgPoolOptions;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    // Knows SOL, USDC and BONK with their real decimals
+    struct KnownTokens;
+
+    #[async_trait]
+    impl TokenRepository for KnownTokens {
+        async fn get_token_by_id(&self, token_id: &str) -> Result<Token> {
+            let (symbol, decimals) = match token_id {
+                SOL_MINT => ("SOL", 9),
+                USDC_MINT => ("USDC", 6),
+                BONK => ("BONK", 5),
+                _ => return Err(anyhow!("Unknown token {}", token_id)),
+            };
+
+            Ok(Token {
+                id: token_id.to_string(),
+                symbol: symbol.to_string(),
+                name: symbol.to_string(),
+                decimals,
+                logo_uri: String::new(),
+                verified: true,
+            })
+        }
+
+        async fn search_tokens(&self, _query: &str) -> Result<Vec<Token>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_token_risk(&self, mint: &str) -> Result<TokenRisk> {
+            Err(anyhow!("Unknown token {}", mint))
+        }
+    }
+
+    struct NoQuotes;
+
+    #[async_trait]
+    impl QuoteService for NoQuotes {
+        async fn get_swap_quote_with_route(
+            &self,
+            _amount: f64,
+            _source_token: &str,
+            _target_token: &str,
+            _slippage: f64,
+            _route: RouteOptions,
+        ) -> Result<QuoteResponse> {
+            Err(anyhow!("no quote in tests"))
+        }
+    }
+
+    fn interactor() -> SwapInteractorImpl<KnownTokens, NoQuotes> {
+        let db_pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        let solana_client =
+            ResilientRpcClient::new(&["http://127.0.0.1:8899".to_string()]).unwrap();
+
+        SwapInteractorImpl::new(
+            Arc::new(db_pool),
+            Arc::new(solana_client),
+            Arc::new(KnownTokens),
+            Arc::new(NoQuotes),
+            Arc::new(SwapService::new(KnownTokens, NoQuotes)),
+        )
+    }
+
+    #[tokio::test]
+    async fn symbols_resolve_to_mints_with_their_decimals() {
+        let interactor = interactor();
+
+        let sol = interactor.resolve_token("sol").await.unwrap();
+        assert_eq!(sol.mint_address, SOL_MINT);
+        assert_eq!(sol.decimals, 9);
+
+        let usdc = interactor.resolve_token("USDC").await.unwrap();
+        assert_eq!(usdc.mint_address, USDC_MINT);
+        assert_eq!(usdc.symbol, "USDC");
+        assert_eq!(usdc.decimals, 6);
+    }
+
+    #[tokio::test]
+    async fn mint_addresses_resolve_through_the_token_repository() {
+        let bonk = interactor().resolve_token(BONK).await.unwrap();
+
+        assert_eq!(bonk.symbol, "BONK");
+        assert_eq!(bonk.decimals, 5);
+    }
+
+    #[tokio::test]
+    async fn unknown_and_untradable_tokens_are_rejected() {
+        let interactor = interactor();
+
+        let err = interactor.resolve_token("NOPE").await.unwrap_err();
+        assert!(err.to_string().contains("Unknown token"));
+
+        let untradable = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+        let err = interactor.resolve_token(untradable).await.unwrap_err();
+        assert!(err.to_string().contains("not tradable"));
+    }
+
+    #[tokio::test]
+    async fn amounts_must_be_positive_numbers() {
+        let interactor = interactor();
+
+        assert_eq!(interactor.validate_amount("0,5").await.unwrap(), 0.5);
+        assert!(interactor.validate_amount("0").await.is_err());
+        assert!(interactor.validate_amount("-1").await.is_err());
+        assert!(interactor.validate_amount("lots").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn swapping_a_token_for_itself_is_rejected_before_quoting() {
+        let interactor = interactor();
+        let sol = interactor.resolve_token("SOL").await.unwrap();
+
+        let err = interactor
+            .get_quote(1, 1.0, sol.clone(), sol)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("must be different"));
+    }
+}
@@ -0,0 +1,95 @@
+use crate::entity::{Token, TokenPrice};
+use crate::solana::client::{price_commitment, ResilientRpcClient};
+use crate::solana::jupiter::token_repository::TokenRepository;
+use crate::solana::jupiter::PriceService;
+use crate::solana::tokens::{get_mint_info, MintInfo};
+use crate::solana::wallet::parse_pubkey;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::warn;
+use std::sync::Arc;
+
+/// Number of largest holders whose share of the supply is shown
+pub const TOP_HOLDERS: usize = 10;
+
+/// Fundamentals of a token shown before buying it
+pub struct TokenInfo {
+    pub mint_address: String,
+    /// `None` when Jupiter doesn't list the token
+    pub token: Option<Token>,
+    pub mint: MintInfo,
+    /// Supply in UI units
+    pub supply: f64,
+    /// Percent of the supply held by the `TOP_HOLDERS` largest accounts
+    pub top_holders_pct: Option<f64>,
+    pub price: Option<TokenPrice>,
+}
+
+#[async_trait]
+pub trait TokenInfoInteractor: Send + Sync {
+    async fn get_token_info(&self, mint_address: &str) -> Result<TokenInfo>;
+}
+
+pub struct TokenInfoInteractorImpl {
+    solana_client: Arc<ResilientRpcClient>,
+    token_repository: Arc<dyn TokenRepository + Send + Sync>,
+    price_service: Arc<dyn PriceService + Send + Sync>,
+}
+
+impl TokenInfoInteractorImpl {
+    pub fn new(
+        solana_client: Arc<ResilientRpcClient>,
+        token_repository: Arc<dyn TokenRepository + Send + Sync>,
+        price_service: Arc<dyn PriceService + Send + Sync>,
+    ) -> Self {
+        Self {
+            solana_client,
+            token_repository,
+            price_service,
+        }
+    }
+}
+
+#[async_trait]
+impl TokenInfoInteractor for TokenInfoInteractorImpl {
+    async fn get_token_info(&self, mint_address: &str) -> Result<TokenInfo> {
+        let mint_pubkey = parse_pubkey(mint_address)
+            .map_err(|_| anyhow!("\"{}\" is not a valid token mint address", mint_address))?;
+
+        // The mint account is required, everything else is shown when available
+        let (mint, supply, largest_accounts, token, price) = tokio::join!(
+            get_mint_info(&self.solana_client, mint_address),
+            self.solana_client
+                .get_token_supply(&mint_pubkey, price_commitment()),
+            self.solana_client
+                .get_token_largest_accounts(&mint_pubkey, price_commitment()),
+            self.token_repository.get_token_by_id(mint_address),
+            self.price_service.get_token_price(mint_address),
+        );
+
+        let mint = mint?;
+        let supply = supply.unwrap_or_else(|e| {
+            warn!("Failed to get supply of {}: {}", mint_address, e);
+            mint.ui_supply()
+        });
+        let top_holders_pct = match largest_accounts {
+            Ok(balances) if supply > 0.0 => {
+                Some(balances.iter().take(TOP_HOLDERS).sum::<f64>() / supply * 100.0)
+            }
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Failed to get largest holders of {}: {}", mint_address, e);
+                None
+            }
+        };
+
+        Ok(TokenInfo {
+            mint_address: mint_address.to_string(),
+            token: token.ok(),
+            mint,
+            supply,
+            top_holders_pct,
+            price: price.ok(),
+        })
+    }
+}
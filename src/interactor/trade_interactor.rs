@@ -1,19 +1,36 @@
-use crate::entity::{BotError, OrderType, Token};
+use crate::entity::{
+    BotError, LimitOrder, LimitOrderFill, OrderType, Token, TradeFailure, TradeLimits,
+};
+use crate::interactor::balance_interactor::BalanceInteractorImpl;
 use crate::interactor::db;
+use crate::logging::RequestContext;
+use crate::services::TradeCooldown;
+use crate::solana::client::ResilientRpcClient;
+use crate::solana::jupiter::models::{PrioritizationFeeLamports, RouteOptions};
+use crate::solana::jupiter::platform_fee::{FeeCollection, PlatformFee};
 use crate::solana::jupiter::quote_service::QuoteService;
-use crate::solana::jupiter::swap_service::SwapService;
+use crate::solana::jupiter::swap_service::{SwapParams, SwapService};
 use crate::solana::jupiter::token_repository::JupiterTokenRepository;
 use crate::solana::jupiter::token_repository::TokenRepository;
 use crate::solana::jupiter::PriceService;
+use crate::solana::jupiter::SOL_MINT;
+use crate::solana::tokens::constants::SOL_DECIMALS;
+use crate::solana::tokens::from_base_units;
+use crate::solana::tokens::transaction::SubmittedSender;
+use crate::solana::utils::{lamports_to_sol, sol_to_lamports};
+use crate::utils::parse_decimal;
 use crate::{solana, validate_solana_address};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use solana_client::nonblocking::rpc_client::RpcClient;
+use chrono::Utc;
+use jupiter_swap_api_client::quote::QuoteResponse;
+use log::{debug, error, info, warn};
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::{Keypair, Signer};
 use sqlx::PgPool;
 use std::str::FromStr;
 use std::sync::Arc;
+use tokio::task::JoinSet;
 
 pub struct TradeResult {
     pub token_address: String,
@@ -24,28 +41,205 @@ pub struct TradeResult {
     pub signature: Option<String>,
     pub success: bool,
     pub error_message: Option<String>,
+    /// Typed cause of the failure, lets the UI offer a fitting next step
+    pub error: Option<BotError>,
+    /// Amount asked for when a sell was reduced to the on-chain token balance
+    pub requested_amount: Option<f64>,
+}
+
+impl TradeResult {
+    /// How much less than requested a sell reduced to the on-chain balance traded
+    pub fn shortfall(&self) -> f64 {
+        self.requested_amount
+            .map_or(0.0, |requested| requested - self.amount)
+    }
+}
+
+/// A buy or sell of `amount` tokens at `price_in_sol`
+pub struct TradeRequest<'a> {
+    pub trade_type: &'a OrderType,
+    pub token_address: &'a str,
+    pub token_symbol: &'a str,
+    pub amount: f64,
+    pub price_in_sol: f64,
+    /// Slippage in percent for this trade only, the user's configured slippage when None
+    pub slippage: Option<f64>,
+}
+
+// Typed cause of a failed swap, Jupiter and the RPC node only report it in the message
+fn swap_failure(error: anyhow::Error, slippage: f64) -> Option<BotError> {
+    if TradeFailure::classify(&error.to_string()) == TradeFailure::Slippage {
+        // Slippage is passed around as a fraction, shown in percent
+        return Some(BotError::SlippageExceeded {
+            slippage: slippage * 100.0,
+        });
+    }
+
+    error.downcast::<BotError>().ok()
+}
+
+/// Price impact in percent above which a trade needs a second confirmation
+pub const HIGH_PRICE_IMPACT_PCT: f64 = 5.0;
+
+/// Price impact in percent from which the confirmation also shows the impact of smaller sizes
+pub const IMPACT_PREVIEW_MIN_PCT: f64 = 1.0;
+
+/// Parts of the order quoted for the impact preview
+pub const IMPACT_PREVIEW_FRACTIONS: [f64; 3] = [0.25, 0.5, 1.0];
+
+/// Most quotes requested for one preview, they are sent at once and count against rate limits
+const MAX_IMPACT_PREVIEW_QUOTES: usize = 4;
+
+/// Price impact of trading one size of a token
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImpactPreview {
+    // Token amount
+    pub amount: f64,
+    // Price impact in percent, None when Jupiter found no route for this size
+    pub price_impact: Option<f64>,
+}
+
+// Jupiter reports the impact as a fraction (0.01 = 1%)
+fn price_impact_pct(quote: &QuoteResponse) -> f64 {
+    quote
+        .price_impact_pct
+        .to_string()
+        .parse::<f64>()
+        .unwrap_or(0.0)
+        * 100.0
+}
+
+// SOL a buy needs in the wallet: the SOL spent, a fee buffer and, when the swap has to
+// create the token account, its rent
+fn required_sol_for_buy(total_sol: f64, has_token_account: bool) -> f64 {
+    total_sol + solana::utils::lamports_to_sol(solana::tokens::reserve_lamports(!has_token_account))
+}
+
+// Sell amount and SOL volume reduced to the on-chain `token_balance` when they exceed it,
+// with the amount asked for if it was reduced
+fn cap_sell_to_balance(amount: f64, total_sol: f64, token_balance: f64) -> (f64, f64, Option<f64>) {
+    if token_balance < amount {
+        (
+            token_balance,
+            total_sol * token_balance / amount,
+            Some(amount),
+        )
+    } else {
+        (amount, total_sol, None)
+    }
+}
+
+/// Expected and worst-case output of a trade, shown before the user confirms it
+pub struct TradeQuote {
+    pub output_symbol: String,
+    pub expected_out: f64,
+    pub minimum_out: f64,
+    // Slippage in percent
+    pub slippage: f64,
+    // Price impact in percent
+    pub price_impact: f64,
+    // AMM labels of the route steps, e.g. "Raydium" or "Orca (40%)" for split routes
+    pub route: Vec<String>,
+    // Platform fee in percent of the SOL spent, only taken on buys
+    pub platform_fee: Option<f64>,
+}
+
+impl TradeQuote {
+    /// Decode the amounts of a Jupiter quote, the minimum is the quote's `other_amount_threshold`
+    pub fn from_quote(
+        quote: &QuoteResponse,
+        output_symbol: &str,
+        output_decimals: u8,
+        slippage: f64,
+    ) -> Self {
+        Self {
+            output_symbol: output_symbol.to_string(),
+            expected_out: from_base_units(quote.out_amount, output_decimals),
+            minimum_out: from_base_units(quote.other_amount_threshold, output_decimals),
+            slippage,
+            price_impact: price_impact_pct(quote),
+            route: quote
+                .route_plan
+                .iter()
+                .map(|step| {
+                    if step.percent < 100 {
+                        format!("{} ({}%)", step.swap_info.label, step.percent)
+                    } else {
+                        step.swap_info.label.clone()
+                    }
+                })
+                .collect(),
+            platform_fee: None,
+        }
+    }
+
+    /// Whether the trade moves the price enough to ask the user twice
+    pub fn is_high_price_impact(&self) -> bool {
+        self.price_impact > HIGH_PRICE_IMPACT_PCT
+    }
 }
 
 #[async_trait]
 pub trait TradeInteractor: Send + Sync {
     async fn validate_token_address(&self, token_address: &str) -> Result<bool>;
     async fn get_token_info(&self, token_address: &str) -> Result<(String, f64, f64)>;
+    // Tokens matching a symbol or name, verified tokens first
+    async fn search_tokens(&self, query: &str) -> Result<Vec<Token>>;
     async fn validate_buy_amount(&self, amount_text: &str) -> Result<f64>;
+    // Parses "0.5 SOL", "25%" or "All" into the amount of SOL to spend
+    async fn validate_buy_spend(&self, amount_text: &str, sol_balance: f64) -> Result<f64>;
     async fn validate_sell_amount(
         &self,
         amount_text: &str,
         token_address: &str,
         user_address: &str,
     ) -> Result<f64>;
-    async fn execute_trade(
+    // Quote for buying or selling `amount` tokens at the user's slippage
+    async fn get_trade_quote(
         &self,
         telegram_id: i64,
         trade_type: &OrderType,
         token_address: &str,
-        token_symbol: &str,
         amount: f64,
         price_in_sol: f64,
-    ) -> Result<TradeResult>;
+    ) -> Result<TradeQuote>;
+    // Price impact of trading each of `sizes` tokens, quoted in parallel. One entry per size in
+    // the same order, at most `MAX_IMPACT_PREVIEW_QUOTES`
+    async fn preview_impact(
+        &self,
+        telegram_id: i64,
+        trade_type: &OrderType,
+        token_address: &str,
+        sizes: &[f64],
+        price_in_sol: f64,
+    ) -> Result<Vec<ImpactPreview>>;
+    // Fails when a buy of `total_sol` exceeds the user's per-trade or rolling 24h spending limit
+    async fn check_trade_limits(
+        &self,
+        telegram_id: i64,
+        trade_type: &OrderType,
+        total_sol: f64,
+    ) -> Result<()>;
+    async fn execute_trade(&self, telegram_id: i64, trade: TradeRequest<'_>)
+        -> Result<TradeResult>;
+
+    // Wrapped SOL can't be sold for SOL, it is unwrapped instead. Returns the signature and SOL received
+    async fn unwrap_sol(&self, telegram_id: i64) -> Result<(String, f64)>;
+}
+
+// A buy or sell swap with the user's wallet and trading settings resolved
+struct TradeParams<'a> {
+    telegram_id: i64,
+    keypair: &'a Keypair,
+    user_address: &'a str,
+    token_address: &'a str,
+    token_symbol: &'a str,
+    amount: f64,
+    price_in_sol: f64,
+    total_sol: f64,
+    slippage: f64,
+    route: RouteOptions,
+    priority_fee: Option<PrioritizationFeeLamports>,
 }
 
 pub struct TradeInteractorImpl<T, Q>
@@ -54,10 +248,14 @@ where
     Q: QuoteService,
 {
     db_pool: Arc<PgPool>,
-    solana_client: Arc<RpcClient>,
+    solana_client: Arc<ResilientRpcClient>,
     price_service: Arc<dyn PriceService + Send + Sync>,
     token_repository: Arc<dyn TokenRepository + Send + Sync>,
     swap_service: Arc<SwapService<T, Q>>,
+    submitted: Option<SubmittedSender>,
+    cooldown: Option<Arc<TradeCooldown>>,
+    // Limit order the trade executes a slice of, with the slice amount in order units
+    limit_order_slice: Option<(LimitOrder, f64)>,
 }
 
 impl<T, Q> TradeInteractorImpl<T, Q>
@@ -67,7 +265,7 @@ where
 {
     pub fn new(
         db_pool: Arc<PgPool>,
-        solana_client: Arc<RpcClient>,
+        solana_client: Arc<ResilientRpcClient>,
         price_service: Arc<dyn PriceService + Send + Sync>,
         token_repository: Arc<dyn TokenRepository + Send + Sync>,
         swap_service: Arc<SwapService<T, Q>>,
@@ -78,9 +276,39 @@ where
             price_service,
             token_repository,
             swap_service,
+            submitted: None,
+            cooldown: None,
+            limit_order_slice: None,
         }
     }
 
+    /// Reject trading a token again within the cooldown, only set for trades the user confirms
+    pub fn with_cooldown(mut self, cooldown: Arc<TradeCooldown>) -> Self {
+        self.cooldown = Some(cooldown);
+        self
+    }
+
+    /// Send the signature of the swap to `submitted` as soon as it is on the network
+    pub fn with_submitted_sender(mut self, submitted: SubmittedSender) -> Self {
+        self.submitted = Some(submitted);
+        self
+    }
+
+    /// Record the fill of `slice_amount` of the limit order in the same transaction as a
+    /// successful trade
+    pub fn with_limit_order_slice(mut self, order: LimitOrder, slice_amount: f64) -> Self {
+        self.limit_order_slice = Some((order, slice_amount));
+        self
+    }
+
+    // Fill of the limit order slice a trade executed. A sell capped to the on-chain balance
+    // fills `shortfall` less, the order stays active unless the rest is dust.
+    fn limit_order_fill(&self, shortfall: f64) -> Option<LimitOrderFill> {
+        self.limit_order_slice
+            .as_ref()
+            .map(|(order, slice_amount)| order.fill_after(slice_amount - shortfall))
+    }
+
     async fn get_token_by_address(&self, token_address: &str) -> Result<Token> {
         self.token_repository.get_token_by_id(token_address).await
     }
@@ -96,15 +324,6 @@ where
 
         Ok(token_balance)
     }
-
-    // Helper method to convert token amount to proper units
-    async fn convert_token_amount_for_swap(&self, amount: f64, token_address: &str) -> Result<f64> {
-        let token = self.get_token_by_address(token_address).await?;
-
-        // For display we use the token amount as is, but for swap we need to consider decimals
-        // This is handled internally by the swap service, so we don't need to adjust here
-        Ok(amount)
-    }
 }
 
 #[async_trait]
@@ -126,12 +345,21 @@ where
         }
     }
 
+    async fn search_tokens(&self, query: &str) -> Result<Vec<Token>> {
+        self.token_repository.search_tokens(query).await
+    }
+
     async fn get_token_info(&self, token_address: &str) -> Result<(String, f64, f64)> {
         // Get token information to display to the user
         let token = self.get_token_by_address(token_address).await?;
 
-        // Get token price info
-        let price_info = self.price_service.get_token_price(token_address).await?;
+        // The trade is made at this price, so it skips the cache. Tokens the price API
+        // doesn't know are quoted through Jupiter, the ones without a swap route fail here
+        // with BotError::TokenNotTradable before the user picks an amount
+        let price_info = self
+            .price_service
+            .get_token_price_fresh(token_address)
+            .await?;
 
         Ok((
             token.symbol,
@@ -141,13 +369,62 @@ where
     }
 
     async fn validate_buy_amount(&self, amount_text: &str) -> Result<f64> {
-        match amount_text.parse::<f64>() {
+        match parse_decimal(amount_text) {
             Ok(amount) if amount > 0.0 => Ok(amount),
             Ok(_) => Err(anyhow!("Amount must be greater than zero")),
             Err(_) => Err(anyhow!("Invalid amount format. Please enter a number.")),
         }
     }
 
+    async fn validate_buy_spend(&self, amount_text: &str, sol_balance: f64) -> Result<f64> {
+        let input = amount_text.trim().to_lowercase();
+
+        // Keep enough SOL for fees and the new token account
+        let spendable =
+            sol_balance - solana::utils::lamports_to_sol(solana::tokens::reserve_lamports(true));
+
+        let spend = if input == "all" {
+            spendable
+        } else if let Some(percentage_str) = input.strip_suffix('%') {
+            match parse_decimal(percentage_str) {
+                Ok(percentage) if percentage > 0.0 && percentage <= 100.0 => {
+                    (sol_balance * percentage / 100.0).min(spendable)
+                }
+                Ok(_) => return Err(anyhow!("Percentage must be between 0 and 100%")),
+                Err(_) => {
+                    return Err(anyhow!(
+                        "Invalid percentage format. Please enter a number followed by %"
+                    ))
+                }
+            }
+        } else if let Some(sol_str) = input.strip_suffix("sol") {
+            match parse_decimal(sol_str) {
+                Ok(sol) if sol > 0.0 => sol,
+                Ok(_) => return Err(anyhow!("Amount must be greater than zero")),
+                Err(_) => return Err(anyhow!("Invalid amount format, e.g. 0.5 SOL")),
+            }
+        } else {
+            return Err(anyhow!(
+                "Invalid amount format. Please enter a number of tokens, a SOL amount (0.5 SOL), a percentage (25%) or 'All'"
+            ));
+        };
+
+        if spend <= 0.0 {
+            return Err(anyhow!(
+                "Insufficient SOL balance. Keep ~0.003 SOL in your wallet for fees."
+            ));
+        }
+
+        if spend > spendable {
+            return Err(anyhow!(
+                "Insufficient SOL balance. You can spend up to {:.6} SOL",
+                spendable.max(0.0)
+            ));
+        }
+
+        Ok(spend)
+    }
+
     async fn validate_sell_amount(
         &self,
         amount_text: &str,
@@ -167,7 +444,7 @@ where
         }
 
         // Otherwise, validate as a normal number
-        match amount_text.parse::<f64>() {
+        match parse_decimal(amount_text) {
             Ok(amount) if amount > 0.0 => {
                 // Verify user has enough tokens
                 let token_balance = self.get_token_balance(token_address, user_address).await?;
@@ -187,18 +464,220 @@ where
             )),
         }
     }
-    async fn execute_trade(
+    async fn get_trade_quote(
         &self,
         telegram_id: i64,
         trade_type: &OrderType,
         token_address: &str,
-        token_symbol: &str,
         amount: f64,
         price_in_sol: f64,
+    ) -> Result<TradeQuote> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+        let slippage = user.get_slippage();
+        let route = user.get_route_options();
+        let token = self.get_token_by_address(token_address).await?;
+
+        // Buys spend SOL for the token, sells return SOL
+        let quote = match trade_type {
+            OrderType::Buy => {
+                // The quoted output is what's left after the platform fee
+                let platform_fee = self.swap_service.platform_fee();
+                let route = RouteOptions {
+                    platform_fee_bps: platform_fee.map(|fee| fee.bps),
+                    ..route
+                };
+                let quote = self
+                    .swap_service
+                    .get_swap_quote(
+                        amount * price_in_sol,
+                        SOL_MINT,
+                        token_address,
+                        slippage / 100.0,
+                        route,
+                    )
+                    .await?;
+                TradeQuote {
+                    platform_fee: platform_fee.map(|fee| fee.bps as f64 / 100.0),
+                    ..TradeQuote::from_quote(&quote, &token.symbol, token.decimals, slippage)
+                }
+            }
+            _ => {
+                let quote = self
+                    .swap_service
+                    .get_swap_quote(amount, token_address, SOL_MINT, slippage / 100.0, route)
+                    .await?;
+                TradeQuote::from_quote(&quote, "SOL", SOL_DECIMALS, slippage)
+            }
+        };
+
+        Ok(quote)
+    }
+
+    async fn preview_impact(
+        &self,
+        telegram_id: i64,
+        trade_type: &OrderType,
+        token_address: &str,
+        sizes: &[f64],
+        price_in_sol: f64,
+    ) -> Result<Vec<ImpactPreview>> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+        let slippage = user.swap_slippage(None);
+        let route = user.get_route_options();
+        let is_buy = matches!(trade_type, OrderType::Buy);
+
+        let sizes = &sizes[..sizes.len().min(MAX_IMPACT_PREVIEW_QUOTES)];
+        let mut previews: Vec<ImpactPreview> = sizes
+            .iter()
+            .map(|&amount| ImpactPreview {
+                amount,
+                price_impact: None,
+            })
+            .collect();
+
+        let mut quotes = JoinSet::new();
+        for (index, &amount) in sizes.iter().enumerate() {
+            let swap_service = self.swap_service.clone();
+            let token_address = token_address.to_string();
+
+            quotes.spawn(RequestContext::inherit(async move {
+                // Buys spend SOL for the token, sells return SOL
+                let quote = if is_buy {
+                    swap_service
+                        .get_swap_quote(
+                            amount * price_in_sol,
+                            SOL_MINT,
+                            &token_address,
+                            slippage,
+                            route,
+                        )
+                        .await
+                } else {
+                    swap_service
+                        .get_swap_quote(amount, &token_address, SOL_MINT, slippage, route)
+                        .await
+                };
+                (index, quote)
+            }));
+        }
+
+        while let Some(joined) = quotes.join_next().await {
+            match joined {
+                Ok((index, Ok(quote))) => {
+                    previews[index].price_impact = Some(price_impact_pct(&quote));
+                }
+                Ok((index, Err(e))) => {
+                    debug!(
+                        "No route for {} {} of {}: {}",
+                        trade_type, previews[index].amount, token_address, e
+                    );
+                }
+                Err(e) => warn!("Impact preview quote failed: {}", e),
+            }
+        }
+
+        Ok(previews)
+    }
+
+    async fn check_trade_limits(
+        &self,
+        telegram_id: i64,
+        trade_type: &OrderType,
+        total_sol: f64,
+    ) -> Result<()> {
+        // Limits only cap spending, sells return SOL
+        if trade_type != &OrderType::Buy {
+            return Ok(());
+        }
+
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+        let limits = user.get_trade_limits();
+        if limits == TradeLimits::default() {
+            return Ok(());
+        }
+
+        // SOL spent on successful buys in the rolling 24h window
+        let since = Utc::now() - chrono::Duration::hours(24);
+        let trades = db::get_trades_since(&self.db_pool, telegram_id, since).await?;
+        let spent_sol = TradeLimits::spent_sol(&trades);
+
+        limits.check(total_sol, spent_sol)
+    }
+
+    async fn execute_trade(
+        &self,
+        telegram_id: i64,
+        trade: TradeRequest<'_>,
     ) -> Result<TradeResult> {
+        let TradeRequest {
+            trade_type,
+            token_address,
+            token_symbol,
+            amount,
+            price_in_sol,
+            slippage,
+        } = trade;
+
+        info!(
+            "Executing {} trade: {} {} @ {} SOL",
+            trade_type, amount, token_symbol, price_in_sol
+        );
+
+        // A stale confirmation of a trade that just went through must not repeat it
+        if let Some(remaining) = self
+            .cooldown
+            .as_ref()
+            .and_then(|cooldown| cooldown.remaining(telegram_id, token_address))
+        {
+            info!(
+                "Trade of {} by {} rejected by cooldown",
+                token_symbol, telegram_id
+            );
+            return Ok(TradeResult {
+                token_address: token_address.to_string(),
+                token_symbol: token_symbol.to_string(),
+                amount,
+                price_in_sol,
+                total_sol: amount * price_in_sol,
+                signature: None,
+                success: false,
+                error_message: Some(format!(
+                    "Please wait {}s before trading {} again",
+                    remaining.as_secs_f64().ceil() as u64,
+                    token_symbol
+                )),
+                error: None,
+                requested_amount: None,
+            });
+        }
+
+        // Reject buys above the user's spending limits before touching the wallet
+        if let Err(e) = self
+            .check_trade_limits(telegram_id, trade_type, amount * price_in_sol)
+            .await
+        {
+            info!("Trade rejected by spending limits: {}", e);
+            return Ok(TradeResult {
+                token_address: token_address.to_string(),
+                token_symbol: token_symbol.to_string(),
+                amount,
+                price_in_sol,
+                total_sol: amount * price_in_sol,
+                signature: None,
+                success: false,
+                error_message: Some(e.to_string()),
+                error: None,
+                requested_amount: None,
+            });
+        }
+
         // Get user wallet info
         let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
 
+        let slippage = user.swap_slippage(slippage);
+        let route = user.get_route_options();
+        let priority_fee = user.get_priority_fee().to_prioritization_fee();
+
         match (user.solana_address, user.encrypted_private_key) {
             (Some(user_address), Some(keypair_base58)) => {
                 // Get user's keypair
@@ -214,6 +693,8 @@ where
                             signature: None,
                             success: false,
                             error_message: Some(format!("Error with private key: {}", e)),
+                            error: None,
+                            requested_amount: None,
                         });
                     }
                 };
@@ -222,32 +703,49 @@ where
                 let total_sol = amount * price_in_sol;
 
                 // Execute the trade based on trade type
-                if trade_type == &OrderType::Buy {
-                    self.execute_buy_trade(
+                let result = if trade_type == &OrderType::Buy {
+                    self.execute_buy_trade(TradeParams {
                         telegram_id,
-                        &keypair,
-                        &user_address,
+                        keypair: &keypair,
+                        user_address: &user_address,
                         token_address,
                         token_symbol,
                         amount,
                         price_in_sol,
                         total_sol,
-                    )
+                        slippage,
+                        route,
+                        priority_fee: priority_fee.clone(),
+                    })
                     .await
                 } else {
                     // SELL
-                    self.execute_sell_trade(
+                    self.execute_sell_trade(TradeParams {
                         telegram_id,
-                        &keypair,
-                        &user_address,
+                        keypair: &keypair,
+                        user_address: &user_address,
                         token_address,
                         token_symbol,
                         amount,
                         price_in_sol,
                         total_sol,
-                    )
+                        slippage,
+                        route,
+                        priority_fee: priority_fee.clone(),
+                    })
                     .await
+                };
+
+                // The wallet's balances have changed, don't show the cached ones
+                BalanceInteractorImpl::invalidate(&user_address);
+
+                if let (Some(cooldown), Ok(result)) = (&self.cooldown, &result) {
+                    if result.success {
+                        cooldown.record(telegram_id, token_address);
+                    }
                 }
+
+                result
             }
             _ => Ok(TradeResult {
                 token_address: token_address.to_string(),
@@ -260,9 +758,43 @@ where
                 error_message: Some(
                     "Wallet not found. Use /create_wallet to create a new wallet.".to_string(),
                 ),
+                error: None,
+                requested_amount: None,
             }),
         }
     }
+
+    async fn unwrap_sol(&self, telegram_id: i64) -> Result<(String, f64)> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+
+        let (user_address, keypair_base58) = match (user.solana_address, user.encrypted_private_key)
+        {
+            (Some(user_address), Some(keypair_base58)) => (user_address, keypair_base58),
+            _ => return Err(BotError::WalletNotFound.into()),
+        };
+        let keypair = solana::keypair_from_base58(&keypair_base58)
+            .map_err(|e| anyhow!("Error with private key: {}", e))?;
+
+        let (signature, sol_received) =
+            solana::unwrap_sol(&self.solana_client, &keypair, self.submitted.as_ref()).await?;
+
+        BalanceInteractorImpl::invalidate(&user_address);
+
+        // Recorded like a swap, so the deposit watcher doesn't report the returned SOL
+        let _ = db::record_swap(
+            &self.db_pool,
+            telegram_id,
+            "wSOL",
+            "SOL",
+            sol_received,
+            sol_received,
+            &Some(signature.clone()),
+            "SUCCESS",
+        )
+        .await;
+
+        Ok((signature, sol_received))
+    }
 }
 
 // Implementation of private helper methods
@@ -271,17 +803,128 @@ where
     T: TokenRepository + Send + Sync + 'static,
     Q: QuoteService + Send + Sync + 'static,
 {
-    async fn execute_buy_trade(
+    /// The platform fee of a buy and how it's collected, `None` when no fee is taken
+    async fn platform_fee_collection(&self) -> Option<(PlatformFee, FeeCollection)> {
+        let fee = self.swap_service.platform_fee()?;
+
+        // Jupiter can only pay the fee into an existing wrapped SOL account
+        let collection =
+            match solana::token_account_exists(&self.solana_client, &fee.recipient, SOL_MINT).await
+            {
+                Ok(true) => FeeCollection::Jupiter,
+                Ok(false) => FeeCollection::Transfer,
+                Err(e) => {
+                    warn!("Failed to check the platform fee account: {}", e);
+                    FeeCollection::Transfer
+                }
+            };
+
+        Some((fee, collection))
+    }
+
+    /// Record a confirmed trade with what it settles
+    ///
+    /// The swap has landed, so a failed write is only logged, except for a limit order fill:
+    /// it's written again on its own, and an error is returned if it can't be, since the
+    /// still active order would execute the same slice again.
+    async fn record_settlement(
+        &self,
+        trade: &db::TradeRecord<'_>,
+        signature: &str,
+        settlement: &db::TradeSettlement<'_>,
+    ) -> Result<()> {
+        let Err(e) = db::record_settled_trade(&self.db_pool, trade, signature, settlement).await
+        else {
+            return Ok(());
+        };
+        error!(
+            "Failed to record {} {}: {}",
+            trade.trade_type.to_lowercase(),
+            signature,
+            e
+        );
+
+        let Some(fill) = settlement.limit_order_fill else {
+            return Ok(());
+        };
+        if let Err(e) = db::record_limit_order_fill(&self.db_pool, fill, signature).await {
+            error!(
+                "Failed to record limit order #{} fill: {}",
+                fill.order_id, e
+            );
+            return Err(BotError::FillNotRecorded {
+                order_id: fill.order_id,
+                signature: signature.to_string(),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Send the platform fee of a confirmed buy Jupiter didn't take, and record it
+    ///
+    /// A failed fee transfer doesn't fail the buy, it's recorded as failed instead.
+    async fn transfer_platform_fee(
         &self,
         telegram_id: i64,
         keypair: &Keypair,
-        user_address: &str,
-        token_address: &str,
-        token_symbol: &str,
-        amount: f64,
-        price_in_sol: f64,
-        total_sol: f64,
-    ) -> Result<TradeResult> {
+        fee: PlatformFee,
+        fee_lamports: u64,
+        trade_signature: &str,
+    ) {
+        let fee_sol = lamports_to_sol(fee_lamports);
+
+        let (fee_signature, status) = match solana::send_sol(
+            &self.solana_client,
+            keypair,
+            &fee.recipient.to_string(),
+            fee_sol,
+            None,
+        )
+        .await
+        {
+            Ok(signature) => (Some(signature), "SUCCESS"),
+            Err(e) => {
+                error!(
+                    "Failed to transfer the platform fee of {} SOL for buy {}: {}",
+                    fee_sol, trade_signature, e
+                );
+                (None, "FAILED")
+            }
+        };
+
+        let record = db::FeeRecord {
+            telegram_id,
+            trade_signature,
+            amount_sol: fee_sol,
+            fee_bps: fee.bps,
+            collection: &FeeCollection::Transfer.to_string(),
+        };
+
+        if let Err(e) = db::record_fee(&self.db_pool, &record, &fee_signature, status).await {
+            error!(
+                "Failed to record the platform fee of buy {}: {}",
+                trade_signature, e
+            );
+        }
+    }
+
+    async fn execute_buy_trade(&self, params: TradeParams<'_>) -> Result<TradeResult> {
+        let TradeParams {
+            telegram_id,
+            keypair,
+            user_address,
+            token_address,
+            token_symbol,
+            amount,
+            price_in_sol,
+            total_sol,
+            slippage,
+            route,
+            priority_fee,
+        } = params;
+
         // For BUY: We're trading from SOL (wrapped SOL) to the target token
         let source_token = "So11111111111111111111111111111111111111112"; // Wrapped SOL address
         let target_token = token_address;
@@ -291,7 +934,16 @@ where
         let sol_balance =
             solana::get_sol_balance(&self.solana_client, &user_pubkey.to_string()).await?;
 
-        if sol_balance < total_sol {
+        // Keep enough SOL for fees and, if needed, rent for the new token account
+        let has_token_account =
+            solana::token_account_exists(&self.solana_client, &user_pubkey, target_token).await?;
+        let required_sol = required_sol_for_buy(total_sol, has_token_account);
+
+        if sol_balance < required_sol {
+            let error = BotError::InsufficientSol {
+                required: required_sol,
+                available: sol_balance,
+            };
             return Ok(TradeResult {
                 token_address: token_address.to_string(),
                 token_symbol: token_symbol.to_string(),
@@ -300,33 +952,47 @@ where
                 total_sol,
                 signature: None,
                 success: false,
-                error_message: Some(format!(
-                    "Insufficient SOL balance. Required: {} SOL",
-                    total_sol
-                )),
+                error_message: Some(error.to_string()),
+                error: Some(error),
+                requested_amount: None,
             });
         }
 
         // Calculate how much SOL we need to send
         let sol_amount = amount * price_in_sol;
 
-        // For slippage, use a default value
-        let slippage = 0.01; // 1%
+        // The platform fee comes out of the SOL spent, whether Jupiter takes it or it's sent
+        // separately, so the user spends the same either way
+        let platform_fee = self.platform_fee_collection().await;
+        let fee_lamports = platform_fee
+            .map(|(fee, _)| fee.fee_lamports(sol_to_lamports(sol_amount)))
+            .unwrap_or(0);
+        let (swap_sol, jupiter_fee) = match platform_fee {
+            Some((fee, FeeCollection::Jupiter)) => (sol_amount, Some(fee)),
+            Some((_, FeeCollection::Transfer)) => {
+                (sol_amount - lamports_to_sol(fee_lamports), None)
+            }
+            None => (sol_amount, None),
+        };
+
+        let swap = SwapParams {
+            amount: swap_sol,
+            source_token,
+            target_token,
+            slippage,
+            route,
+            platform_fee: jupiter_fee,
+        };
 
         // Prepare the swap
         let swap_response = match self
             .swap_service
-            .prepare_swap(
-                sol_amount,
-                source_token,
-                target_token,
-                slippage,
-                user_address,
-            )
+            .prepare_swap(&swap, priority_fee.clone(), user_address)
             .await
         {
             Ok(response) => response,
             Err(e) => {
+                error!("Failed to prepare swap: {}", e);
                 return Ok(TradeResult {
                     token_address: token_address.to_string(),
                     token_symbol: token_symbol.to_string(),
@@ -336,31 +1002,66 @@ where
                     signature: None,
                     success: false,
                     error_message: Some(format!("Failed to prepare swap: {}", e)),
+                    error: None,
+                    requested_amount: None,
                 });
             }
         };
 
         // Execute the swap transaction
+        info!("Swap prepared, executing transaction");
         match self
             .swap_service
-            .execute_swap_transaction(&self.solana_client, keypair, &swap_response)
+            .execute_swap_with_retries(
+                &self.solana_client,
+                keypair,
+                swap_response,
+                self.submitted.as_ref(),
+                |attempt| {
+                    self.swap_service.prepare_swap(
+                        &swap,
+                        priority_fee.as_ref().map(|fee| fee.bumped(attempt)),
+                        user_address,
+                    )
+                },
+            )
             .await
         {
             Ok(signature) => {
-                // Record the trade in the database
-                let _ = db::record_trade(
-                    &self.db_pool,
+                info!("Swap executed: {}", signature);
+
+                // Record the trade with the order fill and the fee Jupiter took from it
+                let limit_order_fill = self.limit_order_fill(0.0);
+                let settlement = db::TradeSettlement {
+                    limit_order_fill: limit_order_fill.as_ref(),
+                    platform_fee: jupiter_fee
+                        .filter(|_| fee_lamports > 0)
+                        .map(|fee| (lamports_to_sol(fee_lamports), fee.bps)),
+                };
+                let trade = db::TradeRecord {
                     telegram_id,
                     token_address,
                     token_symbol,
                     amount,
                     price_in_sol,
-                    total_sol,
-                    "BUY",
-                    &Some(signature.clone()),
-                    "SUCCESS",
-                )
-                .await;
+                    total_paid: total_sol,
+                    trade_type: "BUY",
+                };
+                self.record_settlement(&trade, &signature, &settlement)
+                    .await?;
+
+                if let Some((fee, FeeCollection::Transfer)) = platform_fee {
+                    if fee_lamports > 0 {
+                        self.transfer_platform_fee(
+                            telegram_id,
+                            keypair,
+                            fee,
+                            fee_lamports,
+                            &signature,
+                        )
+                        .await;
+                    }
+                }
 
                 Ok(TradeResult {
                     token_address: token_address.to_string(),
@@ -371,23 +1072,25 @@ where
                     signature: Some(signature),
                     success: true,
                     error_message: None,
+                    error: None,
+                    requested_amount: None,
                 })
             }
             Err(e) => {
+                error!("Failed to execute swap: {}", e);
+
                 // Record failed transaction
-                let _ = db::record_trade(
-                    &self.db_pool,
+                let (signature, status) = BotError::failed_transaction_record(&e);
+                let trade = db::TradeRecord {
                     telegram_id,
                     token_address,
                     token_symbol,
                     amount,
                     price_in_sol,
-                    total_sol,
-                    "BUY",
-                    &None::<String>,
-                    "FAILED",
-                )
-                .await;
+                    total_paid: total_sol,
+                    trade_type: "BUY",
+                };
+                let _ = db::record_trade(&self.db_pool, &trade, &signature, status).await;
 
                 Ok(TradeResult {
                     token_address: token_address.to_string(),
@@ -395,30 +1098,37 @@ where
                     amount,
                     price_in_sol,
                     total_sol,
-                    signature: None,
+                    signature,
                     success: false,
                     error_message: Some(format!("Failed to execute swap: {}", e)),
+                    error: swap_failure(e, slippage),
+                    requested_amount: None,
                 })
             }
         }
     }
 
-    async fn execute_sell_trade(
-        &self,
-        telegram_id: i64,
-        keypair: &Keypair,
-        user_address: &str,
-        token_address: &str,
-        token_symbol: &str,
-        amount: f64,
-        price_in_sol: f64,
-        total_sol: f64,
-    ) -> Result<TradeResult> {
+    async fn execute_sell_trade(&self, params: TradeParams<'_>) -> Result<TradeResult> {
+        let TradeParams {
+            telegram_id,
+            keypair,
+            user_address,
+            token_address,
+            token_symbol,
+            amount,
+            price_in_sol,
+            total_sol,
+            slippage,
+            route,
+            priority_fee,
+        } = params;
+
         // For SELL: We're trading from the token to SOL (wrapped SOL)
         let source_token = token_address;
         let target_token = "So11111111111111111111111111111111111111112"; // Wrapped SOL address
 
-        // Check if user has enough tokens to sell
+        // The amount may have been sized off cached balances, the on-chain balance read here
+        // is authoritative and the sell is capped to it
         let token_balances = solana::get_token_balances(&self.solana_client, &user_address).await?;
         let token_balance = token_balances
             .iter()
@@ -426,7 +1136,12 @@ where
             .map(|balance| balance.amount)
             .unwrap_or(0.0);
 
-        if token_balance < amount {
+        if token_balance <= 0.0 {
+            let error = BotError::InsufficientToken {
+                symbol: token_symbol.to_string(),
+                required: amount,
+                available: token_balance,
+            };
             return Ok(TradeResult {
                 token_address: token_address.to_string(),
                 token_symbol: token_symbol.to_string(),
@@ -435,24 +1150,39 @@ where
                 total_sol,
                 signature: None,
                 success: false,
-                error_message: Some(format!(
-                    "Insufficient token balance. Required: {} {}",
-                    amount, token_symbol
-                )),
+                error_message: Some(error.to_string()),
+                error: Some(error),
+                requested_amount: None,
             });
         }
 
-        // For slippage, use a default value
-        let slippage = 0.01; // 1%
+        let (amount, total_sol, requested_amount) =
+            cap_sell_to_balance(amount, total_sol, token_balance);
+        if let Some(requested) = requested_amount {
+            warn!(
+                "Sell of {} {} reduced to the on-chain balance of {}",
+                requested, token_symbol, token_balance
+            );
+        }
+
+        let swap = SwapParams {
+            amount,
+            source_token,
+            target_token,
+            slippage,
+            route,
+            platform_fee: None,
+        };
 
         // Prepare the swap
         let swap_response = match self
             .swap_service
-            .prepare_swap(amount, source_token, target_token, slippage, user_address)
+            .prepare_swap(&swap, priority_fee.clone(), user_address)
             .await
         {
             Ok(response) => response,
             Err(e) => {
+                error!("Failed to prepare swap: {}", e);
                 return Ok(TradeResult {
                     token_address: token_address.to_string(),
                     token_symbol: token_symbol.to_string(),
@@ -462,58 +1192,83 @@ where
                     signature: None,
                     success: false,
                     error_message: Some(format!("Failed to prepare swap: {}", e)),
+                    error: None,
+                    requested_amount,
                 });
             }
         };
 
         // Execute the swap transaction
+        info!("Swap prepared, executing transaction");
         match self
             .swap_service
-            .execute_swap_transaction(&self.solana_client, keypair, &swap_response)
+            .execute_swap_with_retries(
+                &self.solana_client,
+                keypair,
+                swap_response,
+                self.submitted.as_ref(),
+                |attempt| {
+                    self.swap_service.prepare_swap(
+                        &swap,
+                        priority_fee.as_ref().map(|fee| fee.bumped(attempt)),
+                        user_address,
+                    )
+                },
+            )
             .await
         {
             Ok(signature) => {
-                // Record the trade in the database
-                let _ = db::record_trade(
-                    &self.db_pool,
-                    telegram_id,
-                    token_address,
-                    token_symbol,
-                    amount,
-                    price_in_sol,
-                    total_sol,
-                    "SELL",
-                    &Some(signature.clone()),
-                    "SUCCESS",
-                )
-                .await;
+                info!("Swap executed: {}", signature);
 
-                Ok(TradeResult {
+                let result = TradeResult {
                     token_address: token_address.to_string(),
                     token_symbol: token_symbol.to_string(),
                     amount,
                     price_in_sol,
                     total_sol,
-                    signature: Some(signature),
+                    signature: Some(signature.clone()),
                     success: true,
                     error_message: None,
-                })
+                    error: None,
+                    requested_amount,
+                };
+
+                // Record the trade with the order fill, if it executed a limit order
+                // A capped sell filled less of the limit order than planned
+                let limit_order_fill = self.limit_order_fill(result.shortfall());
+                let settlement = db::TradeSettlement {
+                    limit_order_fill: limit_order_fill.as_ref(),
+                    platform_fee: None,
+                };
+                let trade = db::TradeRecord {
+                    telegram_id,
+                    token_address,
+                    token_symbol,
+                    amount,
+                    price_in_sol,
+                    total_paid: total_sol,
+                    trade_type: "SELL",
+                };
+                self.record_settlement(&trade, &signature, &settlement)
+                    .await?;
+
+                Ok(result)
             }
             Err(e) => {
+                error!("Failed to execute swap: {}", e);
+
                 // Record failed transaction
-                let _ = db::record_trade(
-                    &self.db_pool,
+                let (signature, status) = BotError::failed_transaction_record(&e);
+                let trade = db::TradeRecord {
                     telegram_id,
                     token_address,
                     token_symbol,
                     amount,
                     price_in_sol,
-                    total_sol,
-                    "SELL",
-                    &None::<String>,
-                    "FAILED",
-                )
-                .await;
+                    total_paid: total_sol,
+                    trade_type: "SELL",
+                };
+                let _ = db::record_trade(&self.db_pool, &trade, &signature, status).await;
 
                 Ok(TradeResult {
                     token_address: token_address.to_string(),
@@ -521,11 +1276,611 @@ where
                     amount,
                     price_in_sol,
                     total_sol,
-                    signature: None,
+                    signature,
                     success: false,
                     error_message: Some(format!("Failed to execute swap: {}", e)),
+                    error: swap_failure(e, slippage),
+                    requested_amount,
                 })
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{LimitOrderStatus, TokenPrice, TokenRisk};
+    use crate::testing::{fake_rpc, rpc_version};
+    use sqlx::postgres::PgPoolOptions;
+    use std::collections::HashMap;
+
+    const TOKEN: &str = "Token11111111111111111111111111111111111111";
+
+    struct TestTokens;
+
+    #[async_trait]
+    impl TokenRepository for TestTokens {
+        async fn get_token_by_id(&self, token_id: &str) -> Result<Token> {
+            Ok(Token {
+                id: token_id.to_string(),
+                symbol: "TKN".to_string(),
+                name: "Token".to_string(),
+                decimals: 6,
+                logo_uri: String::new(),
+                verified: true,
+            })
+        }
+
+        async fn search_tokens(&self, _query: &str) -> Result<Vec<Token>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_token_risk(&self, _mint: &str) -> Result<TokenRisk> {
+            Err(anyhow!("not used"))
+        }
+    }
+
+    struct NoQuotes;
+
+    #[async_trait]
+    impl QuoteService for NoQuotes {
+        async fn get_swap_quote_with_route(
+            &self,
+            _amount: f64,
+            _source_token: &str,
+            _target_token: &str,
+            _slippage: f64,
+            _route: RouteOptions,
+        ) -> Result<QuoteResponse> {
+            Err(anyhow!("not used"))
+        }
+    }
+
+    // A cached price of 0.4 SOL, while the market is at 0.5 SOL
+    struct StaleCache;
+
+    fn price(price_in_sol: f64) -> TokenPrice {
+        TokenPrice {
+            token_id: TOKEN.to_string(),
+            symbol: "TKN".to_string(),
+            price_in_sol,
+            price_in_usdc: price_in_sol * 150.0,
+            timestamp: 0,
+            price_change_24h: None,
+        }
+    }
+
+    #[async_trait]
+    impl PriceService for StaleCache {
+        async fn get_sol_price(&self) -> Result<f64> {
+            Ok(150.0)
+        }
+
+        async fn get_token_price(&self, _token_id: &str) -> Result<TokenPrice> {
+            Ok(price(0.4))
+        }
+
+        async fn get_token_price_fresh(&self, _token_id: &str) -> Result<TokenPrice> {
+            Ok(price(0.5))
+        }
+
+        async fn get_prices(&self, _vs_token: Option<&str>) -> Result<HashMap<String, f64>> {
+            Ok(HashMap::new())
+        }
+    }
+
+    fn interactor() -> TradeInteractorImpl<TestTokens, NoQuotes> {
+        // Never connected, the tested calls don't touch the database or the network
+        let db_pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        let solana_client =
+            ResilientRpcClient::new(&["http://127.0.0.1:8899".to_string()]).unwrap();
+
+        TradeInteractorImpl::new(
+            Arc::new(db_pool),
+            Arc::new(solana_client),
+            Arc::new(StaleCache),
+            Arc::new(TestTokens),
+            Arc::new(SwapService::new(TestTokens, NoQuotes)),
+        )
+    }
+
+    // Sell order of 100 tokens at 0.5 SOL with 40 of them filled
+    fn sell_order() -> LimitOrder {
+        LimitOrder {
+            id: 7,
+            user_id: 1,
+            token_address: TOKEN.to_string(),
+            token_symbol: "TKN".to_string(),
+            order_type: OrderType::Sell.to_string(),
+            price_in_sol: 0.5,
+            amount: 100.0,
+            total_sol: 50.0,
+            current_price_in_sol: None,
+            tx_signature: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            status: LimitOrderStatus::Active.to_string(),
+            retry_count: 0,
+            expires_at: None,
+            failure_reason: None,
+            filled_amount: 40.0,
+            retry_slippage: None,
+            trigger_metric: "PRICE".to_string(),
+            trigger_value: None,
+            price_currency: "SOL".to_string(),
+            next_retry_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn capped_sell_of_the_last_slice_keeps_the_order_active() {
+        // The last 60 tokens, but the wallet only held 45
+        let interactor = interactor().with_limit_order_slice(sell_order(), 60.0);
+
+        let fill = interactor.limit_order_fill(15.0).unwrap();
+        assert_eq!(fill.filled_amount, 85.0);
+        assert_eq!(fill.status, LimitOrderStatus::Active);
+
+        let fill = interactor.limit_order_fill(0.0).unwrap();
+        assert_eq!(fill.filled_amount, 100.0);
+        assert_eq!(fill.status, LimitOrderStatus::Filled);
+    }
+
+    #[tokio::test]
+    async fn trades_are_priced_past_the_cache() {
+        let (symbol, price_in_sol, price_in_usdc) =
+            interactor().get_token_info(TOKEN).await.unwrap();
+
+        assert_eq!(symbol, "TKN");
+        assert_eq!(price_in_sol, 0.5);
+        assert_eq!(price_in_usdc, 75.0);
+    }
+
+    #[test]
+    fn sell_of_a_stale_cached_amount_is_capped_to_the_on_chain_balance() {
+        // The cache still shows 100 tokens worth 5 SOL, the wallet holds 80
+        let (amount, total_sol, requested) = cap_sell_to_balance(100.0, 5.0, 80.0);
+
+        assert_eq!(amount, 80.0);
+        assert_eq!(total_sol, 4.0);
+        assert_eq!(requested, Some(100.0));
+    }
+
+    #[test]
+    fn sell_within_the_on_chain_balance_is_kept() {
+        assert_eq!(cap_sell_to_balance(50.0, 2.5, 80.0), (50.0, 2.5, None));
+        assert_eq!(cap_sell_to_balance(80.0, 4.0, 80.0), (80.0, 4.0, None));
+    }
+
+    #[test]
+    fn buy_into_an_existing_token_account_only_reserves_fees() {
+        let required = required_sol_for_buy(1.0, true);
+
+        assert!((required - 1.001).abs() < 1e-12);
+    }
+
+    #[test]
+    fn buy_creating_the_token_account_also_reserves_its_rent() {
+        let required = required_sol_for_buy(1.0, false);
+
+        assert!((required - 1.00303928).abs() < 1e-12);
+        // A wallet holding exactly the trade amount plus fees can't pay the rent
+        assert!(1.001 < required);
+    }
+
+    #[test]
+    fn insufficient_sol_error_includes_the_reserve() {
+        let error = BotError::InsufficientSol {
+            required: required_sol_for_buy(0.5, false),
+            available: 0.5,
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "Insufficient funds: 0.503039 SOL needed including fees, wallet has 0.500000 SOL"
+        );
+    }
+
+    // SOL that stays in the wallet for fees and the new token account
+    const RESERVE_SOL: f64 = 0.00303928;
+
+    #[tokio::test]
+    async fn buy_spend_accepts_sol_amounts() {
+        let interactor = interactor();
+
+        assert_eq!(
+            interactor.validate_buy_spend("0.5 SOL", 1.0).await.unwrap(),
+            0.5
+        );
+        assert_eq!(
+            interactor.validate_buy_spend("0,25sol", 1.0).await.unwrap(),
+            0.25
+        );
+    }
+
+    #[tokio::test]
+    async fn buy_spend_accepts_percentages_of_the_balance() {
+        let interactor = interactor();
+
+        assert_eq!(
+            interactor.validate_buy_spend("25%", 1.0).await.unwrap(),
+            0.25
+        );
+        // The whole balance can't be spent, the reserve is kept
+        let all = interactor.validate_buy_spend("100%", 1.0).await.unwrap();
+        assert!((all - (1.0 - RESERVE_SOL)).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn buy_spend_all_keeps_the_reserve() {
+        let all = interactor().validate_buy_spend("All", 1.0).await.unwrap();
+
+        assert!((all - (1.0 - RESERVE_SOL)).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn buy_spend_rejects_more_than_the_spendable_balance() {
+        let interactor = interactor();
+
+        let err = interactor
+            .validate_buy_spend("2 SOL", 1.0)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("spend up to 0.996961 SOL"));
+
+        let err = interactor
+            .validate_buy_spend("All", 0.001)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Keep ~0.003 SOL"));
+    }
+
+    #[tokio::test]
+    async fn buy_spend_rejects_invalid_input() {
+        let interactor = interactor();
+
+        for input in ["0%", "150%", "abc%", "0 SOL", "lots sol", "10", ""] {
+            assert!(
+                interactor.validate_buy_spend(input, 1.0).await.is_err(),
+                "{}",
+                input
+            );
+        }
+    }
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    // Jupiter quote for 1 SOL to USDC in the API's JSON format, amounts in base units
+    fn sample_quote(
+        out_amount: &str,
+        other_amount_threshold: &str,
+        price_impact_pct: &str,
+        route: &[(&str, u8)],
+    ) -> QuoteResponse {
+        let route_plan: Vec<serde_json::Value> = route
+            .iter()
+            .map(|(label, percent)| {
+                serde_json::json!({
+                    "swapInfo": {
+                        "ammKey": "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2",
+                        "label": label,
+                        "inputMint": SOL_MINT,
+                        "outputMint": USDC,
+                        "inAmount": "1000000000",
+                        "outAmount": out_amount,
+                        "feeAmount": "250000",
+                        "feeMint": SOL_MINT,
+                    },
+                    "percent": percent,
+                })
+            })
+            .collect();
+
+        serde_json::from_value(serde_json::json!({
+            "inputMint": SOL_MINT,
+            "inAmount": "1000000000",
+            "outputMint": USDC,
+            "outAmount": out_amount,
+            "otherAmountThreshold": other_amount_threshold,
+            "swapMode": "ExactIn",
+            "slippageBps": 50,
+            "platformFee": null,
+            "priceImpactPct": price_impact_pct,
+            "routePlan": route_plan,
+            "contextSlot": 299_000_000u64,
+            "timeTaken": 0.01,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn minimum_received_is_the_decoded_quote_threshold() {
+        let quote = sample_quote("150000000", "149250000", "0.0001", &[("Raydium", 100)]);
+
+        let trade_quote = TradeQuote::from_quote(&quote, "USDC", 6, 0.5);
+
+        assert_eq!(trade_quote.output_symbol, "USDC");
+        assert_eq!(trade_quote.expected_out, 150.0);
+        assert_eq!(trade_quote.minimum_out, 149.25);
+        assert_eq!(trade_quote.slippage, 0.5);
+    }
+
+    #[test]
+    fn quote_above_the_impact_threshold_is_high() {
+        let quote = sample_quote("140000000", "139300000", "0.0612", &[("Raydium", 100)]);
+
+        let trade_quote = TradeQuote::from_quote(&quote, "USDC", 6, 0.5);
+
+        assert!((trade_quote.price_impact - 6.12).abs() < 1e-9);
+        assert!(trade_quote.is_high_price_impact());
+    }
+
+    #[test]
+    fn quote_below_the_impact_threshold_is_not_high() {
+        let quote = sample_quote("150000000", "149250000", "0.0012", &[("Raydium", 100)]);
+
+        let trade_quote = TradeQuote::from_quote(&quote, "USDC", 6, 0.5);
+
+        assert!((trade_quote.price_impact - 0.12).abs() < 1e-9);
+        assert!(!trade_quote.is_high_price_impact());
+    }
+
+    #[test]
+    fn split_route_labels_show_each_share() {
+        let quote = sample_quote(
+            "150000000",
+            "149250000",
+            "0.0001",
+            &[("Orca", 40), ("Raydium", 60)],
+        );
+
+        let trade_quote = TradeQuote::from_quote(&quote, "USDC", 6, 0.5);
+
+        assert_eq!(trade_quote.route, vec!["Orca (40%)", "Raydium (60%)"]);
+    }
+
+    #[test]
+    fn single_hop_route_shows_the_plain_label() {
+        let quote = sample_quote("150000000", "149250000", "0.0001", &[("Meteora DLMM", 100)]);
+
+        let trade_quote = TradeQuote::from_quote(&quote, "USDC", 6, 0.5);
+
+        assert_eq!(trade_quote.route, vec!["Meteora DLMM"]);
+    }
+
+    #[test]
+    fn slippage_failure_is_typed_with_the_tolerance_in_percent() {
+        let error = anyhow!("Transaction simulation failed: custom program error: 0x1771");
+
+        assert!(matches!(
+            swap_failure(error, 0.01),
+            Some(BotError::SlippageExceeded { slippage }) if slippage == 1.0
+        ));
+    }
+
+    #[test]
+    fn typed_swap_failure_is_kept() {
+        let error: anyhow::Error = BotError::InsufficientSol {
+            required: 1.2,
+            available: 0.5,
+        }
+        .into();
+
+        assert!(matches!(
+            swap_failure(error, 0.005),
+            Some(BotError::InsufficientSol { required, available })
+                if required == 1.2 && available == 0.5
+        ));
+    }
+
+    #[test]
+    fn unknown_swap_failure_has_no_type() {
+        assert!(swap_failure(anyhow!("Jupiter returned 502"), 0.005).is_none());
+    }
+
+    fn sell(amount: f64) -> TradeRequest<'static> {
+        TradeRequest {
+            trade_type: &OrderType::Sell,
+            token_address: TOKEN,
+            token_symbol: "TKN",
+            amount,
+            price_in_sol: 0.01,
+            slippage: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn trade_within_the_cooldown_is_rejected() {
+        let cooldown = Arc::new(TradeCooldown::new(std::time::Duration::from_secs(60)));
+        cooldown.record(1001, TOKEN);
+        let interactor = interactor().with_cooldown(cooldown);
+
+        let result = interactor.execute_trade(1001, sell(10.0)).await.unwrap();
+
+        assert!(!result.success);
+        assert_eq!(
+            result.error_message.as_deref(),
+            Some("Please wait 60s before trading TKN again")
+        );
+    }
+
+    #[tokio::test]
+    async fn trade_after_the_cooldown_goes_on_to_the_wallet() {
+        let cooldown = Arc::new(TradeCooldown::new(std::time::Duration::from_secs(60)));
+        let traded_at = std::time::Instant::now() - std::time::Duration::from_secs(61);
+        cooldown.record_at(1001, TOKEN, traded_at);
+
+        // No database behind the pool, reaching the wallet lookup means the cooldown let it pass
+        let db_pool = PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(100))
+            .connect_lazy("postgres://127.0.0.1:9/unused")
+            .unwrap();
+        let mut interactor = interactor().with_cooldown(cooldown);
+        interactor.db_pool = Arc::new(db_pool);
+
+        assert!(interactor.execute_trade(1001, sell(10.0)).await.is_err());
+    }
+
+    // Records the slippage each quote is asked for, then fails before anything is sent
+    #[derive(Clone, Default)]
+    struct RecordingQuotes {
+        slippages: Arc<std::sync::Mutex<Vec<f64>>>,
+    }
+
+    #[async_trait]
+    impl QuoteService for RecordingQuotes {
+        async fn get_swap_quote_with_route(
+            &self,
+            _amount: f64,
+            _source_token: &str,
+            _target_token: &str,
+            slippage: f64,
+            _route: RouteOptions,
+        ) -> Result<QuoteResponse> {
+            self.slippages.lock().unwrap().push(slippage);
+            Err(anyhow!("no quote in tests"))
+        }
+    }
+
+    // JSON-RPC node where every wallet holds 10 SOL and no token accounts
+    async fn serve_rpc() -> String {
+        let (url, _calls) = fake_rpc(|method, params| {
+            let context = serde_json::json!({ "slot": 1 });
+            match method {
+                "getBalance" => {
+                    serde_json::json!({ "context": context, "value": 10_000_000_000u64 })
+                }
+                "getMultipleAccounts" => {
+                    let count = params[0].as_array().map_or(0, Vec::len);
+                    let accounts = vec![serde_json::Value::Null; count];
+                    serde_json::json!({ "context": context, "value": accounts })
+                }
+                "getVersion" => rpc_version(),
+                method => panic!("unexpected RPC call {}", method),
+            }
+        })
+        .await;
+
+        url
+    }
+
+    // A user at 2% slippage with a wallet, trading through the fake node
+    async fn slippage_interactor(
+        pool: PgPool,
+        quotes: RecordingQuotes,
+    ) -> TradeInteractorImpl<TestTokens, RecordingQuotes> {
+        db::create_user(&pool, 1001, None, None).await.unwrap();
+        let (mnemonic, keypair, address) = solana::generate_wallet().unwrap();
+        db::save_wallet_info(&pool, 1001, &address, &keypair, &mnemonic)
+            .await
+            .unwrap();
+        db::update_user_slippage(&pool, 1001, 200).await.unwrap();
+
+        let solana_client = ResilientRpcClient::new(&[serve_rpc().await]).unwrap();
+
+        TradeInteractorImpl::new(
+            Arc::new(pool),
+            Arc::new(solana_client),
+            Arc::new(StaleCache),
+            Arc::new(TestTokens),
+            Arc::new(SwapService::new(TestTokens, quotes)),
+        )
+    }
+
+    fn buy(slippage: Option<f64>) -> TradeRequest<'static> {
+        TradeRequest {
+            trade_type: &OrderType::Buy,
+            token_address: USDC,
+            token_symbol: "USDC",
+            amount: 10.0,
+            price_in_sol: 0.01,
+            slippage,
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs DATABASE_URL"]
+    async fn stored_slippage_reaches_the_swap_as_a_fraction(pool: PgPool) {
+        let quotes = RecordingQuotes::default();
+        let interactor = slippage_interactor(pool, quotes.clone()).await;
+
+        let result = interactor.execute_trade(1001, buy(None)).await.unwrap();
+
+        assert!(!result.success);
+        assert_eq!(*quotes.slippages.lock().unwrap(), vec![0.02]);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs DATABASE_URL"]
+    async fn per_trade_slippage_replaces_the_stored_one(pool: PgPool) {
+        let quotes = RecordingQuotes::default();
+        let interactor = slippage_interactor(pool, quotes.clone()).await;
+
+        let result = interactor
+            .execute_trade(1001, buy(Some(3.0)))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        assert_eq!(*quotes.slippages.lock().unwrap(), vec![0.03]);
+    }
+
+    // Quotes with a price impact of 1% per SOL traded, there is no route above 1.5 SOL
+    struct ImpactQuotes;
+
+    #[async_trait]
+    impl QuoteService for ImpactQuotes {
+        async fn get_swap_quote_with_route(
+            &self,
+            amount: f64,
+            _source_token: &str,
+            _target_token: &str,
+            _slippage: f64,
+            _route: RouteOptions,
+        ) -> Result<QuoteResponse> {
+            if amount > 1.5 {
+                return Err(anyhow!("no route"));
+            }
+            let impact = (amount / 100.0).to_string();
+            Ok(sample_quote(
+                "150000000",
+                "149250000",
+                &impact,
+                &[("Raydium", 100)],
+            ))
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    #[ignore = "needs DATABASE_URL"]
+    async fn impact_preview_has_one_entry_per_size(pool: PgPool) {
+        db::create_user(&pool, 1001, None, None).await.unwrap();
+        let solana_client =
+            ResilientRpcClient::new(&["http://127.0.0.1:8899".to_string()]).unwrap();
+        let interactor = TradeInteractorImpl::new(
+            Arc::new(pool),
+            Arc::new(solana_client),
+            Arc::new(StaleCache),
+            Arc::new(TestTokens),
+            Arc::new(SwapService::new(TestTokens, ImpactQuotes)),
+        );
+
+        // 0.25, 0.5, 1 and 2 SOL at 0.01 SOL per token, the fifth size is over the cap
+        let sizes = [25.0, 50.0, 100.0, 200.0, 400.0];
+        let previews = interactor
+            .preview_impact(1001, &OrderType::Buy, TOKEN, &sizes, 0.01)
+            .await
+            .unwrap();
+
+        let amounts: Vec<f64> = previews.iter().map(|preview| preview.amount).collect();
+        assert_eq!(amounts, vec![25.0, 50.0, 100.0, 200.0]);
+        for (preview, expected) in previews.iter().zip([0.25, 0.5, 1.0]) {
+            assert!((preview.price_impact.unwrap() - expected).abs() < 1e-9);
+        }
+        // Unroutable sizes keep their entry without an impact
+        assert_eq!(previews[3].price_impact, None);
+    }
+}
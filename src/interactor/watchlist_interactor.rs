@@ -1,4 +1,4 @@
-use crate::entity::WatchlistItem;
+use crate::entity::{PriceCurrency, WatchlistItem, WatchlistPage, WatchlistSort};
 use crate::interactor::db;
 use crate::solana::jupiter::price_service::PriceService;
 use crate::solana::jupiter::token_repository::TokenRepository;
@@ -8,9 +8,16 @@ use async_trait::async_trait;
 use sqlx::PgPool;
 use std::sync::Arc;
 
+/// Number of tokens shown on one page of the watchlist
+pub const WATCHLIST_PAGE_SIZE: usize = 8;
+
 #[async_trait]
 pub trait WatchlistInteractor: Send + Sync {
     async fn get_watchlist(&self, telegram_id: i64) -> Result<Vec<WatchlistItem>>;
+    async fn get_watchlist_page(&self, telegram_id: i64, page: usize) -> Result<WatchlistPage>;
+    async fn get_default_quote(&self, telegram_id: i64) -> Result<PriceCurrency>;
+    async fn set_watchlist_sort(&self, telegram_id: i64, sort: WatchlistSort) -> Result<()>;
+    async fn toggle_watchlist_alerts_only(&self, telegram_id: i64) -> Result<()>;
     async fn add_to_watchlist(
         &self,
         telegram_id: i64,
@@ -23,7 +30,7 @@ pub trait WatchlistInteractor: Send + Sync {
         token_address: &str,
     ) -> Result<Option<WatchlistItem>>;
     async fn validate_token_address(&self, token_address: &str) -> Result<bool>;
-    async fn refresh_watchlist_prices(&self, telegram_id: i64) -> Result<Vec<WatchlistItem>>;
+    async fn refresh_watchlist_prices(&self, telegram_id: i64) -> Result<()>;
 }
 
 pub struct WatchlistInteractorImpl {
@@ -54,6 +61,85 @@ impl WatchlistInteractor for WatchlistInteractorImpl {
             .map_err(|e| anyhow!("Failed to get watchlist: {}", e))
     }
 
+    async fn get_watchlist_page(&self, telegram_id: i64, page: usize) -> Result<WatchlistPage> {
+        // Sort and filter are kept in the user's settings
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+        let sort = user.get_watchlist_sort();
+        let alerts_only = user.get_watchlist_alerts_only();
+        let quote = user.get_default_quote();
+
+        let total = db::count_user_watchlist(&self.db_pool, telegram_id, alerts_only)
+            .await
+            .map_err(|e| anyhow!("Failed to get watchlist: {}", e))? as usize;
+
+        // Pages past the end, e.g. after removing tokens, show the last page
+        let pages = total.div_ceil(WATCHLIST_PAGE_SIZE).max(1);
+        let page = page.min(pages - 1);
+
+        let items = db::get_user_watchlist_page(
+            &self.db_pool,
+            telegram_id,
+            sort,
+            alerts_only,
+            WATCHLIST_PAGE_SIZE as i64,
+            (page * WATCHLIST_PAGE_SIZE) as i64,
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get watchlist: {}", e))?;
+
+        // Prices are stored in SOL, USDC users see them converted at the current SOL price
+        let sol_price_in_usdc = match quote {
+            PriceCurrency::Sol => None,
+            PriceCurrency::Usdc => self.price_service.get_sol_price().await.ok(),
+        };
+
+        Ok(WatchlistPage {
+            items,
+            page,
+            pages,
+            sort,
+            alerts_only,
+            quote,
+            sol_price_in_usdc,
+        })
+    }
+
+    async fn get_default_quote(&self, telegram_id: i64) -> Result<PriceCurrency> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+
+        Ok(user.get_default_quote())
+    }
+
+    async fn set_watchlist_sort(&self, telegram_id: i64, sort: WatchlistSort) -> Result<()> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+
+        db::update_user_watchlist_view(
+            &self.db_pool,
+            telegram_id,
+            sort,
+            user.get_watchlist_alerts_only(),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to save watchlist sort: {}", e))?;
+
+        Ok(())
+    }
+
+    async fn toggle_watchlist_alerts_only(&self, telegram_id: i64) -> Result<()> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+
+        db::update_user_watchlist_view(
+            &self.db_pool,
+            telegram_id,
+            user.get_watchlist_sort(),
+            !user.get_watchlist_alerts_only(),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to save watchlist filter: {}", e))?;
+
+        Ok(())
+    }
+
     async fn add_to_watchlist(
         &self,
         telegram_id: i64,
@@ -119,7 +205,7 @@ impl WatchlistInteractor for WatchlistInteractorImpl {
         }
     }
 
-    async fn refresh_watchlist_prices(&self, telegram_id: i64) -> Result<Vec<WatchlistItem>> {
+    async fn refresh_watchlist_prices(&self, telegram_id: i64) -> Result<()> {
         // Get current watchlist
         let watchlist = self.get_watchlist(telegram_id).await?;
 
@@ -142,7 +228,6 @@ impl WatchlistInteractor for WatchlistInteractorImpl {
             }
         }
 
-        // Get updated watchlist
-        self.get_watchlist(telegram_id).await
+        Ok(())
     }
 }
@@ -1,13 +1,70 @@
-use crate::entity::{BotError, TokenBalance};
+use crate::entity::{BatchLine, BatchLineError, BatchWithdrawal, BotError, TokenBalance};
+use crate::interactor::balance_interactor::BalanceInteractorImpl;
 use crate::interactor::db;
 use crate::solana;
-use crate::solana::jupiter::PriceService;
+use crate::solana::client::ResilientRpcClient;
+use crate::solana::jupiter::{PriceService, SOL_MINT};
+use crate::solana::tokens::constants::{
+    ESTIMATED_SOL_FEE, TOKEN_2022_PROGRAM_ID, TOKEN_ACCOUNT_RENT_LAMPORTS,
+};
+use crate::solana::tokens::transaction::SubmittedSender;
+use crate::solana::utils::{address_kind, lamports_to_sol};
+use crate::solana::wallet::parse_pubkey;
+use crate::utils::parse_decimal;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use solana_client::nonblocking::rpc_client::RpcClient;
+use log::{error, info, warn};
+use solana_sdk::account::Account;
+use solana_sdk::program_pack::Pack;
 use sqlx::PgPool;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Outcome of checking a withdrawal recipient
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecipientCheck {
+    /// Regular wallet, `creates_token_account` is set when the recipient doesn't hold the
+    /// token yet and the sender pays rent for the new token account. `warning` is set for
+    /// off-curve addresses, sending there is allowed but may lock the funds.
+    Valid {
+        creates_token_account: bool,
+        warning: Option<String>,
+    },
+    /// Not a valid Solana address
+    InvalidAddress,
+    /// Token mint, token account or program, funds sent there would be lost
+    Rejected(String),
+}
+
+/// Reason why funds must not be sent to this account, `None` for a regular wallet
+///
+/// Accounts owned by a token program are mints or token accounts and executable accounts
+/// are programs. A missing account is a new wallet that hasn't received anything yet.
+pub fn recipient_rejection_reason(account: Option<&Account>) -> Option<String> {
+    let account = account?;
+
+    if account.executable {
+        return Some("This address is a program. Funds sent to it can't be recovered.".to_string());
+    }
+
+    let owner = account.owner.to_string();
+    if account.owner == spl_token::ID || owner == TOKEN_2022_PROGRAM_ID {
+        if account.data.len() == spl_token::state::Mint::LEN {
+            return Some(
+                "This address is a token mint, not a wallet. Funds sent to it can't be recovered."
+                    .to_string(),
+            );
+        }
+
+        return Some(
+            "This address is a token account. Please enter the owner's wallet address instead."
+                .to_string(),
+        );
+    }
+
+    None
+}
+
 pub struct WithdrawResult {
     pub token_address: String,
     pub token_symbol: String,
@@ -16,14 +73,23 @@ pub struct WithdrawResult {
     pub signature: Option<String>,
     pub success: bool,
     pub error_message: Option<String>,
+    /// Typed cause of the failure, lets the UI offer a fitting next step
+    pub error: Option<BotError>,
 }
 
 #[async_trait]
 pub trait WithdrawInteractor: Send + Sync {
     async fn get_user_tokens(&self, telegram_id: i64) -> Result<Vec<TokenBalance>>;
     async fn get_token_price(&self, token_address: &str) -> Result<(f64, f64)>;
-    async fn validate_recipient_address(&self, address: &str) -> Result<bool>;
+    // Checks the address is a regular wallet that can receive the token
+    async fn validate_recipient_address(
+        &self,
+        address: &str,
+        token_address: &str,
+    ) -> Result<RecipientCheck>;
     async fn validate_withdraw_amount(&self, amount_text: &str, token_balance: f64) -> Result<f64>;
+    // Whole SOL balance minus the network fee, returned as `(amount, fee)` in SOL
+    async fn max_sol_withdrawal(&self, telegram_id: i64) -> Result<(f64, f64)>;
     async fn execute_withdraw(
         &self,
         telegram_id: i64,
@@ -33,26 +99,47 @@ pub trait WithdrawInteractor: Send + Sync {
         amount: f64,
         price_in_sol: f64,
     ) -> Result<WithdrawResult>;
+    // Resolves the tokens of parsed batch lines, checks every recipient and the balances
+    async fn validate_batch(
+        &self,
+        telegram_id: i64,
+        lines: &[BatchLine],
+    ) -> Result<(Vec<BatchWithdrawal>, Vec<BatchLineError>)>;
+    // Sends the transfers one by one, the rest is skipped after a failure unless `continue_on_error`
+    async fn execute_batch(
+        &self,
+        telegram_id: i64,
+        withdrawals: &[BatchWithdrawal],
+        continue_on_error: bool,
+    ) -> Result<Vec<WithdrawResult>>;
 }
 
 pub struct WithdrawInteractorImpl {
     db_pool: Arc<PgPool>,
-    solana_client: Arc<RpcClient>,
+    solana_client: Arc<ResilientRpcClient>,
     price_service: Arc<dyn PriceService + Send + Sync>,
+    submitted: Option<SubmittedSender>,
 }
 
 impl WithdrawInteractorImpl {
     pub fn new(
         db_pool: Arc<PgPool>,
-        solana_client: Arc<RpcClient>,
+        solana_client: Arc<ResilientRpcClient>,
         price_service: Arc<dyn PriceService + Send + Sync>,
     ) -> Self {
         Self {
             db_pool,
             solana_client,
             price_service,
+            submitted: None,
         }
     }
+
+    /// Send the signature of the withdrawal to `submitted` as soon as it is on the network
+    pub fn with_submitted_sender(mut self, submitted: SubmittedSender) -> Self {
+        self.submitted = Some(submitted);
+        self
+    }
 }
 
 #[async_trait]
@@ -77,6 +164,7 @@ impl WithdrawInteractor for WithdrawInteractorImpl {
             symbol: "SOL".to_string(),
             amount: sol_balance,
             mint_address: "So11111111111111111111111111111111111111112".to_string(), // Wrapped SOL address
+            decimals: 9,
         });
 
         // Filter out zero balances
@@ -105,8 +193,47 @@ impl WithdrawInteractor for WithdrawInteractorImpl {
         }
     }
 
-    async fn validate_recipient_address(&self, address: &str) -> Result<bool> {
-        Ok(crate::utils::validate_solana_address(address))
+    async fn validate_recipient_address(
+        &self,
+        address: &str,
+        token_address: &str,
+    ) -> Result<RecipientCheck> {
+        if !crate::utils::validate_solana_address(address) {
+            return Ok(RecipientCheck::InvalidAddress);
+        }
+
+        let recipient = parse_pubkey(address)?;
+
+        let accounts = self
+            .solana_client
+            .get_multiple_accounts(&[recipient])
+            .await
+            .map_err(|e| anyhow!("Failed to get recipient account: {}", e))?;
+        let account = accounts.into_iter().next().flatten();
+
+        if let Some(reason) = recipient_rejection_reason(account.as_ref()) {
+            warn!("Rejected withdrawal recipient {}: {}", address, reason);
+            return Ok(RecipientCheck::Rejected(reason));
+        }
+
+        // Off-curve addresses are only warned about, some users send to them on purpose
+        let warning = address_kind(&recipient).warning();
+        if let Some(warning) = &warning {
+            warn!(
+                "Withdrawal recipient {} needs a warning: {}",
+                address, warning
+            );
+        }
+
+        // SOL needs no token account, SPL tokens create one if the recipient has none
+        let creates_token_account = token_address != SOL_MINT
+            && !solana::token_account_exists(&self.solana_client, &recipient, token_address)
+                .await?;
+
+        Ok(RecipientCheck::Valid {
+            creates_token_account,
+            warning,
+        })
     }
 
     async fn validate_withdraw_amount(&self, amount_text: &str, token_balance: f64) -> Result<f64> {
@@ -121,7 +248,7 @@ impl WithdrawInteractor for WithdrawInteractorImpl {
         // Check if it's a percentage
         if amount_text.ends_with('%') {
             let percentage_str = amount_text.trim_end_matches('%');
-            match percentage_str.parse::<f64>() {
+            match parse_decimal(percentage_str) {
                 Ok(percentage) if percentage > 0.0 && percentage <= 100.0 => {
                     let amount = token_balance * (percentage / 100.0);
                     if amount <= 0.0 {
@@ -139,7 +266,7 @@ impl WithdrawInteractor for WithdrawInteractorImpl {
         }
 
         // Regular amount validation
-        match amount_text.parse::<f64>() {
+        match parse_decimal(amount_text) {
             Ok(amount) if amount > 0.0 => {
                 if amount > token_balance {
                     return Err(anyhow!(
@@ -156,6 +283,24 @@ impl WithdrawInteractor for WithdrawInteractorImpl {
         }
     }
 
+    async fn max_sol_withdrawal(&self, telegram_id: i64) -> Result<(f64, f64)> {
+        let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
+
+        let address = user
+            .solana_address
+            .ok_or_else(|| BotError::WalletNotFound)?;
+
+        let (lamports, fee) = solana::max_sol_transfer(&self.solana_client, &address).await?;
+        if lamports == 0 {
+            return Err(anyhow!(
+                "Your SOL balance doesn't cover the network fee of {} SOL",
+                lamports_to_sol(fee)
+            ));
+        }
+
+        Ok((lamports_to_sol(lamports), lamports_to_sol(fee)))
+    }
+
     async fn execute_withdraw(
         &self,
         telegram_id: i64,
@@ -169,7 +314,7 @@ impl WithdrawInteractor for WithdrawInteractorImpl {
         let user = db::get_user_by_telegram_id(&self.db_pool, telegram_id).await?;
 
         match (user.solana_address, user.encrypted_private_key) {
-            (Some(_), Some(keypair_base58)) => {
+            (Some(user_address), Some(keypair_base58)) => {
                 // Get private key
                 let keypair = match solana::keypair_from_base58(&keypair_base58) {
                     Ok(k) => k,
@@ -182,26 +327,42 @@ impl WithdrawInteractor for WithdrawInteractorImpl {
                             signature: None,
                             success: false,
                             error_message: Some(format!("Error with private key: {}", e)),
+                            error: None,
                         });
                     }
                 };
 
                 // Send transaction
+                info!("Sending {} {} to {}", amount, token_symbol, recipient);
                 let result = if token_symbol.to_uppercase() == "SOL" {
-                    solana::send_sol(&self.solana_client, &keypair, recipient, amount).await
+                    solana::send_sol(
+                        &self.solana_client,
+                        &keypair,
+                        recipient,
+                        amount,
+                        self.submitted.as_ref(),
+                    )
+                    .await
                 } else {
                     solana::send_spl_token(
                         &self.solana_client,
                         &keypair,
                         recipient,
+                        token_address,
                         token_symbol,
                         amount,
+                        self.submitted.as_ref(),
                     )
                     .await
                 };
 
+                // The wallet's balances have changed, don't show the cached ones
+                BalanceInteractorImpl::invalidate(&user_address);
+
                 match result {
                     Ok(signature) => {
+                        info!("Withdrawal sent: {}", signature);
+
                         // Record transaction to database
                         let _ = db::record_transaction(
                             &self.db_pool,
@@ -222,18 +383,22 @@ impl WithdrawInteractor for WithdrawInteractorImpl {
                             signature: Some(signature),
                             success: true,
                             error_message: None,
+                            error: None,
                         })
                     }
                     Err(e) => {
+                        error!("Withdrawal failed: {}", e);
+
                         // Record failed transaction
+                        let (signature, status) = BotError::failed_transaction_record(&e);
                         let _ = db::record_transaction(
                             &self.db_pool,
                             telegram_id,
                             recipient,
                             amount,
                             token_symbol,
-                            &None::<String>,
-                            "FAILED",
+                            &signature,
+                            status,
                         )
                         .await;
 
@@ -242,9 +407,10 @@ impl WithdrawInteractor for WithdrawInteractorImpl {
                             token_symbol: token_symbol.to_string(),
                             amount,
                             recipient: recipient.to_string(),
-                            signature: None,
+                            signature,
                             success: false,
                             error_message: Some(e.to_string()),
+                            error: e.downcast::<BotError>().ok(),
                         })
                     }
                 }
@@ -259,7 +425,321 @@ impl WithdrawInteractor for WithdrawInteractorImpl {
                 error_message: Some(
                     "Wallet not found. Use /create_wallet to create a new wallet.".to_string(),
                 ),
+                error: None,
             }),
         }
     }
+
+    async fn validate_batch(
+        &self,
+        telegram_id: i64,
+        lines: &[BatchLine],
+    ) -> Result<(Vec<BatchWithdrawal>, Vec<BatchLineError>)> {
+        let tokens = self.get_user_tokens(telegram_id).await?;
+
+        let mut withdrawals = Vec::new();
+        let mut errors = Vec::new();
+        let mut prices: HashMap<String, f64> = HashMap::new();
+        // Total amount and last line of every token, the balance error is shown on that line
+        let mut totals: HashMap<String, (f64, usize)> = HashMap::new();
+        let mut new_token_accounts = 0;
+
+        for line in lines {
+            let token_query = line.token.as_deref().unwrap_or("SOL");
+            let Some(token) = tokens.iter().find(|token| {
+                token.symbol.eq_ignore_ascii_case(token_query) || token.mint_address == token_query
+            }) else {
+                errors.push(BatchLineError::new(
+                    line.line,
+                    format!("you don't hold any {}", token_query),
+                ));
+                continue;
+            };
+
+            match self
+                .validate_recipient_address(&line.recipient, &token.mint_address)
+                .await
+            {
+                // Batches are pasted from prepared lists, off-curve warnings aren't repeated here
+                Ok(RecipientCheck::Valid {
+                    creates_token_account,
+                    ..
+                }) => {
+                    if creates_token_account {
+                        new_token_accounts += 1;
+                    }
+                }
+                Ok(RecipientCheck::InvalidAddress) => {
+                    errors.push(BatchLineError::new(line.line, "invalid Solana address"));
+                    continue;
+                }
+                Ok(RecipientCheck::Rejected(reason)) => {
+                    errors.push(BatchLineError::new(line.line, reason));
+                    continue;
+                }
+                Err(e) => {
+                    errors.push(BatchLineError::new(line.line, e.to_string()));
+                    continue;
+                }
+            }
+
+            let price_in_sol = match prices.get(&token.mint_address) {
+                Some(price_in_sol) => *price_in_sol,
+                None => {
+                    // The price is only shown in the summary, a missing one doesn't block the transfer
+                    let price_in_sol = self
+                        .get_token_price(&token.mint_address)
+                        .await
+                        .map(|(price_in_sol, _)| price_in_sol)
+                        .unwrap_or(0.0);
+                    prices.insert(token.mint_address.clone(), price_in_sol);
+                    price_in_sol
+                }
+            };
+
+            let total = totals
+                .entry(token.mint_address.clone())
+                .or_insert((0.0, line.line));
+            total.0 += line.amount;
+            total.1 = line.line;
+
+            withdrawals.push(BatchWithdrawal {
+                recipient: line.recipient.clone(),
+                amount: line.amount,
+                token_address: token.mint_address.clone(),
+                token_symbol: token.symbol.clone(),
+                price_in_sol,
+            });
+        }
+
+        for (token_address, (total, last_line)) in &totals {
+            let Some(token) = tokens.iter().find(|t| &t.mint_address == token_address) else {
+                continue;
+            };
+
+            if token_address != SOL_MINT && *total > token.amount {
+                errors.push(BatchLineError::new(
+                    *last_line,
+                    format!(
+                        "the batch sends {} {} but your balance is {} {}",
+                        total, token.symbol, token.amount, token.symbol
+                    ),
+                ));
+            }
+        }
+
+        // Every transfer pays a network fee and new token accounts cost rent, both in SOL
+        if let Some(last_line) = lines.last().map(|line| line.line) {
+            let sol_balance = tokens
+                .iter()
+                .find(|token| token.mint_address == SOL_MINT)
+                .map_or(0.0, |token| token.amount);
+            let sol_sent = totals.get(SOL_MINT).map_or(0.0, |(total, _)| *total);
+            let fees = lamports_to_sol(
+                ESTIMATED_SOL_FEE * withdrawals.len() as u64
+                    + TOKEN_ACCOUNT_RENT_LAMPORTS * new_token_accounts,
+            );
+
+            if !withdrawals.is_empty() && sol_sent + fees > sol_balance {
+                errors.push(BatchLineError::new(
+                    last_line,
+                    format!(
+                        "the batch needs {} SOL including network fees and rent but your balance is {} SOL",
+                        sol_sent + fees,
+                        sol_balance
+                    ),
+                ));
+            }
+        }
+
+        errors.sort_by_key(|error| error.line);
+
+        Ok((withdrawals, errors))
+    }
+
+    async fn execute_batch(
+        &self,
+        telegram_id: i64,
+        withdrawals: &[BatchWithdrawal],
+        continue_on_error: bool,
+    ) -> Result<Vec<WithdrawResult>> {
+        let mut results = Vec::new();
+
+        for withdrawal in withdrawals {
+            let result = self
+                .execute_withdraw(
+                    telegram_id,
+                    &withdrawal.token_address,
+                    &withdrawal.token_symbol,
+                    &withdrawal.recipient,
+                    withdrawal.amount,
+                    withdrawal.price_in_sol,
+                )
+                .await
+                .unwrap_or_else(|e| WithdrawResult {
+                    token_address: withdrawal.token_address.clone(),
+                    token_symbol: withdrawal.token_symbol.clone(),
+                    amount: withdrawal.amount,
+                    recipient: withdrawal.recipient.clone(),
+                    signature: None,
+                    success: false,
+                    error_message: Some(e.to_string()),
+                    error: e.downcast::<BotError>().ok(),
+                });
+
+            let failed = !result.success;
+            results.push(result);
+
+            if failed && !continue_on_error {
+                info!(
+                    "Batch withdrawal of {} stopped after a failed transfer",
+                    telegram_id
+                );
+                break;
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn account(owner: Pubkey, data_len: usize, executable: bool) -> Account {
+        Account {
+            lamports: 1_461_600,
+            data: vec![0; data_len],
+            owner,
+            executable,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn wallet_and_new_addresses_are_accepted() {
+        let wallet = account(solana_sdk::system_program::ID, 0, false);
+
+        assert_eq!(recipient_rejection_reason(Some(&wallet)), None);
+        // Nothing was ever sent to it, the account doesn't exist yet
+        assert_eq!(recipient_rejection_reason(None), None);
+    }
+
+    #[test]
+    fn token_mint_is_rejected() {
+        let mint = account(spl_token::ID, spl_token::state::Mint::LEN, false);
+
+        let reason = recipient_rejection_reason(Some(&mint)).unwrap();
+        assert!(reason.contains("token mint"));
+    }
+
+    #[test]
+    fn token_2022_mint_is_rejected() {
+        let owner = parse_pubkey(TOKEN_2022_PROGRAM_ID).unwrap();
+        let mint = account(owner, spl_token::state::Mint::LEN, false);
+
+        let reason = recipient_rejection_reason(Some(&mint)).unwrap();
+        assert!(reason.contains("token mint"));
+    }
+
+    #[test]
+    fn token_account_is_rejected_with_a_hint_to_use_the_owner() {
+        let token_account = account(spl_token::ID, spl_token::state::Account::LEN, false);
+
+        let reason = recipient_rejection_reason(Some(&token_account)).unwrap();
+        assert!(reason.contains("token account"));
+        assert!(reason.contains("owner's wallet address"));
+    }
+
+    #[test]
+    fn program_is_rejected() {
+        let program = account(solana_sdk::bpf_loader_upgradeable::ID, 36, true);
+
+        let reason = recipient_rejection_reason(Some(&program)).unwrap();
+        assert!(reason.contains("is a program"));
+    }
+
+    #[tokio::test]
+    async fn malformed_address_is_invalid_without_an_rpc_call() {
+        let services = crate::commands::testing::services();
+        let interactor = WithdrawInteractorImpl::new(
+            services.db_pool(),
+            services.solana_client(),
+            services.price_service(),
+        );
+
+        let check = interactor
+            .validate_recipient_address("not-an-address", SOL_MINT)
+            .await
+            .unwrap();
+
+        assert_eq!(check, RecipientCheck::InvalidAddress);
+    }
+
+    fn batch(recipients: usize) -> Vec<BatchWithdrawal> {
+        (0..recipients)
+            .map(|i| BatchWithdrawal {
+                recipient: Pubkey::new_unique().to_string(),
+                amount: 0.1 * (i + 1) as f64,
+                token_address: SOL_MINT.to_string(),
+                token_symbol: "SOL".to_string(),
+                price_in_sol: 1.0,
+            })
+            .collect()
+    }
+
+    // Every transfer fails, there is no database behind the pool
+    fn failing_interactor() -> WithdrawInteractorImpl {
+        let services = crate::commands::testing::services();
+        let db_pool = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(100))
+            .connect_lazy("postgres://127.0.0.1:9/unused")
+            .unwrap();
+
+        WithdrawInteractorImpl::new(
+            Arc::new(db_pool),
+            services.solana_client(),
+            services.price_service(),
+        )
+    }
+
+    #[tokio::test]
+    async fn batch_stops_at_the_first_failed_transfer() {
+        let withdrawals = batch(3);
+
+        let results = failing_interactor()
+            .execute_batch(1001, &withdrawals, false)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].success);
+        assert_eq!(results[0].recipient, withdrawals[0].recipient);
+        assert!(results[0].error_message.is_some());
+    }
+
+    #[tokio::test]
+    async fn batch_continues_past_failed_transfers_when_asked() {
+        let withdrawals = batch(3);
+
+        let results = failing_interactor()
+            .execute_batch(1001, &withdrawals, true)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|result| !result.success));
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| result.amount)
+                .collect::<Vec<_>>(),
+            withdrawals
+                .iter()
+                .map(|withdrawal| withdrawal.amount)
+                .collect::<Vec<_>>()
+        );
+    }
 }
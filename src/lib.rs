@@ -10,8 +10,12 @@ pub mod commands;
 pub mod di;
 /// Domain entities and data structures
 pub mod entity;
+/// Localized bot messages
+pub mod i18n;
 /// Business logic interactors
 pub mod interactor;
+/// Request-scoped logging context
+pub mod logging;
 /// Presentation layer
 pub mod presenter;
 /// QR code utility functions
@@ -20,6 +24,9 @@ pub mod qrcodeutils;
 pub mod router;
 /// Solana blockchain interactions
 pub mod solana;
+/// Fake servers for tests
+#[cfg(test)]
+pub(crate) mod testing;
 /// Utility functions
 pub mod utils;
 /// View layer for rendering responses
@@ -34,13 +41,26 @@ pub use entity::{State, TokenBalance, User};
 pub use interactor::db;
 pub use presenter::Presenter;
 pub use router::{Router, TelegramRouter};
-pub use solana::create_solana_client;
+pub use solana::{create_solana_client, ResilientRpcClient};
 use teloxide::dispatching::dialogue::InMemStorage;
 pub use utils::{generate_qr_code, validate_solana_address};
 
 /// Version of the library
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Router, bot, services and dialogue storage returned by [`create_application`]
+pub type Application = (
+    TelegramRouter,
+    teloxide::Bot,
+    std::sync::Arc<ServiceContainer>,
+    std::sync::Arc<InMemStorage<State>>,
+    services::LimitOrderService,
+    services::DcaOrderService,
+    services::DialogueTimeoutService,
+    services::WalletFollowService,
+    services::DepositWatcher,
+);
+
 /// Create and initialize the application with all dependencies
 ///
 /// This function serves as the main entry point for creating a fully
@@ -50,18 +70,12 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 ///
 /// * `bot` - Telegram bot instance
 /// * `db_pool` - Database connection pool
-/// * `solana_client` - Initialized Solana client
+/// * `solana_client` - Initialized Solana client with RPC failover
 pub fn create_application(
     bot: teloxide::Bot,
     db_pool: std::sync::Arc<sqlx::PgPool>,
-    solana_client: std::sync::Arc<solana_client::nonblocking::rpc_client::RpcClient>,
-) -> (
-    TelegramRouter,
-    teloxide::Bot,
-    std::sync::Arc<ServiceContainer>,
-    std::sync::Arc<InMemStorage<State>>,
-    services::LimitOrderService,
-) {
+    solana_client: std::sync::Arc<solana::client::ResilientRpcClient>,
+) -> Application {
     use std::sync::Arc;
     use teloxide::dispatching::dialogue::InMemStorage;
 
@@ -78,5 +92,32 @@ pub fn create_application(
     let limit_order_service =
         services::LimitOrderService::new(service_container.clone(), bot.clone());
 
-    (router, bot, service_container, storage, limit_order_service)
+    // Create DCA order service
+    let dca_order_service = services::DcaOrderService::new(service_container.clone(), bot.clone());
+
+    // Create the service that reports trades of followed wallets
+    let wallet_follow_service =
+        services::WalletFollowService::new(service_container.clone(), bot.clone());
+
+    // Create the service that reports deposits to user wallets
+    let deposit_watcher = services::DepositWatcher::new(service_container.clone(), bot.clone());
+
+    // Create the service that resets abandoned dialogues
+    let dialogue_timeout_service = services::DialogueTimeoutService::new(
+        storage.clone(),
+        service_container.dialogue_activity(),
+        bot.clone(),
+    );
+
+    (
+        router,
+        bot,
+        service_container,
+        storage,
+        limit_order_service,
+        dca_order_service,
+        dialogue_timeout_service,
+        wallet_follow_service,
+        deposit_watcher,
+    )
 }
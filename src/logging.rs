@@ -0,0 +1,183 @@
+use std::future::Future;
+use tokio::task::JoinHandle;
+use tracing::{info_span, Instrument, Span};
+use tracing_subscriber::EnvFilter;
+
+/// Identifies a single user request in the logs
+///
+/// Router endpoints run their handler inside `RequestContext::scope`, which enters a
+/// `request` span with the correlation id and Telegram id. Every log line written while the
+/// handler runs carries the span fields, so one trade can be followed through quote,
+/// prepare and execute. Tasks a handler spawns stay in the span when started with [`spawn`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestContext {
+    pub correlation_id: String,
+    pub telegram_id: i64,
+}
+
+impl RequestContext {
+    /// Create a context with a new random correlation id
+    pub fn new(telegram_id: i64) -> Self {
+        Self {
+            correlation_id: format!("{:08x}", rand::random::<u32>()),
+            telegram_id,
+        }
+    }
+
+    /// Context for the sender of an incoming message
+    pub fn from_message(msg: &teloxide::types::Message) -> Self {
+        Self::new(msg.from.as_ref().map_or(0, |user| user.id.0 as i64))
+    }
+
+    /// `request` span with the `cid` and `user` fields of this context
+    pub fn span(&self) -> Span {
+        info_span!("request", cid = %self.correlation_id, user = self.telegram_id)
+    }
+
+    /// Run the future inside the span of this context
+    pub fn scope<F: Future>(self, future: F) -> impl Future<Output = F::Output> {
+        future.instrument(self.span())
+    }
+
+    /// Wrap the future in the current span, if any, so it keeps it on another task
+    ///
+    /// A span is entered per poll, a future passed to `tokio::spawn` or a `JoinSet` runs
+    /// outside the request span unless it is wrapped first.
+    pub fn inherit<F: Future>(future: F) -> impl Future<Output = F::Output> {
+        future.in_current_span()
+    }
+}
+
+/// `tokio::spawn` that keeps the request span of the spawning task in the logs
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(RequestContext::inherit(future))
+}
+
+/// Initialize the tracing subscriber, filtered by `RUST_LOG` (default `info`)
+///
+/// `log` records of the bot and its dependencies are forwarded to tracing, so lines written
+/// inside a request span get its `cid` and `user` fields too.
+pub fn init_logger() {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use tokio::task::JoinSet;
+    use tracing::subscriber::DefaultGuard;
+
+    // Formatted log output, shared with the subscriber
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn line(&self, message: &str) -> String {
+            let output = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+            output
+                .lines()
+                .find(|line| line.ends_with(message))
+                .unwrap_or_else(|| panic!("no `{}` line in:\n{}", message, output))
+                .to_string()
+        }
+    }
+
+    // Tests run on a current thread runtime, so spawned tasks also log to this subscriber
+    fn capture_logs() -> (Captured, DefaultGuard) {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        (captured, tracing::subscriber::set_default(subscriber))
+    }
+
+    #[tokio::test]
+    async fn spawned_tasks_keep_the_request_context() {
+        let (logs, _guard) = capture_logs();
+        let context = RequestContext::new(42);
+
+        context
+            .clone()
+            .scope(async {
+                spawn(async { tracing::info!("spawned") }).await.unwrap();
+                tokio::spawn(async { tracing::info!("plain") })
+                    .await
+                    .unwrap();
+            })
+            .await;
+
+        let fields = format!("cid={} user=42", context.correlation_id);
+        assert!(logs.line("spawned").contains(&fields));
+        // A plain spawn is why the wrapper exists
+        assert!(!logs.line("plain").contains("cid="));
+    }
+
+    #[tokio::test]
+    async fn join_set_tasks_inherit_the_request_context() {
+        let (logs, _guard) = capture_logs();
+        let context = RequestContext::new(7);
+
+        context
+            .clone()
+            .scope(async {
+                let mut tasks = JoinSet::new();
+                for i in 0..3 {
+                    tasks.spawn(RequestContext::inherit(async move {
+                        tracing::info!("quote {}", i)
+                    }));
+                }
+                tasks.join_all().await
+            })
+            .await;
+
+        let fields = format!("cid={} user=7", context.correlation_id);
+        for i in 0..3 {
+            assert!(logs.line(&format!("quote {}", i)).contains(&fields));
+        }
+    }
+
+    #[tokio::test]
+    async fn log_records_carry_the_request_context() {
+        // The bridge `init_logger` installs, most of the bot logs through `log`
+        let _ = tracing_log::LogTracer::init();
+        let (logs, _guard) = capture_logs();
+        let context = RequestContext::new(42);
+
+        context.clone().scope(async { log::info!("quoted") }).await;
+
+        let fields = format!("cid={} user=42", context.correlation_id);
+        assert!(logs.line("quoted").contains(&fields));
+    }
+
+    #[tokio::test]
+    async fn spawn_outside_a_request_has_no_context() {
+        let (logs, _guard) = capture_logs();
+
+        spawn(async { tracing::info!("background") }).await.unwrap();
+
+        assert!(!logs.line("background").contains("cid="));
+    }
+}
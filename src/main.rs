@@ -5,22 +5,31 @@
 //! and execute trades directly from Telegram chats.
 use anyhow::Context;
 use dotenv::dotenv;
-use log::{error, info};
+use log::{error, info, warn};
 use solana_trade_bot::{create_solana_client, Router};
 use sqlx::postgres::PgPoolOptions;
 use std::env;
 use std::sync::Arc;
+use std::time::Duration;
 use teloxide::{dptree, Bot};
 use tokio;
 
+// Connections of the main database pool
+const DB_MAX_CONNECTIONS: u32 = 10;
+// How long a query waits for a free connection before failing
+const DB_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(5);
+// Connections are replaced after this age, so none outlive a Postgres restart for long
+const DB_MAX_LIFETIME: Duration = Duration::from_secs(30 * 60);
+const DB_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
 /// Application entry point
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Load environment variables from .env file
     dotenv().ok();
 
-    // Initialize logging with default level of "info"
-    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
+    // Initialize logging with default level of "info", request logs are tagged with a correlation id
+    solana_trade_bot::logging::init_logger();
     info!(
         "Starting Solana Wallet Telegram Bot v{}",
         solana_trade_bot::VERSION
@@ -36,13 +45,23 @@ async fn main() -> anyhow::Result<()> {
     let solana_rpc_url = env::var("SOLANA_RPC_URL")
         .context("SOLANA_RPC_URL must be set in environment variables")?;
 
+    // A typo in a self-hosted Jupiter URL would only show up at the first trade
+    solana_trade_bot::solana::jupiter::Config::from_env()
+        .validate()
+        .context("Invalid Jupiter API URL")?;
+
     // Create Telegram bot instance
     let bot = Bot::new(bot_token);
 
     // Setup database connection pool
     info!("Connecting to database...");
+    // Connections are pinged before use, dead ones after a database restart are reopened
     let db_pool = PgPoolOptions::new()
-        .max_connections(10)
+        .max_connections(DB_MAX_CONNECTIONS)
+        .test_before_acquire(true)
+        .acquire_timeout(DB_ACQUIRE_TIMEOUT)
+        .max_lifetime(DB_MAX_LIFETIME)
+        .idle_timeout(DB_IDLE_TIMEOUT)
         .connect(&database_url)
         .await
         .context("Failed to create database connection pool")?;
@@ -78,15 +97,67 @@ async fn main() -> anyhow::Result<()> {
     info!("Initializing bot application...");
 
     // Initialize the application components
-    let (router, bot, service_container, storage, mut limit_order_service) =
-        solana_trade_bot::create_application(bot, db_pool, solana_client);
+    let (
+        router,
+        bot,
+        service_container,
+        storage,
+        mut limit_order_service,
+        mut dca_order_service,
+        mut dialogue_timeout_service,
+        mut wallet_follow_service,
+        mut deposit_watcher,
+    ) = solana_trade_bot::create_application(bot, db_pool, solana_client);
+
+    let cluster = service_container.cluster();
+    info!("Solana cluster: {}", cluster);
+
+    // Orders are executed through Jupiter, which is only available on mainnet
+    if cluster.supports_jupiter() {
+        // Start limit order background service
+        info!("Starting limit order background service...");
+        if let Err(e) = limit_order_service.start().await {
+            error!("Failed to start limit order service: {}", e);
+        } else {
+            info!("Limit order service started successfully");
+        }
+
+        // Start DCA order background service
+        info!("Starting DCA order background service...");
+        if let Err(e) = dca_order_service.start().await {
+            error!("Failed to start DCA order service: {}", e);
+        } else {
+            info!("DCA order service started successfully");
+        }
+    } else {
+        warn!(
+            "Jupiter is not available on {}, limit order and DCA services are disabled",
+            cluster
+        );
+    }
+
+    // Start the idle dialogue sweep
+    info!("Starting dialogue timeout service...");
+    if let Err(e) = dialogue_timeout_service.start().await {
+        error!("Failed to start dialogue timeout service: {}", e);
+    } else {
+        info!("Dialogue timeout service started successfully");
+    }
 
-    // Start limit order background service
-    info!("Starting limit order background service...");
-    if let Err(e) = limit_order_service.start().await {
-        error!("Failed to start limit order service: {}", e);
+    // Start the followed wallet notifier
+    info!("Starting wallet follow service...");
+    if let Err(e) = wallet_follow_service.start().await {
+        error!("Failed to start wallet follow service: {}", e);
     } else {
-        info!("Limit order service started successfully");
+        info!("Wallet follow service started successfully");
+    }
+
+    // Start the deposit notifier
+    info!("Starting deposit watcher...");
+    if let Err(e) = deposit_watcher.start().await {
+        error!("Failed to start deposit watcher: {}", e);
+    } else {
+        info!("Deposit watcher started successfully");
     }
 
     // Get the handler from the router
@@ -105,5 +176,21 @@ async fn main() -> anyhow::Result<()> {
     info!("Stopping limit order service...");
     limit_order_service.stop().await;
 
+    // Stop DCA order service
+    info!("Stopping DCA order service...");
+    dca_order_service.stop().await;
+
+    // Stop dialogue timeout service
+    info!("Stopping dialogue timeout service...");
+    dialogue_timeout_service.stop().await;
+
+    // Stop wallet follow service
+    info!("Stopping wallet follow service...");
+    wallet_follow_service.stop().await;
+
+    // Stop deposit watcher
+    info!("Stopping deposit watcher...");
+    deposit_watcher.stop().await;
+
     Ok(())
 }
@@ -0,0 +1,39 @@
+use crate::interactor::account_interactor::AccountInteractor;
+use crate::view::account_view::AccountView;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait AccountPresenter: Send + Sync {
+    async fn show_account_info(&self, telegram_id: i64) -> Result<()>;
+}
+
+pub struct AccountPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> AccountPresenterImpl<I, V>
+where
+    I: AccountInteractor,
+    V: AccountView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+}
+
+#[async_trait]
+impl<I, V> AccountPresenter for AccountPresenterImpl<I, V>
+where
+    I: AccountInteractor + Send + Sync,
+    V: AccountView + Send + Sync,
+{
+    async fn show_account_info(&self, telegram_id: i64) -> Result<()> {
+        match self.interactor.get_account_info(telegram_id).await {
+            Ok(info) => self.view.display_account_info(&info).await,
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::interactor::admin_interactor::AdminInteractor;
+use crate::view::admin_view::AdminView;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Messages sent before pausing, Telegram allows about 30 messages per second
+const BROADCAST_BATCH_SIZE: usize = 25;
+const BROADCAST_BATCH_DELAY: Duration = Duration::from_secs(1);
+
+#[async_trait]
+pub trait AdminPresenter: Send + Sync {
+    /// Runs `/admin <subcommand> [args]`, non-admins get the unknown command reply
+    async fn handle_command(&self, telegram_id: i64, args: &str) -> Result<()>;
+}
+
+pub struct AdminPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> AdminPresenterImpl<I, V>
+where
+    I: AdminInteractor,
+    V: AdminView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+
+    async fn broadcast(&self, telegram_id: i64, text: &str) -> Result<()> {
+        if text.is_empty() {
+            return self.view.display_usage().await;
+        }
+
+        let recipients = self.interactor.get_broadcast_recipients().await?;
+        info!(
+            "Broadcast to {} users started by admin {}",
+            recipients.len(),
+            telegram_id
+        );
+        self.view
+            .display_broadcast_started(recipients.len())
+            .await?;
+
+        let mut sent = 0;
+        let mut failed = 0;
+
+        for (batch_index, batch) in recipients.chunks(BROADCAST_BATCH_SIZE).enumerate() {
+            if batch_index > 0 {
+                tokio::time::sleep(BROADCAST_BATCH_DELAY).await;
+            }
+
+            for recipient in batch {
+                match self.view.deliver_broadcast(*recipient, text).await {
+                    Ok(()) => sent += 1,
+                    Err(e) => {
+                        // Mostly users who blocked the bot
+                        warn!("Failed to deliver broadcast to {}: {}", recipient, e);
+                        failed += 1;
+                    }
+                }
+            }
+        }
+
+        info!("Broadcast finished: {} sent, {} failed", sent, failed);
+        self.view.display_broadcast_report(sent, failed).await
+    }
+
+    // "/admin blacklist [add <mint> [reason] | remove <mint>]", no arguments lists the tokens
+    async fn blacklist(&self, telegram_id: i64, args: &str) -> Result<()> {
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let (mint_address, reason) = rest
+            .trim()
+            .split_once(char::is_whitespace)
+            .map(|(mint, reason)| (mint, Some(reason.trim())))
+            .unwrap_or((rest.trim(), None));
+
+        match action {
+            "" => {
+                let tokens = self.interactor.get_blacklisted_tokens().await?;
+                self.view.display_blacklist(&tokens).await
+            }
+            "add" if !mint_address.is_empty() => {
+                self.interactor
+                    .blacklist_token(telegram_id, mint_address, reason)
+                    .await?;
+                info!("Admin {} blacklisted token {}", telegram_id, mint_address);
+                self.view.display_token_blacklisted(mint_address).await
+            }
+            "remove" if !mint_address.is_empty() => {
+                let removed = self.interactor.unblacklist_token(mint_address).await?;
+                self.view
+                    .display_token_unblacklisted(mint_address, removed)
+                    .await
+            }
+            _ => self.view.display_usage().await,
+        }
+    }
+}
+
+#[async_trait]
+impl<I, V> AdminPresenter for AdminPresenterImpl<I, V>
+where
+    I: AdminInteractor + Send + Sync,
+    V: AdminView + Send + Sync,
+{
+    async fn handle_command(&self, telegram_id: i64, args: &str) -> Result<()> {
+        // Don't reveal that the command exists
+        if !self.interactor.is_admin(telegram_id) {
+            warn!("Admin command attempted by Telegram ID: {}", telegram_id);
+            return self.view.display_unknown_command().await;
+        }
+
+        let args = args.trim();
+        let (subcommand, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+
+        match subcommand {
+            "users" => match self.interactor.get_user_overview().await {
+                Ok(overview) => self.view.display_user_overview(&overview).await,
+                Err(e) => self.view.display_error(e.to_string()).await,
+            },
+            "broadcast" => match self.broadcast(telegram_id, rest.trim()).await {
+                Ok(()) => Ok(()),
+                Err(e) => self.view.display_error(e.to_string()).await,
+            },
+            "blacklist" => match self.blacklist(telegram_id, rest.trim()).await {
+                Ok(()) => Ok(()),
+                Err(e) => self.view.display_error(e.to_string()).await,
+            },
+            _ => self.view.display_usage().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::BlacklistedToken;
+    use crate::interactor::admin_interactor::UserOverview;
+    use anyhow::anyhow;
+    use std::sync::Mutex;
+
+    const ADMIN_ID: i64 = 42;
+
+    // Admin 42 and three users, the second one blocked the bot
+    struct Users;
+
+    #[async_trait]
+    impl AdminInteractor for Users {
+        fn is_admin(&self, telegram_id: i64) -> bool {
+            telegram_id == ADMIN_ID
+        }
+
+        async fn get_user_overview(&self) -> Result<UserOverview> {
+            Ok(UserOverview {
+                total_users: 3,
+                recent_users: Vec::new(),
+            })
+        }
+
+        async fn get_broadcast_recipients(&self) -> Result<Vec<i64>> {
+            Ok(vec![1, 2, 3])
+        }
+
+        async fn get_blacklisted_tokens(&self) -> Result<Vec<BlacklistedToken>> {
+            Ok(Vec::new())
+        }
+
+        async fn blacklist_token(&self, _: i64, _: &str, _: Option<&str>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn unblacklist_token(&self, _: &str) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    // Records what was shown, delivering to user 2 fails
+    #[derive(Default)]
+    struct RecordingView {
+        shown: Mutex<Vec<String>>,
+    }
+
+    impl RecordingView {
+        fn show(&self, what: String) -> Result<()> {
+            self.shown.lock().unwrap().push(what);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl AdminView for RecordingView {
+        async fn display_unknown_command(&self) -> Result<()> {
+            self.show("unknown command".to_string())
+        }
+
+        async fn display_usage(&self) -> Result<()> {
+            self.show("usage".to_string())
+        }
+
+        async fn display_user_overview(&self, overview: &UserOverview) -> Result<()> {
+            self.show(format!("{} users", overview.total_users))
+        }
+
+        async fn display_broadcast_started(&self, recipients: usize) -> Result<()> {
+            self.show(format!("broadcast to {}", recipients))
+        }
+
+        async fn deliver_broadcast(&self, telegram_id: i64, text: &str) -> Result<()> {
+            if telegram_id == 2 {
+                return Err(anyhow!("Forbidden: bot was blocked by the user"));
+            }
+            self.show(format!("{} <- {}", telegram_id, text))
+        }
+
+        async fn display_broadcast_report(&self, sent: usize, failed: usize) -> Result<()> {
+            self.show(format!("{} sent, {} failed", sent, failed))
+        }
+
+        async fn display_blacklist(&self, tokens: &[BlacklistedToken]) -> Result<()> {
+            self.show(format!("{} blacklisted", tokens.len()))
+        }
+
+        async fn display_token_blacklisted(&self, mint_address: &str) -> Result<()> {
+            self.show(format!("blacklisted {}", mint_address))
+        }
+
+        async fn display_token_unblacklisted(&self, mint_address: &str, _: bool) -> Result<()> {
+            self.show(format!("unblacklisted {}", mint_address))
+        }
+
+        async fn display_error(&self, error_message: String) -> Result<()> {
+            self.show(format!("error: {}", error_message))
+        }
+    }
+
+    async fn run(telegram_id: i64, args: &str) -> Vec<String> {
+        let view = Arc::new(RecordingView::default());
+        let presenter = AdminPresenterImpl::new(Arc::new(Users), view.clone());
+
+        presenter.handle_command(telegram_id, args).await.unwrap();
+
+        let shown = view.shown.lock().unwrap().clone();
+        shown
+    }
+
+    #[tokio::test]
+    async fn non_admin_gets_the_unknown_command_reply() {
+        assert_eq!(run(7, "users").await, vec!["unknown command"]);
+        assert_eq!(
+            run(7, "broadcast Maintenance at 10:00").await,
+            vec!["unknown command"]
+        );
+    }
+
+    #[tokio::test]
+    async fn admin_runs_subcommands() {
+        assert_eq!(run(ADMIN_ID, "users").await, vec!["3 users"]);
+        assert_eq!(run(ADMIN_ID, "").await, vec!["usage"]);
+    }
+
+    #[tokio::test]
+    async fn broadcast_counts_users_who_blocked_the_bot_as_failed() {
+        let shown = run(ADMIN_ID, "broadcast Maintenance at 10:00").await;
+
+        assert_eq!(
+            shown,
+            vec![
+                "broadcast to 3",
+                "1 <- Maintenance at 10:00",
+                "3 <- Maintenance at 10:00",
+                "2 sent, 1 failed",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn broadcast_without_text_shows_the_usage() {
+        assert_eq!(run(ADMIN_ID, "broadcast   ").await, vec!["usage"]);
+    }
+}
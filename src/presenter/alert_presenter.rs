@@ -0,0 +1,148 @@
+use crate::entity::BotError;
+use crate::interactor::alert_interactor::AlertInteractor;
+use crate::view::alert_view::AlertView;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait AlertPresenter: Send + Sync {
+    async fn show_alerts(&self, telegram_id: i64) -> Result<()>;
+    async fn start_create_alert_flow(&self) -> Result<()>;
+    /// Returns the token symbol when the address is valid
+    async fn handle_token_address(&self, address_text: &str) -> Result<Option<String>>;
+    /// Creates the alert, returns false when the input is invalid and should be re-entered
+    async fn handle_condition(
+        &self,
+        telegram_id: i64,
+        token_address: &str,
+        token_symbol: &str,
+        condition_text: &str,
+    ) -> Result<bool>;
+    async fn delete_alert(&self, telegram_id: i64, alert_id: i32) -> Result<()>;
+}
+
+pub struct AlertPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> AlertPresenterImpl<I, V>
+where
+    I: AlertInteractor,
+    V: AlertView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+}
+
+#[async_trait]
+impl<I, V> AlertPresenter for AlertPresenterImpl<I, V>
+where
+    I: AlertInteractor + Send + Sync,
+    V: AlertView + Send + Sync,
+{
+    async fn show_alerts(&self, telegram_id: i64) -> Result<()> {
+        info!("Fetching price alerts for user: {}", telegram_id);
+
+        match self.interactor.get_alerts(telegram_id).await {
+            Ok(alerts) => self.view.display_alerts(alerts).await,
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+
+    async fn start_create_alert_flow(&self) -> Result<()> {
+        info!("Starting price alert creation flow");
+        self.view.prompt_for_token_address().await
+    }
+
+    async fn handle_token_address(&self, address_text: &str) -> Result<Option<String>> {
+        info!("Processing alert token address: {}", address_text);
+
+        if !self.interactor.validate_token_address(address_text).await? {
+            self.view.display_invalid_token_address().await?;
+            return Ok(None);
+        }
+
+        match self.interactor.get_token_info(address_text).await {
+            Ok((token_symbol, price_in_sol, price_in_usdc)) => {
+                self.view
+                    .prompt_for_condition(&token_symbol, price_in_sol, price_in_usdc)
+                    .await?;
+                Ok(Some(token_symbol))
+            }
+            Err(e) => {
+                self.view
+                    .display_error(BotError::token_info_message(&e))
+                    .await?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn handle_condition(
+        &self,
+        telegram_id: i64,
+        token_address: &str,
+        token_symbol: &str,
+        condition_text: &str,
+    ) -> Result<bool> {
+        let (direction, target_price, one_shot) =
+            match self.interactor.parse_alert_condition(condition_text) {
+                Ok(condition) => condition,
+                Err(e) => {
+                    self.view.display_invalid_input(e.to_string()).await?;
+                    return Ok(false);
+                }
+            };
+
+        match self
+            .interactor
+            .create_alert(
+                telegram_id,
+                token_address,
+                token_symbol,
+                direction,
+                target_price,
+                one_shot,
+            )
+            .await
+        {
+            Ok(alert_id) => {
+                self.view
+                    .display_alert_created(
+                        alert_id,
+                        token_symbol,
+                        direction,
+                        target_price,
+                        one_shot,
+                    )
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    async fn delete_alert(&self, telegram_id: i64, alert_id: i32) -> Result<()> {
+        info!("Deleting price alert: {}", alert_id);
+
+        match self.interactor.delete_alert(telegram_id, alert_id).await {
+            Ok(true) => {
+                self.view.display_alert_deleted(alert_id).await?;
+                self.show_alerts(telegram_id).await
+            }
+            Ok(false) => {
+                self.view
+                    .display_error(format!("Price alert #{} not found", alert_id))
+                    .await
+            }
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+}
@@ -12,8 +12,14 @@ pub trait BalancePresenter: Send + Sync {
     // Original method
     async fn show_balances(&self, telegram_id: i64) -> Result<()>;
 
-    // New method for refreshing balance with existing message
-    async fn refresh_balances(&self, telegram_id: i64, message: Option<Message>) -> Result<()>;
+    // New method for refreshing balance with existing message,
+    // `force_refresh` bypasses the balance cache
+    async fn refresh_balances(
+        &self,
+        telegram_id: i64,
+        message: Option<Message>,
+        force_refresh: bool,
+    ) -> Result<()>;
 }
 
 pub struct BalancePresenterImpl<I, V> {
@@ -41,21 +47,13 @@ where
     async fn show_balances(&self, telegram_id: i64) -> Result<()> {
         let message = self.view.display_loading().await?;
 
-        match self.interactor.get_wallet_balances(telegram_id).await {
-            Ok((address, sol_balance, token_balances, usd_values)) => {
-                // Calculate total USD value
-                let total_usd: f64 = usd_values.iter().map(|(_, value)| value).sum();
-
-                self.view
-                    .display_balances(
-                        address,
-                        sol_balance,
-                        token_balances,
-                        usd_values,
-                        total_usd,
-                        message,
-                    )
-                    .await?;
+        match self
+            .interactor
+            .get_wallet_balances(telegram_id, false)
+            .await
+        {
+            Ok(balances) => {
+                self.view.display_balances(&balances, message).await?;
             }
             Err(e) => {
                 if let Some(wallet_error) = e.downcast_ref::<BotError>() {
@@ -77,7 +75,12 @@ where
     }
 
     // New implementation for refresh
-    async fn refresh_balances(&self, telegram_id: i64, message: Option<Message>) -> Result<()> {
+    async fn refresh_balances(
+        &self,
+        telegram_id: i64,
+        message: Option<Message>,
+        force_refresh: bool,
+    ) -> Result<()> {
         info!("Refreshing balances for user: {}", telegram_id);
 
         // Show loading message or update existing one
@@ -88,21 +91,14 @@ where
         };
 
         // Get wallet balances from interactor
-        match self.interactor.get_wallet_balances(telegram_id).await {
-            Ok((address, sol_balance, token_balances, usd_values)) => {
-                // Calculate total USD value
-                let total_usd: f64 = usd_values.iter().map(|(_, value)| value).sum();
-
-                // Display balances using view
+        match self
+            .interactor
+            .get_wallet_balances(telegram_id, force_refresh)
+            .await
+        {
+            Ok(balances) => {
                 self.view
-                    .display_balances(
-                        address,
-                        sol_balance,
-                        token_balances,
-                        usd_values,
-                        total_usd,
-                        loading_message,
-                    )
+                    .display_balances(&balances, loading_message)
                     .await?;
             }
             Err(e) => {
@@ -129,3 +125,46 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::testing::{fake_telegram, run_with_large_stack};
+    use crate::interactor::balance_interactor::WalletBalances;
+    use crate::view::balance_view::TelegramBalanceView;
+    use teloxide::types::ChatId;
+
+    // User who never created a wallet
+    struct NoWallet;
+
+    #[async_trait]
+    impl BalanceInteractor for NoWallet {
+        async fn get_wallet_balances(&self, _: i64, _: bool) -> Result<WalletBalances> {
+            Err(BotError::WalletNotFound.into())
+        }
+    }
+
+    #[test]
+    fn user_without_a_wallet_gets_no_low_balance_warning() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        run_with_large_stack(move || async move {
+            let (bot, mut calls) = fake_telegram().await;
+            let view = Arc::new(TelegramBalanceView::new(bot, ChatId(1)));
+            let presenter = BalancePresenterImpl::new(Arc::new(NoWallet), view);
+
+            presenter.show_balances(1).await.unwrap();
+
+            // The loading message, then the no wallet reply in its place
+            let mut bodies = Vec::new();
+            while let Ok(call) = calls.try_recv() {
+                bodies.push(call.body);
+            }
+            tx.send(bodies).unwrap();
+        });
+        let bodies = rx.recv().unwrap();
+
+        assert_eq!(bodies.len(), 2);
+        assert!(bodies[1].contains("You don't have a wallet yet"));
+        assert!(bodies.iter().all(|body| !body.contains("Low SOL balance")));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::interactor::cleanup_interactor::CleanupInteractor;
+use crate::view::cleanup_view::CleanupView;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait CleanupPresenter: Send + Sync {
+    /// Lists the empty token accounts and asks the user to confirm closing them
+    async fn show_cleanup_preview(&self, telegram_id: i64) -> Result<()>;
+    async fn execute_cleanup(&self, telegram_id: i64) -> Result<()>;
+    async fn cancel_cleanup(&self) -> Result<()>;
+}
+
+pub struct CleanupPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> CleanupPresenterImpl<I, V>
+where
+    I: CleanupInteractor,
+    V: CleanupView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+}
+
+#[async_trait]
+impl<I, V> CleanupPresenter for CleanupPresenterImpl<I, V>
+where
+    I: CleanupInteractor + Send + Sync,
+    V: CleanupView + Send + Sync,
+{
+    async fn show_cleanup_preview(&self, telegram_id: i64) -> Result<()> {
+        match self.interactor.preview_cleanup(telegram_id).await {
+            Ok(preview) if preview.accounts.is_empty() => {
+                self.view.display_nothing_to_clean().await
+            }
+            Ok(preview) => self.view.display_preview(&preview).await,
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+
+    async fn execute_cleanup(&self, telegram_id: i64) -> Result<()> {
+        let processing_msg = self.view.display_processing().await?;
+
+        match self.interactor.execute_cleanup(telegram_id).await {
+            Ok(result) => {
+                info!(
+                    "Closed {} empty token accounts, reclaimed {} lamports",
+                    result.closed_accounts, result.reclaimed_lamports
+                );
+                self.view
+                    .display_cleanup_result(&result, processing_msg)
+                    .await
+            }
+            Err(e) => {
+                self.view
+                    .display_error(format!("Failed to close token accounts: {}", e))
+                    .await
+            }
+        }
+    }
+
+    async fn cancel_cleanup(&self) -> Result<()> {
+        self.view.display_cleanup_cancelled().await
+    }
+}
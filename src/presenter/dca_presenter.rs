@@ -0,0 +1,186 @@
+use crate::entity::BotError;
+use crate::interactor::dca_interactor::DcaInteractor;
+use crate::utils::parse_duration;
+use crate::view::dca_view::DcaView;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait DcaPresenter: Send + Sync {
+    async fn show_dca_orders(&self, telegram_id: i64) -> Result<()>;
+    async fn start_create_order_flow(&self) -> Result<()>;
+    /// Returns the token symbol when the address is valid
+    async fn handle_token_address(&self, address_text: &str) -> Result<Option<String>>;
+    /// Returns the SOL amount per buy when the input is valid
+    async fn handle_amount(&self, amount_text: &str) -> Result<Option<f64>>;
+    /// Returns the interval in seconds when the input is valid
+    async fn handle_interval(&self, interval_text: &str) -> Result<Option<i64>>;
+    /// Creates the order, returns false when the input is invalid and should be re-entered
+    async fn handle_runs(
+        &self,
+        telegram_id: i64,
+        token_address: &str,
+        token_symbol: &str,
+        sol_per_interval: f64,
+        interval_seconds: i64,
+        runs_text: &str,
+    ) -> Result<bool>;
+    async fn cancel_order(&self, telegram_id: i64, order_id: i32) -> Result<()>;
+}
+
+pub struct DcaPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> DcaPresenterImpl<I, V>
+where
+    I: DcaInteractor,
+    V: DcaView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+}
+
+#[async_trait]
+impl<I, V> DcaPresenter for DcaPresenterImpl<I, V>
+where
+    I: DcaInteractor + Send + Sync,
+    V: DcaView + Send + Sync,
+{
+    async fn show_dca_orders(&self, telegram_id: i64) -> Result<()> {
+        info!("Fetching DCA orders for user: {}", telegram_id);
+
+        match self.interactor.get_active_dca_orders(telegram_id).await {
+            Ok(orders) => self.view.display_dca_orders(orders).await,
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+
+    async fn start_create_order_flow(&self) -> Result<()> {
+        info!("Starting DCA order creation flow");
+        self.view.prompt_for_token_address().await
+    }
+
+    async fn handle_token_address(&self, address_text: &str) -> Result<Option<String>> {
+        info!("Processing DCA token address: {}", address_text);
+
+        if !self.interactor.validate_token_address(address_text).await? {
+            self.view.display_invalid_token_address().await?;
+            return Ok(None);
+        }
+
+        match self.interactor.get_token_info(address_text).await {
+            Ok((token_symbol, price_in_sol, price_in_usdc)) => {
+                self.view
+                    .prompt_for_amount(address_text, &token_symbol, price_in_sol, price_in_usdc)
+                    .await?;
+                Ok(Some(token_symbol))
+            }
+            Err(e) => {
+                self.view
+                    .display_error(BotError::token_info_message(&e))
+                    .await?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn handle_amount(&self, amount_text: &str) -> Result<Option<f64>> {
+        match self.interactor.validate_sol_amount(amount_text) {
+            Ok(amount) => {
+                self.view.prompt_for_interval().await?;
+                Ok(Some(amount))
+            }
+            Err(e) => {
+                self.view.display_invalid_input(e.to_string()).await?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn handle_interval(&self, interval_text: &str) -> Result<Option<i64>> {
+        match parse_duration(interval_text) {
+            Ok(interval) => {
+                self.view.prompt_for_runs().await?;
+                Ok(Some(interval.num_seconds()))
+            }
+            Err(e) => {
+                self.view.display_invalid_input(e.to_string()).await?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn handle_runs(
+        &self,
+        telegram_id: i64,
+        token_address: &str,
+        token_symbol: &str,
+        sol_per_interval: f64,
+        interval_seconds: i64,
+        runs_text: &str,
+    ) -> Result<bool> {
+        let total_runs = match self.interactor.validate_runs(runs_text) {
+            Ok(runs) => runs,
+            Err(e) => {
+                self.view.display_invalid_input(e.to_string()).await?;
+                return Ok(false);
+            }
+        };
+
+        info!(
+            "Creating DCA order: {} SOL of {} every {}s, {} times",
+            sol_per_interval, token_symbol, interval_seconds, total_runs
+        );
+
+        match self
+            .interactor
+            .create_dca_order(
+                telegram_id,
+                token_address,
+                token_symbol,
+                sol_per_interval,
+                interval_seconds,
+                total_runs,
+            )
+            .await
+        {
+            Ok(order_id) => {
+                self.view
+                    .display_order_created(
+                        order_id,
+                        token_symbol,
+                        sol_per_interval,
+                        interval_seconds,
+                        total_runs,
+                    )
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    async fn cancel_order(&self, telegram_id: i64, order_id: i32) -> Result<()> {
+        info!("Cancelling DCA order: {}", order_id);
+
+        match self
+            .interactor
+            .cancel_dca_order(telegram_id, order_id)
+            .await
+        {
+            Ok(()) => {
+                self.view.display_order_cancelled(order_id).await?;
+                self.show_dca_orders(telegram_id).await
+            }
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+}
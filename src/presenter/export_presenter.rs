@@ -0,0 +1,81 @@
+use crate::entity::BotError;
+use crate::interactor::export_interactor::ExportInteractor;
+use crate::view::export_view::ExportView;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long the exported secrets stay in the chat by default
+pub const DEFAULT_EXPORT_MESSAGE_TTL: Duration = Duration::from_secs(60);
+
+#[async_trait]
+pub trait ExportPresenter: Send + Sync {
+    /// Warns about the risks and asks for confirmation, only in private chats
+    async fn show_export_warning(&self, is_private_chat: bool) -> Result<()>;
+    async fn export_wallet(&self, telegram_id: i64, is_private_chat: bool) -> Result<()>;
+    async fn cancel_export(&self) -> Result<()>;
+}
+
+pub struct ExportPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> ExportPresenterImpl<I, V>
+where
+    I: ExportInteractor,
+    V: ExportView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+
+    // Delay before the export message is deleted, configured with EXPORT_MESSAGE_TTL_SECS
+    fn export_message_ttl() -> Duration {
+        env::var("EXPORT_MESSAGE_TTL_SECS")
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_EXPORT_MESSAGE_TTL)
+    }
+}
+
+#[async_trait]
+impl<I, V> ExportPresenter for ExportPresenterImpl<I, V>
+where
+    I: ExportInteractor + Send + Sync,
+    V: ExportView + Send + Sync,
+{
+    async fn show_export_warning(&self, is_private_chat: bool) -> Result<()> {
+        if !is_private_chat {
+            return self.view.display_private_chat_required().await;
+        }
+
+        self.view.display_export_warning().await
+    }
+
+    async fn export_wallet(&self, telegram_id: i64, is_private_chat: bool) -> Result<()> {
+        if !is_private_chat {
+            return self.view.display_private_chat_required().await;
+        }
+
+        match self.interactor.export_wallet(telegram_id).await {
+            Ok(export) => {
+                self.view
+                    .display_wallet_export(&export, Self::export_message_ttl())
+                    .await
+            }
+            Err(e) => match e.downcast_ref::<BotError>() {
+                Some(BotError::WalletNotFound) => self.view.display_no_wallet().await,
+                _ => self.view.display_error(e.to_string()).await,
+            },
+        }
+    }
+
+    async fn cancel_export(&self) -> Result<()> {
+        self.view.display_export_cancelled().await
+    }
+}
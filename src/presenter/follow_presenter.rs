@@ -0,0 +1,83 @@
+use crate::interactor::follow_interactor::FollowInteractor;
+use crate::view::follow_view::FollowView;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait FollowPresenter: Send + Sync {
+    async fn show_followed_wallets(&self, telegram_id: i64) -> Result<()>;
+    async fn start_follow_flow(&self) -> Result<()>;
+    /// Follows the wallet, returns false when the address should be re-entered
+    async fn handle_wallet_address(&self, telegram_id: i64, address_text: &str) -> Result<bool>;
+    async fn unfollow_wallet(&self, telegram_id: i64, followed_wallet_id: i32) -> Result<()>;
+}
+
+pub struct FollowPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> FollowPresenterImpl<I, V>
+where
+    I: FollowInteractor,
+    V: FollowView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+}
+
+#[async_trait]
+impl<I, V> FollowPresenter for FollowPresenterImpl<I, V>
+where
+    I: FollowInteractor + Send + Sync,
+    V: FollowView + Send + Sync,
+{
+    async fn show_followed_wallets(&self, telegram_id: i64) -> Result<()> {
+        info!("Fetching followed wallets for user: {}", telegram_id);
+
+        match self.interactor.get_followed_wallets(telegram_id).await {
+            Ok(wallets) => self.view.display_followed_wallets(wallets).await,
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+
+    async fn start_follow_flow(&self) -> Result<()> {
+        self.view.prompt_for_wallet_address().await
+    }
+
+    async fn handle_wallet_address(&self, telegram_id: i64, address_text: &str) -> Result<bool> {
+        match self
+            .interactor
+            .follow_wallet(telegram_id, address_text)
+            .await
+        {
+            Ok(_) => {
+                self.view.display_wallet_followed(address_text).await?;
+                Ok(true)
+            }
+            Err(e) => {
+                self.view.display_invalid_input(e.to_string()).await?;
+                Ok(false)
+            }
+        }
+    }
+
+    async fn unfollow_wallet(&self, telegram_id: i64, followed_wallet_id: i32) -> Result<()> {
+        match self
+            .interactor
+            .unfollow_wallet(telegram_id, followed_wallet_id)
+            .await
+        {
+            Ok(true) => self.view.display_wallet_unfollowed().await,
+            Ok(false) => {
+                self.view
+                    .display_error("This wallet is not followed anymore".to_string())
+                    .await
+            }
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::interactor::history_interactor::HistoryInteractor;
+use crate::view::history_view::HistoryView;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use std::sync::Arc;
+use teloxide::types::MessageId;
+
+#[async_trait]
+pub trait HistoryPresenter: Send + Sync {
+    async fn show_history(
+        &self,
+        telegram_id: i64,
+        page: usize,
+        message_id: Option<MessageId>,
+    ) -> Result<()>;
+}
+
+pub struct HistoryPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> HistoryPresenterImpl<I, V>
+where
+    I: HistoryInteractor,
+    V: HistoryView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+}
+
+#[async_trait]
+impl<I, V> HistoryPresenter for HistoryPresenterImpl<I, V>
+where
+    I: HistoryInteractor + Send + Sync,
+    V: HistoryView + Send + Sync,
+{
+    async fn show_history(
+        &self,
+        telegram_id: i64,
+        page: usize,
+        message_id: Option<MessageId>,
+    ) -> Result<()> {
+        info!("Fetching history page {} for user: {}", page, telegram_id);
+
+        match self.interactor.get_history_page(telegram_id, page).await {
+            Ok(history) => self.view.display_history(history, message_id).await,
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+}
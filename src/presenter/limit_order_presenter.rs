@@ -1,6 +1,7 @@
 // ./src/presenter/limit_order_presenter.rs
-use crate::entity::OrderType;
+use crate::entity::{BotError, NewLimitOrder, OrderBook, OrderType};
 use crate::interactor::limit_order_interactor::LimitOrderInteractor;
+use crate::utils::parse_decimal;
 use crate::view::limit_order_view::LimitOrderView;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -10,6 +11,8 @@ use std::sync::Arc;
 #[async_trait]
 pub trait LimitOrderPresenter: Send + Sync {
     async fn show_limit_orders(&self, telegram_id: i64) -> Result<()>;
+    async fn refresh_limit_orders(&self, telegram_id: i64) -> Result<()>;
+    async fn show_order_book(&self, telegram_id: i64) -> Result<()>;
     async fn start_create_order_flow(&self) -> Result<()>;
     async fn handle_order_type_selection(&self, order_type: OrderType) -> Result<()>;
     async fn handle_token_address(&self, address_text: &str, order_type: &OrderType) -> Result<()>;
@@ -23,13 +26,9 @@ pub trait LimitOrderPresenter: Send + Sync {
     ) -> Result<()>;
     async fn handle_confirmation(
         &self,
-        confirmation_text: &str,
-        order_type: &OrderType,
-        token_address: &str,
-        token_symbol: &str,
-        price_in_sol: f64,
-        amount: f64,
-        total_sol: f64,
+        confirmed: bool,
+        order: &NewLimitOrder,
+        expiry: Option<chrono::Duration>,
         telegram_id: i64,
     ) -> Result<()>;
     async fn cancel_order(&self, order_id: i32) -> Result<()>;
@@ -71,6 +70,41 @@ where
         Ok(())
     }
 
+    async fn refresh_limit_orders(&self, telegram_id: i64) -> Result<()> {
+        info!("Refreshing limit order prices for user: {}", telegram_id);
+
+        match self.interactor.get_active_limit_orders(telegram_id).await {
+            Ok(mut orders) => {
+                self.interactor.refresh_order_prices(&mut orders).await;
+                self.view.display_limit_orders(orders).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn show_order_book(&self, telegram_id: i64) -> Result<()> {
+        info!("Showing the order book for user: {}", telegram_id);
+
+        // The ladder is built around live prices
+        match self.interactor.get_active_limit_orders(telegram_id).await {
+            Ok(mut orders) => {
+                self.interactor.refresh_order_prices(&mut orders).await;
+                self.view
+                    .display_order_book(OrderBook::group(orders))
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
     async fn start_create_order_flow(&self) -> Result<()> {
         info!("Starting limit order creation flow");
         self.view.prompt_for_order_type().await
@@ -101,7 +135,7 @@ where
                 }
                 Err(e) => {
                     self.view
-                        .display_error(format!("Error getting token info: {}", e))
+                        .display_error(BotError::token_info_message(&e))
                         .await?;
                     Ok(())
                 }
@@ -138,7 +172,7 @@ where
         {
             Ok((price, amount, total_sol)) => {
                 // For sell orders, calculate what percentage of holdings this represents
-                let percentage_info = if *order_type == OrderType::Sell && !is_percentage {
+                let percentage_info = if order_type.is_sell() && !is_percentage {
                     // Calculate percentage of balance if this isn't already a percentage-specified order
                     match self
                         .interactor
@@ -160,7 +194,7 @@ where
                     let parts: Vec<&str> = price_amount_text.trim().split_whitespace().collect();
                     if parts.len() >= 2 && parts[1].ends_with('%') {
                         let percentage_str = parts[1].trim_end_matches('%');
-                        if let Ok(percentage) = percentage_str.parse::<f64>() {
+                        if let Ok(percentage) = parse_decimal(percentage_str) {
                             format!(" ({}% of your holdings)", percentage)
                         } else {
                             "".to_string()
@@ -197,54 +231,36 @@ where
 
     async fn handle_confirmation(
         &self,
-        confirmation_text: &str,
-        order_type: &OrderType,
-        token_address: &str,
-        token_symbol: &str,
-        price_in_sol: f64,
-        amount: f64,
-        total_sol: f64,
+        confirmed: bool,
+        order: &NewLimitOrder,
+        expiry: Option<chrono::Duration>,
         telegram_id: i64,
     ) -> Result<()> {
-        let confirmation = confirmation_text.to_lowercase();
-
-        if confirmation == "yes" || confirmation == "y" {
+        if confirmed {
             info!(
                 "Creating limit order: {:?} {} {} @ {}",
-                order_type, amount, token_symbol, price_in_sol
+                order.order_type, order.amount, order.token_symbol, order.price_in_sol
             );
 
+            // Expiry is counted from the moment the order is confirmed
+            let expires_at = expiry.map(|duration| chrono::Utc::now() + duration);
+
             // Create the order
             let result = self
                 .interactor
-                .create_limit_order(
-                    telegram_id,
-                    order_type,
-                    token_address,
-                    token_symbol,
-                    price_in_sol,
-                    amount,
-                    total_sol,
-                )
+                .create_limit_order(telegram_id, order, expires_at)
                 .await?;
 
             if result.success {
                 if let Some(order_id) = result.order_id {
                     self.view
-                        .display_order_creation_success(
-                            order_type,
-                            token_symbol,
-                            price_in_sol,
-                            amount,
-                            order_id,
-                            total_sol,
-                        )
+                        .display_order_creation_success(order, order_id, expires_at)
                         .await?;
                 } else {
                     self.view
                         .display_order_creation_error(
-                            order_type,
-                            token_symbol,
+                            &order.order_type,
+                            &order.token_symbol,
                             "Unknown error".to_string(),
                         )
                         .await?;
@@ -252,8 +268,8 @@ where
             } else {
                 self.view
                     .display_order_creation_error(
-                        order_type,
-                        token_symbol,
+                        &order.order_type,
+                        &order.token_symbol,
                         result
                             .error_message
                             .unwrap_or_else(|| "Unknown error".to_string()),
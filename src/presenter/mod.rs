@@ -1,10 +1,24 @@
 use async_trait::async_trait;
 
+pub mod account_presenter;
+pub mod admin_presenter;
+pub mod alert_presenter;
 pub mod balance_presenter;
+pub mod cleanup_presenter;
+pub mod dca_presenter;
+pub mod export_presenter;
+pub mod follow_presenter;
+pub mod history_presenter;
 pub mod limit_order_presenter;
+pub mod portfolio_presenter;
+pub mod positions_presenter;
 pub mod price_presenter;
+pub mod referral_presenter;
 pub mod send_presenter;
 pub mod settings_presenter;
+pub mod status_presenter;
+pub mod swap_presenter;
+pub mod token_info_presenter;
 pub mod trade_presenter;
 pub mod wallet_presenter;
 pub mod watchlist_presenter;
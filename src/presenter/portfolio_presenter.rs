@@ -0,0 +1,42 @@
+use crate::interactor::portfolio_interactor::PortfolioInteractor;
+use crate::view::portfolio_view::PortfolioView;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait PortfolioPresenter: Send + Sync {
+    async fn show_portfolio(&self, telegram_id: i64) -> Result<()>;
+}
+
+pub struct PortfolioPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> PortfolioPresenterImpl<I, V>
+where
+    I: PortfolioInteractor,
+    V: PortfolioView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+}
+
+#[async_trait]
+impl<I, V> PortfolioPresenter for PortfolioPresenterImpl<I, V>
+where
+    I: PortfolioInteractor + Send + Sync,
+    V: PortfolioView + Send + Sync,
+{
+    async fn show_portfolio(&self, telegram_id: i64) -> Result<()> {
+        info!("Building portfolio for user: {}", telegram_id);
+
+        match self.interactor.get_portfolio(telegram_id).await {
+            Ok(summary) => self.view.display_portfolio(summary).await,
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::interactor::positions_interactor::PositionsInteractor;
+use crate::view::positions_view::PositionsView;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait PositionsPresenter: Send + Sync {
+    async fn show_positions(&self, telegram_id: i64) -> Result<()>;
+}
+
+pub struct PositionsPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> PositionsPresenterImpl<I, V>
+where
+    I: PositionsInteractor,
+    V: PositionsView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+}
+
+#[async_trait]
+impl<I, V> PositionsPresenter for PositionsPresenterImpl<I, V>
+where
+    I: PositionsInteractor + Send + Sync,
+    V: PositionsView + Send + Sync,
+{
+    async fn show_positions(&self, telegram_id: i64) -> Result<()> {
+        info!("Building positions for user: {}", telegram_id);
+
+        match self.interactor.get_positions(telegram_id).await {
+            Ok(summary) => self.view.display_positions(summary).await,
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+}
@@ -1,12 +1,17 @@
+use crate::entity::PriceCurrency;
 use crate::interactor::price_interactor::PriceInteractor;
+use crate::utils::sparkline;
 use crate::view::price_view::PriceView;
 use anyhow::Result;
 use async_trait::async_trait;
 use std::sync::Arc;
 
+/// Number of characters of the price sparkline
+pub const SPARKLINE_WIDTH: usize = 12;
+
 #[async_trait]
 pub trait PricePresenter: Send + Sync {
-    async fn show_token_price(&self, token_id: &str) -> Result<()>;
+    async fn show_token_price(&self, token_id: &str, quote: PriceCurrency) -> Result<()>;
 }
 
 pub struct PricePresenterImpl<I, V> {
@@ -30,18 +35,16 @@ where
     I: PriceInteractor + Send + Sync,
     V: PriceView + Send + Sync,
 {
-    async fn show_token_price(&self, token_id: &str) -> Result<()> {
+    async fn show_token_price(&self, token_id: &str, quote: PriceCurrency) -> Result<()> {
         self.view.display_loading(token_id).await?;
 
         match self.interactor.get_token_price(token_id).await {
             Ok(price_info) => {
+                // Omitted until the bot has seen the token's price a few times
+                let history = self.interactor.get_price_history(token_id).await;
+
                 self.view
-                    .display_price(
-                        &price_info.token_id,
-                        &price_info.symbol,
-                        price_info.price_in_sol,
-                        price_info.price_in_usdc,
-                    )
+                    .display_price(&price_info, quote, sparkline(&history, SPARKLINE_WIDTH))
                     .await?;
             }
             Err(e) => {
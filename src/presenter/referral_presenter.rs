@@ -0,0 +1,42 @@
+use crate::interactor::referral_interactor::{referral_link, ReferralInteractor};
+use crate::view::referral_view::ReferralView;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait ReferralPresenter: Send + Sync {
+    async fn show_referrals(&self, telegram_id: i64, bot_username: &str) -> Result<()>;
+}
+
+pub struct ReferralPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> ReferralPresenterImpl<I, V>
+where
+    I: ReferralInteractor,
+    V: ReferralView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+}
+
+#[async_trait]
+impl<I, V> ReferralPresenter for ReferralPresenterImpl<I, V>
+where
+    I: ReferralInteractor + Send + Sync,
+    V: ReferralView + Send + Sync,
+{
+    async fn show_referrals(&self, telegram_id: i64, bot_username: &str) -> Result<()> {
+        match self.interactor.count_referrals(telegram_id).await {
+            Ok(count) => {
+                let link = referral_link(bot_username, telegram_id);
+                self.view.display_referrals(&link, count).await
+            }
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+}
@@ -48,6 +48,9 @@ where
 
     async fn handle_recipient_address(&self, address_text: &str) -> Result<()> {
         if self.interactor.validate_address(address_text).await? {
+            if let Some(warning) = self.interactor.recipient_warning(address_text).await? {
+                self.view.display_recipient_warning(&warning).await?;
+            }
             self.view.prompt_for_amount().await?;
             Ok(())
         } else {
@@ -1,3 +1,7 @@
+use crate::entity::{
+    parse_slippage_bps, Explorer, NotificationKind, PriorityFee, TradeLimitKind,
+    DEFAULT_QUICK_BUY_AMOUNTS,
+};
 use crate::interactor::settings_interactor::SettingsInteractor;
 use crate::view::settings_view::SettingsView;
 use anyhow::Result;
@@ -10,6 +14,47 @@ pub trait SettingsPresenter: Send + Sync {
     async fn show_slippage_prompt(&self, telegram_id: i64) -> Result<()>;
     async fn update_slippage(&self, telegram_id: i64, slippage_text: &str) -> Result<()>;
     async fn set_preset_slippage(&self, telegram_id: i64, slippage: f64) -> Result<()>;
+    async fn show_priority_fee_prompt(&self, telegram_id: i64) -> Result<()>;
+    async fn set_priority_fee(&self, telegram_id: i64, priority_fee: PriorityFee) -> Result<()>;
+    async fn show_explorer_prompt(&self, telegram_id: i64) -> Result<()>;
+    async fn set_explorer(&self, telegram_id: i64, explorer: Explorer) -> Result<()>;
+    async fn toggle_default_quote(&self, telegram_id: i64) -> Result<()>;
+    async fn toggle_trade_card(&self, telegram_id: i64) -> Result<()>;
+    async fn show_low_balance_prompt(&self, telegram_id: i64) -> Result<()>;
+    async fn set_low_balance_sol(&self, telegram_id: i64, low_balance_sol: f64) -> Result<()>;
+    async fn show_auto_confirm_prompt(&self, telegram_id: i64) -> Result<()>;
+    async fn set_auto_confirm_below_sol(
+        &self,
+        telegram_id: i64,
+        auto_confirm_below_sol: f64,
+    ) -> Result<()>;
+    async fn show_quick_buy_prompt(&self, telegram_id: i64) -> Result<()>;
+    async fn update_quick_buy_amounts(&self, telegram_id: i64, amounts_text: &str) -> Result<()>;
+    async fn reset_quick_buy_amounts(&self, telegram_id: i64) -> Result<()>;
+    async fn show_advanced_swap_menu(&self, telegram_id: i64) -> Result<()>;
+    async fn toggle_direct_routes(&self, telegram_id: i64) -> Result<()>;
+    async fn show_max_accounts_prompt(&self, telegram_id: i64) -> Result<()>;
+    async fn update_max_accounts(&self, telegram_id: i64, max_accounts_text: &str) -> Result<()>;
+    async fn reset_max_accounts(&self, telegram_id: i64) -> Result<()>;
+    async fn show_validity_slots_prompt(&self, telegram_id: i64) -> Result<()>;
+    async fn update_validity_slots(
+        &self,
+        telegram_id: i64,
+        validity_slots_text: &str,
+    ) -> Result<()>;
+    async fn reset_validity_slots(&self, telegram_id: i64) -> Result<()>;
+    async fn show_trade_limit_prompt(&self, telegram_id: i64, kind: TradeLimitKind) -> Result<()>;
+    async fn set_trade_limit(
+        &self,
+        telegram_id: i64,
+        kind: TradeLimitKind,
+        limit: Option<f64>,
+    ) -> Result<()>;
+    async fn show_notifications_menu(&self, telegram_id: i64) -> Result<()>;
+    async fn toggle_notification(&self, telegram_id: i64, kind: NotificationKind) -> Result<()>;
+    async fn export_settings(&self, telegram_id: i64) -> Result<()>;
+    async fn show_import_prompt(&self) -> Result<()>;
+    async fn import_settings(&self, telegram_id: i64, settings_json: &str) -> Result<()>;
 }
 
 pub struct SettingsPresenterImpl<I, V> {
@@ -37,8 +82,7 @@ where
         // Get user settings
         match self.interactor.get_user_settings(telegram_id).await {
             Ok(user) => {
-                let slippage = user.get_slippage();
-                self.view.display_settings_menu(slippage).await?;
+                self.view.display_settings_menu(&user).await?;
             }
             Err(e) => {
                 self.view.display_error(e.to_string()).await?;
@@ -52,8 +96,10 @@ where
         // Get current slippage value
         match self.interactor.get_user_settings(telegram_id).await {
             Ok(user) => {
-                let current_slippage = user.get_slippage();
-                self.view.display_slippage_prompt(current_slippage).await?;
+                let current_slippage_bps = user.get_slippage_bps();
+                self.view
+                    .display_slippage_prompt(current_slippage_bps)
+                    .await?;
             }
             Err(e) => {
                 self.view.display_error(e.to_string()).await?;
@@ -64,34 +110,490 @@ where
     }
 
     async fn update_slippage(&self, telegram_id: i64, slippage_text: &str) -> Result<()> {
-        // Parse slippage percentage
-        match slippage_text.trim().trim_end_matches('%').parse::<f64>() {
-            Ok(slippage) => {
+        // Parse "0.5%" or "50bps", out of range values are rejected
+        match parse_slippage_bps(slippage_text) {
+            Ok(slippage_bps) => {
                 // Update slippage in database
-                match self.interactor.update_slippage(telegram_id, slippage).await {
-                    Ok(updated_slippage) => {
-                        self.view.display_slippage_updated(updated_slippage).await?;
+                match self
+                    .interactor
+                    .update_slippage(telegram_id, slippage_bps)
+                    .await
+                {
+                    Ok(updated_slippage_bps) => {
+                        self.view
+                            .display_slippage_updated(updated_slippage_bps)
+                            .await?;
                     }
                     Err(e) => {
                         self.view.display_error(e.to_string()).await?;
                     }
                 }
             }
+            Err(e) => {
+                self.view.display_invalid_slippage(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn set_preset_slippage(&self, telegram_id: i64, slippage: f64) -> Result<()> {
+        // Presets are percentages, stored as basis points
+        let slippage_bps = (slippage * 100.0).round() as u64;
+
+        match self
+            .interactor
+            .update_slippage(telegram_id, slippage_bps)
+            .await
+        {
+            Ok(updated_slippage_bps) => {
+                self.view
+                    .display_slippage_updated(updated_slippage_bps)
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn show_priority_fee_prompt(&self, telegram_id: i64) -> Result<()> {
+        // Get current priority fee preset
+        match self.interactor.get_user_settings(telegram_id).await {
+            Ok(user) => {
+                let current_fee = user.get_priority_fee();
+                self.view.display_priority_fee_prompt(current_fee).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn set_priority_fee(&self, telegram_id: i64, priority_fee: PriorityFee) -> Result<()> {
+        // Update priority fee in database
+        match self
+            .interactor
+            .update_priority_fee(telegram_id, priority_fee)
+            .await
+        {
+            Ok(updated_fee) => {
+                self.view.display_priority_fee_updated(updated_fee).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn show_explorer_prompt(&self, telegram_id: i64) -> Result<()> {
+        // Get current explorer
+        match self.interactor.get_user_settings(telegram_id).await {
+            Ok(user) => {
+                self.view
+                    .display_explorer_prompt(user.get_explorer())
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn set_explorer(&self, telegram_id: i64, explorer: Explorer) -> Result<()> {
+        // Update explorer in database
+        match self.interactor.update_explorer(telegram_id, explorer).await {
+            Ok(updated_explorer) => {
+                self.view.display_explorer_updated(updated_explorer).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn toggle_default_quote(&self, telegram_id: i64) -> Result<()> {
+        match self.interactor.toggle_default_quote(telegram_id).await {
+            Ok(_) => self.show_settings_menu(telegram_id).await,
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+
+    async fn toggle_trade_card(&self, telegram_id: i64) -> Result<()> {
+        match self.interactor.toggle_trade_card(telegram_id).await {
+            Ok(_) => self.show_settings_menu(telegram_id).await,
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+
+    async fn show_low_balance_prompt(&self, telegram_id: i64) -> Result<()> {
+        // Get current threshold
+        match self.interactor.get_user_settings(telegram_id).await {
+            Ok(user) => {
+                self.view
+                    .display_low_balance_prompt(user.get_low_balance_sol())
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn set_low_balance_sol(&self, telegram_id: i64, low_balance_sol: f64) -> Result<()> {
+        // Update threshold in database
+        match self
+            .interactor
+            .update_low_balance_sol(telegram_id, low_balance_sol)
+            .await
+        {
+            Ok(updated) => {
+                self.view.display_low_balance_updated(updated).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn show_auto_confirm_prompt(&self, telegram_id: i64) -> Result<()> {
+        // Get current threshold
+        match self.interactor.get_user_settings(telegram_id).await {
+            Ok(user) => {
+                self.view
+                    .display_auto_confirm_prompt(user.get_auto_confirm_below_sol())
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn set_auto_confirm_below_sol(
+        &self,
+        telegram_id: i64,
+        auto_confirm_below_sol: f64,
+    ) -> Result<()> {
+        // Update threshold in database
+        match self
+            .interactor
+            .update_auto_confirm_below_sol(telegram_id, auto_confirm_below_sol)
+            .await
+        {
+            Ok(updated) => {
+                self.view.display_auto_confirm_updated(updated).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn show_quick_buy_prompt(&self, telegram_id: i64) -> Result<()> {
+        // Get current amounts
+        match self.interactor.get_user_settings(telegram_id).await {
+            Ok(user) => {
+                self.view
+                    .display_quick_buy_prompt(&user.get_quick_buy_amounts())
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn update_quick_buy_amounts(&self, telegram_id: i64, amounts_text: &str) -> Result<()> {
+        // Parse amounts like "0.1 0.5 1 5" or "0.1, 0.5, 1 SOL"
+        let text = amounts_text.to_lowercase().replace("sol", " ");
+        let parsed: Result<Vec<f64>, String> = text
+            .split(|c: char| c.is_whitespace() || c == ',' || c == '/')
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse::<f64>().map_err(|_| part.to_string()))
+            .collect();
+
+        let amounts = match parsed {
+            Ok(amounts) => amounts,
+            Err(part) => {
+                self.view
+                    .display_error(format!("Invalid amount \"{}\"", part))
+                    .await?;
+                return Ok(());
+            }
+        };
+
+        match self
+            .interactor
+            .update_quick_buy_amounts(telegram_id, amounts)
+            .await
+        {
+            Ok(updated) => {
+                self.view.display_quick_buy_updated(&updated).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn reset_quick_buy_amounts(&self, telegram_id: i64) -> Result<()> {
+        match self
+            .interactor
+            .update_quick_buy_amounts(telegram_id, DEFAULT_QUICK_BUY_AMOUNTS.to_vec())
+            .await
+        {
+            Ok(updated) => {
+                self.view.display_quick_buy_updated(&updated).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn show_advanced_swap_menu(&self, telegram_id: i64) -> Result<()> {
+        match self.interactor.get_user_settings(telegram_id).await {
+            Ok(user) => {
+                self.view
+                    .display_advanced_swap_menu(user.get_route_options())
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn toggle_direct_routes(&self, telegram_id: i64) -> Result<()> {
+        match self.interactor.toggle_direct_routes(telegram_id).await {
+            Ok(route) => {
+                self.view.display_advanced_swap_menu(route).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn show_max_accounts_prompt(&self, telegram_id: i64) -> Result<()> {
+        match self.interactor.get_user_settings(telegram_id).await {
+            Ok(user) => {
+                self.view
+                    .display_max_accounts_prompt(user.get_route_options().max_accounts)
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn update_max_accounts(&self, telegram_id: i64, max_accounts_text: &str) -> Result<()> {
+        let max_accounts = match max_accounts_text.trim().parse::<u64>() {
+            Ok(max_accounts) => max_accounts,
             Err(_) => {
                 self.view
-                    .display_invalid_slippage("Invalid number format".to_string())
+                    .display_error("Invalid number. Please enter a whole number.".to_string())
                     .await?;
+                return Ok(());
+            }
+        };
+
+        match self
+            .interactor
+            .update_max_accounts(telegram_id, Some(max_accounts))
+            .await
+        {
+            Ok(route) => {
+                self.view.display_advanced_swap_menu(route).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
             }
         }
 
         Ok(())
     }
 
-    async fn set_preset_slippage(&self, telegram_id: i64, slippage: f64) -> Result<()> {
-        // Update slippage in database
-        match self.interactor.update_slippage(telegram_id, slippage).await {
-            Ok(updated_slippage) => {
-                self.view.display_slippage_updated(updated_slippage).await?;
+    async fn reset_max_accounts(&self, telegram_id: i64) -> Result<()> {
+        match self.interactor.update_max_accounts(telegram_id, None).await {
+            Ok(route) => {
+                self.view.display_advanced_swap_menu(route).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn show_validity_slots_prompt(&self, telegram_id: i64) -> Result<()> {
+        match self.interactor.get_user_settings(telegram_id).await {
+            Ok(user) => {
+                self.view
+                    .display_validity_slots_prompt(user.get_route_options().validity_slots)
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn update_validity_slots(
+        &self,
+        telegram_id: i64,
+        validity_slots_text: &str,
+    ) -> Result<()> {
+        let validity_slots = match validity_slots_text.trim().parse::<u64>() {
+            Ok(validity_slots) => validity_slots,
+            Err(_) => {
+                self.view
+                    .display_error("Invalid number. Please enter a whole number.".to_string())
+                    .await?;
+                return Ok(());
+            }
+        };
+
+        match self
+            .interactor
+            .update_validity_slots(telegram_id, Some(validity_slots))
+            .await
+        {
+            Ok(route) => {
+                self.view.display_advanced_swap_menu(route).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn reset_validity_slots(&self, telegram_id: i64) -> Result<()> {
+        match self
+            .interactor
+            .update_validity_slots(telegram_id, None)
+            .await
+        {
+            Ok(route) => {
+                self.view.display_advanced_swap_menu(route).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn show_trade_limit_prompt(&self, telegram_id: i64, kind: TradeLimitKind) -> Result<()> {
+        // Get current limit value
+        match self.interactor.get_user_settings(telegram_id).await {
+            Ok(user) => {
+                let current_limit = user.get_trade_limits().get(kind);
+                self.view
+                    .display_trade_limit_prompt(kind, current_limit)
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn set_trade_limit(
+        &self,
+        telegram_id: i64,
+        kind: TradeLimitKind,
+        limit: Option<f64>,
+    ) -> Result<()> {
+        // Update the limit in database
+        match self
+            .interactor
+            .update_trade_limit(telegram_id, kind, limit)
+            .await
+        {
+            Ok(updated_limit) => {
+                self.view
+                    .display_trade_limit_updated(kind, updated_limit)
+                    .await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn show_notifications_menu(&self, telegram_id: i64) -> Result<()> {
+        match self.interactor.get_notifications(telegram_id).await {
+            Ok(notifications) => {
+                self.view.display_notifications_menu(&notifications).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn toggle_notification(&self, telegram_id: i64, kind: NotificationKind) -> Result<()> {
+        match self.interactor.toggle_notification(telegram_id, kind).await {
+            Ok(notifications) => {
+                self.view.display_notifications_menu(&notifications).await?;
+            }
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn export_settings(&self, telegram_id: i64) -> Result<()> {
+        match self.interactor.export_settings(telegram_id).await {
+            Ok(settings_json) => {
+                self.view.display_settings_export(&settings_json).await?;
             }
             Err(e) => {
                 self.view.display_error(e.to_string()).await?;
@@ -100,4 +602,25 @@ where
 
         Ok(())
     }
+
+    async fn show_import_prompt(&self) -> Result<()> {
+        self.view.display_settings_import_prompt().await
+    }
+
+    async fn import_settings(&self, telegram_id: i64, settings_json: &str) -> Result<()> {
+        match self
+            .interactor
+            .import_settings(telegram_id, settings_json)
+            .await
+        {
+            Ok(()) => {
+                self.view.display_settings_imported().await?;
+            }
+            Err(e) => {
+                self.view.display_invalid_import(e.to_string()).await?;
+            }
+        }
+
+        Ok(())
+    }
 }
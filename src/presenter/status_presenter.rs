@@ -0,0 +1,45 @@
+use crate::interactor::status_interactor::StatusInteractor;
+use crate::view::status_view::StatusView;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::warn;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait StatusPresenter: Send + Sync {
+    /// Shows the health report, non-admins get the unknown command reply
+    async fn show_status(&self, telegram_id: i64) -> Result<()>;
+}
+
+pub struct StatusPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> StatusPresenterImpl<I, V>
+where
+    I: StatusInteractor,
+    V: StatusView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+}
+
+#[async_trait]
+impl<I, V> StatusPresenter for StatusPresenterImpl<I, V>
+where
+    I: StatusInteractor + Send + Sync,
+    V: StatusView + Send + Sync,
+{
+    async fn show_status(&self, telegram_id: i64) -> Result<()> {
+        // Don't reveal that the command exists
+        if !self.interactor.is_admin(telegram_id) {
+            warn!("Status command attempted by Telegram ID: {}", telegram_id);
+            return self.view.display_unknown_command().await;
+        }
+
+        let report = self.interactor.get_status().await;
+        self.view.display_status(&report).await
+    }
+}
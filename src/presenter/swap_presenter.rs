@@ -0,0 +1,130 @@
+use crate::interactor::swap_interactor::{SwapInteractor, SwapQuote};
+use crate::view::swap_view::SwapView;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait SwapPresenter: Send + Sync {
+    /// Shows the swap quote, returns it when the user should be asked for confirmation
+    async fn show_swap_quote(
+        &self,
+        telegram_id: i64,
+        amount_text: &str,
+        source_token: &str,
+        target_token: &str,
+    ) -> Result<Option<SwapQuote>>;
+    async fn show_usage(&self) -> Result<()>;
+    async fn execute_swap(
+        &self,
+        telegram_id: i64,
+        amount: f64,
+        source_token: &str,
+        target_token: &str,
+    ) -> Result<()>;
+    async fn cancel_swap(&self) -> Result<()>;
+}
+
+pub struct SwapPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> SwapPresenterImpl<I, V>
+where
+    I: SwapInteractor,
+    V: SwapView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+}
+
+#[async_trait]
+impl<I, V> SwapPresenter for SwapPresenterImpl<I, V>
+where
+    I: SwapInteractor + Send + Sync,
+    V: SwapView + Send + Sync,
+{
+    async fn show_swap_quote(
+        &self,
+        telegram_id: i64,
+        amount_text: &str,
+        source_token: &str,
+        target_token: &str,
+    ) -> Result<Option<SwapQuote>> {
+        info!(
+            "Quoting swap of {} {} to {}",
+            amount_text, source_token, target_token
+        );
+
+        let amount = match self.interactor.validate_amount(amount_text).await {
+            Ok(amount) => amount,
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+                return Ok(None);
+            }
+        };
+
+        let source = match self.interactor.resolve_token(source_token).await {
+            Ok(token) => token,
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+                return Ok(None);
+            }
+        };
+
+        let target = match self.interactor.resolve_token(target_token).await {
+            Ok(token) => token,
+            Err(e) => {
+                self.view.display_error(e.to_string()).await?;
+                return Ok(None);
+            }
+        };
+
+        match self
+            .interactor
+            .get_quote(telegram_id, amount, source, target)
+            .await
+        {
+            Ok(quote) => {
+                self.view.display_quote(&quote).await?;
+                Ok(Some(quote))
+            }
+            Err(e) => {
+                self.view
+                    .display_error(format!("Failed to get swap quote: {}", e))
+                    .await?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn show_usage(&self) -> Result<()> {
+        self.view.display_usage().await
+    }
+
+    async fn execute_swap(
+        &self,
+        telegram_id: i64,
+        amount: f64,
+        source_token: &str,
+        target_token: &str,
+    ) -> Result<()> {
+        let processing_msg = self.view.display_processing().await?;
+
+        match self
+            .interactor
+            .execute_swap(telegram_id, amount, source_token, target_token)
+            .await
+        {
+            Ok(result) => self.view.display_swap_result(&result, processing_msg).await,
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+
+    async fn cancel_swap(&self) -> Result<()> {
+        self.view.display_swap_cancelled().await
+    }
+}
@@ -0,0 +1,41 @@
+use crate::interactor::token_info_interactor::TokenInfoInteractor;
+use crate::view::token_info_view::TokenInfoView;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait TokenInfoPresenter: Send + Sync {
+    async fn show_token_info(&self, mint_address: &str) -> Result<()>;
+}
+
+pub struct TokenInfoPresenterImpl<I, V> {
+    interactor: Arc<I>,
+    view: Arc<V>,
+}
+
+impl<I, V> TokenInfoPresenterImpl<I, V>
+where
+    I: TokenInfoInteractor,
+    V: TokenInfoView,
+{
+    pub fn new(interactor: Arc<I>, view: Arc<V>) -> Self {
+        Self { interactor, view }
+    }
+}
+
+#[async_trait]
+impl<I, V> TokenInfoPresenter for TokenInfoPresenterImpl<I, V>
+where
+    I: TokenInfoInteractor + Send + Sync,
+    V: TokenInfoView + Send + Sync,
+{
+    async fn show_token_info(&self, mint_address: &str) -> Result<()> {
+        match self.interactor.get_token_info(mint_address).await {
+            Ok(info) => self.view.display_token_info(&info).await?,
+            Err(e) => self.view.display_error(e.to_string()).await?,
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
-use crate::entity::OrderType;
-use crate::interactor::trade_interactor::TradeInteractor;
+use crate::entity::{BotError, OrderType};
+use crate::interactor::trade_interactor::{TradeInteractor, TradeRequest};
 use crate::view::trade_view::TradeView;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -65,7 +65,7 @@ where
                 }
                 Err(e) => {
                     self.view
-                        .display_error(format!("Error getting token info: {}", e))
+                        .display_error(BotError::token_info_message(&e))
                         .await?;
                     Ok(())
                 }
@@ -98,15 +98,23 @@ where
                 .interactor
                 .execute_trade(
                     telegram_id,
-                    trade_type,
-                    token_address,
-                    token_symbol,
-                    amount,
-                    price_in_sol,
+                    TradeRequest {
+                        trade_type,
+                        token_address,
+                        token_symbol,
+                        amount,
+                        price_in_sol,
+                        slippage: None,
+                    },
                 )
                 .await?;
 
             if result.success {
+                // A sell may have been capped to the on-chain balance
+                let (amount, total_sol) = match result.requested_amount {
+                    Some(_) => (result.amount, result.total_sol),
+                    None => (amount, total_sol),
+                };
                 self.view
                     .display_trade_success(
                         trade_type,
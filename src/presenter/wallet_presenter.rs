@@ -8,6 +8,7 @@ use std::sync::Arc;
 pub trait WalletPresenter: Send + Sync {
     async fn create_wallet(&self, telegram_id: i64) -> Result<()>;
     async fn show_wallet_address(&self, telegram_id: i64) -> Result<()>;
+    async fn show_deposit(&self, telegram_id: i64, amount: Option<f64>) -> Result<()>;
 }
 
 pub struct WalletPresenterImpl<I, V> {
@@ -67,4 +68,17 @@ where
             }
         }
     }
+
+    async fn show_deposit(&self, telegram_id: i64, amount: Option<f64>) -> Result<()> {
+        match self.interactor.get_wallet_info(telegram_id).await? {
+            Some((address, _mnemonic)) => {
+                self.view.display_deposit_address(address, amount).await?;
+                Ok(())
+            }
+            None => {
+                self.view.display_no_wallet().await?;
+                Ok(())
+            }
+        }
+    }
 }
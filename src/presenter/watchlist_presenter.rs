@@ -1,13 +1,30 @@
+use crate::entity::WatchlistSort;
 use crate::interactor::watchlist_interactor::WatchlistInteractor;
+use crate::presenter::price_presenter::SPARKLINE_WIDTH;
 use crate::solana::jupiter::price_service::PriceService;
+use crate::utils::sparkline;
 use crate::view::watchlist_view::WatchlistView;
 use anyhow::Result;
 use async_trait::async_trait;
 use std::sync::Arc;
+use teloxide::types::MessageId;
 
 #[async_trait]
 pub trait WatchlistPresenter: Send + Sync {
     async fn show_watchlist(&self, telegram_id: i64) -> Result<()>;
+    async fn show_watchlist_page(
+        &self,
+        telegram_id: i64,
+        message_id: MessageId,
+        page: usize,
+    ) -> Result<()>;
+    async fn sort_watchlist(
+        &self,
+        telegram_id: i64,
+        message_id: MessageId,
+        sort: WatchlistSort,
+    ) -> Result<()>;
+    async fn toggle_alerts_only(&self, telegram_id: i64, message_id: MessageId) -> Result<()>;
     async fn show_token_detail(&self, telegram_id: i64, token_address: &str) -> Result<()>;
     async fn add_to_watchlist(&self, telegram_id: i64, token_address: &str) -> Result<()>;
     async fn remove_from_watchlist(&self, telegram_id: i64, token_address: &str) -> Result<()>;
@@ -46,7 +63,7 @@ where
     V: WatchlistView + Send + Sync,
 {
     async fn show_watchlist(&self, telegram_id: i64) -> Result<()> {
-        match self.interactor.get_watchlist(telegram_id).await {
+        match self.interactor.get_watchlist_page(telegram_id, 0).await {
             Ok(watchlist) => {
                 self.view.display_watchlist(watchlist).await?;
             }
@@ -58,6 +75,43 @@ where
         Ok(())
     }
 
+    async fn show_watchlist_page(
+        &self,
+        telegram_id: i64,
+        message_id: MessageId,
+        page: usize,
+    ) -> Result<()> {
+        match self.interactor.get_watchlist_page(telegram_id, page).await {
+            Ok(watchlist) => self.view.update_watchlist(message_id, watchlist).await,
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+
+    async fn sort_watchlist(
+        &self,
+        telegram_id: i64,
+        message_id: MessageId,
+        sort: WatchlistSort,
+    ) -> Result<()> {
+        if let Err(e) = self.interactor.set_watchlist_sort(telegram_id, sort).await {
+            return self.view.display_error(e.to_string()).await;
+        }
+
+        self.show_watchlist_page(telegram_id, message_id, 0).await
+    }
+
+    async fn toggle_alerts_only(&self, telegram_id: i64, message_id: MessageId) -> Result<()> {
+        if let Err(e) = self
+            .interactor
+            .toggle_watchlist_alerts_only(telegram_id)
+            .await
+        {
+            return self.view.display_error(e.to_string()).await;
+        }
+
+        self.show_watchlist_page(telegram_id, message_id, 0).await
+    }
+
     async fn show_token_detail(&self, telegram_id: i64, token_address: &str) -> Result<()> {
         match self
             .interactor
@@ -65,13 +119,30 @@ where
             .await
         {
             Ok(Some(item)) => {
-                // Get USDC price in addition to SOL price
-                let price_in_usdc = match self.price_service.get_token_price(token_address).await {
-                    Ok(price_info) => Some(price_info.price_in_usdc),
-                    Err(_) => None,
-                };
+                // Get USDC price and 24h change in addition to SOL price
+                let (price_in_usdc, price_change_24h) =
+                    match self.price_service.get_token_price(token_address).await {
+                        Ok(price_info) => {
+                            (Some(price_info.price_in_usdc), price_info.price_change_24h)
+                        }
+                        Err(_) => (None, None),
+                    };
+                let history = self.price_service.get_price_history(token_address).await;
+                let quote = self
+                    .interactor
+                    .get_default_quote(telegram_id)
+                    .await
+                    .unwrap_or_default();
 
-                self.view.display_token_detail(item, price_in_usdc).await?;
+                self.view
+                    .display_token_detail(
+                        item,
+                        price_in_usdc,
+                        quote,
+                        price_change_24h,
+                        sparkline(&history, SPARKLINE_WIDTH),
+                    )
+                    .await?;
             }
             Ok(None) => {
                 self.view
@@ -143,10 +214,9 @@ where
     }
 
     async fn refresh_watchlist(&self, telegram_id: i64) -> Result<()> {
+        // The saved sort and filter are kept, the list starts again at the first page
         match self.interactor.refresh_watchlist_prices(telegram_id).await {
-            Ok(watchlist) => {
-                self.view.display_watchlist(watchlist).await?;
-            }
+            Ok(()) => self.show_watchlist(telegram_id).await?,
             Err(e) => {
                 self.view.display_error(e.to_string()).await?;
             }
@@ -1,12 +1,19 @@
-use crate::interactor::withdraw_interactor::WithdrawInteractor;
+use crate::interactor::withdraw_interactor::{RecipientCheck, WithdrawInteractor};
 use crate::view::withdraw_view::WithdrawView;
 use anyhow::Result;
 use async_trait::async_trait;
 use std::sync::Arc;
+use teloxide::types::MessageId;
 
 #[async_trait]
 pub trait WithdrawPresenter: Send + Sync {
     async fn start_withdraw_flow(&self, telegram_id: i64) -> Result<()>;
+    async fn show_token_page(
+        &self,
+        telegram_id: i64,
+        message_id: MessageId,
+        page: usize,
+    ) -> Result<()>;
     async fn show_token_details(&self, token_address: &str, telegram_id: i64) -> Result<()>;
     async fn handle_recipient_address(
         &self,
@@ -83,6 +90,22 @@ where
         }
     }
 
+    async fn show_token_page(
+        &self,
+        telegram_id: i64,
+        message_id: MessageId,
+        page: usize,
+    ) -> Result<()> {
+        match self.interactor.get_user_tokens(telegram_id).await {
+            Ok(tokens) => {
+                self.view
+                    .update_token_selection_page(message_id, tokens, page)
+                    .await
+            }
+            Err(e) => self.view.display_error(e.to_string()).await,
+        }
+    }
+
     async fn show_token_details(&self, token_address: &str, telegram_id: i64) -> Result<()> {
         // Get token info and balance
         match self.interactor.get_user_tokens(telegram_id).await {
@@ -138,18 +161,34 @@ where
         price_in_usdc: f64,
     ) -> Result<()> {
         // Validate the recipient address
-        if self
+        match self
             .interactor
-            .validate_recipient_address(address_text)
+            .validate_recipient_address(address_text, token_address)
             .await?
         {
-            // If valid, prompt for amount
-            self.view
-                .prompt_for_amount(token_symbol, amount, price_in_sol, price_in_usdc)
-                .await?;
-        } else {
-            // If invalid, show error and prompt again
-            self.view.display_invalid_address().await?;
+            RecipientCheck::Valid {
+                creates_token_account,
+                warning,
+            } => {
+                if let Some(warning) = warning {
+                    self.view.display_recipient_warning(&warning).await?;
+                }
+                if creates_token_account {
+                    self.view.display_token_account_notice(token_symbol).await?;
+                }
+
+                // If valid, prompt for amount
+                self.view
+                    .prompt_for_amount(token_symbol, amount, price_in_sol, price_in_usdc)
+                    .await?;
+            }
+            RecipientCheck::InvalidAddress => {
+                // If invalid, show error and prompt again
+                self.view.display_invalid_address().await?;
+            }
+            RecipientCheck::Rejected(reason) => {
+                self.view.display_rejected_address(reason).await?;
+            }
         }
 
         Ok(())
@@ -1,13 +1,106 @@
 use anyhow::{anyhow, Result};
+use lazy_static::lazy_static;
 use png;
 use resvg::render;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use tiny_skia::Pixmap;
-use usvg::{Options, Tree};
+use usvg::{fontdb, Options, Tree};
+
+use crate::utils::generate_qr_code;
+
+/// Most rendered QR codes kept in memory, the oldest one is dropped first
+const QR_CACHE_CAPACITY: usize = 256;
+
+// Rendered PNGs by encoded text, in the order they were rendered
+#[derive(Default)]
+struct QrCache {
+    images: HashMap<String, Vec<u8>>,
+    order: VecDeque<String>,
+}
+
+lazy_static! {
+    static ref QR_CACHE: Mutex<QrCache> = Mutex::new(QrCache::default());
+    // System fonts for SVG text, loaded once on the first render
+    static ref FONT_DB: Arc<fontdb::Database> = {
+        let mut fonts = fontdb::Database::new();
+        fonts.load_system_fonts();
+
+        // sans-serif defaults to Arial, fall back to any installed font when it's missing
+        let query = fontdb::Query {
+            families: &[fontdb::Family::SansSerif],
+            ..fontdb::Query::default()
+        };
+        if fonts.query(&query).is_none() {
+            let families: Vec<String> = fonts
+                .faces()
+                .filter_map(|face| face.families.first())
+                .map(|(family, _)| family.clone())
+                .collect();
+            let installed = families
+                .iter()
+                .find(|family| family.contains("Sans"))
+                .or(families.first());
+            if let Some(family) = installed.cloned() {
+                fonts.set_sans_serif_family(family);
+            }
+        }
+
+        Arc::new(fonts)
+    };
+}
+
+/// PNG of a QR code encoding `content`, rendered once and then served from memory.
+///
+/// Entries are keyed by the encoded text, so a different wallet address or payment link
+/// never gets a stale image.
+pub fn qr_code_png(content: &str) -> Result<Vec<u8>> {
+    cached_png(&QR_CACHE, content, |content| {
+        render_svg_to_png(&generate_qr_code(content)?)
+    })
+}
+
+// PNG of `content` from the cache, rendered with `render` and cached on a miss
+fn cached_png(
+    cache: &Mutex<QrCache>,
+    content: &str,
+    render: impl FnOnce(&str) -> Result<Vec<u8>>,
+) -> Result<Vec<u8>> {
+    if let Some(png) = cache.lock().unwrap().images.get(content) {
+        return Ok(png.clone());
+    }
+
+    // Rendered without holding the lock, two racing renders produce the same image
+    let png = render(content)?;
+
+    let mut cache = cache.lock().unwrap();
+    if !cache.images.contains_key(content) {
+        if cache.order.len() >= QR_CACHE_CAPACITY {
+            if let Some(oldest) = cache.order.pop_front() {
+                cache.images.remove(&oldest);
+            }
+        }
+        cache.order.push_back(content.to_string());
+        cache.images.insert(content.to_string(), png.clone());
+    }
+
+    Ok(png)
+}
+
+/// Whether any system font is installed, SVG text is not drawn without one
+pub fn fonts_available() -> bool {
+    !FONT_DB.is_empty()
+}
 
 /// Converts SVG (as bytes) to PNG (returns Vec<u8> with PNG data).
-pub fn convert_svg_to_png(svg_data: &[u8]) -> Result<Vec<u8>> {
+///
+/// Text is drawn with the system fonts, see `fonts_available`.
+pub fn render_svg_to_png(svg_data: &[u8]) -> Result<Vec<u8>> {
     // 1) Parse SVG using usvg
-    let opt = Options::default();
+    let opt = Options {
+        fontdb: FONT_DB.clone(),
+        ..Options::default()
+    };
     let tree = Tree::from_data(svg_data, &opt).map_err(|e| anyhow!("Error parsing SVG: {}", e))?;
 
     // 2) Get SVG dimensions from the root node
@@ -34,3 +127,104 @@ pub fn convert_svg_to_png(svg_data: &[u8]) -> Result<Vec<u8>> {
 
     Ok(png_data)
 }
+
+/// Decodes the first readable QR code in a JPEG or PNG image and returns its text.
+pub fn decode_qr(image_data: &[u8]) -> Option<String> {
+    let image = image::load_from_memory(image_data).ok()?.to_luma8();
+    let (width, height) = image.dimensions();
+
+    let mut prepared =
+        rqrr::PreparedImage::prepare_from_greyscale(width as usize, height as usize, |x, y| {
+            image.get_pixel(x as u32, y as u32).0[0]
+        });
+    let content = prepared
+        .detect_grids()
+        .into_iter()
+        .find_map(|grid| grid.decode().ok().map(|(_, content)| content))?;
+
+    let content = content.trim();
+    if content.is_empty() {
+        None
+    } else {
+        Some(content.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::io::Cursor;
+
+    const ADDRESS: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    #[test]
+    fn generated_address_qr_decodes_to_the_address() {
+        let png = qr_code_png(ADDRESS).unwrap();
+
+        assert_eq!(decode_qr(&png).as_deref(), Some(ADDRESS));
+    }
+
+    // Renders `content` as its bytes, counting the renders
+    fn render_counted<'a>(renders: &'a Cell<usize>) -> impl FnOnce(&str) -> Result<Vec<u8>> + 'a {
+        move |content| {
+            renders.set(renders.get() + 1);
+            Ok(content.as_bytes().to_vec())
+        }
+    }
+
+    #[test]
+    fn cached_qr_code_is_not_rendered_again() {
+        let cache = Mutex::new(QrCache::default());
+        let renders = Cell::new(0);
+
+        let first = cached_png(&cache, ADDRESS, render_counted(&renders)).unwrap();
+        let second = cached_png(&cache, ADDRESS, render_counted(&renders)).unwrap();
+
+        assert_eq!(renders.get(), 1);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn other_content_is_rendered_separately() {
+        let cache = Mutex::new(QrCache::default());
+        let renders = Cell::new(0);
+
+        cached_png(&cache, ADDRESS, render_counted(&renders)).unwrap();
+        let other = cached_png(&cache, "solana:other", render_counted(&renders)).unwrap();
+
+        assert_eq!(renders.get(), 2);
+        assert_eq!(other, b"solana:other");
+    }
+
+    #[test]
+    fn oldest_qr_code_is_dropped_at_capacity() {
+        let cache = Mutex::new(QrCache::default());
+        let renders = Cell::new(0);
+
+        for i in 0..=QR_CACHE_CAPACITY {
+            cached_png(&cache, &i.to_string(), render_counted(&renders)).unwrap();
+        }
+        cached_png(&cache, "0", render_counted(&renders)).unwrap();
+
+        assert_eq!(renders.get(), QR_CACHE_CAPACITY + 2);
+        assert_eq!(cache.lock().unwrap().images.len(), QR_CACHE_CAPACITY);
+    }
+
+    #[test]
+    fn photo_without_a_qr_code_decodes_to_nothing() {
+        let blank = image::GrayImage::from_pixel(200, 200, image::Luma([255]));
+        let mut png = Vec::new();
+        blank
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        assert_eq!(decode_qr(&png), None);
+    }
+
+    #[test]
+    fn data_that_is_not_an_image_decodes_to_nothing() {
+        assert_eq!(decode_qr(b"not an image"), None);
+        assert_eq!(decode_qr(&[]), None);
+    }
+}
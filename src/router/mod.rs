@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use std::sync::Arc;
 use teloxide::{
     dispatching::dialogue::Dialogue, dispatching::dialogue::InMemStorage,
-    dispatching::UpdateHandler, prelude::*,
+    dispatching::UpdateHandler, prelude::*, types::UpdateKind,
 };
 
 use crate::commands::{
@@ -10,6 +10,9 @@ use crate::commands::{
 };
 use crate::di::ServiceContainer;
 use crate::entity::State;
+use crate::interactor::db;
+use crate::logging::RequestContext;
+use crate::services::RequestKind;
 
 type MyDialogue = Dialogue<State, InMemStorage<State>>;
 
@@ -40,14 +43,36 @@ impl Router for TelegramRouter {
         let services2 = self.services.clone();
         let services3 = self.services.clone();
         let services4 = self.services.clone();
+        let services5 = self.services.clone();
+        let services6 = self.services.clone();
+        let services7 = self.services.clone();
+        let services8 = self.services.clone();
+        let services9 = self.services.clone();
+        let services10 = self.services.clone();
+        let services11 = self.services.clone();
+        let services12 = self.services.clone();
+        let services13 = self.services.clone();
+        let services14 = self.services.clone();
+        let services15 = self.services.clone();
+        let services16 = self.services.clone();
+        let services17 = self.services.clone();
+        let services18 = self.services.clone();
+        let services19 = self.services.clone();
+        let services20 = self.services.clone();
+        let services21 = self.services.clone();
+        let services22 = self.services.clone();
+        let services23 = self.services.clone();
+        let services24 = self.services.clone();
+        let services25 = self.services.clone();
+        let services26 = self.services.clone();
         let services_for_callbacks = self.services.clone();
 
         // Use BotCommands enum with teloxide's command filter
         let command_handler = teloxide::filter_command::<BotCommands, _>()
-            .branch(case![BotCommands::Start].endpoint(
+            .branch(case![BotCommands::Start(payload)].endpoint(
                 move |bot: Bot, msg: Message, _dialogue: MyDialogue| {
                     let services_local = services1.clone();
-                    let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
                     async move {
                         commands::start::StartCommand::execute(
                             bot,
@@ -63,7 +88,7 @@ impl Router for TelegramRouter {
             .branch(case![BotCommands::Menu].endpoint(
                 move |bot: Bot, msg: Message, dialogue: MyDialogue| {
                     let services_local = services2.clone();
-                    let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
                     async move {
                         commands::menu::MenuCommand::execute(
                             bot,
@@ -76,10 +101,42 @@ impl Router for TelegramRouter {
                     }
                 },
             ))
+            .branch(case![BotCommands::Balance].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services22.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::wallet::BalanceCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::Address].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services23.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::wallet::AddressCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
             .branch(case![BotCommands::CreateWallet].endpoint(
                 move |bot: Bot, msg: Message, dialogue: MyDialogue| {
                     let services_local = services3.clone();
-                    let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
                     async move {
                         commands::wallet::CreateWalletCommand::execute(
                             bot,
@@ -92,10 +149,298 @@ impl Router for TelegramRouter {
                     }
                 },
             ))
+            .branch(case![BotCommands::Deposit(args)].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services19.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::wallet::DepositCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::Price(args)].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services24.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::price::PriceCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::Info(args)].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services20.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::info::InfoCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::Dca].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services5.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::dca::DcaCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::Portfolio].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services6.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::portfolio::PortfolioCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::Positions].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services26.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::positions::PositionsCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::History].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services10.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::history::HistoryCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::Alerts].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services7.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::alert::AlertCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::Send].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services25.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    RequestContext::new(telegram_id).scope(async move {
+                        commands::withdraw::WithdrawCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    })
+                },
+            ))
+            .branch(case![BotCommands::Swap(args)].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services8.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    RequestContext::new(telegram_id).scope(async move {
+                        commands::swap::SwapCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    })
+                },
+            ))
+            .branch(case![BotCommands::Cleanup].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services12.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    RequestContext::new(telegram_id).scope(async move {
+                        commands::cleanup::CleanupCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    })
+                },
+            ))
+            .branch(case![BotCommands::Follow].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services14.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::follow::FollowCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::Export].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services13.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::export::ExportCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::Referral].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services16.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::referral::ReferralCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::WhoAmI].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services21.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::whoami::WhoAmICommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::Language].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services9.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::language::LanguageCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
+            .branch(case![BotCommands::Settings(args)].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services18.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    RequestContext::new(telegram_id).scope(async move {
+                        commands::settings::SettingsCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    })
+                },
+            ))
+            .branch(case![BotCommands::Cancel].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services11.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    async move {
+                        commands::cancel::CancelCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    }
+                },
+            ))
             .branch(case![BotCommands::Help].endpoint(
                 move |bot: Bot, msg: Message, dialogue: MyDialogue| {
                     let services_local = services4.clone();
-                    let telegram_id = msg.from().map_or(0, |user| user.id.0 as i64);
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
                     async move {
                         commands::help::HelpCommand::execute(
                             bot,
@@ -107,6 +452,38 @@ impl Router for TelegramRouter {
                         .await
                     }
                 },
+            ))
+            .branch(case![BotCommands::Admin(args)].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services15.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    RequestContext::new(telegram_id).scope(async move {
+                        commands::admin::AdminCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    })
+                },
+            ))
+            .branch(case![BotCommands::Status].endpoint(
+                move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                    let services_local = services17.clone();
+                    let telegram_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+                    RequestContext::new(telegram_id).scope(async move {
+                        commands::status::StatusCommand::execute(
+                            bot,
+                            msg,
+                            telegram_id,
+                            Some(dialogue),
+                            services_local,
+                        )
+                        .await
+                    })
+                },
             ));
 
         let services_for_dialog1 = self.services.clone();
@@ -127,6 +504,21 @@ impl Router for TelegramRouter {
         let services_for_dialog16 = self.services.clone();
         let services_for_dialog17 = self.services.clone();
         let services_for_dialog18 = self.services.clone();
+        let services_for_dialog19 = self.services.clone();
+        let services_for_dialog20 = self.services.clone();
+        let services_for_dialog21 = self.services.clone();
+        let services_for_dialog22 = self.services.clone();
+        let services_for_dialog23 = self.services.clone();
+        let services_for_dialog24 = self.services.clone();
+        let services_for_dialog25 = self.services.clone();
+        let services_for_dialog26 = self.services.clone();
+        let services_for_dialog27 = self.services.clone();
+        let services_for_dialog28 = self.services.clone();
+        let services_for_dialog29 = self.services.clone();
+        let services_for_dialog30 = self.services.clone();
+        let services_for_dialog31 = self.services.clone();
+        let services_for_dialog32 = self.services.clone();
+        let services_for_dialog33 = self.services.clone();
 
         let message_handler = Update::filter_message().branch(command_handler).branch(
             dptree::entry()
@@ -141,12 +533,13 @@ impl Router for TelegramRouter {
                     .endpoint(
                         move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
                             let services = services_for_dialog1.clone();
-                            async move {
+                            let context = RequestContext::from_message(&msg);
+                            context.scope(async move {
                                 withdraw::receive_recipient_address(
                                     bot, msg, state, dialogue, services,
                                 )
                                 .await
-                            }
+                            })
                         },
                     ),
                 )
@@ -162,12 +555,13 @@ impl Router for TelegramRouter {
                     .endpoint(
                         move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
                             let services = services_for_dialog2.clone();
-                            async move {
+                            let context = RequestContext::from_message(&msg);
+                            context.scope(async move {
                                 withdraw::receive_withdraw_amount(
                                     bot, msg, state, dialogue, services,
                                 )
                                 .await
-                            }
+                            })
                         },
                     ),
                 )
@@ -184,12 +578,13 @@ impl Router for TelegramRouter {
                     .endpoint(
                         move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
                             let services = services_for_dialog3.clone();
-                            async move {
+                            let context = RequestContext::from_message(&msg);
+                            context.scope(async move {
                                 withdraw::receive_withdraw_confirmation(
                                     bot, msg, state, dialogue, services,
                                 )
                                 .await
-                            }
+                            })
                         },
                     ),
                 )
@@ -237,6 +632,30 @@ impl Router for TelegramRouter {
                         },
                     ),
                 )
+                .branch(
+                    case![State::AwaitingLimitOrderExpiry {
+                        order_type,
+                        token_address,
+                        token_symbol,
+                        price_in_sol,
+                        amount,
+                        total_sol,
+                        trigger_metric,
+                        trigger_value,
+                        price_currency
+                    }]
+                    .endpoint(
+                        move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
+                            let services = services_for_dialog19.clone();
+                            async move {
+                                commands::limit_order::receive_expiry(
+                                    bot, msg, state, dialogue, services,
+                                )
+                                .await
+                            }
+                        },
+                    ),
+                )
                 .branch(
                     case![State::AwaitingLimitOrderConfirmation {
                         order_type,
@@ -244,7 +663,11 @@ impl Router for TelegramRouter {
                         token_symbol,
                         price_in_sol,
                         amount,
-                        total_sol
+                        total_sol,
+                        trigger_metric,
+                        trigger_value,
+                        price_currency,
+                        expiry
                     }]
                     .endpoint(
                         move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
@@ -258,6 +681,99 @@ impl Router for TelegramRouter {
                         },
                     ),
                 )
+                .branch(case![State::AwaitingLimitOrderEdit { order_id }].endpoint(
+                    move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
+                        let services = services_for_dialog33.clone();
+                        async move {
+                            commands::limit_order::receive_order_edit(
+                                bot, msg, state, dialogue, services,
+                            )
+                            .await
+                        }
+                    },
+                ))
+                .branch(case![State::AwaitingDcaTokenAddress].endpoint(
+                    move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                        let services = services_for_dialog20.clone();
+                        async move {
+                            commands::dca::receive_token_address(bot, msg, dialogue, services).await
+                        }
+                    },
+                ))
+                .branch(
+                    case![State::AwaitingDcaAmount {
+                        token_address,
+                        token_symbol
+                    }]
+                    .endpoint(
+                        move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
+                            let services = services_for_dialog21.clone();
+                            async move {
+                                commands::dca::receive_amount(bot, msg, state, dialogue, services)
+                                    .await
+                            }
+                        },
+                    ),
+                )
+                .branch(
+                    case![State::AwaitingDcaInterval {
+                        token_address,
+                        token_symbol,
+                        sol_per_interval
+                    }]
+                    .endpoint(
+                        move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
+                            let services = services_for_dialog22.clone();
+                            async move {
+                                commands::dca::receive_interval(bot, msg, state, dialogue, services)
+                                    .await
+                            }
+                        },
+                    ),
+                )
+                .branch(
+                    case![State::AwaitingDcaRuns {
+                        token_address,
+                        token_symbol,
+                        sol_per_interval,
+                        interval_seconds
+                    }]
+                    .endpoint(
+                        move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
+                            let services = services_for_dialog23.clone();
+                            async move {
+                                commands::dca::receive_runs(bot, msg, state, dialogue, services)
+                                    .await
+                            }
+                        },
+                    ),
+                )
+                .branch(case![State::AwaitingAlertTokenAddress].endpoint(
+                    move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                        let services = services_for_dialog24.clone();
+                        async move {
+                            commands::alert::receive_token_address(bot, msg, dialogue, services)
+                                .await
+                        }
+                    },
+                ))
+                .branch(
+                    case![State::AwaitingAlertCondition {
+                        token_address,
+                        token_symbol
+                    }]
+                    .endpoint(
+                        move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
+                            let services = services_for_dialog25.clone();
+                            async move {
+                                commands::alert::receive_condition(
+                                    bot, msg, state, dialogue, services,
+                                )
+                                .await
+                            }
+                        },
+                    ),
+                )
                 .branch(case![State::AwaitingSlippageInput].endpoint(
                     move |bot: Bot, msg: Message, dialogue: MyDialogue| {
                         let services = services_for_dialog12.clone();
@@ -267,6 +783,60 @@ impl Router for TelegramRouter {
                         }
                     },
                 ))
+                .branch(case![State::AwaitingQuickBuyAmountsInput].endpoint(
+                    move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                        let services = services_for_dialog28.clone();
+                        async move {
+                            commands::settings::handle_quick_buy_amounts_input(
+                                bot, msg, dialogue, services,
+                            )
+                            .await
+                        }
+                    },
+                ))
+                .branch(case![State::AwaitingMaxAccountsInput].endpoint(
+                    move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                        let services = services_for_dialog29.clone();
+                        async move {
+                            commands::settings::handle_max_accounts_input(
+                                bot, msg, dialogue, services,
+                            )
+                            .await
+                        }
+                    },
+                ))
+                .branch(case![State::AwaitingValiditySlotsInput].endpoint(
+                    move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                        let services = services_for_dialog32.clone();
+                        async move {
+                            commands::settings::handle_validity_slots_input(
+                                bot, msg, dialogue, services,
+                            )
+                            .await
+                        }
+                    },
+                ))
+                .branch(case![State::AwaitingBatchWithdrawInput].endpoint(
+                    move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                        let services = services_for_dialog31.clone();
+                        let context = RequestContext::from_message(&msg);
+                        context.scope(async move {
+                            withdraw::receive_batch_withdraw_input(bot, msg, dialogue, services)
+                                .await
+                        })
+                    },
+                ))
+                .branch(case![State::AwaitingSettingsImport].endpoint(
+                    move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                        let services = services_for_dialog30.clone();
+                        async move {
+                            commands::settings::handle_settings_import_input(
+                                bot, msg, dialogue, services,
+                            )
+                            .await
+                        }
+                    },
+                ))
                 .branch(case![State::AwaitingWatchlistTokenAddress].endpoint(
                     move |bot: Bot, msg: Message, dialogue: MyDialogue| {
                         let services = services_for_dialog13.clone();
@@ -289,10 +859,11 @@ impl Router for TelegramRouter {
                     .endpoint(
                         move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
                             let services = services_for_dialog14.clone();
-                            async move {
+                            let context = RequestContext::from_message(&msg);
+                            context.scope(async move {
                                 trade::receive_sell_amount(bot, msg, state, dialogue, services)
                                     .await
-                            }
+                            })
                         },
                     ),
                 )
@@ -303,26 +874,48 @@ impl Router for TelegramRouter {
                         amount,
                         price_in_sol,
                         total_sol,
-                        total_usdc
+                        total_usdc,
+                        high_price_impact
                     }]
                     .endpoint(
                         move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
                             let services = services_for_dialog15.clone();
-                            async move {
+                            let context = RequestContext::from_message(&msg);
+                            context.scope(async move {
                                 trade::receive_sell_confirmation(
                                     bot, msg, state, dialogue, services,
                                 )
                                 .await
-                            }
+                            })
                         },
                     ),
                 )
                 .branch(case![State::AwaitingBuyManualAddress].endpoint(
                     move |bot: Bot, msg: Message, dialogue: MyDialogue| {
                         let services = services_for_dialog16.clone();
-                        async move {
+                        let context = RequestContext::from_message(&msg);
+                        context.scope(async move {
                             trade::receive_buy_manual_address(bot, msg, dialogue, services).await
-                        }
+                        })
+                    },
+                ))
+                .branch(case![State::AwaitingBuyTokenSearch].endpoint(
+                    move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                        let services = services_for_dialog26.clone();
+                        let context = RequestContext::from_message(&msg);
+                        context.scope(async move {
+                            trade::receive_buy_token_search(bot, msg, dialogue, services).await
+                        })
+                    },
+                ))
+                .branch(case![State::AwaitingFollowWalletAddress].endpoint(
+                    move |bot: Bot, msg: Message, dialogue: MyDialogue| {
+                        let services = services_for_dialog27.clone();
+                        let context = RequestContext::from_message(&msg);
+                        context.scope(async move {
+                            commands::follow::receive_wallet_address(bot, msg, dialogue, services)
+                                .await
+                        })
                     },
                 ))
                 .branch(
@@ -335,9 +928,10 @@ impl Router for TelegramRouter {
                     .endpoint(
                         move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
                             let services = services_for_dialog17.clone();
-                            async move {
+                            let context = RequestContext::from_message(&msg);
+                            context.scope(async move {
                                 trade::receive_buy_amount(bot, msg, state, dialogue, services).await
-                            }
+                            })
                         },
                     ),
                 )
@@ -348,15 +942,17 @@ impl Router for TelegramRouter {
                         amount,
                         price_in_sol,
                         total_sol,
-                        total_usdc
+                        total_usdc,
+                        high_price_impact
                     }]
                     .endpoint(
                         move |bot: Bot, msg: Message, state: State, dialogue: MyDialogue| {
                             let services = services_for_dialog18.clone();
-                            async move {
+                            let context = RequestContext::from_message(&msg);
+                            context.scope(async move {
                                 trade::receive_buy_confirmation(bot, msg, state, dialogue, services)
                                     .await
-                            }
+                            })
                         },
                     ),
                 ),
@@ -366,12 +962,127 @@ impl Router for TelegramRouter {
         let callback_handler = Update::filter_callback_query().endpoint(
             move |bot: Bot, q: CallbackQuery, dialogue: MyDialogue| {
                 let services = services_for_callbacks.clone();
-                async move { handle_callback(bot, q, dialogue, services).await }
+                let context = RequestContext::new(q.from.id.0 as i64);
+                context.scope(async move { handle_callback(bot, q, dialogue, services).await })
             },
         );
 
+        // Remember when each chat was last active so idle dialogues can be expired
+        let dialogue_activity = self.services.dialogue_activity();
+
+        // Requests over the user's limit are answered here and never reach the handlers
+        let services_for_rate_limit = self.services.clone();
+        let rate_limit_handler = dptree::filter_async(move |update: Update, state: State| {
+            let services = services_for_rate_limit.clone();
+            async move { is_rate_limited(&services, &update, &state).await }
+        })
+        .endpoint(reply_rate_limited);
+
         teloxide::dispatching::dialogue::enter::<Update, InMemStorage<State>, State, _>()
+            .inspect(move |dialogue: MyDialogue| dialogue_activity.touch(dialogue.chat_id()))
+            .branch(rate_limit_handler)
             .branch(message_handler)
             .branch(callback_handler)
     }
 }
+
+// Trades, swaps and withdrawals are executed from confirmations, everything else only reads
+fn request_kind(update: &Update, state: &State) -> RequestKind {
+    let is_trade = match &update.kind {
+        UpdateKind::Message(msg) => {
+            msg.text().is_some_and(|text| text.starts_with("/swap"))
+                || matches!(
+                    state,
+                    State::AwaitingBuyConfirmation { .. }
+                        | State::AwaitingSellConfirmation { .. }
+                        | State::AwaitingWithdrawConfirmation { .. }
+                        | State::AwaitingLimitOrderConfirmation { .. }
+                        | State::AwaitingConfirmation { .. }
+                )
+        }
+        UpdateKind::CallbackQuery(q) => matches!(
+            q.data.as_deref(),
+            Some(
+                "dialog_confirm"
+                    | "confirm_swap"
+                    | "confirm_cleanup"
+                    | "batch_withdraw_stop"
+                    | "batch_withdraw_continue"
+            )
+        ),
+        _ => false,
+    };
+
+    if is_trade {
+        RequestKind::Trade
+    } else {
+        RequestKind::Read
+    }
+}
+
+// Buy and sell amounts and quick-buy buttons, executed without a confirmation under auto-confirm
+fn may_auto_confirm(update: &Update, state: &State) -> bool {
+    match &update.kind {
+        UpdateKind::Message(_) => matches!(
+            state,
+            State::AwaitingBuyAmount { .. } | State::AwaitingSellAmount { .. }
+        ),
+        UpdateKind::CallbackQuery(q) => q
+            .data
+            .as_deref()
+            .is_some_and(|data| data.starts_with("buy_quick_")),
+        _ => false,
+    }
+}
+
+// Whether the user has auto-confirm switched on, a lookup error counts as off
+async fn has_auto_confirm(services: &ServiceContainer, telegram_id: i64) -> bool {
+    db::get_user_by_telegram_id(&services.db_pool(), telegram_id)
+        .await
+        .is_ok_and(|user| user.get_auto_confirm_below_sol() > 0.0)
+}
+
+// Takes a token from the user's bucket, true when the request must be rejected
+async fn is_rate_limited(services: &ServiceContainer, update: &Update, state: &State) -> bool {
+    let telegram_id = match update.from() {
+        Some(user) => user.id.0 as i64,
+        None => return false,
+    };
+
+    // Without a confirmation step the amount itself executes the trade
+    let mut kind = request_kind(update, state);
+    if kind == RequestKind::Read
+        && may_auto_confirm(update, state)
+        && has_auto_confirm(services, telegram_id).await
+    {
+        kind = RequestKind::Trade;
+    }
+
+    if services.rate_limiter().check(telegram_id, kind) {
+        return false;
+    }
+
+    log::warn!(
+        "Rate limit exceeded by Telegram ID {} ({:?} request)",
+        telegram_id,
+        kind
+    );
+    true
+}
+
+async fn reply_rate_limited(bot: Bot, update: Update) -> anyhow::Result<()> {
+    const SLOW_DOWN: &str =
+        "⏳ Slow down! You are sending requests too fast, try again in a moment.";
+
+    match update.kind {
+        UpdateKind::Message(msg) => {
+            bot.send_message(msg.chat.id, SLOW_DOWN).await?;
+        }
+        UpdateKind::CallbackQuery(q) => {
+            bot.answer_callback_query(q.id).text(SLOW_DOWN).await?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
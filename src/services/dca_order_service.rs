@@ -0,0 +1,222 @@
+use crate::di::ServiceContainer;
+use crate::entity::{
+    should_notify, Cluster, DcaOrder, DcaOrderStatus, NotificationKind, OrderType,
+};
+use crate::interactor::db;
+use crate::interactor::trade_interactor::{TradeInteractor, TradeInteractorImpl, TradeRequest};
+use crate::utils::format_tx_url;
+use crate::view::dca_view::format_interval;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::{debug, error, info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::{prelude::*, types::ParseMode, utils::html, Bot};
+use tokio::select;
+use tokio::sync::mpsc;
+use tokio::time::interval;
+
+pub struct DcaOrderService {
+    services: Arc<ServiceContainer>,
+    bot: Bot,
+    stop_tx: Option<mpsc::Sender<()>>,
+}
+
+impl DcaOrderService {
+    pub fn new(services: Arc<ServiceContainer>, bot: Bot) -> Self {
+        Self {
+            services,
+            bot,
+            stop_tx: None,
+        }
+    }
+
+    // Start the background service that executes due DCA buys
+    pub async fn start(&mut self) -> Result<()> {
+        if self.stop_tx.is_some() {
+            warn!("DCA order service is already running");
+            return Ok(());
+        }
+
+        // Create a channel for stopping the service
+        let (stop_tx, mut stop_rx) = mpsc::channel::<()>(1);
+        self.stop_tx = Some(stop_tx);
+
+        let services_clone = self.services.clone();
+        let bot_clone = self.bot.clone();
+
+        tokio::spawn(async move {
+            // Create an interval ticker that triggers every 30 seconds
+            let mut interval = interval(Duration::from_secs(30));
+
+            loop {
+                select! {
+                    _ = interval.tick() => {
+                        debug!("Running DCA order check");
+
+                        if let Err(e) = Self::process_dca_orders(&services_clone, &bot_clone).await {
+                            error!("Error processing DCA orders: {}", e);
+                        }
+                    }
+                    // When we receive a stop signal, exit the loop
+                    _ = stop_rx.recv() => {
+                        info!("Stopping DCA order service");
+                        break;
+                    }
+                }
+            }
+        });
+
+        info!("DCA order service started");
+        Ok(())
+    }
+
+    // Stop the background service
+    pub async fn stop(&mut self) {
+        if let Some(tx) = self.stop_tx.take() {
+            let _ = tx.send(()).await;
+            info!("DCA order service stop signal sent");
+        }
+    }
+
+    async fn process_dca_orders(services: &Arc<ServiceContainer>, bot: &Bot) -> Result<()> {
+        let db_pool = services.db_pool();
+
+        let due_orders = db::get_due_dca_orders(&db_pool).await?;
+
+        if due_orders.is_empty() {
+            return Ok(());
+        }
+
+        info!("Processing {} due DCA orders", due_orders.len());
+
+        for order in &due_orders {
+            // Reschedule before buying so a failing order doesn't retry on every tick
+            let schedule = order.advance(Utc::now());
+            if let Err(e) = db::update_dca_order_schedule(&db_pool, order.id, &schedule).await {
+                error!("Failed to reschedule DCA order #{}: {}", order.id, e);
+                continue;
+            }
+
+            let result = Self::execute_order(services, order).await;
+
+            if let Err(e) = Self::notify_user(
+                services,
+                bot,
+                order,
+                &result,
+                schedule.remaining_runs,
+                schedule.status == DcaOrderStatus::Completed,
+            )
+            .await
+            {
+                error!("Failed to notify user about DCA order #{}: {}", order.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Execute a single DCA buy, returns the transaction signature
+    async fn execute_order(services: &Arc<ServiceContainer>, order: &DcaOrder) -> Result<String> {
+        let db_pool = services.db_pool();
+
+        let user = db::get_user_by_id(&db_pool, order.user_id).await?;
+
+        // Convert the SOL amount into a token amount at the current price
+        let price_info = services
+            .price_service()
+            .get_token_price_fresh(&order.token_address)
+            .await?;
+
+        if price_info.price_in_sol <= 0.0 {
+            return Err(anyhow!("No price available for {}", order.token_symbol));
+        }
+
+        let amount = order.sol_per_interval / price_info.price_in_sol;
+
+        let interactor = TradeInteractorImpl::new(
+            db_pool.clone(),
+            services.solana_client(),
+            services.price_service(),
+            services.token_repository(),
+            services.swap_service(),
+        );
+
+        let result = interactor
+            .execute_trade(
+                user.telegram_id,
+                TradeRequest {
+                    trade_type: &OrderType::Buy,
+                    token_address: &order.token_address,
+                    token_symbol: &order.token_symbol,
+                    amount,
+                    price_in_sol: price_info.price_in_sol,
+                    slippage: None,
+                },
+            )
+            .await?;
+
+        if result.success {
+            Ok(result.signature.unwrap_or_else(|| "unknown".to_string()))
+        } else {
+            Err(anyhow!(result
+                .error_message
+                .unwrap_or_else(|| "Unknown error".to_string())))
+        }
+    }
+
+    async fn notify_user(
+        services: &Arc<ServiceContainer>,
+        bot: &Bot,
+        order: &DcaOrder,
+        result: &Result<String>,
+        remaining_runs: i32,
+        completed: bool,
+    ) -> Result<()> {
+        let user = db::get_user_by_id(&services.db_pool(), order.user_id).await?;
+
+        // Failed buys are always reported, the user may have to top up the wallet
+        if result.is_ok() && !should_notify(&user, NotificationKind::DcaBuys) {
+            return Ok(());
+        }
+
+        let mut message = match result {
+            Ok(signature) => format!(
+                "✅ <b>DCA Buy Executed</b>\n\n\
+                 DCA order #{} bought {:.6} SOL of {}:\n\
+                 • Transaction: <a href=\"{}\">View on {}</a>",
+                order.id,
+                order.sol_per_interval,
+                order.token_symbol,
+                format_tx_url(signature, user.get_explorer(), Cluster::current()),
+                user.get_explorer()
+            ),
+            Err(e) => format!(
+                "❌ <b>DCA Buy Failed</b>\n\n\
+                 DCA order #{} could not buy {:.6} SOL of {}:\n\
+                 • Error: {}",
+                order.id,
+                order.sol_per_interval,
+                order.token_symbol,
+                html::escape(&e.to_string())
+            ),
+        };
+
+        if completed {
+            message.push_str("\n\nThis was the last buy, the DCA order is now completed.");
+        } else {
+            message.push_str(&format!(
+                "\n\n{} buys left, next in {}.",
+                remaining_runs,
+                format_interval(order.interval_seconds)
+            ));
+        }
+
+        bot.send_message(ChatId(user.telegram_id), message)
+            .parse_mode(ParseMode::Html)
+            .await?;
+
+        Ok(())
+    }
+}
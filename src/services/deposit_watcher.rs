@@ -0,0 +1,194 @@
+use crate::di::ServiceContainer;
+use crate::entity::{should_notify, Cluster, Deposit, NotificationKind, SignatureDiff, User};
+use crate::interactor::db;
+use crate::solana::activity;
+use crate::utils::format_tx_url;
+use anyhow::Result;
+use log::{debug, error, info, warn};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::{prelude::*, types::ParseMode, Bot};
+use tokio::select;
+use tokio::sync::mpsc;
+use tokio::time::interval;
+
+/// Signatures requested per wallet on every check
+const SIGNATURES_PER_CHECK: usize = 20;
+
+pub struct DepositWatcher {
+    services: Arc<ServiceContainer>,
+    bot: Bot,
+    stop_tx: Option<mpsc::Sender<()>>,
+}
+
+impl DepositWatcher {
+    pub fn new(services: Arc<ServiceContainer>, bot: Bot) -> Self {
+        Self {
+            services,
+            bot,
+            stop_tx: None,
+        }
+    }
+
+    // Start the background service that reports SOL and tokens arriving in user wallets
+    pub async fn start(&mut self) -> Result<()> {
+        if self.stop_tx.is_some() {
+            warn!("Deposit watcher is already running");
+            return Ok(());
+        }
+
+        // Create a channel for stopping the service
+        let (stop_tx, mut stop_rx) = mpsc::channel::<()>(1);
+        self.stop_tx = Some(stop_tx);
+
+        let services_clone = self.services.clone();
+        let bot_clone = self.bot.clone();
+
+        tokio::spawn(async move {
+            // Create an interval ticker that triggers every 30 seconds
+            let mut interval = interval(Duration::from_secs(30));
+
+            loop {
+                select! {
+                    _ = interval.tick() => {
+                        debug!("Running deposit check");
+
+                        if let Err(e) = Self::check_wallets(&services_clone, &bot_clone).await {
+                            error!("Error checking wallets for deposits: {}", e);
+                        }
+                    }
+                    // When we receive a stop signal, exit the loop
+                    _ = stop_rx.recv() => {
+                        info!("Stopping deposit watcher");
+                        break;
+                    }
+                }
+            }
+        });
+
+        info!("Deposit watcher started");
+        Ok(())
+    }
+
+    // Stop the background service
+    pub async fn stop(&mut self) {
+        if let Some(tx) = self.stop_tx.take() {
+            let _ = tx.send(()).await;
+            info!("Deposit watcher stop signal sent");
+        }
+    }
+
+    async fn check_wallets(services: &Arc<ServiceContainer>, bot: &Bot) -> Result<()> {
+        let users = db::get_users_with_wallets(&services.db_pool()).await?;
+
+        for user in &users {
+            if let Err(e) = Self::check_wallet(services, bot, user).await {
+                error!(
+                    "Failed to check deposits of user {}: {}",
+                    user.telegram_id, e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn check_wallet(services: &Arc<ServiceContainer>, bot: &Bot, user: &User) -> Result<()> {
+        let Some(address) = &user.solana_address else {
+            return Ok(());
+        };
+
+        let db_pool = services.db_pool();
+        let solana_client = services.solana_client();
+
+        let signatures =
+            activity::get_recent_signatures(&solana_client, address, SIGNATURES_PER_CHECK).await?;
+        let diff = SignatureDiff::between(user.last_seen_signature.as_ref(), &signatures);
+
+        // The newest signature is still saved, so turning deposits back on doesn't report old ones
+        if !diff.new_signatures.is_empty() && should_notify(user, NotificationKind::Deposits) {
+            // Transactions the user made through the bot are not deposits
+            let own_signatures: HashSet<String> =
+                db::get_user_recorded_signatures(&db_pool, user.id, &diff.new_signatures)
+                    .await?
+                    .into_iter()
+                    .collect();
+
+            for signature in &diff.new_signatures {
+                if own_signatures.contains(signature) {
+                    continue;
+                }
+
+                let wallet_activity =
+                    match activity::get_wallet_activity(&solana_client, address, signature).await {
+                        Ok(Some(wallet_activity)) => wallet_activity,
+                        Ok(None) => continue,
+                        Err(e) => {
+                            error!("Failed to get activity of {}: {}", signature, e);
+                            continue;
+                        }
+                    };
+
+                if let Some(deposit) = Deposit::from_activity(&wallet_activity, &own_signatures) {
+                    if let Err(e) = Self::notify_user(services, bot, user, &deposit).await {
+                        error!(
+                            "Failed to notify user {} about deposit {}: {}",
+                            user.telegram_id, signature, e
+                        );
+                    }
+                }
+            }
+        }
+
+        if let Some(last_signature) = &diff.last_signature {
+            if user.last_seen_signature.as_ref() != Some(last_signature) {
+                db::update_user_last_seen_signature(&db_pool, user.id, last_signature).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn notify_user(
+        services: &Arc<ServiceContainer>,
+        bot: &Bot,
+        user: &User,
+        deposit: &Deposit,
+    ) -> Result<()> {
+        let mut lines = Vec::new();
+
+        if deposit.sol_amount > 0.0 {
+            lines.push(format!("• <b>+{:.6} SOL</b>", deposit.sol_amount));
+        }
+
+        for change in &deposit.tokens {
+            let symbol = match services
+                .token_repository()
+                .get_token_by_id(&change.mint)
+                .await
+            {
+                Ok(token) => token.symbol,
+                Err(_) => format!("{}…", &change.mint[..change.mint.len().min(6)]),
+            };
+
+            lines.push(format!("• <b>+{:.6} {}</b>", change.amount, symbol));
+        }
+
+        let explorer = user.get_explorer();
+        let message = format!(
+            "📥 <b>Deposit Received</b>\n\n\
+             {}\n\n\
+             <a href=\"{}\">View on {}</a>",
+            lines.join("\n"),
+            format_tx_url(&deposit.signature, explorer, Cluster::current()),
+            explorer
+        );
+
+        bot.send_message(ChatId(user.telegram_id), message)
+            .parse_mode(ParseMode::Html)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,238 @@
+use crate::entity::State;
+use anyhow::Result;
+use log::{debug, error, info, warn};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use teloxide::dispatching::dialogue::{InMemStorage, Storage};
+use teloxide::{prelude::*, Bot};
+use tokio::select;
+use tokio::sync::mpsc;
+use tokio::time::interval;
+
+/// Dialogues without any user activity for this long are reset to `State::Start`
+pub const DIALOGUE_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Last activity time per chat
+///
+/// `InMemStorage` doesn't keep timestamps, so the router records every incoming
+/// update here and the timeout service uses it to find abandoned dialogues.
+#[derive(Default)]
+pub struct DialogueActivity {
+    last_seen: Mutex<HashMap<ChatId, Instant>>,
+}
+
+impl DialogueActivity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record activity in the chat at the current time
+    pub fn touch(&self, chat_id: ChatId) {
+        self.touch_at(chat_id, Instant::now());
+    }
+
+    pub fn touch_at(&self, chat_id: ChatId, at: Instant) {
+        self.last_seen.lock().unwrap().insert(chat_id, at);
+    }
+
+    /// Remove and return the chats that have been idle for at least `timeout`
+    pub fn take_idle(&self, now: Instant, timeout: Duration) -> Vec<ChatId> {
+        let mut last_seen = self.last_seen.lock().unwrap();
+
+        let idle: Vec<ChatId> = last_seen
+            .iter()
+            .filter(|(_, seen)| now.saturating_duration_since(**seen) >= timeout)
+            .map(|(chat_id, _)| *chat_id)
+            .collect();
+
+        for chat_id in &idle {
+            last_seen.remove(chat_id);
+        }
+
+        idle
+    }
+}
+
+pub struct DialogueTimeoutService {
+    storage: Arc<InMemStorage<State>>,
+    activity: Arc<DialogueActivity>,
+    bot: Bot,
+    stop_tx: Option<mpsc::Sender<()>>,
+}
+
+impl DialogueTimeoutService {
+    pub fn new(
+        storage: Arc<InMemStorage<State>>,
+        activity: Arc<DialogueActivity>,
+        bot: Bot,
+    ) -> Self {
+        Self {
+            storage,
+            activity,
+            bot,
+            stop_tx: None,
+        }
+    }
+
+    // Start the background service that resets abandoned dialogues
+    pub async fn start(&mut self) -> Result<()> {
+        if self.stop_tx.is_some() {
+            warn!("Dialogue timeout service is already running");
+            return Ok(());
+        }
+
+        // Create a channel for stopping the service
+        let (stop_tx, mut stop_rx) = mpsc::channel::<()>(1);
+        self.stop_tx = Some(stop_tx);
+
+        let storage_clone = self.storage.clone();
+        let activity_clone = self.activity.clone();
+        let bot_clone = self.bot.clone();
+
+        tokio::spawn(async move {
+            // Create an interval ticker that triggers every minute
+            let mut interval = interval(Duration::from_secs(60));
+
+            loop {
+                select! {
+                    _ = interval.tick() => {
+                        debug!("Running idle dialogue sweep");
+
+                        if let Err(e) = Self::reset_idle_dialogues(&storage_clone, &activity_clone, &bot_clone).await {
+                            error!("Error resetting idle dialogues: {}", e);
+                        }
+                    }
+                    // When we receive a stop signal, exit the loop
+                    _ = stop_rx.recv() => {
+                        info!("Stopping dialogue timeout service");
+                        break;
+                    }
+                }
+            }
+        });
+
+        info!("Dialogue timeout service started");
+        Ok(())
+    }
+
+    // Stop the background service
+    pub async fn stop(&mut self) {
+        if let Some(tx) = self.stop_tx.take() {
+            let _ = tx.send(()).await;
+            info!("Dialogue timeout service stop signal sent");
+        }
+    }
+
+    async fn reset_idle_dialogues(
+        storage: &Arc<InMemStorage<State>>,
+        activity: &DialogueActivity,
+        bot: &Bot,
+    ) -> Result<()> {
+        for chat_id in activity.take_idle(Instant::now(), DIALOGUE_IDLE_TIMEOUT) {
+            match storage.clone().get_dialogue(chat_id).await? {
+                Some(State::Start) | None => continue,
+                Some(_) => {}
+            }
+
+            storage.clone().remove_dialogue(chat_id).await?;
+            info!("Reset idle dialogue in chat {}", chat_id);
+
+            if let Err(e) = bot
+                .send_message(
+                    chat_id,
+                    "⌛ Your pending action has expired. Use /menu to start again.",
+                )
+                .await
+            {
+                error!(
+                    "Failed to notify chat {} about expired dialogue: {}",
+                    chat_id, e
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(600);
+
+    #[test]
+    fn chats_idle_for_the_timeout_are_taken() {
+        let activity = DialogueActivity::new();
+        let now = Instant::now();
+        let start = now.checked_sub(TIMEOUT * 2).unwrap();
+        activity.touch_at(ChatId(1), start);
+        activity.touch_at(ChatId(2), start + TIMEOUT);
+        activity.touch_at(ChatId(3), now);
+
+        let mut idle = activity.take_idle(now, TIMEOUT);
+        idle.sort_by_key(|chat_id| chat_id.0);
+
+        assert_eq!(idle, [ChatId(1), ChatId(2)]);
+    }
+
+    #[test]
+    fn taken_chats_are_not_taken_again() {
+        let activity = DialogueActivity::new();
+        let now = Instant::now();
+        activity.touch_at(ChatId(1), now.checked_sub(TIMEOUT).unwrap());
+
+        assert_eq!(activity.take_idle(now, TIMEOUT), [ChatId(1)]);
+        assert!(activity.take_idle(now, TIMEOUT).is_empty());
+    }
+
+    #[test]
+    fn new_activity_restarts_the_idle_time() {
+        let activity = DialogueActivity::new();
+        let now = Instant::now();
+        activity.touch_at(ChatId(1), now.checked_sub(TIMEOUT).unwrap());
+        activity.touch_at(ChatId(1), now);
+
+        assert!(activity.take_idle(now, TIMEOUT).is_empty());
+    }
+
+    #[tokio::test]
+    async fn idle_dialogues_are_reset_to_start() {
+        let storage = InMemStorage::<State>::new();
+        let activity = DialogueActivity::new();
+        let idle_since = Instant::now()
+            .checked_sub(DIALOGUE_IDLE_TIMEOUT + Duration::from_secs(1))
+            .unwrap();
+
+        storage
+            .clone()
+            .update_dialogue(ChatId(1), State::AwaitingRecipientAddress)
+            .await
+            .unwrap();
+        activity.touch_at(ChatId(1), idle_since);
+        storage
+            .clone()
+            .update_dialogue(ChatId(2), State::AwaitingPriceTokenAddress)
+            .await
+            .unwrap();
+        activity.touch(ChatId(2));
+
+        // Nothing listens there, the expiry notice fails without stopping the sweep
+        let bot = Bot::new("123:abc").set_api_url("http://127.0.0.1:9".parse().unwrap());
+        DialogueTimeoutService::reset_idle_dialogues(&storage, &activity, &bot)
+            .await
+            .unwrap();
+
+        assert!(storage
+            .clone()
+            .get_dialogue(ChatId(1))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            storage.clone().get_dialogue(ChatId(2)).await.unwrap(),
+            Some(State::AwaitingPriceTokenAddress)
+        ));
+    }
+}
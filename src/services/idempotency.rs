@@ -0,0 +1,156 @@
+use dashmap::mapref::entry::Entry;
+use dashmap::{DashMap, DashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Rejects an action while the same action is still being executed
+///
+/// A second tap on "Confirm", a repeated "yes" or a redelivered callback can reach the
+/// confirmation handlers before the first one has reset the dialogue. Every trade and
+/// withdrawal is keyed by user, kind, token and amount, and a key can only be started once
+/// until the action finishes. Keys older than `window` are dropped so an execution that
+/// never finishes doesn't block the user forever.
+pub struct IdempotencyGuard {
+    window: Duration,
+    in_flight: DashMap<String, Instant>,
+    /// Actions that must not run again until a restart, see [`InFlight::hold`]
+    held: DashSet<String>,
+}
+
+/// Marks an action as in progress until it is dropped
+pub struct InFlight {
+    guard: Arc<IdempotencyGuard>,
+    key: String,
+    started: Instant,
+}
+
+impl IdempotencyGuard {
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(120);
+
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            in_flight: DashMap::new(),
+            held: DashSet::new(),
+        }
+    }
+
+    /// Start the action, `None` when the same action is already in progress
+    pub fn begin(self: &Arc<Self>, key: impl Into<String>) -> Option<InFlight> {
+        self.begin_at(key, Instant::now())
+    }
+
+    pub fn begin_at(self: &Arc<Self>, key: impl Into<String>, now: Instant) -> Option<InFlight> {
+        let key = key.into();
+        if self.held.contains(&key) {
+            return None;
+        }
+
+        match self.in_flight.entry(key.clone()) {
+            Entry::Occupied(mut entry) => {
+                if now.saturating_duration_since(*entry.get()) < self.window {
+                    return None;
+                }
+                entry.insert(now);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(now);
+            }
+        }
+
+        Some(InFlight {
+            guard: self.clone(),
+            key,
+            started: now,
+        })
+    }
+}
+
+impl InFlight {
+    /// Keep the action from starting again, for an outcome that couldn't be recorded
+    pub fn hold(self) {
+        self.guard.held.insert(self.key.clone());
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        // Only release our own entry, an expired one may have been taken over meanwhile
+        self.guard
+            .in_flight
+            .remove_if(&self.key, |_, started| *started == self.started);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn action_can_start_again_once_finished() {
+        let guard = Arc::new(IdempotencyGuard::new(Duration::from_secs(120)));
+
+        let first = guard.begin("buy:1").unwrap();
+        assert!(guard.begin("buy:1").is_none());
+
+        drop(first);
+        assert!(guard.begin("buy:1").is_some());
+    }
+
+    #[test]
+    fn stale_action_is_taken_over_after_the_window() {
+        let guard = Arc::new(IdempotencyGuard::new(Duration::from_secs(120)));
+        let now = Instant::now();
+
+        let stale = guard.begin_at("buy:1", now).unwrap();
+        let current = guard.begin_at("buy:1", now + Duration::from_secs(121));
+        assert!(current.is_some());
+
+        // The stale execution finishing doesn't release the new one
+        drop(stale);
+        assert!(guard.begin("buy:1").is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn only_one_of_two_concurrent_confirmations_executes() {
+        let guard = Arc::new(IdempotencyGuard::new(Duration::from_secs(120)));
+        let executed = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+
+        let confirm = || {
+            let guard = guard.clone();
+            let executed = executed.clone();
+            let barrier = barrier.clone();
+            tokio::spawn(async move {
+                barrier.wait().await;
+                let Some(_in_flight) = guard.begin("buy:1001:mint:1.5") else {
+                    return false;
+                };
+                // Stands in for the swap, the second confirmation arrives meanwhile
+                executed.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                true
+            })
+        };
+
+        let (first, second) = tokio::join!(confirm(), confirm());
+
+        assert!(first.unwrap() ^ second.unwrap());
+        assert_eq!(executed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn held_action_never_starts_again() {
+        let guard = Arc::new(IdempotencyGuard::new(Duration::from_secs(1)));
+        let now = Instant::now();
+
+        guard.begin_at("order:1", now).unwrap().hold();
+
+        assert!(guard.begin_at("order:1", now).is_none());
+        assert!(guard
+            .begin_at("order:1", now + Duration::from_secs(60))
+            .is_none());
+        assert!(guard.begin_at("order:2", now).is_some());
+    }
+}
@@ -1,18 +1,92 @@
 use crate::di::ServiceContainer;
-use crate::entity::{LimitOrder, LimitOrderStatus, OrderType, WatchlistItem};
+use crate::entity::{
+    market_cap, should_notify, AlertDirection, BotError, Cluster, LimitOrder, LimitOrderFill,
+    LimitOrderStatus, NotificationKind, OrderType, PriceAlert, TokenPrice, TradeFailure,
+    WatchlistItem,
+};
+use crate::i18n::Locale;
+use crate::interactor::balance_interactor::{
+    BalanceInteractor, BalanceInteractorImpl, WalletBalances,
+};
 use crate::interactor::db;
-use crate::interactor::trade_interactor::{TradeInteractor, TradeInteractorImpl};
-use crate::solana::jupiter::price_service::PriceService;
+use crate::interactor::trade_interactor::{
+    TradeInteractor, TradeInteractorImpl, TradeRequest, TradeResult,
+};
+use crate::utils::{format_amount, format_price, format_tx_url, parse_duration};
 use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use lazy_static::lazy_static;
 use log::{debug, error, info, warn};
 use std::collections::{HashMap, HashSet};
+use std::env;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
-use teloxide::{prelude::*, types::ParseMode, Bot};
+use teloxide::{prelude::*, types::ParseMode, utils::html, Bot};
 use tokio::select;
 use tokio::sync::mpsc;
-use tokio::time::{interval, sleep, Instant};
+use tokio::time::{interval, Instant};
+
+/// Largest part of a limit order executed per price check, in SOL
+pub const DEFAULT_MAX_SLICE_SOL: f64 = 5.0;
+
+/// Slippage added (in percent) after every attempt that failed on price movement
+const SLIPPAGE_STEP: f64 = 0.5;
+
+/// Escalation stops at the highest slippage the settings allow
+const MAX_RETRY_SLIPPAGE: f64 = 5.0;
+
+/// Retries after the first failed attempt of an order
+pub const DEFAULT_MAX_RETRIES: i32 = 2;
+
+/// Minutes to wait before the first, second, ... retry, the last step repeats
+pub const DEFAULT_RETRY_BACKOFF_MINUTES: [i64; 3] = [1, 5, 15];
+
+lazy_static! {
+    // Configured with LIMIT_ORDER_MAX_SLICE_SOL, zero or less executes orders at once
+    static ref MAX_SLICE_SOL: f64 = env::var("LIMIT_ORDER_MAX_SLICE_SOL")
+        .ok()
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .filter(|value| value.is_finite())
+        .unwrap_or(DEFAULT_MAX_SLICE_SOL);
+
+    // Configured with LIMIT_ORDER_MAX_RETRIES, zero fails orders on the first error
+    static ref MAX_RETRIES: i32 = env::var("LIMIT_ORDER_MAX_RETRIES")
+        .ok()
+        .and_then(|value| value.trim().parse::<i32>().ok())
+        .filter(|value| *value >= 0)
+        .unwrap_or(DEFAULT_MAX_RETRIES);
+
+    // Configured with LIMIT_ORDER_RETRY_BACKOFF, e.g. "1m,5m,15m"
+    static ref RETRY_BACKOFF: Vec<chrono::Duration> = env::var("LIMIT_ORDER_RETRY_BACKOFF")
+        .ok()
+        .and_then(|value| {
+            value
+                .split(',')
+                .map(parse_duration)
+                .collect::<Result<Vec<_>>>()
+                .map_err(|e| warn!("Ignoring LIMIT_ORDER_RETRY_BACKOFF \"{}\": {}", value, e))
+                .ok()
+        })
+        .filter(|backoff| !backoff.is_empty())
+        .unwrap_or_else(|| {
+            DEFAULT_RETRY_BACKOFF_MINUTES
+                .iter()
+                .map(|minutes| chrono::Duration::minutes(*minutes))
+                .collect()
+        });
+}
+
+// Mint address and symbol of the tokens of the orders filled in one run, per user
+type FilledTokens = HashMap<i64, HashMap<String, String>>;
+
+// Orders, watchlists and alerts loaded for one run, checked against each new token price
+struct PriceWatchers<'a> {
+    active_orders: &'a [LimitOrder],
+    watchlist_by_user: &'a HashMap<i64, Vec<WatchlistItem>>,
+    active_alerts: &'a [PriceAlert],
+}
 
 pub struct LimitOrderService {
     services: Arc<ServiceContainer>,
@@ -92,10 +166,13 @@ impl LimitOrderService {
 
         // Collect all the token addresses we need to check prices for
         let mut all_tokens = HashMap::new();
-        let mut token_prices = HashMap::new();
+
+        // Expire stale orders before looking at prices
+        Self::expire_limit_orders(services, bot).await;
 
         // 1. Get all active limit orders
         let active_orders = db::get_all_active_limit_orders(&db_pool).await?;
+        let mut filled_tokens = FilledTokens::new();
 
         if !active_orders.is_empty() {
             info!("Processing {} active limit orders", active_orders.len());
@@ -106,6 +183,13 @@ impl LimitOrderService {
             }
         }
 
+        // Get all active price alerts
+        let active_alerts = db::get_all_active_price_alerts(&db_pool).await?;
+
+        for alert in &active_alerts {
+            all_tokens.insert(alert.token_address.clone(), alert.token_symbol.clone());
+        }
+
         // 2. Get all watchlist items from all users
         let mut all_users = HashSet::new();
         let mut watchlist_by_user = HashMap::new();
@@ -139,210 +223,564 @@ impl LimitOrderService {
             }
         }
 
-        // 3. Process all token prices in a single pass
+        // 3. Fetch all token prices in one batch, then apply them token by token
         if !all_tokens.is_empty() {
             info!("Getting prices for {} unique tokens", all_tokens.len());
 
-            let price_service = services.price_service();
-
-            // Get price for each token (no duplicates)
-            for (token_address, token_symbol) in all_tokens {
-                match price_service.get_token_price(&token_address).await {
-                    Ok(price_info) => {
-                        let price_in_sol = price_info.price_in_sol;
-                        debug!("Got price for {}: {} SOL", token_symbol, price_in_sol);
-
-                        // Store price for later use
-                        token_prices.insert(token_address.clone(), price_in_sol);
-
-                        // 4. Update limit orders with this token
-                        for order in active_orders
-                            .iter()
-                            .filter(|o| o.token_address == token_address)
-                        {
-                            if let Err(e) = db::update_limit_order_current_price(
-                                &db_pool,
-                                order.id,
-                                price_in_sol,
-                            )
-                            .await
-                            {
-                                error!("Failed to update limit order #{} price: {}", order.id, e);
-                            }
-
-                            // Check if we need to execute the order
-                            let should_execute = match order.order_type.as_str() {
-                                "BUY" => price_in_sol <= order.price_in_sol,
-                                "SELL" => price_in_sol >= order.price_in_sol,
-                                _ => false,
-                            };
-
-                            if should_execute {
-                                info!(
-                                    "Executing {} order #{} for {} {} at {} SOL (current price: {})",
-                                    order.order_type,
-                                    order.id,
-                                    order.amount,
-                                    order.token_symbol,
-                                    order.price_in_sol,
-                                    price_in_sol
-                                );
-
-                                if let Err(e) =
-                                    Self::execute_order(services, bot, order, price_in_sol).await
-                                {
-                                    error!("Failed to execute order #{}: {}", order.id, e);
-                                }
-                            }
-                        }
+            let token_ids: Vec<&str> = all_tokens.keys().map(String::as_str).collect();
+            let prices = services
+                .price_service()
+                .get_token_prices(&token_ids)
+                .await?;
+
+            let watchers = PriceWatchers {
+                active_orders: &active_orders,
+                watchlist_by_user: &watchlist_by_user,
+                active_alerts: &active_alerts,
+            };
+
+            // Orders are executed one at a time, so two orders can't spend the same balance
+            for (token_address, token_symbol) in &all_tokens {
+                match prices.get(token_address) {
+                    Some(price_info) => {
+                        debug!(
+                            "Got price for {}: {} SOL",
+                            token_symbol, price_info.price_in_sol
+                        );
+
+                        Self::apply_token_price(
+                            services,
+                            bot,
+                            token_address,
+                            token_symbol,
+                            price_info,
+                            &watchers,
+                            &mut filled_tokens,
+                        )
+                        .await;
+                    }
+                    None => {
+                        error!("Failed to get price for token {}", token_symbol);
+                    }
+                }
+            }
+        } else {
+            debug!("No tokens to process");
+        }
+
+        // One balance message per user however many of their orders were filled
+        Self::send_balance_updates(services, bot, filled_tokens).await;
+
+        Ok(())
+    }
+
+    // Show the new SOL and token balances of users whose orders were filled in this run
+    async fn send_balance_updates(
+        services: &Arc<ServiceContainer>,
+        bot: &Bot,
+        filled_tokens: FilledTokens,
+    ) {
+        let db_pool = services.db_pool();
+
+        for (telegram_id, tokens) in filled_tokens {
+            let user = match db::get_user_by_telegram_id(&db_pool, telegram_id).await {
+                Ok(user) => user,
+                Err(e) => {
+                    error!(
+                        "Failed to load user {} for a balance update: {}",
+                        telegram_id, e
+                    );
+                    continue;
+                }
+            };
+            if !should_notify(&user, NotificationKind::BalanceUpdates) {
+                continue;
+            }
+
+            // The trades changed the balances, the cached ones are stale
+            let interactor = BalanceInteractorImpl::new(
+                db_pool.clone(),
+                services.solana_client(),
+                services.price_service(),
+            );
+            let balances = match interactor.get_wallet_balances(telegram_id, true).await {
+                Ok(balances) => balances,
+                Err(e) => {
+                    error!("Failed to get balances of user {}: {}", telegram_id, e);
+                    continue;
+                }
+            };
+
+            let message =
+                balance_update_message(&balances, &tokens, user.get_locale().unwrap_or_default());
 
-                        // 5. Update watchlist items with this token
-                        for (telegram_id, watchlist) in &watchlist_by_user {
-                            for item in watchlist
-                                .iter()
-                                .filter(|w| w.token_address == token_address)
-                            {
-                                if let Err(e) = db::update_watchlist_price(
-                                    &db_pool,
-                                    *telegram_id,
-                                    &token_address,
-                                    price_in_sol,
-                                )
-                                .await
-                                {
-                                    error!("Failed to update watchlist price for user {}, token {}: {}", 
-                                        telegram_id, token_symbol, e);
-                                }
-                            }
+            if let Err(e) = bot
+                .send_message(ChatId(telegram_id), message)
+                .parse_mode(ParseMode::Html)
+                .await
+            {
+                error!("Failed to send balance update to {}: {}", telegram_id, e);
+            }
+        }
+    }
+
+    // Update orders, watchlists and alerts of a token with its new price
+    async fn apply_token_price(
+        services: &Arc<ServiceContainer>,
+        bot: &Bot,
+        token_address: &str,
+        token_symbol: &str,
+        price_info: &TokenPrice,
+        watchers: &PriceWatchers<'_>,
+        filled_tokens: &mut FilledTokens,
+    ) {
+        let db_pool = services.db_pool();
+        let price_in_sol = price_info.price_in_sol;
+
+        // 4. Update limit orders with this token
+        for order in watchers
+            .active_orders
+            .iter()
+            .filter(|o| o.token_address == token_address)
+        {
+            if let Err(e) =
+                db::update_limit_order_current_price(&db_pool, order.id, price_in_sol).await
+            {
+                error!("Failed to update limit order #{} price: {}", order.id, e);
+            }
+
+            // Check if we need to execute the order, orders that expired while prices
+            // were being fetched are left for the next run, failed ones wait for their backoff
+            let now = Utc::now();
+            let should_execute = order.is_executable(now)
+                && Self::is_triggered(services, order, price_in_sol, price_info.price_in_usdc)
+                    .await;
+
+            if should_execute {
+                // An order that is being edited is left for the next run, an edit can't
+                // start while the order executes
+                let Some(in_flight) = services
+                    .idempotency_guard()
+                    .begin(LimitOrder::execution_key(order.id))
+                else {
+                    debug!("Limit order #{} is being edited, skipped", order.id);
+                    continue;
+                };
+
+                // An edit saved since the orders were loaded changes the price or amount
+                let order = match db::get_limit_order_by_id(&db_pool, order.id).await {
+                    Ok(current) => match unchanged_since_load(order, current) {
+                        Some(current) => current,
+                        None => {
+                            debug!("Limit order #{} changed meanwhile, skipped", order.id);
+                            continue;
                         }
+                    },
+                    Err(e) => {
+                        error!("Failed to reload limit order #{}: {}", order.id, e);
+                        continue;
                     }
+                };
+                let order = &order;
+
+                // The batch price may be cached, the trade is made at a fresh one
+                let fresh_price = match services
+                    .price_service()
+                    .get_token_price_fresh(&order.token_address)
+                    .await
+                {
+                    Ok(price) => price,
                     Err(e) => {
-                        error!("Failed to get price for token {}: {}", token_symbol, e);
+                        error!("Failed to get a fresh price for order #{}: {}", order.id, e);
+                        continue;
                     }
+                };
+                if !Self::is_triggered(
+                    services,
+                    order,
+                    fresh_price.price_in_sol,
+                    fresh_price.price_in_usdc,
+                )
+                .await
+                {
+                    debug!("Limit order #{} no longer triggered, skipped", order.id);
+                    continue;
                 }
 
-                // Add a small delay between API calls to avoid rate limiting
-                sleep(Duration::from_millis(100)).await;
+                info!(
+                    "Executing {} order #{} for {} {} at {} SOL (current price: {})",
+                    order.order_type,
+                    order.id,
+                    order.remaining_amount(),
+                    order.token_symbol,
+                    order.price_in_sol,
+                    fresh_price.price_in_sol
+                );
+
+                if let Err(e) = Self::execute_order(
+                    services,
+                    bot,
+                    order,
+                    fresh_price.price_in_sol,
+                    filled_tokens,
+                )
+                .await
+                {
+                    error!("Failed to execute order #{}: {}", order.id, e);
+
+                    // The slice was traded but the order is still active, it must not
+                    // execute again before the bot restarts
+                    if let Some(BotError::FillNotRecorded { .. }) = e.downcast_ref::<BotError>() {
+                        in_flight.hold();
+                    }
+                }
             }
-        } else {
-            debug!("No tokens to process");
         }
 
+        // 5. Update watchlist items with this token
+        for (telegram_id, watchlist) in watchers.watchlist_by_user {
+            for _item in watchlist
+                .iter()
+                .filter(|w| w.token_address == token_address)
+            {
+                if let Err(e) =
+                    db::update_watchlist_price(&db_pool, *telegram_id, token_address, price_in_sol)
+                        .await
+                {
+                    error!(
+                        "Failed to update watchlist price for user {}, token {}: {}",
+                        telegram_id, token_symbol, e
+                    );
+                }
+            }
+        }
+
+        // 6. Check price alerts for this token
+        for alert in watchers
+            .active_alerts
+            .iter()
+            .filter(|a| a.token_address == token_address)
+        {
+            if let Err(e) = Self::check_price_alert(services, bot, alert, price_in_sol).await {
+                error!("Failed to check price alert #{}: {}", alert.id, e);
+            }
+        }
+    }
+
+    // Whether the order's trigger is met, market cap and FDV orders wait while the supply is unknown
+    async fn is_triggered(
+        services: &Arc<ServiceContainer>,
+        order: &LimitOrder,
+        price_in_sol: f64,
+        price_in_usdc: f64,
+    ) -> bool {
+        let order_type = match OrderType::from_str(&order.order_type) {
+            Ok(order_type) => order_type,
+            Err(_) => return false,
+        };
+        let trigger_metric = order.trigger_metric();
+
+        match order.trigger_value {
+            Some(target) if trigger_metric.needs_supply() => {
+                match services.token_supply().supply(&order.token_address).await {
+                    Ok(supply) => {
+                        let value = market_cap(price_in_usdc, supply);
+                        debug!(
+                            "{} of {} for order #{}: ${:.0} (target ${:.0})",
+                            trigger_metric.display_name(),
+                            order.token_symbol,
+                            order.id,
+                            value,
+                            target
+                        );
+                        order.is_valuation_triggered(&order_type, price_in_usdc, supply)
+                    }
+                    Err(e) => {
+                        warn!("Skipping {} order #{}: {}", trigger_metric, order.id, e);
+                        false
+                    }
+                }
+            }
+            // USDC orders follow the token's USD price, whatever SOL does meanwhile
+            _ => order.is_price_triggered(&order_type, price_in_sol, price_in_usdc),
+        }
+    }
+
+    // Notify the user when the price crosses the alert target
+    async fn check_price_alert(
+        services: &Arc<ServiceContainer>,
+        bot: &Bot,
+        alert: &PriceAlert,
+        price_in_sol: f64,
+    ) -> Result<()> {
+        let db_pool = services.db_pool();
+
+        let triggered = alert.is_crossed(price_in_sol);
+        let is_active = !(triggered && alert.one_shot);
+
+        db::update_price_alert_check(&db_pool, alert.id, price_in_sol, triggered, is_active)
+            .await?;
+
+        if !triggered {
+            return Ok(());
+        }
+
+        info!(
+            "Price alert #{} triggered: {} at {} SOL",
+            alert.id, alert.token_symbol, price_in_sol
+        );
+
+        let user = db::get_user_by_id(&db_pool, alert.user_id).await?;
+        if !should_notify(&user, NotificationKind::PriceAlerts) {
+            return Ok(());
+        }
+
+        let direction = match alert.direction() {
+            Some(AlertDirection::Below) => "below",
+            _ => "above",
+        };
+
+        let footer = if alert.one_shot {
+            "This alert has been disabled."
+        } else {
+            "You will be notified again the next time the price crosses the target."
+        };
+
+        bot.send_message(
+            ChatId(user.telegram_id),
+            format!(
+                "🔔 <b>Price Alert</b>\n\n\
+                 {} is now {} {} SOL\n\
+                 • Current price: {} SOL\n\n\
+                 {}",
+                html::escape(&alert.token_symbol),
+                direction,
+                format_price(alert.target_price_in_sol),
+                format_price(price_in_sol),
+                footer
+            ),
+        )
+        .parse_mode(ParseMode::Html)
+        .await?;
+
         Ok(())
     }
 
+    // Mark orders past their expiry as expired and notify their owners
+    async fn expire_limit_orders(services: &Arc<ServiceContainer>, bot: &Bot) {
+        let db_pool = services.db_pool();
+
+        let expired_orders = match db::expire_limit_orders(&db_pool).await {
+            Ok(orders) => orders,
+            Err(e) => {
+                error!("Failed to expire limit orders: {}", e);
+                return;
+            }
+        };
+
+        for order in expired_orders {
+            info!("Limit order #{} expired", order.id);
+
+            let user = match db::get_user_by_id(&db_pool, order.user_id).await {
+                Ok(user) => user,
+                Err(e) => {
+                    error!("Failed to get owner of expired order #{}: {}", order.id, e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = bot
+                .send_message(
+                    ChatId(user.telegram_id),
+                    format!(
+                        "⌛ <b>Limit Order Expired</b>\n\n\
+                         Your limit {} order #{} has expired without being fully filled:\n\
+                         • {}{}",
+                        order.order_type,
+                        order.id,
+                        Self::order_summary(&order),
+                        Self::progress_suffix(&order),
+                    ),
+                )
+                .parse_mode(ParseMode::Html)
+                .await
+            {
+                error!(
+                    "Failed to notify user about expired order #{}: {}",
+                    order.id, e
+                );
+            }
+        }
+    }
+
     // Execute a single limit order
     async fn execute_order(
         services: &Arc<ServiceContainer>,
         bot: &Bot,
         order: &LimitOrder,
         current_price: f64,
+        filled_tokens: &mut FilledTokens,
     ) -> Result<()> {
         let db_pool = services.db_pool();
 
         // Get user's telegram ID
         let user = db::get_user_by_id(&db_pool, order.user_id).await?;
         let telegram_id = user.telegram_id;
+        let explorer = user.get_explorer();
 
-        let order_type = match order.order_type.as_str() {
-            "BUY" => OrderType::Buy,
-            "SELL" => OrderType::Sell,
-            _ => return Err(anyhow!("Unknown order type: {}", order.order_type)),
-        };
-
-        // Create trade interactor
-        let solana_client = services.solana_client();
-        let price_service = services.price_service();
-        let token_repository = services.token_repository();
-        let swap_service = services.swap_service();
+        // Large orders are executed in slices to keep the price impact of each trade low
+        let slice = OrderSlice::next(order, current_price, *MAX_SLICE_SOL);
 
-        let interactor = Arc::new(TradeInteractorImpl::new(
+        // A successful trade records the fill in the same transaction as the trade
+        let interactor = TradeInteractorImpl::new(
             db_pool.clone(),
-            solana_client.clone(),
-            price_service.clone(),
-            token_repository.clone(),
-            swap_service.clone(),
-        ));
+            services.solana_client(),
+            services.price_service(),
+            services.token_repository(),
+            services.swap_service(),
+        )
+        .with_limit_order_slice(order.clone(), slice.amount);
+        let retries = RetryPolicy {
+            max_retries: *MAX_RETRIES,
+            backoff: &RETRY_BACKOFF,
+        };
 
-        // Execute the trade
-        let result = interactor
-            .execute_trade(
-                telegram_id,
-                &OrderType::from_str(&order.order_type).unwrap(),
-                &order.token_address,
-                &order.token_symbol,
-                order.amount,
-                current_price, // Use current market price
-            )
-            .await?;
+        let outcome = trade_slice(
+            &interactor,
+            telegram_id,
+            order,
+            &slice,
+            current_price,
+            user.get_slippage(),
+            &retries,
+        )
+        .await?;
 
         // Update order status based on trade result
-        if result.success {
-            // Mark order as filled
-            db::update_limit_order_status(
-                &db_pool,
-                order.id,
-                &LimitOrderStatus::Filled,
-                result.signature.as_deref(),
-            )
-            .await?;
+        match outcome {
+            SliceOutcome::Traded {
+                fill,
+                traded_amount,
+                signature,
+            } => {
+                filled_tokens
+                    .entry(telegram_id)
+                    .or_default()
+                    .insert(order.token_address.clone(), order.token_symbol.clone());
 
-            // Notify user about successful execution
-            bot.send_message(
-                ChatId(telegram_id),
-                format!(
-                    "✅ <b>Limit Order Executed</b>\n\n\
-                     Your limit {} order #{} has been filled:\n\
-                     • {:.6} SOL ({:.6} {} tokens) at {:.6} SOL\n\
-                     • Market price: {:.6} SOL\n\
-                     • Transaction: <a href=\"https://explorer.solana.com/tx/{}\">View on Explorer</a>",
-                    order.order_type,
+                let filled = LimitOrder {
+                    filled_amount: fill.filled_amount,
+                    ..order.clone()
+                };
+
+                if !should_notify(&user, NotificationKind::OrderFills) {
+                    return Ok(());
+                }
+
+                if fill.status == LimitOrderStatus::Filled {
+                    // Notify user about successful execution
+                    bot.send_message(
+                        ChatId(telegram_id),
+                        format!(
+                            "✅ <b>Limit Order Executed</b>\n\n\
+                         Your limit {} order #{} has been filled:\n\
+                         • {}\n\
+                         • Market price: {} SOL\n\
+                         • Transaction: <a href=\"{}\">View on {}</a>",
+                            order.order_type,
+                            order.id,
+                            Self::order_summary(order),
+                            format_price(current_price),
+                            format_tx_url(&signature, explorer, Cluster::current()),
+                            explorer,
+                        ),
+                    )
+                    .parse_mode(ParseMode::Html)
+                    .await?;
+                } else {
+                    // The order stays active, the rest is executed on the next checks
+                    bot.send_message(
+                        ChatId(telegram_id),
+                        format!(
+                            "🧩 <b>Limit Order Partially Filled</b>\n\n\
+                         Your limit {} order #{} is {}:\n\
+                         • Executed: {:.6} {} tokens at {} SOL\n\
+                         • Remaining: {}\n\
+                         • Transaction: <a href=\"{}\">View on {}</a>\n\n\
+                         The rest will be executed while the price stays within the limit.",
+                            order.order_type,
+                            order.id,
+                            filled.fill_progress(),
+                            traded_amount,
+                            html::escape(&order.token_symbol),
+                            format_price(current_price),
+                            Self::remaining_text(&filled),
+                            format_tx_url(&signature, explorer, Cluster::current()),
+                            explorer,
+                        ),
+                    )
+                    .parse_mode(ParseMode::Html)
+                    .await?;
+                }
+            }
+            SliceOutcome::Rejected { reason } => {
+                Self::fail_order(services, bot, telegram_id, order, &reason).await?;
+            }
+            SliceOutcome::Retry {
+                retry_count,
+                retry_slippage,
+                delay,
+                failure,
+                error_message,
+            } => {
+                let next_retry_at = Utc::now() + delay;
+                let slippage_note = match (failure, retry_slippage) {
+                    (TradeFailure::Slippage, Some(slippage)) => {
+                        format!("\n• Next attempt slippage: {:.1}%", slippage)
+                    }
+                    _ => String::new(),
+                };
+
+                db::update_limit_order_retry_count(
+                    &db_pool,
                     order.id,
-                    order.total_sol,
-                    order.amount,
-                    order.token_symbol,
-                    order.price_in_sol,
-                    current_price,
-                    result.signature.unwrap_or_else(|| "unknown".to_string()),
-                ),
-            )
-                .parse_mode(ParseMode::Html)
+                    retry_count,
+                    retry_slippage,
+                    next_retry_at,
+                )
                 .await?;
-        } else {
-            // Check retry count and potentially retry
-            if order.retry_count < 2 {
-                // Allow up to 3 attempts total (initial + 2 retries)
-                // Increment retry count
-                let new_retry_count = order.retry_count + 1;
-
-                db::update_limit_order_retry_count(&db_pool, order.id, new_retry_count).await?;
 
                 // Notify user about retry
-                bot.send_message(
+                if should_notify(&user, NotificationKind::OrderRetries) {
+                    bot.send_message(
                     ChatId(telegram_id),
                     format!(
                         "⚠️ <b>Limit Order Retry</b>\n\n\
                          Your limit {} order #{} execution failed but will be retried automatically:\n\
-                         • {:.6} SOL ({:.6} {} tokens) at {:.6} SOL\n\
-                         • Market price: {:.6} SOL\n\
-                         • Retry attempt: {} of 3\n\
-                         • Error: {}",
+                         • {}{}\n\
+                         • Market price: {} SOL\n\
+                         • Retry attempt: {} of {}, in {} ({} UTC)\n\
+                         • Error: {}{}",
                         order.order_type,
                         order.id,
-                        order.total_sol,
-                        order.amount,
-                        order.token_symbol,
-                        order.price_in_sol,
-                        current_price,
-                        new_retry_count,
-                        result.error_message.unwrap_or_else(|| "Unknown error".to_string()),
+                        Self::order_summary(order),
+                        Self::progress_suffix(order),
+                        format_price(current_price),
+                        retry_count,
+                        *MAX_RETRIES,
+                        format_delay(delay),
+                        next_retry_at.format("%H:%M"),
+                        html::escape(&error_message),
+                        slippage_note,
                     ),
                 )
                     .parse_mode(ParseMode::Html)
                     .await?;
+                }
 
-                // Note: We don't mark it as failed, so it will be tried again next cycle
-            } else {
+                // Note: We don't mark it as failed, it is tried again once the backoff is over
+            }
+            SliceOutcome::RetriesExhausted { error_message } => {
                 // We've exceeded retry attempts, mark as failed
                 db::update_limit_order_status(&db_pool, order.id, &LimitOrderStatus::Failed, None)
                     .await?;
@@ -352,19 +790,18 @@ impl LimitOrderService {
                     ChatId(telegram_id),
                     format!(
                         "❌ <b>Limit Order Failed</b>\n\n\
-                         Your limit {} order #{} could not be executed after 3 attempts:\n\
-                         • {:.6} SOL ({:.6} {} tokens) at {:.6} SOL\n\
-                         • Market price: {:.6} SOL\n\
+                         Your limit {} order #{} could not be executed after {}:\n\
+                         • {}{}\n\
+                         • Market price: {} SOL\n\
                          • Error: {}\n\n\
                          The order has been marked as failed. Please check your wallet and try again.",
                         order.order_type,
                         order.id,
-                        order.total_sol,
-                        order.amount,
-                        order.token_symbol,
-                        order.price_in_sol,
-                        current_price,
-                        result.error_message.unwrap_or_else(|| "Unknown error".to_string()),
+                        format_attempts(*MAX_RETRIES + 1),
+                        Self::order_summary(order),
+                        Self::progress_suffix(order),
+                        format_price(current_price),
+                        html::escape(&error_message),
                     ),
                 )
                     .parse_mode(ParseMode::Html)
@@ -374,4 +811,717 @@ impl LimitOrderService {
 
         Ok(())
     }
+
+    // Mark the order as failed without retrying and tell the user why
+    async fn fail_order(
+        services: &Arc<ServiceContainer>,
+        bot: &Bot,
+        telegram_id: i64,
+        order: &LimitOrder,
+        reason: &str,
+    ) -> Result<()> {
+        db::fail_limit_order(&services.db_pool(), order.id, reason).await?;
+
+        bot.send_message(
+            ChatId(telegram_id),
+            format!(
+                "❌ <b>Limit Order Failed</b>\n\n\
+                 Your limit {} order #{} was not executed:\n\
+                 • {}{}\n\
+                 • Reason: {}",
+                order.order_type,
+                order.id,
+                Self::order_summary(order),
+                Self::progress_suffix(order),
+                html::escape(reason),
+            ),
+        )
+        .parse_mode(ParseMode::Html)
+        .await?;
+
+        Ok(())
+    }
+
+    // "0.5 SOL (1000 BONK tokens) at 0.0005 SOL"
+    fn order_summary(order: &LimitOrder) -> String {
+        format!(
+            "{:.6} SOL ({:.6} {} tokens) at {} SOL",
+            order.total_sol,
+            order.amount,
+            html::escape(&order.token_symbol),
+            format_price(order.price_in_sol)
+        )
+    }
+
+    // ", 3.2/10 filled" for orders that were partially executed
+    fn progress_suffix(order: &LimitOrder) -> String {
+        if order.is_partially_filled() {
+            format!(", {}", order.fill_progress())
+        } else {
+            String::new()
+        }
+    }
+
+    // What is left of a partially filled order, SOL to spend for buys and tokens for sells
+    fn remaining_text(order: &LimitOrder) -> String {
+        let remaining = order.remaining_amount();
+
+        if order.is_buy() {
+            format!("{:.6} SOL to spend", order.slice_total_sol(remaining))
+        } else {
+            format!(
+                "{:.6} {} tokens",
+                remaining,
+                html::escape(&order.token_symbol)
+            )
+        }
+    }
+}
+
+// The order as stored now, None when it was cancelled, filled or its price or amount was
+// edited since `loaded` was read. An edited order executes on the next run at its new values
+fn unchanged_since_load(loaded: &LimitOrder, current: Option<LimitOrder>) -> Option<LimitOrder> {
+    current.filter(|current| {
+        current.status == LimitOrderStatus::Active.to_string()
+            && current.price_in_sol == loaded.price_in_sol
+            && current.amount == loaded.amount
+    })
+}
+
+// Trades the slices of limit orders, the trade interactor outside of tests
+#[async_trait]
+trait SliceTrader: Send + Sync {
+    async fn check_limits(
+        &self,
+        telegram_id: i64,
+        trade_type: &OrderType,
+        total_sol: f64,
+    ) -> Result<()>;
+    async fn trade(&self, telegram_id: i64, trade: TradeRequest<'_>) -> Result<TradeResult>;
+}
+
+#[async_trait]
+impl<T: TradeInteractor> SliceTrader for T {
+    async fn check_limits(
+        &self,
+        telegram_id: i64,
+        trade_type: &OrderType,
+        total_sol: f64,
+    ) -> Result<()> {
+        self.check_trade_limits(telegram_id, trade_type, total_sol)
+            .await
+    }
+
+    async fn trade(&self, telegram_id: i64, trade: TradeRequest<'_>) -> Result<TradeResult> {
+        self.execute_trade(telegram_id, trade).await
+    }
+}
+
+// The part of an order executed in one run
+struct OrderSlice {
+    // Token amount of the order the slice fills
+    amount: f64,
+    // SOL volume of the slice at the limit price
+    total_sol: f64,
+    // A buy spends the slice's SOL at the market price, a sell sells the slice's tokens
+    trade_amount: f64,
+}
+
+impl OrderSlice {
+    // Next slice of the order, at most `max_slice_sol` worth
+    fn next(order: &LimitOrder, current_price: f64, max_slice_sol: f64) -> Self {
+        let amount = order.next_slice(max_slice_sol, order.slice_price(current_price));
+
+        Self {
+            amount,
+            total_sol: order.slice_total_sol(amount),
+            trade_amount: order.tokens_to_trade(amount, current_price),
+        }
+    }
+}
+
+// How often and how late failed orders are tried again
+struct RetryPolicy<'a> {
+    max_retries: i32,
+    backoff: &'a [chrono::Duration],
+}
+
+// What an execution attempt does to the order
+enum SliceOutcome {
+    // The slice was traded, `fill` is recorded with the trade. A sell capped to the on-chain
+    // balance traded less than the slice.
+    Traded {
+        fill: LimitOrderFill,
+        traded_amount: f64,
+        signature: String,
+    },
+    // Retrying won't help, e.g. over the spending limits or without enough funds
+    Rejected {
+        reason: String,
+    },
+    // Tried again with `retry_slippage` once `delay` is over
+    Retry {
+        retry_count: i32,
+        retry_slippage: Option<f64>,
+        delay: chrono::Duration,
+        failure: TradeFailure,
+        error_message: String,
+    },
+    // The last retry failed too
+    RetriesExhausted {
+        error_message: String,
+    },
+}
+
+// Trade the slice of the order, the unfilled remainder is kept when the trade fails
+async fn trade_slice(
+    trader: &dyn SliceTrader,
+    telegram_id: i64,
+    order: &LimitOrder,
+    slice: &OrderSlice,
+    current_price: f64,
+    user_slippage: f64,
+    retries: &RetryPolicy<'_>,
+) -> Result<SliceOutcome> {
+    let order_type = OrderType::from_str(&order.order_type)
+        .map_err(|_| anyhow!("Unknown order type: {}", order.order_type))?;
+
+    // Orders above the spending limits fail right away, retrying won't help
+    if let Err(e) = trader
+        .check_limits(telegram_id, &order_type, slice.total_sol)
+        .await
+    {
+        return Ok(SliceOutcome::Rejected {
+            reason: e.to_string(),
+        });
+    }
+
+    // After a slippage failure the escalated slippage is used instead of the user's
+    let result = trader
+        .trade(
+            telegram_id,
+            TradeRequest {
+                trade_type: &order_type,
+                token_address: &order.token_address,
+                token_symbol: &order.token_symbol,
+                amount: slice.trade_amount,
+                price_in_sol: current_price, // Use current market price
+                slippage: order.retry_slippage,
+            },
+        )
+        .await?;
+
+    if result.success {
+        // Same fill as the settlement records
+        let shortfall = result.shortfall();
+        return Ok(SliceOutcome::Traded {
+            fill: order.fill_after(slice.amount - shortfall),
+            traded_amount: slice.trade_amount - shortfall,
+            signature: result.signature.unwrap_or_else(|| "unknown".to_string()),
+        });
+    }
+
+    let error_message = result
+        .error_message
+        .unwrap_or_else(|| "Unknown error".to_string());
+    let failure = TradeFailure::classify(&error_message);
+
+    if !failure.is_retriable() {
+        // Retrying won't help, e.g. the wallet doesn't have enough funds
+        return Ok(SliceOutcome::Rejected {
+            reason: error_message,
+        });
+    }
+    if order.retry_count >= retries.max_retries {
+        return Ok(SliceOutcome::RetriesExhausted { error_message });
+    }
+
+    // Increment retry count, the next attempt waits for the backoff
+    let retry_count = order.retry_count + 1;
+
+    // The price moved too fast, the next attempt tolerates more slippage
+    let retry_slippage = match failure {
+        TradeFailure::Slippage => Some(escalate_slippage(
+            order.retry_slippage.unwrap_or(user_slippage),
+        )),
+        _ => order.retry_slippage,
+    };
+
+    Ok(SliceOutcome::Retry {
+        retry_count,
+        retry_slippage,
+        delay: retry_delay(retry_count, retries.backoff),
+        failure,
+        error_message,
+    })
+}
+
+// New SOL balance and the balances of the filled tokens, each token with its own decimals
+// and sorted by symbol
+fn balance_update_message(
+    balances: &WalletBalances,
+    tokens: &HashMap<String, String>,
+    locale: Locale,
+) -> String {
+    let mut message = format!(
+        "💰 <b>Balance after fill</b>\n\n• SOL: {}",
+        format_amount(balances.sol_balance, locale)
+    );
+    let mut tokens: Vec<(&String, &String)> = tokens.iter().collect();
+    tokens.sort_by_key(|(_, token_symbol)| *token_symbol);
+
+    for (token_address, token_symbol) in tokens {
+        // A token that was sold completely has no balance left
+        let amount = balances
+            .token_balances
+            .iter()
+            .find(|balance| &balance.mint_address == token_address)
+            .map_or_else(|| "0".to_string(), |balance| balance.display_amount());
+        message.push_str(&format!("\n• {}: {}", html::escape(token_symbol), amount));
+    }
+
+    message
+}
+
+// Slippage for the attempt after a slippage failure, in percent
+fn escalate_slippage(slippage: f64) -> f64 {
+    (slippage + SLIPPAGE_STEP).min(MAX_RETRY_SLIPPAGE)
+}
+
+// Wait before the given retry (1 for the first), retries past the schedule use its last step
+fn retry_delay(retry: i32, backoff: &[chrono::Duration]) -> chrono::Duration {
+    let index = (retry.max(1) - 1) as usize;
+    backoff
+        .get(index)
+        .or_else(|| backoff.last())
+        .copied()
+        .unwrap_or_else(chrono::Duration::zero)
+}
+
+// "45s", "5m", "2h" or "1d", rounded down to the largest whole unit
+fn format_delay(delay: chrono::Duration) -> String {
+    if delay.num_days() > 0 {
+        format!("{}d", delay.num_days())
+    } else if delay.num_hours() > 0 {
+        format!("{}h", delay.num_hours())
+    } else if delay.num_minutes() > 0 {
+        format!("{}m", delay.num_minutes())
+    } else {
+        format!("{}s", delay.num_seconds())
+    }
+}
+
+// "1 attempt", "3 attempts"
+fn format_attempts(attempts: i32) -> String {
+    if attempts == 1 {
+        "1 attempt".to_string()
+    } else {
+        format!("{} attempts", attempts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::TokenBalance;
+    use crate::services::IdempotencyGuard;
+    use std::sync::Mutex;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+    const SOLD: &str = "Sold111111111111111111111111111111111111111";
+    const TELEGRAM_ID: i64 = 1001;
+
+    // Swaps that fail with the queued errors first, then succeed. Remembers the traded amounts
+    // and caps trades to `balance` like sells are capped to the on-chain balance
+    #[derive(Default)]
+    struct FakeSwaps {
+        errors: Mutex<Vec<&'static str>>,
+        trades: Mutex<Vec<f64>>,
+        balance: Option<f64>,
+    }
+
+    impl FakeSwaps {
+        fn failing(errors: &[&'static str]) -> Self {
+            Self {
+                errors: Mutex::new(errors.iter().rev().copied().collect()),
+                ..Self::default()
+            }
+        }
+
+        fn holding(balance: f64) -> Self {
+            Self {
+                balance: Some(balance),
+                ..Self::default()
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SliceTrader for FakeSwaps {
+        async fn check_limits(
+            &self,
+            _telegram_id: i64,
+            _trade_type: &OrderType,
+            _total_sol: f64,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn trade(&self, _telegram_id: i64, trade: TradeRequest<'_>) -> Result<TradeResult> {
+            self.trades.lock().unwrap().push(trade.amount);
+            let error_message = self.errors.lock().unwrap().pop().map(str::to_string);
+            let amount = self
+                .balance
+                .map_or(trade.amount, |balance| trade.amount.min(balance));
+
+            Ok(TradeResult {
+                token_address: trade.token_address.to_string(),
+                token_symbol: trade.token_symbol.to_string(),
+                amount,
+                price_in_sol: trade.price_in_sol,
+                total_sol: amount * trade.price_in_sol,
+                signature: error_message.is_none().then(|| "sig".to_string()),
+                success: error_message.is_none(),
+                error_message,
+                error: None,
+                requested_amount: (amount < trade.amount).then_some(trade.amount),
+            })
+        }
+    }
+
+    fn order(order_type: OrderType, amount: f64, total_sol: f64) -> LimitOrder {
+        LimitOrder {
+            id: 7,
+            user_id: 1,
+            token_address: BONK.to_string(),
+            token_symbol: "BONK".to_string(),
+            order_type: order_type.to_string(),
+            price_in_sol: total_sol / amount,
+            amount,
+            total_sol,
+            current_price_in_sol: None,
+            tx_signature: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            status: LimitOrderStatus::Active.to_string(),
+            retry_count: 0,
+            expires_at: None,
+            failure_reason: None,
+            filled_amount: 0.0,
+            retry_slippage: None,
+            trigger_metric: "PRICE".to_string(),
+            trigger_value: None,
+            price_currency: "SOL".to_string(),
+            next_retry_at: None,
+        }
+    }
+
+    fn default_backoff() -> Vec<chrono::Duration> {
+        DEFAULT_RETRY_BACKOFF_MINUTES
+            .iter()
+            .map(|minutes| chrono::Duration::minutes(*minutes))
+            .collect()
+    }
+
+    // One price check of the order at `price`, the outcome is applied like the database does
+    async fn tick(swaps: &FakeSwaps, order: &mut LimitOrder, price: f64) -> SliceOutcome {
+        let backoff = default_backoff();
+        let retries = RetryPolicy {
+            max_retries: DEFAULT_MAX_RETRIES,
+            backoff: &backoff,
+        };
+        let slice = OrderSlice::next(order, price, DEFAULT_MAX_SLICE_SOL);
+
+        let outcome = trade_slice(swaps, TELEGRAM_ID, order, &slice, price, 1.0, &retries)
+            .await
+            .unwrap();
+
+        match &outcome {
+            SliceOutcome::Traded { fill, .. } => {
+                order.filled_amount = fill.filled_amount;
+                order.status = fill.status.to_string();
+            }
+            SliceOutcome::Retry {
+                retry_count,
+                retry_slippage,
+                ..
+            } => {
+                order.retry_count = *retry_count;
+                order.retry_slippage = *retry_slippage;
+            }
+            SliceOutcome::Rejected { .. } | SliceOutcome::RetriesExhausted { .. } => {
+                order.status = LimitOrderStatus::Failed.to_string();
+            }
+        }
+
+        outcome
+    }
+
+    #[tokio::test]
+    async fn large_buy_fills_one_slice_per_tick_and_completes_on_the_last() {
+        let swaps = FakeSwaps::default();
+        // 20 SOL at 2 SOL per token, 5 SOL slices
+        let mut order = order(OrderType::Buy, 10.0, 20.0);
+
+        for expected_filled in [2.5, 5.0, 7.5] {
+            tick(&swaps, &mut order, 2.0).await;
+
+            assert_eq!(order.filled_amount, expected_filled);
+            assert_eq!(order.status, LimitOrderStatus::Active.to_string());
+        }
+        tick(&swaps, &mut order, 2.0).await;
+
+        assert_eq!(order.filled_amount, 10.0);
+        assert_eq!(order.status, LimitOrderStatus::Filled.to_string());
+        assert_eq!(*swaps.trades.lock().unwrap(), vec![2.5; 4]);
+    }
+
+    #[tokio::test]
+    async fn large_sell_sells_one_slice_of_tokens_per_tick() {
+        let swaps = FakeSwaps::default();
+        // 30 tokens worth 15 SOL, 5 SOL slices
+        let mut order = order(OrderType::Sell, 30.0, 15.0);
+
+        for expected_filled in [10.0, 20.0, 30.0] {
+            tick(&swaps, &mut order, 0.5).await;
+
+            assert_eq!(order.filled_amount, expected_filled);
+        }
+
+        assert_eq!(order.status, LimitOrderStatus::Filled.to_string());
+        assert_eq!(*swaps.trades.lock().unwrap(), vec![10.0; 3]);
+    }
+
+    #[tokio::test]
+    async fn capped_sell_fills_only_what_was_sold() {
+        // The whole order fits in one slice, but the wallet only holds 6 of the 10 tokens
+        let swaps = FakeSwaps::holding(6.0);
+        let mut order = order(OrderType::Sell, 10.0, 5.0);
+
+        let outcome = tick(&swaps, &mut order, 0.5).await;
+
+        assert!(matches!(
+            outcome,
+            SliceOutcome::Traded { traded_amount, .. } if traded_amount == 6.0
+        ));
+        assert_eq!(order.filled_amount, 6.0);
+        assert_eq!(order.status, LimitOrderStatus::Active.to_string());
+    }
+
+    #[tokio::test]
+    async fn failed_slice_keeps_the_remainder_and_bumps_the_retry() {
+        let mut order = order(OrderType::Buy, 10.0, 20.0);
+        tick(&FakeSwaps::default(), &mut order, 2.0).await;
+        let swaps = FakeSwaps::failing(&["Transaction expired: block height exceeded"]);
+
+        let outcome = tick(&swaps, &mut order, 2.0).await;
+
+        assert!(matches!(
+            outcome,
+            SliceOutcome::Retry { retry_count: 1, .. }
+        ));
+        assert_eq!(order.filled_amount, 2.5);
+        assert_eq!(order.status, LimitOrderStatus::Active.to_string());
+
+        tick(&swaps, &mut order, 2.0).await;
+
+        assert_eq!(order.filled_amount, 5.0);
+    }
+
+    #[test]
+    fn balance_update_shows_each_token_with_its_decimals() {
+        let balances = WalletBalances {
+            address: "wallet".to_string(),
+            sol_balance: 1.23456789,
+            token_balances: vec![TokenBalance {
+                symbol: "BONK".to_string(),
+                amount: 1234.56789,
+                mint_address: BONK.to_string(),
+                decimals: 5,
+            }],
+            usd_values: Vec::new(),
+            updated_at: Utc::now(),
+            low_balance_sol: 0.0,
+        };
+        let tokens = HashMap::from([
+            (BONK.to_string(), "BONK".to_string()),
+            (SOLD.to_string(), "SOLD".to_string()),
+        ]);
+
+        let message = balance_update_message(&balances, &tokens, Locale::En);
+
+        assert!(message.contains("• SOL: 1.2346"));
+        assert!(message.contains("• BONK: 1234.56789"));
+        assert!(message.contains("• SOLD: 0"));
+    }
+
+    #[test]
+    fn balance_update_lists_tokens_by_symbol_with_their_own_decimals() {
+        let balance = |symbol: &str, amount: f64, mint_address: &str, decimals: u8| TokenBalance {
+            symbol: symbol.to_string(),
+            amount,
+            mint_address: mint_address.to_string(),
+            decimals,
+        };
+        let balances = WalletBalances {
+            address: "wallet".to_string(),
+            sol_balance: 2.0,
+            token_balances: vec![
+                balance("WIF", 0.123456789, SOLD, 9),
+                balance("USDC", 12.3456789, BONK, 6),
+            ],
+            usd_values: Vec::new(),
+            updated_at: Utc::now(),
+            low_balance_sol: 0.0,
+        };
+        let tokens = HashMap::from([
+            (SOLD.to_string(), "WIF".to_string()),
+            (BONK.to_string(), "USDC".to_string()),
+        ]);
+
+        let message = balance_update_message(&balances, &tokens, Locale::En);
+
+        assert!(message.ends_with("\n• USDC: 12.345679\n• WIF: 0.123456789"));
+    }
+
+    #[test]
+    fn balance_update_escapes_token_symbols() {
+        let balances = WalletBalances {
+            address: "wallet".to_string(),
+            sol_balance: 2.0,
+            token_balances: Vec::new(),
+            usd_values: Vec::new(),
+            updated_at: Utc::now(),
+            low_balance_sol: 0.0,
+        };
+        let tokens = HashMap::from([(SOLD.to_string(), "<b>A&B</b>".to_string())]);
+
+        let message = balance_update_message(&balances, &tokens, Locale::En);
+
+        assert!(message.ends_with("\n• &lt;b&gt;A&amp;B&lt;/b&gt;: 0"));
+    }
+
+    // The order after `db::update_limit_order_params` saved an edit
+    #[test]
+    fn tiny_order_prices_keep_their_significant_digits() {
+        let mut order = order(OrderType::Buy, 1_000_000.0, 0.5);
+        order.price_in_sol = 0.0000000123;
+
+        assert_eq!(
+            LimitOrderService::order_summary(&order),
+            "0.500000 SOL (1000000.000000 BONK tokens) at 0.0₇123 SOL"
+        );
+    }
+
+    fn edited(order: &LimitOrder, price_in_sol: f64, total_sol: f64) -> LimitOrder {
+        LimitOrder {
+            price_in_sol,
+            amount: total_sol / price_in_sol,
+            total_sol,
+            retry_count: 0,
+            retry_slippage: None,
+            next_retry_at: None,
+            ..order.clone()
+        }
+    }
+
+    #[test]
+    fn edit_saved_before_the_run_is_executed_at_the_new_values() {
+        let loaded = edited(&order(OrderType::Buy, 10.0, 20.0), 1.5, 30.0);
+
+        let current = unchanged_since_load(&loaded, Some(loaded.clone())).unwrap();
+
+        assert_eq!(current.price_in_sol, 1.5);
+        assert_eq!(current.amount, 20.0);
+        assert_eq!(OrderSlice::next(&current, 1.5, 0.0).total_sol, 30.0);
+    }
+
+    #[test]
+    fn order_edited_or_cancelled_mid_execution_is_skipped() {
+        let loaded = order(OrderType::Buy, 10.0, 20.0);
+        let cancelled = LimitOrder {
+            status: LimitOrderStatus::Cancelled.to_string(),
+            ..loaded.clone()
+        };
+
+        assert!(unchanged_since_load(&loaded, Some(edited(&loaded, 1.5, 20.0))).is_none());
+        assert!(unchanged_since_load(&loaded, Some(edited(&loaded, 2.0, 30.0))).is_none());
+        assert!(unchanged_since_load(&loaded, Some(cancelled)).is_none());
+        assert!(unchanged_since_load(&loaded, None).is_none());
+    }
+
+    #[test]
+    fn edit_is_rejected_while_the_order_executes() {
+        let guard = Arc::new(IdempotencyGuard::new(IdempotencyGuard::DEFAULT_WINDOW));
+        let executing = guard.begin(LimitOrder::execution_key(7));
+
+        assert!(executing.is_some());
+        assert!(guard.begin(LimitOrder::execution_key(7)).is_none());
+        assert!(guard.begin(LimitOrder::execution_key(8)).is_some());
+
+        drop(executing);
+        assert!(guard.begin(LimitOrder::execution_key(7)).is_some());
+    }
+
+    #[test]
+    fn retries_back_off_along_the_schedule_then_repeat_its_last_step() {
+        let backoff = default_backoff();
+
+        assert_eq!(retry_delay(1, &backoff), chrono::Duration::minutes(1));
+        assert_eq!(retry_delay(2, &backoff), chrono::Duration::minutes(5));
+        assert_eq!(retry_delay(3, &backoff), chrono::Duration::minutes(15));
+        assert_eq!(retry_delay(4, &backoff), chrono::Duration::minutes(15));
+        assert_eq!(retry_delay(0, &backoff), chrono::Duration::minutes(1));
+        assert_eq!(retry_delay(1, &[]), chrono::Duration::zero());
+    }
+
+    #[tokio::test]
+    async fn order_fails_once_its_retries_are_used_up() {
+        let error = "RPC error: Blockhash not found";
+        let swaps = FakeSwaps::failing(&[error; DEFAULT_MAX_RETRIES as usize + 1]);
+        let mut order = order(OrderType::Buy, 10.0, 20.0);
+
+        for retry in 1..=DEFAULT_MAX_RETRIES {
+            let outcome = tick(&swaps, &mut order, 2.0).await;
+
+            let SliceOutcome::Retry {
+                retry_count, delay, ..
+            } = outcome
+            else {
+                panic!("retry {} expected", retry);
+            };
+            assert_eq!(retry_count, retry);
+            assert_eq!(delay, retry_delay(retry, &default_backoff()));
+        }
+        let outcome = tick(&swaps, &mut order, 2.0).await;
+
+        assert!(matches!(
+            outcome,
+            SliceOutcome::RetriesExhausted { error_message } if error_message == error
+        ));
+        assert_eq!(order.status, LimitOrderStatus::Failed.to_string());
+        assert_eq!(order.filled_amount, 0.0);
+        assert_eq!(
+            swaps.trades.lock().unwrap().len(),
+            DEFAULT_MAX_RETRIES as usize + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn insufficient_funds_fail_the_order_without_a_retry() {
+        let swaps = FakeSwaps::failing(&["Insufficient funds for the swap"]);
+        let mut order = order(OrderType::Buy, 10.0, 20.0);
+
+        let outcome = tick(&swaps, &mut order, 2.0).await;
+
+        assert!(matches!(outcome, SliceOutcome::Rejected { .. }));
+        assert_eq!(order.retry_count, 0);
+    }
+
+    #[test]
+    fn slippage_escalates_by_a_step_up_to_the_cap() {
+        assert_eq!(escalate_slippage(1.0), 1.5);
+        assert_eq!(escalate_slippage(4.8), MAX_RETRY_SLIPPAGE);
+        assert_eq!(escalate_slippage(MAX_RETRY_SLIPPAGE), MAX_RETRY_SLIPPAGE);
+    }
 }
@@ -1,3 +1,21 @@
+pub mod dca_order_service;
+pub mod deposit_watcher;
+pub mod dialogue_timeout_service;
+pub mod idempotency;
 pub mod limit_order_service;
+pub mod rate_limiter;
+pub mod token_risk;
+pub mod token_supply;
+pub mod trade_cooldown;
+pub mod wallet_follow_service;
 
+pub use dca_order_service::DcaOrderService;
+pub use deposit_watcher::DepositWatcher;
+pub use dialogue_timeout_service::{DialogueActivity, DialogueTimeoutService};
+pub use idempotency::{IdempotencyGuard, InFlight};
 pub use limit_order_service::LimitOrderService;
+pub use rate_limiter::{RateLimiter, RequestKind};
+pub use token_risk::TokenRiskChecker;
+pub use token_supply::TokenSupplyCache;
+pub use trade_cooldown::TradeCooldown;
+pub use wallet_follow_service::WalletFollowService;
@@ -0,0 +1,191 @@
+use dashmap::DashMap;
+use std::env;
+use std::time::{Duration, Instant};
+
+/// Kind of request, trades and withdrawals have a stricter limit than read-only requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestKind {
+    Read,
+    Trade,
+}
+
+/// Allows `requests` requests per `window`, refilled continuously
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub window: Duration,
+}
+
+impl RateLimit {
+    pub fn new(requests: u32, window: Duration) -> Self {
+        Self { requests, window }
+    }
+
+    // Read `<prefix>_REQUESTS` and `<prefix>_WINDOW_SECS`, falling back to the default
+    fn from_env(prefix: &str, default: RateLimit) -> Self {
+        let requests = env::var(format!("{}_REQUESTS", prefix))
+            .ok()
+            .and_then(|value| value.trim().parse::<u32>().ok())
+            .filter(|requests| *requests > 0)
+            .unwrap_or(default.requests);
+        let window = env::var(format!("{}_WINDOW_SECS", prefix))
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(default.window);
+
+        Self { requests, window }
+    }
+}
+
+/// Token bucket of a single user
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket
+    pub fn new(limit: &RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.requests as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Take one token if available, refilling for the time passed since the last call
+    pub fn try_take(&mut self, limit: &RateLimit, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let refill_rate = limit.requests as f64 / limit.window.as_secs_f64();
+
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * refill_rate).min(limit.requests as f64);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Per Telegram user rate limiter
+///
+/// Every user has a bucket for read-only requests and a separate, smaller one for trades,
+/// so browsing menus can't use up the trade allowance and the other way round.
+/// Limits are configured with `RATE_LIMIT_*` and `TRADE_RATE_LIMIT_*` environment variables.
+pub struct RateLimiter {
+    read_limit: RateLimit,
+    trade_limit: RateLimit,
+    read_buckets: DashMap<i64, TokenBucket>,
+    trade_buckets: DashMap<i64, TokenBucket>,
+}
+
+impl RateLimiter {
+    /// Default: 30 read-only requests and 5 trades per minute
+    pub const DEFAULT_READ_LIMIT: RateLimit = RateLimit {
+        requests: 30,
+        window: Duration::from_secs(60),
+    };
+    pub const DEFAULT_TRADE_LIMIT: RateLimit = RateLimit {
+        requests: 5,
+        window: Duration::from_secs(60),
+    };
+
+    pub fn new(read_limit: RateLimit, trade_limit: RateLimit) -> Self {
+        Self {
+            read_limit,
+            trade_limit,
+            read_buckets: DashMap::new(),
+            trade_buckets: DashMap::new(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(
+            RateLimit::from_env("RATE_LIMIT", Self::DEFAULT_READ_LIMIT),
+            RateLimit::from_env("TRADE_RATE_LIMIT", Self::DEFAULT_TRADE_LIMIT),
+        )
+    }
+
+    /// Record a request, returns false when the user has exceeded the limit
+    pub fn check(&self, telegram_id: i64, kind: RequestKind) -> bool {
+        self.check_at(telegram_id, kind, Instant::now())
+    }
+
+    pub fn check_at(&self, telegram_id: i64, kind: RequestKind, now: Instant) -> bool {
+        let (limit, buckets) = match kind {
+            RequestKind::Read => (&self.read_limit, &self.read_buckets),
+            RequestKind::Trade => (&self.trade_limit, &self.trade_buckets),
+        };
+
+        buckets
+            .entry(telegram_id)
+            .or_insert_with(|| TokenBucket::new(limit, now))
+            .try_take(limit, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: RateLimit = RateLimit {
+        requests: 3,
+        window: Duration::from_secs(60),
+    };
+
+    #[test]
+    fn full_bucket_allows_the_limit_then_rejects() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(&LIMIT, now);
+
+        assert!(bucket.try_take(&LIMIT, now));
+        assert!(bucket.try_take(&LIMIT, now));
+        assert!(bucket.try_take(&LIMIT, now));
+        assert!(!bucket.try_take(&LIMIT, now));
+    }
+
+    #[test]
+    fn bucket_refills_over_the_window() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(&LIMIT, now);
+        for _ in 0..3 {
+            bucket.try_take(&LIMIT, now);
+        }
+
+        // One token comes back every 20 seconds
+        assert!(!bucket.try_take(&LIMIT, now + Duration::from_secs(19)));
+        assert!(bucket.try_take(&LIMIT, now + Duration::from_secs(20)));
+        assert!(!bucket.try_take(&LIMIT, now + Duration::from_secs(21)));
+    }
+
+    #[test]
+    fn refill_is_capped_at_the_limit() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(&LIMIT, now);
+        let later = now + Duration::from_secs(3600);
+
+        for _ in 0..3 {
+            assert!(bucket.try_take(&LIMIT, later));
+        }
+        assert!(!bucket.try_take(&LIMIT, later));
+    }
+
+    #[test]
+    fn trades_have_their_own_bucket() {
+        let limiter = RateLimiter::new(LIMIT, RateLimit::new(1, Duration::from_secs(60)));
+        let now = Instant::now();
+
+        assert!(limiter.check_at(1, RequestKind::Trade, now));
+        assert!(!limiter.check_at(1, RequestKind::Trade, now));
+
+        // Reads and other users are not affected by the exceeded trade limit
+        assert!(limiter.check_at(1, RequestKind::Read, now));
+        assert!(limiter.check_at(2, RequestKind::Trade, now));
+    }
+}
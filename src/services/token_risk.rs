@@ -0,0 +1,92 @@
+use crate::entity::{
+    TokenRisk, TokenRiskFlag, LIQUIDITY_PROBE_SOL, LOW_LIQUIDITY_PRICE_IMPACT_PCT,
+};
+use crate::interactor::db;
+use crate::solana::jupiter::quote_service::QuoteService;
+use crate::solana::jupiter::token_repository::TokenRepository;
+use crate::solana::jupiter::SOL_MINT;
+use log::warn;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+// Slippage of the liquidity probe quote, as a fraction
+const PROBE_SLIPPAGE: f64 = 0.01;
+
+/// Collects the scam warnings shown before a token is bought
+///
+/// A token is flagged when an admin blacklisted it, when its mint or freeze authority is
+/// still set, or when buying `LIQUIDITY_PROBE_SOL` would move its price a lot. Tokens
+/// verified by Jupiter skip the authority checks, stablecoins like USDC keep a freeze
+/// authority on purpose. Checks that fail are logged and skipped, a warning is a hint and
+/// must not block trading when an API is down.
+pub struct TokenRiskChecker {
+    db_pool: Arc<PgPool>,
+    token_repository: Arc<dyn TokenRepository + Send + Sync>,
+    quote_service: Arc<dyn QuoteService + Send + Sync>,
+}
+
+impl TokenRiskChecker {
+    pub fn new(
+        db_pool: Arc<PgPool>,
+        token_repository: Arc<dyn TokenRepository + Send + Sync>,
+        quote_service: Arc<dyn QuoteService + Send + Sync>,
+    ) -> Self {
+        Self {
+            db_pool,
+            token_repository,
+            quote_service,
+        }
+    }
+
+    /// Risk flags of the token, empty when buying it looks safe
+    pub async fn assess(&self, token_address: &str) -> TokenRisk {
+        let mut risk = TokenRisk::default();
+
+        if token_address == SOL_MINT {
+            return risk;
+        }
+
+        match db::get_blacklisted_token(&self.db_pool, token_address).await {
+            Ok(Some(entry)) => risk.add(TokenRiskFlag::Blacklisted(entry.reason)),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to check blacklist for {}: {}", token_address, e),
+        }
+
+        let verified = self
+            .token_repository
+            .get_token_by_id(token_address)
+            .await
+            .map(|token| token.verified)
+            .unwrap_or(false);
+
+        if !verified {
+            match self.token_repository.get_token_risk(token_address).await {
+                Ok(mint_risk) => risk.flags.extend(mint_risk.flags),
+                Err(e) => warn!("Failed to inspect mint of {}: {}", token_address, e),
+            }
+        }
+
+        match self
+            .quote_service
+            .get_swap_quote(LIQUIDITY_PROBE_SOL, SOL_MINT, token_address, PROBE_SLIPPAGE)
+            .await
+        {
+            Ok(quote) => {
+                // Jupiter reports the impact as a fraction (0.01 = 1%)
+                let price_impact = quote
+                    .price_impact_pct
+                    .to_string()
+                    .parse::<f64>()
+                    .unwrap_or(0.0)
+                    * 100.0;
+
+                if price_impact > LOW_LIQUIDITY_PRICE_IMPACT_PCT {
+                    risk.add(TokenRiskFlag::LowLiquidity { price_impact });
+                }
+            }
+            Err(e) => warn!("Failed to probe liquidity of {}: {}", token_address, e),
+        }
+
+        risk
+    }
+}
@@ -0,0 +1,52 @@
+use crate::solana::client::{price_commitment, ResilientRpcClient};
+use crate::solana::wallet::parse_pubkey;
+use anyhow::{anyhow, Result};
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long a token supply is kept before it is fetched again
+pub const DEFAULT_SUPPLY_CACHE_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Token supplies for market cap triggers, cached since they rarely change
+pub struct TokenSupplyCache {
+    solana_client: Arc<ResilientRpcClient>,
+    ttl: Duration,
+    supplies: DashMap<String, (f64, Instant)>,
+}
+
+impl TokenSupplyCache {
+    pub fn new(solana_client: Arc<ResilientRpcClient>) -> Self {
+        Self {
+            solana_client,
+            ttl: DEFAULT_SUPPLY_CACHE_TTL,
+            supplies: DashMap::new(),
+        }
+    }
+
+    /// Supply of the token in UI units, fails when it can't be determined
+    pub async fn supply(&self, token_address: &str) -> Result<f64> {
+        if let Some(entry) = self.supplies.get(token_address) {
+            let (supply, fetched_at) = *entry;
+            if fetched_at.elapsed() < self.ttl {
+                return Ok(supply);
+            }
+        }
+
+        let mint = parse_pubkey(token_address)?;
+        let supply = self
+            .solana_client
+            .get_token_supply(&mint, price_commitment())
+            .await
+            .map_err(|e| anyhow!("Failed to get supply of {}: {}", token_address, e))?;
+
+        if !supply.is_finite() || supply <= 0.0 {
+            return Err(anyhow!("Token {} has no supply", token_address));
+        }
+
+        self.supplies
+            .insert(token_address.to_string(), (supply, Instant::now()));
+
+        Ok(supply)
+    }
+}
@@ -0,0 +1,140 @@
+use dashmap::DashMap;
+use std::env;
+use std::time::{Duration, Instant};
+
+/// Blocks trading the same token again right after a trade
+///
+/// A stale confirmation button tapped after the trade went through would repeat it.
+/// The last successful trade of every user and token is remembered, and a new trade of
+/// the token is rejected until `cooldown` has passed. A zero cooldown turns it off.
+pub struct TradeCooldown {
+    cooldown: Duration,
+    last_trades: DashMap<(i64, String), Instant>,
+}
+
+impl TradeCooldown {
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            last_trades: DashMap::new(),
+        }
+    }
+
+    /// Cooldown from TRADE_COOLDOWN_SECS, off when unset
+    pub fn from_env() -> Self {
+        let cooldown = env::var("TRADE_COOLDOWN_SECS")
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::ZERO);
+
+        Self::new(cooldown)
+    }
+
+    /// Time left before the user can trade the token again, `None` when trading is allowed
+    pub fn remaining(&self, telegram_id: i64, token_address: &str) -> Option<Duration> {
+        self.remaining_at(telegram_id, token_address, Instant::now())
+    }
+
+    pub fn remaining_at(
+        &self,
+        telegram_id: i64,
+        token_address: &str,
+        now: Instant,
+    ) -> Option<Duration> {
+        if self.cooldown.is_zero() {
+            return None;
+        }
+
+        let last_trade = *self
+            .last_trades
+            .get(&(telegram_id, token_address.to_string()))?;
+        let elapsed = now.saturating_duration_since(last_trade);
+
+        self.cooldown
+            .checked_sub(elapsed)
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Start the cooldown after a successful trade
+    pub fn record(&self, telegram_id: i64, token_address: &str) {
+        self.record_at(telegram_id, token_address, Instant::now());
+    }
+
+    pub fn record_at(&self, telegram_id: i64, token_address: &str, now: Instant) {
+        if self.cooldown.is_zero() {
+            return;
+        }
+
+        // Drop expired entries so the map doesn't grow with every token ever traded
+        self.last_trades
+            .retain(|_, last_trade| now.saturating_duration_since(*last_trade) < self.cooldown);
+        self.last_trades
+            .insert((telegram_id, token_address.to_string()), now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "Token11111111111111111111111111111111111111";
+    const OTHER_TOKEN: &str = "Other11111111111111111111111111111111111111";
+
+    #[test]
+    fn trade_within_the_cooldown_is_rejected() {
+        let cooldown = TradeCooldown::new(Duration::from_secs(30));
+        let traded_at = Instant::now();
+        cooldown.record_at(1001, TOKEN, traded_at);
+
+        assert_eq!(
+            cooldown.remaining_at(1001, TOKEN, traded_at + Duration::from_secs(10)),
+            Some(Duration::from_secs(20))
+        );
+    }
+
+    #[test]
+    fn trade_after_the_cooldown_is_allowed() {
+        let cooldown = TradeCooldown::new(Duration::from_secs(30));
+        let traded_at = Instant::now();
+        cooldown.record_at(1001, TOKEN, traded_at);
+
+        assert_eq!(
+            cooldown.remaining_at(1001, TOKEN, traded_at + Duration::from_secs(30)),
+            None
+        );
+        assert_eq!(
+            cooldown.remaining_at(1001, TOKEN, traded_at + Duration::from_secs(90)),
+            None
+        );
+    }
+
+    #[test]
+    fn cooldown_is_per_user_and_token() {
+        let cooldown = TradeCooldown::new(Duration::from_secs(30));
+        let traded_at = Instant::now();
+        cooldown.record_at(1001, TOKEN, traded_at);
+
+        assert_eq!(cooldown.remaining_at(1001, OTHER_TOKEN, traded_at), None);
+        assert_eq!(cooldown.remaining_at(1002, TOKEN, traded_at), None);
+    }
+
+    #[test]
+    fn zero_cooldown_is_off() {
+        let cooldown = TradeCooldown::new(Duration::ZERO);
+        let traded_at = Instant::now();
+        cooldown.record_at(1001, TOKEN, traded_at);
+
+        assert_eq!(cooldown.remaining_at(1001, TOKEN, traded_at), None);
+    }
+
+    #[test]
+    fn expired_trades_are_dropped_on_the_next_record() {
+        let cooldown = TradeCooldown::new(Duration::from_secs(30));
+        let traded_at = Instant::now();
+        cooldown.record_at(1001, TOKEN, traded_at);
+        cooldown.record_at(1001, OTHER_TOKEN, traded_at + Duration::from_secs(60));
+
+        assert_eq!(cooldown.last_trades.len(), 1);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::di::ServiceContainer;
+use crate::entity::FollowedWallet;
+use crate::interactor::db;
+use crate::solana::activity::{self, TokenChange, WalletActivity};
+use anyhow::Result;
+use log::{debug, error, info, warn};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::{prelude::*, types::ParseMode, Bot};
+use tokio::select;
+use tokio::sync::mpsc;
+use tokio::time::interval;
+
+/// Signatures requested per followed wallet on every check
+const SIGNATURES_PER_CHECK: usize = 20;
+
+pub struct WalletFollowService {
+    services: Arc<ServiceContainer>,
+    bot: Bot,
+    stop_tx: Option<mpsc::Sender<()>>,
+}
+
+impl WalletFollowService {
+    pub fn new(services: Arc<ServiceContainer>, bot: Bot) -> Self {
+        Self {
+            services,
+            bot,
+            stop_tx: None,
+        }
+    }
+
+    // Start the background service that reports trades of followed wallets
+    pub async fn start(&mut self) -> Result<()> {
+        if self.stop_tx.is_some() {
+            warn!("Wallet follow service is already running");
+            return Ok(());
+        }
+
+        // Create a channel for stopping the service
+        let (stop_tx, mut stop_rx) = mpsc::channel::<()>(1);
+        self.stop_tx = Some(stop_tx);
+
+        let services_clone = self.services.clone();
+        let bot_clone = self.bot.clone();
+
+        tokio::spawn(async move {
+            // Create an interval ticker that triggers every 30 seconds
+            let mut interval = interval(Duration::from_secs(30));
+
+            loop {
+                select! {
+                    _ = interval.tick() => {
+                        debug!("Running followed wallet check");
+
+                        if let Err(e) = Self::check_followed_wallets(&services_clone, &bot_clone).await {
+                            error!("Error checking followed wallets: {}", e);
+                        }
+                    }
+                    // When we receive a stop signal, exit the loop
+                    _ = stop_rx.recv() => {
+                        info!("Stopping wallet follow service");
+                        break;
+                    }
+                }
+            }
+        });
+
+        info!("Wallet follow service started");
+        Ok(())
+    }
+
+    // Stop the background service
+    pub async fn stop(&mut self) {
+        if let Some(tx) = self.stop_tx.take() {
+            let _ = tx.send(()).await;
+            info!("Wallet follow service stop signal sent");
+        }
+    }
+
+    async fn check_followed_wallets(services: &Arc<ServiceContainer>, bot: &Bot) -> Result<()> {
+        let db_pool = services.db_pool();
+        let solana_client = services.solana_client();
+
+        let followed_wallets = db::get_all_followed_wallets(&db_pool).await?;
+
+        if followed_wallets.is_empty() {
+            return Ok(());
+        }
+
+        // Several users may follow the same wallet, fetch its history only once
+        let mut signatures_by_address: HashMap<String, Vec<String>> = HashMap::new();
+        let mut activity_by_signature: HashMap<String, Option<WalletActivity>> = HashMap::new();
+
+        for followed in &followed_wallets {
+            let address = &followed.wallet_address;
+
+            if !signatures_by_address.contains_key(address) {
+                match activity::get_recent_signatures(&solana_client, address, SIGNATURES_PER_CHECK)
+                    .await
+                {
+                    Ok(signatures) => {
+                        signatures_by_address.insert(address.clone(), signatures);
+                    }
+                    Err(e) => {
+                        error!(
+                            "Failed to get signatures of followed wallet {}: {}",
+                            address, e
+                        );
+                        continue;
+                    }
+                }
+            }
+
+            let diff = followed.diff_signatures(&signatures_by_address[address]);
+
+            for signature in &diff.new_signatures {
+                if !activity_by_signature.contains_key(signature) {
+                    let wallet_activity =
+                        match activity::get_wallet_activity(&solana_client, address, signature)
+                            .await
+                        {
+                            Ok(wallet_activity) => wallet_activity,
+                            Err(e) => {
+                                error!("Failed to get activity of {}: {}", signature, e);
+                                None
+                            }
+                        };
+                    activity_by_signature.insert(signature.clone(), wallet_activity);
+                }
+
+                if let Some(wallet_activity) = &activity_by_signature[signature] {
+                    if let Err(e) =
+                        Self::notify_follower(services, bot, followed, wallet_activity).await
+                    {
+                        error!(
+                            "Failed to notify follower of wallet {}: {}",
+                            followed.wallet_address, e
+                        );
+                    }
+                }
+            }
+
+            if let Some(last_signature) = &diff.last_signature {
+                if followed.last_signature.as_ref() != Some(last_signature) {
+                    db::update_followed_wallet_signature(&db_pool, followed.id, last_signature)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn notify_follower(
+        services: &Arc<ServiceContainer>,
+        bot: &Bot,
+        followed: &FollowedWallet,
+        wallet_activity: &WalletActivity,
+    ) -> Result<()> {
+        // Plain SOL transfers and other transactions without token changes are not trades
+        if wallet_activity.token_changes.is_empty() {
+            return Ok(());
+        }
+
+        let user = db::get_user_by_id(&services.db_pool(), followed.user_id).await?;
+
+        let mut lines = Vec::new();
+        for change in &wallet_activity.token_changes {
+            let symbol = Self::token_symbol(services, change).await;
+            let action = if change.amount > 0.0 {
+                "bought"
+            } else {
+                "sold"
+            };
+
+            lines.push(format!(
+                "• {} <b>{:.6} {}</b>",
+                action,
+                change.amount.abs(),
+                symbol
+            ));
+        }
+
+        // A single token trade against SOL shows what was paid or received
+        if wallet_activity.token_changes.len() == 1 && wallet_activity.sol_change.abs() > 0.0001 {
+            let sol_text = if wallet_activity.sol_change < 0.0 {
+                format!("• paid {:.4} SOL", -wallet_activity.sol_change)
+            } else {
+                format!("• received {:.4} SOL", wallet_activity.sol_change)
+            };
+            lines.push(sol_text);
+        }
+
+        let message = format!(
+            "👀 <b>Followed Wallet Activity</b>\n\n\
+             Wallet <code>{}</code>:\n{}\n\n\
+             <a href=\"https://explorer.solana.com/tx/{}\">View on Explorer</a>",
+            followed.short_address(),
+            lines.join("\n"),
+            wallet_activity.signature
+        );
+
+        bot.send_message(ChatId(user.telegram_id), message)
+            .parse_mode(ParseMode::Html)
+            .await?;
+
+        Ok(())
+    }
+
+    // Token symbol from the token list, the shortened mint when it's unknown
+    async fn token_symbol(services: &Arc<ServiceContainer>, change: &TokenChange) -> String {
+        match services
+            .token_repository()
+            .get_token_by_id(&change.mint)
+            .await
+        {
+            Ok(token) => token.symbol,
+            Err(_) => format!("{}…", &change.mint[..change.mint.len().min(6)]),
+        }
+    }
+}
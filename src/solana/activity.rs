@@ -0,0 +1,293 @@
+use crate::solana::client::ResilientRpcClient;
+use crate::solana::jupiter::SOL_MINT;
+use crate::solana::utils::LAMPORTS_PER_SOL;
+use crate::solana::wallet::parse_pubkey;
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::json;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Change of one token balance in a transaction, positive when the wallet received tokens
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenChange {
+    pub mint: String,
+    pub amount: f64,
+}
+
+/// Balance changes of a wallet caused by one transaction
+#[derive(Debug, Clone)]
+pub struct WalletActivity {
+    pub signature: String,
+    pub token_changes: Vec<TokenChange>,
+    // Includes the fee when the wallet paid it
+    pub sol_change: f64,
+}
+
+// Only the parts of the getTransaction response needed for balance changes
+#[derive(Debug, Deserialize)]
+struct RawTransaction {
+    meta: Option<RawTransactionMeta>,
+    transaction: RawTransactionBody,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTransactionMeta {
+    err: Option<serde_json::Value>,
+    #[serde(default)]
+    pre_balances: Vec<u64>,
+    #[serde(default)]
+    post_balances: Vec<u64>,
+    #[serde(default)]
+    pre_token_balances: Vec<RawTokenBalance>,
+    #[serde(default)]
+    post_token_balances: Vec<RawTokenBalance>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawTransactionBody {
+    message: RawMessage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMessage {
+    account_keys: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTokenBalance {
+    mint: String,
+    owner: Option<String>,
+    ui_token_amount: RawTokenAmount,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTokenAmount {
+    ui_amount: Option<f64>,
+}
+
+/// Most recent signatures of the address, newest first
+pub async fn get_recent_signatures(
+    client: &ResilientRpcClient,
+    address: &str,
+    limit: usize,
+) -> Result<Vec<String>> {
+    let pubkey = parse_pubkey(address)?;
+
+    let config = GetConfirmedSignaturesForAddress2Config {
+        before: None,
+        until: None,
+        limit: Some(limit),
+        commitment: Some(client.commitment()),
+    };
+
+    let signatures = client
+        .get_signatures_for_address(&pubkey, config)
+        .await
+        .map_err(|e| anyhow!("Failed to get signatures for {}: {}", address, e))?;
+
+    Ok(signatures
+        .into_iter()
+        .map(|status| status.signature)
+        .collect())
+}
+
+/// Token and SOL balance changes of `owner` in the transaction
+///
+/// Returns None for failed transactions and transactions that aren't available yet.
+pub async fn get_wallet_activity(
+    client: &ResilientRpcClient,
+    owner: &str,
+    signature: &str,
+) -> Result<Option<WalletActivity>> {
+    // Make sure a malformed signature isn't sent to the RPC node
+    Signature::from_str(signature).map_err(|e| anyhow!("Invalid signature: {}", e))?;
+
+    let params = json!([
+        signature,
+        {
+            "encoding": "json",
+            "maxSupportedTransactionVersion": 0,
+            "commitment": client.commitment().commitment
+        }
+    ]);
+    let transaction: Option<RawTransaction> = client
+        .execute("get_transaction", |client| {
+            let params = params.clone();
+            async move { client.send(RpcRequest::GetTransaction, params).await }
+        })
+        .await
+        .map_err(|e| anyhow!("Failed to get transaction {}: {}", signature, e))?;
+
+    let Some(transaction) = transaction else {
+        return Ok(None);
+    };
+    let Some(meta) = transaction.meta else {
+        return Ok(None);
+    };
+
+    if meta.err.is_some() {
+        return Ok(None);
+    }
+
+    Ok(Some(WalletActivity {
+        signature: signature.to_string(),
+        token_changes: token_changes(&meta, owner),
+        sol_change: sol_change(&meta, &transaction.transaction.message.account_keys, owner),
+    }))
+}
+
+// Per mint difference between post and pre token balances owned by `owner`
+fn token_changes(meta: &RawTransactionMeta, owner: &str) -> Vec<TokenChange> {
+    let mut changes: HashMap<&str, f64> = HashMap::new();
+
+    let owned = |balance: &&RawTokenBalance| balance.owner.as_deref() == Some(owner);
+
+    for balance in meta.pre_token_balances.iter().filter(owned) {
+        *changes.entry(&balance.mint).or_default() -=
+            balance.ui_token_amount.ui_amount.unwrap_or(0.0);
+    }
+    for balance in meta.post_token_balances.iter().filter(owned) {
+        *changes.entry(&balance.mint).or_default() +=
+            balance.ui_token_amount.ui_amount.unwrap_or(0.0);
+    }
+
+    let mut changes: Vec<TokenChange> = changes
+        .into_iter()
+        // Wrapped SOL is reported through the SOL balance change instead
+        .filter(|(mint, amount)| *mint != SOL_MINT && amount.abs() > f64::EPSILON)
+        .map(|(mint, amount)| TokenChange {
+            mint: mint.to_string(),
+            amount,
+        })
+        .collect();
+    changes.sort_by(|a, b| a.mint.cmp(&b.mint));
+
+    changes
+}
+
+// Difference of the owner's lamports, zero when the owner isn't a static account key
+fn sol_change(meta: &RawTransactionMeta, account_keys: &[String], owner: &str) -> f64 {
+    let Some(index) = account_keys.iter().position(|key| key == owner) else {
+        return 0.0;
+    };
+
+    match (meta.pre_balances.get(index), meta.post_balances.get(index)) {
+        (Some(pre), Some(post)) => (*post as f64 - *pre as f64) / LAMPORTS_PER_SOL,
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const OTHER: &str = "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn token_balance(mint: &str, owner: &str, ui_amount: f64) -> serde_json::Value {
+        json!({
+            "accountIndex": 2,
+            "mint": mint,
+            "owner": owner,
+            "uiTokenAmount": { "uiAmount": ui_amount, "decimals": 5, "amount": "0" },
+        })
+    }
+
+    // getTransaction response of a swap by OWNER, the pool (OTHER) is on the other side
+    fn swap(pre: Vec<serde_json::Value>, post: Vec<serde_json::Value>) -> RawTransaction {
+        serde_json::from_value(json!({
+            "meta": {
+                "err": null,
+                "preBalances": [2_000_000_000u64, 50_000_000_000u64],
+                "postBalances": [1_499_995_000u64, 50_500_000_000u64],
+                "preTokenBalances": pre,
+                "postTokenBalances": post,
+            },
+            "transaction": {
+                "message": { "accountKeys": [OWNER, OTHER] },
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn buy_shows_the_token_received_and_the_sol_paid() {
+        let transaction = swap(
+            vec![token_balance(BONK, OTHER, 9_000_000.0)],
+            vec![
+                token_balance(BONK, OWNER, 1_000_000.0),
+                token_balance(BONK, OTHER, 8_000_000.0),
+            ],
+        );
+        let meta = transaction.meta.as_ref().unwrap();
+
+        // The new token account has no pre balance, the pool's side is ignored
+        assert_eq!(
+            token_changes(meta, OWNER),
+            vec![TokenChange {
+                mint: BONK.to_string(),
+                amount: 1_000_000.0,
+            }]
+        );
+        let sol = sol_change(meta, &transaction.transaction.message.account_keys, OWNER);
+        assert!((sol + 0.500005).abs() < 1e-9);
+    }
+
+    #[test]
+    fn selling_everything_is_a_negative_change() {
+        let transaction = swap(
+            vec![
+                token_balance(BONK, OWNER, 250.5),
+                token_balance(USDC, OWNER, 10.0),
+            ],
+            vec![token_balance(USDC, OWNER, 10.0)],
+        );
+
+        let changes = token_changes(transaction.meta.as_ref().unwrap(), OWNER);
+
+        // The unchanged USDC balance isn't reported
+        assert_eq!(
+            changes,
+            vec![TokenChange {
+                mint: BONK.to_string(),
+                amount: -250.5,
+            }]
+        );
+    }
+
+    #[test]
+    fn wrapped_sol_is_left_to_the_sol_change() {
+        let transaction = swap(
+            vec![token_balance(SOL_MINT, OWNER, 0.0)],
+            vec![
+                token_balance(SOL_MINT, OWNER, 0.5),
+                token_balance(USDC, OWNER, 75.0),
+            ],
+        );
+
+        let changes = token_changes(transaction.meta.as_ref().unwrap(), OWNER);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].mint, USDC);
+    }
+
+    #[test]
+    fn owner_outside_the_static_keys_has_no_sol_change() {
+        let transaction = swap(Vec::new(), Vec::new());
+        let keys = &transaction.transaction.message.account_keys;
+
+        let sol = sol_change(transaction.meta.as_ref().unwrap(), keys, BONK);
+
+        assert_eq!(sol, 0.0);
+    }
+}
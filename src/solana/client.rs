@@ -1,11 +1,546 @@
-use anyhow::Result;
-use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::commitment_config::CommitmentConfig;
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    nonblocking::rpc_client::RpcClient,
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, SerializableTransaction},
+    rpc_request::TokenAccountsFilter,
+    rpc_response::{RpcConfirmedTransactionStatusWithSignature, RpcKeyedAccount},
+};
+use solana_sdk::{
+    account::Account,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    hash::Hash,
+    message::Message,
+    pubkey::Pubkey,
+    signature::Signature,
+};
+use std::env;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-/// Create a Solana client with confirmed commitment
-pub fn create_solana_client(rpc_url: &str) -> Result<Arc<RpcClient>> {
-    let client = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
+/// Number of retries against a single endpoint before rotating to the next one
+const MAX_RETRIES_PER_ENDPOINT: u32 = 3;
 
-    Ok(Arc::new(client))
+/// Initial delay between retries, doubled after every failed attempt
+const BASE_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+// Error messages returned by RPC nodes that are worth retrying
+const TRANSIENT_ERROR_MARKERS: [&str; 7] = [
+    "429",
+    "503",
+    "too many requests",
+    "service unavailable",
+    "node is behind",
+    "node is unhealthy",
+    "timed out",
+];
+
+/// Commitment of reads that don't pass one, from SOLANA_COMMITMENT (confirmed when unset)
+pub fn commitment_from_env() -> CommitmentConfig {
+    env::var("SOLANA_COMMITMENT")
+        .ok()
+        .and_then(|value| CommitmentLevel::from_str(value.trim()).ok())
+        .map(|commitment| CommitmentConfig { commitment })
+        .unwrap_or_else(CommitmentConfig::confirmed)
+}
+
+/// Commitment of balance and account reads a trade or transfer relies on
+///
+/// At least confirmed, a processed block can still be rolled back and show funds that
+/// never arrive. A stricter default like finalized is kept.
+pub fn balance_commitment(default: CommitmentConfig) -> CommitmentConfig {
+    if default.is_at_least_confirmed() {
+        default
+    } else {
+        CommitmentConfig::confirmed()
+    }
+}
+
+/// Commitment of price inputs like token supplies, the newest state matters more than finality
+pub fn price_commitment() -> CommitmentConfig {
+    CommitmentConfig::processed()
+}
+
+/// Solana RPC client with retries and failover between several endpoints
+///
+/// Transient errors (connection failures, rate limits, unavailable nodes) are retried
+/// with exponential backoff. When an endpoint keeps failing the client rotates to the
+/// next configured endpoint, which is then used by all subsequent calls.
+pub struct ResilientRpcClient {
+    clients: Vec<Arc<RpcClient>>,
+    current: AtomicUsize,
+    max_retries: u32,
+    base_delay: Duration,
+}
+
+impl ResilientRpcClient {
+    /// Create a client for the given endpoints with the SOLANA_COMMITMENT default commitment
+    pub fn new(rpc_urls: &[String]) -> Result<Self> {
+        Self::with_commitment(rpc_urls, commitment_from_env())
+    }
+
+    /// Create a client for the given endpoints, `commitment` is used by calls without one
+    pub fn with_commitment(rpc_urls: &[String], commitment: CommitmentConfig) -> Result<Self> {
+        if rpc_urls.is_empty() {
+            return Err(anyhow!("At least one Solana RPC URL must be provided"));
+        }
+
+        let clients = rpc_urls
+            .iter()
+            .map(|url| Arc::new(RpcClient::new_with_commitment(url.clone(), commitment)))
+            .collect();
+
+        Ok(Self {
+            clients,
+            current: AtomicUsize::new(0),
+            max_retries: MAX_RETRIES_PER_ENDPOINT,
+            base_delay: BASE_RETRY_DELAY,
+        })
+    }
+
+    /// Override the retry policy
+    pub fn with_retry_policy(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Client for the endpoint that is currently in use
+    pub fn current(&self) -> Arc<RpcClient> {
+        self.clients[self.current_index()].clone()
+    }
+
+    /// Commitment used by calls that don't pass one
+    pub fn commitment(&self) -> CommitmentConfig {
+        self.current().commitment()
+    }
+
+    /// Number of configured endpoints
+    pub fn endpoint_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    fn current_index(&self) -> usize {
+        self.current.load(Ordering::Relaxed) % self.clients.len()
+    }
+
+    // Switch to the next endpoint unless another call has already done so
+    fn rotate_from(&self, failed_index: usize) {
+        if self.clients.len() < 2 {
+            return;
+        }
+
+        let next_index = (failed_index + 1) % self.clients.len();
+        if self
+            .current
+            .compare_exchange(
+                failed_index,
+                next_index,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+        {
+            warn!(
+                "Switching Solana RPC endpoint from {} to {}",
+                self.clients[failed_index].url(),
+                self.clients[next_index].url()
+            );
+        }
+    }
+
+    /// Run an RPC call with retries and endpoint failover
+    pub async fn execute<T, F, Fut>(&self, operation: &str, call: F) -> ClientResult<T>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = ClientResult<T>>,
+    {
+        let mut last_error = None;
+
+        // Try every endpoint once, starting from the current one
+        for _ in 0..self.clients.len() {
+            let index = self.current_index();
+            let client = self.clients[index].clone();
+            let mut delay = self.base_delay;
+
+            for attempt in 0..=self.max_retries {
+                match call(client.clone()).await {
+                    Ok(value) => return Ok(value),
+                    Err(e) if !is_transient(&e) => return Err(e),
+                    Err(e) => {
+                        debug!(
+                            "{} failed on {} (attempt {}/{}): {}",
+                            operation,
+                            client.url(),
+                            attempt + 1,
+                            self.max_retries + 1,
+                            e
+                        );
+                        last_error = Some(e);
+                    }
+                }
+
+                if attempt < self.max_retries {
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+            }
+
+            self.rotate_from(index);
+        }
+
+        Err(last_error.expect("at least one RPC attempt is made"))
+    }
+
+    /// Current slot and round trip time of the active endpoint
+    ///
+    /// Not retried, so the latency is the one of a single request.
+    pub async fn check_health(&self) -> ClientResult<(u64, Duration)> {
+        let client = self.current();
+        let started = Instant::now();
+        let slot = client.get_slot().await?;
+
+        Ok((slot, started.elapsed()))
+    }
+
+    pub async fn get_balance(
+        &self,
+        pubkey: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> ClientResult<u64> {
+        self.execute("get_balance", |client| async move {
+            Ok(client
+                .get_balance_with_commitment(pubkey, commitment)
+                .await?
+                .value)
+        })
+        .await
+    }
+
+    /// Latest blockhash and the last block height a transaction using it is valid for
+    pub async fn get_latest_blockhash(&self) -> ClientResult<(Hash, u64)> {
+        self.execute("get_latest_blockhash", |client| async move {
+            let commitment = client.commitment();
+            client
+                .get_latest_blockhash_with_commitment(commitment)
+                .await
+        })
+        .await
+    }
+
+    pub async fn get_block_height(&self) -> ClientResult<u64> {
+        self.execute("get_block_height", |client| async move {
+            client.get_block_height().await
+        })
+        .await
+    }
+
+    pub async fn get_fee_for_message(&self, message: &Message) -> ClientResult<u64> {
+        self.execute("get_fee_for_message", |client| async move {
+            client.get_fee_for_message(message).await
+        })
+        .await
+    }
+
+    pub async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> ClientResult<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        self.execute("get_signatures_for_address", |client| {
+            let config = config.clone();
+            async move {
+                client
+                    .get_signatures_for_address_with_config(address, config)
+                    .await
+            }
+        })
+        .await
+    }
+
+    pub async fn send_transaction(
+        &self,
+        transaction: &(impl SerializableTransaction + Sync),
+    ) -> ClientResult<Signature> {
+        self.execute("send_transaction", |client| async move {
+            client.send_transaction(transaction).await
+        })
+        .await
+    }
+
+    /// Account at `pubkey`, `None` when it doesn't exist
+    pub async fn get_account(
+        &self,
+        pubkey: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> ClientResult<Option<Account>> {
+        self.execute("get_account", |client| async move {
+            Ok(client
+                .get_account_with_commitment(pubkey, commitment)
+                .await?
+                .value)
+        })
+        .await
+    }
+
+    /// Accounts at `pubkeys` in the same order, `None` for the ones that don't exist
+    pub async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> ClientResult<Vec<Option<Account>>> {
+        self.execute("get_multiple_accounts", |client| async move {
+            client.get_multiple_accounts(pubkeys).await
+        })
+        .await
+    }
+
+    /// Minted supply of a token in UI units, i.e. divided by the token's decimals
+    pub async fn get_token_supply(
+        &self,
+        mint: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> ClientResult<f64> {
+        self.execute("get_token_supply", |client| async move {
+            let supply = client
+                .get_token_supply_with_commitment(mint, commitment)
+                .await?
+                .value;
+            Ok(supply
+                .ui_amount
+                .unwrap_or_else(|| supply.ui_amount_string.parse().unwrap_or(0.0)))
+        })
+        .await
+    }
+
+    /// Balances of the largest holders of a token in UI units, at most 20 accounts
+    pub async fn get_token_largest_accounts(
+        &self,
+        mint: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> ClientResult<Vec<f64>> {
+        self.execute("get_token_largest_accounts", |client| async move {
+            let accounts = client
+                .get_token_largest_accounts_with_commitment(mint, commitment)
+                .await?
+                .value;
+            Ok(accounts
+                .into_iter()
+                .map(|account| {
+                    account
+                        .amount
+                        .ui_amount
+                        .unwrap_or_else(|| account.amount.ui_amount_string.parse().unwrap_or(0.0))
+                })
+                .collect())
+        })
+        .await
+    }
+
+    pub async fn get_token_accounts_by_owner(
+        &self,
+        owner: &Pubkey,
+        token_account_filter: TokenAccountsFilter,
+        commitment: CommitmentConfig,
+    ) -> ClientResult<Vec<RpcKeyedAccount>> {
+        self.execute("get_token_accounts_by_owner", |client| {
+            let filter = token_account_filter.clone();
+            async move {
+                Ok(client
+                    .get_token_accounts_by_owner_with_commitment(owner, filter, commitment)
+                    .await?
+                    .value)
+            }
+        })
+        .await
+    }
+}
+
+// Connection errors and rate limiting are retried, everything else is returned as is
+fn is_transient(error: &ClientError) -> bool {
+    match error.kind() {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => true,
+        ClientErrorKind::RpcError(_) => {
+            let message = error.to_string().to_lowercase();
+            TRANSIENT_ERROR_MARKERS
+                .iter()
+                .any(|marker| message.contains(marker))
+        }
+        _ => false,
+    }
+}
+
+/// Create a Solana client with the SOLANA_COMMITMENT default commitment
+///
+/// `rpc_url` may contain several comma-separated endpoints, the first one is used
+/// until it starts failing.
+pub fn create_solana_client(rpc_url: &str) -> Result<Arc<ResilientRpcClient>> {
+    let rpc_urls: Vec<String> = rpc_url
+        .split(',')
+        .map(|url| url.trim())
+        .filter(|url| !url.is_empty())
+        .map(|url| url.to_string())
+        .collect();
+
+    Ok(Arc::new(ResilientRpcClient::new(&rpc_urls)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fake_rpc, rpc_version, RpcCall};
+    use std::io;
+    use std::sync::atomic::AtomicU32;
+    use tokio::sync::mpsc;
+
+    fn client(urls: &[&str], max_retries: u32) -> ResilientRpcClient {
+        let urls: Vec<String> = urls.iter().map(|url| url.to_string()).collect();
+        ResilientRpcClient::new(&urls)
+            .unwrap()
+            .with_retry_policy(max_retries, Duration::ZERO)
+    }
+
+    fn connection_refused() -> ClientError {
+        io::Error::new(io::ErrorKind::ConnectionRefused, "connection refused").into()
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors_until_the_call_succeeds() {
+        let client = client(&["http://rpc-1"], 3);
+        let calls = AtomicU32::new(0);
+
+        let result = client
+            .execute("get_balance", |_| {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if call < 2 {
+                        Err(connection_refused())
+                    } else {
+                        Ok(42u64)
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_the_next_endpoint() {
+        let client = client(&["http://rpc-1", "http://rpc-2"], 1);
+        let calls = AtomicU32::new(0);
+
+        let result = client
+            .execute("get_balance", |rpc| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if rpc.url().contains("rpc-1") {
+                        Err(connection_refused())
+                    } else {
+                        Ok(42u64)
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(client.current().url().contains("rpc-2"));
+    }
+
+    #[tokio::test]
+    async fn returns_the_last_error_when_every_attempt_fails() {
+        let client = client(&["http://rpc-1"], 2);
+        let calls = AtomicU32::new(0);
+
+        let result: ClientResult<u64> = client
+            .execute("get_balance", |_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err(connection_refused()) }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn balance_reads_are_at_least_confirmed() {
+        assert_eq!(
+            balance_commitment(CommitmentConfig::processed()),
+            CommitmentConfig::confirmed()
+        );
+        assert_eq!(
+            balance_commitment(CommitmentConfig::confirmed()),
+            CommitmentConfig::confirmed()
+        );
+        assert_eq!(
+            balance_commitment(CommitmentConfig::finalized()),
+            CommitmentConfig::finalized()
+        );
+    }
+
+    // JSON-RPC node answering balance and supply reads
+    async fn balance_rpc() -> (String, mpsc::UnboundedReceiver<RpcCall>) {
+        fake_rpc(|method, _params| {
+            let context = serde_json::json!({ "slot": 1 });
+            match method {
+                "getVersion" => rpc_version(),
+                "getBalance" => serde_json::json!({ "context": context, "value": 5_000 }),
+                "getTokenSupply" => {
+                    let supply = serde_json::json!({
+                        "amount": "1000",
+                        "decimals": 0,
+                        "uiAmount": 1000.0,
+                        "uiAmountString": "1000",
+                    });
+                    serde_json::json!({ "context": context, "value": supply })
+                }
+                method => panic!("unexpected RPC call {}", method),
+            }
+        })
+        .await
+    }
+
+    // Params of the next call to `method`
+    async fn params_of(
+        calls: &mut mpsc::UnboundedReceiver<RpcCall>,
+        method: &str,
+    ) -> serde_json::Value {
+        loop {
+            let call = calls.recv().await.unwrap();
+            if call.method == method {
+                return call.params;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn the_requested_commitment_reaches_the_rpc_call() {
+        let (url, mut calls) = balance_rpc().await;
+        let client =
+            ResilientRpcClient::with_commitment(&[url], CommitmentConfig::confirmed()).unwrap();
+        let pubkey = Pubkey::new_unique();
+
+        let balance = client
+            .get_balance(&pubkey, CommitmentConfig::finalized())
+            .await
+            .unwrap();
+        assert_eq!(balance, 5_000);
+        let sent = params_of(&mut calls, "getBalance").await;
+        assert_eq!(sent[0], pubkey.to_string());
+        assert_eq!(sent[1]["commitment"], "finalized");
+
+        let supply = client
+            .get_token_supply(&pubkey, price_commitment())
+            .await
+            .unwrap();
+        assert_eq!(supply, 1000.0);
+        let sent = params_of(&mut calls, "getTokenSupply").await;
+        assert_eq!(sent[1]["commitment"], "processed");
+    }
 }
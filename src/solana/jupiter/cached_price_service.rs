@@ -0,0 +1,281 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use log::debug;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use crate::entity::TokenPrice;
+use crate::solana::jupiter::price_service::PriceService;
+
+/// How long a token price is reused before it is requested again
+pub const DEFAULT_PRICE_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Minimum time between two points of a token's price history
+pub const PRICE_HISTORY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How far back the price history goes
+pub const PRICE_HISTORY_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Price service decorator that caches token prices for a short time
+///
+/// Command handlers and the background order loops often ask for the same token
+/// within a few seconds, sharing the result keeps the bot below Jupiter rate limits.
+/// Use `get_token_price_fresh` where a stale price is not acceptable, e.g. before a trade.
+///
+/// Fetched prices are also sampled into a short history for sparklines. Tokens the bot
+/// keeps checking, e.g. watchlist tokens, build up a history over the day.
+pub struct CachedPriceService<P: PriceService> {
+    inner: P,
+    ttl: Duration,
+    prices: RwLock<HashMap<String, (TokenPrice, Instant)>>,
+    history: RwLock<HashMap<String, Vec<(Instant, f64)>>>,
+}
+
+impl<P: PriceService> CachedPriceService<P> {
+    pub fn new(inner: P) -> Self {
+        Self::with_ttl(inner, DEFAULT_PRICE_CACHE_TTL)
+    }
+
+    pub fn with_ttl(inner: P, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            prices: RwLock::new(HashMap::new()),
+            history: RwLock::new(HashMap::new()),
+        }
+    }
+
+    // Cached price if it is still within the TTL
+    fn cached_price(&self, token_id: &str) -> Option<TokenPrice> {
+        let prices = self.prices.read().unwrap();
+        prices
+            .get(token_id)
+            .filter(|(_, fetched_at)| fetched_at.elapsed() < self.ttl)
+            .map(|(price, _)| price.clone())
+    }
+
+    fn cache_price(&self, token_id: &str, price: &TokenPrice) {
+        let mut prices = self.prices.write().unwrap();
+
+        // Drop expired entries so the map doesn't grow with every token ever requested
+        let ttl = self.ttl;
+        prices.retain(|_, (_, fetched_at)| fetched_at.elapsed() < ttl);
+
+        prices.insert(token_id.to_string(), (price.clone(), Instant::now()));
+    }
+
+    // Add the price to the token's history, at most one point per PRICE_HISTORY_INTERVAL
+    fn record_history(&self, token_id: &str, price: &TokenPrice) {
+        let mut history = self.history.write().unwrap();
+        let now = Instant::now();
+
+        // Forget points older than the window, and tokens that have no recent points left
+        history.retain(|_, points| {
+            points.retain(|(at, _)| now.duration_since(*at) < PRICE_HISTORY_WINDOW);
+            !points.is_empty()
+        });
+
+        let points = history.entry(token_id.to_string()).or_default();
+        let due = points
+            .last()
+            .is_none_or(|(at, _)| now.duration_since(*at) >= PRICE_HISTORY_INTERVAL);
+        if due {
+            points.push((now, price.price_in_usdc));
+        }
+    }
+}
+
+#[async_trait]
+impl<P: PriceService> PriceService for CachedPriceService<P> {
+    async fn get_sol_price(&self) -> Result<f64> {
+        self.inner.get_sol_price().await
+    }
+
+    async fn get_token_price(&self, token_id: &str) -> Result<TokenPrice> {
+        if let Some(price) = self.cached_price(token_id) {
+            debug!("Price cache hit: {}", token_id);
+            return Ok(price);
+        }
+
+        self.get_token_price_fresh(token_id).await
+    }
+
+    async fn get_token_price_fresh(&self, token_id: &str) -> Result<TokenPrice> {
+        let price = self.inner.get_token_price(token_id).await?;
+        self.cache_price(token_id, &price);
+        self.record_history(token_id, &price);
+
+        Ok(price)
+    }
+
+    async fn get_token_prices(&self, token_ids: &[&str]) -> Result<HashMap<String, TokenPrice>> {
+        let mut prices = HashMap::new();
+        let mut missing = Vec::new();
+
+        for token_id in token_ids {
+            match self.cached_price(token_id) {
+                Some(price) => {
+                    prices.insert(token_id.to_string(), price);
+                }
+                None => missing.push(*token_id),
+            }
+        }
+
+        if missing.is_empty() {
+            return Ok(prices);
+        }
+
+        debug!(
+            "Price cache hits: {}, fetching {} prices",
+            prices.len(),
+            missing.len()
+        );
+
+        for (token_id, price) in self.inner.get_token_prices(&missing).await? {
+            self.cache_price(&token_id, &price);
+            self.record_history(&token_id, &price);
+            prices.insert(token_id, price);
+        }
+
+        Ok(prices)
+    }
+
+    async fn get_token_prices_fresh(
+        &self,
+        token_ids: &[&str],
+    ) -> Result<HashMap<String, TokenPrice>> {
+        let prices = self.inner.get_token_prices(token_ids).await?;
+        for (token_id, price) in prices.iter() {
+            self.cache_price(token_id, price);
+            self.record_history(token_id, price);
+        }
+
+        Ok(prices)
+    }
+
+    async fn get_prices(&self, vs_token: Option<&str>) -> Result<HashMap<String, f64>> {
+        self.inner.get_prices(vs_token).await
+    }
+
+    async fn get_price_history(&self, token_id: &str) -> Vec<f64> {
+        let history = self.history.read().unwrap();
+        history
+            .get(token_id)
+            .map(|points| points.iter().map(|(_, price)| *price).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const TOKEN: &str = "Token11111111111111111111111111111111111111";
+    const OTHER_TOKEN: &str = "Other11111111111111111111111111111111111111";
+
+    // Counts the prices requested from the underlying service
+    #[derive(Default)]
+    struct CountingPrices {
+        requested: AtomicUsize,
+    }
+
+    fn price(token_id: &str) -> TokenPrice {
+        TokenPrice {
+            token_id: token_id.to_string(),
+            symbol: "TKN".to_string(),
+            price_in_sol: 0.5,
+            price_in_usdc: 75.0,
+            timestamp: 0,
+            price_change_24h: None,
+        }
+    }
+
+    #[async_trait]
+    impl PriceService for CountingPrices {
+        async fn get_sol_price(&self) -> Result<f64> {
+            Ok(150.0)
+        }
+
+        async fn get_token_price(&self, token_id: &str) -> Result<TokenPrice> {
+            self.requested.fetch_add(1, Ordering::SeqCst);
+            Ok(price(token_id))
+        }
+
+        async fn get_token_prices(
+            &self,
+            token_ids: &[&str],
+        ) -> Result<HashMap<String, TokenPrice>> {
+            self.requested.fetch_add(token_ids.len(), Ordering::SeqCst);
+            Ok(token_ids
+                .iter()
+                .map(|token_id| (token_id.to_string(), price(token_id)))
+                .collect())
+        }
+
+        async fn get_prices(&self, _vs_token: Option<&str>) -> Result<HashMap<String, f64>> {
+            Ok(HashMap::new())
+        }
+    }
+
+    fn requested(service: &CachedPriceService<CountingPrices>) -> usize {
+        service.inner.requested.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn second_call_within_the_ttl_is_served_from_the_cache() {
+        let service = CachedPriceService::new(CountingPrices::default());
+
+        service.get_token_price(TOKEN).await.unwrap();
+        service.get_token_price(TOKEN).await.unwrap();
+
+        assert_eq!(requested(&service), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_price_is_requested_again() {
+        let service = CachedPriceService::with_ttl(CountingPrices::default(), Duration::ZERO);
+
+        service.get_token_price(TOKEN).await.unwrap();
+        service.get_token_price(TOKEN).await.unwrap();
+
+        assert_eq!(requested(&service), 2);
+    }
+
+    #[tokio::test]
+    async fn fresh_price_skips_the_cache() {
+        let service = CachedPriceService::new(CountingPrices::default());
+        service.get_token_prices(&[TOKEN]).await.unwrap();
+
+        service.get_token_price_fresh(TOKEN).await.unwrap();
+
+        assert_eq!(requested(&service), 2);
+    }
+
+    #[tokio::test]
+    async fn fresh_prices_skip_the_cache() {
+        let service = CachedPriceService::new(CountingPrices::default());
+        service.get_token_prices(&[TOKEN]).await.unwrap();
+
+        service.get_token_prices_fresh(&[TOKEN]).await.unwrap();
+        // The fresh prices are cached for the next cached lookup
+        service.get_token_prices(&[TOKEN]).await.unwrap();
+
+        assert_eq!(requested(&service), 2);
+    }
+
+    #[tokio::test]
+    async fn batch_only_requests_tokens_missing_from_the_cache() {
+        let service = CachedPriceService::new(CountingPrices::default());
+        service.get_token_price(TOKEN).await.unwrap();
+
+        let prices = service
+            .get_token_prices(&[TOKEN, OTHER_TOKEN])
+            .await
+            .unwrap();
+
+        assert_eq!(prices.len(), 2);
+        assert_eq!(requested(&service), 2);
+    }
+}
@@ -1,12 +1,27 @@
+use anyhow::{anyhow, Result};
+use reqwest::Url;
+
+const DEFAULT_QUOTE_API_URL: &str = "https://quote-api.jup.ag/v6";
+const DEFAULT_PRICE_CHANGE_API_URL: &str = "https://lite-api.jup.ag/price/v3";
+
 /// Application configuration
+///
+/// The Jupiter endpoints can point to a self-hosted Jupiter API with JUPITER_QUOTE_URL,
+/// JUPITER_SWAP_URL and JUPITER_PRICE_URL.
 #[derive(Debug, Clone)]
 pub struct Config {
     /// URL for quote API
     pub quote_api_url: String,
 
+    /// URL for swap API, the quote API serves swaps too unless set
+    pub swap_api_url: String,
+
     /// URL for price API
     pub price_api_url: String,
 
+    /// URL for the price API that reports USD prices and the 24h change of many tokens at once
+    pub price_change_api_url: String,
+
     /// SOL token address (wrapped)
     pub sol_token_address: String,
 
@@ -17,8 +32,10 @@ pub struct Config {
 impl Default for Config {
     fn default() -> Self {
         Self {
-            quote_api_url: "https://quote-api.jup.ag/v6".to_string(),
+            quote_api_url: DEFAULT_QUOTE_API_URL.to_string(),
+            swap_api_url: DEFAULT_QUOTE_API_URL.to_string(),
             price_api_url: "https://price.jup.ag/v1".to_string(),
+            price_change_api_url: DEFAULT_PRICE_CHANGE_API_URL.to_string(),
             sol_token_address: "So11111111111111111111111111111111111111112".to_string(),
             usdc_token_address: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
         }
@@ -30,15 +47,48 @@ impl Config {
     pub fn from_env() -> Self {
         use std::env;
 
+        // JUPITER_* take precedence over the older names
+        let url_var = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| env::var(name).ok())
+                .map(|url| url.trim().trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty())
+        };
+
+        let quote_api_url = url_var(&["JUPITER_QUOTE_URL", "QUOTE_API_URL"])
+            .unwrap_or_else(|| DEFAULT_QUOTE_API_URL.to_string());
+
         Self {
-            quote_api_url: env::var("QUOTE_API_URL")
-                .unwrap_or_else(|_| "https://quote-api.jup.ag/v6".to_string()),
+            swap_api_url: url_var(&["JUPITER_SWAP_URL"]).unwrap_or_else(|| quote_api_url.clone()),
+            quote_api_url,
             price_api_url: env::var("PRICE_API_URL")
                 .unwrap_or_else(|_| "https://price.jup.ag/v1".to_string()),
+            price_change_api_url: url_var(&["JUPITER_PRICE_URL", "PRICE_CHANGE_API_URL"])
+                .unwrap_or_else(|| DEFAULT_PRICE_CHANGE_API_URL.to_string()),
             sol_token_address: env::var("SOL_TOKEN_ADDRESS")
                 .unwrap_or_else(|_| "So11111111111111111111111111111111111111112".to_string()),
             usdc_token_address: env::var("USDC_TOKEN_ADDRESS")
                 .unwrap_or_else(|_| "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string()),
         }
     }
+
+    /// Check that the Jupiter endpoints are http(s) URLs, run at startup
+    pub fn validate(&self) -> Result<()> {
+        let urls = [
+            ("JUPITER_QUOTE_URL", &self.quote_api_url),
+            ("JUPITER_SWAP_URL", &self.swap_api_url),
+            ("JUPITER_PRICE_URL", &self.price_change_api_url),
+            ("PRICE_API_URL", &self.price_api_url),
+        ];
+
+        for (name, url) in urls {
+            let parsed = Url::parse(url).map_err(|e| anyhow!("{} \"{}\": {}", name, url, e))?;
+            if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+                return Err(anyhow!("{} \"{}\" must be an http(s) URL", name, url));
+            }
+        }
+
+        Ok(())
+    }
 }
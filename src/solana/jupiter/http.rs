@@ -0,0 +1,131 @@
+use anyhow::anyhow;
+use lazy_static::lazy_static;
+use reqwest::Client;
+use std::env;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::entity::BotError;
+
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    static ref CONNECT_TIMEOUT: Duration = timeout_from_env("JUPITER_CONNECT_TIMEOUT_SECS")
+        .unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+    static ref REQUEST_TIMEOUT: Duration = timeout_from_env("JUPITER_REQUEST_TIMEOUT_SECS")
+        .unwrap_or(DEFAULT_REQUEST_TIMEOUT);
+    // One connection pool for all Jupiter services
+    static ref HTTP_CLIENT: Client = client_with_timeouts(*CONNECT_TIMEOUT, *REQUEST_TIMEOUT);
+}
+
+fn client_with_timeouts(connect_timeout: Duration, request_timeout: Duration) -> Client {
+    Client::builder()
+        .connect_timeout(connect_timeout)
+        .timeout(request_timeout)
+        .build()
+        .unwrap_or_else(|_| Client::new())
+}
+
+fn timeout_from_env(name: &str) -> Option<Duration> {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+}
+
+/// HTTP client for Jupiter APIs, a hung endpoint fails after the configured timeouts
+pub fn http_client() -> Client {
+    HTTP_CLIENT.clone()
+}
+
+/// Error for a failed request, timeouts and connection failures become `JupiterUnavailable`
+pub fn request_error(what: &str, error: reqwest::Error) -> anyhow::Error {
+    if error.is_timeout() || error.is_connect() {
+        anyhow!(BotError::JupiterUnavailable(format!("{}: {}", what, error)))
+    } else {
+        anyhow!("{}: {}", what, error)
+    }
+}
+
+/// Limit a call through the Jupiter SDK, which has no timeouts of its own
+pub async fn with_timeout<F: Future>(what: &str, future: F) -> anyhow::Result<F::Output> {
+    timeout_after(*REQUEST_TIMEOUT, what, future).await
+}
+
+async fn timeout_after<F: Future>(
+    limit: Duration,
+    what: &str,
+    future: F,
+) -> anyhow::Result<F::Output> {
+    tokio::time::timeout(limit, future).await.map_err(|_| {
+        anyhow!(BotError::JupiterUnavailable(format!(
+            "{} timed out after {}s",
+            what,
+            limit.as_secs()
+        )))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    // Accepts connections and never answers
+    async fn hung_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/quote", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let mut sockets = Vec::new();
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                sockets.push(socket);
+            }
+        });
+
+        url
+    }
+
+    fn is_jupiter_unavailable(error: &anyhow::Error) -> bool {
+        matches!(
+            error.downcast_ref::<BotError>(),
+            Some(BotError::JupiterUnavailable(_))
+        )
+    }
+
+    #[tokio::test]
+    async fn request_to_a_hung_endpoint_times_out_as_unavailable() {
+        let url = hung_server().await;
+        let client = client_with_timeouts(Duration::from_secs(1), Duration::from_millis(200));
+
+        let error = client.get(&url).send().await.unwrap_err();
+        assert!(error.is_timeout());
+
+        let error = request_error("HTTP request failed", error);
+        assert!(is_jupiter_unavailable(&error), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn sdk_call_past_the_limit_times_out_as_unavailable() {
+        let call = tokio::time::sleep(Duration::from_secs(5));
+
+        let error = timeout_after(Duration::from_millis(50), "Jupiter quote", call)
+            .await
+            .unwrap_err();
+
+        assert!(is_jupiter_unavailable(&error), "{}", error);
+        assert!(error.to_string().contains("Jupiter quote timed out"));
+    }
+
+    #[tokio::test]
+    async fn sdk_call_within_the_limit_returns_its_output() {
+        let output = timeout_after(Duration::from_secs(5), "Jupiter quote", async { 7 })
+            .await
+            .unwrap();
+
+        assert_eq!(output, 7);
+    }
+}
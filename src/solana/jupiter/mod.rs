@@ -1,18 +1,26 @@
+pub mod cached_price_service;
 pub mod config;
+pub mod http;
 pub mod models;
+pub mod platform_fee;
 pub mod price_service;
 pub mod quote_service;
 pub mod route_service;
 pub mod swap_service;
+#[cfg(test)]
+pub(crate) mod testing;
 pub mod token_repository;
 
 // Re-export commonly used items
 pub use models::{
     JupiterToken, PrioritizationFeeLamports, PrioritizationFeeLamportsWrapper, QuoteParams,
-    QuoteResponse, RoutePlan, SwapInfo, SwapMode, SwapRequest, SwapResponse, SOL_MINT, USDC_MINT,
+    QuoteResponse, RouteOptions, RoutePlan, SwapInfo, SwapMode, SwapRequest, SwapResponse,
+    SOL_MINT, USDC_MINT,
 };
 
+pub use cached_price_service::CachedPriceService;
 pub use config::Config;
+pub use platform_fee::{FeeCollection, PlatformFee};
 pub use price_service::PriceService;
 pub use quote_service::QuoteService;
 pub use route_service::RouteService;
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 // src/solana/jupiter/models.rs
+use crate::solana::tokens::transaction::bumped_priority_fee;
 use anyhow::anyhow;
+use jupiter_swap_api_client::transaction_config::PrioritizationFeeLamports as JupiterPrioritizationFeeLamports;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
@@ -108,6 +110,17 @@ pub struct JupiterToken {
     pub decimals: u8,
     #[serde(rename = "logoURI")]
     pub logo_uri: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl JupiterToken {
+    /// Whether Jupiter lists the token as verified
+    pub fn is_verified(&self) -> bool {
+        self.tags
+            .iter()
+            .any(|tag| tag == "verified" || tag == "strict")
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -136,6 +149,20 @@ pub struct QuoteParams {
     pub only_direct_routes: Option<bool>,
     pub exclude_dexes: Option<Vec<String>>,
     pub max_accounts: Option<u64>,
+    pub platform_fee_bps: Option<u8>,
+}
+
+/// Route restrictions from the user's advanced swap settings, the default keeps Jupiter's routing
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct RouteOptions {
+    // Single-hop routes only, for wallets that hit the transaction size limit
+    pub only_direct_routes: bool,
+    // Most accounts a route may use, None leaves Jupiter's default of 64
+    pub max_accounts: Option<u64>,
+    // Slots a swap transaction stays valid for, None keeps the full blockhash lifetime
+    pub validity_slots: Option<u64>,
+    // Operator fee Jupiter takes from the swap, never set from user settings
+    pub platform_fee_bps: Option<u8>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -181,6 +208,29 @@ pub enum PrioritizationFeeLamports {
     Exact { lamports: u64 },
 }
 
+impl PrioritizationFeeLamports {
+    /// Fee for resubmitting a dropped swap, Jupiter already adjusts an automatic fee
+    pub fn bumped(&self, attempt: u32) -> Self {
+        match self {
+            PrioritizationFeeLamports::Auto => PrioritizationFeeLamports::Auto,
+            PrioritizationFeeLamports::Exact { lamports } => PrioritizationFeeLamports::Exact {
+                lamports: bumped_priority_fee(*lamports, attempt),
+            },
+        }
+    }
+}
+
+impl From<PrioritizationFeeLamports> for JupiterPrioritizationFeeLamports {
+    fn from(fee: PrioritizationFeeLamports) -> Self {
+        match fee {
+            PrioritizationFeeLamports::Auto => JupiterPrioritizationFeeLamports::Auto,
+            PrioritizationFeeLamports::Exact { lamports } => {
+                JupiterPrioritizationFeeLamports::Lamports(lamports)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SwapRequest {
@@ -0,0 +1,90 @@
+use log::warn;
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::native_mint;
+use std::env;
+use std::fmt;
+
+use crate::solana::wallet::parse_pubkey;
+
+/// Fee the bot operator takes on buys, a share of the SOL spent
+///
+/// Off unless PLATFORM_FEE_BPS and PLATFORM_FEE_RECIPIENT are both set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlatformFee {
+    /// Basis points of the SOL spent, Jupiter takes at most 255
+    pub bps: u8,
+    /// Wallet the fee is paid to
+    pub recipient: Pubkey,
+}
+
+impl PlatformFee {
+    /// `None` for a zero fee
+    pub fn new(bps: u8, recipient: Pubkey) -> Option<Self> {
+        (bps > 0).then_some(Self { bps, recipient })
+    }
+
+    /// Fee from PLATFORM_FEE_BPS paid to PLATFORM_FEE_RECIPIENT, off when either is unset
+    pub fn from_env() -> Option<Self> {
+        let bps = env::var("PLATFORM_FEE_BPS").ok()?;
+        let bps = match bps.trim().parse::<u8>() {
+            Ok(bps) => bps,
+            Err(_) => {
+                warn!(
+                    "Invalid PLATFORM_FEE_BPS \"{}\", no platform fee is taken",
+                    bps
+                );
+                return None;
+            }
+        };
+        if bps == 0 {
+            return None;
+        }
+
+        let recipient = match env::var("PLATFORM_FEE_RECIPIENT") {
+            Ok(recipient) => parse_pubkey(recipient.trim()),
+            Err(_) => {
+                warn!("PLATFORM_FEE_BPS is set without PLATFORM_FEE_RECIPIENT, no platform fee is taken");
+                return None;
+            }
+        };
+        match recipient {
+            Ok(recipient) => Self::new(bps, recipient),
+            Err(e) => {
+                warn!(
+                    "Invalid PLATFORM_FEE_RECIPIENT, no platform fee is taken: {}",
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Wrapped SOL account of the recipient, Jupiter pays the fee of a buy into it
+    pub fn fee_account(&self) -> Pubkey {
+        get_associated_token_address(&self.recipient, &native_mint::ID)
+    }
+
+    /// Fee on `lamports` spent, rounded down
+    pub fn fee_lamports(&self, lamports: u64) -> u64 {
+        (lamports as u128 * self.bps as u128 / 10_000) as u64
+    }
+}
+
+/// How the platform fee of a buy was collected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeCollection {
+    /// Taken by Jupiter from the swap into the recipient's wrapped SOL account
+    Jupiter,
+    /// Sent in a separate transfer, when the recipient has no wrapped SOL account
+    Transfer,
+}
+
+impl fmt::Display for FeeCollection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeeCollection::Jupiter => write!(f, "JUPITER"),
+            FeeCollection::Transfer => write!(f, "TRANSFER"),
+        }
+    }
+}
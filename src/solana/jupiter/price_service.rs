@@ -1,11 +1,16 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use log::{debug, warn};
 use reqwest::Client;
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+use tokio::time::{sleep_until, Instant};
 
 use crate::entity::TokenPrice;
+use crate::solana::jupiter::http;
 use crate::solana::jupiter::quote_service::QuoteService;
 use crate::solana::jupiter::token_repository::TokenRepository;
 use crate::solana::jupiter::Config;
@@ -16,6 +21,51 @@ struct ErrorResponse {
     error: String,
 }
 
+/// Most mints the price API accepts in one request
+const PRICE_BATCH_SIZE: usize = 50;
+
+/// Price requests in flight at the same time during a batch
+const PRICE_FETCH_CONCURRENCY: usize = 5;
+
+/// Minimum time between two price requests, shared by all concurrent fetches
+const PRICE_REQUEST_SPACING: Duration = Duration::from_millis(100);
+
+// Entry of the price API response, tokens the API doesn't know are left out of the response
+#[derive(Deserialize)]
+struct PriceEntry {
+    #[serde(rename = "usdPrice")]
+    usd_price: f64,
+    #[serde(rename = "priceChange24h")]
+    price_change_24h: Option<f64>,
+}
+
+/// Hands out request slots at least `spacing` apart to any number of tasks
+struct RequestPacer {
+    spacing: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RequestPacer {
+    fn new(spacing: Duration) -> Self {
+        Self {
+            spacing,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    // Wait for the next free slot, the lock isn't held while sleeping
+    async fn wait(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.spacing;
+            slot
+        };
+
+        sleep_until(slot).await;
+    }
+}
+
 /// Interface for token price information service
 #[async_trait]
 pub trait PriceService: Send + Sync {
@@ -25,8 +75,44 @@ pub trait PriceService: Send + Sync {
     /// Get token price in SOL and USDC
     async fn get_token_price(&self, token_id: &str) -> Result<TokenPrice>;
 
+    /// Get token price bypassing any cache, for callers that are about to trade
+    async fn get_token_price_fresh(&self, token_id: &str) -> Result<TokenPrice> {
+        self.get_token_price(token_id).await
+    }
+
+    /// Get prices of several tokens at once, keyed by mint
+    ///
+    /// Tokens without a price are left out of the map instead of failing the whole batch.
+    async fn get_token_prices(&self, token_ids: &[&str]) -> Result<HashMap<String, TokenPrice>> {
+        let mut prices = HashMap::new();
+
+        for token_id in token_ids {
+            match self.get_token_price(token_id).await {
+                Ok(price) => {
+                    prices.insert(token_id.to_string(), price);
+                }
+                Err(e) => debug!("No price for {}: {}", token_id, e),
+            }
+        }
+
+        Ok(prices)
+    }
+
+    /// Get prices of several tokens at once bypassing any cache, like `get_token_prices`
+    async fn get_token_prices_fresh(
+        &self,
+        token_ids: &[&str],
+    ) -> Result<HashMap<String, TokenPrice>> {
+        self.get_token_prices(token_ids).await
+    }
+
     /// Get prices for multiple tokens
     async fn get_prices(&self, vs_token: Option<&str>) -> Result<HashMap<String, f64>>;
+
+    /// Recent USD prices of the token, oldest first, empty when no history is kept
+    async fn get_price_history(&self, _token_id: &str) -> Vec<f64> {
+        Vec::new()
+    }
 }
 
 /// Implementation of price service using Jupiter API
@@ -35,6 +121,7 @@ pub struct JupiterPriceService<T: TokenRepository, Q: QuoteService> {
     quote_service: Q,
     http_client: Client,
     config: Config,
+    request_pacer: RequestPacer,
     sol_usdc_price: f64,
 }
 
@@ -44,12 +131,19 @@ impl<T: TokenRepository, Q: QuoteService> JupiterPriceService<T, Q> {
         Self {
             token_repository,
             quote_service,
-            http_client: Client::new(),
+            http_client: http::http_client(),
             config,
+            request_pacer: RequestPacer::new(PRICE_REQUEST_SPACING),
             sol_usdc_price: 0.0, // Will be updated on first call
         }
     }
 
+    /// Sets the minimum time between two price requests
+    pub fn with_request_spacing(mut self, spacing: Duration) -> Self {
+        self.request_pacer = RequestPacer::new(spacing);
+        self
+    }
+
     /// Updates cached SOL price in USDC
     async fn refresh_sol_price(&self) -> Result<f64> {
         // Get quote using QuoteService
@@ -69,6 +163,165 @@ impl<T: TokenRepository, Q: QuoteService> JupiterPriceService<T, Q> {
         Ok(sol_price_in_usdc)
     }
 
+    /// USD prices of up to `PRICE_BATCH_SIZE` tokens from the price API
+    async fn fetch_usd_prices(&self, token_ids: &[&str]) -> Result<HashMap<String, PriceEntry>> {
+        self.request_pacer.wait().await;
+
+        let url = format!(
+            "{}?ids={}",
+            self.config.price_change_api_url,
+            token_ids.join(",")
+        );
+
+        let response = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| http::request_error("HTTP request failed", e))?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Price API error: {}", response.status()));
+        }
+
+        // Unknown tokens are missing or null, both are skipped
+        let entries: HashMap<String, Option<PriceEntry>> = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to parse prices response: {}", e))?;
+
+        Ok(entries
+            .into_iter()
+            .filter_map(|(token_id, entry)| entry.map(|entry| (token_id, entry)))
+            .filter(|(_, entry)| entry.usd_price.is_finite() && entry.usd_price > 0.0)
+            .collect())
+    }
+
+    /// Price of a token the price API doesn't know, from a quote of 1 token to SOL
+    async fn quote_token_price(&self, token_id: &str, sol_usdc_price: f64) -> Result<TokenPrice> {
+        let token = self.token_repository.get_token_by_id(token_id).await?;
+
+        // Get quote for exchanging 1 unit of token to SOL
+        self.request_pacer.wait().await;
+        let quote = self
+            .quote_service
+            .get_swap_quote(
+                1.0,
+                token_id,
+                &self.config.sol_token_address,
+                0.5, // 0.5% slippage
+            )
+            .await?;
+
+        // Convert to SOL considering decimals (9)
+        let price_in_sol = quote.out_amount as f64 / 1_000_000_000.0;
+
+        Ok(TokenPrice {
+            token_id: token_id.to_string(),
+            symbol: token.symbol,
+            price_in_sol,
+            price_in_usdc: price_in_sol * sol_usdc_price,
+            timestamp: now_secs(),
+            price_change_24h: None,
+        })
+    }
+
+    /// Price of every requested token, with the error of each token that couldn't be priced
+    ///
+    /// Prices in SOL are derived from the USD prices. Tokens the price API doesn't know,
+    /// e.g. freshly launched ones, are priced with a quote instead.
+    async fn price_tokens(
+        &self,
+        token_ids: &[&str],
+    ) -> Result<HashMap<String, Result<TokenPrice>>> {
+        let sol_token = self.config.sol_token_address.as_str();
+
+        let mut requested: Vec<&str> = token_ids.to_vec();
+        requested.sort_unstable();
+        requested.dedup();
+
+        // SOL is always requested, it converts the USD prices to SOL
+        let mut batch = requested.clone();
+        if !batch.contains(&sol_token) {
+            batch.push(sol_token);
+        }
+
+        // Up to PRICE_FETCH_CONCURRENCY requests at a time, paced by the shared rate limit
+        let requests: Vec<_> = batch
+            .chunks(PRICE_BATCH_SIZE)
+            .map(|chunk| self.fetch_usd_prices(chunk))
+            .collect();
+        let responses: Vec<_> = stream::iter(requests)
+            .buffer_unordered(PRICE_FETCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut usd_prices = HashMap::new();
+        for response in responses {
+            match response {
+                Ok(entries) => usd_prices.extend(entries),
+                Err(e) => warn!("Batch price request failed, falling back to quotes: {}", e),
+            }
+        }
+
+        let sol_entry = usd_prices.get(sol_token);
+        let sol_usdc_price = match sol_entry.map(|entry| entry.usd_price) {
+            Some(price) => price,
+            None => self.get_sol_price().await?,
+        };
+
+        // Tokens missing from the price API are quoted, with the same bound and pacing
+        let lookups: Vec<_> = requested
+            .iter()
+            .filter(|token_id| **token_id != sol_token)
+            .map(|token_id| self.price_token(token_id, &usd_prices, sol_usdc_price))
+            .collect();
+        let mut prices: HashMap<_, _> = stream::iter(lookups)
+            .buffer_unordered(PRICE_FETCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        if requested.contains(&sol_token) {
+            let price = TokenPrice {
+                token_id: sol_token.to_string(),
+                symbol: "SOL".to_string(),
+                price_in_sol: 1.0,
+                price_in_usdc: sol_usdc_price,
+                timestamp: now_secs(),
+                price_change_24h: sol_entry.and_then(|entry| entry.price_change_24h),
+            };
+            prices.insert(sol_token.to_string(), Ok(price));
+        }
+
+        Ok(prices)
+    }
+
+    /// Price of a token other than SOL, from its USD price or from a quote
+    async fn price_token(
+        &self,
+        token_id: &str,
+        usd_prices: &HashMap<String, PriceEntry>,
+        sol_usdc_price: f64,
+    ) -> (String, Result<TokenPrice>) {
+        let price = match usd_prices.get(token_id) {
+            Some(entry) => self
+                .token_repository
+                .get_token_by_id(token_id)
+                .await
+                .map(|token| TokenPrice {
+                    token_id: token_id.to_string(),
+                    symbol: token.symbol,
+                    price_in_sol: entry.usd_price / sol_usdc_price,
+                    price_in_usdc: entry.usd_price,
+                    timestamp: now_secs(),
+                    price_change_24h: entry.price_change_24h.filter(|change| change.is_finite()),
+                }),
+            None => self.quote_token_price(token_id, sol_usdc_price).await,
+        };
+
+        (token_id.to_string(), price)
+    }
+
     /// Checks API response for errors
     fn check_for_api_error<D>(&self, value: serde_json::Value) -> Result<D>
     where
@@ -96,56 +349,29 @@ impl<T: TokenRepository + Send + Sync, Q: QuoteService + Send + Sync> PriceServi
     }
 
     /// Get token price in SOL and USDC
+    ///
+    /// Errors are the token's own, e.g. `BotError::TokenNotTradable` when no route exists.
     async fn get_token_price(&self, token_id: &str) -> Result<TokenPrice> {
-        // If we are requesting SOL price, return known values
-        if token_id == self.config.sol_token_address {
-            let sol_price = self.get_sol_price().await?;
-
-            return Ok(TokenPrice {
-                token_id: self.config.sol_token_address.clone(),
-                symbol: "SOL".to_string(),
-                price_in_sol: 1.0,
-                price_in_usdc: sol_price,
-                timestamp: SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs(),
-            });
-        }
-
-        // Get token information
-        let token = self.token_repository.get_token_by_id(token_id).await?;
-
-        // Get quote for exchanging 1 unit of token to SOL
-        let quote = self
-            .quote_service
-            .get_swap_quote(
-                1.0,
-                token_id,
-                &self.config.sol_token_address,
-                0.5, // 0.5% slippage
-            )
-            .await?;
-
-        // Convert to SOL considering decimals (9)
-        let price_in_sol = quote.out_amount as f64 / 1_000_000_000.0;
-
-        // Get current SOL/USDC price if needed
-        let sol_usdc_price = self.get_sol_price().await?;
+        self.price_tokens(&[token_id])
+            .await?
+            .remove(token_id)
+            .unwrap_or_else(|| Err(anyhow!("No price available for token {}", token_id)))
+    }
 
-        // Calculate price in USDC
-        let price_in_usdc = price_in_sol * sol_usdc_price;
+    /// Get prices of several tokens with one price API request per `PRICE_BATCH_SIZE` tokens
+    async fn get_token_prices(&self, token_ids: &[&str]) -> Result<HashMap<String, TokenPrice>> {
+        let prices = self.price_tokens(token_ids).await?;
 
-        Ok(TokenPrice {
-            token_id: token_id.to_string(),
-            symbol: token.symbol,
-            price_in_sol,
-            price_in_usdc,
-            timestamp: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-        })
+        Ok(prices
+            .into_iter()
+            .filter_map(|(token_id, price)| match price {
+                Ok(price) => Some((token_id, price)),
+                Err(e) => {
+                    debug!("No price for {}: {}", token_id, e);
+                    None
+                }
+            })
+            .collect())
     }
 
     /// Get prices for multiple tokens
@@ -160,7 +386,7 @@ impl<T: TokenRepository + Send + Sync, Q: QuoteService + Send + Sync> PriceServi
             .get(&url)
             .send()
             .await
-            .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+            .map_err(|e| http::request_error("HTTP request failed", e))?;
 
         if !response.status().is_success() {
             let error_text = response
@@ -179,3 +405,215 @@ impl<T: TokenRepository + Send + Sync, Q: QuoteService + Send + Sync> PriceServi
         Ok(price_data)
     }
 }
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{BotError, Token, TokenRisk};
+    use crate::solana::jupiter::models::RouteOptions;
+    use crate::testing::{fake_http, HttpResponse};
+    use jupiter_swap_api_client::quote::QuoteResponse;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const PRICED_TOKEN: &str = "Priced1111111111111111111111111111111111111";
+    const NO_ROUTE_TOKEN: &str = "NoRoute111111111111111111111111111111111111";
+
+    struct TestTokens;
+
+    #[async_trait]
+    impl TokenRepository for TestTokens {
+        async fn get_token_by_id(&self, token_id: &str) -> Result<Token> {
+            Ok(Token {
+                id: token_id.to_string(),
+                symbol: token_id[..4].to_uppercase(),
+                name: token_id.to_string(),
+                decimals: 6,
+                logo_uri: String::new(),
+                verified: false,
+            })
+        }
+
+        async fn search_tokens(&self, _query: &str) -> Result<Vec<Token>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_token_risk(&self, _mint: &str) -> Result<TokenRisk> {
+            Err(anyhow!("not used"))
+        }
+    }
+
+    // No token has a swap route
+    struct NoRoutes;
+
+    #[async_trait]
+    impl QuoteService for NoRoutes {
+        async fn get_swap_quote_with_route(
+            &self,
+            _amount: f64,
+            _source_token: &str,
+            _target_token: &str,
+            _slippage: f64,
+            _route: RouteOptions,
+        ) -> Result<QuoteResponse> {
+            Err(anyhow!(BotError::TokenNotTradable))
+        }
+    }
+
+    // Requests seen by the test price API
+    #[derive(Default)]
+    struct PriceApiStats {
+        requests: AtomicUsize,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    // Price API answering with the given USD prices after `delay`, unknown mints are left out
+    async fn serve_prices(
+        usd_prices: HashMap<String, f64>,
+        delay: Duration,
+    ) -> (String, Arc<PriceApiStats>) {
+        let stats = Arc::new(PriceApiStats::default());
+        let server_stats = stats.clone();
+
+        let url = fake_http(move |request| {
+            let body: serde_json::Map<String, serde_json::Value> = request
+                .path
+                .split_once("ids=")
+                .map(|(_, ids)| ids)
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|id| {
+                    usd_prices
+                        .get(id)
+                        .map(|price| (id.to_string(), serde_json::json!({ "usdPrice": price })))
+                })
+                .collect();
+            let stats = server_stats.clone();
+
+            async move {
+                stats.requests.fetch_add(1, Ordering::SeqCst);
+                let in_flight = stats.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                stats.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+                tokio::time::sleep(delay).await;
+                stats.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                HttpResponse::ok(serde_json::Value::Object(body).to_string())
+            }
+        })
+        .await;
+
+        (format!("{}/price", url), stats)
+    }
+
+    async fn price_service(
+        usd_prices: &[(&str, f64)],
+        delay: Duration,
+    ) -> (
+        JupiterPriceService<TestTokens, NoRoutes>,
+        Arc<PriceApiStats>,
+    ) {
+        let usd_prices = usd_prices
+            .iter()
+            .map(|(token_id, price)| (token_id.to_string(), *price))
+            .collect();
+        let (url, stats) = serve_prices(usd_prices, delay).await;
+        let config = Config {
+            price_change_api_url: url,
+            ..Config::default()
+        };
+
+        (
+            JupiterPriceService::new(TestTokens, NoRoutes, config),
+            stats,
+        )
+    }
+
+    #[tokio::test]
+    async fn token_without_a_route_fails_with_token_not_tradable() {
+        let sol_token = Config::default().sol_token_address;
+        let (service, _) = price_service(&[(sol_token.as_str(), 150.0)], Duration::ZERO).await;
+
+        let error = service.get_token_price(NO_ROUTE_TOKEN).await.unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<BotError>(),
+            Some(BotError::TokenNotTradable)
+        ));
+        assert_eq!(
+            BotError::token_info_message(&error),
+            BotError::TokenNotTradable.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_leaves_out_tokens_without_a_price() {
+        let sol_token = Config::default().sol_token_address;
+        let (service, _) = price_service(
+            &[(sol_token.as_str(), 150.0), (PRICED_TOKEN, 3.0)],
+            Duration::ZERO,
+        )
+        .await;
+
+        let prices = service
+            .get_token_prices(&[PRICED_TOKEN, NO_ROUTE_TOKEN])
+            .await
+            .unwrap();
+
+        assert_eq!(prices.len(), 1);
+        let price = &prices[PRICED_TOKEN];
+        assert_eq!(price.symbol, "PRIC");
+        assert_eq!(price.price_in_usdc, 3.0);
+        assert!((price.price_in_sol - 0.02).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn batch_requests_stay_within_the_concurrency_bound() {
+        let sol_token = Config::default().sol_token_address;
+        let mints: Vec<String> = (0..300).map(|i| format!("Mint{:040}", i)).collect();
+        let mut usd_prices: Vec<(&str, f64)> = mints
+            .iter()
+            .enumerate()
+            .map(|(i, mint)| (mint.as_str(), (i + 1) as f64))
+            .collect();
+        usd_prices.push((sol_token.as_str(), 100.0));
+        let (service, stats) = price_service(&usd_prices, Duration::from_millis(50)).await;
+        let service = service.with_request_spacing(Duration::ZERO);
+
+        let token_ids: Vec<&str> = mints.iter().map(String::as_str).collect();
+        let prices = service.get_token_prices(&token_ids).await.unwrap();
+
+        // 300 mints and SOL make 7 requests of at most PRICE_BATCH_SIZE mints
+        assert_eq!(stats.requests.load(Ordering::SeqCst), 7);
+        let max_in_flight = stats.max_in_flight.load(Ordering::SeqCst);
+        assert!(max_in_flight > 1, "requests weren't concurrent");
+        assert!(max_in_flight <= PRICE_FETCH_CONCURRENCY);
+
+        // Every token gets its own price, however the responses interleave
+        assert_eq!(prices.len(), mints.len());
+        for (i, mint) in mints.iter().enumerate() {
+            let price = &prices[mint.as_str()];
+            assert_eq!(price.token_id, *mint);
+            assert_eq!(price.price_in_usdc, (i + 1) as f64);
+            assert!((price.price_in_sol - (i + 1) as f64 / 100.0).abs() < 1e-12);
+        }
+    }
+
+    #[tokio::test]
+    async fn request_pacer_spaces_concurrent_requests() {
+        let pacer = RequestPacer::new(Duration::from_millis(50));
+        let started = Instant::now();
+
+        futures::future::join_all((0..4).map(|_| pacer.wait())).await;
+
+        // The first slot is free, the other three wait for one spacing each
+        assert!(started.elapsed() >= Duration::from_millis(150));
+    }
+}
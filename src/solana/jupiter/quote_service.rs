@@ -1,4 +1,8 @@
+use crate::entity::BotError;
+use crate::solana::jupiter::http;
+use crate::solana::jupiter::models::{QuoteParams, RouteOptions};
 use crate::solana::jupiter::token_repository::TokenRepository;
+use crate::solana::tokens::to_base_units;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use jupiter_swap_api_client::quote::{QuoteRequest, QuoteResponse};
@@ -7,6 +11,23 @@ use log::{debug, info};
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 
+// Jupiter error codes and messages for tokens without a swap route, matched lowercase
+const NO_ROUTE_MARKERS: [&str; 5] = [
+    "could_not_find_any_route",
+    "could not find any route",
+    "no_routes_found",
+    "token_not_tradable",
+    "is not tradable",
+];
+
+/// Whether a quote error means the token has no route at all, as opposed to a transient failure
+pub fn is_no_route_error(error_message: &str) -> bool {
+    let message = error_message.to_lowercase();
+    NO_ROUTE_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
 /// Service for getting token exchange quotes
 #[async_trait]
 pub trait QuoteService: Send + Sync {
@@ -16,6 +37,25 @@ pub trait QuoteService: Send + Sync {
         source_token: &str,
         target_token: &str,
         slippage: f64,
+    ) -> Result<QuoteResponse> {
+        self.get_swap_quote_with_route(
+            amount,
+            source_token,
+            target_token,
+            slippage,
+            RouteOptions::default(),
+        )
+        .await
+    }
+
+    /// Quote restricted to the routes allowed by the user's advanced swap settings
+    async fn get_swap_quote_with_route(
+        &self,
+        amount: f64,
+        source_token: &str,
+        target_token: &str,
+        slippage: f64,
+        route: RouteOptions,
     ) -> Result<QuoteResponse>;
 }
 
@@ -32,17 +72,24 @@ impl<T: TokenRepository> JupiterQuoteService<T> {
             jupiter_client: JupiterSwapApiClient::new("https://quote-api.jup.ag/v6".to_string()),
         }
     }
+
+    /// Use another Jupiter API, e.g. a self-hosted one
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.jupiter_client = JupiterSwapApiClient::new(api_url.to_string());
+        self
+    }
 }
 
 #[async_trait]
 impl<T: TokenRepository + Send + Sync> QuoteService for JupiterQuoteService<T> {
     /// Gets a quote for token exchange
-    async fn get_swap_quote(
+    async fn get_swap_quote_with_route(
         &self,
         amount: f64,
         source_token: &str,
         target_token: &str,
         slippage: f64,
+        route: RouteOptions,
     ) -> Result<QuoteResponse> {
         // Get token information to determine decimals
         let source_token_info = &self
@@ -50,37 +97,33 @@ impl<T: TokenRepository + Send + Sync> QuoteService for JupiterQuoteService<T> {
             .get_token_by_id(&source_token.to_string())
             .await?;
 
-        // Convert amount considering decimals
-        let decimals = source_token_info.decimals as u32;
-        let amount_in = (amount * 10f64.powi(decimals as i32)) as u64;
-
-        // Convert slippage to basis points
-        let slippage_bps = (slippage * 10000.0) as u16;
+        // Convert amount into base units of the source token
+        let amount_in = to_base_units(amount, source_token_info.decimals);
 
-        // Parse token addresses to Pubkey
-        let input_mint = Pubkey::from_str(source_token)
-            .map_err(|e| anyhow!("Invalid source token address: {}", e))?;
-
-        let output_mint = Pubkey::from_str(target_token)
-            .map_err(|e| anyhow!("Invalid target token address: {}", e))?;
+        if amount_in == 0 {
+            return Err(anyhow!(
+                "Amount {} is below the smallest unit of {}",
+                amount,
+                source_token_info.symbol
+            ));
+        }
 
-        // Create quote request via SDK
-        let quote_request = QuoteRequest {
-            amount: amount_in,
-            input_mint,
-            output_mint,
-            slippage_bps,
-            ..QuoteRequest::default()
-        };
+        let params = quote_params(source_token, target_token, amount_in, slippage, &route);
+        let quote_request = build_quote_request(&params)?;
 
         debug!("Requesting quote with parameters: {:?}", quote_request);
 
         // Send request via SDK
-        let quote_response = self
-            .jupiter_client
-            .quote(&quote_request)
-            .await
-            .map_err(|e| anyhow!("Failed to get quote from Jupiter API: {}", e))?;
+        let quote_response =
+            http::with_timeout("Jupiter quote", self.jupiter_client.quote(&quote_request))
+                .await?
+                .map_err(|e| {
+                    if is_no_route_error(&e.to_string()) {
+                        anyhow!(BotError::TokenNotTradable)
+                    } else {
+                        anyhow!("Failed to get quote from Jupiter API: {}", e)
+                    }
+                })?;
 
         info!(
             "Quote received successfully: input_amount={}, output_amount={}",
@@ -90,3 +133,166 @@ impl<T: TokenRepository + Send + Sync> QuoteService for JupiterQuoteService<T> {
         Ok(quote_response)
     }
 }
+
+/// Slippage fraction (0.005 for 0.5%) in basis points
+///
+/// Rounded, a percentage set in basis points like 0.57% is 56.99... bps as a float.
+pub fn slippage_to_bps(slippage: f64) -> u64 {
+    (slippage * 10000.0).round() as u64
+}
+
+/// Quote parameters for a swap, only the route options the user turned on are sent
+pub fn quote_params(
+    source_token: &str,
+    target_token: &str,
+    amount_in: u64,
+    slippage: f64,
+    route: &RouteOptions,
+) -> QuoteParams {
+    // Slippage is sent in basis points
+    QuoteParams {
+        input_mint: source_token.to_string(),
+        output_mint: target_token.to_string(),
+        amount: amount_in,
+        slippage_bps: slippage_to_bps(slippage),
+        only_direct_routes: route.only_direct_routes.then_some(true),
+        exclude_dexes: None,
+        max_accounts: route.max_accounts,
+        platform_fee_bps: route.platform_fee_bps,
+    }
+}
+
+/// Convert quote parameters into the SDK request, unset route options keep Jupiter's defaults
+pub fn build_quote_request(params: &QuoteParams) -> Result<QuoteRequest> {
+    // Parse token addresses to Pubkey
+    let input_mint = Pubkey::from_str(&params.input_mint)
+        .map_err(|e| anyhow!("Invalid source token address: {}", e))?;
+
+    let output_mint = Pubkey::from_str(&params.output_mint)
+        .map_err(|e| anyhow!("Invalid target token address: {}", e))?;
+
+    Ok(QuoteRequest {
+        amount: params.amount,
+        input_mint,
+        output_mint,
+        slippage_bps: params.slippage_bps as u16,
+        only_direct_routes: params.only_direct_routes,
+        max_accounts: params
+            .max_accounts
+            .map(|max_accounts| max_accounts as usize),
+        platform_fee_bps: params.platform_fee_bps,
+        ..QuoteRequest::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{Token, TokenRisk};
+    use crate::solana::jupiter::testing::fake_jupiter;
+
+    #[test]
+    fn slippage_set_in_basis_points_survives_the_percent_round_trip() {
+        for bps in 10..=500u64 {
+            let percent = bps as f64 / 100.0;
+
+            assert_eq!(slippage_to_bps(percent / 100.0), bps);
+        }
+    }
+
+    const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+    const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn quote_request(route: RouteOptions) -> QuoteRequest {
+        build_quote_request(&quote_params(
+            SOL_MINT,
+            USDC_MINT,
+            1_000_000_000,
+            0.005,
+            &route,
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn direct_routes_sets_the_only_direct_routes_parameter() {
+        let request = quote_request(RouteOptions {
+            only_direct_routes: true,
+            ..RouteOptions::default()
+        });
+
+        assert_eq!(request.only_direct_routes, Some(true));
+    }
+
+    #[test]
+    fn max_accounts_is_passed_to_the_quote_request() {
+        let request = quote_request(RouteOptions {
+            max_accounts: Some(32),
+            ..RouteOptions::default()
+        });
+
+        assert_eq!(request.max_accounts, Some(32));
+        assert_eq!(request.only_direct_routes, None);
+    }
+
+    #[test]
+    fn default_route_options_leave_jupiter_defaults() {
+        let request = quote_request(RouteOptions::default());
+
+        assert_eq!(request.only_direct_routes, None);
+        assert_eq!(request.max_accounts, None);
+        assert_eq!(request.amount, 1_000_000_000);
+        assert_eq!(request.slippage_bps, 50);
+    }
+
+    #[test]
+    fn invalid_mint_is_rejected() {
+        let params = quote_params("not-a-mint", USDC_MINT, 1, 0.005, &RouteOptions::default());
+
+        assert!(build_quote_request(&params).is_err());
+    }
+
+    struct TestTokens;
+
+    #[async_trait]
+    impl TokenRepository for TestTokens {
+        async fn get_token_by_id(&self, token_id: &str) -> Result<Token> {
+            Ok(Token {
+                id: token_id.to_string(),
+                symbol: "SOL".to_string(),
+                name: "Wrapped SOL".to_string(),
+                decimals: 9,
+                logo_uri: String::new(),
+                verified: true,
+            })
+        }
+
+        async fn search_tokens(&self, _query: &str) -> Result<Vec<Token>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_token_risk(&self, _mint: &str) -> Result<TokenRisk> {
+            Err(anyhow!("not used"))
+        }
+    }
+
+    #[tokio::test]
+    async fn quotes_are_requested_from_the_configured_api_url() {
+        let (url, mut calls) = fake_jupiter().await;
+        let service = JupiterQuoteService::new(TestTokens).with_api_url(&url);
+
+        let quote = service
+            .get_swap_quote_with_route(1.0, SOL_MINT, USDC_MINT, 0.005, RouteOptions::default())
+            .await;
+
+        assert!(quote.is_err());
+        let call = calls.recv().await.unwrap();
+        assert_eq!(call.method, "GET");
+        assert!(
+            call.path.starts_with("/self-hosted/quote?"),
+            "{}",
+            call.path
+        );
+        assert!(call.path.contains("amount=1000000000"), "{}", call.path);
+    }
+}
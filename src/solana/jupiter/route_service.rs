@@ -5,6 +5,7 @@ use reqwest::Client;
 use serde::Deserialize;
 use std::collections::HashMap;
 
+use crate::solana::jupiter::http;
 use crate::solana::jupiter::Config;
 
 /// Interface for exchange route service
@@ -24,7 +25,7 @@ impl JupiterRouteService {
     /// Creates a new instance of route service
     pub fn new(config: Config) -> Self {
         Self {
-            http_client: Client::new(),
+            http_client: http::http_client(),
             config,
         }
     }
@@ -53,7 +54,7 @@ impl RouteService for JupiterRouteService {
             .get(&url)
             .send()
             .await
-            .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+            .map_err(|e| http::request_error("HTTP request failed", e))?;
 
         if !response.status().is_success() {
             let error_text = response
@@ -1,5 +1,13 @@
+use crate::entity::BotError;
+use crate::solana::client::ResilientRpcClient;
+use crate::solana::jupiter::http;
+use crate::solana::jupiter::models::{PrioritizationFeeLamports, RouteOptions};
+use crate::solana::jupiter::platform_fee::PlatformFee;
 use crate::solana::jupiter::quote_service::QuoteService;
 use crate::solana::jupiter::token_repository::TokenRepository;
+use crate::solana::tokens::transaction::{
+    dropped_retries, should_resubmit, wait_for_confirmation, SubmittedSender,
+};
 use anyhow::{anyhow, Result};
 use bincode;
 use jupiter_swap_api_client::{
@@ -8,20 +16,37 @@ use jupiter_swap_api_client::{
     transaction_config::TransactionConfig,
     JupiterSwapApiClient,
 };
-use log::{debug, info};
+use log::{debug, info, warn};
 use reqwest::Client as HttpClient;
-use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_address_lookup_table_interface::state::AddressLookupTable;
+use solana_sdk::message::{v0, AddressLookupTableAccount, VersionedMessage};
+use solana_sdk::signature::{Signature, Signer};
 use solana_sdk::transaction::VersionedTransaction;
 use solana_sdk::{pubkey::Pubkey, signature::Keypair};
 use std::collections::HashMap;
+use std::future::Future;
 use std::str::FromStr;
-use std::sync::Arc;
+
+/// The trade a swap is prepared for, `amount` in units of `source_token`
+#[derive(Debug, Clone, Copy)]
+pub struct SwapParams<'a> {
+    pub amount: f64,
+    pub source_token: &'a str,
+    pub target_token: &'a str,
+    pub slippage: f64,
+    pub route: RouteOptions,
+    pub platform_fee: Option<PlatformFee>,
+}
 
 /// Service for performing swap operations using Jupiter
 pub struct SwapService<T: TokenRepository, Q: QuoteService> {
     token_repository: T,
     quote_service: Q,
     jupiter_client: JupiterSwapApiClient,
+    // Request legacy transactions instead of versioned ones
+    as_legacy_transaction: bool,
+    // Operator fee on buys, None when it's off
+    platform_fee: Option<PlatformFee>,
 }
 
 impl<T: TokenRepository, Q: QuoteService> SwapService<T, Q> {
@@ -31,18 +56,82 @@ impl<T: TokenRepository, Q: QuoteService> SwapService<T, Q> {
             token_repository,
             quote_service,
             jupiter_client: JupiterSwapApiClient::new("https://quote-api.jup.ag/v6".to_string()),
+            as_legacy_transaction: false,
+            platform_fee: None,
+        }
+    }
+
+    /// Use another Jupiter API for swaps, e.g. a self-hosted one
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.jupiter_client = JupiterSwapApiClient::new(api_url.to_string());
+        self
+    }
+
+    /// Request legacy transactions, for RPC nodes or wallets without v0 transaction support
+    pub fn with_legacy_transactions(mut self, as_legacy_transaction: bool) -> Self {
+        self.as_legacy_transaction = as_legacy_transaction;
+        self
+    }
+
+    /// Take an operator fee on buys
+    pub fn with_platform_fee(mut self, platform_fee: Option<PlatformFee>) -> Self {
+        self.platform_fee = platform_fee;
+        self
+    }
+
+    /// The configured operator fee, the caller decides whether a swap pays it
+    pub fn platform_fee(&self) -> Option<PlatformFee> {
+        self.platform_fee
+    }
+
+    /// Jupiter transaction settings for a swap
+    ///
+    /// The priority fee is applied if set, otherwise Jupiter defaults are used. With a
+    /// validity set Jupiter picks a blockhash that expires after that many slots, so a swap
+    /// stuck in congestion can't land late at a stale price.
+    fn transaction_config(
+        &self,
+        priority_fee: Option<PrioritizationFeeLamports>,
+        route: &RouteOptions,
+        platform_fee: Option<PlatformFee>,
+    ) -> TransactionConfig {
+        TransactionConfig {
+            prioritization_fee_lamports: priority_fee.map(Into::into),
+            as_legacy_transaction: self.as_legacy_transaction,
+            blockhash_slots_to_expiry: route
+                .validity_slots
+                .and_then(|slots| u8::try_from(slots).ok()),
+            correct_last_valid_block_height: route.validity_slots.is_some(),
+            fee_account: platform_fee.map(|fee| fee.fee_account()),
+            ..TransactionConfig::default()
         }
     }
 
     /// Prepares and retrieves a swap transaction
+    ///
+    /// With a `platform_fee` Jupiter takes the fee from the input into the fee account, so the
+    /// input has to be SOL.
     pub async fn prepare_swap(
         &self,
-        amount: f64,
-        source_token: &str,
-        target_token: &str,
-        slippage: f64,
+        params: &SwapParams<'_>,
+        priority_fee: Option<PrioritizationFeeLamports>,
         user_public_key: &str,
     ) -> Result<SwapResponse> {
+        let SwapParams {
+            amount,
+            source_token,
+            target_token,
+            slippage,
+            route,
+            platform_fee,
+        } = *params;
+
+        // The quote has to include the fee, the swap transaction is built from it
+        let route = RouteOptions {
+            platform_fee_bps: platform_fee.map(|fee| fee.bps),
+            ..route
+        };
+
         // Get quote
         debug!(
             "Getting swap quote for {} {} to {}",
@@ -50,18 +139,20 @@ impl<T: TokenRepository, Q: QuoteService> SwapService<T, Q> {
         );
         let quote_response = &self
             .quote_service
-            .get_swap_quote(amount, source_token, target_token, slippage)
+            .get_swap_quote_with_route(amount, source_token, target_token, slippage, route)
             .await?;
 
         // Parse user's pubkey
         let user_pubkey = Pubkey::from_str(user_public_key)
             .map_err(|e| anyhow!("Invalid user public key: {}", e))?;
 
+        let config = self.transaction_config(priority_fee, &route, platform_fee);
+
         // Create swap request
         let swap_request = JupiterSwapRequest {
             user_public_key: user_pubkey,
             quote_response: quote_response.clone(),
-            config: TransactionConfig::default(),
+            config,
         };
 
         debug!(
@@ -70,11 +161,13 @@ impl<T: TokenRepository, Q: QuoteService> SwapService<T, Q> {
         );
 
         // Get swap transaction via SDK
-        let swap_response = self
-            .jupiter_client
-            .swap(&swap_request, Some(HashMap::new()))
-            .await
-            .map_err(|e| anyhow!("Failed to get swap transaction: {}", e))?;
+        let swap_response = http::with_timeout(
+            "Jupiter swap",
+            self.jupiter_client
+                .swap(&swap_request, Some(HashMap::new())),
+        )
+        .await?
+        .map_err(|e| anyhow!("Failed to get swap transaction: {}", e))?;
 
         info!(
             "Swap transaction received: tx_length={}",
@@ -85,34 +178,93 @@ impl<T: TokenRepository, Q: QuoteService> SwapService<T, Q> {
     }
 
     /// Executes (signs and sends) the swap transaction to the network
+    ///
+    /// Jupiter returns versioned (v0) transactions unless a legacy transaction was requested,
+    /// the address lookup tables of a v0 route are checked before the transaction is sent.
+    /// Waits until the transaction is confirmed, `submitted` receives the signature as soon
+    /// as the transaction is sent. A transaction whose blockhash already expired is not sent,
+    /// `BotError::TransactionExpired` is returned instead.
     pub async fn execute_swap_transaction(
         &self,
-        solana_client: &Arc<RpcClient>,
+        solana_client: &ResilientRpcClient,
         keypair: &Keypair,
         swap_response: &SwapResponse,
+        submitted: Option<&SubmittedSender>,
     ) -> Result<String> {
         info!("Executing swap transaction");
-        println!("Raw tx len: {}", swap_response.swap_transaction.len());
+        debug!("Raw tx len: {}", swap_response.swap_transaction.len());
+
+        let transaction = sign_swap_transaction(&swap_response.swap_transaction, keypair)?;
 
-        let versioned_transaction: VersionedTransaction =
-            bincode::deserialize(&swap_response.swap_transaction)
-                .map_err(|e| anyhow!("Failed to deserialize transaction: {}", e))?;
+        match &transaction.message {
+            VersionedMessage::Legacy(_) => debug!("Swap uses a legacy transaction"),
+            VersionedMessage::V0(message) => {
+                let lookup_tables = resolve_lookup_tables(solana_client, message).await?;
+                debug!(
+                    "Swap uses a v0 transaction with {} lookup tables",
+                    lookup_tables.len()
+                );
+            }
+        }
 
-        // Sign the transaction
-        let signed_versioned_transaction =
-            VersionedTransaction::try_new(versioned_transaction.message, &[keypair])
-                .map_err(|e| anyhow!("Failed to sign transaction: {}", e))?;
+        check_not_expired(solana_client, swap_response.last_valid_block_height).await?;
 
         info!("Calling network");
 
         let signature = solana_client
-            .send_and_confirm_transaction(&signed_versioned_transaction)
+            .send_transaction(&transaction)
             .await
             .map_err(|e| anyhow!("Failed to send transaction: {}", e))?;
 
-        println!("Transaction signature: {}", signature);
+        info!("Transaction signature: {}", signature);
 
-        Ok(signature.to_string())
+        wait_for_confirmation(solana_client, &signature, submitted).await
+    }
+
+    /// Executes the swap, preparing it again with a fresh blockhash when it is dropped
+    ///
+    /// `reprepare` receives the resubmit attempt, starting at 1, and returns the new swap
+    /// transaction, e.g. with a bumped priority fee. At most `dropped_retries()` resubmits
+    /// are made, swaps that failed on chain are returned as is.
+    pub async fn execute_swap_with_retries<F, Fut>(
+        &self,
+        solana_client: &ResilientRpcClient,
+        keypair: &Keypair,
+        swap_response: SwapResponse,
+        submitted: Option<&SubmittedSender>,
+        reprepare: F,
+    ) -> Result<String>
+    where
+        F: Fn(u32) -> Fut,
+        Fut: Future<Output = Result<SwapResponse>>,
+    {
+        let mut swap_response = swap_response;
+        let mut attempt = 0;
+
+        loop {
+            let result = self
+                .execute_swap_transaction(solana_client, keypair, &swap_response, submitted)
+                .await;
+
+            if attempt < dropped_retries()
+                && should_resubmit(
+                    solana_client,
+                    &result,
+                    swap_response.last_valid_block_height,
+                )
+                .await
+            {
+                attempt += 1;
+                warn!(
+                    "Swap transaction dropped, resubmitting, attempt {}",
+                    attempt
+                );
+                swap_response = reprepare(attempt).await?;
+                continue;
+            }
+
+            return result;
+        }
     }
 
     /// Gets a swap transaction audit
@@ -142,11 +294,12 @@ impl<T: TokenRepository, Q: QuoteService> SwapService<T, Q> {
         };
 
         // Get swap instructions via SDK
-        let swap_instructions = self
-            .jupiter_client
-            .swap_instructions(&swap_request)
-            .await
-            .map_err(|e| anyhow!("Failed to get swap instructions: {}", e))?;
+        let swap_instructions = http::with_timeout(
+            "Jupiter swap instructions",
+            self.jupiter_client.swap_instructions(&swap_request),
+        )
+        .await?
+        .map_err(|e| anyhow!("Failed to get swap instructions: {}", e))?;
 
         Ok(swap_instructions)
     }
@@ -157,9 +310,504 @@ impl<T: TokenRepository, Q: QuoteService> SwapService<T, Q> {
         source_token: &str,
         target_token: &str,
         slippage: f64,
+        route: RouteOptions,
     ) -> Result<QuoteResponse> {
         self.quote_service
-            .get_swap_quote(amount, source_token, target_token, slippage)
+            .get_swap_quote_with_route(amount, source_token, target_token, slippage, route)
             .await
     }
 }
+
+/// Fail with `BotError::TransactionExpired` once the block height passed the last one the
+/// swap transaction is valid for
+///
+/// Sending it would only fail on chain, and the quote it was built from is stale by then.
+/// When the block height can't be fetched the transaction is sent, an expired one is
+/// rejected by the network anyway.
+pub async fn check_not_expired(
+    solana_client: &ResilientRpcClient,
+    last_valid_block_height: u64,
+) -> Result<()> {
+    match solana_client.get_block_height().await {
+        Ok(block_height) => ensure_valid_at(block_height, last_valid_block_height),
+        Err(e) => {
+            warn!("Failed to get block height, sending swap anyway: {}", e);
+            Ok(())
+        }
+    }
+}
+
+// `BotError::TransactionExpired` once `block_height` is past the last valid one
+fn ensure_valid_at(block_height: u64, last_valid_block_height: u64) -> Result<()> {
+    if block_height > last_valid_block_height {
+        warn!(
+            "Swap transaction expired at block height {}, current {}",
+            last_valid_block_height, block_height
+        );
+        return Err(BotError::TransactionExpired.into());
+    }
+
+    Ok(())
+}
+
+/// Deserialize a swap transaction returned by Jupiter and sign it with the user keypair
+///
+/// Both legacy and v0 transactions are accepted. The user has to be the fee payer, the
+/// signature is put into the user's signer slot so signatures of other signers are kept.
+pub fn sign_swap_transaction(
+    transaction_bytes: &[u8],
+    keypair: &Keypair,
+) -> Result<VersionedTransaction> {
+    let mut transaction: VersionedTransaction = bincode::deserialize(transaction_bytes)
+        .map_err(|e| anyhow!("Failed to deserialize transaction: {}", e))?;
+
+    let user_pubkey = keypair.pubkey();
+    let required_signatures = transaction.message.header().num_required_signatures as usize;
+    let signers = transaction
+        .message
+        .static_account_keys()
+        .get(..required_signatures)
+        .ok_or_else(|| anyhow!("Swap transaction has an invalid message header"))?;
+
+    if signers.first() != Some(&user_pubkey) {
+        return Err(anyhow!(
+            "Swap transaction fee payer is not the wallet {}",
+            user_pubkey
+        ));
+    }
+
+    transaction
+        .signatures
+        .resize(required_signatures, Signature::default());
+    transaction.signatures[0] = keypair
+        .try_sign_message(&transaction.message.serialize())
+        .map_err(|e| anyhow!("Failed to sign transaction: {}", e))?;
+
+    Ok(transaction)
+}
+
+/// Fetch the address lookup tables used by a v0 message and check the indexes it uses
+///
+/// A route referencing a missing or deactivated table would fail on-chain, failing here
+/// gives the user a clear error before any fee is paid.
+pub async fn resolve_lookup_tables(
+    solana_client: &ResilientRpcClient,
+    message: &v0::Message,
+) -> Result<Vec<AddressLookupTableAccount>> {
+    if message.address_table_lookups.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let table_keys: Vec<Pubkey> = message
+        .address_table_lookups
+        .iter()
+        .map(|lookup| lookup.account_key)
+        .collect();
+
+    let accounts = solana_client
+        .get_multiple_accounts(&table_keys)
+        .await
+        .map_err(|e| anyhow!("Failed to get address lookup tables: {}", e))?;
+
+    let mut lookup_tables = Vec::with_capacity(table_keys.len());
+
+    for (lookup, account) in message.address_table_lookups.iter().zip(accounts) {
+        let account = account
+            .ok_or_else(|| anyhow!("Address lookup table {} not found", lookup.account_key))?;
+
+        let table = AddressLookupTable::deserialize(&account.data)
+            .map_err(|e| anyhow!("Invalid address lookup table {}: {}", lookup.account_key, e))?;
+
+        if table.meta.deactivation_slot != u64::MAX {
+            return Err(anyhow!(
+                "Address lookup table {} is deactivated",
+                lookup.account_key
+            ));
+        }
+
+        let out_of_range = lookup
+            .writable_indexes
+            .iter()
+            .chain(&lookup.readonly_indexes)
+            .any(|index| *index as usize >= table.addresses.len());
+
+        if out_of_range {
+            return Err(anyhow!(
+                "Swap transaction uses an unknown entry of address lookup table {}",
+                lookup.account_key
+            ));
+        }
+
+        lookup_tables.push(AddressLookupTableAccount {
+            key: lookup.account_key,
+            addresses: table.addresses.to_vec(),
+        });
+    }
+
+    Ok(lookup_tables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{Token, TokenRisk, User};
+    use crate::solana::jupiter::quote_service::JupiterQuoteService;
+    use crate::solana::jupiter::testing::fake_jupiter;
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use jupiter_swap_api_client::transaction_config::PrioritizationFeeLamports as JupiterPrioritizationFeeLamports;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::Message;
+    use std::sync::Mutex;
+
+    struct NoTokens;
+
+    #[async_trait]
+    impl TokenRepository for NoTokens {
+        async fn get_token_by_id(&self, token_id: &str) -> Result<Token> {
+            Err(anyhow!("Unknown token {}", token_id))
+        }
+
+        async fn search_tokens(&self, _query: &str) -> Result<Vec<Token>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_token_risk(&self, mint: &str) -> Result<TokenRisk> {
+            Err(anyhow!("Unknown token {}", mint))
+        }
+    }
+
+    // Remembers the slippage of every quote, then fails it so no swap is requested
+    #[derive(Default)]
+    struct RecordingQuotes {
+        slippages: Mutex<Vec<f64>>,
+    }
+
+    #[async_trait]
+    impl QuoteService for RecordingQuotes {
+        async fn get_swap_quote_with_route(
+            &self,
+            _amount: f64,
+            _source_token: &str,
+            _target_token: &str,
+            slippage: f64,
+            _route: RouteOptions,
+        ) -> Result<QuoteResponse> {
+            self.slippages.lock().unwrap().push(slippage);
+            Err(anyhow!("no quote in tests"))
+        }
+    }
+
+    fn user_with_slippage_bps(slippage_bps: u64) -> User {
+        User {
+            id: 1,
+            telegram_id: 1001,
+            username: None,
+            solana_address: None,
+            encrypted_private_key: None,
+            mnemonic: None,
+            created_at: Utc::now(),
+            settings: Some(serde_json::json!({ "slippage_bps": slippage_bps })),
+            referred_by: None,
+            last_seen_signature: None,
+        }
+    }
+
+    // Swap-like instruction paid by `payer`, the pool account is only in the lookup table
+    fn v0_swap_transaction(payer: &Pubkey, lookup_table: &AddressLookupTableAccount) -> Vec<u8> {
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1, 2, 3],
+            vec![
+                AccountMeta::new(*payer, true),
+                AccountMeta::new(lookup_table.addresses[0], false),
+            ],
+        );
+        let message = v0::Message::try_compile(
+            payer,
+            &[instruction],
+            std::slice::from_ref(lookup_table),
+            Hash::new_unique(),
+        )
+        .unwrap();
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(message),
+        };
+
+        bincode::serialize(&transaction).unwrap()
+    }
+
+    fn lookup_table() -> AddressLookupTableAccount {
+        AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![Pubkey::new_unique()],
+        }
+    }
+
+    #[test]
+    fn v0_transaction_with_lookup_tables_is_signed() {
+        let keypair = Keypair::new();
+        let bytes = v0_swap_transaction(&keypair.pubkey(), &lookup_table());
+
+        let transaction = sign_swap_transaction(&bytes, &keypair).unwrap();
+
+        let VersionedMessage::V0(message) = &transaction.message else {
+            panic!("v0 message expected");
+        };
+        assert_eq!(message.address_table_lookups.len(), 1);
+        assert_eq!(transaction.signatures.len(), 1);
+        assert!(transaction.signatures[0]
+            .verify(keypair.pubkey().as_ref(), &transaction.message.serialize()));
+    }
+
+    #[test]
+    fn legacy_transaction_is_signed() {
+        let keypair = Keypair::new();
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1],
+            vec![AccountMeta::new(keypair.pubkey(), true)],
+        );
+        let message = Message::new_with_blockhash(
+            &[instruction],
+            Some(&keypair.pubkey()),
+            &Hash::new_unique(),
+        );
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::Legacy(message),
+        };
+        let bytes = bincode::serialize(&transaction).unwrap();
+
+        let signed = sign_swap_transaction(&bytes, &keypair).unwrap();
+
+        assert!(matches!(signed.message, VersionedMessage::Legacy(_)));
+        assert!(signed.signatures[0].verify(keypair.pubkey().as_ref(), &signed.message.serialize()));
+    }
+
+    #[test]
+    fn transaction_paid_by_another_wallet_is_rejected() {
+        let bytes = v0_swap_transaction(&Pubkey::new_unique(), &lookup_table());
+
+        let err = sign_swap_transaction(&bytes, &Keypair::new()).unwrap_err();
+
+        assert!(err.to_string().contains("fee payer"));
+    }
+
+    #[test]
+    fn garbage_is_not_a_transaction() {
+        let err = sign_swap_transaction(&[1, 2, 3], &Keypair::new()).unwrap_err();
+
+        assert!(err.to_string().contains("deserialize"));
+    }
+
+    #[test]
+    fn validity_setting_reaches_the_transaction_config() {
+        let service = SwapService::new(NoTokens, RecordingQuotes::default());
+        let mut user = user_with_slippage_bps(100);
+        user.settings = Some(serde_json::json!({ "validity_slots": 60 }));
+
+        let config = service.transaction_config(None, &user.get_route_options(), None);
+
+        assert_eq!(config.blockhash_slots_to_expiry, Some(60));
+        assert!(config.correct_last_valid_block_height);
+    }
+
+    #[test]
+    fn without_a_validity_the_full_blockhash_lifetime_is_kept() {
+        let service = SwapService::new(NoTokens, RecordingQuotes::default());
+
+        let config = service.transaction_config(None, &RouteOptions::default(), None);
+
+        assert_eq!(config.blockhash_slots_to_expiry, None);
+        assert!(!config.correct_last_valid_block_height);
+    }
+
+    #[test]
+    fn expired_blockhash_returns_the_expiry_error() {
+        let err = ensure_valid_at(1_001, 1_000).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<BotError>(),
+            Some(BotError::TransactionExpired)
+        ));
+        assert!(err.to_string().contains("not submitted at a stale price"));
+    }
+
+    #[test]
+    fn blockhash_is_valid_up_to_its_last_block_height() {
+        assert!(ensure_valid_at(999, 1_000).is_ok());
+        assert!(ensure_valid_at(1_000, 1_000).is_ok());
+    }
+
+    #[test]
+    fn high_priority_fee_reaches_the_transaction_config() {
+        let service = SwapService::new(NoTokens, RecordingQuotes::default());
+        let mut user = user_with_slippage_bps(100);
+        user.settings = Some(serde_json::json!({ "priority_fee": "high" }));
+
+        let config = service.transaction_config(
+            user.get_priority_fee().to_prioritization_fee(),
+            &RouteOptions::default(),
+            None,
+        );
+
+        assert!(matches!(
+            config.prioritization_fee_lamports,
+            Some(JupiterPrioritizationFeeLamports::Lamports(1_000_000))
+        ));
+    }
+
+    const SOL: &str = "So11111111111111111111111111111111111111112";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    // Every token is SOL, for quotes of 1 SOL
+    struct SolTokens;
+
+    #[async_trait]
+    impl TokenRepository for SolTokens {
+        async fn get_token_by_id(&self, token_id: &str) -> Result<Token> {
+            Ok(Token {
+                id: token_id.to_string(),
+                symbol: "SOL".to_string(),
+                name: "Wrapped SOL".to_string(),
+                decimals: 9,
+                logo_uri: String::new(),
+                verified: true,
+            })
+        }
+
+        async fn search_tokens(&self, _query: &str) -> Result<Vec<Token>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_token_risk(&self, _mint: &str) -> Result<TokenRisk> {
+            Err(anyhow!("not used"))
+        }
+    }
+
+    // Quotes 1 SOL for 150 USDC on a single route
+    struct FixedQuote;
+
+    #[async_trait]
+    impl QuoteService for FixedQuote {
+        async fn get_swap_quote_with_route(
+            &self,
+            _amount: f64,
+            _source_token: &str,
+            _target_token: &str,
+            _slippage: f64,
+            _route: RouteOptions,
+        ) -> Result<QuoteResponse> {
+            Ok(serde_json::from_value(serde_json::json!({
+                "inputMint": SOL,
+                "inAmount": "1000000000",
+                "outputMint": USDC,
+                "outAmount": "150000000",
+                "otherAmountThreshold": "149250000",
+                "swapMode": "ExactIn",
+                "slippageBps": 50,
+                "platformFee": null,
+                "priceImpactPct": "0.0001",
+                "routePlan": [{
+                    "swapInfo": {
+                        "ammKey": "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2",
+                        "label": "Raydium",
+                        "inputMint": SOL,
+                        "outputMint": USDC,
+                        "inAmount": "1000000000",
+                        "outAmount": "150000000",
+                        "feeAmount": "250000",
+                        "feeMint": SOL,
+                    },
+                    "percent": 100,
+                }],
+                "contextSlot": 299_000_000u64,
+                "timeTaken": 0.01,
+            }))?)
+        }
+    }
+
+    fn buy(platform_fee: Option<PlatformFee>) -> SwapParams<'static> {
+        SwapParams {
+            amount: 1.0,
+            source_token: SOL,
+            target_token: USDC,
+            slippage: 0.005,
+            route: RouteOptions::default(),
+            platform_fee,
+        }
+    }
+
+    #[tokio::test]
+    async fn swaps_are_requested_from_the_configured_api_url() {
+        let (url, mut calls) = fake_jupiter().await;
+        let service = SwapService::new(NoTokens, FixedQuote).with_api_url(&url);
+        let user = Keypair::new().pubkey().to_string();
+
+        assert!(service.prepare_swap(&buy(None), None, &user).await.is_err());
+
+        let call = calls.recv().await.unwrap();
+        assert_eq!(call.method, "POST");
+        assert!(call.path.starts_with("/self-hosted/swap"), "{}", call.path);
+        assert!(call.body.contains(&user), "{}", call.body);
+    }
+
+    #[tokio::test]
+    async fn platform_fee_bps_reach_the_quote_request() {
+        let (url, mut calls) = fake_jupiter().await;
+        let quotes = JupiterQuoteService::new(SolTokens).with_api_url(&url);
+        let service = SwapService::new(NoTokens, quotes).with_api_url(&url);
+        let fee = PlatformFee::new(50, Keypair::new().pubkey()).unwrap();
+        let user = Keypair::new().pubkey().to_string();
+
+        assert!(service
+            .prepare_swap(&buy(Some(fee)), None, &user)
+            .await
+            .is_err());
+
+        // The quote fails in tests, only the fee's part of the quote request can be checked
+        let quote = calls.recv().await.unwrap();
+        assert!(
+            quote.path.starts_with("/self-hosted/quote?"),
+            "{}",
+            quote.path
+        );
+        assert!(quote.path.contains("platformFeeBps=50"), "{}", quote.path);
+    }
+
+    #[tokio::test]
+    async fn platform_fee_account_reaches_the_swap_request() {
+        let (url, mut calls) = fake_jupiter().await;
+        let service = SwapService::new(NoTokens, FixedQuote).with_api_url(&url);
+        let fee = PlatformFee::new(50, Keypair::new().pubkey()).unwrap();
+        let user = Keypair::new().pubkey().to_string();
+
+        assert!(service
+            .prepare_swap(&buy(Some(fee)), None, &user)
+            .await
+            .is_err());
+
+        let swap = calls.recv().await.unwrap();
+        let request: serde_json::Value = serde_json::from_str(&swap.body).unwrap();
+        assert_eq!(
+            request["feeAccount"],
+            serde_json::json!(fee.fee_account().to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn swap_without_a_platform_fee_has_no_fee_account() {
+        let (url, mut calls) = fake_jupiter().await;
+        let service = SwapService::new(NoTokens, FixedQuote).with_api_url(&url);
+        let user = Keypair::new().pubkey().to_string();
+
+        assert!(service.prepare_swap(&buy(None), None, &user).await.is_err());
+
+        let swap = calls.recv().await.unwrap();
+        let request: serde_json::Value = serde_json::from_str(&swap.body).unwrap();
+        assert!(request["feeAccount"].is_null(), "{}", swap.body);
+    }
+}
@@ -0,0 +1,18 @@
+//! Helpers for Jupiter service tests: a local Jupiter API that records its requests
+
+use crate::testing::{fake_http, HttpRequest, HttpResponse};
+use tokio::sync::mpsc;
+
+/// Jupiter API under `/self-hosted` that fails every request, each request is sent to the
+/// returned receiver. Returns the base URL to configure.
+pub async fn fake_jupiter() -> (String, mpsc::UnboundedReceiver<HttpRequest>) {
+    let (calls_tx, calls_rx) = mpsc::unbounded_channel();
+
+    let url = fake_http(move |request| {
+        let _ = calls_tx.send(request);
+        async { HttpResponse::with_status(500, r#"{"error":"unavailable in tests"}"#) }
+    })
+    .await;
+
+    (format!("{}/self-hosted", url), calls_rx)
+}
@@ -1,33 +1,154 @@
 // src/repositories/token_repository.rs
-use crate::entity::Token;
+use crate::entity::{Token, TokenRisk};
+use crate::solana::client::{balance_commitment, ResilientRpcClient};
+use crate::solana::jupiter::http;
 use crate::solana::jupiter::{JupiterToken, SOL_MINT, USDC_MINT};
+use crate::solana::wallet::parse_pubkey;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use log::{error, info};
+use lazy_static::lazy_static;
+use log::{debug, error, info};
 use reqwest::Client;
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::env;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// How long token metadata is kept before it is fetched again
+pub const DEFAULT_TOKEN_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Maximum number of tokens returned by a search
+pub const TOKEN_SEARCH_LIMIT: usize = 8;
+
+/// Token list used for searching by symbol or name
+pub const DEFAULT_TOKEN_LIST_URL: &str = "https://api.jup.ag/tokens/v1/tagged/verified";
+
+type TokenCache = Arc<RwLock<HashMap<String, (Token, Instant)>>>;
+type TokenListCache = Arc<RwLock<Option<(Arc<Vec<Token>>, Instant)>>>;
+
+lazy_static! {
+    // Shared by all repository instances, handlers create a new repository per request
+    static ref SHARED_TOKEN_CACHE: TokenCache = Arc::new(RwLock::new(HashMap::new()));
+    static ref SHARED_TOKEN_LIST: TokenListCache = Arc::new(RwLock::new(None));
+    static ref TOKEN_LIST_URL: String =
+        env::var("TOKEN_LIST_URL").unwrap_or_else(|_| DEFAULT_TOKEN_LIST_URL.to_string());
+    static ref TOKEN_CACHE_TTL: Duration = env::var("TOKEN_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_TOKEN_CACHE_TTL);
+}
 
 /// Repository for working with tokens
 #[async_trait]
 pub trait TokenRepository: Send + Sync {
     /// Get token information by its ID
     async fn get_token_by_id(&self, token_id: &str) -> Result<Token>;
+
+    /// Find tokens whose symbol or name matches the query, best matches first
+    async fn search_tokens(&self, query: &str) -> Result<Vec<Token>>;
+
+    /// Risk flags from the on-chain mint account, e.g. an active mint or freeze authority
+    async fn get_token_risk(&self, mint: &str) -> Result<TokenRisk>;
 }
 
 /// Implementation of the repository for working with Jupiter tokens
+///
+/// Token metadata is cached in memory for `TOKEN_CACHE_TTL_SECS` seconds (1 hour by default).
+/// The cache is shared between all instances and safe to use from several tasks at once.
 pub struct JupiterTokenRepository {
     http_client: Client,
-    token_cache: Arc<Mutex<HashMap<String, Token>>>,
+    token_cache: TokenCache,
+    token_list: TokenListCache,
+    cache_ttl: Duration,
+    // Needed to inspect mint accounts, token metadata comes from Jupiter
+    solana_client: Option<Arc<ResilientRpcClient>>,
 }
 
 impl JupiterTokenRepository {
     /// Creates a new instance of the Jupiter repository
     pub fn new() -> Self {
         Self {
-            http_client: Client::new(),
-            token_cache: Arc::new(Mutex::new(HashMap::new())),
+            http_client: http::http_client(),
+            token_cache: SHARED_TOKEN_CACHE.clone(),
+            token_list: SHARED_TOKEN_LIST.clone(),
+            cache_ttl: *TOKEN_CACHE_TTL,
+            solana_client: None,
+        }
+    }
+
+    /// RPC client used to read mint accounts for `get_token_risk`
+    pub fn with_solana_client(mut self, solana_client: Arc<ResilientRpcClient>) -> Self {
+        self.solana_client = Some(solana_client);
+        self
+    }
+
+    /// Override how long cached token metadata stays valid
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    /// Drop all cached token metadata
+    pub fn clear_cache(&self) {
+        self.token_cache.write().unwrap().clear();
+        *self.token_list.write().unwrap() = None;
+    }
+
+    // Cached token if it hasn't expired yet
+    fn cached_token(&self, token_id: &str) -> Option<Token> {
+        let cache = self.token_cache.read().unwrap();
+        cache
+            .get(token_id)
+            .filter(|(_, cached_at)| cached_at.elapsed() < self.cache_ttl)
+            .map(|(token, _)| token.clone())
+    }
+
+    fn cache_token(&self, token_id: &str, token: &Token) {
+        let mut cache = self.token_cache.write().unwrap();
+        cache.insert(token_id.to_string(), (token.clone(), Instant::now()));
+    }
+
+    // Full token list for searching, fetched once per cache TTL
+    async fn token_list(&self) -> Result<Arc<Vec<Token>>> {
+        if let Some((tokens, fetched_at)) = self.token_list.read().unwrap().as_ref() {
+            if fetched_at.elapsed() < self.cache_ttl {
+                debug!("Token list cache hit");
+                return Ok(tokens.clone());
+            }
+        }
+
+        let response = self
+            .http_client
+            .get(TOKEN_LIST_URL.as_str())
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch token list from Jupiter API: {}", e);
+                http::request_error("Failed to fetch token list from API", e)
+            })?;
+
+        if !response.status().is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            error!("Jupiter API error [token_list]: {}", error_text);
+            return Err(anyhow!("Jupiter API error: {}", error_text));
         }
+
+        let jupiter_tokens: Vec<JupiterToken> = response.json().await.map_err(|e| {
+            error!("Failed to parse token list response: {}", e);
+            anyhow!("Failed to parse token list response: {}", e)
+        })?;
+
+        let tokens: Arc<Vec<Token>> =
+            Arc::new(jupiter_tokens.into_iter().map(Token::from).collect());
+        info!("Fetched token list with {} tokens", tokens.len());
+
+        *self.token_list.write().unwrap() = Some((tokens.clone(), Instant::now()));
+
+        Ok(tokens)
     }
 }
 
@@ -38,11 +159,9 @@ impl TokenRepository for JupiterTokenRepository {
         info!("Getting token by ID: {}", token_id);
 
         // Check cache first
-        {
-            let cache = self.token_cache.lock().unwrap();
-            if let Some(token) = cache.get(token_id) {
-                return Ok(token.clone());
-            }
+        if let Some(token) = self.cached_token(token_id) {
+            debug!("Token cache hit: {}", token_id);
+            return Ok(token);
         }
 
         // Request token via API
@@ -50,7 +169,7 @@ impl TokenRepository for JupiterTokenRepository {
 
         let response = self.http_client.get(&url).send().await.map_err(|e| {
             error!("Failed to fetch token from Jupiter API: {}", e);
-            anyhow!("Failed to fetch token from API: {}", e)
+            http::request_error("Failed to fetch token from API", e)
         })?;
 
         info!(
@@ -68,6 +187,7 @@ impl TokenRepository for JupiterTokenRepository {
                     name: "Solana".to_string(),
                     decimals: 9,
                     logo_uri: "".to_string(),
+                    verified: true,
                 };
 
                 return Ok(sol);
@@ -78,6 +198,7 @@ impl TokenRepository for JupiterTokenRepository {
                     name: "USD Coin".to_string(),
                     decimals: 6,
                     logo_uri: "".to_string(),
+                    verified: true,
                 };
 
                 return Ok(usdc);
@@ -98,20 +219,255 @@ impl TokenRepository for JupiterTokenRepository {
         })?;
 
         // Convert to our token format
-        let token = Token {
+        let token = Token::from(jupiter_token);
+
+        // Update cache
+        self.cache_token(token_id, &token);
+
+        Ok(token)
+    }
+
+    /// Searches the Jupiter token list by symbol or name
+    async fn search_tokens(&self, query: &str) -> Result<Vec<Token>> {
+        info!("Searching tokens: {}", query);
+
+        let tokens = self.token_list().await?;
+        Ok(rank_tokens(&tokens, query, TOKEN_SEARCH_LIMIT))
+    }
+
+    /// Reads the mint authority and freeze authority of the token's mint account
+    async fn get_token_risk(&self, mint: &str) -> Result<TokenRisk> {
+        let solana_client = self
+            .solana_client
+            .as_ref()
+            .ok_or_else(|| anyhow!("No Solana RPC client to inspect token mints"))?;
+
+        let mint_pubkey = parse_pubkey(mint)?;
+        // Checked right before a buy, a mint seen only in a processed block may not exist yet
+        let account = solana_client
+            .get_account(&mint_pubkey, balance_commitment(solana_client.commitment()))
+            .await
+            .map_err(|e| anyhow!("Failed to get mint account of {}: {}", mint, e))?
+            .ok_or_else(|| anyhow!("Mint account of {} not found", mint))?;
+
+        TokenRisk::from_mint_account(&account)
+    }
+}
+
+impl From<JupiterToken> for Token {
+    fn from(jupiter_token: JupiterToken) -> Self {
+        let verified = jupiter_token.is_verified();
+
+        Token {
             id: jupiter_token.address,
             symbol: jupiter_token.symbol,
             name: jupiter_token.name,
             decimals: jupiter_token.decimals,
             logo_uri: jupiter_token.logo_uri.unwrap_or_default(),
-        };
+            verified,
+        }
+    }
+}
 
-        // Update cache
-        {
-            let mut cache = self.token_cache.lock().unwrap();
-            cache.insert(token.id.clone(), token.clone());
+// How well a token matches the search query, lower is better
+fn match_rank(token: &Token, query: &str) -> Option<u8> {
+    let symbol = token.symbol.to_lowercase();
+    let name = token.name.to_lowercase();
+
+    if symbol == query {
+        Some(0)
+    } else if name == query {
+        Some(1)
+    } else if symbol.starts_with(query) {
+        Some(2)
+    } else if name.starts_with(query) {
+        Some(3)
+    } else if symbol.contains(query) || name.contains(query) {
+        Some(4)
+    } else {
+        None
+    }
+}
+
+/// Best matches for the query, at most `limit` tokens
+///
+/// Verified tokens always come first so a scam token copying a popular ticker can't
+/// push the real one off the list. Within the same verification status exact symbol
+/// matches rank above name matches and prefix matches above substring matches.
+pub fn rank_tokens(tokens: &[Token], query: &str, limit: usize) -> Vec<Token> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<(bool, u8, &Token)> = tokens
+        .iter()
+        .filter_map(|token| match_rank(token, &query).map(|rank| (!token.verified, rank, token)))
+        .collect();
+
+    // Shorter symbols first within the same rank, "SOL" before "SOLAMA"
+    matches.sort_by_key(|(unverified, rank, token)| (*unverified, *rank, token.symbol.len()));
+
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, _, token)| token.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    // Repository with its own cache, tests don't share the process-wide one
+    fn repository() -> JupiterTokenRepository {
+        JupiterTokenRepository {
+            http_client: http::http_client(),
+            token_cache: Arc::new(RwLock::new(HashMap::new())),
+            token_list: Arc::new(RwLock::new(None)),
+            cache_ttl: DEFAULT_TOKEN_CACHE_TTL,
+            solana_client: None,
         }
+    }
 
-        Ok(token)
+    fn token(id: &str, symbol: &str) -> Token {
+        Token {
+            id: id.to_string(),
+            symbol: symbol.to_string(),
+            name: symbol.to_string(),
+            decimals: 5,
+            logo_uri: String::new(),
+            verified: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn cached_token_is_returned_without_a_request() {
+        let repository = repository();
+        repository.cache_token(BONK, &token(BONK, "BONK"));
+
+        let bonk = repository.get_token_by_id(BONK).await.unwrap();
+
+        assert_eq!(bonk.symbol, "BONK");
+        assert_eq!(bonk.decimals, 5);
+    }
+
+    #[test]
+    fn cached_token_expires_after_the_ttl() {
+        let repository = repository().with_cache_ttl(Duration::ZERO);
+        repository.cache_token(BONK, &token(BONK, "BONK"));
+
+        assert!(repository.cached_token(BONK).is_none());
+    }
+
+    #[tokio::test]
+    async fn cached_token_list_is_searched_without_a_request() {
+        let repository = repository();
+        let tokens = vec![token(BONK, "BONK"), token(SOL_MINT, "SOL")];
+        *repository.token_list.write().unwrap() = Some((Arc::new(tokens), Instant::now()));
+
+        let found = repository.search_tokens("bon").await.unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, BONK);
+    }
+
+    #[test]
+    fn clear_cache_drops_tokens_and_the_token_list() {
+        let repository = repository();
+        repository.cache_token(BONK, &token(BONK, "BONK"));
+        *repository.token_list.write().unwrap() = Some((Arc::new(Vec::new()), Instant::now()));
+
+        repository.clear_cache();
+
+        assert!(repository.cached_token(BONK).is_none());
+        assert!(repository.token_list.read().unwrap().is_none());
+    }
+
+    #[test]
+    fn concurrent_fills_and_reads_keep_every_token() {
+        let repository = Arc::new(repository());
+
+        let handles: Vec<_> = (0..8)
+            .map(|thread| {
+                let repository = repository.clone();
+                std::thread::spawn(move || {
+                    for i in 0..100 {
+                        let id = format!("mint-{}-{}", thread, i);
+                        repository.cache_token(&id, &token(&id, "TKN"));
+                        assert_eq!(repository.cached_token(&id).unwrap().id, id);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(repository.token_cache.read().unwrap().len(), 800);
+    }
+
+    fn named(id: &str, symbol: &str, name: &str, verified: bool) -> Token {
+        Token {
+            name: name.to_string(),
+            verified,
+            ..token(id, symbol)
+        }
+    }
+
+    fn symbols(tokens: &[Token]) -> Vec<&str> {
+        tokens.iter().map(|token| token.symbol.as_str()).collect()
+    }
+
+    #[test]
+    fn exact_symbol_ranks_above_prefix_and_substring_matches() {
+        let tokens = vec![
+            named("mint-1", "JITOSOL", "Jito Staked SOL", true),
+            named("mint-2", "SOLAMA", "Solama", true),
+            named("mint-3", "SOL", "Wrapped SOL", true),
+        ];
+
+        let found = rank_tokens(&tokens, "sol", TOKEN_SEARCH_LIMIT);
+
+        assert_eq!(symbols(&found), vec!["SOL", "SOLAMA", "JITOSOL"]);
+    }
+
+    #[test]
+    fn exact_name_ranks_above_a_symbol_prefix() {
+        let tokens = vec![
+            named("mint-1", "BONKINU", "Bonk Inu", true),
+            named("mint-2", "BNK", "Bonk", true),
+        ];
+
+        let found = rank_tokens(&tokens, "Bonk", TOKEN_SEARCH_LIMIT);
+
+        assert_eq!(symbols(&found), vec!["BNK", "BONKINU"]);
+    }
+
+    #[test]
+    fn verified_tokens_rank_above_an_unverified_exact_match() {
+        let tokens = vec![
+            named("scam-mint", "USDC", "USD Coin", false),
+            named("mint-2", "USDCET", "USDC (Wormhole)", true),
+            named(USDC_MINT, "USDC", "USD Coin", true),
+        ];
+
+        let found = rank_tokens(&tokens, "usdc", TOKEN_SEARCH_LIMIT);
+
+        let ids: Vec<&str> = found.iter().map(|token| token.id.as_str()).collect();
+        assert_eq!(ids, vec![USDC_MINT, "mint-2", "scam-mint"]);
+    }
+
+    #[test]
+    fn search_is_limited_and_ignores_blank_queries() {
+        let tokens: Vec<Token> = (0..20)
+            .map(|i| token(&format!("mint-{}", i), &format!("CAT{}", i)))
+            .collect();
+
+        assert_eq!(rank_tokens(&tokens, "cat", 5).len(), 5);
+        assert!(rank_tokens(&tokens, "  ", 5).is_empty());
+        assert!(rank_tokens(&tokens, "dog", 5).is_empty());
     }
 }
@@ -1,4 +1,5 @@
 // Re-export everything from submodules
+pub mod activity;
 pub mod client;
 pub mod jupiter;
 pub mod tokens;
@@ -6,9 +7,9 @@ pub mod utils;
 pub mod wallet;
 
 // Re-export commonly used items
-pub use client::create_solana_client;
+pub use client::{create_solana_client, ResilientRpcClient};
 pub use tokens::constants::{RAY_MINT, USDC_MINT, USDT_MINT};
-pub use tokens::native::{get_sol_balance, send_sol};
-pub use tokens::spl::{get_token_balances, send_spl_token};
+pub use tokens::native::{get_sol_balance, max_sol_transfer, send_sol, unwrap_sol};
+pub use tokens::spl::{get_token_balances, send_spl_token, token_account_exists};
 pub use utils::{get_mint_from_symbol, get_symbol_from_mint};
 pub use wallet::{generate_wallet, keypair_from_base58};
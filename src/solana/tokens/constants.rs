@@ -3,6 +3,9 @@ pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
 pub const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
 pub const RAY_MINT: &str = "4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R";
 
+// Token-2022 program, owns mints and token accounts like the original token program
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
 // Define token decimals
 pub const SOL_DECIMALS: u8 = 9;
 pub const USDC_DECIMALS: u8 = 6;
@@ -11,3 +14,5 @@ pub const RAY_DECIMALS: u8 = 6;
 
 // Fee constants
 pub const ESTIMATED_SOL_FEE: u64 = 5000; // in lamports
+pub const FEE_RESERVE_LAMPORTS: u64 = 1_000_000; // buffer for base and priority fees
+pub const TOKEN_ACCOUNT_RENT_LAMPORTS: u64 = 2_039_280; // rent-exempt minimum for a new token account
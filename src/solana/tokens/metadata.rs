@@ -0,0 +1,157 @@
+use anyhow::{anyhow, Result};
+use solana_sdk::account::Account;
+use solana_sdk::program_pack::Pack;
+use spl_token::state::Mint;
+
+use super::constants::TOKEN_2022_PROGRAM_ID;
+use super::from_base_units;
+use crate::solana::client::{balance_commitment, ResilientRpcClient};
+use crate::solana::wallet::parse_pubkey;
+
+/// On-chain fields of a token mint
+#[derive(Debug, Clone, PartialEq)]
+pub struct MintInfo {
+    pub decimals: u8,
+    /// Minted supply in base units
+    pub supply: u64,
+    /// Account that can mint more tokens, `None` when the supply is fixed
+    pub mint_authority: Option<String>,
+    /// Account that can freeze token accounts, `None` when nobody can
+    pub freeze_authority: Option<String>,
+    pub is_token_2022: bool,
+}
+
+impl MintInfo {
+    /// Parse a mint account of the SPL token or Token-2022 program
+    ///
+    /// Token-2022 mints start with the same layout, extensions after it are ignored.
+    pub fn from_account(account: &Account) -> Result<Self> {
+        let is_token_2022 = account.owner.to_string() == TOKEN_2022_PROGRAM_ID;
+        if account.owner != spl_token::ID && !is_token_2022 {
+            return Err(anyhow!("Account is not owned by a token program"));
+        }
+
+        let data = account
+            .data
+            .get(..Mint::LEN)
+            .ok_or_else(|| anyhow!("Account is not a token mint"))?;
+        let mint = Mint::unpack_from_slice(data)
+            .map_err(|e| anyhow!("Failed to read token mint: {}", e))?;
+
+        Ok(Self {
+            decimals: mint.decimals,
+            supply: mint.supply,
+            mint_authority: mint.mint_authority.map(|key| key.to_string()).into(),
+            freeze_authority: mint.freeze_authority.map(|key| key.to_string()).into(),
+            is_token_2022,
+        })
+    }
+
+    /// Minted supply in UI units
+    pub fn ui_supply(&self) -> f64 {
+        from_base_units(self.supply, self.decimals)
+    }
+
+    /// More tokens can still be minted, diluting holders
+    pub fn is_inflatable(&self) -> bool {
+        self.mint_authority.is_some()
+    }
+
+    /// Token accounts can be frozen, so bought tokens may never be sellable
+    pub fn is_freezable(&self) -> bool {
+        self.freeze_authority.is_some()
+    }
+}
+
+/// Fetch and parse the mint account of a token
+pub async fn get_mint_info(solana_client: &ResilientRpcClient, mint: &str) -> Result<MintInfo> {
+    let mint_pubkey = parse_pubkey(mint)?;
+    // A mint seen only in a processed block may not exist yet
+    let account = solana_client
+        .get_account(&mint_pubkey, balance_commitment(solana_client.commitment()))
+        .await
+        .map_err(|e| anyhow!("Failed to get mint account of {}: {}", mint, e))?
+        .ok_or_else(|| anyhow!("Mint account of {} not found", mint))?;
+
+    MintInfo::from_account(&account)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    // Packed mint account of 1000 tokens with 6 decimals
+    fn mint_account(
+        owner: Pubkey,
+        mint_authority: Option<Pubkey>,
+        freeze_authority: Option<Pubkey>,
+    ) -> Account {
+        let mint = Mint {
+            mint_authority: mint_authority.into(),
+            supply: 1_000_000_000,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: freeze_authority.into(),
+        };
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(mint, &mut data).unwrap();
+
+        Account {
+            lamports: 1_461_600,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn parses_a_mint_with_both_authorities() {
+        let authority = Pubkey::new_unique();
+        let account = mint_account(spl_token::ID, Some(authority), Some(authority));
+
+        let mint = MintInfo::from_account(&account).unwrap();
+
+        assert_eq!(mint.decimals, 6);
+        assert_eq!(mint.ui_supply(), 1000.0);
+        assert_eq!(mint.mint_authority, Some(authority.to_string()));
+        assert_eq!(mint.freeze_authority, Some(authority.to_string()));
+        assert!(mint.is_inflatable());
+        assert!(mint.is_freezable());
+        assert!(!mint.is_token_2022);
+    }
+
+    #[test]
+    fn parses_a_mint_with_revoked_authorities() {
+        let account = mint_account(spl_token::ID, None, None);
+
+        let mint = MintInfo::from_account(&account).unwrap();
+
+        assert_eq!(mint.mint_authority, None);
+        assert_eq!(mint.freeze_authority, None);
+        assert!(!mint.is_inflatable());
+        assert!(!mint.is_freezable());
+    }
+
+    #[test]
+    fn token_2022_extensions_after_the_mint_are_ignored() {
+        let token_2022 = Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap();
+        let mut account = mint_account(token_2022, None, Some(Pubkey::new_unique()));
+        account.data.extend_from_slice(&[1; 83]);
+
+        let mint = MintInfo::from_account(&account).unwrap();
+
+        assert!(mint.is_token_2022);
+        assert!(!mint.is_inflatable());
+        assert!(mint.is_freezable());
+    }
+
+    #[test]
+    fn accounts_of_other_programs_are_not_mints() {
+        let account = mint_account(solana_sdk::system_program::ID, None, None);
+
+        assert!(MintInfo::from_account(&account).is_err());
+    }
+}
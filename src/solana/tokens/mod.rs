@@ -1,12 +1,85 @@
 // Re-export submodules
 pub mod constants;
+pub mod metadata;
 pub mod native;
 pub mod spl;
 pub mod transaction;
 
 // Re-export commonly used items
 pub use constants::{RAY_MINT, USDC_MINT, USDT_MINT};
+pub use metadata::{get_mint_info, MintInfo};
 pub use native::get_sol_balance;
+pub use native::max_sol_transfer;
 pub use native::send_sol;
+pub use native::unwrap_sol;
 pub use spl::get_token_balances;
 pub use spl::send_spl_token;
+pub use spl::token_account_exists;
+
+use constants::{FEE_RESERVE_LAMPORTS, TOKEN_ACCOUNT_RENT_LAMPORTS};
+
+/// Convert a human readable token amount into base units (e.g. 1.5 USDC -> 1_500_000),
+/// rounding to the nearest unit so that values like 0.1 don't lose a unit to float error
+pub fn to_base_units(amount: f64, decimals: u8) -> u64 {
+    (amount * 10_f64.powi(decimals as i32)).round() as u64
+}
+
+/// Convert base units into a human readable token amount
+pub fn from_base_units(amount: u64, decimals: u8) -> f64 {
+    amount as f64 / 10_f64.powi(decimals as i32)
+}
+
+/// Lamports that must stay in the wallet after a trade: a fee buffer plus rent for
+/// the destination token account when it has to be created by the swap
+pub fn reserve_lamports(needs_token_account: bool) -> u64 {
+    if needs_token_account {
+        FEE_RESERVE_LAMPORTS + TOKEN_ACCOUNT_RENT_LAMPORTS
+    } else {
+        FEE_RESERVE_LAMPORTS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_six_decimal_tokens() {
+        assert_eq!(to_base_units(1.5, 6), 1_500_000);
+        assert_eq!(to_base_units(0.000001, 6), 1);
+        assert_eq!(from_base_units(1_500_000, 6), 1.5);
+    }
+
+    #[test]
+    fn converts_eight_decimal_tokens() {
+        assert_eq!(to_base_units(2.12345678, 8), 212_345_678);
+        assert_eq!(from_base_units(212_345_678, 8), 2.12345678);
+    }
+
+    #[test]
+    fn converts_nine_decimal_tokens() {
+        assert_eq!(to_base_units(0.1, 9), 100_000_000);
+        assert_eq!(to_base_units(1.0, 9), 1_000_000_000);
+        assert_eq!(from_base_units(123_456_789, 9), 0.123456789);
+    }
+
+    #[test]
+    fn rounds_to_the_nearest_base_unit() {
+        // 0.29 * 10^6 is 289999.99... as a float
+        assert_eq!(to_base_units(0.29, 6), 290_000);
+        assert_eq!(to_base_units(1.0000004, 6), 1_000_000);
+        assert_eq!(to_base_units(1.0000006, 6), 1_000_001);
+        assert_eq!(to_base_units(0.0000004, 6), 0);
+    }
+
+    #[test]
+    fn reserve_covers_fees_and_new_token_account_rent() {
+        assert_eq!(reserve_lamports(false), FEE_RESERVE_LAMPORTS);
+        assert_eq!(
+            reserve_lamports(true),
+            FEE_RESERVE_LAMPORTS + TOKEN_ACCOUNT_RENT_LAMPORTS
+        );
+        // Stays under the ~0.003 SOL users are told to keep for fees
+        assert!(reserve_lamports(true) < 3_100_000);
+    }
+}
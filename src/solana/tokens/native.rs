@@ -1,34 +1,80 @@
 use crate::entity::BotError;
+use crate::solana::client::{balance_commitment, ResilientRpcClient};
 use crate::solana::tokens::constants::ESTIMATED_SOL_FEE;
-use crate::solana::tokens::transaction::send_transaction;
+use crate::solana::tokens::transaction::{send_transaction, SubmittedSender};
 use crate::solana::utils::{lamports_to_sol, sol_to_lamports};
 use crate::solana::wallet::parse_pubkey;
 use anyhow::{anyhow, Result};
-use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::TokenAccountsFilter;
 use solana_sdk::{
+    instruction::Instruction,
+    message::Message,
+    pubkey::Pubkey,
     signature::{Keypair, Signer},
     system_instruction,
 };
+use spl_token::{instruction as token_instruction, native_mint, ID as TOKEN_PROGRAM_ID};
 
 /// Get SOL balance
-pub async fn get_sol_balance(client: &RpcClient, address: &str) -> Result<f64> {
+pub async fn get_sol_balance(client: &ResilientRpcClient, address: &str) -> Result<f64> {
     let pubkey = parse_pubkey(address)?;
 
     let balance = client
-        .get_balance(&pubkey)
+        .get_balance(&pubkey, balance_commitment(client.commitment()))
         .await
-        .map_err(|e| anyhow!("Failed to get balance: {}", e))?;
+        .map_err(|e| BotError::RpcUnavailable(format!("failed to get balance: {}", e)))?;
 
     // Convert from lamports to SOL
     Ok(lamports_to_sol(balance))
 }
 
-/// Send SOL
+/// Largest SOL transfer the wallet can pay for, as `(lamports to send, network fee)`
+///
+/// Draining a wallet to zero lamports needs no rent reserve, so only the fee is kept back.
+pub async fn max_sol_transfer(client: &ResilientRpcClient, address: &str) -> Result<(u64, u64)> {
+    let pubkey = parse_pubkey(address)?;
+
+    let balance = client
+        .get_balance(&pubkey, balance_commitment(client.commitment()))
+        .await
+        .map_err(|e| BotError::RpcUnavailable(format!("failed to get balance: {}", e)))?;
+
+    let fee = estimate_transfer_fee(client, &pubkey).await.ok();
+
+    Ok(max_transfer(balance, fee))
+}
+
+// Lamports left to send once the fee is kept back, `ESTIMATED_SOL_FEE` when the fee is unknown
+fn max_transfer(balance: u64, fee: Option<u64>) -> (u64, u64) {
+    let fee = fee.unwrap_or(ESTIMATED_SOL_FEE);
+
+    (balance.saturating_sub(fee), fee)
+}
+
+/// Fee the network charges for a plain SOL transfer paid by `sender`
+async fn estimate_transfer_fee(client: &ResilientRpcClient, sender: &Pubkey) -> Result<u64> {
+    let (recent_blockhash, _) = client
+        .get_latest_blockhash()
+        .await
+        .map_err(|e| anyhow!("Failed to get recent blockhash: {}", e))?;
+
+    // The recipient doesn't change the fee, so the sender stands in for it
+    let instruction = system_instruction::transfer(sender, sender, 0);
+    let message = Message::new_with_blockhash(&[instruction], Some(sender), &recent_blockhash);
+
+    client
+        .get_fee_for_message(&message)
+        .await
+        .map_err(|e| anyhow!("Failed to estimate fee: {}", e))
+}
+
+/// Send SOL, `submitted` receives the signature before the transfer is confirmed
 pub async fn send_sol(
-    client: &RpcClient,
+    client: &ResilientRpcClient,
     keypair: &Keypair,
     recipient: &str,
     amount: f64,
+    submitted: Option<&SubmittedSender>,
 ) -> Result<String> {
     // Convert recipient string to pubkey
     let recipient_pubkey = parse_pubkey(recipient)?;
@@ -36,21 +82,174 @@ pub async fn send_sol(
     // Check sender balance
     let sender_pubkey = keypair.pubkey();
     let sender_balance = client
-        .get_balance(&sender_pubkey)
+        .get_balance(&sender_pubkey, balance_commitment(client.commitment()))
         .await
-        .map_err(|e| anyhow!("Failed to get sender balance: {}", e))?;
+        .map_err(|e| BotError::RpcUnavailable(format!("failed to get sender balance: {}", e)))?;
 
     // Convert amount to lamports
     let lamports = sol_to_lamports(amount);
 
     // Make sure sender has enough balance (including estimated fees)
     if sender_balance < lamports + ESTIMATED_SOL_FEE {
-        return Err(BotError::InsufficientFunds.into());
+        return Err(BotError::InsufficientSol {
+            required: lamports_to_sol(lamports + ESTIMATED_SOL_FEE),
+            available: lamports_to_sol(sender_balance),
+        }
+        .into());
     }
 
     // Create transfer instruction
     let instruction = system_instruction::transfer(&sender_pubkey, &recipient_pubkey, lamports);
 
     // Execute transaction
-    send_transaction(client, keypair, &[instruction]).await
+    send_transaction(client, keypair, &[instruction], submitted).await
+}
+
+/// Wrapped SOL (wSOL) token accounts of a wallet
+#[derive(Debug, Clone, Default)]
+pub struct WrappedSol {
+    pub accounts: Vec<Pubkey>,
+    // Wrapped amount plus the rent of the accounts, all of it is returned on unwrap
+    pub lamports: u64,
+}
+
+/// Find the owner's wrapped SOL accounts
+pub async fn find_wrapped_sol(client: &ResilientRpcClient, owner: &Pubkey) -> Result<WrappedSol> {
+    let token_accounts = client
+        .get_token_accounts_by_owner(
+            owner,
+            TokenAccountsFilter::Mint(native_mint::ID),
+            balance_commitment(client.commitment()),
+        )
+        .await
+        .map_err(|e| BotError::RpcUnavailable(format!("failed to get token accounts: {}", e)))?;
+
+    let mut wrapped = WrappedSol::default();
+
+    for keyed_account in token_accounts {
+        wrapped.accounts.push(parse_pubkey(&keyed_account.pubkey)?);
+        wrapped.lamports += keyed_account.account.lamports;
+    }
+
+    Ok(wrapped)
+}
+
+/// Build instructions that unwrap the accounts into native SOL
+///
+/// Closing a wrapped SOL account sends all of its lamports to the owner, whatever its token
+/// balance says, so no swap or sync is needed.
+pub fn build_unwrap_sol_instructions(
+    accounts: &[Pubkey],
+    owner: &Pubkey,
+) -> Result<Vec<Instruction>> {
+    accounts
+        .iter()
+        .map(|account| {
+            token_instruction::close_account(&TOKEN_PROGRAM_ID, account, owner, owner, &[owner])
+                .map_err(|e| anyhow!("Failed to create close account instruction: {}", e))
+        })
+        .collect()
+}
+
+/// Unwrap all wrapped SOL of the keypair's wallet, returns the signature and the SOL received
+pub async fn unwrap_sol(
+    client: &ResilientRpcClient,
+    keypair: &Keypair,
+    submitted: Option<&SubmittedSender>,
+) -> Result<(String, f64)> {
+    let owner = keypair.pubkey();
+    let wrapped = find_wrapped_sol(client, &owner).await?;
+
+    if wrapped.accounts.is_empty() {
+        return Err(anyhow!("There is no wrapped SOL in your wallet"));
+    }
+
+    let instructions = build_unwrap_sol_instructions(&wrapped.accounts, &owner)?;
+    let signature = send_transaction(client, keypair, &instructions, submitted).await?;
+
+    Ok((signature, lamports_to_sol(wrapped.lamports)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_transfer_keeps_the_fee_back() {
+        assert_eq!(max_transfer(1_000_000, None), (995_000, 5_000));
+        assert_eq!(max_transfer(1_000_000, Some(10_000)), (990_000, 10_000));
+    }
+
+    #[test]
+    fn max_transfer_is_zero_below_the_fee() {
+        assert_eq!(max_transfer(3_000, None), (0, 5_000));
+        assert_eq!(max_transfer(5_000, None), (0, 5_000));
+        assert_eq!(max_transfer(0, None), (0, 5_000));
+    }
+
+    #[test]
+    fn unwrap_closes_every_wrapped_sol_account_to_the_owner() {
+        let owner = Pubkey::new_unique();
+        let accounts = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let instructions = build_unwrap_sol_instructions(&accounts, &owner).unwrap();
+
+        assert_eq!(instructions.len(), 2);
+        for (instruction, account) in instructions.iter().zip(accounts.iter()) {
+            assert_eq!(instruction.program_id, TOKEN_PROGRAM_ID);
+            assert_eq!(
+                token_instruction::TokenInstruction::unpack(&instruction.data).unwrap(),
+                token_instruction::TokenInstruction::CloseAccount
+            );
+            // Closed account, lamports destination and the signing owner
+            let keys: Vec<Pubkey> = instruction
+                .accounts
+                .iter()
+                .map(|meta| meta.pubkey)
+                .collect();
+            assert_eq!(keys, vec![*account, owner, owner]);
+            assert!(instruction.accounts[2].is_signer);
+        }
+    }
+
+    #[test]
+    fn nothing_to_unwrap_builds_no_instructions() {
+        let instructions = build_unwrap_sol_instructions(&[], &Pubkey::new_unique()).unwrap();
+
+        assert!(instructions.is_empty());
+    }
+
+    // Nothing listens on this port, every call fails without retries
+    fn unreachable_rpc() -> ResilientRpcClient {
+        ResilientRpcClient::new(&["http://127.0.0.1:9".to_string()])
+            .unwrap()
+            .with_retry_policy(0, std::time::Duration::ZERO)
+    }
+
+    #[tokio::test]
+    async fn unreachable_rpc_is_reported_as_unavailable() {
+        let error = get_sol_balance(
+            &unreachable_rpc(),
+            "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<BotError>(),
+            Some(BotError::RpcUnavailable(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn malformed_address_is_reported_before_calling_the_rpc() {
+        let error = get_sol_balance(&unreachable_rpc(), "not-an-address")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<BotError>(),
+            Some(BotError::InvalidAddress(address)) if address == "not-an-address"
+        ));
+    }
 }
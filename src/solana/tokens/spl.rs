@@ -1,8 +1,8 @@
 use anyhow::{anyhow, Result};
-use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_client::rpc_request::TokenAccountsFilter;
 use solana_client::rpc_response::RpcKeyedAccount;
 use solana_sdk::account::Account;
+use solana_sdk::instruction::Instruction;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::{Keypair, Signer};
 use spl_associated_token_account::{
@@ -11,21 +11,29 @@ use spl_associated_token_account::{
 use spl_token::{instruction as token_instruction, ID as TOKEN_PROGRAM_ID};
 
 use crate::entity::{BotError, TokenBalance};
+use crate::solana::client::{balance_commitment, ResilientRpcClient};
 use crate::solana::jupiter::token_repository::JupiterTokenRepository;
 use crate::solana::jupiter::TokenRepository;
-use crate::solana::tokens::constants::{RAY_MINT, USDC_MINT, USDT_MINT};
-use crate::solana::tokens::transaction::send_transaction;
+use crate::solana::tokens::from_base_units;
+use crate::solana::tokens::transaction::{send_transaction, SubmittedSender};
 use crate::solana::utils::convert_to_token_amount;
 use crate::solana::wallet::parse_pubkey;
 
 /// Get token balances
-pub async fn get_token_balances(client: &RpcClient, address: &str) -> Result<Vec<TokenBalance>> {
+pub async fn get_token_balances(
+    client: &ResilientRpcClient,
+    address: &str,
+) -> Result<Vec<TokenBalance>> {
     let pubkey: Pubkey = parse_pubkey(address)?;
 
     let token_accounts: Vec<RpcKeyedAccount> = client
-        .get_token_accounts_by_owner(&pubkey, TokenAccountsFilter::ProgramId(spl_token::ID))
+        .get_token_accounts_by_owner(
+            &pubkey,
+            TokenAccountsFilter::ProgramId(spl_token::ID),
+            balance_commitment(client.commitment()),
+        )
         .await
-        .map_err(|e| anyhow!("Failed to get token accounts: {}", e))?;
+        .map_err(|e| BotError::RpcUnavailable(format!("failed to get token accounts: {}", e)))?;
 
     let mut balances: Vec<TokenBalance> = Vec::new();
 
@@ -33,11 +41,14 @@ pub async fn get_token_balances(client: &RpcClient, address: &str) -> Result<Vec
         let token_account_pubkey: Pubkey = parse_pubkey(&keyed_account.pubkey.to_string())?;
 
         let token_account = client
-            .get_token_account(&token_account_pubkey)
+            .execute("get_token_account", |client| async move {
+                client.get_token_account(&token_account_pubkey).await
+            })
             .await?
             .unwrap();
         let mint_id = token_account.mint.to_string();
         let token_amount = token_account.token_amount.ui_amount.unwrap();
+        let decimals = token_account.token_amount.decimals;
 
         let token_repository = JupiterTokenRepository::new();
         let token = token_repository
@@ -49,115 +60,260 @@ pub async fn get_token_balances(client: &RpcClient, address: &str) -> Result<Vec
             symbol: token.symbol,
             amount: token_amount,
             mint_address: mint_id.clone(),
+            decimals,
         });
     }
 
     Ok(balances)
 }
 
-/// Send SPL token
+/// Check whether the owner's associated token account for the mint already exists
+pub async fn token_account_exists(
+    client: &ResilientRpcClient,
+    owner: &Pubkey,
+    mint: &str,
+) -> Result<bool> {
+    let mint_pubkey = parse_pubkey(mint)?;
+    let token_account = get_associated_token_address(owner, &mint_pubkey);
+
+    let accounts = client
+        .get_multiple_accounts(&[token_account])
+        .await
+        .map_err(|e| anyhow!("Failed to get token account: {}", e))?;
+
+    Ok(accounts.into_iter().next().flatten().is_some())
+}
+
+/// Maximum number of accounts closed in a single transaction
+const MAX_CLOSE_ACCOUNTS_PER_TRANSACTION: usize = 20;
+
+/// Token account without tokens, closing it returns its rent to the owner
+#[derive(Debug, Clone)]
+pub struct EmptyTokenAccount {
+    pub address: Pubkey,
+    pub mint: String,
+    pub lamports: u64,
+}
+
+/// Result of closing empty token accounts
+#[derive(Debug, Clone, Default)]
+pub struct TokenAccountCleanup {
+    pub closed_accounts: usize,
+    pub reclaimed_lamports: u64,
+    pub signatures: Vec<String>,
+}
+
+/// Find the owner's token accounts with a zero balance
+///
+/// Accounts holding any amount, even dust, are skipped since closing them would fail.
+pub async fn find_empty_token_accounts(
+    client: &ResilientRpcClient,
+    owner: &Pubkey,
+) -> Result<Vec<EmptyTokenAccount>> {
+    let token_accounts: Vec<RpcKeyedAccount> = client
+        .get_token_accounts_by_owner(
+            owner,
+            TokenAccountsFilter::ProgramId(spl_token::ID),
+            balance_commitment(client.commitment()),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get token accounts: {}", e))?;
+
+    let mut empty_accounts = Vec::new();
+
+    for keyed_account in token_accounts {
+        let address: Pubkey = parse_pubkey(&keyed_account.pubkey.to_string())?;
+
+        let token_account = match client
+            .execute("get_token_account", |client| async move {
+                client.get_token_account(&address).await
+            })
+            .await?
+        {
+            Some(token_account) => token_account,
+            None => continue,
+        };
+
+        if !is_closable(&token_account.token_amount.amount) {
+            continue;
+        }
+
+        empty_accounts.push(EmptyTokenAccount {
+            address,
+            mint: token_account.mint.to_string(),
+            lamports: keyed_account.account.lamports,
+        });
+    }
+
+    Ok(empty_accounts)
+}
+
+// Only accounts without any tokens can be closed, `raw_amount` is in base units
+fn is_closable(raw_amount: &str) -> bool {
+    raw_amount.parse::<u64>().is_ok_and(|amount| amount == 0)
+}
+
+/// Build instructions that close the accounts and send their rent to the owner
+pub fn build_close_account_instructions(
+    accounts: &[EmptyTokenAccount],
+    owner: &Pubkey,
+) -> Result<Vec<Instruction>> {
+    accounts
+        .iter()
+        .map(|account| {
+            token_instruction::close_account(
+                &TOKEN_PROGRAM_ID,
+                &account.address,
+                owner,
+                owner,
+                &[owner],
+            )
+            .map_err(|e| anyhow!("Failed to create close account instruction: {}", e))
+        })
+        .collect()
+}
+
+/// Close all empty token accounts of the keypair's wallet to reclaim their rent
+pub async fn close_empty_token_accounts(
+    client: &ResilientRpcClient,
+    keypair: &Keypair,
+) -> Result<TokenAccountCleanup> {
+    let owner = keypair.pubkey();
+    let empty_accounts = find_empty_token_accounts(client, &owner).await?;
+
+    let mut cleanup = TokenAccountCleanup::default();
+
+    for batch in empty_accounts.chunks(MAX_CLOSE_ACCOUNTS_PER_TRANSACTION) {
+        let instructions = build_close_account_instructions(batch, &owner)?;
+        let signature = send_transaction(client, keypair, &instructions, None).await?;
+
+        cleanup.closed_accounts += batch.len();
+        cleanup.reclaimed_lamports += batch.iter().map(|account| account.lamports).sum::<u64>();
+        cleanup.signatures.push(signature);
+    }
+
+    Ok(cleanup)
+}
+
+/// Send SPL token of the given mint, `submitted` receives the signature before the transfer is confirmed
+///
+/// `token_symbol` is only used in messages.
 pub async fn send_spl_token(
-    client: &RpcClient,
+    client: &ResilientRpcClient,
     keypair: &Keypair,
     recipient: &str,
+    mint_address: &str,
     token_symbol: &str,
     amount: f64,
+    submitted: Option<&SubmittedSender>,
 ) -> Result<String> {
     // Convert recipient string to pubkey
     let recipient_pubkey: Pubkey = parse_pubkey(recipient)?;
+    let mint_pubkey: Pubkey = parse_pubkey(mint_address)?;
+
+    // Get mint info
+    let commitment = balance_commitment(client.commitment());
+    let mint_info: Account = client
+        .get_account(&mint_pubkey, commitment)
+        .await
+        .map_err(|e| anyhow!("Failed to get mint info: {}", e))?
+        .ok_or_else(|| anyhow!("Failed to get mint info: {} not found", mint_address))?;
 
-    // Get token mint address based on symbol
-    let mint_address: &str = match token_symbol.to_uppercase().as_str() {
-        "USDC" => USDC_MINT,
-        "USDT" => USDT_MINT,
-        "RAY" => RAY_MINT,
-        _ => return Err(anyhow!("Unsupported token symbol: {}", token_symbol)),
+    // Token-2022 mints need a different program for the transfer
+    if mint_info.owner != TOKEN_PROGRAM_ID {
+        return Err(anyhow!(
+            "{} is not a standard SPL token and can't be sent from the bot yet",
+            token_symbol
+        ));
+    }
+
+    // mint_info.data is Vec<u8>
+    let mint_data: Vec<u8> = mint_info.data;
+
+    let decimals: u8 = if mint_data.len() > 44 {
+        mint_data[44]
+    } else {
+        6
     };
 
-    let mint_pubkey: Pubkey = parse_pubkey(mint_address)?;
+    // Convert amount to token units
+    let token_amount: u64 = convert_to_token_amount(amount, decimals);
+    if token_amount == 0 {
+        return Err(anyhow!("Amount is too small to send"));
+    }
 
     // Get sender's token account
     let sender_pubkey: Pubkey = keypair.pubkey();
     let sender_token_account: Pubkey = get_associated_token_address(&sender_pubkey, &mint_pubkey);
 
-    // Check if sender has the token account
-    match client.get_account(&sender_token_account).await {
-        Ok(sender_token_account_info) => {
-            // sender_token_account_info has Account type (raw).
-            let account_data: Vec<u8> = sender_token_account_info.data;
-
-            if account_data.len() < 72 {
-                return Err(anyhow!("Sender token account data too short").into());
-            }
+    // A missing token account means there is nothing to send
+    let token_account_amount: u64 =
+        match client.get_account(&sender_token_account, commitment).await {
+            Ok(Some(sender_token_account_info)) => {
+                // sender_token_account_info has Account type (raw).
+                let account_data: Vec<u8> = sender_token_account_info.data;
 
-            let token_account_amount: u64 = u64::from_le_bytes(account_data[64..72].try_into()?);
+                if account_data.len() < 72 {
+                    return Err(anyhow!("Sender token account data too short"));
+                }
 
-            // Get mint info
-            let mint_info: Account = client
-                .get_account(&mint_pubkey)
-                .await
-                .map_err(|e| anyhow!("Failed to get mint info: {}", e))?;
+                u64::from_le_bytes(account_data[64..72].try_into()?)
+            }
+            _ => 0,
+        };
 
-            // mint_info.data is also Vec<u8>
-            let mint_data: Vec<u8> = mint_info.data;
+    // Make sure sender has enough tokens
+    if token_account_amount < token_amount {
+        return Err(BotError::InsufficientToken {
+            symbol: token_symbol.to_string(),
+            required: amount,
+            available: from_base_units(token_account_amount, decimals),
+        }
+        .into());
+    }
 
-            let decimals: u8 = if mint_data.len() > 44 {
-                mint_data[44]
-            } else {
-                6
-            };
+    // Get or create recipient's associated token account
+    let recipient_token_account: Pubkey =
+        get_associated_token_address(&recipient_pubkey, &mint_pubkey);
 
-            // Convert amount to token units
-            let token_amount: u64 = convert_to_token_amount(amount, decimals);
+    // Prepare instructions
+    let mut instructions = Vec::new();
 
-            // Make sure sender has enough tokens
-            if token_account_amount < token_amount {
-                return Err(BotError::InsufficientFunds.into());
-            }
+    // Check if recipient token account exists and create if not
+    if !matches!(
+        client
+            .get_account(&recipient_token_account, commitment)
+            .await,
+        Ok(Some(_))
+    ) {
+        instructions.push(create_associated_token_account_idempotent(
+            &sender_pubkey,
+            &recipient_pubkey,
+            &mint_pubkey,
+            &TOKEN_PROGRAM_ID,
+        ));
+    }
 
-            // Get or create recipient's associated token account
-            let recipient_token_account: Pubkey =
-                get_associated_token_address(&recipient_pubkey, &mint_pubkey);
-
-            // Prepare instructions
-            let mut instructions = Vec::new();
-
-            // Check if recipient token account exists and create if not
-            if client.get_account(&recipient_token_account).await.is_err() {
-                instructions.push(create_associated_token_account_idempotent(
-                    &sender_pubkey,
-                    &recipient_pubkey,
-                    &mint_pubkey,
-                    &TOKEN_PROGRAM_ID,
-                ));
-            }
+    // Add token transfer instruction
+    instructions.push(
+        token_instruction::transfer(
+            &TOKEN_PROGRAM_ID,
+            &sender_token_account,
+            &recipient_token_account,
+            &sender_pubkey,
+            &[&sender_pubkey],
+            token_amount,
+        )
+        .map_err(|e| anyhow!("Failed to create token transfer instruction: {}", e))?,
+    );
 
-            // Add token transfer instruction
-            instructions.push(
-                token_instruction::transfer(
-                    &TOKEN_PROGRAM_ID,
-                    &sender_token_account,
-                    &recipient_token_account,
-                    &sender_pubkey,
-                    &[&sender_pubkey],
-                    token_amount,
-                )
-                .map_err(|e| anyhow!("Failed to create token transfer instruction: {}", e))?,
-            );
-
-            // Execute transaction
-            send_transaction(client, keypair, &instructions).await
-        }
-        Err(_) => Err(anyhow!(
-            "Sender doesn't have a token account for {}",
-            token_symbol
-        )),
-    }
+    // Execute transaction
+    send_transaction(client, keypair, &instructions, submitted).await
 }
 
 /// Get balance of a specific SPL token
 pub async fn get_spl_token_balance(
-    client: &RpcClient,
+    client: &ResilientRpcClient,
     address: &str,
     token_symbol: &str,
 ) -> Result<f64> {
@@ -171,3 +327,62 @@ pub async fn get_spl_token_balance(
     // If token not found, return 0
     Ok(0.0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_account(lamports: u64) -> EmptyTokenAccount {
+        EmptyTokenAccount {
+            address: Pubkey::new_unique(),
+            mint: Pubkey::new_unique().to_string(),
+            lamports,
+        }
+    }
+
+    #[test]
+    fn only_accounts_without_tokens_are_closable() {
+        assert!(is_closable("0"));
+        // A single base unit of dust would make the close fail
+        assert!(!is_closable("1"));
+        assert!(!is_closable("2500000"));
+        assert!(!is_closable(""));
+    }
+
+    #[test]
+    fn close_instructions_return_the_rent_to_the_owner() {
+        let owner = Pubkey::new_unique();
+        let accounts = vec![empty_account(2_039_280), empty_account(2_039_280)];
+
+        let instructions = build_close_account_instructions(&accounts, &owner).unwrap();
+
+        assert_eq!(instructions.len(), 2);
+        for (instruction, account) in instructions.iter().zip(&accounts) {
+            let expected = token_instruction::close_account(
+                &TOKEN_PROGRAM_ID,
+                &account.address,
+                &owner,
+                &owner,
+                &[&owner],
+            )
+            .unwrap();
+            assert_eq!(instruction, &expected);
+            assert_eq!(instruction.program_id, TOKEN_PROGRAM_ID);
+            assert_eq!(instruction.accounts[0].pubkey, account.address);
+            // Rent goes to the owner, who also signs as the account authority
+            assert_eq!(instruction.accounts[1].pubkey, owner);
+            assert_eq!(instruction.accounts[2].pubkey, owner);
+            assert!(instruction
+                .accounts
+                .iter()
+                .any(|meta| meta.pubkey == owner && meta.is_signer));
+        }
+    }
+
+    #[test]
+    fn no_accounts_build_no_instructions() {
+        let instructions = build_close_account_instructions(&[], &Pubkey::new_unique()).unwrap();
+
+        assert!(instructions.is_empty());
+    }
+}
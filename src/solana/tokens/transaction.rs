@@ -1,36 +1,349 @@
+use crate::entity::{BotError, TransactionStatus};
+use crate::solana::client::ResilientRpcClient;
 use anyhow::{anyhow, Result};
-use solana_client::nonblocking::rpc_client::RpcClient;
+use lazy_static::lazy_static;
+use log::{info, warn};
 use solana_sdk::{
+    commitment_config::CommitmentConfig,
     instruction::Instruction,
-    signature::{Keypair, Signer},
+    signature::{Keypair, Signature, Signer},
     transaction::Transaction as SolanaTransaction,
 };
+use std::env;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// How long a submitted transaction is polled before it is considered dropped
+///
+/// A blockhash expires after about 60-90 seconds, a transaction that hasn't landed by
+/// then can no longer be processed.
+pub const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Delay between signature status checks
+pub const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long to wait for the blockhash of a dropped transaction to expire before resubmitting
+pub const BLOCKHASH_EXPIRY_WAIT: Duration = Duration::from_secs(60);
+
+/// Upper bound for DROPPED_TX_RETRIES so a congested network isn't flooded with resubmits
+const MAX_DROPPED_RETRIES: u32 = 5;
+
+lazy_static! {
+    static ref DROPPED_RETRIES: u32 = env::var("DROPPED_TX_RETRIES")
+        .ok()
+        .and_then(|value| value.trim().parse::<u32>().ok())
+        .map(|retries| retries.min(MAX_DROPPED_RETRIES))
+        .unwrap_or(0);
+    static ref RETRY_FEE_BUMP_PERCENT: u64 = env::var("DROPPED_TX_FEE_BUMP_PERCENT")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(50);
+}
+
+/// How many times a dropped transaction is resubmitted, 0 (the default) disables it.
+/// Set with the DROPPED_TX_RETRIES environment variable.
+pub fn dropped_retries() -> u32 {
+    *DROPPED_RETRIES
+}
+
+/// Priority fee for the given resubmit attempt, raised by DROPPED_TX_FEE_BUMP_PERCENT
+/// (default 50%) of the original fee per attempt
+pub fn bumped_priority_fee(lamports: u64, attempt: u32) -> u64 {
+    let bump = lamports.saturating_mul(*RETRY_FEE_BUMP_PERCENT) / 100;
+    lamports.saturating_add(bump.saturating_mul(attempt as u64))
+}
+
+/// Receives the signature as soon as a transaction is submitted, before it is confirmed
+pub type SubmittedSender = UnboundedSender<String>;
 
 /// Execute a transaction with the provided instructions
+///
+/// Returns the signature once the transaction is confirmed, a failed or dropped
+/// transaction is returned as `BotError::TransactionNotConfirmed`. A dropped transaction
+/// is signed again with a fresh blockhash up to `dropped_retries()` times.
 pub async fn send_transaction(
-    client: &RpcClient,
+    client: &ResilientRpcClient,
     keypair: &Keypair,
     instructions: &[Instruction],
+    submitted: Option<&SubmittedSender>,
 ) -> Result<String> {
-    // Get recent blockhash
-    let recent_blockhash = client
-        .get_latest_blockhash()
-        .await
-        .map_err(|e| anyhow!("Failed to get recent blockhash: {}", e))?;
-
-    // Create transaction
-    let transaction = SolanaTransaction::new_signed_with_payer(
-        instructions,
-        Some(&keypair.pubkey()),
-        &[keypair],
-        recent_blockhash,
-    );
-
-    // Send transaction
-    let signature = client
-        .send_and_confirm_transaction(&transaction)
+    let mut attempt = 0;
+
+    loop {
+        // Get recent blockhash
+        let (recent_blockhash, last_valid_block_height) = client
+            .get_latest_blockhash()
+            .await
+            .map_err(|e| anyhow!("Failed to get recent blockhash: {}", e))?;
+
+        // Create transaction
+        let transaction = SolanaTransaction::new_signed_with_payer(
+            instructions,
+            Some(&keypair.pubkey()),
+            &[keypair],
+            recent_blockhash,
+        );
+
+        // Send transaction
+        let signature = client
+            .send_transaction(&transaction)
+            .await
+            .map_err(|e| anyhow!("Failed to send transaction: {}", e))?;
+
+        let result = wait_for_confirmation(client, &signature, submitted).await;
+
+        if attempt < dropped_retries()
+            && should_resubmit(client, &result, last_valid_block_height).await
+        {
+            attempt += 1;
+            warn!(
+                "Resubmitting dropped transaction {}, attempt {}",
+                signature, attempt
+            );
+            continue;
+        }
+
+        return result;
+    }
+}
+
+/// Whether the result is a dropped transaction that can be sent again
+///
+/// Transactions that failed on chain are never resubmitted. A dropped one is only
+/// resubmitted once its blockhash expired, before that it could still land and the
+/// resubmit would execute it twice.
+pub async fn should_resubmit(
+    client: &ResilientRpcClient,
+    result: &Result<String>,
+    last_valid_block_height: u64,
+) -> bool {
+    match dropped_signature(result) {
+        Some(signature) => can_resubmit(client, &signature, last_valid_block_height).await,
+        None => false,
+    }
+}
+
+/// Signature of a transaction that was sent but dropped, `None` for confirmed transactions,
+/// transactions that failed on chain and errors before sending
+pub fn dropped_signature(result: &Result<String>) -> Option<Signature> {
+    let Err(error) = result else {
+        return None;
+    };
+
+    BotError::dropped_signature(error).and_then(|signature| signature.parse().ok())
+}
+
+/// Wait up to `BLOCKHASH_EXPIRY_WAIT` for the block height to pass
+/// `last_valid_block_height`, then check the transaction didn't land in the meantime
+///
+/// Returns false when that can't be confirmed, a transaction is never sent twice on a guess.
+pub async fn can_resubmit(
+    client: &ResilientRpcClient,
+    signature: &Signature,
+    last_valid_block_height: u64,
+) -> bool {
+    let started_at = Instant::now();
+
+    loop {
+        match client.get_block_height().await {
+            Ok(block_height) if block_height > last_valid_block_height => break,
+            Ok(_) => {}
+            Err(e) => warn!("Failed to get block height: {}", e),
+        }
+
+        if started_at.elapsed() >= BLOCKHASH_EXPIRY_WAIT {
+            warn!(
+                "Blockhash of dropped transaction {} did not expire",
+                signature
+            );
+            return false;
+        }
+
+        tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+    }
+
+    match client
+        .execute("get_signature_statuses", |client| async move {
+            client.get_signature_statuses(&[*signature]).await
+        })
         .await
-        .map_err(|e| anyhow!("Failed to send transaction: {}", e))?;
+    {
+        Ok(response) => matches!(response.value.first(), Some(None)),
+        Err(e) => {
+            warn!("Failed to get status of transaction {}: {}", signature, e);
+            false
+        }
+    }
+}
+
+/// Report the submitted signature and wait until the transaction is confirmed
+pub async fn wait_for_confirmation(
+    client: &ResilientRpcClient,
+    signature: &Signature,
+    submitted: Option<&SubmittedSender>,
+) -> Result<String> {
+    info!("Transaction submitted: {}", signature);
+
+    if let Some(submitted) = submitted {
+        let _ = submitted.send(signature.to_string());
+    }
+
+    match confirm_transaction(client, signature, client.commitment()).await? {
+        TransactionStatus::Confirmed => Ok(signature.to_string()),
+        status => {
+            warn!("Transaction {} {}", signature, status);
+            Err(BotError::TransactionNotConfirmed {
+                signature: signature.to_string(),
+                status,
+            }
+            .into())
+        }
+    }
+}
+
+/// Poll the signature status until the transaction reaches `commitment`, fails on chain
+/// or `CONFIRMATION_TIMEOUT` passes
+pub async fn confirm_transaction(
+    client: &ResilientRpcClient,
+    signature: &Signature,
+    commitment: CommitmentConfig,
+) -> Result<TransactionStatus> {
+    let started_at = Instant::now();
+
+    loop {
+        // RPC errors are retried until the timeout, the transaction may still land
+        let (error, reached_commitment) = match client
+            .execute("get_signature_statuses", |client| async move {
+                client.get_signature_statuses(&[*signature]).await
+            })
+            .await
+        {
+            Ok(response) => match response.value.first() {
+                Some(Some(status)) => (
+                    status.err.as_ref().map(|error| error.to_string()),
+                    status.satisfies_commitment(commitment),
+                ),
+                _ => (None, false),
+            },
+            Err(e) => {
+                warn!("Failed to get status of transaction {}: {}", signature, e);
+                (None, false)
+            }
+        };
+
+        let timed_out = started_at.elapsed() >= CONFIRMATION_TIMEOUT;
+        if let Some(status) = settled_status(error, reached_commitment, timed_out) {
+            return Ok(status);
+        }
+
+        tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+    }
+}
+
+// Final status after a poll, None while the transaction may still be confirmed.
+// An on-chain error or the commitment reached in the last poll wins over the timeout.
+fn settled_status(
+    error: Option<String>,
+    reached_commitment: bool,
+    timed_out: bool,
+) -> Option<TransactionStatus> {
+    if let Some(error) = error {
+        return Some(TransactionStatus::Failed(error));
+    }
+
+    if reached_commitment {
+        return Some(TransactionStatus::Confirmed);
+    }
+
+    timed_out.then_some(TransactionStatus::Dropped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn not_confirmed(signature: &Signature, status: TransactionStatus) -> Result<String> {
+        Err(BotError::TransactionNotConfirmed {
+            signature: signature.to_string(),
+            status,
+        }
+        .into())
+    }
+
+    #[test]
+    fn dropped_transaction_is_resubmitted() {
+        let signature = Signature::new_unique();
+
+        let result = not_confirmed(&signature, TransactionStatus::Dropped);
+
+        assert_eq!(dropped_signature(&result), Some(signature));
+    }
+
+    #[test]
+    fn transaction_failed_on_chain_is_not_resubmitted() {
+        let signature = Signature::new_unique();
+
+        let result = not_confirmed(
+            &signature,
+            TransactionStatus::Failed("custom program error: 0x1771".to_string()),
+        );
+
+        assert_eq!(dropped_signature(&result), None);
+    }
+
+    #[test]
+    fn confirmed_transactions_and_send_errors_are_not_resubmitted() {
+        assert_eq!(dropped_signature(&Ok("signature".to_string())), None);
+        assert_eq!(
+            dropped_signature(&Err(anyhow!("Failed to send transaction"))),
+            None
+        );
+        assert_eq!(
+            dropped_signature(&Err(BotError::TransactionExpired.into())),
+            None
+        );
+    }
+
+    #[test]
+    fn priority_fee_is_bumped_per_attempt() {
+        assert_eq!(bumped_priority_fee(1_000, 0), 1_000);
+        assert_eq!(bumped_priority_fee(1_000, 1), 1_500);
+        assert_eq!(bumped_priority_fee(1_000, 2), 2_000);
+        assert_eq!(bumped_priority_fee(u64::MAX, 3), u64::MAX);
+    }
+
+    #[test]
+    fn transaction_reaching_the_commitment_is_confirmed() {
+        assert_eq!(
+            settled_status(None, true, false),
+            Some(TransactionStatus::Confirmed)
+        );
+    }
+
+    #[test]
+    fn transaction_with_an_on_chain_error_is_failed() {
+        let error = "Error processing Instruction 2: custom program error: 0x1771".to_string();
+
+        assert_eq!(
+            settled_status(Some(error.clone()), true, false),
+            Some(TransactionStatus::Failed(error))
+        );
+    }
+
+    #[test]
+    fn pending_transaction_is_polled_until_the_timeout() {
+        assert_eq!(settled_status(None, false, false), None);
+        assert_eq!(
+            settled_status(None, false, true),
+            Some(TransactionStatus::Dropped)
+        );
+    }
 
-    Ok(signature.to_string())
+    #[test]
+    fn confirmation_in_the_last_poll_wins_over_the_timeout() {
+        assert_eq!(
+            settled_status(None, true, true),
+            Some(TransactionStatus::Confirmed)
+        );
+    }
 }
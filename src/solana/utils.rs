@@ -1,9 +1,97 @@
 use crate::solana::tokens::constants::{RAY_MINT, USDC_MINT, USDT_MINT};
+use crate::solana::tokens::to_base_units;
 use solana_sdk::pubkey::Pubkey;
 
 // Constants for conversion
 pub const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
 
+// Programs users sometimes paste instead of a wallet, with their names
+const KNOWN_PROGRAMS: [(&str, &str); 9] = [
+    ("11111111111111111111111111111111", "System Program"),
+    (
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+        "Token Program",
+    ),
+    (
+        "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+        "Token-2022 Program",
+    ),
+    (
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL",
+        "Associated Token Program",
+    ),
+    (
+        "ComputeBudget111111111111111111111111111111",
+        "Compute Budget Program",
+    ),
+    (
+        "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr",
+        "Memo Program",
+    ),
+    (
+        "Stake11111111111111111111111111111111111111",
+        "Stake Program",
+    ),
+    (
+        "Vote111111111111111111111111111111111111111",
+        "Vote Program",
+    ),
+    (
+        "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4",
+        "Jupiter Aggregator",
+    ),
+];
+
+/// What an address is, judged from the address alone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AddressKind {
+    /// On the ed25519 curve, someone holds the key and can sign for it
+    Wallet,
+    /// Off the curve, e.g. a PDA or token account, nobody can sign for it
+    OffCurve,
+    /// A well-known program id, with the program's name
+    Program(&'static str),
+}
+
+impl AddressKind {
+    /// Warning to show before sending funds to the address, `None` for a wallet
+    pub fn warning(&self) -> Option<String> {
+        match self {
+            AddressKind::Wallet => None,
+            AddressKind::OffCurve => Some(
+                "This address is off-curve (e.g. a program-derived or token account address), \
+                no wallet key can sign for it. Funds sent there may be stuck unless the program \
+                that owns it can move them."
+                    .to_string(),
+            ),
+            AddressKind::Program(name) => Some(format!(
+                "This address is the {}, not a wallet. Funds sent there are usually lost.",
+                name
+            )),
+        }
+    }
+}
+
+/// Whether the address is a point on the ed25519 curve, i.e. a keypair can exist for it
+pub fn is_on_curve(pubkey: &Pubkey) -> bool {
+    pubkey.is_on_curve()
+}
+
+/// Classify an address without fetching its account
+pub fn address_kind(pubkey: &Pubkey) -> AddressKind {
+    let address = pubkey.to_string();
+
+    if let Some((_, name)) = KNOWN_PROGRAMS.iter().find(|(id, _)| *id == address) {
+        return AddressKind::Program(name);
+    }
+
+    if is_on_curve(pubkey) {
+        AddressKind::Wallet
+    } else {
+        AddressKind::OffCurve
+    }
+}
+
 /// Convert lamports to SOL
 pub fn lamports_to_sol(lamports: u64) -> f64 {
     lamports as f64 / LAMPORTS_PER_SOL
@@ -11,12 +99,12 @@ pub fn lamports_to_sol(lamports: u64) -> f64 {
 
 /// Convert SOL to lamports
 pub fn sol_to_lamports(sol: f64) -> u64 {
-    (sol * LAMPORTS_PER_SOL) as u64
+    (sol * LAMPORTS_PER_SOL).round() as u64
 }
 
 /// Convert amount with decimals to token units
 pub fn convert_to_token_amount(amount: f64, decimals: u8) -> u64 {
-    (amount * 10_f64.powi(decimals as i32)) as u64
+    to_base_units(amount, decimals)
 }
 
 /// Get token info from mint address
@@ -49,3 +137,41 @@ pub fn get_symbol_from_mint(mint: &str) -> String {
         _ => "Unknown".to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::{Keypair, Signer};
+    use std::str::FromStr;
+
+    #[test]
+    fn keypair_address_is_a_wallet() {
+        let wallet = Keypair::new().pubkey();
+
+        assert_eq!(address_kind(&wallet), AddressKind::Wallet);
+        assert_eq!(address_kind(&wallet).warning(), None);
+    }
+
+    #[test]
+    fn program_derived_address_is_off_curve() {
+        let program = Pubkey::from_str("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4").unwrap();
+        let (pda, _) = Pubkey::find_program_address(&[b"authority"], &program);
+
+        assert_eq!(address_kind(&pda), AddressKind::OffCurve);
+        assert!(address_kind(&pda).warning().unwrap().contains("off-curve"));
+    }
+
+    #[test]
+    fn program_id_is_named() {
+        let program = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+
+        assert_eq!(
+            address_kind(&program),
+            AddressKind::Program("Token Program")
+        );
+        assert!(address_kind(&program)
+            .warning()
+            .unwrap()
+            .contains("the Token Program, not a wallet"));
+    }
+}
@@ -5,6 +5,8 @@ use rand::{rng, RngCore};
 use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
 use std::str::FromStr;
 
+use crate::entity::BotError;
+
 /// Generate new wallet with mnemonic phrase.
 pub fn generate_wallet() -> Result<(String, String, String)> {
     // 1) Create 16 bytes (128 bits) of random entropy
@@ -70,5 +72,5 @@ pub fn keypair_from_base58(keypair_base58: &str) -> Result<Keypair> {
 
 /// Convert base58 string to Solana `Pubkey`.
 pub fn parse_pubkey(address: &str) -> Result<Pubkey> {
-    Pubkey::from_str(address).map_err(|e| anyhow!("Invalid Solana address: {}", e))
+    Pubkey::from_str(address).map_err(|_| BotError::InvalidAddress(address.to_string()).into())
 }
@@ -0,0 +1,155 @@
+//! Local HTTP servers standing in for Telegram, Jupiter and the Solana RPC in tests
+
+use serde_json::Value;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+/// Request received by a fake server
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: String,
+    /// Path with the query string
+    pub path: String,
+    pub body: String,
+}
+
+/// Status and JSON body a fake server answers with
+#[derive(Debug)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self::with_status(200, body)
+    }
+
+    pub fn with_status(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+}
+
+/// Local HTTP server answering every request with `respond`, returns its base URL
+///
+/// Each connection serves a single request, so concurrent requests are answered
+/// concurrently.
+pub async fn fake_http<F, Fut>(respond: F) -> String
+where
+    F: Fn(HttpRequest) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = HttpResponse> + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let respond = Arc::new(respond);
+
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let respond = respond.clone();
+
+            tokio::spawn(async move {
+                let Some(request) = read_request(&mut socket).await else {
+                    return;
+                };
+                let response = respond(request).await;
+
+                let reply = format!(
+                    "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.status,
+                    if response.status == 200 { "OK" } else { "Error" },
+                    response.body.len(),
+                    response.body
+                );
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            });
+        }
+    });
+
+    url
+}
+
+// Read the head and the whole body, so closing the socket doesn't reset the connection
+async fn read_request(socket: &mut TcpStream) -> Option<HttpRequest> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    let header_end = loop {
+        if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        let read = socket.read(&mut buf).await.unwrap();
+        if read == 0 {
+            return None;
+        }
+        request.extend_from_slice(&buf[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&request[..header_end]).to_string();
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    while request.len() < header_end + content_length {
+        let read = socket.read(&mut buf).await.unwrap();
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+
+    let mut request_line = head.split_whitespace();
+    Some(HttpRequest {
+        method: request_line.next().unwrap_or_default().to_string(),
+        path: request_line.next().unwrap_or_default().to_string(),
+        body: String::from_utf8_lossy(&request[header_end..]).to_string(),
+    })
+}
+
+/// JSON-RPC call received by [`fake_rpc`]
+#[derive(Debug, Clone)]
+pub struct RpcCall {
+    pub method: String,
+    pub params: Value,
+}
+
+/// Solana JSON-RPC node answering each call with the result `respond` gives for its method
+/// and params. Every call is also sent to the returned receiver, which may be dropped.
+pub async fn fake_rpc<F>(respond: F) -> (String, mpsc::UnboundedReceiver<RpcCall>)
+where
+    F: Fn(&str, &Value) -> Value + Send + Sync + 'static,
+{
+    let (calls_tx, calls_rx) = mpsc::unbounded_channel();
+
+    let url = fake_http(move |request| {
+        let call: Value = serde_json::from_str(&request.body).unwrap();
+        let method = call["method"].as_str().unwrap_or_default().to_string();
+        let result = respond(&method, &call["params"]);
+        let _ = calls_tx.send(RpcCall {
+            method,
+            params: call["params"].clone(),
+        });
+
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": call["id"],
+            "result": result,
+        });
+        async move { HttpResponse::ok(body.to_string()) }
+    })
+    .await;
+
+    (url, calls_rx)
+}
+
+/// `getVersion` result of the fake node
+pub fn rpc_version() -> Value {
+    serde_json::json!({ "solana-core": "2.2.0", "feature-set": 0 })
+}
@@ -5,6 +5,9 @@ use regex::Regex;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 
+use crate::entity::{Cluster, Explorer, PriceCurrency};
+use crate::i18n::{self, Locale};
+
 // Generate QR code for a Solana address
 pub fn generate_qr_code(address: &str) -> Result<Vec<u8>> {
     // Create QR code with high error correction
@@ -30,26 +33,302 @@ pub fn validate_solana_address(address: &str) -> bool {
     Pubkey::from_str(address).is_ok()
 }
 
+/// Parse a number typed by a user, accepting a comma as the decimal separator ("0,5")
+///
+/// Input where the comma could be a thousands separator ("1,000", "1,000,000", "1,000.5") is
+/// rejected rather than guessed, a wrong guess would trade a thousand times the amount.
+pub fn parse_decimal(input: &str) -> Result<f64> {
+    let input = input.trim();
+    let ambiguous = || {
+        anyhow!(
+            "\"{}\" is ambiguous, use a dot for decimals, e.g. 1.5",
+            input
+        )
+    };
+
+    let normalized = match input.matches(',').count() {
+        0 => input.to_string(),
+        1 if !input.contains('.') => {
+            let (integer, fraction) = input.split_once(',').unwrap_or((input, ""));
+            let integer_digits = integer
+                .trim_start_matches(['-', '+'])
+                .trim_start_matches('0');
+            if fraction.len() == 3 && !integer_digits.is_empty() {
+                return Err(ambiguous());
+            }
+            format!("{}.{}", integer, fraction)
+        }
+        _ => return Err(ambiguous()),
+    };
+
+    // "inf" and "NaN" parse as floats but are no amounts
+    normalized
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| anyhow!("Invalid number \"{}\"", input))
+}
+
 // Parse amount and token from input string
 pub fn parse_amount_and_token(input: &str) -> Option<(f64, &str)> {
     lazy_static! {
-        static ref RE: Regex = Regex::new(r"^(\d+(?:\.\d+)?)\s+([A-Za-z]+)$").unwrap();
+        static ref RE: Regex = Regex::new(r"^(\d+(?:[.,]\d+)?)\s+([A-Za-z]+)$").unwrap();
     }
 
     RE.captures(input).and_then(|cap| {
         let amount_str = cap.get(1)?.as_str();
         let token = cap.get(2)?.as_str();
 
-        amount_str.parse::<f64>().ok().map(|amount| (amount, token))
+        parse_decimal(amount_str).ok().map(|amount| (amount, token))
     })
 }
 
-// Format amount with appropriate precision
-pub fn format_amount(amount: f64, token: &str) -> String {
-    match token.to_uppercase().as_str() {
-        "SOL" => format!("{:.9}", amount),           // 9 decimals
-        "USDC" | "USDT" => format!("{:.6}", amount), // 6 decimals
-        _ => format!("{:.6}", amount),               // Default to 6 decimals
+// Amounts from a million up are shown compact, e.g. 1.5M
+const COMPACT_UNITS: [(f64, &str); 3] = [
+    (1e12, "number.trillion"),
+    (1e9, "number.billion"),
+    (1e6, "number.million"),
+];
+
+// Digits after the point are capped, smaller amounts are shown as 0
+const MAX_DISPLAY_DECIMALS: usize = 12;
+
+/// Format an amount for messages with the separators of the user's language
+///
+/// Large amounts are grouped (1,234.56) or compact (1.5M), amounts below 1 keep four
+/// significant digits (0.00001234) instead of being rounded away.
+pub fn format_amount(amount: f64, locale: Locale) -> String {
+    if !amount.is_finite() {
+        return amount.to_string();
+    }
+
+    let sign = if amount < 0.0 { "-" } else { "" };
+    let abs = amount.abs();
+
+    if let Some((unit, key)) = COMPACT_UNITS.iter().find(|(unit, _)| abs >= *unit) {
+        let compact = trim_zeros(format!("{:.2}", abs / unit));
+        return format!(
+            "{}{}{}",
+            sign,
+            localize_number(&compact, locale),
+            i18n::t(key, locale)
+        );
+    }
+
+    let decimals = if abs >= 1000.0 {
+        2
+    } else if abs >= 1.0 {
+        4
+    } else if abs > 0.0 {
+        // Four significant digits
+        ((-abs.log10()).ceil() as usize + 3).min(MAX_DISPLAY_DECIMALS)
+    } else {
+        0
+    };
+
+    let formatted = trim_zeros(format!("{:.*}", decimals, abs));
+    if formatted == "0" {
+        return formatted;
+    }
+
+    format!("{}{}", sign, localize_number(&formatted, locale))
+}
+
+/// Format a USD value, cents are always shown from $0.01 up to the compact range
+pub fn format_usd(amount: f64, locale: Locale) -> String {
+    let sign = if amount < 0.0 { "-" } else { "" };
+    let abs = amount.abs();
+
+    if (0.01..1e6).contains(&abs) {
+        format!(
+            "{}${}",
+            sign,
+            localize_number(&format!("{:.2}", abs), locale)
+        )
+    } else {
+        format!("{}${}", sign, format_amount(abs, locale))
+    }
+}
+
+/// Format a price in both quotes, the chosen quote first, e.g. ("$1.50", "0.01 SOL")
+pub fn format_quoted_price(
+    price_in_sol: f64,
+    price_in_usdc: f64,
+    quote: &PriceCurrency,
+    locale: Locale,
+) -> (String, String) {
+    let sol = format!("{} SOL", format_locale_price(price_in_sol, locale));
+    let usd = if price_in_usdc.abs() >= 1.0 {
+        format_usd(price_in_usdc, locale)
+    } else {
+        format!("${}", format_locale_price(price_in_usdc, locale))
+    };
+
+    match quote {
+        PriceCurrency::Sol => (sol, usd),
+        PriceCurrency::Usdc => (usd, sol),
+    }
+}
+
+// Significant digits of prices below 1
+const PRICE_SIGNIFICANT_DIGITS: usize = 4;
+
+// Prices with this many zeros after the point are written with a subscript zero count
+const SUBSCRIPT_ZEROS_FROM: usize = 4;
+
+const SUBSCRIPT_DIGITS: [char; 10] = ['₀', '₁', '₂', '₃', '₄', '₅', '₆', '₇', '₈', '₉'];
+
+/// Format a token price so that even tiny meme token prices keep their digits
+///
+/// Prices from 1 are rounded to four decimals (two from 1000), smaller prices keep four
+/// significant digits. From four zeros after the point the zeros are counted in
+/// subscript, 0.0000000123 becomes 0.0₇123.
+pub fn format_price(price: f64) -> String {
+    if !price.is_finite() {
+        return price.to_string();
+    }
+
+    let sign = if price < 0.0 { "-" } else { "" };
+    let abs = price.abs();
+
+    if abs == 0.0 {
+        return "0".to_string();
+    }
+
+    if abs >= 1.0 {
+        let decimals = if abs >= 1000.0 { 2 } else { 4 };
+        return format!("{}{}", sign, trim_zeros(format!("{:.*}", decimals, abs)));
+    }
+
+    // Zeros between the point and the first significant digit
+    let zeros = (-abs.log10()).ceil() as usize - 1;
+    let formatted = trim_zeros(format!("{:.*}", zeros + PRICE_SIGNIFICANT_DIGITS, abs));
+
+    // Counted again on the rounded price, 0.00009999 rounds to 0.0001
+    let fraction = formatted.strip_prefix("0.").unwrap_or_default();
+    let zeros = fraction.len() - fraction.trim_start_matches('0').len();
+    if zeros < SUBSCRIPT_ZEROS_FROM {
+        return format!("{}{}", sign, formatted);
+    }
+
+    let subscript: String = zeros
+        .to_string()
+        .chars()
+        .filter_map(|digit| digit.to_digit(10))
+        .map(|digit| SUBSCRIPT_DIGITS[digit as usize])
+        .collect();
+
+    format!("{}0.0{}{}", sign, subscript, &fraction[zeros..])
+}
+
+/// `format_price` with the locale's separators, for prices shown in a sentence
+pub fn format_locale_price(price: f64, locale: Locale) -> String {
+    localize_number(&format_price(price), locale)
+}
+
+/// Format a USD price, cents from $1 up and `format_price` digits below, e.g. $0.0₅123
+pub fn format_usd_price(price: f64) -> String {
+    if price.abs() >= 1.0 {
+        format!("${:.2}", price)
+    } else {
+        format!("${}", format_price(price))
+    }
+}
+
+/// Format a price change in percent as "▲ 5.20%" or "▼ 3.10%"
+pub fn format_price_change(change: f64, locale: Locale) -> String {
+    let arrow = if change < 0.0 { "▼" } else { "▲" };
+
+    format!(
+        "{} {}%",
+        arrow,
+        localize_number(&format!("{:.2}", change.abs()), locale)
+    )
+}
+
+// Block characters of a sparkline, from the lowest to the highest value
+const SPARKLINE_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Render prices as a unicode sparkline of at most `width` characters
+///
+/// The points are split into `width` equal buckets and every bucket shows its average,
+/// scaled between the lowest and highest bucket. Returns `None` for fewer than two points,
+/// there is no trend to show.
+pub fn sparkline(points: &[f64], width: usize) -> Option<String> {
+    let points: Vec<f64> = points.iter().copied().filter(|p| p.is_finite()).collect();
+    let width = width.min(points.len());
+    if width < 2 {
+        return None;
+    }
+
+    let buckets: Vec<f64> = (0..width)
+        .map(|i| {
+            let bucket = &points[i * points.len() / width..(i + 1) * points.len() / width];
+            bucket.iter().sum::<f64>() / bucket.len() as f64
+        })
+        .collect();
+
+    let min = buckets.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = buckets.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let top = SPARKLINE_LEVELS.len() - 1;
+
+    Some(
+        buckets
+            .iter()
+            .map(|value| {
+                // A flat line is drawn in the middle
+                let level = if max - min <= f64::EPSILON * max.abs() {
+                    top / 2
+                } else {
+                    ((value - min) / (max - min) * top as f64).round() as usize
+                };
+                SPARKLINE_LEVELS[level.min(top)]
+            })
+            .collect(),
+    )
+}
+
+// Format a token amount with the token's decimals, trailing zeros are dropped
+pub fn format_token_amount(amount: f64, decimals: u8) -> String {
+    trim_zeros(format!("{:.*}", decimals as usize, amount))
+}
+
+// "1.500" -> "1.5", "2.000" -> "2"
+fn trim_zeros(formatted: String) -> String {
+    if formatted.contains('.') {
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    } else {
+        formatted
+    }
+}
+
+// Apply the locale's group and decimal separators to a plain "1234567.89"
+fn localize_number(number: &str, locale: Locale) -> String {
+    let (integer, fraction) = match number.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (number, None),
+    };
+
+    let group_separator = i18n::t("number.group_separator", locale);
+    let mut grouped = String::new();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push_str(group_separator);
+        }
+        grouped.push(digit);
+    }
+
+    match fraction {
+        Some(fraction) => format!(
+            "{}{}{}",
+            grouped,
+            i18n::t("number.decimal_separator", locale),
+            fraction
+        ),
+        None => grouped,
     }
 }
 
@@ -96,6 +375,115 @@ pub fn parse_solana_address(address: &str) -> Result<Pubkey> {
     Pubkey::from_str(address).map_err(|_| anyhow!("Invalid Solana address format"))
 }
 
+/// Transfer request carried by a Solana Pay link
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolanaPayRequest {
+    pub recipient: String,
+    pub amount: Option<f64>,
+    /// Token mint, `None` means native SOL
+    pub spl_token: Option<String>,
+}
+
+// Check whether the input looks like a Solana Pay link rather than a plain address
+pub fn is_solana_pay_uri(input: &str) -> bool {
+    input.trim().starts_with("solana:")
+}
+
+// Parse a Solana Pay transfer link like "solana:<address>?amount=1.5&spl-token=<mint>"
+pub fn parse_solana_pay(uri: &str) -> Result<SolanaPayRequest> {
+    let rest = uri
+        .trim()
+        .strip_prefix("solana:")
+        .ok_or_else(|| anyhow!("Payment links must start with \"solana:\""))?;
+
+    let (recipient, query) = rest.split_once('?').unwrap_or((rest, ""));
+    if !validate_solana_address(recipient) {
+        return Err(anyhow!(
+            "The recipient in the link is not a valid Solana address"
+        ));
+    }
+
+    let mut amount = None;
+    let mut spl_token = None;
+
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+        match key {
+            "amount" => {
+                let value = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|value| value.is_finite() && *value > 0.0)
+                    .ok_or_else(|| anyhow!("Invalid amount \"{}\" in the link", value))?;
+                amount = Some(value);
+            }
+            "spl-token" => {
+                if !validate_solana_address(value) {
+                    return Err(anyhow!("The token in the link is not a valid mint address"));
+                }
+                spl_token = Some(value.to_string());
+            }
+            // label, message, memo and reference don't affect the transfer
+            _ => {}
+        }
+    }
+
+    Ok(SolanaPayRequest {
+        recipient: recipient.to_string(),
+        amount,
+        spl_token,
+    })
+}
+
+// Build a Solana Pay transfer link for native SOL, optionally with a requested amount
+pub fn solana_pay_uri(address: &str, amount: Option<f64>) -> String {
+    match amount {
+        Some(amount) => format!("solana:{}?amount={}", address, amount),
+        None => format!("solana:{}", address),
+    }
+}
+
+// Parse a duration like "30m", "1h", "24h" or "7d", up to 365 days
+pub fn parse_duration(input: &str) -> Result<chrono::Duration> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"^(\d+)\s*([mhd])$").unwrap();
+    }
+
+    let input = input.trim().to_lowercase();
+
+    let cap = RE
+        .captures(&input)
+        .ok_or_else(|| anyhow!("Invalid duration format. Use e.g. 30m, 1h, 24h or 7d"))?;
+
+    let value = cap[1]
+        .parse::<i64>()
+        .map_err(|_| anyhow!("Invalid duration value"))?;
+
+    if value == 0 {
+        return Err(anyhow!("Duration must be greater than zero"));
+    }
+
+    match &cap[2] {
+        "m" => chrono::Duration::try_minutes(value),
+        "h" => chrono::Duration::try_hours(value),
+        _ => chrono::Duration::try_days(value),
+    }
+    .filter(|duration| *duration <= chrono::Duration::days(365))
+    .ok_or_else(|| anyhow!("Duration cannot exceed 365 days"))
+}
+
+// Parse limit order expiry like "1h", "24h", "7d" or "never"
+pub fn parse_order_expiry(input: &str) -> Result<Option<chrono::Duration>> {
+    let normalized = input.trim().to_lowercase();
+
+    if normalized == "never" || normalized == "gtc" {
+        return Ok(None);
+    }
+
+    parse_duration(&normalized).map(Some)
+}
+
 // Shorten address for display
 pub fn shorten_address(address: &str) -> String {
     if address.len() <= 10 {
@@ -107,3 +495,350 @@ pub fn shorten_address(address: &str) -> String {
 
     format!("{}...{}", start, end)
 }
+
+// Transaction link on the chosen explorer, non-mainnet clusters are passed as a query param
+pub fn format_tx_url(signature: &str, explorer: Explorer, cluster: Cluster) -> String {
+    let (base, cluster_param) = match explorer {
+        Explorer::SolanaExplorer => (
+            "https://explorer.solana.com/tx",
+            match cluster {
+                Cluster::Mainnet => None,
+                Cluster::Devnet => Some("devnet"),
+                Cluster::Testnet => Some("testnet"),
+            },
+        ),
+        Explorer::Solscan => (
+            "https://solscan.io/tx",
+            match cluster {
+                Cluster::Mainnet => None,
+                Cluster::Devnet => Some("devnet"),
+                Cluster::Testnet => Some("testnet"),
+            },
+        ),
+        Explorer::SolanaFm => (
+            "https://solana.fm/tx",
+            match cluster {
+                Cluster::Mainnet => None,
+                Cluster::Devnet => Some("devnet-solana"),
+                Cluster::Testnet => Some("testnet-solana"),
+            },
+        ),
+    };
+
+    match cluster_param {
+        Some(cluster) => format!("{}/{}?cluster={}", base, signature, cluster),
+        None => format!("{}/{}", base, signature),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPIENT: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    #[test]
+    fn parses_dot_and_comma_decimals() {
+        assert_eq!(parse_decimal("1.5").unwrap(), 1.5);
+        assert_eq!(parse_decimal("0,5").unwrap(), 0.5);
+        assert_eq!(parse_decimal(" 2,25 ").unwrap(), 2.25);
+        assert_eq!(parse_decimal("10").unwrap(), 10.0);
+    }
+
+    #[test]
+    fn parses_a_leading_separator() {
+        assert_eq!(parse_decimal(".5").unwrap(), 0.5);
+        assert_eq!(parse_decimal(",5").unwrap(), 0.5);
+    }
+
+    #[test]
+    fn parses_negative_numbers() {
+        // Range checks are left to the callers
+        assert_eq!(parse_decimal("-0,5").unwrap(), -0.5);
+        assert_eq!(parse_decimal("-1.25").unwrap(), -1.25);
+    }
+
+    #[test]
+    fn three_decimals_after_a_leading_zero_are_not_ambiguous() {
+        assert_eq!(parse_decimal("0,125").unwrap(), 0.125);
+        assert_eq!(parse_decimal("-0,125").unwrap(), -0.125);
+    }
+
+    #[test]
+    fn rejects_possible_thousands_separators() {
+        for input in ["1,000", "1,000,000", "1,000.5", "1.000,5", "1,5,0"] {
+            let err = parse_decimal(input).unwrap_err();
+            assert!(err.to_string().contains("ambiguous"), "{}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        for input in ["", "abc", "1.5 SOL", "1..5", ",", "inf", "NaN"] {
+            assert!(parse_decimal(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parses_a_bare_solana_pay_address() {
+        let request = parse_solana_pay(&format!("solana:{}", RECIPIENT)).unwrap();
+
+        assert_eq!(
+            request,
+            SolanaPayRequest {
+                recipient: RECIPIENT.to_string(),
+                amount: None,
+                spl_token: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_a_solana_pay_amount() {
+        let uri = format!("solana:{}?amount=1.5&label=Coffee", RECIPIENT);
+
+        let request = parse_solana_pay(&uri).unwrap();
+
+        assert_eq!(request.recipient, RECIPIENT);
+        assert_eq!(request.amount, Some(1.5));
+        assert_eq!(request.spl_token, None);
+    }
+
+    #[test]
+    fn parses_a_solana_pay_spl_token() {
+        let uri = format!("solana:{}?amount=10&spl-token={}", RECIPIENT, USDC);
+
+        let request = parse_solana_pay(&uri).unwrap();
+
+        assert_eq!(request.amount, Some(10.0));
+        assert_eq!(request.spl_token.as_deref(), Some(USDC));
+    }
+
+    #[test]
+    fn rejects_malformed_solana_pay_links() {
+        assert!(parse_solana_pay(RECIPIENT).is_err());
+        assert!(parse_solana_pay("solana:not-an-address").is_err());
+        assert!(parse_solana_pay(&format!("solana:{}?amount=abc", RECIPIENT)).is_err());
+        assert!(parse_solana_pay(&format!("solana:{}?amount=-1", RECIPIENT)).is_err());
+        assert!(parse_solana_pay(&format!("solana:{}?spl-token=xyz", RECIPIENT)).is_err());
+    }
+
+    #[test]
+    fn solana_pay_link_round_trips() {
+        let uri = solana_pay_uri(RECIPIENT, Some(0.25));
+
+        assert!(is_solana_pay_uri(&uri));
+        assert_eq!(parse_solana_pay(&uri).unwrap().amount, Some(0.25));
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(
+            parse_duration("30m").unwrap(),
+            chrono::Duration::minutes(30)
+        );
+        assert_eq!(parse_duration(" 1H ").unwrap(), chrono::Duration::hours(1));
+        assert_eq!(parse_duration("24 h").unwrap(), chrono::Duration::hours(24));
+        assert_eq!(parse_duration("7d").unwrap(), chrono::Duration::days(7));
+        assert_eq!(parse_duration("365d").unwrap(), chrono::Duration::days(365));
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("0h").is_err());
+        assert!(parse_duration("1w").is_err());
+        assert!(parse_duration("-1h").is_err());
+        assert!(parse_duration("1.5h").is_err());
+        assert!(parse_duration("366d").is_err());
+        assert!(parse_duration("99999999999999999999d").is_err());
+    }
+
+    #[test]
+    fn never_expiring_orders() {
+        assert_eq!(parse_order_expiry("never").unwrap(), None);
+        assert_eq!(parse_order_expiry("GTC").unwrap(), None);
+        assert_eq!(
+            parse_order_expiry("1h").unwrap(),
+            Some(chrono::Duration::hours(1))
+        );
+    }
+
+    #[test]
+    fn large_amounts_are_compact() {
+        assert_eq!(format_amount(1_500_000.0, Locale::En), "1.5M");
+        assert_eq!(format_amount(2_000_000_000.0, Locale::En), "2B");
+        assert_eq!(format_amount(3_250_000_000_000.0, Locale::En), "3.25T");
+        assert_eq!(format_amount(1_500_000.0, Locale::Ru), "1,5\u{a0}млн");
+    }
+
+    #[test]
+    fn thousands_are_grouped() {
+        assert_eq!(format_amount(1_500.0, Locale::En), "1,500");
+        assert_eq!(format_amount(1_234.567, Locale::En), "1,234.57");
+        assert_eq!(format_amount(999_999.0, Locale::En), "999,999");
+        assert_eq!(format_amount(1_234.5, Locale::Ru), "1\u{a0}234,5");
+        assert_eq!(format_amount(-1_500.0, Locale::En), "-1,500");
+    }
+
+    #[test]
+    fn small_amounts_keep_four_significant_digits() {
+        assert_eq!(format_amount(12.3456789, Locale::En), "12.3457");
+        assert_eq!(format_amount(0.5, Locale::En), "0.5");
+        assert_eq!(format_amount(0.0000123456, Locale::En), "0.00001235");
+        assert_eq!(format_amount(0.000001, Locale::En), "0.000001");
+        assert_eq!(format_amount(0.0000123456, Locale::Ru), "0,00001235");
+    }
+
+    #[test]
+    fn zero_and_dust_amounts_are_shown_as_zero() {
+        assert_eq!(format_amount(0.0, Locale::En), "0");
+        assert_eq!(format_amount(-0.0, Locale::En), "0");
+        assert_eq!(format_amount(1e-15, Locale::En), "0");
+    }
+
+    #[test]
+    fn usd_values_show_cents() {
+        assert_eq!(format_usd(1_234.5, Locale::En), "$1,234.50");
+        assert_eq!(format_usd(0.5, Locale::En), "$0.50");
+        assert_eq!(format_usd(0.004, Locale::En), "$0.004");
+        assert_eq!(format_usd(2_500_000.0, Locale::En), "$2.5M");
+        assert_eq!(format_usd(0.0, Locale::En), "$0");
+    }
+
+    #[test]
+    fn locale_price_keeps_the_small_price_digits() {
+        assert_eq!(format_locale_price(0.0000000123, Locale::En), "0.0₇123");
+        assert_eq!(format_locale_price(0.00012344, Locale::En), "0.0001234");
+        assert_eq!(
+            format_locale_price(0.00012344, Locale::Ru),
+            format!(
+                "0{}0001234",
+                i18n::t("number.decimal_separator", Locale::Ru)
+            )
+        );
+    }
+
+    #[test]
+    fn price_changes_show_their_direction_with_two_decimals() {
+        assert_eq!(format_price_change(5.2, Locale::En), "▲ 5.20%");
+        assert_eq!(format_price_change(-3.104, Locale::En), "▼ 3.10%");
+        assert_eq!(format_price_change(12.3456, Locale::En), "▲ 12.35%");
+        assert_eq!(format_price_change(0.0, Locale::En), "▲ 0.00%");
+        assert_eq!(
+            format_price_change(-1_234.5, Locale::Ru),
+            "▼ 1\u{a0}234,50%"
+        );
+    }
+
+    #[test]
+    fn sparkline_scales_points_between_the_lowest_and_highest() {
+        assert_eq!(sparkline(&[1.0, 2.0, 3.0, 4.0], 4).as_deref(), Some("▁▃▆█"));
+        assert_eq!(sparkline(&[4.0, 1.0], 8).as_deref(), Some("█▁"));
+    }
+
+    #[test]
+    fn sparkline_buckets_average_their_points() {
+        let points = [1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0];
+        assert_eq!(sparkline(&points, 4).as_deref(), Some("▁▃▆█"));
+
+        // Buckets of uneven size, 0..2, 2..5 and 5..8
+        let points = [0.0, 0.0, 3.0, 3.0, 3.0, 9.0, 9.0, 9.0];
+        assert_eq!(sparkline(&points, 3).as_deref(), Some("▁▃█"));
+    }
+
+    #[test]
+    fn sparkline_needs_two_points() {
+        assert_eq!(sparkline(&[], 8), None);
+        assert_eq!(sparkline(&[1.0], 8), None);
+        assert_eq!(sparkline(&[1.0, f64::NAN], 8), None);
+        assert_eq!(sparkline(&[1.0, 2.0], 1), None);
+    }
+
+    #[test]
+    fn flat_sparkline_is_drawn_in_the_middle() {
+        assert_eq!(sparkline(&[2.5, 2.5, 2.5], 8).as_deref(), Some("▄▄▄"));
+    }
+
+    #[test]
+    fn shortens_long_addresses_only() {
+        assert_eq!(shorten_address(RECIPIENT), "9WzDX...tAWWM");
+        assert_eq!(shorten_address("short"), "short");
+    }
+
+    const SIGNATURE: &str =
+        "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+
+    #[test]
+    fn mainnet_tx_urls_have_no_cluster_param() {
+        assert_eq!(
+            format_tx_url(SIGNATURE, Explorer::SolanaExplorer, Cluster::Mainnet),
+            format!("https://explorer.solana.com/tx/{}", SIGNATURE)
+        );
+        assert_eq!(
+            format_tx_url(SIGNATURE, Explorer::Solscan, Cluster::Mainnet),
+            format!("https://solscan.io/tx/{}", SIGNATURE)
+        );
+        assert_eq!(
+            format_tx_url(SIGNATURE, Explorer::SolanaFm, Cluster::Mainnet),
+            format!("https://solana.fm/tx/{}", SIGNATURE)
+        );
+    }
+
+    #[test]
+    fn devnet_tx_urls_use_each_explorers_cluster_name() {
+        assert_eq!(
+            format_tx_url(SIGNATURE, Explorer::SolanaExplorer, Cluster::Devnet),
+            format!(
+                "https://explorer.solana.com/tx/{}?cluster=devnet",
+                SIGNATURE
+            )
+        );
+        assert_eq!(
+            format_tx_url(SIGNATURE, Explorer::Solscan, Cluster::Devnet),
+            format!("https://solscan.io/tx/{}?cluster=devnet", SIGNATURE)
+        );
+        assert_eq!(
+            format_tx_url(SIGNATURE, Explorer::SolanaFm, Cluster::Devnet),
+            format!("https://solana.fm/tx/{}?cluster=devnet-solana", SIGNATURE)
+        );
+    }
+
+    #[test]
+    fn testnet_tx_urls_use_each_explorers_cluster_name() {
+        assert!(
+            format_tx_url(SIGNATURE, Explorer::SolanaExplorer, Cluster::Testnet)
+                .ends_with("?cluster=testnet")
+        );
+        assert!(
+            format_tx_url(SIGNATURE, Explorer::Solscan, Cluster::Testnet)
+                .ends_with("?cluster=testnet")
+        );
+        assert!(
+            format_tx_url(SIGNATURE, Explorer::SolanaFm, Cluster::Testnet)
+                .ends_with("?cluster=testnet-solana")
+        );
+    }
+
+    #[test]
+    fn token_amounts_use_the_tokens_decimals() {
+        // 0 decimals, e.g. an NFT or a whole-unit token
+        assert_eq!(format_token_amount(3.0, 0), "3");
+        // 2 decimals
+        assert_eq!(format_token_amount(12.5, 2), "12.5");
+        assert_eq!(format_token_amount(0.01, 2), "0.01");
+        // 6 decimals, e.g. USDC
+        assert_eq!(format_token_amount(1.234567, 6), "1.234567");
+        assert_eq!(format_token_amount(100.0, 6), "100");
+        // 9 decimals keep digits a fixed 6 would round away
+        assert_eq!(format_token_amount(0.000000123, 9), "0.000000123");
+        assert_eq!(format_token_amount(1.123456789, 9), "1.123456789");
+    }
+
+    #[test]
+    fn token_amounts_are_rounded_to_the_tokens_decimals() {
+        assert_eq!(format_token_amount(2.999, 2), "3");
+        assert_eq!(format_token_amount(0.4, 0), "0");
+    }
+}
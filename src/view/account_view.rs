@@ -0,0 +1,124 @@
+use crate::interactor::account_interactor::AccountInfo;
+use crate::utils::shorten_address;
+use anyhow::Result;
+use async_trait::async_trait;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+    utils::html,
+    Bot,
+};
+
+#[async_trait]
+pub trait AccountView: Send + Sync {
+    async fn display_account_info(&self, info: &AccountInfo) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramAccountView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramAccountView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+#[async_trait]
+impl AccountView for TelegramAccountView {
+    async fn display_account_info(&self, info: &AccountInfo) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "← Back to Menu",
+            "menu",
+        )]]);
+
+        let username = match &info.username {
+            Some(username) => format!("@{}", html::escape(username)),
+            None => "not set".to_string(),
+        };
+        let wallet = match &info.wallet_address {
+            Some(address) => shorten_address(address),
+            None => "no wallet yet".to_string(),
+        };
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "👤 <b>Account Info</b>\n\n\
+                     • Telegram ID: <code>{}</code>\n\
+                     • Username: {}\n\
+                     • Registered: {} UTC\n\
+                     • Wallet: <code>{}</code>\n\
+                     • Active limit orders: {}\n\
+                     • Active DCA orders: {}\n\
+                     • Bot version: {}\n\n\
+                     You can share this message with support, it contains no keys.",
+                    info.telegram_id,
+                    username,
+                    info.registered_at.format("%Y-%m-%d %H:%M"),
+                    wallet,
+                    info.active_limit_orders,
+                    info.active_dca_orders,
+                    info.version
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("❌ Error: {}", error_message))
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::User;
+    use chrono::Utc;
+
+    fn render(info: AccountInfo) -> String {
+        let (tx, rx) = std::sync::mpsc::channel();
+        crate::commands::testing::run_with_large_stack(move || async move {
+            let (bot, mut calls) = crate::commands::testing::fake_telegram().await;
+            let view = TelegramAccountView::new(bot, ChatId(1));
+
+            view.display_account_info(&info).await.unwrap();
+
+            tx.send(calls.recv().await.unwrap().body).unwrap();
+        });
+        rx.recv().unwrap()
+    }
+
+    #[test]
+    fn account_info_shows_the_address_and_never_the_keys() {
+        let (mnemonic, private_key, address) = crate::solana::generate_wallet().unwrap();
+        let user = User {
+            id: 1,
+            telegram_id: 1001,
+            username: Some("alice".to_string()),
+            solana_address: Some(address.clone()),
+            encrypted_private_key: Some(private_key.clone()),
+            mnemonic: Some(mnemonic.clone()),
+            settings: None,
+            created_at: Utc::now(),
+            referred_by: None,
+            last_seen_signature: None,
+        };
+
+        let sent = render(AccountInfo::from_user(user, 2, 1));
+
+        assert!(sent.contains(&shorten_address(&address)));
+        assert!(sent.contains("1001"));
+        assert!(!sent.contains(&private_key));
+        assert!(!sent.contains(&mnemonic));
+    }
+}
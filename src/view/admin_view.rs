@@ -0,0 +1,217 @@
+use crate::entity::BlacklistedToken;
+use crate::interactor::admin_interactor::UserOverview;
+use crate::view::message_utils::send_long_message;
+use anyhow::Result;
+use async_trait::async_trait;
+use teloxide::{prelude::*, types::ParseMode, utils::html, Bot, RequestError};
+
+#[async_trait]
+pub trait AdminView: Send + Sync {
+    async fn display_unknown_command(&self) -> Result<()>;
+    async fn display_usage(&self) -> Result<()>;
+    async fn display_user_overview(&self, overview: &UserOverview) -> Result<()>;
+    async fn display_broadcast_started(&self, recipients: usize) -> Result<()>;
+    /// Sends the broadcast text to a single user
+    async fn deliver_broadcast(&self, telegram_id: i64, text: &str) -> Result<()>;
+    async fn display_broadcast_report(&self, sent: usize, failed: usize) -> Result<()>;
+    async fn display_blacklist(&self, tokens: &[BlacklistedToken]) -> Result<()>;
+    async fn display_token_blacklisted(&self, mint_address: &str) -> Result<()>;
+    async fn display_token_unblacklisted(&self, mint_address: &str, removed: bool) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramAdminView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramAdminView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+#[async_trait]
+impl AdminView for TelegramAdminView {
+    async fn display_unknown_command(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "Unknown command. Use /help to see the available commands.",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn display_usage(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "<b>Admin commands</b>\n\n\
+                 /admin users - show registered users\n\
+                 /admin broadcast &lt;text&gt; - send a message to all users\n\
+                 /admin blacklist - show blacklisted tokens\n\
+                 /admin blacklist add &lt;mint&gt; [reason] - warn users before they buy a token\n\
+                 /admin blacklist remove &lt;mint&gt; - remove a token from the blacklist",
+            )
+            .parse_mode(ParseMode::Html)
+            .await?;
+        Ok(())
+    }
+
+    async fn display_user_overview(&self, overview: &UserOverview) -> Result<()> {
+        let mut message = format!(
+            "<b>Users</b>\n\nRegistered users: <b>{}</b>\n\n",
+            overview.total_users
+        );
+
+        if !overview.recent_users.is_empty() {
+            message.push_str("<b>Latest users:</b>\n");
+        }
+
+        for user in &overview.recent_users {
+            let username = user
+                .username
+                .as_deref()
+                .map(|username| format!(" @{}", username))
+                .unwrap_or_default();
+            let wallet = if user.solana_address.is_some() {
+                ""
+            } else {
+                ", no wallet"
+            };
+
+            message.push_str(&format!(
+                "• <code>{}</code>{} - {}{}\n",
+                user.telegram_id,
+                username,
+                user.created_at.format("%Y-%m-%d"),
+                wallet
+            ));
+        }
+
+        send_long_message(
+            &self.bot,
+            self.chat_id,
+            message,
+            Some(ParseMode::Html),
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn display_broadcast_started(&self, recipients: usize) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!("📣 Sending the broadcast to {} users...", recipients),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn deliver_broadcast(&self, telegram_id: i64, text: &str) -> Result<()> {
+        let result = self.bot.send_message(ChatId(telegram_id), text).await;
+
+        // Telegram asks to slow down when the flood limit is hit, wait and try once more
+        if let Err(RequestError::RetryAfter(retry_after)) = result {
+            tokio::time::sleep(retry_after.duration()).await;
+            self.bot.send_message(ChatId(telegram_id), text).await?;
+        } else {
+            result?;
+        }
+
+        Ok(())
+    }
+
+    async fn display_broadcast_report(&self, sent: usize, failed: usize) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "✅ <b>Broadcast finished</b>\n\n\
+                     • Delivered: {}\n\
+                     • Failed: {} (users who blocked the bot or deleted their account)",
+                    sent, failed
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .await?;
+        Ok(())
+    }
+
+    async fn display_blacklist(&self, tokens: &[BlacklistedToken]) -> Result<()> {
+        if tokens.is_empty() {
+            self.bot
+                .send_message(self.chat_id, "No tokens are blacklisted.")
+                .await?;
+            return Ok(());
+        }
+
+        let mut message = format!("<b>Blacklisted tokens ({})</b>\n\n", tokens.len());
+        for token in tokens {
+            let reason = token
+                .reason
+                .as_deref()
+                .map(|reason| format!(" - {}", html::escape(reason)))
+                .unwrap_or_default();
+
+            message.push_str(&format!(
+                "• <code>{}</code>{}\n  added by <code>{}</code> on {}\n",
+                token.mint_address,
+                reason,
+                token.added_by,
+                token.created_at.format("%Y-%m-%d")
+            ));
+        }
+
+        send_long_message(
+            &self.bot,
+            self.chat_id,
+            message,
+            Some(ParseMode::Html),
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn display_token_blacklisted(&self, mint_address: &str) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "✅ Token <code>{}</code> blacklisted. Users are warned before buying it.",
+                    mint_address
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .await?;
+        Ok(())
+    }
+
+    async fn display_token_unblacklisted(&self, mint_address: &str, removed: bool) -> Result<()> {
+        let text = if removed {
+            format!(
+                "✅ Token <code>{}</code> removed from the blacklist.",
+                mint_address
+            )
+        } else {
+            format!("Token <code>{}</code> isn't blacklisted.", mint_address)
+        };
+
+        self.bot
+            .send_message(self.chat_id, text)
+            .parse_mode(ParseMode::Html)
+            .await?;
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("❌ Error: {}", error_message))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use crate::entity::{AlertDirection, PriceAlert};
+use crate::utils::{format_price, format_usd_price};
+use crate::view::message_utils::send_long_message;
+use anyhow::Result;
+use async_trait::async_trait;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+    Bot,
+};
+
+#[async_trait]
+pub trait AlertView: Send + Sync {
+    async fn display_alerts(&self, alerts: Vec<PriceAlert>) -> Result<()>;
+    async fn prompt_for_token_address(&self) -> Result<()>;
+    async fn display_invalid_token_address(&self) -> Result<()>;
+    async fn prompt_for_condition(
+        &self,
+        token_symbol: &str,
+        price_in_sol: f64,
+        price_in_usdc: f64,
+    ) -> Result<()>;
+    async fn display_alert_created(
+        &self,
+        alert_id: i32,
+        token_symbol: &str,
+        direction: AlertDirection,
+        target_price_in_sol: f64,
+        one_shot: bool,
+    ) -> Result<()>;
+    async fn display_alert_deleted(&self, alert_id: i32) -> Result<()>;
+    async fn display_invalid_input(&self, error_message: String) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramAlertView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramAlertView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+#[async_trait]
+impl AlertView for TelegramAlertView {
+    async fn display_alerts(&self, alerts: Vec<PriceAlert>) -> Result<()> {
+        let mut message = "<b>Your Price Alerts</b>\n\n".to_string();
+        let mut keyboard_buttons = vec![];
+
+        if alerts.is_empty() {
+            message.push_str("You don't have any active price alerts.\n");
+        }
+
+        for alert in &alerts {
+            let direction = match alert.direction() {
+                Some(AlertDirection::Below) => "below",
+                _ => "above",
+            };
+
+            message.push_str(&format!(
+                "• <b>#{}</b>: {} {} {:.6} SOL{}\n",
+                alert.id,
+                alert.token_symbol,
+                direction,
+                alert.target_price_in_sol,
+                if alert.one_shot { "" } else { " (repeating)" }
+            ));
+
+            keyboard_buttons.push(vec![InlineKeyboardButton::callback(
+                format!("❌ Delete #{} ({})", alert.id, alert.token_symbol),
+                format!("alert_delete_{}", alert.id),
+            )]);
+        }
+
+        keyboard_buttons.push(vec![
+            InlineKeyboardButton::callback("Add Alert", "alert_add"),
+            InlineKeyboardButton::callback("Back to Menu", "menu"),
+        ]);
+
+        send_long_message(
+            &self.bot,
+            self.chat_id,
+            message,
+            Some(ParseMode::Html),
+            Some(InlineKeyboardMarkup::new(keyboard_buttons)),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn prompt_for_token_address(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "Please enter the token contract address you want to set an alert for:",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn display_invalid_token_address(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "Invalid token address. Please enter a valid Solana token contract address:",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn prompt_for_condition(
+        &self,
+        token_symbol: &str,
+        price_in_sol: f64,
+        price_in_usdc: f64,
+    ) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "Token: {}\nCurrent price: {} SOL ({})\n\nWhen should you be notified? Enter 'above' or 'below' and a price in SOL:\n\nExample: above 0.5\nAdd 'repeat' to keep the alert after it fires: below 0.2 repeat",
+                    token_symbol,
+                    format_price(price_in_sol),
+                    format_usd_price(price_in_usdc)
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn display_alert_created(
+        &self,
+        alert_id: i32,
+        token_symbol: &str,
+        direction: AlertDirection,
+        target_price_in_sol: f64,
+        one_shot: bool,
+    ) -> Result<()> {
+        let direction = match direction {
+            AlertDirection::Above => "rises above",
+            AlertDirection::Below => "drops below",
+        };
+
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback("View Alerts", "alerts"),
+            InlineKeyboardButton::callback("Back to Menu", "menu"),
+        ]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "✅ Price Alert #{} created.\nYou will be notified when {} {} {} SOL{}.",
+                    alert_id,
+                    token_symbol,
+                    direction,
+                    format_price(target_price_in_sol),
+                    if one_shot {
+                        ""
+                    } else {
+                        ", every time it crosses"
+                    }
+                ),
+            )
+            .reply_markup(keyboard)
+            .await?;
+        Ok(())
+    }
+
+    async fn display_alert_deleted(&self, alert_id: i32) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Price alert #{} deleted.", alert_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn display_invalid_input(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Invalid input: {}", error_message))
+            .await?;
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Error: {}", error_message))
+            .await?;
+        Ok(())
+    }
+}
@@ -1,11 +1,15 @@
 use crate::commands::ui;
-use crate::entity::TokenBalance;
+use crate::entity::{Cluster, TokenBalance};
+use crate::i18n::Locale;
+use crate::interactor::balance_interactor::WalletBalances;
+use crate::utils::{format_amount, format_usd};
+use crate::view::message_utils::{edit_long_message, edit_or_ignore_unchanged, send_long_message};
 use anyhow::Result;
 use async_trait::async_trait;
-use chrono;
+use chrono::Utc;
 use teloxide::{
     prelude::*,
-    types::{Message, ParseMode},
+    types::{InlineKeyboardButton, Message, ParseMode},
     Bot,
 };
 
@@ -15,11 +19,7 @@ pub trait BalanceView: Send + Sync {
     async fn display_loading_update(&self, message: Message) -> Result<Option<Message>>;
     async fn display_balances(
         &self,
-        address: String,
-        sol_balance: f64,
-        token_balances: Vec<TokenBalance>,
-        usd_values: Vec<(String, f64)>,
-        total_usd: f64,
+        balances: &WalletBalances,
         message: Option<Message>,
     ) -> Result<()>;
 
@@ -30,11 +30,41 @@ pub trait BalanceView: Send + Sync {
 pub struct TelegramBalanceView {
     bot: Bot,
     chat_id: ChatId,
+    cluster: Cluster,
+    locale: Locale,
 }
 
 impl TelegramBalanceView {
     pub fn new(bot: Bot, chat_id: ChatId) -> Self {
-        Self { bot, chat_id }
+        Self {
+            bot,
+            chat_id,
+            cluster: Cluster::Mainnet,
+            locale: Locale::default(),
+        }
+    }
+
+    /// Format amounts with the separators of the user's language
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Show which cluster the bot runs on, a banner is added outside of mainnet
+    pub fn with_cluster(mut self, cluster: Cluster) -> Self {
+        self.cluster = cluster;
+        self
+    }
+
+    fn format_cluster_banner(&self) -> String {
+        if self.cluster.is_mainnet() {
+            return String::new();
+        }
+
+        format!(
+            "🧪 <b>{}</b> · test network, tokens have no real value\n\n",
+            self.cluster
+        )
     }
 
     // Helper function to format wallet address
@@ -46,9 +76,9 @@ impl TelegramBalanceView {
     }
 
     fn format_total_portfolio_text(&self, total_usd: f64) -> String {
-        // Add total portfolio value
+        // Add total portfolio value, SOL included
         if total_usd > 0.0 {
-            let text = format!("<b>Total Portfolio Value:</b> ${:.2}", total_usd);
+            let text = format!("<b>Total:</b> {}", format_usd(total_usd, self.locale));
 
             return text;
         }
@@ -56,46 +86,59 @@ impl TelegramBalanceView {
         String::new()
     }
 
+    // USD value of the symbol, None when its price is not available
+    fn usd_value(usd_values: &[(String, Option<f64>)], symbol: &str) -> Option<f64> {
+        usd_values
+            .iter()
+            .find(|(value_symbol, _)| value_symbol == symbol)
+            .and_then(|(_, value)| *value)
+    }
+
     fn format_spl_tokens_text(
         &self,
-        token_balances: &Vec<TokenBalance>,
-        usd_values: &Vec<(String, f64)>,
+        token_balances: &[TokenBalance],
+        usd_values: &[(String, Option<f64>)],
     ) -> String {
-        // If there are token balances, display them in a separate message
-        if !token_balances.is_empty() {
-            let mut tokens_text = "\n\n<b>Token Balances</b>\n\n".to_string();
-            let mut any_token_gt_zero = false;
-
-            for token in token_balances {
-                if token.amount > 0.0 {
-                    any_token_gt_zero = true;
-                    // Get USD value for this token
-                    let token_usd = usd_values
-                        .iter()
-                        .find(|(symbol, _)| symbol == &token.symbol)
-                        .map(|(_, value)| *value)
-                        .unwrap_or(0.0);
-
-                    if token_usd > 0.0 {
-                        tokens_text.push_str(&format!(
-                            "• <b>{}</b>: {:.6} (${:.2})\n",
-                            token.symbol, token.amount, token_usd
-                        ));
-                    } else {
-                        tokens_text
-                            .push_str(&format!("• <b>{}</b>: {:.6}\n", token.symbol, token.amount));
-                    }
-                }
-            }
+        // Tokens with a balance, the most valuable first and those without a price last
+        let mut tokens: Vec<(&TokenBalance, Option<f64>)> = token_balances
+            .iter()
+            .filter(|token| token.amount > 0.0)
+            .map(|token| (token, Self::usd_value(usd_values, &token.symbol)))
+            .collect();
 
-            if (!any_token_gt_zero) {
-                return String::new();
-            }
+        if tokens.is_empty() {
+            return String::new();
+        }
 
-            return tokens_text;
+        tokens.sort_by(|(_, a), (_, b)| {
+            b.unwrap_or(f64::NEG_INFINITY)
+                .total_cmp(&a.unwrap_or(f64::NEG_INFINITY))
+        });
+
+        let mut tokens_text = "\n\n<b>Token Balances</b>\n\n".to_string();
+
+        for (token, token_usd) in tokens {
+            match token_usd {
+                Some(token_usd) if token_usd > 0.0 => tokens_text.push_str(&format!(
+                    "• <b>{}</b>: {} ({})\n",
+                    token.symbol,
+                    format_amount(token.amount, self.locale),
+                    format_usd(token_usd, self.locale)
+                )),
+                Some(_) => tokens_text.push_str(&format!(
+                    "• <b>{}</b>: {}\n",
+                    token.symbol,
+                    format_amount(token.amount, self.locale)
+                )),
+                None => tokens_text.push_str(&format!(
+                    "• <b>{}</b>: {} (price n/a)\n",
+                    token.symbol,
+                    format_amount(token.amount, self.locale)
+                )),
+            }
         }
 
-        String::new()
+        tokens_text
     }
 }
 
@@ -111,81 +154,115 @@ impl BalanceView for TelegramBalanceView {
     }
 
     async fn display_loading_update(&self, message: Message) -> Result<Option<Message>> {
-        let updated_msg = self
-            .bot
-            .edit_message_text(
-                self.chat_id,
-                message.id,
-                "Refreshing balance information...",
-            )
-            .await?;
+        // A second refresh tap finds the loading text already in place
+        edit_or_ignore_unchanged(self.bot.edit_message_text(
+            self.chat_id,
+            message.id,
+            "Refreshing balance information...",
+        ))
+        .await?;
 
-        Ok(Some(updated_msg))
+        Ok(Some(message))
     }
 
     async fn display_balances(
         &self,
-        address: String,
-        sol_balance: f64,
-        token_balances: Vec<TokenBalance>,
-        usd_values: Vec<(String, f64)>,
-        total_usd: f64,
+        balances: &WalletBalances,
         message: Option<Message>,
     ) -> Result<()> {
-        // Get SOL price in USD from the usd_values array
-        let sol_usd_value = usd_values
-            .iter()
-            .find(|(symbol, _)| symbol == "SOL")
-            .map(|(_, value)| *value)
-            .unwrap_or(0.0);
-
-        // Calculate SOL price by dividing the USD value by the balance (if balance > 0)
-        let sol_price = if sol_balance > 0.0 {
-            sol_usd_value / sol_balance
-        } else {
-            0.0
+        let WalletBalances {
+            address,
+            sol_balance,
+            token_balances,
+            usd_values,
+            updated_at,
+            ..
+        } = balances;
+        let total_usd = balances.total_usd();
+        // Threshold the SOL balance is below, shows the low balance warning
+        let low_balance_warning = balances.low_balance_warning();
+
+        // Get SOL value in USD from the usd_values array
+        let sol_usd_text = match Self::usd_value(usd_values, "SOL") {
+            Some(sol_usd_value) => format_usd(sol_usd_value, self.locale),
+            None => "price n/a".to_string(),
         };
 
         let sol_text = format!(
             "<b>Solana</b> · 🔑\n\
             <code>{}</code>\n\n\
-            Balance: <b>{:.6}</b> SOL (${:.2})",
-            address, sol_balance, sol_usd_value
+            Balance: <b>{}</b> SOL ({})",
+            address,
+            format_amount(*sol_balance, self.locale),
+            sol_usd_text
         );
 
-        let token_text = self.format_spl_tokens_text(&token_balances, &usd_values);
+        let token_text = self.format_spl_tokens_text(token_balances, usd_values);
 
         let portfolio_total = self.format_total_portfolio_text(total_usd);
 
+        // Balances may come from the cache, show how old they are
+        let age_secs = (Utc::now() - *updated_at).num_seconds().max(0);
         let updated_text = format!(
             "—\n\n\
-            Updated: {} UTC",
-            chrono::Utc::now().format("%H:%M:%S")
+            Network: {}\n\
+            Updated: {} UTC ({}s ago)",
+            self.cluster,
+            updated_at.format("%H:%M:%S"),
+            age_secs
         );
 
-        let text = sol_text
+        let low_balance_text = match low_balance_warning {
+            Some(threshold) => format!(
+                "⚠️ <b>Low SOL balance</b>\n\
+                Your balance is below {} SOL, trades and withdrawals may fail \
+                because network fees can't be paid. Deposit some SOL to continue.\n\n",
+                threshold
+            ),
+            None => String::new(),
+        };
+
+        let text = self.format_cluster_banner()
+            + low_balance_text.as_str()
+            + sol_text.as_str()
             + token_text.as_str()
             + "\n\n"
             + portfolio_total.as_str()
             + "\n\n"
             + updated_text.as_str();
 
-        // Get the keyboard from UI module
-        let keyboard = ui::create_wallet_menu_keyboard();
+        // Get the keyboard from UI module, the deposit button shows the address and QR code
+        let mut keyboard = ui::create_wallet_menu_keyboard();
+        if low_balance_warning.is_some() {
+            keyboard.inline_keyboard.insert(
+                0,
+                vec![InlineKeyboardButton::callback(
+                    "💰 How to deposit",
+                    "address",
+                )],
+            );
+        }
 
-        // Update existing message or send a new one
+        // Update existing message or send a new one, splitting it if there are many tokens
         if let Some(msg) = message {
-            self.bot
-                .edit_message_text(self.chat_id, msg.id, text)
-                .parse_mode(ParseMode::Html)
-                .reply_markup(keyboard)
-                .await?;
+            edit_long_message(
+                &self.bot,
+                self.chat_id,
+                msg.id,
+                text,
+                Some(ParseMode::Html),
+                Some(keyboard),
+            )
+            .await?;
         } else {
-            self.bot
-                .send_message(self.chat_id, text)
-                .parse_mode(ParseMode::Html)
-                .reply_markup(keyboard)
-                .await?;
+            send_long_message(
+                &self.bot,
+                self.chat_id,
+                text,
+                Some(ParseMode::Html),
+                Some(keyboard),
+            )
+            .await?;
         }
 
         Ok(())
@@ -224,3 +301,114 @@ impl BalanceView for TelegramBalanceView {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(symbol: &str, amount: f64) -> TokenBalance {
+        TokenBalance {
+            symbol: symbol.to_string(),
+            amount,
+            mint_address: format!("{}-mint", symbol),
+            decimals: 6,
+        }
+    }
+
+    fn view() -> TelegramBalanceView {
+        TelegramBalanceView::new(Bot::new("123:abc"), ChatId(1))
+    }
+
+    #[test]
+    fn tokens_are_listed_by_usd_value() {
+        let tokens = vec![
+            token("WIF", 10.0),
+            token("BONK", 1000.0),
+            token("JUP", 50.0),
+        ];
+        let usd_values = vec![
+            ("SOL".to_string(), Some(300.0)),
+            ("WIF".to_string(), Some(25.0)),
+            ("BONK".to_string(), Some(2.0)),
+            ("JUP".to_string(), Some(40.0)),
+        ];
+
+        let text = view().format_spl_tokens_text(&tokens, &usd_values);
+
+        let jup = text.find("<b>JUP</b>").unwrap();
+        let wif = text.find("<b>WIF</b>").unwrap();
+        let bonk = text.find("<b>BONK</b>").unwrap();
+        assert!(jup < wif && wif < bonk);
+    }
+
+    #[test]
+    fn token_without_a_price_is_shown_last_as_price_na() {
+        let tokens = vec![token("WIF", 10.0), token("BONK", 1000.0)];
+        let usd_values = vec![("WIF".to_string(), None), ("BONK".to_string(), Some(2.0))];
+
+        let text = view().format_spl_tokens_text(&tokens, &usd_values);
+
+        assert!(text.contains("• <b>WIF</b>: 10 (price n/a)"));
+        assert!(text.find("<b>BONK</b>").unwrap() < text.find("<b>WIF</b>").unwrap());
+    }
+
+    #[test]
+    fn empty_token_accounts_are_hidden() {
+        let tokens = vec![token("BONK", 0.0)];
+
+        assert!(view()
+            .format_spl_tokens_text(&tokens, &[("BONK".to_string(), Some(0.0))])
+            .is_empty());
+    }
+
+    #[test]
+    fn total_footer_shows_the_usd_sum() {
+        assert_eq!(
+            view().format_total_portfolio_text(320.5),
+            "<b>Total:</b> $320.50"
+        );
+        assert!(view().format_total_portfolio_text(0.0).is_empty());
+    }
+
+    // Renders the balances through a local Telegram API and returns the message sent
+    fn render(balances: WalletBalances) -> String {
+        let (tx, rx) = std::sync::mpsc::channel();
+        crate::commands::testing::run_with_large_stack(move || async move {
+            let (bot, mut calls) = crate::commands::testing::fake_telegram().await;
+            let view = TelegramBalanceView::new(bot, ChatId(1));
+
+            view.display_balances(&balances, None).await.unwrap();
+
+            tx.send(calls.recv().await.unwrap().body).unwrap();
+        });
+        rx.recv().unwrap()
+    }
+
+    fn wallet(sol_balance: f64, low_balance_sol: f64) -> WalletBalances {
+        WalletBalances {
+            address: "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM".to_string(),
+            sol_balance,
+            token_balances: Vec::new(),
+            usd_values: vec![("SOL".to_string(), Some(sol_balance * 150.0))],
+            updated_at: Utc::now(),
+            low_balance_sol,
+        }
+    }
+
+    #[test]
+    fn balance_below_the_threshold_shows_the_warning_and_deposit_button() {
+        let sent = render(wallet(0.004, 0.01));
+
+        assert!(sent.contains("Low SOL balance"));
+        assert!(sent.contains("below 0.01 SOL"));
+        assert!(sent.contains("How to deposit"));
+    }
+
+    #[test]
+    fn balance_above_the_threshold_has_no_warning() {
+        let sent = render(wallet(0.5, 0.01));
+
+        assert!(!sent.contains("Low SOL balance"));
+        assert!(!sent.contains("How to deposit"));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::interactor::cleanup_interactor::CleanupPreview;
+use crate::solana::tokens::spl::TokenAccountCleanup;
+use crate::solana::utils::lamports_to_sol;
+use anyhow::Result;
+use async_trait::async_trait;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, Message, ParseMode},
+    Bot,
+};
+
+#[async_trait]
+pub trait CleanupView: Send + Sync {
+    async fn display_preview(&self, preview: &CleanupPreview) -> Result<()>;
+    async fn display_nothing_to_clean(&self) -> Result<()>;
+    async fn display_processing(&self) -> Result<Option<Message>>;
+    async fn display_cleanup_result(
+        &self,
+        result: &TokenAccountCleanup,
+        message: Option<Message>,
+    ) -> Result<()>;
+    async fn display_cleanup_cancelled(&self) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramCleanupView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramCleanupView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+#[async_trait]
+impl CleanupView for TelegramCleanupView {
+    async fn display_preview(&self, preview: &CleanupPreview) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback("✅ Close Accounts", "confirm_cleanup"),
+            InlineKeyboardButton::callback("❌ Cancel", "cancel_cleanup"),
+        ]]);
+
+        let accounts_list = preview
+            .accounts
+            .iter()
+            .map(|account| format!("• <code>{}</code>", account.mint))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "<b>Token Account Cleanup</b>\n\n\
+                    Found <b>{}</b> empty token account(s):\n{}\n\n\
+                    Closing them will return <b>{:.6} SOL</b> of rent to your wallet.\n\
+                    Do you want to proceed?",
+                    preview.accounts.len(),
+                    accounts_list,
+                    lamports_to_sol(preview.reclaimable_lamports)
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+        Ok(())
+    }
+
+    async fn display_nothing_to_clean(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "Your wallet has no empty token accounts to close.",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn display_processing(&self) -> Result<Option<Message>> {
+        let message = self
+            .bot
+            .send_message(self.chat_id, "Closing empty token accounts... Please wait.")
+            .await?;
+
+        Ok(Some(message))
+    }
+
+    async fn display_cleanup_result(
+        &self,
+        result: &TokenAccountCleanup,
+        message: Option<Message>,
+    ) -> Result<()> {
+        let text = if result.closed_accounts == 0 {
+            "Your wallet has no empty token accounts to close.".to_string()
+        } else {
+            let signatures = result
+                .signatures
+                .iter()
+                .map(|signature| format!("https://explorer.solana.com/tx/{}", signature))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            format!(
+                "✅ Closed {} token account(s) and reclaimed {:.6} SOL.\nCheck transaction(s):\n{}",
+                result.closed_accounts,
+                lamports_to_sol(result.reclaimed_lamports),
+                signatures
+            )
+        };
+
+        if let Some(msg) = message {
+            self.bot
+                .edit_message_text(self.chat_id, msg.id, text)
+                .await?;
+        } else {
+            self.bot.send_message(self.chat_id, text).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn display_cleanup_cancelled(&self) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, "Cleanup cancelled.")
+            .await?;
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Error: {}", error_message))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+use crate::entity::DcaOrder;
+use crate::utils::{format_price, format_usd_price};
+use crate::view::message_utils::send_long_message;
+use anyhow::Result;
+use async_trait::async_trait;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+    Bot,
+};
+
+#[async_trait]
+pub trait DcaView: Send + Sync {
+    async fn display_dca_orders(&self, orders: Vec<DcaOrder>) -> Result<()>;
+    async fn prompt_for_token_address(&self) -> Result<()>;
+    async fn display_invalid_token_address(&self) -> Result<()>;
+    async fn prompt_for_amount(
+        &self,
+        token_address: &str,
+        token_symbol: &str,
+        price_in_sol: f64,
+        price_in_usdc: f64,
+    ) -> Result<()>;
+    async fn prompt_for_interval(&self) -> Result<()>;
+    async fn prompt_for_runs(&self) -> Result<()>;
+    async fn display_order_created(
+        &self,
+        order_id: i32,
+        token_symbol: &str,
+        sol_per_interval: f64,
+        interval_seconds: i64,
+        total_runs: i32,
+    ) -> Result<()>;
+    async fn display_order_cancelled(&self, order_id: i32) -> Result<()>;
+    async fn display_invalid_input(&self, error_message: String) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramDcaView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramDcaView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+// Format an interval in seconds, e.g. "1d 12h"
+pub fn format_interval(interval_seconds: i64) -> String {
+    let days = interval_seconds / 86_400;
+    let hours = (interval_seconds % 86_400) / 3_600;
+    let minutes = (interval_seconds % 3_600) / 60;
+
+    let parts: Vec<String> = [(days, "d"), (hours, "h"), (minutes, "m")]
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect();
+
+    if parts.is_empty() {
+        format!("{}s", interval_seconds)
+    } else {
+        parts.join(" ")
+    }
+}
+
+#[async_trait]
+impl DcaView for TelegramDcaView {
+    async fn display_dca_orders(&self, orders: Vec<DcaOrder>) -> Result<()> {
+        let mut message = "<b>Your DCA Orders</b>\n\n".to_string();
+        let mut keyboard_buttons = vec![];
+
+        if orders.is_empty() {
+            message.push_str("You don't have any active DCA orders.\n");
+        }
+
+        for order in &orders {
+            message.push_str(&format!(
+                "• <b>#{}</b>: {:.6} SOL of {} every {}\n  {} buys left, next at {} UTC\n",
+                order.id,
+                order.sol_per_interval,
+                order.token_symbol,
+                format_interval(order.interval_seconds),
+                order.remaining_runs,
+                order.next_run_at.format("%Y-%m-%d %H:%M")
+            ));
+
+            keyboard_buttons.push(vec![InlineKeyboardButton::callback(
+                format!("❌ Cancel #{} ({})", order.id, order.token_symbol),
+                format!("dca_cancel_{}", order.id),
+            )]);
+        }
+
+        keyboard_buttons.push(vec![
+            InlineKeyboardButton::callback("Create DCA Order", "dca_create"),
+            InlineKeyboardButton::callback("Back to Menu", "menu"),
+        ]);
+
+        send_long_message(
+            &self.bot,
+            self.chat_id,
+            message,
+            Some(ParseMode::Html),
+            Some(InlineKeyboardMarkup::new(keyboard_buttons)),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn prompt_for_token_address(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "Please enter the token contract address you want to buy regularly:",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn display_invalid_token_address(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "Invalid token address. Please enter a valid Solana token contract address:",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn prompt_for_amount(
+        &self,
+        token_address: &str,
+        token_symbol: &str,
+        price_in_sol: f64,
+        price_in_usdc: f64,
+    ) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "Token: {} ({})\nCurrent price: {} SOL ({})\n\nHow much SOL should be spent on each buy?",
+                    token_symbol,
+                    token_address,
+                    format_price(price_in_sol),
+                    format_usd_price(price_in_usdc)
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn prompt_for_interval(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "How often should the token be bought?\n\nEnter an interval such as 30m, 4h, 1d or 7d:",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn prompt_for_runs(&self) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, "How many buys should be made in total?")
+            .await?;
+        Ok(())
+    }
+
+    async fn display_order_created(
+        &self,
+        order_id: i32,
+        token_symbol: &str,
+        sol_per_interval: f64,
+        interval_seconds: i64,
+        total_runs: i32,
+    ) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback("View DCA Orders", "dca"),
+            InlineKeyboardButton::callback("Back to Menu", "menu"),
+        ]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "✅ DCA Order #{} created successfully.\nBuying {:.6} SOL of {} every {}, {} times ({:.6} SOL in total).\n\nThe first buy will be made shortly.",
+                    order_id,
+                    sol_per_interval,
+                    token_symbol,
+                    format_interval(interval_seconds),
+                    total_runs,
+                    sol_per_interval * total_runs as f64
+                ),
+            )
+            .reply_markup(keyboard)
+            .await?;
+        Ok(())
+    }
+
+    async fn display_order_cancelled(&self, order_id: i32) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("DCA order #{} cancelled.", order_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn display_invalid_input(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Invalid input: {}", error_message))
+            .await?;
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Error: {}", error_message))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use crate::interactor::export_interactor::WalletExport;
+use crate::logging;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::error;
+use std::time::Duration;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+    Bot,
+};
+
+#[async_trait]
+pub trait ExportView: Send + Sync {
+    async fn display_private_chat_required(&self) -> Result<()>;
+    async fn display_export_warning(&self) -> Result<()>;
+    /// Shows the secrets and deletes the message after `delete_after`
+    async fn display_wallet_export(
+        &self,
+        export: &WalletExport,
+        delete_after: Duration,
+    ) -> Result<()>;
+    async fn display_export_cancelled(&self) -> Result<()>;
+    async fn display_no_wallet(&self) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramExportView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramExportView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+#[async_trait]
+impl ExportView for TelegramExportView {
+    async fn display_private_chat_required(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "🔒 For your safety the wallet can only be exported in a private chat with the bot.",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn display_export_warning(&self) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback("⚠️ Show Private Key", "confirm_export"),
+            InlineKeyboardButton::callback("❌ Cancel", "cancel_export"),
+        ]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                "<b>⚠️ Export Wallet</b>\n\n\
+                You are about to reveal your recovery phrase and private key.\n\n\
+                • Anyone who sees them can take <b>all funds</b> in this wallet.\n\
+                • Never share them, support will never ask for them.\n\
+                • Make sure nobody can see your screen.\n\n\
+                The message will be deleted automatically after a short time.",
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+        Ok(())
+    }
+
+    async fn display_wallet_export(
+        &self,
+        export: &WalletExport,
+        delete_after: Duration,
+    ) -> Result<()> {
+        let mnemonic_text = match &export.mnemonic {
+            Some(mnemonic) => format!(
+                "<b>Recovery phrase:</b>\n<tg-spoiler>{}</tg-spoiler>\n\n",
+                mnemonic
+            ),
+            None => String::new(),
+        };
+
+        let message = self
+            .bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "<b>🔑 Wallet Backup</b>\n\n\
+                    <b>Address:</b>\n<code>{}</code>\n\n\
+                    {}\
+                    <b>Private key:</b>\n<tg-spoiler><code>{}</code></tg-spoiler>\n\n\
+                    ⚠️ Store these offline and never share them.\n\
+                    This message will be deleted in {} seconds.",
+                    export.address,
+                    mnemonic_text,
+                    export.private_key,
+                    delete_after.as_secs()
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .await?;
+
+        // Remove the secrets from the chat history once the user had time to copy them
+        let bot = self.bot.clone();
+        let chat_id = self.chat_id;
+        logging::spawn(async move {
+            tokio::time::sleep(delete_after).await;
+
+            if let Err(e) = bot.delete_message(chat_id, message.id).await {
+                error!("Failed to delete wallet export message: {}", e);
+            }
+        });
+
+        Ok(())
+    }
+
+    async fn display_export_cancelled(&self) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, "Export cancelled.")
+            .await?;
+        Ok(())
+    }
+
+    async fn display_no_wallet(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "You don't have a wallet yet. Use /create_wallet to create a new wallet.",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Error: {}", error_message))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use crate::entity::FollowedWallet;
+use crate::view::message_utils::send_paged_message;
+use anyhow::Result;
+use async_trait::async_trait;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+    Bot,
+};
+
+#[async_trait]
+pub trait FollowView: Send + Sync {
+    async fn display_followed_wallets(&self, wallets: Vec<FollowedWallet>) -> Result<()>;
+    async fn prompt_for_wallet_address(&self) -> Result<()>;
+    async fn display_wallet_followed(&self, wallet_address: &str) -> Result<()>;
+    async fn display_wallet_unfollowed(&self) -> Result<()>;
+    async fn display_invalid_input(&self, error_message: String) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramFollowView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramFollowView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+#[async_trait]
+impl FollowView for TelegramFollowView {
+    async fn display_followed_wallets(&self, wallets: Vec<FollowedWallet>) -> Result<()> {
+        let mut message = "<b>Followed Wallets</b>\n\n".to_string();
+        let mut keyboard_buttons = vec![];
+
+        if wallets.is_empty() {
+            message.push_str(
+                "You don't follow any wallets yet.\n\
+                Follow a wallet to get notified when it buys or sells tokens.\n",
+            );
+        }
+
+        for wallet in &wallets {
+            message.push_str(&format!("• <code>{}</code>\n", wallet.wallet_address));
+
+            keyboard_buttons.push(vec![InlineKeyboardButton::callback(
+                format!("❌ Unfollow {}", wallet.short_address()),
+                format!("follow_delete_{}", wallet.id),
+            )]);
+        }
+
+        keyboard_buttons.push(vec![
+            InlineKeyboardButton::callback("Follow Wallet", "follow_add"),
+            InlineKeyboardButton::callback("Back to Menu", "menu"),
+        ]);
+
+        send_paged_message(
+            &self.bot,
+            self.chat_id,
+            None,
+            message,
+            Some(ParseMode::Html),
+            Some(InlineKeyboardMarkup::new(keyboard_buttons)),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn prompt_for_wallet_address(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "Please enter the Solana wallet address you want to follow:",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn display_wallet_followed(&self, wallet_address: &str) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback("View Followed", "follow"),
+            InlineKeyboardButton::callback("Back to Menu", "menu"),
+        ]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "✅ Now following <code>{}</code>.\n\
+                    You will be notified about its new token buys and sells, earlier activity is skipped.",
+                    wallet_address
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+        Ok(())
+    }
+
+    async fn display_wallet_unfollowed(&self) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, "Wallet unfollowed.")
+            .await?;
+        Ok(())
+    }
+
+    async fn display_invalid_input(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Invalid input: {}", error_message))
+            .await?;
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Error: {}", error_message))
+            .await?;
+        Ok(())
+    }
+}
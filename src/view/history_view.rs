@@ -0,0 +1,105 @@
+use crate::interactor::history_interactor::HistoryPage;
+use crate::view::message_utils::send_paged_message;
+use anyhow::Result;
+use async_trait::async_trait;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageId, ParseMode},
+    Bot,
+};
+
+#[async_trait]
+pub trait HistoryView: Send + Sync {
+    /// Sends the page, or edits `message_id` in place when paging through the history
+    async fn display_history(
+        &self,
+        history: HistoryPage,
+        message_id: Option<MessageId>,
+    ) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramHistoryView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramHistoryView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+#[async_trait]
+impl HistoryView for TelegramHistoryView {
+    async fn display_history(
+        &self,
+        history: HistoryPage,
+        message_id: Option<MessageId>,
+    ) -> Result<()> {
+        let mut message = format!("<b>Transaction History</b> (page {})\n\n", history.page + 1);
+
+        if history.entries.is_empty() {
+            message.push_str("No transactions yet.\n");
+        }
+
+        for entry in &history.entries {
+            message.push_str(&format!(
+                "{} <b>{}</b> {}\n• {}\n",
+                entry.kind.icon(),
+                entry.kind,
+                entry.timestamp.format("%Y-%m-%d %H:%M UTC"),
+                entry.amount
+            ));
+
+            match (&entry.tx_signature, entry.is_failed()) {
+                (Some(signature), false) => message.push_str(&format!(
+                    "• <a href=\"https://explorer.solana.com/tx/{}\">View on Explorer</a>\n\n",
+                    signature
+                )),
+                _ => message.push_str("• ❌ Failed\n\n"),
+            }
+        }
+
+        let mut navigation = Vec::new();
+        if history.has_newer() {
+            navigation.push(InlineKeyboardButton::callback(
+                "◀ Newer",
+                format!("history_page_{}", history.page - 1),
+            ));
+        }
+        if history.has_older {
+            navigation.push(InlineKeyboardButton::callback(
+                "Older ▶",
+                format!("history_page_{}", history.page + 1),
+            ));
+        }
+
+        let mut keyboard_buttons = Vec::new();
+        if !navigation.is_empty() {
+            keyboard_buttons.push(navigation);
+        }
+        keyboard_buttons.push(vec![InlineKeyboardButton::callback("Back to Menu", "menu")]);
+
+        let keyboard = InlineKeyboardMarkup::new(keyboard_buttons);
+
+        send_paged_message(
+            &self.bot,
+            self.chat_id,
+            message_id,
+            message,
+            Some(ParseMode::Html),
+            Some(keyboard),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Error: {}", error_message))
+            .await?;
+        Ok(())
+    }
+}
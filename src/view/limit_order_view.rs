@@ -1,15 +1,64 @@
-use crate::entity::{LimitOrder, OrderType};
+use crate::commands::ui;
+use crate::entity::{LimitOrder, NewLimitOrder, OrderBook, OrderType, PriceCurrency};
+use crate::utils::{format_price, format_usd_price};
+use crate::view::message_utils::send_long_message;
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
 use teloxide::{
     prelude::*,
     types::{InlineKeyboardButton, InlineKeyboardMarkup, Message, ParseMode},
     Bot,
 };
 
+// Orders above this get the separate "Cancel Order" and "Edit Order" menus instead of buttons each
+const MAX_CANCEL_BUTTONS: usize = 30;
+
+// Cancel and edit buttons shown next to each other in a row
+const CANCEL_BUTTONS_PER_ROW: usize = 3;
+
+// One rung of the order book ladder, e.g. "Sell #12: 1000 at 0.000015 SOL (+25.00%)"
+fn order_book_line(order: &LimitOrder, current_price_in_sol: Option<f64>) -> String {
+    let order_type = OrderType::from_str(&order.order_type)
+        .map(|order_type| order_type.display_name())
+        .unwrap_or("Order");
+
+    // USDC orders show their target, their distance isn't known in SOL
+    let (price, distance) = match order
+        .trigger_value
+        .filter(|_| order.price_currency() == PriceCurrency::Usdc)
+    {
+        Some(target) => (format!("${}", target), "".to_string()),
+        None => {
+            let distance = match current_price_in_sol.filter(|price| *price > 0.0) {
+                Some(current_price) => format!(
+                    " ({:+.2}%)",
+                    (order.price_in_sol / current_price - 1.0) * 100.0
+                ),
+                None => "".to_string(),
+            };
+            (
+                format!("{} SOL", format_price(order.price_in_sol)),
+                distance,
+            )
+        }
+    };
+
+    format!(
+        "  {} #{}: {:.6} at {}{}\n",
+        order_type,
+        order.id,
+        order.remaining_amount(),
+        price,
+        distance
+    )
+}
+
 #[async_trait]
 pub trait LimitOrderView: Send + Sync {
     async fn display_limit_orders(&self, orders: Vec<LimitOrder>) -> Result<()>;
+    async fn display_order_book(&self, books: Vec<OrderBook>) -> Result<()>;
     async fn display_no_orders(&self) -> Result<()>;
     async fn prompt_for_order_type(&self) -> Result<()>;
     async fn prompt_for_token_address(&self, order_type: &OrderType) -> Result<()>;
@@ -44,12 +93,9 @@ pub trait LimitOrderView: Send + Sync {
     ) -> Result<()>;
     async fn display_order_creation_success(
         &self,
-        order_type: &OrderType,
-        token_symbol: &str,
-        price_in_sol: f64,
-        amount: f64,
+        order: &NewLimitOrder,
         order_id: i32,
-        total_sol: f64,
+        expires_at: Option<DateTime<Utc>>,
     ) -> Result<()>;
     async fn display_order_creation_error(
         &self,
@@ -84,19 +130,17 @@ impl LimitOrderView for TelegramLimitOrderView {
         total_sol: f64,
         percentage_info: String,
     ) -> Result<()> {
-        let order_type_str = match order_type {
-            OrderType::Buy => "BUY",
-            OrderType::Sell => "SELL",
-        };
+        let order_type_str = order_type.to_string();
 
         self.bot
             .send_message(
                 self.chat_id,
                 format!(
-                    "Please confirm your limit order:\n\n{} {:.6} SOL ({:.6} {} tokens{}) @ {:.6} SOL each\n\nDo you want to proceed? (yes/no)",
-                    order_type_str, total_sol, amount, token_symbol, percentage_info, price_in_sol
+                    "Please confirm your limit order:\n\n{} {:.6} SOL ({:.6} {} tokens{}) @ {} SOL each\n\nDo you want to proceed? (yes/no)",
+                    order_type_str, total_sol, amount, token_symbol, percentage_info, format_price(price_in_sol)
                 ),
             )
+            .reply_markup(ui::create_confirmation_keyboard())
             .await?;
         Ok(())
     }
@@ -128,26 +172,38 @@ impl LimitOrderView for TelegramLimitOrderView {
             return self.display_no_orders().await;
         }
 
-        // Group orders by type
-        let mut buy_orders: Vec<&LimitOrder> = Vec::new();
-        let mut sell_orders: Vec<&LimitOrder> = Vec::new();
-
-        for order in &orders {
-            if order.order_type == "BUY" {
-                buy_orders.push(order);
-            } else if order.order_type == "SELL" {
-                sell_orders.push(order);
-            }
-        }
-
         // Format message
         let mut message = "<b>Your Active Limit Orders</b>\n\n".to_string();
+        // Cancel buttons in the order the orders are listed
+        let mut cancel_buttons = Vec::new();
+        let mut edit_buttons = Vec::new();
+
+        // Add a section for each order type
+        for (order_type, title) in [
+            (OrderType::Buy, "Buy Orders"),
+            (OrderType::Sell, "Sell Orders"),
+            (OrderType::StopLoss, "Stop-Loss Orders"),
+            (OrderType::TakeProfit, "Take-Profit Orders"),
+        ] {
+            let type_orders: Vec<&LimitOrder> = orders
+                .iter()
+                .filter(|order| order.order_type == order_type.to_string())
+                .collect();
+
+            if type_orders.is_empty() {
+                continue;
+            }
 
-        // Add buy orders section
-        if !buy_orders.is_empty() {
-            message.push_str("<b>Buy Orders:</b>\n");
-            for order in buy_orders {
-                let price_diff = if let Some(current_price) = order.current_price_in_sol {
+            message.push_str(&format!("<b>{}:</b>\n", title));
+            for order in type_orders {
+                let usdc_target = order
+                    .trigger_value
+                    .filter(|_| order.price_currency() == PriceCurrency::Usdc);
+
+                // Only SOL prices are stored, USDC orders can't compare with the current price
+                let price_diff = if let (Some(current_price), None) =
+                    (order.current_price_in_sol, usdc_target)
+                {
                     let diff_percent = ((current_price / order.price_in_sol) * 100.0) - 100f64;
                     format!(
                         " ({:.2}% {})",
@@ -158,65 +214,151 @@ impl LimitOrderView for TelegramLimitOrderView {
                     "".to_string()
                 };
 
-                message.push_str(&format!(
-                    "• <b>#{}</b>: {:.6} SOL ({:.6} {}) at {:.6} SOL{}\n",
-                    order.id,
-                    order.total_sol,
-                    order.amount,
-                    order.token_symbol,
-                    order.price_in_sol,
-                    price_diff
-                ));
-            }
-            message.push_str("\n");
-        }
+                let expiry = match order.expires_at {
+                    Some(expires_at) => {
+                        format!(", expires {} UTC", expires_at.format("%Y-%m-%d %H:%M"))
+                    }
+                    None => "".to_string(),
+                };
 
-        // Add sell orders section
-        if !sell_orders.is_empty() {
-            message.push_str("<b>Sell Orders:</b>\n");
-            for order in sell_orders {
-                let price_diff = if let Some(current_price) = order.current_price_in_sol {
-                    let diff_percent = ((current_price / order.price_in_sol) * 100.0) - 100f64;
-                    format!(
-                        " ({:.2}% {})",
-                        diff_percent.abs(),
-                        if diff_percent < 0.0 { "above" } else { "below" }
-                    )
+                // Market cap and FDV orders show their USD target
+                let trigger = match order.trigger_value {
+                    Some(target) if order.trigger_metric().needs_supply() => format!(
+                        ", when {} {} ${:.0}",
+                        order.trigger_metric().display_name(),
+                        order_type.trigger_comparison(),
+                        target
+                    ),
+                    _ => "".to_string(),
+                };
+
+                let progress = if order.is_partially_filled() {
+                    format!(", {}", order.fill_progress())
                 } else {
                     "".to_string()
                 };
 
+                let price = match usdc_target {
+                    Some(target) => format!("${}", target),
+                    None => format!("{} SOL", format_price(order.price_in_sol)),
+                };
+
                 message.push_str(&format!(
-                    "• <b>#{}</b>: {:.6} SOL ({:.6} {}) at {:.6} SOL{}\n",
+                    "• <b>#{}</b>: {:.6} SOL ({:.6} {}) at {}{}{}{}{}\n",
                     order.id,
                     order.total_sol,
                     order.amount,
                     order.token_symbol,
-                    order.price_in_sol,
-                    price_diff
+                    price,
+                    trigger,
+                    price_diff,
+                    progress,
+                    expiry
                 ));
+                cancel_buttons.push(InlineKeyboardButton::callback(
+                    format!("❌ Cancel #{}", order.id),
+                    format!("cancel_order_{}", order.id),
+                ));
+                // Partially filled orders can only be cancelled
+                if order.filled_amount <= 0.0 {
+                    edit_buttons.push(InlineKeyboardButton::callback(
+                        format!("✏️ Edit #{}", order.id),
+                        format!("edit_order_{}", order.id),
+                    ));
+                }
             }
             message.push_str("\n");
         }
 
-        // Create keyboard with buttons
+        // Every order gets its own cancel and edit buttons, long lists keep the separate menus
+        let mut keyboard_buttons: Vec<Vec<InlineKeyboardButton>> =
+            if cancel_buttons.len() <= MAX_CANCEL_BUTTONS {
+                cancel_buttons
+                    .chunks(CANCEL_BUTTONS_PER_ROW)
+                    .chain(edit_buttons.chunks(CANCEL_BUTTONS_PER_ROW))
+                    .map(|row| row.to_vec())
+                    .collect()
+            } else {
+                vec![vec![
+                    InlineKeyboardButton::callback("Edit Order", "edit_limit_order"),
+                    InlineKeyboardButton::callback("Cancel Order", "cancel_limit_order"),
+                ]]
+            };
+        keyboard_buttons.push(vec![
+            InlineKeyboardButton::callback("Create Limit Order", "create_limit_order"),
+            InlineKeyboardButton::callback("🔄 Refresh", "refresh_limit_orders"),
+        ]);
+        keyboard_buttons.push(vec![
+            InlineKeyboardButton::callback("📖 Order Book", "limit_order_book"),
+            InlineKeyboardButton::callback("Back to Menu", "menu"),
+        ]);
+        let keyboard = InlineKeyboardMarkup::new(keyboard_buttons);
+
+        // Send message with keyboard, split into several messages for long order lists
+        send_long_message(
+            &self.bot,
+            self.chat_id,
+            message,
+            Some(ParseMode::Html),
+            Some(keyboard),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn display_order_book(&self, books: Vec<OrderBook>) -> Result<()> {
+        if books.is_empty() {
+            return self.display_no_orders().await;
+        }
+
+        let mut message = "<b>Your Order Book</b>\n\n".to_string();
+
+        for book in &books {
+            message.push_str(&format!("<b>{}</b>\n", book.token_symbol));
+
+            // Sells from the highest down to the current price, then buys going down from it
+            if book.sells.is_empty() {
+                message.push_str("  <i>no sell orders</i>\n");
+            }
+            for order in book.sells.iter().rev() {
+                message.push_str(&order_book_line(order, book.current_price_in_sol));
+            }
+
+            match book.current_price_in_sol {
+                Some(current_price) => message.push_str(&format!(
+                    "  ── now {} SOL ──\n",
+                    format_price(current_price)
+                )),
+                None => message.push_str("  ── current price unknown ──\n"),
+            }
+
+            for order in &book.buys {
+                message.push_str(&order_book_line(order, book.current_price_in_sol));
+            }
+            if book.buys.is_empty() {
+                message.push_str("  <i>no buy orders</i>\n");
+            }
+            message.push('\n');
+        }
+
         let keyboard = InlineKeyboardMarkup::new(vec![
             vec![
-                InlineKeyboardButton::callback("Create Limit Order", "create_limit_order"),
-                InlineKeyboardButton::callback("Back to Menu", "menu"),
-            ],
-            vec![
-                InlineKeyboardButton::callback("Cancel Order", "cancel_limit_order"),
-                InlineKeyboardButton::callback("🔄 Refresh", "refresh_limit_orders"),
+                InlineKeyboardButton::callback("🔄 Refresh", "limit_order_book"),
+                InlineKeyboardButton::callback("📋 Orders List", "limit_orders"),
             ],
+            vec![InlineKeyboardButton::callback("Back to Menu", "menu")],
         ]);
 
-        // Send message with keyboard
-        self.bot
-            .send_message(self.chat_id, message)
-            .parse_mode(ParseMode::Html)
-            .reply_markup(keyboard)
-            .await?;
+        // Split into several messages when the user has many orders
+        send_long_message(
+            &self.bot,
+            self.chat_id,
+            message,
+            Some(ParseMode::Html),
+            Some(keyboard),
+        )
+        .await?;
 
         Ok(())
     }
@@ -248,6 +390,10 @@ impl LimitOrderView for TelegramLimitOrderView {
                 InlineKeyboardButton::callback("Limit Buy Order", "limit_buy_order"),
                 InlineKeyboardButton::callback("Limit Sell Order", "limit_sell_order"),
             ],
+            vec![
+                InlineKeyboardButton::callback("Stop-Loss", "stop_loss_order"),
+                InlineKeyboardButton::callback("Take-Profit", "take_profit_order"),
+            ],
             vec![InlineKeyboardButton::callback("Back to Menu", "menu")],
         ]);
 
@@ -261,10 +407,7 @@ impl LimitOrderView for TelegramLimitOrderView {
     }
 
     async fn prompt_for_token_address(&self, order_type: &OrderType) -> Result<()> {
-        let action = match order_type {
-            OrderType::Buy => "buy",
-            OrderType::Sell => "sell",
-        };
+        let action = if order_type.is_sell() { "sell" } else { "buy" };
 
         self.bot
             .send_message(
@@ -296,17 +439,14 @@ impl LimitOrderView for TelegramLimitOrderView {
         current_price_in_sol: f64,
         current_price_in_usdc: f64,
     ) -> Result<()> {
-        let action = match order_type {
-            OrderType::Buy => "buy",
-            OrderType::Sell => "sell",
-        };
+        let action = if order_type.is_sell() { "sell" } else { "buy" };
 
         self.bot
             .send_message(
                 self.chat_id,
                 format!(
-                    "Token: {} ({})\nCurrent price: {:.6} SOL (${:.2})\n\nPlease enter the price in SOL and total volume in SOL to {} in the format:\n<price> <volume_in_sol>\n\nExample: 0.5 10 (10 SOL volume at price 0.5 SOL per token)",
-                    token_symbol, token_address, current_price_in_sol, current_price_in_usdc, action
+                    "Token: {} ({})\nCurrent price: {} SOL ({})\n\nPlease enter the price in SOL and total volume in SOL to {} in the format:\n<price> <volume_in_sol>\n\nExample: 0.5 10 (10 SOL volume at price 0.5 SOL per token)\n\nTo set the price in USD instead, start it with $:\n$0.0015 10\n\nTo trigger on the market cap or FDV in USD instead, start with mc or fdv:\nmc 2.5M 10",
+                    token_symbol, token_address, format_price(current_price_in_sol), format_usd_price(current_price_in_usdc), action
                 ),
             )
             .await?;
@@ -322,16 +462,20 @@ impl LimitOrderView for TelegramLimitOrderView {
 
     async fn display_order_creation_success(
         &self,
-        order_type: &OrderType,
-        token_symbol: &str,
-        price_in_sol: f64,
-        amount: f64,
+        order: &NewLimitOrder,
         order_id: i32,
-        total_sol: f64,
+        expires_at: Option<DateTime<Utc>>,
     ) -> Result<()> {
-        let order_type_str = match order_type {
-            OrderType::Buy => "Buy",
-            OrderType::Sell => "Sell",
+        let order_type_str = order.order_type.display_name();
+
+        let trigger_info = match order.order_type {
+            OrderType::Buy | OrderType::StopLoss => "drops to or below",
+            OrderType::Sell | OrderType::TakeProfit => "rises to or above",
+        };
+
+        let expiry_info = match expires_at {
+            Some(expires_at) => format!("\nExpires: {} UTC", expires_at.format("%Y-%m-%d %H:%M")),
+            None => "".to_string(),
         };
 
         let keyboard = InlineKeyboardMarkup::new(vec![vec![
@@ -343,8 +487,8 @@ impl LimitOrderView for TelegramLimitOrderView {
             .send_message(
                 self.chat_id,
                 format!(
-                    "✅ Limit {} Order #{} created successfully.\nVolume: {:.6} SOL ({:.6} {} tokens)\nPrice: {:.6} SOL per token\n\nYour order will execute when the market price reaches your specified price.",
-                    order_type_str, order_id, total_sol, amount, token_symbol, price_in_sol
+                    "✅ Limit {} Order #{} created successfully.\nVolume: {:.6} SOL ({:.6} {} tokens)\nPrice: {} SOL per token{}\n\nYour order will execute when the market price {} your specified price.",
+                    order_type_str, order_id, order.total_sol, order.amount, order.token_symbol, format_price(order.price_in_sol), expiry_info, trigger_info
                 ),
             )
             .reply_markup(keyboard)
@@ -358,10 +502,7 @@ impl LimitOrderView for TelegramLimitOrderView {
         token_symbol: &str,
         error_message: String,
     ) -> Result<()> {
-        let order_type_str = match order_type {
-            OrderType::Buy => "buy",
-            OrderType::Sell => "sell",
-        };
+        let order_type_str = order_type.display_name().to_lowercase();
 
         self.bot
             .send_message(
@@ -389,3 +530,89 @@ impl LimitOrderView for TelegramLimitOrderView {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(id: i32, order_type: OrderType, filled_amount: f64) -> LimitOrder {
+        let now = Utc::now();
+        LimitOrder {
+            id,
+            user_id: 1,
+            token_address: "mint".to_string(),
+            token_symbol: "TKN".to_string(),
+            order_type: order_type.to_string(),
+            price_in_sol: 0.5,
+            amount: 10.0,
+            total_sol: 5.0,
+            current_price_in_sol: None,
+            tx_signature: None,
+            created_at: now,
+            updated_at: now,
+            status: "ACTIVE".to_string(),
+            retry_count: 0,
+            expires_at: None,
+            failure_reason: None,
+            filled_amount,
+            retry_slippage: None,
+            trigger_metric: "PRICE".to_string(),
+            trigger_value: None,
+            price_currency: "SOL".to_string(),
+            next_retry_at: None,
+        }
+    }
+
+    // Callback data of the keyboard sent with the order list
+    fn list_callbacks(orders: Vec<LimitOrder>) -> Vec<String> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        crate::commands::testing::run_with_large_stack(move || async move {
+            let (bot, mut calls) = crate::commands::testing::fake_telegram().await;
+            let view = TelegramLimitOrderView::new(bot, ChatId(1));
+
+            view.display_limit_orders(orders).await.unwrap();
+
+            tx.send(calls.recv().await.unwrap().body).unwrap();
+        });
+        let body: serde_json::Value = serde_json::from_str(&rx.recv().unwrap()).unwrap();
+
+        body["reply_markup"]["inline_keyboard"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|row| row.as_array().unwrap())
+            .map(|button| button["callback_data"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn every_order_gets_a_cancel_button_with_its_id() {
+        let callbacks = list_callbacks(vec![
+            order(7, OrderType::Buy, 0.0),
+            order(i32::MAX, OrderType::Sell, 1.0),
+        ]);
+
+        assert!(callbacks.contains(&"cancel_order_7".to_string()));
+        assert!(callbacks.contains(&format!("cancel_order_{}", i32::MAX)));
+        assert!(callbacks.contains(&"edit_order_7".to_string()));
+        // Partially filled orders can't be edited
+        assert!(!callbacks.contains(&format!("edit_order_{}", i32::MAX)));
+        assert!(!callbacks.contains(&"cancel_limit_order".to_string()));
+        // Telegram rejects callback data over 64 bytes
+        assert!(callbacks.iter().all(|data| data.len() <= 64));
+    }
+
+    #[test]
+    fn long_order_lists_keep_the_cancel_menu() {
+        let orders = (1..=MAX_CANCEL_BUTTONS as i32 + 1)
+            .map(|id| order(id, OrderType::Buy, 0.0))
+            .collect();
+
+        let callbacks = list_callbacks(orders);
+
+        assert!(callbacks.contains(&"cancel_limit_order".to_string()));
+        assert!(!callbacks
+            .iter()
+            .any(|data| data.starts_with("cancel_order_")));
+    }
+}
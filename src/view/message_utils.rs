@@ -0,0 +1,531 @@
+use anyhow::{anyhow, Result};
+use lazy_static::lazy_static;
+use std::env;
+use std::future::IntoFuture;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardMarkup, MessageId, ParseMode},
+    ApiError, Bot, RequestError,
+};
+
+/// Hard limit imposed by the Telegram Bot API on a single text message
+pub const TELEGRAM_MAX_MESSAGE_LENGTH: usize = 4096;
+
+/// Lower bound for the configured limit so that a chunk can always fit a few lines
+const MIN_MESSAGE_LENGTH: usize = 256;
+
+lazy_static! {
+    static ref MESSAGE_LIMIT: usize = env::var("TELEGRAM_MESSAGE_LIMIT")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .map(|limit| limit.clamp(MIN_MESSAGE_LENGTH, TELEGRAM_MAX_MESSAGE_LENGTH))
+        .unwrap_or(TELEGRAM_MAX_MESSAGE_LENGTH);
+}
+
+/// Returns the maximum message length used when splitting long output.
+/// Can be lowered with the TELEGRAM_MESSAGE_LIMIT environment variable.
+pub fn message_limit() -> usize {
+    *MESSAGE_LIMIT
+}
+
+/// Splits a message into chunks that fit into `limit` characters.
+///
+/// Chunks are cut at line breaks where possible, then at whitespace. In HTML mode tags and
+/// entities are never cut, and tags that are still open at a chunk boundary are closed at the
+/// end of the chunk and reopened at the start of the next one.
+pub fn split_message(text: &str, limit: usize, html: bool) -> Vec<String> {
+    if char_len(text) <= limit {
+        return vec![text.to_string()];
+    }
+
+    let mut splitter = MessageSplitter::new(limit, html);
+
+    for line in text.split_inclusive('\n') {
+        splitter.push_line(line);
+    }
+
+    splitter.finish()
+}
+
+/// Sends a message, splitting it into several messages if it exceeds the Telegram limit.
+/// The reply markup is attached to the last chunk. Returns the last sent message.
+pub async fn send_long_message(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: String,
+    parse_mode: Option<ParseMode>,
+    reply_markup: Option<InlineKeyboardMarkup>,
+) -> Result<Message> {
+    let chunks = split_message(&text, message_limit(), parse_mode == Some(ParseMode::Html));
+
+    send_chunks(bot, chat_id, chunks, parse_mode, reply_markup).await
+}
+
+/// Edits an existing message with the first chunk of `text` and sends the rest as new
+/// messages. The reply markup is attached to the last chunk.
+pub async fn edit_long_message(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    text: String,
+    parse_mode: Option<ParseMode>,
+    reply_markup: Option<InlineKeyboardMarkup>,
+) -> Result<()> {
+    let mut chunks = split_message(&text, message_limit(), parse_mode == Some(ParseMode::Html));
+    let first_chunk = chunks.remove(0);
+
+    let mut request = bot.edit_message_text(chat_id, message_id, first_chunk);
+
+    if let Some(mode) = parse_mode {
+        request = request.parse_mode(mode);
+    }
+
+    if chunks.is_empty() {
+        if let Some(keyboard) = reply_markup {
+            request = request.reply_markup(keyboard);
+        }
+        return edit_or_ignore_unchanged(request).await;
+    }
+
+    edit_or_ignore_unchanged(request).await?;
+
+    send_chunks(bot, chat_id, chunks, parse_mode, reply_markup).await?;
+
+    Ok(())
+}
+
+/// Sends a page with navigation buttons, or edits `message_id` in place when paging.
+///
+/// A page is always a single message: if the text were split, the buttons would move to a
+/// new last chunk and the next page edit would leave the earlier chunks behind. A page that
+/// doesn't fit is cut at a line boundary instead, see [`fit_page`].
+pub async fn send_paged_message(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    text: String,
+    parse_mode: Option<ParseMode>,
+    reply_markup: Option<InlineKeyboardMarkup>,
+) -> Result<()> {
+    let page = fit_page(&text, message_limit(), parse_mode == Some(ParseMode::Html));
+
+    match message_id {
+        Some(message_id) => {
+            let mut request = bot.edit_message_text(chat_id, message_id, page);
+
+            if let Some(mode) = parse_mode {
+                request = request.parse_mode(mode);
+            }
+            if let Some(keyboard) = reply_markup {
+                request = request.reply_markup(keyboard);
+            }
+
+            edit_or_ignore_unchanged(request).await
+        }
+        None => {
+            send_chunks(bot, chat_id, vec![page], parse_mode, reply_markup).await?;
+            Ok(())
+        }
+    }
+}
+
+/// Marker appended to a page that was cut to fit into a single message
+const TRUNCATED_MARKER: &str = "…";
+
+/// Keeps the first chunk of `text` that fits into `limit` characters, marking it as truncated
+/// when the rest had to be dropped. Open HTML tags are closed like in [`split_message`].
+pub fn fit_page(text: &str, limit: usize, html: bool) -> String {
+    if char_len(text) <= limit {
+        return text.to_string();
+    }
+
+    let chunks = split_message(text, limit - char_len(TRUNCATED_MARKER), html);
+    let mut page = chunks.into_iter().next().unwrap_or_default();
+    page.push_str(TRUNCATED_MARKER);
+    page
+}
+
+/// Whether Telegram rejected an edit because the text and keyboard are already the same
+pub fn is_message_not_modified(error: &RequestError) -> bool {
+    matches!(error, RequestError::Api(ApiError::MessageNotModified))
+}
+
+/// Sends an edit request and treats "message is not modified" as success.
+///
+/// Refreshing a message whose content didn't change (same prices, same balance) makes
+/// Telegram reject the edit, which is not a failure from the user's point of view.
+pub async fn edit_or_ignore_unchanged<R, T>(request: R) -> Result<()>
+where
+    R: IntoFuture<Output = Result<T, RequestError>>,
+{
+    match request.await {
+        Ok(_) => Ok(()),
+        Err(e) if is_message_not_modified(&e) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn send_chunks(
+    bot: &Bot,
+    chat_id: ChatId,
+    chunks: Vec<String>,
+    parse_mode: Option<ParseMode>,
+    reply_markup: Option<InlineKeyboardMarkup>,
+) -> Result<Message> {
+    let last_index = chunks.len() - 1;
+    let mut last_message = None;
+
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        let mut request = bot.send_message(chat_id, chunk);
+
+        if let Some(mode) = parse_mode {
+            request = request.parse_mode(mode);
+        }
+
+        if index == last_index {
+            if let Some(keyboard) = reply_markup.clone() {
+                request = request.reply_markup(keyboard);
+            }
+        }
+
+        last_message = Some(request.await?);
+    }
+
+    last_message.ok_or_else(|| anyhow!("No message chunks to send"))
+}
+
+fn char_len(text: &str) -> usize {
+    text.chars().count()
+}
+
+// Name of an HTML tag, e.g. "a" for `<a href="...">` and `</a>`
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches('<')
+        .trim_start_matches('/')
+        .trim_end_matches('>')
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+// Splits a line into tags, entities, whitespace runs and words
+fn tokenize(line: &str, html: bool) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = line;
+
+    while !rest.is_empty() {
+        let first = rest.chars().next().unwrap();
+
+        let token_len = if html && first == '<' {
+            rest.find('>').map(|end| end + 1).unwrap_or(rest.len())
+        } else if html && first == '&' {
+            match rest.find(';') {
+                Some(end) if end <= 10 && !rest[..end].contains(char::is_whitespace) => end + 1,
+                _ => 1,
+            }
+        } else if first.is_whitespace() {
+            rest.find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len())
+        } else {
+            rest.find(|c: char| c.is_whitespace() || (html && (c == '<' || c == '&')))
+                .unwrap_or(rest.len())
+        };
+
+        let (token, tail) = rest.split_at(token_len);
+        tokens.push(token);
+        rest = tail;
+    }
+
+    tokens
+}
+
+struct MessageSplitter {
+    limit: usize,
+    html: bool,
+    chunks: Vec<String>,
+    current: String,
+    // Length of the reopened tags at the beginning of the current chunk
+    prefix_len: usize,
+    open_tags: Vec<String>,
+}
+
+impl MessageSplitter {
+    fn new(limit: usize, html: bool) -> Self {
+        Self {
+            limit,
+            html,
+            chunks: Vec::new(),
+            current: String::new(),
+            prefix_len: 0,
+            open_tags: Vec::new(),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        self.current.len() > self.prefix_len
+    }
+
+    fn apply_tags(open_tags: &mut Vec<String>, segment: &str) {
+        for token in tokenize(segment, true) {
+            if !token.starts_with('<') || !token.ends_with('>') {
+                continue;
+            }
+
+            let name = tag_name(token);
+            if token.starts_with("</") {
+                if let Some(pos) = open_tags.iter().rposition(|tag| tag_name(tag) == name) {
+                    open_tags.truncate(pos);
+                }
+            } else if !token.ends_with("/>") {
+                open_tags.push(token.to_string());
+            }
+        }
+    }
+
+    fn closing_tags(open_tags: &[String]) -> String {
+        open_tags
+            .iter()
+            .rev()
+            .map(|tag| format!("</{}>", tag_name(tag)))
+            .collect()
+    }
+
+    // Checks whether the segment fits into the current chunk, including the closing tags
+    // that would have to be appended afterwards
+    fn fits(&self, segment: &str) -> bool {
+        let closing_len = if self.html {
+            let mut open_tags = self.open_tags.clone();
+            Self::apply_tags(&mut open_tags, segment);
+            char_len(&Self::closing_tags(&open_tags))
+        } else {
+            0
+        };
+
+        char_len(&self.current) + char_len(segment) + closing_len <= self.limit
+    }
+
+    fn append(&mut self, segment: &str) {
+        self.current.push_str(segment);
+        if self.html {
+            Self::apply_tags(&mut self.open_tags, segment);
+        }
+    }
+
+    fn flush(&mut self) {
+        let mut chunk = std::mem::take(&mut self.current);
+
+        if self.html {
+            chunk.push_str(&Self::closing_tags(&self.open_tags));
+            self.current = self.open_tags.concat();
+        }
+
+        self.prefix_len = self.current.len();
+        self.chunks.push(chunk);
+    }
+
+    fn push_line(&mut self, line: &str) {
+        if self.fits(line) {
+            self.append(line);
+            return;
+        }
+
+        if self.has_content() {
+            self.flush();
+            if self.fits(line) {
+                self.append(line);
+                return;
+            }
+        }
+
+        // The line alone is too long, fall back to splitting it by tokens
+        for token in tokenize(line, self.html) {
+            self.push_token(token);
+        }
+    }
+
+    fn push_token(&mut self, token: &str) {
+        if self.fits(token) {
+            self.append(token);
+            return;
+        }
+
+        if self.has_content() {
+            self.flush();
+            if self.fits(token) {
+                self.append(token);
+                return;
+            }
+        }
+
+        // Tags and entities must stay intact even if they don't fit
+        if self.html && (token.starts_with('<') || token.starts_with('&')) {
+            self.append(token);
+            return;
+        }
+
+        // A single word longer than the limit, split it by characters
+        let mut buf = [0u8; 4];
+        for c in token.chars() {
+            let piece: &str = c.encode_utf8(&mut buf);
+            if !self.fits(piece) && self.has_content() {
+                self.flush();
+            }
+            self.current.push(c);
+        }
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        if self.has_content() {
+            self.flush();
+        }
+
+        self.chunks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tags that are opened and not closed again within the chunk
+    fn unbalanced_tags(chunk: &str) -> Vec<String> {
+        let mut open_tags = Vec::new();
+        MessageSplitter::apply_tags(&mut open_tags, chunk);
+        open_tags
+    }
+
+    fn history_body() -> String {
+        let mut body = String::from("<b>Transaction History</b> (page 1)\n\n");
+        for i in 0..60 {
+            body.push_str(&format!(
+                "🟢 <b>Buy</b> 2024-01-{:02} 12:00 UTC\n• 1.5 SOL &amp; fees\n\
+                 • <a href=\"https://explorer.solana.com/tx/{}\">View on Explorer</a>\n\n",
+                i % 28 + 1,
+                "5".repeat(88)
+            ));
+        }
+        body
+    }
+
+    #[test]
+    fn short_message_is_not_split() {
+        assert_eq!(split_message("<b>hi</b>", 4096, true), vec!["<b>hi</b>"]);
+    }
+
+    #[test]
+    fn long_html_body_splits_without_breaking_tags() {
+        let body = history_body();
+        assert!(char_len(&body) > TELEGRAM_MAX_MESSAGE_LENGTH);
+
+        let chunks = split_message(&body, TELEGRAM_MAX_MESSAGE_LENGTH, true);
+        assert!(chunks.len() > 1);
+
+        for chunk in &chunks {
+            assert!(char_len(chunk) <= TELEGRAM_MAX_MESSAGE_LENGTH);
+            assert!(
+                unbalanced_tags(chunk).is_empty(),
+                "unbalanced chunk: {}",
+                chunk
+            );
+            for token in tokenize(chunk, true) {
+                if token.starts_with('<') {
+                    assert!(token.ends_with('>'), "cut tag: {}", token);
+                }
+            }
+        }
+
+        // Splitting happens on line boundaries, so no line is lost or merged
+        let joined = chunks.concat();
+        assert_eq!(joined.matches("View on Explorer").count(), 60);
+        assert_eq!(joined.matches("&amp;").count(), 60);
+    }
+
+    #[test]
+    fn tags_open_across_a_split_are_closed_and_reopened() {
+        let body = format!("<b>{}</b>", "word ".repeat(100));
+        let chunks = split_message(&body, MIN_MESSAGE_LENGTH, true);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.starts_with("<b>"));
+            assert!(chunk.ends_with("</b>"));
+            assert!(char_len(chunk) <= MIN_MESSAGE_LENGTH);
+        }
+    }
+
+    #[test]
+    fn page_that_fits_is_kept_as_is() {
+        assert_eq!(fit_page("<b>page</b>\n", 4096, true), "<b>page</b>\n");
+    }
+
+    #[test]
+    fn long_page_is_cut_into_a_single_message() {
+        let body = history_body();
+        let page = fit_page(&body, TELEGRAM_MAX_MESSAGE_LENGTH, true);
+
+        assert!(char_len(&page) <= TELEGRAM_MAX_MESSAGE_LENGTH);
+        assert!(page.ends_with(TRUNCATED_MARKER));
+        assert!(page.starts_with("<b>Transaction History</b>"));
+        assert!(unbalanced_tags(&page).is_empty());
+    }
+
+    // Telegram's answer to an edit that changes nothing
+    fn not_modified(method: &str) -> String {
+        if method.eq_ignore_ascii_case("editMessageText") {
+            serde_json::json!({
+                "ok": false,
+                "error_code": 400,
+                "description": "Bad Request: message is not modified: specified new message \
+                    content and reply markup are exactly the same as a current content and \
+                    reply markup of the message",
+            })
+            .to_string()
+        } else {
+            crate::commands::testing::response_body(method)
+        }
+    }
+
+    // Telegram's answer when the chat is gone
+    fn chat_not_found(_method: &str) -> String {
+        serde_json::json!({
+            "ok": false,
+            "error_code": 400,
+            "description": "Bad Request: chat not found",
+        })
+        .to_string()
+    }
+
+    async fn edit(respond: fn(&str) -> String) -> Result<()> {
+        let (bot, _calls) = crate::commands::testing::fake_telegram_with(respond).await;
+
+        edit_or_ignore_unchanged(bot.edit_message_text(ChatId(1), MessageId(1), "same text")).await
+    }
+
+    #[tokio::test]
+    async fn unchanged_edit_is_not_an_error() {
+        assert!(edit(not_modified).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn successful_edit_is_ok() {
+        assert!(edit(crate::commands::testing::response_body).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn other_edit_errors_are_returned() {
+        let err = edit(chat_not_found).await.unwrap_err();
+
+        assert!(err.to_string().contains("chat not found"), "{}", err);
+    }
+
+    #[test]
+    fn only_message_not_modified_counts_as_unchanged() {
+        assert!(is_message_not_modified(&RequestError::Api(
+            ApiError::MessageNotModified
+        )));
+        assert!(!is_message_not_modified(&RequestError::Api(
+            ApiError::ChatNotFound
+        )));
+    }
+}
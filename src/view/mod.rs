@@ -1,10 +1,26 @@
 use async_trait::async_trait;
 
+pub mod account_view;
+pub mod admin_view;
+pub mod alert_view;
 pub mod balance_view;
+pub mod cleanup_view;
+pub mod dca_view;
+pub mod export_view;
+pub mod follow_view;
+pub mod history_view;
 pub mod limit_order_view;
+pub mod message_utils;
+pub mod portfolio_view;
+pub mod positions_view;
 pub mod price_view;
+pub mod referral_view;
 pub mod send_view;
 pub mod settings_view;
+pub mod status_view;
+pub mod swap_view;
+pub mod token_info_view;
+pub mod trade_card;
 pub mod trade_view;
 pub mod wallet_view;
 pub(crate) mod watchlist_view;
@@ -0,0 +1,167 @@
+use crate::entity::PortfolioSummary;
+use crate::view::message_utils::send_long_message;
+use anyhow::Result;
+use async_trait::async_trait;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+    Bot,
+};
+
+#[async_trait]
+pub trait PortfolioView: Send + Sync {
+    async fn display_portfolio(&self, summary: PortfolioSummary) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramPortfolioView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramPortfolioView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+// Format a SOL gain or loss with a colored marker, e.g. "🟢 +0.120000 SOL"
+fn format_pnl(pnl: f64) -> String {
+    let marker = if pnl > 0.0 {
+        "🟢"
+    } else if pnl < 0.0 {
+        "🔴"
+    } else {
+        "⚪"
+    };
+
+    format!("{} {:+.6} SOL", marker, pnl)
+}
+
+#[async_trait]
+impl PortfolioView for TelegramPortfolioView {
+    async fn display_portfolio(&self, summary: PortfolioSummary) -> Result<()> {
+        let mut message = "<b>Your Portfolio</b>\n\n".to_string();
+
+        if summary.positions.is_empty() {
+            message.push_str("You don't have any open positions from trades made with the bot.\n");
+        }
+
+        for position in &summary.positions {
+            let basis = &position.cost_basis;
+
+            message.push_str(&format!(
+                "<b>{}</b>\n• Amount: {:.6}\n• Avg. cost: {:.6} SOL\n• Cost basis: {:.6} SOL\n",
+                position.token_symbol,
+                basis.amount,
+                basis.average_cost(),
+                basis.total_cost
+            ));
+
+            match (
+                position.current_value(),
+                position.unrealized_pnl(),
+                position.unrealized_pnl_percent(),
+            ) {
+                (Some(value), Some(pnl), percent) => {
+                    message.push_str(&format!(
+                        "• Value: {:.6} SOL\n• P&L: {}{}\n\n",
+                        value,
+                        format_pnl(pnl),
+                        percent
+                            .map(|percent| format!(" ({:+.2}%)", percent))
+                            .unwrap_or_default()
+                    ));
+                }
+                _ => message.push_str("• Value: price unavailable\n\n"),
+            }
+        }
+
+        message.push_str(&format!(
+            "<b>Total</b>\n• Cost basis: {:.6} SOL\n• Value: {:.6} SOL\n• Unrealized P&L: {}\n• Realized P&L: {}",
+            summary.total_cost(),
+            summary.total_value(),
+            format_pnl(summary.unrealized_pnl()),
+            format_pnl(summary.realized_pnl)
+        ));
+
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback("🔄 Refresh", "portfolio"),
+            InlineKeyboardButton::callback("Back to Menu", "menu"),
+        ]]);
+
+        send_long_message(
+            &self.bot,
+            self.chat_id,
+            message,
+            Some(ParseMode::Html),
+            Some(keyboard),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Error: {}", error_message))
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{CostBasis, PortfolioPosition};
+    use crate::view::message_utils::message_limit;
+
+    // Bodies of the messages sent for the summary
+    fn render(summary: PortfolioSummary) -> Vec<serde_json::Value> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        crate::commands::testing::run_with_large_stack(move || async move {
+            let (bot, mut calls) = crate::commands::testing::fake_telegram().await;
+            let view = TelegramPortfolioView::new(bot, ChatId(1));
+
+            view.display_portfolio(summary).await.unwrap();
+
+            let mut bodies = Vec::new();
+            while let Ok(call) = calls.try_recv() {
+                bodies.push(serde_json::from_str(&call.body).unwrap());
+            }
+            tx.send(bodies).unwrap();
+        });
+        rx.recv().unwrap()
+    }
+
+    #[test]
+    fn long_portfolio_is_split_across_messages() {
+        let positions = (0..80)
+            .map(|i| PortfolioPosition {
+                token_address: format!("mint{}", i),
+                token_symbol: format!("TOKEN{}", i),
+                cost_basis: CostBasis {
+                    amount: 1000.0,
+                    total_cost: 1.0,
+                    realized_pnl: 0.0,
+                },
+                current_price_in_sol: Some(0.0012),
+            })
+            .collect();
+
+        let messages = render(PortfolioSummary {
+            positions,
+            realized_pnl: 0.5,
+        });
+
+        assert!(messages.len() > 1);
+        for message in &messages {
+            assert!(message["text"].as_str().unwrap().chars().count() <= message_limit());
+        }
+        let (last, rest) = messages.split_last().unwrap();
+        assert!(last["text"].as_str().unwrap().contains("Realized P&L"));
+        // The keyboard comes with the end of the report
+        assert!(last["reply_markup"].is_object());
+        assert!(rest.iter().all(|message| message["reply_markup"].is_null()));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::entity::{LimitOrder, OrderType, PositionsSummary, PriceCurrency};
+use crate::utils::format_price;
+use crate::view::message_utils::send_long_message;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::str::FromStr;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+    Bot,
+};
+
+#[async_trait]
+pub trait PositionsView: Send + Sync {
+    async fn display_positions(&self, summary: PositionsSummary) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramPositionsView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramPositionsView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+// Pending order with its trigger, e.g. "• Sell #12: 1000 at ≥ 0.000015 SOL"
+fn format_order(order: &LimitOrder) -> String {
+    let order_type = OrderType::from_str(&order.order_type).ok();
+    let name = order_type
+        .as_ref()
+        .map_or("Order", |order_type| order_type.display_name());
+    let comparison = order_type
+        .as_ref()
+        .map_or("", |order_type| order_type.trigger_comparison());
+
+    let price = match order
+        .trigger_value
+        .filter(|_| order.price_currency() == PriceCurrency::Usdc)
+    {
+        Some(target) => format!("${}", target),
+        None => format!("{} SOL", format_price(order.price_in_sol)),
+    };
+
+    format!(
+        "• {} #{}: {:.6} at {} {}\n",
+        name,
+        order.id,
+        order.remaining_amount(),
+        comparison,
+        price
+    )
+}
+
+#[async_trait]
+impl PositionsView for TelegramPositionsView {
+    async fn display_positions(&self, summary: PositionsSummary) -> Result<()> {
+        let mut message = format!(
+            "<b>Your Positions</b>\n\nSOL: {:.6}\n\n",
+            summary.sol_balance
+        );
+
+        if summary.positions.is_empty() {
+            message.push_str("You don't hold any tokens and have no active limit orders.\n");
+        }
+
+        for position in &summary.positions {
+            message.push_str(&format!("<b>{}</b>\n", position.token_symbol));
+
+            match &position.balance {
+                Some(balance) => {
+                    let value = match (position.value_in_sol(), position.value_in_usdc()) {
+                        (Some(value_in_sol), Some(value_in_usdc)) => {
+                            format!(" (≈ {:.6} SOL / ${:.2})", value_in_sol, value_in_usdc)
+                        }
+                        _ => " (price unavailable)".to_string(),
+                    };
+                    message.push_str(&format!("• Held: {}{}\n", balance.display_amount(), value));
+                }
+                None => message.push_str("• Held: none, pending buys only\n"),
+            }
+
+            for order in &position.orders {
+                message.push_str(&format_order(order));
+            }
+            message.push('\n');
+        }
+
+        let keyboard = InlineKeyboardMarkup::new(vec![
+            vec![
+                InlineKeyboardButton::callback("🔄 Refresh", "positions"),
+                InlineKeyboardButton::callback("Limit Orders", "limit_orders"),
+            ],
+            vec![InlineKeyboardButton::callback("Back to Menu", "menu")],
+        ]);
+
+        send_long_message(
+            &self.bot,
+            self.chat_id,
+            message,
+            Some(ParseMode::Html),
+            Some(keyboard),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Error: {}", error_message))
+            .await?;
+        Ok(())
+    }
+}
@@ -1,3 +1,7 @@
+use crate::entity::{PriceCurrency, TokenPrice};
+use crate::i18n::Locale;
+use crate::utils::{format_price_change, format_quoted_price};
+use crate::view::message_utils::edit_or_ignore_unchanged;
 use anyhow::Result;
 use async_trait::async_trait;
 use std::format;
@@ -8,10 +12,9 @@ pub trait PriceView: Send + Sync {
     async fn display_loading(&self, token_id: &str) -> Result<Option<Message>>;
     async fn display_price(
         &self,
-        token_id: &str,
-        symbol: &str,
-        price_in_sol: f64,
-        price_in_usdc: f64,
+        price: &TokenPrice,
+        quote: PriceCurrency,
+        sparkline: Option<String>,
     ) -> Result<()>;
     async fn display_error(&self, error_message: String) -> Result<()>;
 }
@@ -20,6 +23,7 @@ pub struct TelegramPriceView {
     bot: Bot,
     chat_id: ChatId,
     loading_message_id: Option<MessageId>,
+    locale: Locale,
 }
 
 impl TelegramPriceView {
@@ -28,8 +32,15 @@ impl TelegramPriceView {
             bot,
             chat_id,
             loading_message_id: None,
+            locale: Locale::default(),
         }
     }
+
+    /// Format prices with the separators of the user's language
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
 }
 
 #[async_trait]
@@ -45,24 +56,33 @@ impl PriceView for TelegramPriceView {
 
     async fn display_price(
         &self,
-        token_id: &str,
-        symbol: &str,
-        price_in_sol: f64,
-        price_in_usdc: f64,
+        price: &TokenPrice,
+        quote: PriceCurrency,
+        sparkline: Option<String>,
     ) -> Result<()> {
-        let token_text = if symbol.is_empty() || symbol == "Unknown" {
-            token_id.to_string()
+        let token_text = if price.symbol.is_empty() || price.symbol == "Unknown" {
+            price.token_id.clone()
         } else {
-            symbol.to_string()
+            price.symbol.clone()
+        };
+        let change = match price.price_change_24h {
+            Some(change) => format!("\n24h: {}", format_price_change(change, self.locale)),
+            None => "".to_string(),
+        };
+        let sparkline = match sparkline {
+            Some(sparkline) => format!("\n{}", sparkline),
+            None => "".to_string(),
         };
+        // The chosen quote comes first, the other one is kept in brackets
+        let (primary, secondary) =
+            format_quoted_price(price.price_in_sol, price.price_in_usdc, &quote, self.locale);
         let text = format!(
-            "Current price for {}:\n≈ {:.6} SOL\n≈ {:.6} USDC",
-            token_text, price_in_sol, price_in_usdc
+            "Current price for {}:\n≈ {}\n(≈ {}){}{}",
+            token_text, primary, secondary, change, sparkline
         );
 
         if let Some(message_id) = self.loading_message_id {
-            self.bot
-                .edit_message_text(self.chat_id, message_id, text)
+            edit_or_ignore_unchanged(self.bot.edit_message_text(self.chat_id, message_id, text))
                 .await?;
         } else {
             self.bot.send_message(self.chat_id, text).await?;
@@ -75,8 +95,7 @@ impl PriceView for TelegramPriceView {
         let text = format!("❌ Error getting price: {}", error_message);
 
         if let Some(message_id) = self.loading_message_id {
-            self.bot
-                .edit_message_text(self.chat_id, message_id, text)
+            edit_or_ignore_unchanged(self.bot.edit_message_text(self.chat_id, message_id, text))
                 .await?;
         } else {
             self.bot.send_message(self.chat_id, text).await?;
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+    Bot,
+};
+
+#[async_trait]
+pub trait ReferralView: Send + Sync {
+    async fn display_referrals(&self, referral_link: &str, referral_count: i64) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramReferralView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramReferralView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+#[async_trait]
+impl ReferralView for TelegramReferralView {
+    async fn display_referrals(&self, referral_link: &str, referral_count: i64) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "← Back to Menu",
+            "menu",
+        )]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "🤝 <b>Invite Friends</b>\n\n\
+                     Share your referral link:\n<code>{}</code>\n\n\
+                     Users invited: <b>{}</b>",
+                    referral_link, referral_count
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("❌ Error: {}", error_message))
+            .await?;
+        Ok(())
+    }
+}
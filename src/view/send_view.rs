@@ -6,6 +6,7 @@ use teloxide::{prelude::*, Bot};
 pub trait SendView: Send + Sync {
     async fn prompt_for_recipient_address(&self) -> Result<()>;
     async fn display_invalid_address(&self) -> Result<()>;
+    async fn display_recipient_warning(&self, warning: &str) -> Result<()>;
     async fn prompt_for_amount(&self) -> Result<()>;
     async fn display_invalid_amount(&self, error_message: String) -> Result<()>;
     async fn prompt_for_confirmation(
@@ -65,6 +66,13 @@ impl SendView for TelegramSendView {
         Ok(())
     }
 
+    async fn display_recipient_warning(&self, warning: &str) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("⚠️ {}", warning))
+            .await?;
+        Ok(())
+    }
+
     async fn prompt_for_amount(&self) -> Result<()> {
         self.bot
             .send_message(
@@ -1,3 +1,9 @@
+use crate::entity::{
+    Explorer, NotificationKind, PriorityFee, TradeLimitKind, TradeLimits, User,
+    AUTO_CONFIRM_PRESETS, DEFAULT_QUICK_BUY_AMOUNTS, LOW_BALANCE_PRESETS, MAX_ACCOUNTS_RANGE,
+    MAX_QUICK_BUY_AMOUNTS, VALIDITY_SLOTS_RANGE,
+};
+use crate::solana::jupiter::RouteOptions;
 use anyhow::Result;
 use async_trait::async_trait;
 use teloxide::{
@@ -8,10 +14,44 @@ use teloxide::{
 
 #[async_trait]
 pub trait SettingsView: Send + Sync {
-    async fn display_settings_menu(&self, slippage: f64) -> Result<()>;
-    async fn display_slippage_prompt(&self, current_slippage: f64) -> Result<()>;
-    async fn display_slippage_updated(&self, new_slippage: f64) -> Result<()>;
+    async fn display_settings_menu(&self, user: &User) -> Result<()>;
+    async fn display_slippage_prompt(&self, current_slippage_bps: u64) -> Result<()>;
+    async fn display_slippage_updated(&self, slippage_bps: u64) -> Result<()>;
     async fn display_invalid_slippage(&self, error_message: String) -> Result<()>;
+    async fn display_priority_fee_prompt(&self, current_fee: PriorityFee) -> Result<()>;
+    async fn display_priority_fee_updated(&self, priority_fee: PriorityFee) -> Result<()>;
+    async fn display_explorer_prompt(&self, current_explorer: Explorer) -> Result<()>;
+    async fn display_explorer_updated(&self, explorer: Explorer) -> Result<()>;
+    async fn display_low_balance_prompt(&self, current_low_balance_sol: f64) -> Result<()>;
+    async fn display_low_balance_updated(&self, low_balance_sol: f64) -> Result<()>;
+    async fn display_auto_confirm_prompt(&self, current_auto_confirm_below_sol: f64) -> Result<()>;
+    async fn display_auto_confirm_updated(&self, auto_confirm_below_sol: f64) -> Result<()>;
+    async fn display_quick_buy_prompt(&self, current_amounts: &[f64]) -> Result<()>;
+    async fn display_quick_buy_updated(&self, amounts: &[f64]) -> Result<()>;
+    async fn display_advanced_swap_menu(&self, route: RouteOptions) -> Result<()>;
+    async fn display_max_accounts_prompt(&self, current_max_accounts: Option<u64>) -> Result<()>;
+    async fn display_validity_slots_prompt(
+        &self,
+        current_validity_slots: Option<u64>,
+    ) -> Result<()>;
+    async fn display_trade_limit_prompt(
+        &self,
+        kind: TradeLimitKind,
+        current_limit: Option<f64>,
+    ) -> Result<()>;
+    async fn display_trade_limit_updated(
+        &self,
+        kind: TradeLimitKind,
+        limit: Option<f64>,
+    ) -> Result<()>;
+    async fn display_notifications_menu(
+        &self,
+        notifications: &[(NotificationKind, bool)],
+    ) -> Result<()>;
+    async fn display_settings_export(&self, settings_json: &str) -> Result<()>;
+    async fn display_settings_import_prompt(&self) -> Result<()>;
+    async fn display_settings_imported(&self) -> Result<()>;
+    async fn display_invalid_import(&self, error_message: String) -> Result<()>;
     async fn display_error(&self, error_message: String) -> Result<()>;
 }
 
@@ -26,15 +66,133 @@ impl TelegramSettingsView {
     }
 }
 
+// "0.5% (50 bps)"
+fn describe_slippage(slippage_bps: u64) -> String {
+    format!("{}% ({} bps)", slippage_bps as f64 / 100.0, slippage_bps)
+}
+
+// "0.01 SOL", or "Off" when the warning is disabled
+fn describe_low_balance(low_balance_sol: f64) -> String {
+    if low_balance_sol > 0.0 {
+        format!("{} SOL", low_balance_sol)
+    } else {
+        "Off".to_string()
+    }
+}
+
+// "Below 0.05 SOL", or "Off" when every trade is confirmed
+fn describe_auto_confirm(auto_confirm_below_sol: f64) -> String {
+    if auto_confirm_below_sol > 0.0 {
+        format!("Below {} SOL", auto_confirm_below_sol)
+    } else {
+        "Off".to_string()
+    }
+}
+
+// "32", or "Default" when Jupiter decides
+fn describe_max_accounts(max_accounts: Option<u64>) -> String {
+    match max_accounts {
+        Some(max_accounts) => max_accounts.to_string(),
+        None => "Default".to_string(),
+    }
+}
+
+// "60 slots", or "Default" when the blockhash lifetime applies
+fn describe_validity_slots(validity_slots: Option<u64>) -> String {
+    match validity_slots {
+        Some(validity_slots) => format!("{} slots", validity_slots),
+        None => "Default".to_string(),
+    }
+}
+
+// "0.1 / 0.5 / 1 / 5 SOL"
+fn describe_quick_buy(amounts: &[f64]) -> String {
+    let amounts: Vec<String> = amounts.iter().map(|amount| amount.to_string()).collect();
+    format!("{} SOL", amounts.join(" / "))
+}
+
 #[async_trait]
 impl SettingsView for TelegramSettingsView {
-    async fn display_settings_menu(&self, slippage: f64) -> Result<()> {
+    async fn display_settings_menu(&self, user: &User) -> Result<()> {
+        let slippage = user.get_slippage();
+        let priority_fee = user.get_priority_fee();
+        let trade_limits = user.get_trade_limits();
+        let explorer = user.get_explorer();
+        let default_quote = user.get_default_quote();
+        let trade_card = user.get_trade_card();
+        let low_balance_sol = user.get_low_balance_sol();
+        let auto_confirm_below_sol = user.get_auto_confirm_below_sol();
+        let quick_buy_amounts = user.get_quick_buy_amounts();
+
         // Create keyboard with settings options
         let keyboard = InlineKeyboardMarkup::new(vec![
             vec![InlineKeyboardButton::callback(
                 format!("Slippage ({}%)", slippage),
                 "set_slippage",
             )],
+            vec![InlineKeyboardButton::callback(
+                format!("Priority Fee ({})", priority_fee),
+                "set_priority_fee",
+            )],
+            vec![InlineKeyboardButton::callback(
+                format!(
+                    "{} ({})",
+                    TradeLimitKind::MaxTrade.title(),
+                    TradeLimits::describe(trade_limits.max_trade_sol)
+                ),
+                "set_max_trade",
+            )],
+            vec![InlineKeyboardButton::callback(
+                format!(
+                    "{} ({})",
+                    TradeLimitKind::Daily.title(),
+                    TradeLimits::describe(trade_limits.daily_limit_sol)
+                ),
+                "set_daily_limit",
+            )],
+            vec![InlineKeyboardButton::callback(
+                format!("Explorer ({})", explorer),
+                "set_explorer",
+            )],
+            vec![InlineKeyboardButton::callback(
+                format!("Price Quote ({})", default_quote),
+                "toggle_default_quote",
+            )],
+            vec![InlineKeyboardButton::callback(
+                format!("Trade Card ({})", if trade_card { "On" } else { "Off" }),
+                "toggle_trade_card",
+            )],
+            vec![InlineKeyboardButton::callback(
+                format!(
+                    "Low SOL Warning ({})",
+                    describe_low_balance(low_balance_sol)
+                ),
+                "set_low_balance",
+            )],
+            vec![InlineKeyboardButton::callback(
+                format!(
+                    "Auto-Confirm ({})",
+                    describe_auto_confirm(auto_confirm_below_sol)
+                ),
+                "set_auto_confirm",
+            )],
+            vec![InlineKeyboardButton::callback(
+                format!("Quick Buy ({})", describe_quick_buy(&quick_buy_amounts)),
+                "set_quick_buy",
+            )],
+            vec![InlineKeyboardButton::callback(
+                "Advanced Swap Settings",
+                "advanced_swap",
+            )],
+            vec![InlineKeyboardButton::callback(
+                "Notifications",
+                "notifications",
+            )],
+            vec![InlineKeyboardButton::callback("Language", "language")],
+            vec![
+                InlineKeyboardButton::callback("Export Settings", "settings_export"),
+                InlineKeyboardButton::callback("Import Settings", "settings_import"),
+            ],
             vec![InlineKeyboardButton::callback("Back to Menu", "menu")],
         ]);
 
@@ -50,7 +208,7 @@ impl SettingsView for TelegramSettingsView {
         Ok(())
     }
 
-    async fn display_slippage_prompt(&self, current_slippage: f64) -> Result<()> {
+    async fn display_slippage_prompt(&self, current_slippage_bps: u64) -> Result<()> {
         // Provide preset options for common values
         let keyboard = InlineKeyboardMarkup::new(vec![
             vec![
@@ -70,9 +228,10 @@ impl SettingsView for TelegramSettingsView {
             .send_message(
                 self.chat_id,
                 format!(
-                    "Your current slippage tolerance is set to <b>{:.1}%</b>\n\n\
-                    Select a preset value or type a custom percentage between 0.1% and 5.0%:",
-                    current_slippage
+                    "Your current slippage tolerance is set to <b>{}</b>\n\n\
+                    Select a preset value or type a custom value between 0.1% and 5.0%, \
+                    as a percentage (0.5%) or in basis points (50bps):",
+                    describe_slippage(current_slippage_bps)
                 ),
             )
             .parse_mode(ParseMode::Html)
@@ -82,7 +241,7 @@ impl SettingsView for TelegramSettingsView {
         Ok(())
     }
 
-    async fn display_slippage_updated(&self, new_slippage: f64) -> Result<()> {
+    async fn display_slippage_updated(&self, slippage_bps: u64) -> Result<()> {
         let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
             "Back to Settings",
             "settings",
@@ -92,8 +251,8 @@ impl SettingsView for TelegramSettingsView {
             .send_message(
                 self.chat_id,
                 format!(
-                    "✅ Slippage tolerance has been updated to <b>{:.1}%</b>",
-                    new_slippage
+                    "✅ Slippage tolerance has been updated to <b>{}</b>",
+                    describe_slippage(slippage_bps)
                 ),
             )
             .parse_mode(ParseMode::Html)
@@ -108,7 +267,7 @@ impl SettingsView for TelegramSettingsView {
             .send_message(
                 self.chat_id,
                 format!(
-                    "⚠️ Invalid slippage value: {}\n\nPlease enter a number between 0.1 and 5.0",
+                    "⚠️ Invalid slippage value: {}\n\nPlease enter e.g. 0.5% or 50bps",
                     error_message
                 ),
             )
@@ -117,6 +276,614 @@ impl SettingsView for TelegramSettingsView {
         Ok(())
     }
 
+    async fn display_priority_fee_prompt(&self, current_fee: PriorityFee) -> Result<()> {
+        // One button per preset, the current one is marked
+        let mut keyboard_buttons: Vec<Vec<InlineKeyboardButton>> = PriorityFee::ALL
+            .iter()
+            .map(|fee| {
+                let label = if *fee == current_fee {
+                    format!("✅ {}", fee.description())
+                } else {
+                    fee.description()
+                };
+                vec![InlineKeyboardButton::callback(
+                    label,
+                    format!("priority_fee_{}", fee.to_string().to_lowercase()),
+                )]
+            })
+            .collect();
+
+        keyboard_buttons.push(vec![InlineKeyboardButton::callback("Cancel", "settings")]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "Your current priority fee is <b>{}</b>\n\n\
+                    A higher priority fee makes swaps land faster when the network is congested. \
+                    <b>Auto</b> uses the fee recommended by Jupiter.",
+                    current_fee.description()
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(InlineKeyboardMarkup::new(keyboard_buttons))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_priority_fee_updated(&self, priority_fee: PriorityFee) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "Back to Settings",
+            "settings",
+        )]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "✅ Priority fee has been updated to <b>{}</b>",
+                    priority_fee.description()
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_explorer_prompt(&self, current_explorer: Explorer) -> Result<()> {
+        // One button per explorer, the current one is marked
+        let mut keyboard_buttons: Vec<Vec<InlineKeyboardButton>> = Explorer::ALL
+            .iter()
+            .map(|explorer| {
+                let label = if *explorer == current_explorer {
+                    format!("✅ {}", explorer)
+                } else {
+                    explorer.to_string()
+                };
+                vec![InlineKeyboardButton::callback(
+                    label,
+                    format!("explorer_{}", explorer.code()),
+                )]
+            })
+            .collect();
+
+        keyboard_buttons.push(vec![InlineKeyboardButton::callback("Cancel", "settings")]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "Transaction links open in <b>{}</b>\n\n\
+                    Choose the block explorer for trade, withdrawal and limit order links:",
+                    current_explorer
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(InlineKeyboardMarkup::new(keyboard_buttons))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_explorer_updated(&self, explorer: Explorer) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "Back to Settings",
+            "settings",
+        )]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!("✅ Transaction links now open in <b>{}</b>", explorer),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_low_balance_prompt(&self, current_low_balance_sol: f64) -> Result<()> {
+        // Preset thresholds and "Off", the current one is marked
+        let label = |low_balance_sol: f64| {
+            let text = describe_low_balance(low_balance_sol);
+            if low_balance_sol == current_low_balance_sol {
+                format!("✅ {}", text)
+            } else {
+                text
+            }
+        };
+
+        let presets: Vec<InlineKeyboardButton> = LOW_BALANCE_PRESETS
+            .iter()
+            .map(|preset| {
+                InlineKeyboardButton::callback(label(*preset), format!("low_balance_{}", preset))
+            })
+            .collect();
+
+        let keyboard = InlineKeyboardMarkup::new(vec![
+            presets,
+            vec![InlineKeyboardButton::callback(
+                label(0.0),
+                "low_balance_off",
+            )],
+            vec![InlineKeyboardButton::callback("Cancel", "settings")],
+        ]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "Low SOL warning: <b>{}</b>\n\n\
+                    The main menu shows a warning when your SOL balance drops below this \
+                    amount, every trade and withdrawal needs a little SOL for network fees.",
+                    describe_low_balance(current_low_balance_sol)
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_low_balance_updated(&self, low_balance_sol: f64) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "Back to Settings",
+            "settings",
+        )]]);
+
+        let text = if low_balance_sol > 0.0 {
+            format!(
+                "✅ You'll be warned when your SOL balance drops below <b>{} SOL</b>",
+                low_balance_sol
+            )
+        } else {
+            "✅ Low SOL balance warning turned off".to_string()
+        };
+
+        self.bot
+            .send_message(self.chat_id, text)
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_auto_confirm_prompt(&self, current_auto_confirm_below_sol: f64) -> Result<()> {
+        // Preset thresholds and "Off", the current one is marked
+        let label = |auto_confirm_below_sol: f64| {
+            let text = describe_auto_confirm(auto_confirm_below_sol);
+            if auto_confirm_below_sol == current_auto_confirm_below_sol {
+                format!("✅ {}", text)
+            } else {
+                text
+            }
+        };
+
+        let presets: Vec<InlineKeyboardButton> = AUTO_CONFIRM_PRESETS
+            .iter()
+            .map(|preset| {
+                InlineKeyboardButton::callback(label(*preset), format!("auto_confirm_{}", preset))
+            })
+            .collect();
+
+        // Two presets per row, "Below 0.05 SOL" is too wide for four
+        let mut rows: Vec<Vec<InlineKeyboardButton>> =
+            presets.chunks(2).map(|row| row.to_vec()).collect();
+        rows.push(vec![InlineKeyboardButton::callback(
+            label(0.0),
+            "auto_confirm_off",
+        )]);
+        rows.push(vec![InlineKeyboardButton::callback("Cancel", "settings")]);
+
+        let keyboard = InlineKeyboardMarkup::new(rows);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "Auto-confirm: <b>{}</b>\n\n\
+                    Buys and sells worth less than this amount are executed right away, \
+                    without the yes/no confirmation. Trades with a high price impact and \
+                    withdrawals are always confirmed.",
+                    describe_auto_confirm(current_auto_confirm_below_sol)
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_auto_confirm_updated(&self, auto_confirm_below_sol: f64) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "Back to Settings",
+            "settings",
+        )]]);
+
+        let text = if auto_confirm_below_sol > 0.0 {
+            format!(
+                "✅ Trades below <b>{} SOL</b> will be executed without confirmation",
+                auto_confirm_below_sol
+            )
+        } else {
+            "✅ Auto-confirm turned off, every trade asks for confirmation".to_string()
+        };
+
+        self.bot
+            .send_message(self.chat_id, text)
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_quick_buy_prompt(&self, current_amounts: &[f64]) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![
+            vec![InlineKeyboardButton::callback(
+                format!(
+                    "Reset to {}",
+                    describe_quick_buy(&DEFAULT_QUICK_BUY_AMOUNTS)
+                ),
+                "quick_buy_reset",
+            )],
+            vec![InlineKeyboardButton::callback("Cancel", "settings")],
+        ]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "Your quick-buy buttons: <b>{}</b>\n\n\
+                    They are shown after you pick a token to buy and skip straight to \
+                    confirmation. Type up to {} SOL amounts separated by spaces \
+                    (e.g. <code>0.1 0.5 1 5</code>):",
+                    describe_quick_buy(current_amounts),
+                    MAX_QUICK_BUY_AMOUNTS
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_quick_buy_updated(&self, amounts: &[f64]) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "Back to Settings",
+            "settings",
+        )]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "✅ Quick-buy buttons set to <b>{}</b>",
+                    describe_quick_buy(amounts)
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_advanced_swap_menu(&self, route: RouteOptions) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![
+            vec![InlineKeyboardButton::callback(
+                format!(
+                    "Direct Routes Only ({})",
+                    if route.only_direct_routes {
+                        "On"
+                    } else {
+                        "Off"
+                    }
+                ),
+                "toggle_direct_routes",
+            )],
+            vec![InlineKeyboardButton::callback(
+                format!(
+                    "Max Accounts ({})",
+                    describe_max_accounts(route.max_accounts)
+                ),
+                "set_max_accounts",
+            )],
+            vec![InlineKeyboardButton::callback(
+                format!(
+                    "Transaction Validity ({})",
+                    describe_validity_slots(route.validity_slots)
+                ),
+                "set_validity_slots",
+            )],
+            vec![InlineKeyboardButton::callback(
+                "Back to Settings",
+                "settings",
+            )],
+        ]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                "<b>Advanced Swap Settings</b>\n\n\
+                If swaps fail because the transaction is too large, restrict the routes \
+                Jupiter may use. Simpler routes can give a slightly worse price.\n\n\
+                • <b>Direct Routes Only</b> - single-hop swaps, no intermediate tokens\n\
+                • <b>Max Accounts</b> - upper limit on the accounts a route may touch\n\
+                • <b>Transaction Validity</b> - slots a swap may take to land, a swap that \
+                expires is not sent at a stale price"
+                    .to_string(),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_max_accounts_prompt(&self, current_max_accounts: Option<u64>) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![
+            vec![InlineKeyboardButton::callback(
+                "Use Jupiter Default",
+                "max_accounts_reset",
+            )],
+            vec![InlineKeyboardButton::callback("Cancel", "advanced_swap")],
+        ]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "Max accounts per route: <b>{}</b>\n\n\
+                    Enter a number between {} and {} (e.g. <code>32</code>):",
+                    describe_max_accounts(current_max_accounts),
+                    MAX_ACCOUNTS_RANGE.start(),
+                    MAX_ACCOUNTS_RANGE.end()
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_validity_slots_prompt(
+        &self,
+        current_validity_slots: Option<u64>,
+    ) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![
+            vec![InlineKeyboardButton::callback(
+                "Use Default",
+                "validity_slots_reset",
+            )],
+            vec![InlineKeyboardButton::callback("Cancel", "advanced_swap")],
+        ]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "Transaction validity: <b>{}</b>\n\n\
+                    A slot is about 0.4 seconds. A swap that doesn't land within the \
+                    validity expires and is not sent at a stale price. By default a swap \
+                    stays valid for the full blockhash lifetime of about 150 slots.\n\n\
+                    Enter a number of slots between {} and {} (e.g. <code>60</code>):",
+                    describe_validity_slots(current_validity_slots),
+                    VALIDITY_SLOTS_RANGE.start(),
+                    VALIDITY_SLOTS_RANGE.end()
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_trade_limit_prompt(
+        &self,
+        kind: TradeLimitKind,
+        current_limit: Option<f64>,
+    ) -> Result<()> {
+        // Preset values and "Off", the current one is marked
+        let label = |limit: Option<f64>| {
+            let text = TradeLimits::describe(limit);
+            if limit == current_limit {
+                format!("✅ {}", text)
+            } else {
+                text
+            }
+        };
+
+        let presets: Vec<InlineKeyboardButton> = kind
+            .presets()
+            .iter()
+            .map(|preset| {
+                InlineKeyboardButton::callback(
+                    label(Some(*preset)),
+                    format!("{}{}", kind.callback_prefix(), preset),
+                )
+            })
+            .collect();
+
+        let keyboard = InlineKeyboardMarkup::new(vec![
+            presets,
+            vec![InlineKeyboardButton::callback(
+                label(None),
+                format!("{}off", kind.callback_prefix()),
+            )],
+            vec![InlineKeyboardButton::callback("Cancel", "settings")],
+        ]);
+
+        let description = match kind {
+            TradeLimitKind::MaxTrade => {
+                "Buys spending more SOL than this in a single trade are rejected."
+            }
+            TradeLimitKind::Daily => {
+                "Buys are rejected once the SOL spent in the last 24 hours would exceed this limit."
+            }
+        };
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "Your current {} is <b>{}</b>\n\n{}\n\
+                    Limit orders and DCA buys are checked as well.",
+                    kind.title().to_lowercase(),
+                    TradeLimits::describe(current_limit),
+                    description
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_trade_limit_updated(
+        &self,
+        kind: TradeLimitKind,
+        limit: Option<f64>,
+    ) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "Back to Settings",
+            "settings",
+        )]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "✅ {} has been updated to <b>{}</b>",
+                    kind.title(),
+                    TradeLimits::describe(limit)
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_notifications_menu(
+        &self,
+        notifications: &[(NotificationKind, bool)],
+    ) -> Result<()> {
+        let mut keyboard_buttons: Vec<Vec<InlineKeyboardButton>> = notifications
+            .iter()
+            .map(|(kind, enabled)| {
+                vec![InlineKeyboardButton::callback(
+                    format!("{} ({})", kind, if *enabled { "On" } else { "Off" }),
+                    format!("toggle_notification_{}", kind.code()),
+                )]
+            })
+            .collect();
+        keyboard_buttons.push(vec![InlineKeyboardButton::callback(
+            "Back to Settings",
+            "settings",
+        )]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                "<b>Notifications</b>\n\n\
+                Choose which messages the bot sends you in the background. \
+                Failed and expired limit orders are always reported."
+                    .to_string(),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(InlineKeyboardMarkup::new(keyboard_buttons))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_settings_export(&self, settings_json: &str) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "Back to Settings",
+            "settings",
+        )]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "<b>Your Settings</b>\n\n<pre>{}</pre>\n\n\
+                    Copy it and use <b>Import Settings</b> on your other account.",
+                    teloxide::utils::html::escape(settings_json)
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_settings_import_prompt(&self) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "Cancel", "settings",
+        )]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                "Paste the settings exported from your other account.\n\n\
+                They replace your slippage, priority fee, quick-buy, explorer and other \
+                preferences, your wallet is not changed.",
+            )
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_settings_imported(&self) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "Back to Settings",
+            "settings",
+        )]]);
+
+        self.bot
+            .send_message(self.chat_id, "✅ Settings have been imported")
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_invalid_import(&self, error_message: String) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "Back to Settings",
+            "settings",
+        )]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "❌ Settings were not imported: {}\n\nNothing has been changed.",
+                    error_message
+                ),
+            )
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
     async fn display_error(&self, error_message: String) -> Result<()> {
         self.bot
             .send_message(self.chat_id, format!("Error: {}", error_message))
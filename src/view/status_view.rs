@@ -0,0 +1,147 @@
+use crate::interactor::status_interactor::StatusReport;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::time::Duration;
+use teloxide::{prelude::*, types::ParseMode, utils::html, Bot};
+
+#[async_trait]
+pub trait StatusView: Send + Sync {
+    async fn display_unknown_command(&self) -> Result<()>;
+    async fn display_status(&self, report: &StatusReport) -> Result<()>;
+}
+
+pub struct TelegramStatusView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramStatusView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+// Milliseconds below a second, seconds with two decimals above
+fn format_latency(latency: Duration) -> String {
+    if latency < Duration::from_secs(1) {
+        format!("{} ms", latency.as_millis())
+    } else {
+        format!("{:.2} s", latency.as_secs_f64())
+    }
+}
+
+// Green line with the details of a passed check, red line with the error otherwise
+fn format_check(name: &str, result: Result<String, &String>) -> String {
+    match result {
+        Ok(details) => format!("🟢 <b>{}</b>: {}", name, details),
+        Err(e) => format!("🔴 <b>{}</b>: {}", name, html::escape(e)),
+    }
+}
+
+// Status message with one line per check
+fn format_status(report: &StatusReport) -> String {
+    let rpc = format_check(
+        "RPC",
+        report
+            .rpc
+            .as_ref()
+            .map(|rpc| format!("slot {} · {}", rpc.slot, format_latency(rpc.latency))),
+    );
+    let database = format_check(
+        "Database",
+        report.database.as_ref().map(|latency| {
+            format!(
+                "{} · {}/{} connections, {} idle",
+                format_latency(*latency),
+                report.pool.size,
+                report.pool.max,
+                report.pool.idle
+            )
+        }),
+    );
+    let limit_orders = format_check(
+        "Active limit orders",
+        report
+            .active_limit_orders
+            .as_ref()
+            .map(|count| count.to_string()),
+    );
+
+    format!(
+        "<b>Bot status</b> · v{}\n\n{}\n{}\n{}",
+        report.version, rpc, database, limit_orders
+    )
+}
+
+#[async_trait]
+impl StatusView for TelegramStatusView {
+    async fn display_unknown_command(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "Unknown command. Use /help to see the available commands.",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn display_status(&self, report: &StatusReport) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format_status(report))
+            .parse_mode(ParseMode::Html)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interactor::status_interactor::{PoolStats, RpcHealth};
+
+    fn report(database: Result<Duration, String>) -> StatusReport {
+        StatusReport {
+            version: "1.2.0",
+            rpc: Ok(RpcHealth {
+                slot: 301_234_567,
+                latency: Duration::from_millis(84),
+            }),
+            database,
+            pool: PoolStats {
+                size: 3,
+                idle: 2,
+                max: 10,
+            },
+            active_limit_orders: Ok(12),
+        }
+    }
+
+    #[test]
+    fn healthy_checks_are_green() {
+        let text = format_status(&report(Ok(Duration::from_millis(3))));
+
+        assert!(text.starts_with("<b>Bot status</b> · v1.2.0"));
+        assert!(text.contains("🟢 <b>RPC</b>: slot 301234567 · 84 ms"));
+        assert!(text.contains("🟢 <b>Database</b>: 3 ms · 3/10 connections, 2 idle"));
+        assert!(text.contains("🟢 <b>Active limit orders</b>: 12"));
+    }
+
+    #[test]
+    fn failing_database_is_red_while_rpc_stays_green() {
+        let text = format_status(&report(Err(
+            "pool timed out while waiting for an open connection <db>".to_string(),
+        )));
+
+        assert!(text.contains("🟢 <b>RPC</b>: slot 301234567 · 84 ms"));
+        assert!(text.contains(
+            "🔴 <b>Database</b>: pool timed out while waiting for an open connection &lt;db&gt;"
+        ));
+        assert!(text.contains("🟢 <b>Active limit orders</b>: 12"));
+    }
+
+    #[test]
+    fn slow_checks_are_shown_in_seconds() {
+        assert_eq!(format_latency(Duration::from_millis(999)), "999 ms");
+        assert_eq!(format_latency(Duration::from_millis(1_250)), "1.25 s");
+    }
+}
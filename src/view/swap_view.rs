@@ -0,0 +1,137 @@
+use crate::interactor::swap_interactor::{SwapQuote, SwapResult};
+use anyhow::Result;
+use async_trait::async_trait;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, Message, ParseMode},
+    Bot,
+};
+
+#[async_trait]
+pub trait SwapView: Send + Sync {
+    async fn display_usage(&self) -> Result<()>;
+    async fn display_quote(&self, quote: &SwapQuote) -> Result<()>;
+    async fn display_processing(&self) -> Result<Option<Message>>;
+    async fn display_swap_result(
+        &self,
+        result: &SwapResult,
+        message: Option<Message>,
+    ) -> Result<()>;
+    async fn display_swap_cancelled(&self) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramSwapView {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramSwapView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self { bot, chat_id }
+    }
+}
+
+#[async_trait]
+impl SwapView for TelegramSwapView {
+    async fn display_usage(&self) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                "Use the command in this format: /swap <amount> <from_token> <to_token>\n\n\
+                Tokens can be symbols (SOL, USDC, USDT, RAY) or mint addresses.\n\
+                Example: /swap 0.5 SOL USDC",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn display_quote(&self, quote: &SwapQuote) -> Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback("✅ Confirm Swap", "confirm_swap"),
+            InlineKeyboardButton::callback("❌ Cancel", "cancel_swap"),
+        ]]);
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "<b>Swap Confirmation</b>\n\n\
+                    • You pay: <b>{} {}</b>\n\
+                    • You receive (estimated): <b>{:.6} {}</b>\n\
+                    • Slippage: <b>{}%</b>\n\n\
+                    Do you want to proceed?",
+                    quote.amount_in,
+                    quote.source.symbol,
+                    quote.amount_out,
+                    quote.target.symbol,
+                    quote.slippage
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+        Ok(())
+    }
+
+    async fn display_processing(&self) -> Result<Option<Message>> {
+        let message = self
+            .bot
+            .send_message(self.chat_id, "Processing your swap... Please wait.")
+            .await?;
+
+        Ok(Some(message))
+    }
+
+    async fn display_swap_result(
+        &self,
+        result: &SwapResult,
+        message: Option<Message>,
+    ) -> Result<()> {
+        let text = match (&result.signature, result.success) {
+            (Some(signature), true) => format!(
+                "✅ Swap completed successfully.\nSold: {} {}\nReceived (estimated): {:.6} {}\nTx Signature: {}\nCheck transaction: https://explorer.solana.com/tx/{}",
+                result.amount_in,
+                result.source_symbol,
+                result.amount_out,
+                result.target_symbol,
+                signature,
+                signature
+            ),
+            _ => format!(
+                "❌ Error swapping {} {} to {}:\n{}",
+                result.amount_in,
+                result.source_symbol,
+                result.target_symbol,
+                result
+                    .error_message
+                    .clone()
+                    .unwrap_or_else(|| "Unknown error".to_string())
+            ),
+        };
+
+        if let Some(msg) = message {
+            self.bot
+                .edit_message_text(self.chat_id, msg.id, text)
+                .await?;
+        } else {
+            self.bot.send_message(self.chat_id, text).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn display_swap_cancelled(&self) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, "Swap cancelled.")
+            .await?;
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, format!("Error: {}", error_message))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use crate::i18n::Locale;
+use crate::interactor::token_info_interactor::{TokenInfo, TOP_HOLDERS};
+use crate::utils::{format_amount, format_price_change, format_usd};
+use anyhow::Result;
+use async_trait::async_trait;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+    utils::html,
+    Bot,
+};
+
+#[async_trait]
+pub trait TokenInfoView: Send + Sync {
+    async fn display_token_info(&self, info: &TokenInfo) -> Result<()>;
+    async fn display_error(&self, error_message: String) -> Result<()>;
+}
+
+pub struct TelegramTokenInfoView {
+    bot: Bot,
+    chat_id: ChatId,
+    locale: Locale,
+}
+
+impl TelegramTokenInfoView {
+    pub fn new(bot: Bot, chat_id: ChatId) -> Self {
+        Self {
+            bot,
+            chat_id,
+            locale: Locale::default(),
+        }
+    }
+
+    /// Format amounts with the separators of the user's language
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+// "✅ Revoked", or a warning with what the authority allows
+fn describe_authority(authority: Option<&String>, warning: &str) -> String {
+    match authority {
+        Some(_) => format!("⚠️ Active, {}", warning),
+        None => "✅ Revoked".to_string(),
+    }
+}
+
+#[async_trait]
+impl TokenInfoView for TelegramTokenInfoView {
+    async fn display_token_info(&self, info: &TokenInfo) -> Result<()> {
+        let title = match &info.token {
+            Some(token) => format!(
+                "<b>{}</b> ({}){}",
+                html::escape(&token.symbol),
+                html::escape(&token.name),
+                if token.verified { " ✓" } else { "" }
+            ),
+            None => "<b>Unknown token</b>".to_string(),
+        };
+
+        let price = match &info.price {
+            Some(price) => {
+                let change = price
+                    .price_change_24h
+                    .map(|change| format!(" · 24h {}", format_price_change(change, self.locale)))
+                    .unwrap_or_default();
+                format!(
+                    "{} SOL ({}){}",
+                    format_amount(price.price_in_sol, self.locale),
+                    format_usd(price.price_in_usdc, self.locale),
+                    change
+                )
+            }
+            None => "Not available".to_string(),
+        };
+
+        let top_holders = match info.top_holders_pct {
+            Some(pct) => format!("{:.1}% of supply", pct),
+            None => "Not available".to_string(),
+        };
+
+        let mut text = format!(
+            "{}\n<code>{}</code>\n\n\
+            • Price: {}\n\
+            • Supply: {}\n\
+            • Decimals: {}\n\
+            • Top {} holders: {}\n\
+            • Mint authority: {}\n\
+            • Freeze authority: {}",
+            title,
+            info.mint_address,
+            price,
+            format_amount(info.supply, self.locale),
+            info.mint.decimals,
+            TOP_HOLDERS,
+            top_holders,
+            describe_authority(
+                info.mint.mint_authority.as_ref(),
+                "more tokens can be minted"
+            ),
+            describe_authority(
+                info.mint.freeze_authority.as_ref(),
+                "your tokens can be frozen"
+            ),
+        );
+
+        if info.mint.is_token_2022 {
+            text.push_str("\n• Program: Token-2022");
+        }
+
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback("Buy", format!("buy_token_{}", info.mint_address)),
+            InlineKeyboardButton::callback("← Back to Menu", "menu"),
+        ]]);
+
+        self.bot
+            .send_message(self.chat_id, text)
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_error(&self, error_message: String) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!("❌ Error getting token info: {}", error_message),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use crate::entity::OrderType;
+use crate::qrcodeutils;
+use anyhow::{anyhow, Result};
+use log::warn;
+use teloxide::{prelude::*, types::InputFile, Bot};
+
+const CARD_WIDTH: u32 = 480;
+const CARD_HEIGHT: u32 = 260;
+
+/// A confirmed trade rendered as a small PNG card to share
+///
+/// The amounts are formatted by the caller, so the card reads like the text message.
+#[derive(Debug, Clone)]
+pub struct TradeCard {
+    pub order_type: OrderType,
+    pub token_symbol: String,
+    pub amount: String,
+    pub price: String,
+    pub total: String,
+}
+
+impl TradeCard {
+    /// SVG of the card, the fields are escaped so a token symbol can't break the markup
+    pub fn svg(&self) -> String {
+        let (title, accent) = if self.order_type.is_sell() {
+            ("SOLD", "#f6465d")
+        } else {
+            ("BOUGHT", "#0ecb81")
+        };
+
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">
+<rect width="{width}" height="{height}" rx="16" fill="#14151a"/>
+<rect x="20" y="28" width="6" height="{bar}" rx="3" fill="{accent}"/>
+<text x="44" y="58" font-family="sans-serif" font-size="28" font-weight="bold" fill="{accent}">{title}</text>
+<text x="44" y="104" font-family="sans-serif" font-size="36" font-weight="bold" fill="#ffffff">{amount} {symbol}</text>
+<text x="44" y="160" font-family="sans-serif" font-size="18" fill="#8a8d99">Price</text>
+<text x="{right}" y="160" font-family="sans-serif" font-size="18" fill="#ffffff" text-anchor="end">{price} SOL</text>
+<text x="44" y="200" font-family="sans-serif" font-size="18" fill="#8a8d99">Total</text>
+<text x="{right}" y="200" font-family="sans-serif" font-size="18" fill="#ffffff" text-anchor="end">{total} SOL</text>
+</svg>"##,
+            width = CARD_WIDTH,
+            height = CARD_HEIGHT,
+            right = CARD_WIDTH - 32,
+            bar = CARD_HEIGHT - 56,
+            accent = accent,
+            title = title,
+            amount = escape_xml(&self.amount),
+            symbol = escape_xml(&self.token_symbol),
+            price = escape_xml(&self.price),
+            total = escape_xml(&self.total),
+        )
+    }
+
+    /// Rasterize the card, fails without a system font since the text would be missing
+    pub fn render_png(&self) -> Result<Vec<u8>> {
+        if !qrcodeutils::fonts_available() {
+            return Err(anyhow!("No fonts installed to render the trade card"));
+        }
+
+        qrcodeutils::render_svg_to_png(self.svg().as_bytes())
+    }
+}
+
+/// Send the card below the trade message, the text message stays the record of the trade
+/// so a card that fails to render or send is only logged
+pub async fn send_trade_card(bot: &Bot, chat_id: ChatId, card: TradeCard) {
+    let png = match tokio::task::spawn_blocking(move || card.render_png()).await {
+        Ok(Ok(png)) => png,
+        Ok(Err(e)) => {
+            warn!("Failed to render trade card: {}", e);
+            return;
+        }
+        Err(e) => {
+            warn!("Trade card rendering panicked: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = bot
+        .send_photo(chat_id, InputFile::memory(png).file_name("trade.png"))
+        .await
+    {
+        warn!("Failed to send trade card: {}", e);
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(token_symbol: &str) -> TradeCard {
+        TradeCard {
+            order_type: OrderType::Buy,
+            token_symbol: token_symbol.to_string(),
+            amount: "1,250.5".to_string(),
+            price: "0.000012".to_string(),
+            total: "0.015006".to_string(),
+        }
+    }
+
+    #[test]
+    fn markup_characters_are_escaped() {
+        assert_eq!(
+            escape_xml(r#"<a href="x">Tom's & co</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom&apos;s &amp; co&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn svg_shows_the_trade_values() {
+        let svg = card("BONK").svg();
+
+        assert!(svg.contains(">BOUGHT</text>"));
+        assert!(svg.contains(">1,250.5 BONK</text>"));
+        assert!(svg.contains(">0.000012 SOL</text>"));
+        assert!(svg.contains(">0.015006 SOL</text>"));
+    }
+
+    #[test]
+    fn svg_escapes_the_token_symbol() {
+        let svg = card("<b>\"X'&").svg();
+
+        assert!(svg.contains(">1,250.5 &lt;b&gt;&quot;X&apos;&amp;</text>"));
+        assert!(!svg.contains("<b>"));
+    }
+
+    #[test]
+    fn card_renders_to_a_png() {
+        let card = card("BONK");
+
+        // Without fonts the text would be missing, so the card isn't rendered at all
+        if !qrcodeutils::fonts_available() {
+            assert!(card.render_png().is_err());
+            return;
+        }
+
+        let png = card.render_png().unwrap();
+        assert!(png.len() > 8);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::entity::OrderType;
+use crate::utils::{format_price, format_usd_price};
 use anyhow::Result;
 use async_trait::async_trait;
 use teloxide::{prelude::*, Bot};
@@ -92,12 +93,9 @@ impl TradeView for TelegramTradeView {
         current_price_in_sol: f64,
         current_price_in_usdc: f64,
     ) -> Result<()> {
-        let action = match order_type {
-            OrderType::Buy => "buy",
-            OrderType::Sell => "sell",
-        };
+        let action = if order_type.is_sell() { "sell" } else { "buy" };
 
-        let additional_instructions = if *order_type == OrderType::Sell {
+        let additional_instructions = if order_type.is_sell() {
             "\n\nFor sell orders, you can also specify a percentage of your holdings:\n<price> <percentage>%\nExample: 0.5 50% (sell 50% of your tokens at 0.5 SOL each)"
         } else {
             ""
@@ -107,8 +105,8 @@ impl TradeView for TelegramTradeView {
             .send_message(
                 self.chat_id,
                 format!(
-                    "Token: {} ({})\nCurrent price: {:.6} SOL (${:.2})\n\nPlease enter the price in SOL and total volume in SOL to {} in the format:\n<price> <volume_in_sol>\nExample: 0.5 10 (10 SOL volume at price 0.5 SOL per token){}",
-                    token_symbol, token_address, current_price_in_sol, current_price_in_usdc, action, additional_instructions
+                    "Token: {} ({})\nCurrent price: {} SOL ({})\n\nPlease enter the price in SOL and total volume in SOL to {} in the format:\n<price> <volume_in_sol>\nExample: 0.5 10 (10 SOL volume at price 0.5 SOL per token){}",
+                    token_symbol, token_address, format_price(current_price_in_sol), format_usd_price(current_price_in_usdc), action, additional_instructions
                 ),
             )
             .await?;
@@ -131,17 +129,14 @@ impl TradeView for TelegramTradeView {
         amount: f64,
         total_sol: f64,
     ) -> Result<()> {
-        let order_type_str = match order_type {
-            OrderType::Buy => "BUY",
-            OrderType::Sell => "SELL",
-        };
+        let order_type_str = if order_type.is_sell() { "SELL" } else { "BUY" };
 
         self.bot
             .send_message(
                 self.chat_id,
                 format!(
-                    "Please confirm your limit order:\n\n{} {:.6} SOL ({:.6} {} tokens) @ {:.6} SOL each\n\nDo you want to proceed? (yes/no)",
-                    order_type_str, total_sol, amount, token_symbol, price_in_sol
+                    "Please confirm your limit order:\n\n{} {:.6} SOL ({:.6} {} tokens) @ {} SOL each\n\nDo you want to proceed? (yes/no)",
+                    order_type_str, total_sol, amount, token_symbol, format_price(price_in_sol)
                 ),
             )
             .await?;
@@ -171,8 +166,8 @@ impl TradeView for TelegramTradeView {
         message: Option<Message>,
     ) -> Result<()> {
         let text = format!(
-            "✅ {} order completed successfully.\nAmount: {} {}\nPrice: {:.6} SOL per token\nTotal: {:.6} SOL\nTx Signature: {}\nCheck transaction: https://explorer.solana.com/tx/{}",
-            trade_type, amount, token_symbol, price_in_sol, total_sol, signature, signature
+            "✅ {} order completed successfully.\nAmount: {} {}\nPrice: {} SOL per token\nTotal: {:.6} SOL\nTx Signature: {}\nCheck transaction: https://explorer.solana.com/tx/{}",
+            trade_type, amount, token_symbol, format_price(price_in_sol), total_sol, signature, signature
         );
 
         if let Some(msg) = message {
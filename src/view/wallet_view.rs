@@ -1,3 +1,4 @@
+use crate::i18n::{self, Locale};
 use crate::qrcodeutils;
 use crate::utils;
 use anyhow::Result;
@@ -12,6 +13,7 @@ use teloxide::{
 pub trait WalletView: Send + Sync {
     async fn display_wallet_created(&self, address: String, mnemonic: String) -> Result<()>;
     async fn display_wallet_address(&self, address: String) -> Result<()>;
+    async fn display_deposit_address(&self, address: String, amount: Option<f64>) -> Result<()>;
     async fn display_no_wallet(&self) -> Result<()>;
     async fn display_wallet_already_exists(&self) -> Result<()>;
     async fn display_error(&self, error_message: String) -> Result<()>;
@@ -20,11 +22,21 @@ pub trait WalletView: Send + Sync {
 pub struct TelegramWalletView {
     bot: Bot,
     chat_id: ChatId,
+    locale: Locale,
 }
 
 impl TelegramWalletView {
     pub fn new(bot: Bot, chat_id: ChatId) -> Self {
-        Self { bot, chat_id }
+        Self {
+            bot,
+            chat_id,
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
     }
 }
 
@@ -35,13 +47,10 @@ impl WalletView for TelegramWalletView {
         self.bot
             .send_message(
                 self.chat_id,
-                std::format!(
-                    "Your Solana wallet has been successfully created!\n\n\
-                Public address: `{}`\n\n\
-                Mnemonic phrase: `{}`\n\n\
-                <b>Important:</b> Save your mnemonic phrase - it's needed to recover access!",
-                    address,
-                    mnemonic
+                i18n::t_with(
+                    "wallet.created",
+                    self.locale,
+                    &[("address", &address), ("mnemonic", &mnemonic)],
                 ),
             )
             .parse_mode(ParseMode::Html)
@@ -51,9 +60,6 @@ impl WalletView for TelegramWalletView {
     }
 
     async fn display_wallet_address(&self, address: String) -> Result<()> {
-        // Generate QR code
-        let qr_svg_data = utils::generate_qr_code(&address)?;
-
         // Send address to user
         self.bot
             .send_message(
@@ -63,8 +69,8 @@ impl WalletView for TelegramWalletView {
             .parse_mode(ParseMode::Html)
             .await?;
 
-        // Send QR code as photo
-        let png_data: Vec<u8> = qrcodeutils::convert_svg_to_png(&qr_svg_data)?;
+        // Send QR code as photo, the address doesn't change so the image is cached
+        let png_data: Vec<u8> = qrcodeutils::qr_code_png(&address)?;
 
         self.bot
             .send_photo(
@@ -77,6 +83,39 @@ impl WalletView for TelegramWalletView {
         Ok(())
     }
 
+    async fn display_deposit_address(&self, address: String, amount: Option<f64>) -> Result<()> {
+        // The QR code is a Solana Pay link, wallet apps pre-fill the amount when scanning it
+        let uri = utils::solana_pay_uri(&address, amount);
+
+        let requested = match amount {
+            Some(amount) => format!("Requested amount: <b>{} SOL</b>\n\n", amount),
+            None => String::new(),
+        };
+
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!(
+                    "📥 Deposit SOL or tokens to your wallet:\n\n<code>{}</code>\n\n{}Tap the address to copy it, or scan the QR code with a wallet app.",
+                    address, requested
+                ),
+            )
+            .parse_mode(ParseMode::Html)
+            .await?;
+
+        let png_data: Vec<u8> = qrcodeutils::qr_code_png(&uri)?;
+
+        self.bot
+            .send_photo(
+                self.chat_id,
+                InputFile::memory(png_data).file_name("deposit.png"),
+            )
+            .caption(uri)
+            .await?;
+
+        Ok(())
+    }
+
     async fn display_no_wallet(&self) -> Result<()> {
         self.bot
             .send_message(
@@ -1,19 +1,28 @@
-use crate::entity::WatchlistItem;
+use crate::commands::ui;
+use crate::entity::{PriceCurrency, WatchlistItem, WatchlistPage, WatchlistSort};
+use crate::i18n::Locale;
+use crate::utils::{format_price, format_price_change, format_usd, format_usd_price};
+use crate::view::message_utils::edit_or_ignore_unchanged;
 use anyhow::Result;
 use async_trait::async_trait;
 use teloxide::{
     prelude::*,
-    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageId, ParseMode},
     Bot,
 };
 
 #[async_trait]
 pub trait WatchlistView: Send + Sync {
-    async fn display_watchlist(&self, watchlist: Vec<WatchlistItem>) -> Result<()>;
+    async fn display_watchlist(&self, watchlist: WatchlistPage) -> Result<()>;
+    async fn update_watchlist(&self, message_id: MessageId, watchlist: WatchlistPage)
+        -> Result<()>;
     async fn display_token_detail(
         &self,
         item: WatchlistItem,
         price_in_usdc: Option<f64>,
+        quote: PriceCurrency,
+        price_change_24h: Option<f64>,
+        sparkline: Option<String>,
     ) -> Result<()>;
     async fn display_empty_watchlist(&self) -> Result<()>;
     async fn prompt_for_token_address(&self) -> Result<()>;
@@ -26,37 +35,98 @@ pub trait WatchlistView: Send + Sync {
 pub struct TelegramWatchlistView {
     bot: Bot,
     chat_id: ChatId,
+    locale: Locale,
 }
 
 impl TelegramWatchlistView {
     pub fn new(bot: Bot, chat_id: ChatId) -> Self {
-        Self { bot, chat_id }
+        Self {
+            bot,
+            chat_id,
+            locale: Locale::default(),
+        }
+    }
+
+    /// Format price changes with the separators of the user's language
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
     }
 }
 
-#[async_trait]
-impl WatchlistView for TelegramWatchlistView {
-    async fn display_watchlist(&self, watchlist: Vec<WatchlistItem>) -> Result<()> {
-        if watchlist.is_empty() {
-            return self.display_empty_watchlist().await;
+impl TelegramWatchlistView {
+    fn watchlist_text(watchlist: &WatchlistPage) -> String {
+        if watchlist.items.is_empty() && watchlist.alerts_only {
+            return "<b>Your Watchlist</b>\n\nNone of your watchlist tokens has an active price alert."
+                .to_string();
         }
 
-        // Create buttons for each token in the watchlist
-        let mut keyboard_buttons = vec![];
+        format!(
+            "<b>Your Watchlist</b>\n\nSorted by {}{}. Select a token for details or add new ones:",
+            watchlist.sort.display_name(),
+            if watchlist.alerts_only {
+                ", tokens with active alerts only"
+            } else {
+                ""
+            }
+        )
+    }
 
-        for item in watchlist {
-            let button_text = format!(
-                "{}: {} SOL",
-                item.token_symbol,
-                format!("{:.6}", item.last_price_in_sol)
-            );
+    fn create_watchlist_keyboard(&self, watchlist: &WatchlistPage) -> InlineKeyboardMarkup {
+        // Create buttons for each token on the page
+        let mut keyboard_buttons: Vec<Vec<InlineKeyboardButton>> = watchlist
+            .items
+            .iter()
+            .map(|item| {
+                let change = match item.change_24h() {
+                    Some(change) => format!(" {}", format_price_change(change, self.locale)),
+                    None => "".to_string(),
+                };
+                // SOL prices stay when the SOL price is unavailable
+                let price = match (&watchlist.quote, watchlist.sol_price_in_usdc) {
+                    (PriceCurrency::Usdc, Some(sol_price)) => {
+                        format_usd(item.last_price_in_sol * sol_price, self.locale)
+                    }
+                    _ => item.format_price(),
+                };
+                let button_text = format!("{}: {}{}", item.token_symbol, price, change);
 
-            keyboard_buttons.push(vec![InlineKeyboardButton::callback(
-                button_text,
-                format!("watchlist_view_{}", item.token_address),
-            )]);
+                vec![InlineKeyboardButton::callback(
+                    button_text,
+                    format!("watchlist_view_{}", item.token_address),
+                )]
+            })
+            .collect();
+
+        if let Some(navigation) =
+            ui::page_navigation(watchlist.page, watchlist.pages, "watchlist_page_")
+        {
+            keyboard_buttons.push(navigation);
         }
 
+        // Sort options, the current one is checked
+        keyboard_buttons.push(
+            [WatchlistSort::Alphabetical, WatchlistSort::Change24h]
+                .iter()
+                .map(|sort| {
+                    let label = if *sort == watchlist.sort {
+                        format!("✓ {}", sort.display_name())
+                    } else {
+                        sort.display_name().to_string()
+                    };
+                    InlineKeyboardButton::callback(label, format!("watchlist_sort_{}", sort.code()))
+                })
+                .collect(),
+        );
+        keyboard_buttons.push(vec![InlineKeyboardButton::callback(
+            if watchlist.alerts_only {
+                "🔔 Alerts Only: On"
+            } else {
+                "🔔 Alerts Only: Off"
+            },
+            "watchlist_filter_alerts",
+        )]);
+
         // Add Add and Back buttons
         keyboard_buttons.push(vec![
             InlineKeyboardButton::callback("➕ Add to List", "watchlist_add"),
@@ -67,30 +137,77 @@ impl WatchlistView for TelegramWatchlistView {
             "menu",
         )]);
 
-        let keyboard = InlineKeyboardMarkup::new(keyboard_buttons);
+        InlineKeyboardMarkup::new(keyboard_buttons)
+    }
+}
+
+#[async_trait]
+impl WatchlistView for TelegramWatchlistView {
+    async fn display_watchlist(&self, watchlist: WatchlistPage) -> Result<()> {
+        if watchlist.items.is_empty() && !watchlist.alerts_only {
+            return self.display_empty_watchlist().await;
+        }
 
         self.bot
-            .send_message(
-                self.chat_id,
-                "<b>Your Watchlist</b>\n\nSelect a token for details or add new ones:",
-            )
+            .send_message(self.chat_id, Self::watchlist_text(&watchlist))
             .parse_mode(ParseMode::Html)
-            .reply_markup(keyboard)
+            .reply_markup(self.create_watchlist_keyboard(&watchlist))
             .await?;
 
         Ok(())
     }
 
+    async fn update_watchlist(
+        &self,
+        message_id: MessageId,
+        watchlist: WatchlistPage,
+    ) -> Result<()> {
+        if watchlist.items.is_empty() && !watchlist.alerts_only {
+            return self.display_empty_watchlist().await;
+        }
+
+        edit_or_ignore_unchanged(
+            self.bot
+                .edit_message_text(self.chat_id, message_id, Self::watchlist_text(&watchlist))
+                .parse_mode(ParseMode::Html)
+                .reply_markup(self.create_watchlist_keyboard(&watchlist)),
+        )
+        .await?;
+
+        Ok(())
+    }
+
     async fn display_token_detail(
         &self,
         item: WatchlistItem,
         price_in_usdc: Option<f64>,
+        quote: PriceCurrency,
+        price_change_24h: Option<f64>,
+        sparkline: Option<String>,
     ) -> Result<()> {
+        let sol_price_text = format!("{} SOL", format_price(item.last_price_in_sol));
         let usdc_price_text = if let Some(price) = price_in_usdc {
-            format!("${:.6} USD", price)
+            format!("{} USD", format_usd_price(price))
         } else {
             "USD price unavailable".to_string()
         };
+        // The chosen quote is in bold, USD falls back to SOL when unavailable
+        let (primary_price, secondary_price) = match (quote, price_in_usdc) {
+            (PriceCurrency::Usdc, Some(_)) => (usdc_price_text, sol_price_text),
+            _ => (sol_price_text, usdc_price_text),
+        };
+
+        let change_text = match price_change_24h {
+            Some(change) => format!(
+                "\n• 24h Change: <b>{}</b>",
+                format_price_change(change, self.locale)
+            ),
+            None => "".to_string(),
+        };
+        let sparkline_text = match sparkline {
+            Some(sparkline) => format!("\n• Trend: {}", sparkline),
+            None => "".to_string(),
+        };
 
         let keyboard = InlineKeyboardMarkup::new(vec![
             vec![InlineKeyboardButton::callback(
@@ -110,14 +227,16 @@ impl WatchlistView for TelegramWatchlistView {
                     "<b>{} Token Details</b>\n\n\
                     • Symbol: <b>{}</b>\n\
                     • Address: <code>{}</code>\n\
-                    • Current Price: <b>{:.6} SOL</b> ({})\n\
+                    • Current Price: <b>{}</b> ({}){}{}\n\
                     • Added: {}\n\
                     • Last Updated: {}",
                     item.token_symbol,
                     item.token_symbol,
                     item.token_address,
-                    item.last_price_in_sol,
-                    usdc_price_text,
+                    primary_price,
+                    secondary_price,
+                    change_text,
+                    sparkline_text,
                     item.created_at.format("%Y-%m-%d %H:%M"),
                     item.updated_at.format("%Y-%m-%d %H:%M")
                 ),
@@ -170,8 +289,9 @@ impl WatchlistView for TelegramWatchlistView {
             .send_message(
                 self.chat_id,
                 format!(
-                    "✅ Added <b>{}</b> to your watchlist\nCurrent price: <b>{:.6} SOL</b>",
-                    item.token_symbol, item.last_price_in_sol
+                    "✅ Added <b>{}</b> to your watchlist\nCurrent price: <b>{} SOL</b>",
+                    item.token_symbol,
+                    format_price(item.last_price_in_sol)
                 ),
             )
             .parse_mode(ParseMode::Html)
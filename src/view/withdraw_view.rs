@@ -1,15 +1,25 @@
+use crate::commands::ui;
 use crate::entity::TokenBalance;
+use crate::i18n::Locale;
+use crate::solana::tokens::constants::TOKEN_ACCOUNT_RENT_LAMPORTS;
+use crate::utils::{format_amount, format_usd};
 use anyhow::Result;
 use async_trait::async_trait;
 use teloxide::{
     prelude::*,
-    types::{InlineKeyboardButton, InlineKeyboardMarkup, Message, ParseMode},
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, Message, MessageId, ParseMode},
     Bot,
 };
 
 #[async_trait]
 pub trait WithdrawView: Send + Sync {
     async fn display_token_selection(&self, tokens: Vec<TokenBalance>) -> Result<()>;
+    async fn update_token_selection_page(
+        &self,
+        message_id: MessageId,
+        tokens: Vec<TokenBalance>,
+        page: usize,
+    ) -> Result<()>;
     async fn display_token_details(
         &self,
         token_symbol: &str,
@@ -20,6 +30,9 @@ pub trait WithdrawView: Send + Sync {
     ) -> Result<()>;
     async fn prompt_for_recipient_address(&self) -> Result<()>;
     async fn display_invalid_address(&self) -> Result<()>;
+    async fn display_rejected_address(&self, reason: String) -> Result<()>;
+    async fn display_recipient_warning(&self, warning: &str) -> Result<()>;
+    async fn display_token_account_notice(&self, token_symbol: &str) -> Result<()>;
     async fn prompt_for_amount(
         &self,
         token_symbol: &str,
@@ -62,11 +75,48 @@ pub trait WithdrawView: Send + Sync {
 pub struct TelegramWithdrawView {
     bot: Bot,
     chat_id: ChatId,
+    locale: Locale,
 }
 
 impl TelegramWithdrawView {
     pub fn new(bot: Bot, chat_id: ChatId) -> Self {
-        Self { bot, chat_id }
+        Self {
+            bot,
+            chat_id,
+            locale: Locale::default(),
+        }
+    }
+
+    /// Format amounts with the separators of the user's language
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    fn create_token_keyboard(tokens: Vec<TokenBalance>, page: usize) -> InlineKeyboardMarkup {
+        // Create keyboard buttons for each token
+        let token_buttons = tokens
+            .into_iter()
+            .map(|token| {
+                let token_text = format!("{}: {}", token.symbol, token.display_amount());
+                vec![InlineKeyboardButton::callback(
+                    token_text,
+                    format!("withdraw_token_{}", token.mint_address),
+                )]
+            })
+            .collect();
+
+        let mut keyboard_buttons = ui::paginate_buttons(token_buttons, page, "withdraw_page_");
+
+        keyboard_buttons.push(vec![InlineKeyboardButton::callback(
+            "📋 Batch Withdraw",
+            "withdraw_batch",
+        )]);
+
+        // Add cancel button
+        keyboard_buttons.push(vec![InlineKeyboardButton::callback("← Cancel", "menu")]);
+
+        InlineKeyboardMarkup::new(keyboard_buttons)
     }
 }
 
@@ -77,24 +127,26 @@ impl WithdrawView for TelegramWithdrawView {
             return self.display_no_tokens().await;
         }
 
-        // Create keyboard buttons for each token
-        let mut keyboard_buttons = Vec::new();
-
-        for token in tokens {
-            let token_text = format!("{}: {:.6}", token.symbol, token.amount);
-            keyboard_buttons.push(vec![InlineKeyboardButton::callback(
-                token_text,
-                format!("withdraw_token_{}", token.mint_address),
-            )]);
-        }
+        let keyboard = Self::create_token_keyboard(tokens, 0);
 
-        // Add cancel button
-        keyboard_buttons.push(vec![InlineKeyboardButton::callback("← Cancel", "menu")]);
+        self.bot
+            .send_message(self.chat_id, "Select a token to withdraw:")
+            .reply_markup(keyboard)
+            .await?;
 
-        let keyboard = InlineKeyboardMarkup::new(keyboard_buttons);
+        Ok(())
+    }
+
+    async fn update_token_selection_page(
+        &self,
+        message_id: MessageId,
+        tokens: Vec<TokenBalance>,
+        page: usize,
+    ) -> Result<()> {
+        let keyboard = Self::create_token_keyboard(tokens, page);
 
         self.bot
-            .send_message(self.chat_id, "Select a token to withdraw:")
+            .edit_message_reply_markup(self.chat_id, message_id)
             .reply_markup(keyboard)
             .await?;
 
@@ -131,18 +183,18 @@ impl WithdrawView for TelegramWithdrawView {
                     "<b>{} Token Details</b>\n\n\
                     • Symbol: <b>{}</b>\n\
                     • Address: <code>{}</code>\n\
-                    • Your Balance: <b>{:.6}</b>\n\
-                    • Price: <b>{:.6} SOL</b> (${:.2})\n\
-                    • Total Value: <b>{:.6} SOL</b> (${:.2})\n\n\
+                    • Your Balance: <b>{}</b>\n\
+                    • Price: <b>{} SOL</b> ({})\n\
+                    • Total Value: <b>{} SOL</b> ({})\n\n\
                     Enter the recipient's Solana address:",
                     token_symbol,
                     token_symbol,
                     short_address,
-                    balance,
-                    price_in_sol,
-                    price_in_usdc,
-                    total_sol_value,
-                    total_usdc_value
+                    format_amount(balance, self.locale),
+                    format_amount(price_in_sol, self.locale),
+                    format_usd(price_in_usdc, self.locale),
+                    format_amount(total_sol_value, self.locale),
+                    format_usd(total_usdc_value, self.locale)
                 ),
             )
             .parse_mode(ParseMode::Html)
@@ -170,6 +222,33 @@ impl WithdrawView for TelegramWithdrawView {
         Ok(())
     }
 
+    async fn display_rejected_address(&self, reason: String) -> Result<()> {
+        self.bot
+            .send_message(
+                self.chat_id,
+                format!("⚠️ {}\n\nPlease enter a wallet address:", reason),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_recipient_warning(&self, warning: &str) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, recipient_warning(warning))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn display_token_account_notice(&self, token_symbol: &str) -> Result<()> {
+        self.bot
+            .send_message(self.chat_id, token_account_notice(token_symbol))
+            .await?;
+
+        Ok(())
+    }
+
     async fn prompt_for_amount(
         &self,
         token_symbol: &str,
@@ -181,15 +260,15 @@ impl WithdrawView for TelegramWithdrawView {
             .send_message(
                 self.chat_id,
                 format!(
-                    "You have <b>{:.6} {}</b> (worth {:.6} SOL / ${:.2}).\n\n\
+                    "You have <b>{} {}</b> (worth {} SOL / {}).\n\n\
                     Enter the amount to withdraw:\n\
                     • Enter a specific amount (e.g. <code>0.5</code>)\n\
                     • Enter a percentage (e.g. <code>50%</code>)\n\
                     • Or type <code>All</code> to withdraw your entire balance",
-                    balance,
+                    format_amount(balance, self.locale),
                     token_symbol,
-                    balance * price_in_sol,
-                    balance * price_in_usdc
+                    format_amount(balance * price_in_sol, self.locale),
+                    format_usd(balance * price_in_usdc, self.locale)
                 ),
             )
             .parse_mode(ParseMode::Html)
@@ -230,11 +309,15 @@ impl WithdrawView for TelegramWithdrawView {
                 self.chat_id,
                 format!(
                     "<b>Confirm Withdrawal</b>\n\n\
-                    • Amount: <b>{:.6} {}</b>\n\
-                    • Value: <b>{:.6} SOL</b> (${:.2})\n\
+                    • Amount: <b>{} {}</b>\n\
+                    • Value: <b>{} SOL</b> ({})\n\
                     • To: <code>{}</code>\n\n\
                     Proceed with this withdrawal? (yes/no)",
-                    amount, token_symbol, total_sol, total_usdc, short_address
+                    format_amount(amount, self.locale),
+                    token_symbol,
+                    format_amount(total_sol, self.locale),
+                    format_usd(total_usdc, self.locale),
+                    short_address
                 ),
             )
             .parse_mode(ParseMode::Html)
@@ -262,11 +345,15 @@ impl WithdrawView for TelegramWithdrawView {
     ) -> Result<()> {
         let text = format!(
             "✅ <b>Withdrawal Successful</b>\n\n\
-            • Amount: <b>{:.6} {}</b>\n\
+            • Amount: <b>{} {}</b>\n\
             • Recipient: <code>{}</code>\n\
             • Tx Signature: <code>{}</code>\n\n\
             <a href=\"https://explorer.solana.com/tx/{}\">View on Explorer</a>",
-            amount, token_symbol, recipient, signature, signature
+            format_amount(amount, self.locale),
+            token_symbol,
+            recipient,
+            signature,
+            signature
         );
 
         if let Some(msg) = message {
@@ -294,10 +381,13 @@ impl WithdrawView for TelegramWithdrawView {
     ) -> Result<()> {
         let text = format!(
             "❌ <b>Withdrawal Failed</b>\n\n\
-            • Amount: <b>{:.6} {}</b>\n\
+            • Amount: <b>{} {}</b>\n\
             • Recipient: <code>{}</code>\n\
             • Error: <code>{}</code>",
-            amount, token_symbol, recipient, error_message
+            format_amount(amount, self.locale),
+            token_symbol,
+            recipient,
+            error_message
         );
 
         if let Some(msg) = message {
@@ -353,3 +443,21 @@ impl WithdrawView for TelegramWithdrawView {
         Ok(())
     }
 }
+
+/// Warning shown before sending to an off-curve address or a known program
+pub fn recipient_warning(warning: &str) -> String {
+    format!(
+        "⚠️ {}\n\nDouble-check the address before confirming. Cancel if you meant a wallet.",
+        warning
+    )
+}
+
+/// Warning shown when the withdrawal creates the recipient's token account
+pub fn token_account_notice(token_symbol: &str) -> String {
+    format!(
+        "ℹ️ The recipient doesn't have a {} token account yet. It will be created with this \
+        withdrawal and about {:.4} SOL rent will be charged to your wallet.",
+        token_symbol,
+        TOKEN_ACCOUNT_RENT_LAMPORTS as f64 / 1_000_000_000.0
+    )
+}